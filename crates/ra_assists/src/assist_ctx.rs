@@ -1,6 +1,6 @@
 //! This module defines `AssistCtx` -- the API surface that is exposed to assists.
 use hir::Semantics;
-use ra_db::FileRange;
+use ra_db::{FileId, FileRange, RelativePathBuf};
 use ra_fmt::{leading_indent, reindent};
 use ra_ide_db::RootDatabase;
 use ra_syntax::{
@@ -9,8 +9,12 @@ use ra_syntax::{
     TokenAtOffset,
 };
 use ra_text_edit::TextEditBuilder;
+use rustc_hash::FxHashMap;
 
-use crate::{AssistAction, AssistFile, AssistId, AssistLabel, GroupLabel, ResolvedAssist};
+use crate::{
+    AssistAction, AssistConfig, AssistFile, AssistId, AssistLabel, GroupLabel, ResolvedAssist,
+    SnippetCap,
+};
 use algo::SyntaxRewriter;
 
 #[derive(Clone, Debug)]
@@ -71,13 +75,14 @@ pub(crate) type AssistHandler = fn(AssistCtx) -> Option<Assist>;
 /// with `should_compute_edit = false`, and then applying the selected edit
 /// again, with `should_compute_edit = true` this time.
 ///
-/// Note, however, that we don't actually use such two-phase logic at the
-/// moment, because the LSP API is pretty awkward in this place, and it's much
-/// easier to just compute the edit eagerly :-)
+/// Clients which support resolving code actions lazily get the assists in
+/// the first phase only, and the edit of the picked assist is computed with
+/// `resolve_assist`.
 #[derive(Clone)]
 pub(crate) struct AssistCtx<'a> {
     pub(crate) sema: &'a Semantics<'a, RootDatabase>,
     pub(crate) db: &'a RootDatabase,
+    pub(crate) config: &'a AssistConfig,
     pub(crate) frange: FileRange,
     source_file: SourceFile,
     should_compute_edit: bool,
@@ -86,11 +91,12 @@ pub(crate) struct AssistCtx<'a> {
 impl<'a> AssistCtx<'a> {
     pub fn new(
         sema: &'a Semantics<'a, RootDatabase>,
+        config: &'a AssistConfig,
         frange: FileRange,
         should_compute_edit: bool,
     ) -> AssistCtx<'a> {
         let source_file = sema.parse(frange.file_id);
-        AssistCtx { sema, db: sema.db, frange, source_file, should_compute_edit }
+        AssistCtx { sema, db: sema.db, config, frange, source_file, should_compute_edit }
     }
 
     pub(crate) fn add_assist(
@@ -186,6 +192,9 @@ pub(crate) struct ActionBuilder<'a, 'b> {
     cursor_position: Option<TextSize>,
     target: Option<TextRange>,
     file: AssistFile,
+    new_files: Vec<(RelativePathBuf, String)>,
+    other_file_edits: FxHashMap<FileId, TextEditBuilder>,
+    is_snippet: bool,
     ctx: &'a AssistCtx<'b>,
 }
 
@@ -196,12 +205,15 @@ impl<'a, 'b> ActionBuilder<'a, 'b> {
             cursor_position: None,
             target: None,
             file: AssistFile::default(),
+            new_files: Vec::new(),
+            other_file_edits: FxHashMap::default(),
+            is_snippet: false,
             ctx,
         }
     }
 
-    pub(crate) fn ctx(&self) -> &AssistCtx<'b> {
-        &self.ctx
+    pub(crate) fn ctx(&self) -> &'a AssistCtx<'b> {
+        self.ctx
     }
 
     /// Replaces specified `range` of text with a given string.
@@ -224,6 +236,28 @@ impl<'a, 'b> ActionBuilder<'a, 'b> {
         self.replace(node.text_range(), replace_with)
     }
 
+    /// Replaces specified `range` of text with a snippet, which can contain
+    /// tabstops like `$0` or `${0:placeholder}`.
+    pub(crate) fn replace_snippet(
+        &mut self,
+        _cap: SnippetCap,
+        range: TextRange,
+        replace_with: impl Into<String>,
+    ) {
+        self.is_snippet = true;
+        self.replace(range, replace_with)
+    }
+
+    /// Inserts a snippet at specified `offset`.
+    pub(crate) fn insert_snippet(
+        &mut self,
+        cap: SnippetCap,
+        offset: TextSize,
+        text: impl Into<String>,
+    ) {
+        self.replace_snippet(cap, TextRange::empty(offset), text)
+    }
+
     /// Remove specified `range` of text.
     #[allow(unused)]
     pub(crate) fn delete(&mut self, range: TextRange) {
@@ -266,12 +300,43 @@ impl<'a, 'b> ActionBuilder<'a, 'b> {
         self.file = assist_file
     }
 
+    /// Get access to the `TextEditBuilder` of an arbitrary file, which is the
+    /// raw builder of this action if `file_id` is the file being edited.
+    pub(crate) fn file_edit_builder(&mut self, file_id: FileId) -> &mut TextEditBuilder {
+        let current_file = match self.file {
+            AssistFile::CurrentFile => self.ctx.frange.file_id,
+            AssistFile::TargetFile(it) => it,
+        };
+        if file_id == current_file {
+            &mut self.edit
+        } else {
+            self.other_file_edits.entry(file_id).or_default()
+        }
+    }
+
+    /// Creates a file at `path`, relative to the source root of the current
+    /// file.
+    pub(crate) fn create_file(&mut self, path: RelativePathBuf, contents: impl Into<String>) {
+        self.new_files.push((path, contents.into()))
+    }
+
     fn build(self) -> AssistAction {
         AssistAction {
             edit: self.edit.finish(),
             cursor_position: self.cursor_position,
             target: self.target,
             file: self.file,
+            new_files: self.new_files,
+            other_file_edits: {
+                let mut edits = self
+                    .other_file_edits
+                    .into_iter()
+                    .map(|(file_id, edit)| (file_id, edit.finish()))
+                    .collect::<Vec<_>>();
+                edits.sort_by_key(|(file_id, _)| *file_id);
+                edits
+            },
+            is_snippet: self.is_snippet,
         }
     }
 }
@@ -1,7 +1,8 @@
 //! `AstTransformer`s are functions that replace nodes in an AST and can be easily combined.
 use rustc_hash::FxHashMap;
 
-use hir::{PathResolution, SemanticsScope};
+use either::Either;
+use hir::{HasSource, PathResolution, SemanticsScope};
 use ra_ide_db::RootDatabase;
 use ra_syntax::{
     algo::SyntaxRewriter,
@@ -46,18 +47,40 @@ impl<'a> SubstituteTypeParams<'a> {
     ) -> SubstituteTypeParams<'a> {
         let substs = get_syntactic_substs(impl_def).unwrap_or_default();
         let generic_def: hir::GenericDef = trait_.into();
-        let substs_by_param: FxHashMap<_, _> = generic_def
+        let mut res = SubstituteTypeParams {
+            source_scope,
+            substs: FxHashMap::default(),
+            previous: Box::new(NullTransformer),
+        };
+        let params = generic_def
             .params(source_scope.db)
             .into_iter()
             // this is a trait impl, so we need to skip the first type parameter -- this is a bit hacky
             .skip(1)
-            .zip(substs.into_iter())
-            .collect();
-        return SubstituteTypeParams {
-            source_scope,
-            substs: substs_by_param,
-            previous: Box::new(NullTransformer),
-        };
+            .zip(substs.into_iter().map(Some).chain(std::iter::repeat(None)));
+        for (param, subst) in params {
+            let subst = match subst {
+                Some(it) => it,
+                // The impl omits parameters which have a default, so use the
+                // default, with the parameters preceding it substituted.
+                // FIXME: paths in the default aren't qualified.
+                None => {
+                    let default = match param.source(source_scope.db).value {
+                        Either::Right(type_param) => type_param.default_type(),
+                        Either::Left(_) => None,
+                    };
+                    match default {
+                        Some(it) => res
+                            .get_substitution_inner(it.syntax())
+                            .and_then(ast::TypeRef::cast)
+                            .unwrap_or_else(|| apply(&res, it)),
+                        None => continue,
+                    }
+                }
+            };
+            res.substs.insert(param, subst);
+        }
+        return res;
 
         // FIXME: It would probably be nicer if we could get this via HIR (i.e. get the
         // trait ref, and then go from the types in the substs back to the syntax)
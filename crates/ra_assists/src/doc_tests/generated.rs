@@ -41,6 +41,107 @@ struct Point {
     )
 }
 
+#[test]
+fn doctest_add_display_impl() {
+    check(
+        "add_display_impl",
+        r#####"
+struct Point<|> { x: f64, y: f64 }
+"#####,
+        r#####"
+struct Point { x: f64, y: f64 }
+
+impl std::fmt::Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Point {{ x: {}, y: {} }}", self.x, self.y)
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_doc_comment() {
+    check(
+        "add_doc_comment",
+        r#####"
+pub fn parse<|>(text: &str) -> Result<u32, Error> {
+    text.parse()
+}
+"#####,
+        r#####"
+/// ${1:Summary}
+///
+/// # Arguments
+///
+/// * `text` - ${2:Description}
+///
+/// # Errors
+///
+/// ${3:Description}
+///
+/// # Panics
+///
+/// ${4:Description}
+///
+/// # Examples
+///
+/// ```
+/// $0
+/// ```
+pub fn parse(text: &str) -> Result<u32, Error> {
+    text.parse()
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_enum_accessors() {
+    check(
+        "add_enum_accessors",
+        r#####"
+enum Value<|> {
+    Null,
+    Number(f64),
+}
+"#####,
+        r#####"
+enum Value {
+    Null,
+    Number(f64),
+}
+
+impl Value {
+    fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    fn is_number(&self) -> bool {
+        matches!(self, Self::Number(..))
+    }
+
+    fn as_number(&self) -> Option<&f64> {
+        if let Self::Number(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    fn try_into_number(self) -> Result<f64, Self> {
+        if let Self::Number(v) = self {
+            Ok(v)
+        } else {
+            Err(self)
+        }
+    }
+}
+
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_explicit_type() {
     check(
@@ -58,6 +159,25 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_add_from_impl_for_enum() {
+    check(
+        "add_from_impl_for_enum",
+        r#####"
+enum A { <|>One(u32) }
+"#####,
+        r#####"
+enum A { One(u32) }
+
+impl From<u32> for A {
+    fn from(v: u32) -> Self {
+        Self::One(v)
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_function() {
     check(
@@ -85,6 +205,30 @@ fn bar(arg: &str, baz: Baz) {
     )
 }
 
+#[test]
+fn doctest_add_getter() {
+    check(
+        "add_getter",
+        r#####"
+struct Person {
+    name: String,<|>
+}
+"#####,
+        r#####"
+struct Person {
+    name: String,
+}
+
+impl Person {
+    fn name(&self) -> &String {
+        &self.name
+    }
+}
+
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_hash() {
     check(
@@ -211,6 +355,57 @@ impl<T: Clone> Ctx<T> {
     )
 }
 
+#[test]
+fn doctest_add_setter() {
+    check(
+        "add_setter",
+        r#####"
+struct Person {
+    name: String,<|>
+}
+"#####,
+        r#####"
+struct Person {
+    name: String,
+}
+
+impl Person {
+    fn set_name(&mut self, value: String) {
+        self.name = value;
+    }
+}
+
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_test_fn() {
+    check(
+        "add_test_fn",
+        r#####"
+fn add<|>(a: i32, b: i32) -> i32 {
+    a + b
+}
+"#####,
+        r#####"
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add() {
+        add(todo!(), todo!());
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_apply_demorgan() {
     check(
@@ -262,6 +457,51 @@ pub(crate) fn frobnicate() {}
     )
 }
 
+#[test]
+fn doctest_convert_for_each_to_for_loop() {
+    check(
+        "convert_for_each_to_for_loop",
+        r#####"
+fn main() {
+    let xs = vec![1, 2, 3];
+    xs.iter().for_each<|>(|x| {
+        println!("{}", x);
+    });
+}
+"#####,
+        r#####"
+fn main() {
+    let xs = vec![1, 2, 3];
+    for x in &xs {
+        println!("{}", x);
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_for_to_iter_chain() {
+    check(
+        "convert_for_to_iter_chain",
+        r#####"
+fn main() {
+    let xs = vec![1, 2, 3];
+    let mut squares = Vec::new();
+    for<|> x in &xs {
+        squares.push(x * x);
+    }
+}
+"#####,
+        r#####"
+fn main() {
+    let xs = vec![1, 2, 3];
+    let mut squares: Vec<_> = xs.iter().map(|x| x * x).collect();
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_to_guarded_return() {
     check(
@@ -286,6 +526,69 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_tuple_struct_to_named_struct() {
+    check(
+        "convert_tuple_struct_to_named_struct",
+        r#####"
+struct Point<|>(f32, f32);
+
+fn origin() -> Point {
+    Point(0.0, 0.0)
+}
+
+fn x(p: &Point) -> f32 {
+    p.0
+}
+"#####,
+        r#####"
+struct Point { field0: f32, field1: f32 }
+
+fn origin() -> Point {
+    Point { field0: 0.0, field1: 0.0 }
+}
+
+fn x(p: &Point) -> f32 {
+    p.field0
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_destructure_binding() {
+    check(
+        "destructure_binding",
+        r#####"
+fn main() {
+    let <|>pair = (1, 2);
+    let sum = pair.0 + pair.1;
+}
+"#####,
+        r#####"
+fn main() {
+    let (pair0, pair1) = (1, 2);
+    let sum = pair0 + pair1;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_extract_module_to_file() {
+    check(
+        "extract_module_to_file",
+        r#####"
+mod <|>foo {
+    fn bar() {}
+}
+"#####,
+        r#####"
+mod foo;
+"#####,
+    )
+}
+
 #[test]
 fn doctest_fill_match_arms() {
     check(
@@ -312,6 +615,21 @@ fn handle(action: Action) {
     )
 }
 
+#[test]
+fn doctest_flatten_imports() {
+    check(
+        "flatten_imports",
+        r#####"
+use std::{fmt::{Debug, Display}, <|>io};
+"#####,
+        r#####"
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::io;
+"#####,
+    )
+}
+
 #[test]
 fn doctest_flip_binexpr() {
     check(
@@ -346,6 +664,23 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_flip_equality() {
+    check(
+        "flip_equality",
+        r#####"
+fn main() {
+    let _ = x =<|>= 2;
+}
+"#####,
+        r#####"
+fn main() {
+    let _ = !(x != 2);
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_flip_trait_bound() {
     check(
@@ -557,6 +892,89 @@ fn handle(action: Action) {
     )
 }
 
+#[test]
+fn doctest_pull_up_to_trait() {
+    check(
+        "pull_up_to_trait",
+        r#####"
+trait Greet {
+    fn greet(&self) -> String;
+}
+
+struct Person;
+
+impl Greet for Person {
+    fn greet<|>(&self) -> String {
+        "Hello".to_string()
+    }
+}
+"#####,
+        r#####"
+trait Greet {
+    fn greet(&self) -> String {
+        "Hello".to_string()
+    }
+}
+
+struct Person;
+
+impl Greet for Person {}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_push_down_to_impl() {
+    check(
+        "push_down_to_impl",
+        r#####"
+trait Greet {
+    fn greet<|>(&self) -> String {
+        "Hello".to_string()
+    }
+}
+
+struct Person;
+
+impl Greet for Person {}
+"#####,
+        r#####"
+trait Greet {
+    fn greet(&self) -> String {
+        "Hello".to_string()
+    }
+}
+
+struct Person;
+
+impl Greet for Person {
+    fn greet(&self) -> String {
+        "Hello".to_string()
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_qualify_path() {
+    check(
+        "qualify_path",
+        r#####"
+fn main() {
+    let map = HashMap<|>::new();
+}
+pub mod std { pub mod collections { pub struct HashMap { } } }
+"#####,
+        r#####"
+fn main() {
+    let map = std::collections::HashMap::new();
+}
+pub mod std { pub mod collections { pub struct HashMap { } } }
+"#####,
+    )
+}
+
 #[test]
 fn doctest_remove_dbg() {
     check(
@@ -677,6 +1095,34 @@ fn compute() -> Option<i32> { None }
     )
 }
 
+#[test]
+fn doctest_replace_match_with_if_let() {
+    check(
+        "replace_match_with_if_let",
+        r#####"
+enum Action { Move { distance: u32 }, Stop }
+
+fn handle(action: Action) {
+    <|>match action {
+        Action::Move { distance } => foo(distance),
+        _ => bar(),
+    }
+}
+"#####,
+        r#####"
+enum Action { Move { distance: u32 }, Stop }
+
+fn handle(action: Action) {
+    if let Action::Move { distance } = action {
+        foo(distance)
+    } else {
+        bar()
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_replace_qualified_name_with_use() {
     check(
@@ -716,6 +1162,51 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_replace_unwrap_with_try() {
+    check(
+        "replace_unwrap_with_try",
+        r#####"
+enum Result<T, E> { Ok(T), Err(E) }
+fn parse() -> Result<i32, ()> { Result::Ok(92) }
+fn double() -> Result<i32, ()> {
+    let x = parse().<|>unwrap();
+    Result::Ok(x * 2)
+}
+"#####,
+        r#####"
+enum Result<T, E> { Ok(T), Err(E) }
+fn parse() -> Result<i32, ()> { Result::Ok(92) }
+fn double() -> Result<i32, ()> {
+    let x = parse()?;
+    Result::Ok(x * 2)
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_replace_unwrap_with_try_returning_result() {
+    check(
+        "replace_unwrap_with_try_returning_result",
+        r#####"
+enum Result<T, E> { Ok(T), Err(E) }
+fn parse() -> Result<i32, ()> { Result::Ok(92) }
+fn main() {
+    let x = parse().<|>unwrap();
+}
+"#####,
+        r#####"
+enum Result<T, E> { Ok(T), Err(E) }
+fn parse() -> Result<i32, ()> { Result::Ok(92) }
+fn main() -> Result<(), ()> {
+    let x = parse()?;
+    Ok(())
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_split_import() {
     check(
@@ -747,3 +1238,26 @@ fn foo() {
 "#####,
     )
 }
+
+#[test]
+fn doctest_wrap_return_type_in_result() {
+    check(
+        "wrap_return_type_in_result",
+        r#####"
+fn parse(s: &str) -> <|>i32 {
+    if s.is_empty() {
+        return 0;
+    }
+    s.len() as i32
+}
+"#####,
+        r#####"
+fn parse(s: &str) -> Result<i32, ${0:Error}> {
+    if s.is_empty() {
+        return Ok(0);
+    }
+    Ok(s.len() as i32)
+}
+"#####,
+    )
+}
@@ -0,0 +1,315 @@
+use ra_syntax::ast::{self, AstNode, NameOwner, TypeAscriptionOwner, VisibilityOwner};
+use stdx::format_to;
+
+use crate::{
+    utils::{find_struct_impl, insert_fn_in_impl, FamousDefs},
+    Assist, AssistCtx, AssistId, AssistKind,
+};
+
+// Assist: add_getter
+//
+// Adds a getter for a struct field. `Copy` fields are returned by value.
+//
+// ```
+// struct Person {
+//     name: String,<|>
+// }
+// ```
+// ->
+// ```
+// struct Person {
+//     name: String,
+// }
+//
+// impl Person {
+//     fn name(&self) -> &String {
+//         &self.name
+//     }
+// }
+//
+// ```
+pub(crate) fn add_getter(ctx: AssistCtx) -> Option<Assist> {
+    let field = ctx.find_node_at_offset::<ast::RecordFieldDef>()?;
+    let strukt = field.syntax().parent()?.parent().and_then(ast::StructDef::cast)?;
+    let field_name = field.name()?;
+    let field_ty = field.ascribed_type()?;
+
+    let fn_name = field_name.text().to_string();
+    let impl_def = find_struct_impl(ctx.sema, &strukt, trim_raw(&fn_name))?;
+
+    let is_copy = {
+        let krate = ctx.sema.scope(field.syntax()).module()?.krate();
+        let ty = ctx.sema.to_def(&field)?.signature_ty(ctx.db);
+        FamousDefs(ctx.sema, krate)
+            .core_marker_Copy()
+            .map_or(false, |copy| ty.impls_trait(ctx.db, copy, &[]))
+    };
+
+    let label = format!("Add a getter for `{}`", fn_name);
+    ctx.add_assist(AssistId("add_getter", AssistKind::Generate), label, |edit| {
+        edit.target(field.syntax().text_range());
+
+        let (ret_ty, ref_) = if is_copy { ("", "") } else { ("&", "&") };
+        let mut buf = String::new();
+        format_to!(
+            buf,
+            "    {}fn {}(&self) -> {}{} {{\n        {}self.{}\n    }}",
+            vis(&strukt),
+            fn_name,
+            ret_ty,
+            field_ty.syntax(),
+            ref_,
+            field_name.text(),
+        );
+        insert_fn_in_impl(edit, &strukt, impl_def, buf);
+    })
+}
+
+// Assist: add_setter
+//
+// Adds a setter for a struct field.
+//
+// ```
+// struct Person {
+//     name: String,<|>
+// }
+// ```
+// ->
+// ```
+// struct Person {
+//     name: String,
+// }
+//
+// impl Person {
+//     fn set_name(&mut self, value: String) {
+//         self.name = value;
+//     }
+// }
+//
+// ```
+pub(crate) fn add_setter(ctx: AssistCtx) -> Option<Assist> {
+    let field = ctx.find_node_at_offset::<ast::RecordFieldDef>()?;
+    let strukt = field.syntax().parent()?.parent().and_then(ast::StructDef::cast)?;
+    let field_name = field.name()?;
+    let field_ty = field.ascribed_type()?;
+
+    let fn_name = format!("set_{}", trim_raw(field_name.text()));
+    let impl_def = find_struct_impl(ctx.sema, &strukt, &fn_name)?;
+
+    let label = format!("Add a setter for `{}`", field_name.text());
+    ctx.add_assist(AssistId("add_setter", AssistKind::Generate), label, |edit| {
+        edit.target(field.syntax().text_range());
+
+        let mut buf = String::new();
+        format_to!(
+            buf,
+            "    {}fn {}(&mut self, value: {}) {{\n        self.{} = value;\n    }}",
+            vis(&strukt),
+            fn_name,
+            field_ty.syntax(),
+            field_name.text(),
+        );
+        insert_fn_in_impl(edit, &strukt, impl_def, buf);
+    })
+}
+
+fn trim_raw(name: &str) -> &str {
+    name.trim_start_matches("r#")
+}
+
+fn vis(strukt: &ast::StructDef) -> String {
+    strukt.visibility().map(|v| format!("{} ", v)).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    fn check_with_core(ra_fixture_before: &str, ra_fixture_after: &str) {
+        let before = format!(
+            "//- main.rs crate:main deps:core\n{}\n{}",
+            ra_fixture_before,
+            FamousDefs::FIXTURE
+        );
+        let after = format!("{}\n\n", ra_fixture_after);
+        check_assist(add_getter, &before, &after)
+    }
+
+    #[test]
+    fn test_add_getter() {
+        check_assist(
+            add_getter,
+            r#"
+struct Person {
+    name: String,<|>
+}
+"#,
+            r#"
+struct Person {
+    name: String,<|>
+}
+
+impl Person {
+    fn name(&self) -> &String {
+        &self.name
+    }
+}
+
+"#,
+        );
+    }
+
+    #[test]
+    fn test_add_getter_copy_field() {
+        check_with_core(
+            r#"struct Person {
+    age: u32,<|>
+}"#,
+            r#"struct Person {
+    age: u32,<|>
+}
+
+impl Person {
+    fn age(&self) -> u32 {
+        self.age
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_add_getter_to_existing_impl() {
+        check_assist(
+            add_getter,
+            r#"
+pub struct Person<T> {
+    name: T,<|>
+}
+
+impl<T> Person<T> {
+    pub fn new(name: T) -> Self { Self { name } }
+}
+"#,
+            r#"
+pub struct Person<T> {
+    name: T,<|>
+}
+
+impl<T> Person<T> {
+    pub fn new(name: T) -> Self { Self { name } }
+
+    pub fn name(&self) -> &T {
+        &self.name
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_add_getter_to_empty_impl() {
+        check_assist(
+            add_getter,
+            r#"
+struct Person {
+    name: String,<|>
+}
+
+impl Person {}
+"#,
+            r#"
+struct Person {
+    name: String,<|>
+}
+
+impl Person {
+    fn name(&self) -> &String {
+        &self.name
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_add_getter_target() {
+        check_assist_target(
+            add_getter,
+            r#"
+struct Person {
+    name: <|>String,
+}
+"#,
+            "name: String",
+        );
+    }
+
+    #[test]
+    fn add_getter_not_applicable_if_fn_exists() {
+        check_assist_not_applicable(
+            add_getter,
+            r#"
+struct Person {
+    name: String,<|>
+}
+
+impl Person {
+    fn name(&self) -> &str { &self.name }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_getter_not_applicable_in_enum_variant() {
+        check_assist_not_applicable(add_getter, "enum E { V { name: String<|> } }");
+    }
+
+    #[test]
+    fn test_add_setter() {
+        check_assist(
+            add_setter,
+            r#"
+struct Person {
+    r#type: String,<|>
+}
+
+impl Person {
+    fn new() -> Self { loop {} }
+}
+"#,
+            r#"
+struct Person {
+    r#type: String,<|>
+}
+
+impl Person {
+    fn new() -> Self { loop {} }
+
+    fn set_type(&mut self, value: String) {
+        self.r#type = value;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_setter_not_applicable_if_fn_exists() {
+        check_assist_not_applicable(
+            add_setter,
+            r#"
+struct Person {
+    name: String,<|>
+}
+
+impl Person {
+    fn set_name(&mut self, name: String) {}
+}
+"#,
+        );
+    }
+}
@@ -6,7 +6,7 @@ use ra_syntax::{
 };
 use stdx::SepBy;
 
-use crate::{Assist, AssistCtx, AssistId};
+use crate::{Assist, AssistCtx, AssistId, AssistKind};
 
 // Assist: add_custom_impl
 //
@@ -48,7 +48,7 @@ pub(crate) fn add_custom_impl(ctx: AssistCtx) -> Option<Assist> {
     let label =
         format!("Add custom impl '{}' for '{}'", trait_token.text().as_str(), annotated_name);
 
-    ctx.add_assist(AssistId("add_custom_impl"), label, |edit| {
+    ctx.add_assist(AssistId("add_custom_impl", AssistKind::Generate), label, |edit| {
         edit.target(attr.syntax().text_range());
 
         let new_attr_input = input
@@ -0,0 +1,231 @@
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner},
+    TextSize,
+};
+use stdx::{format_to, SepBy};
+
+use crate::{utils::generate_trait_impl_text, Assist, AssistCtx, AssistId, AssistKind};
+
+// Assist: add_display_impl
+//
+// Adds a `Display` impl for a type, writing out its fields.
+//
+// ```
+// struct Point<|> { x: f64, y: f64 }
+// ```
+// ->
+// ```
+// struct Point { x: f64, y: f64 }
+//
+// impl std::fmt::Display for Point {
+//     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+//         write!(f, "Point {{ x: {}, y: {} }}", self.x, self.y)
+//     }
+// }
+// ```
+pub(crate) fn add_display_impl(ctx: AssistCtx) -> Option<Assist> {
+    let nominal = ctx.find_node_at_offset::<ast::NominalDef>()?;
+    let name = nominal.name()?.text().to_string();
+    if has_display_impl(&nominal, &name) {
+        return None;
+    }
+
+    let body = match &nominal {
+        ast::NominalDef::StructDef(strukt) => {
+            let fields = field_names(&strukt.kind());
+            let args = fields.iter().map(|field| format!("self.{}", field));
+            write_call(&name, &strukt.kind(), &fields, args)
+        }
+        ast::NominalDef::EnumDef(enum_) => {
+            let mut buf = String::from("match self {\n");
+            for variant in enum_.variant_list()?.variants() {
+                let variant_name = variant.name()?.text().to_string();
+                let kind = variant.kind();
+                let fields = field_names(&kind);
+                let bindings = fields
+                    .iter()
+                    .map(|field| match kind {
+                        ast::StructKind::Tuple(_) => format!("field{}", field),
+                        _ => field.clone(),
+                    })
+                    .collect::<Vec<_>>();
+                let pat = match kind {
+                    ast::StructKind::Record(_) => {
+                        format!(" {{ {} }}", bindings.iter().sep_by(", "))
+                    }
+                    ast::StructKind::Tuple(_) => format!("({})", bindings.iter().sep_by(", ")),
+                    ast::StructKind::Unit => String::new(),
+                };
+                let write = write_call(&variant_name, &kind, &fields, bindings.iter().cloned());
+                format_to!(buf, "            Self::{}{} => {},\n", variant_name, pat, write);
+            }
+            buf.push_str("        }");
+            buf
+        }
+        ast::NominalDef::UnionDef(_) => return None,
+    };
+
+    ctx.add_assist(AssistId("add_display_impl", AssistKind::Generate), "Add `Display` impl", |edit| {
+        edit.target(nominal.syntax().text_range());
+        let code = format!(
+            "    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{\n        {}\n    }}",
+            body
+        );
+        let text = generate_trait_impl_text(&nominal, "std::fmt::Display", &code);
+        let start_offset = nominal.syntax().text_range().end();
+        let cursor_offset = text.find(&body).map_or(0, |it| it as u32);
+        edit.set_cursor(start_offset + TextSize::from(cursor_offset));
+        edit.insert(start_offset, text);
+    })
+}
+
+/// Returns the names of the fields, which are indices for tuple fields.
+fn field_names(kind: &ast::StructKind) -> Vec<String> {
+    match kind {
+        ast::StructKind::Record(it) => {
+            it.fields().filter_map(|field| Some(field.name()?.text().to_string())).collect()
+        }
+        ast::StructKind::Tuple(it) => (0..it.fields().count()).map(|idx| idx.to_string()).collect(),
+        ast::StructKind::Unit => Vec::new(),
+    }
+}
+
+/// Returns a `write!` call listing `fields` the way they are declared.
+fn write_call(
+    name: &str,
+    kind: &ast::StructKind,
+    fields: &[String],
+    args: impl Iterator<Item = String>,
+) -> String {
+    let format = match kind {
+        ast::StructKind::Record(_) if !fields.is_empty() => {
+            let fields = fields.iter().map(|field| format!("{}: {{}}", field)).sep_by(", ");
+            format!("{} {{{{ {} }}}}", name, fields)
+        }
+        ast::StructKind::Tuple(_) if !fields.is_empty() => {
+            format!("{}({})", name, fields.iter().map(|_| "{}").sep_by(", "))
+        }
+        _ => name.to_string(),
+    };
+    let mut buf = format!("write!(f, \"{}\"", format);
+    for arg in args {
+        format_to!(buf, ", {}", arg);
+    }
+    buf.push(')');
+    buf
+}
+
+// FIXME: this is syntactic, so impls in other files and impls naming the type
+// through an alias aren't found.
+fn has_display_impl(nominal: &ast::NominalDef, name: &str) -> bool {
+    let last_segment = |ty: Option<ast::TypeRef>| match ty {
+        Some(ast::TypeRef::PathType(it)) => {
+            it.path().and_then(|it| it.segment()).and_then(|it| it.name_ref())
+        }
+        _ => None,
+    };
+    let root = match nominal.syntax().ancestors().last() {
+        Some(it) => it,
+        None => return false,
+    };
+    root.descendants().filter_map(ast::ImplDef::cast).any(|impl_def| {
+        last_segment(impl_def.target_trait()).map_or(false, |it| it.text() == "Display")
+            && last_segment(impl_def.target_type()).map_or(false, |it| it.text() == name)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_display_impl_for_record_struct() {
+        check_assist(
+            add_display_impl,
+            "struct Point<|> { x: f64, y: f64 }",
+            r#"struct Point { x: f64, y: f64 }
+
+impl std::fmt::Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        <|>write!(f, "Point {{ x: {}, y: {} }}", self.x, self.y)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_display_impl_for_generic_tuple_struct() {
+        check_assist(
+            add_display_impl,
+            "struct Pair<|><'a, T>(&'a T, T);",
+            r#"struct Pair<'a, T>(&'a T, T);
+
+impl<'a, T> std::fmt::Display for Pair<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        <|>write!(f, "Pair({}, {})", self.0, self.1)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_display_impl_for_unit_struct() {
+        check_assist(
+            add_display_impl,
+            "struct Unit<|>;",
+            r#"struct Unit;
+
+impl std::fmt::Display for Unit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        <|>write!(f, "Unit")
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_display_impl_for_enum() {
+        check_assist(
+            add_display_impl,
+            "enum Shape<|> { Empty, Circle(f64), Rect { w: f64, h: f64 } }",
+            r#"enum Shape { Empty, Circle(f64), Rect { w: f64, h: f64 } }
+
+impl std::fmt::Display for Shape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        <|>match self {
+            Self::Empty => write!(f, "Empty"),
+            Self::Circle(field0) => write!(f, "Circle({})", field0),
+            Self::Rect { w, h } => write!(f, "Rect {{ w: {}, h: {} }}", w, h),
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_display_impl_target() {
+        check_assist_target(add_display_impl, "struct Unit<|>;", "struct Unit;");
+    }
+
+    #[test]
+    fn not_applicable_with_existing_impl() {
+        check_assist_not_applicable(
+            add_display_impl,
+            r#"
+struct Point<|> { x: f64, y: f64 }
+
+impl fmt::Display for Point {}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_unions() {
+        check_assist_not_applicable(add_display_impl, "union U<|> { a: u32, b: f32 }");
+    }
+}
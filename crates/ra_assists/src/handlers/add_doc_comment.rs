@@ -0,0 +1,339 @@
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, AttrsOwner, DocCommentsOwner},
+    SyntaxKind::{COMMENT, WHITESPACE},
+    SyntaxNode, TextSize,
+};
+
+use crate::{Assist, AssistCtx, AssistId, AssistKind};
+
+// Assist: add_doc_comment
+//
+// Adds a documentation comment template to an undocumented function, struct
+// or enum.
+//
+// ```
+// pub fn parse<|>(text: &str) -> Result<u32, Error> {
+//     text.parse()
+// }
+// ```
+// ->
+// ```
+// /// ${1:Summary}
+// ///
+// /// # Arguments
+// ///
+// /// * `text` - ${2:Description}
+// ///
+// /// # Errors
+// ///
+// /// ${3:Description}
+// ///
+// /// # Panics
+// ///
+// /// ${4:Description}
+// ///
+// /// # Examples
+// ///
+// /// ```
+// /// $0
+// /// ```
+// pub fn parse(text: &str) -> Result<u32, Error> {
+//     text.parse()
+// }
+// ```
+pub(crate) fn add_doc_comment(ctx: AssistCtx) -> Option<Assist> {
+    let node = ctx.token_at_offset().left_biased()?.parent().ancestors().find(|it| {
+        ast::FnDef::can_cast(it.kind())
+            || ast::StructDef::can_cast(it.kind())
+            || ast::EnumDef::can_cast(it.kind())
+    })?;
+
+    let mut template = Template::new(ctx.config.snippet_cap.is_some());
+    template.summary();
+    if let Some(fn_def) = ast::FnDef::cast(node.clone()) {
+        if is_documented(&fn_def) {
+            return None;
+        }
+        if let Some(body) = fn_def.body() {
+            if body.syntax().text_range().contains_range(ctx.frange.range) {
+                return None;
+            }
+        }
+        let params = fn_def.param_list().into_iter().flat_map(|it| it.params());
+        let params = params.filter_map(|it| it.pat()).map(|it| it.to_string()).collect::<Vec<_>>();
+        if !params.is_empty() {
+            template.section("Arguments");
+            for param in params {
+                let placeholder = template.placeholder("Description");
+                template.line(format!("* `{}` - {}", param, placeholder));
+            }
+        }
+        if returns_result(&fn_def) {
+            template.section("Errors");
+            let placeholder = template.placeholder("Description");
+            template.line(placeholder);
+        }
+        template.section("Panics");
+        let placeholder = template.placeholder("Description");
+        template.line(placeholder);
+    } else if let Some(strukt) = ast::StructDef::cast(node.clone()) {
+        if is_documented(&strukt) {
+            return None;
+        }
+    } else if let Some(enum_def) = ast::EnumDef::cast(node.clone()) {
+        if is_documented(&enum_def) {
+            return None;
+        }
+    }
+    template.examples();
+
+    let snippet_cap = ctx.config.snippet_cap;
+    ctx.add_assist(
+        AssistId("add_doc_comment", AssistKind::Generate),
+        "Add documentation comment",
+        |edit| {
+            edit.target(node.text_range());
+            // Non-doc comments in front of the item are kept right above it.
+            let first_token = node
+                .children_with_tokens()
+                .find(|it| it.kind() != COMMENT && it.kind() != WHITESPACE)
+                .map_or(node.text_range(), |it| it.text_range());
+            let offset = first_token.start();
+            let text = template.render(&node);
+            match snippet_cap {
+                Some(cap) => {
+                    edit.insert_snippet(cap, offset, text);
+                    edit.set_cursor(offset);
+                }
+                None => {
+                    edit.insert(offset, text);
+                    edit.set_cursor(offset + TextSize::of("/// "));
+                }
+            }
+        },
+    )
+}
+
+fn is_documented(node: &(impl DocCommentsOwner + AttrsOwner)) -> bool {
+    node.doc_comments().any(|it| it.kind().doc.is_some())
+        || node.attrs().any(|it| it.simple_name().map_or(false, |name| name == "doc"))
+}
+
+fn returns_result(fn_def: &ast::FnDef) -> bool {
+    match fn_def.ret_type().and_then(|it| it.type_ref()) {
+        Some(ast::TypeRef::PathType(it)) => it
+            .path()
+            .and_then(|it| it.segment())
+            .and_then(|it| it.name_ref())
+            .map_or(false, |it| it.text() == "Result"),
+        _ => false,
+    }
+}
+
+/// The lines of the comment, without the leading `///`.
+struct Template {
+    lines: Vec<String>,
+    snippet: bool,
+    n_tabstops: usize,
+}
+
+impl Template {
+    fn new(snippet: bool) -> Template {
+        Template { lines: Vec::new(), snippet, n_tabstops: 0 }
+    }
+
+    fn placeholder(&mut self, text: &str) -> String {
+        if !self.snippet {
+            return text.to_string();
+        }
+        self.n_tabstops += 1;
+        format!("${{{}:{}}}", self.n_tabstops, text)
+    }
+
+    fn line(&mut self, line: impl Into<String>) {
+        self.lines.push(line.into());
+    }
+
+    fn summary(&mut self) {
+        let placeholder = self.placeholder("Summary");
+        self.line(placeholder);
+    }
+
+    fn section(&mut self, title: &str) {
+        self.line("");
+        self.line(format!("# {}", title));
+        self.line("");
+    }
+
+    fn examples(&mut self) {
+        self.section("Examples");
+        self.line("```");
+        self.line(if self.snippet { "$0" } else { "" });
+        self.line("```");
+    }
+
+    fn render(&self, item: &SyntaxNode) -> String {
+        let indent = "    ".repeat(IndentLevel::from_node(item).0 as usize);
+        let mut buf = String::new();
+        for line in self.lines.iter() {
+            if line.is_empty() {
+                buf.push_str("///");
+            } else {
+                buf.push_str("/// ");
+                buf.push_str(line);
+            }
+            buf.push('\n');
+            buf.push_str(&indent);
+        }
+        buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        helpers::{
+            check_assist, check_assist_not_applicable, check_assist_target,
+            check_assist_with_config,
+        },
+        AssistConfig,
+    };
+
+    use super::*;
+
+    #[test]
+    fn add_doc_comment_to_fn() {
+        check_assist(
+            add_doc_comment,
+            r#"
+fn <|>frobnicate(x: u32, (a, b): (i32, i32)) -> u32 {
+    x
+}
+"#,
+            r#"
+<|>/// ${1:Summary}
+///
+/// # Arguments
+///
+/// * `x` - ${2:Description}
+/// * `(a, b)` - ${3:Description}
+///
+/// # Panics
+///
+/// ${4:Description}
+///
+/// # Examples
+///
+/// ```
+/// $0
+/// ```
+fn frobnicate(x: u32, (a, b): (i32, i32)) -> u32 {
+    x
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_doc_comment_to_method() {
+        check_assist(
+            add_doc_comment,
+            r#"
+impl S {
+    // Not a doc comment
+    #[inline]
+    pub fn <|>len(&self) -> io::Result<usize> {
+        Ok(0)
+    }
+}
+"#,
+            r#"
+impl S {
+    // Not a doc comment
+    <|>/// ${1:Summary}
+    ///
+    /// # Errors
+    ///
+    /// ${2:Description}
+    ///
+    /// # Panics
+    ///
+    /// ${3:Description}
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// $0
+    /// ```
+    #[inline]
+    pub fn len(&self) -> io::Result<usize> {
+        Ok(0)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_doc_comment_to_struct() {
+        check_assist(
+            add_doc_comment,
+            r#"
+pub struct <|>Point {
+    x: i32,
+}
+"#,
+            r#"
+<|>/// ${1:Summary}
+///
+/// # Examples
+///
+/// ```
+/// $0
+/// ```
+pub struct Point {
+    x: i32,
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_doc_comment_without_snippets() {
+        let mut config = AssistConfig::default();
+        config.allow_snippets(false);
+        check_assist_with_config(
+            add_doc_comment,
+            config,
+            r#"
+enum <|>E { A }
+"#,
+            r#"
+/// <|>Summary
+///
+/// # Examples
+///
+/// ```
+///
+/// ```
+enum E { A }
+"#,
+        );
+    }
+
+    #[test]
+    fn add_doc_comment_target() {
+        check_assist_target(add_doc_comment, "enum <|>E { A }", "enum E { A }");
+    }
+
+    #[test]
+    fn not_applicable_to_documented_items() {
+        check_assist_not_applicable(add_doc_comment, "/// Docs\nfn <|>foo() {}");
+        check_assist_not_applicable(add_doc_comment, "#[doc = \"Docs\"]\nstruct <|>S;");
+    }
+
+    #[test]
+    fn not_applicable_in_fn_body() {
+        check_assist_not_applicable(add_doc_comment, "fn foo() { <|>bar(); }");
+    }
+}
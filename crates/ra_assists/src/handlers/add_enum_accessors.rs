@@ -0,0 +1,293 @@
+use ra_syntax::ast::{self, AstNode, NameOwner, TypeAscriptionOwner, VisibilityOwner};
+use stdx::{format_to, to_lower_snake_case, SepBy};
+
+use crate::{
+    utils::{find_adt_impl, has_fn, insert_fn_in_impl},
+    Assist, AssistCtx, AssistId, AssistKind,
+};
+
+// Assist: add_enum_accessors
+//
+// Adds `is_`, `as_` and `try_into_` methods for each variant of an enum. Unit
+// variants only get an `is_` method.
+//
+// ```
+// enum Value<|> {
+//     Null,
+//     Number(f64),
+// }
+// ```
+// ->
+// ```
+// enum Value {
+//     Null,
+//     Number(f64),
+// }
+//
+// impl Value {
+//     fn is_null(&self) -> bool {
+//         matches!(self, Self::Null)
+//     }
+//
+//     fn is_number(&self) -> bool {
+//         matches!(self, Self::Number(..))
+//     }
+//
+//     fn as_number(&self) -> Option<&f64> {
+//         if let Self::Number(v) = self {
+//             Some(v)
+//         } else {
+//             None
+//         }
+//     }
+//
+//     fn try_into_number(self) -> Result<f64, Self> {
+//         if let Self::Number(v) = self {
+//             Ok(v)
+//         } else {
+//             Err(self)
+//         }
+//     }
+// }
+//
+// ```
+pub(crate) fn add_enum_accessors(ctx: AssistCtx) -> Option<Assist> {
+    let enum_def = ctx.find_node_at_offset::<ast::EnumDef>()?;
+    let impl_def = find_adt_impl(ctx.sema, &enum_def.clone().into())?;
+    let vis = enum_def.visibility().map(|v| format!("{} ", v)).unwrap_or_default();
+
+    let mut methods = Vec::new();
+    let mut add_method = |fn_name: String, code: String| {
+        if impl_def.as_ref().map_or(true, |it| !has_fn(it, &fn_name)) {
+            methods.push(code);
+        }
+    };
+    for variant in enum_def.variant_list()?.variants() {
+        let name = variant.name()?;
+        let snake_name = to_lower_snake_case(name.text());
+        let path = format!("Self::{}", name.text());
+
+        // The bindings of the fields, and their types.
+        let (fields, pat, is_pat) = match variant.kind() {
+            ast::StructKind::Unit => (Vec::new(), path.clone(), path),
+            ast::StructKind::Tuple(list) => {
+                let types = list.fields().map(|it| it.type_ref()).collect::<Option<Vec<_>>>()?;
+                let fields = types
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, ty)| {
+                        let binding =
+                            if types.len() == 1 { "v".to_string() } else { format!("v{}", idx) };
+                        (binding, ty.to_string())
+                    })
+                    .collect::<Vec<_>>();
+                let pat = format!("{}({})", path, fields.iter().map(|(it, _)| it).sep_by(", "));
+                (fields, pat, format!("{}(..)", path))
+            }
+            ast::StructKind::Record(list) => {
+                let fields = list
+                    .fields()
+                    .map(|it| {
+                        Some((it.name()?.text().to_string(), it.ascribed_type()?.to_string()))
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                let pat =
+                    format!("{} {{ {} }}", path, fields.iter().map(|(it, _)| it).sep_by(", "));
+                (fields, pat, format!("{} {{ .. }}", path))
+            }
+        };
+
+        let fn_name = format!("is_{}", snake_name);
+        let mut buf = String::new();
+        format_to!(buf, "    {}fn {}(&self) -> bool {{\n", vis, fn_name);
+        format_to!(buf, "        matches!(self, {})\n    }}", is_pat);
+        add_method(fn_name, buf);
+
+        if fields.is_empty() {
+            continue;
+        }
+        let value = tuple(fields.iter().map(|(binding, _)| binding.clone()));
+
+        let fn_name = format!("as_{}", snake_name);
+        let ty = tuple(fields.iter().map(|(_, ty)| format!("&{}", ty)));
+        let mut buf = String::new();
+        format_to!(buf, "    {}fn {}(&self) -> Option<{}> {{\n", vis, fn_name, ty);
+        format_to!(buf, "        if let {} = self {{\n            Some({})\n", pat, value);
+        buf.push_str("        } else {\n            None\n        }\n    }");
+        add_method(fn_name, buf);
+
+        let fn_name = format!("try_into_{}", snake_name);
+        let ty = tuple(fields.iter().map(|(_, ty)| ty.clone()));
+        let mut buf = String::new();
+        format_to!(buf, "    {}fn {}(self) -> Result<{}, Self> {{\n", vis, fn_name, ty);
+        format_to!(buf, "        if let {} = self {{\n            Ok({})\n", pat, value);
+        buf.push_str("        } else {\n            Err(self)\n        }\n    }");
+        add_method(fn_name, buf);
+    }
+    if methods.is_empty() {
+        return None;
+    }
+
+    ctx.add_assist(
+        AssistId("add_enum_accessors", AssistKind::Generate),
+        "Add accessors for the variants",
+        |edit| {
+            edit.target(enum_def.syntax().text_range());
+            insert_fn_in_impl(edit, &enum_def, impl_def, methods.join("\n\n"));
+        },
+    )
+}
+
+/// Wraps `items` in a tuple, unless there is a single one.
+fn tuple(items: impl Iterator<Item = String>) -> String {
+    let items = items.collect::<Vec<_>>();
+    if items.len() == 1 {
+        items[0].clone()
+    } else {
+        format!("({})", items.iter().sep_by(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn test_add_enum_accessors() {
+        check_assist(
+            add_enum_accessors,
+            r#"
+pub enum Shape<|><T> {
+    Point,
+    Line(T, T),
+    Circle { center: T, radius: f64 },
+}
+"#,
+            r#"
+pub enum Shape<|><T> {
+    Point,
+    Line(T, T),
+    Circle { center: T, radius: f64 },
+}
+
+impl<T> Shape<T> {
+    pub fn is_point(&self) -> bool {
+        matches!(self, Self::Point)
+    }
+
+    pub fn is_line(&self) -> bool {
+        matches!(self, Self::Line(..))
+    }
+
+    pub fn as_line(&self) -> Option<(&T, &T)> {
+        if let Self::Line(v0, v1) = self {
+            Some((v0, v1))
+        } else {
+            None
+        }
+    }
+
+    pub fn try_into_line(self) -> Result<(T, T), Self> {
+        if let Self::Line(v0, v1) = self {
+            Ok((v0, v1))
+        } else {
+            Err(self)
+        }
+    }
+
+    pub fn is_circle(&self) -> bool {
+        matches!(self, Self::Circle { .. })
+    }
+
+    pub fn as_circle(&self) -> Option<(&T, &f64)> {
+        if let Self::Circle { center, radius } = self {
+            Some((center, radius))
+        } else {
+            None
+        }
+    }
+
+    pub fn try_into_circle(self) -> Result<(T, f64), Self> {
+        if let Self::Circle { center, radius } = self {
+            Ok((center, radius))
+        } else {
+            Err(self)
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_add_enum_accessors_to_existing_impl() {
+        check_assist(
+            add_enum_accessors,
+            r#"
+enum Token<|> {
+    Eof,
+    IntLiteral(u64),
+}
+
+impl Token {
+    fn is_eof(&self) -> bool {
+        matches!(self, Self::Eof)
+    }
+
+    fn as_int_literal(&self) -> Option<&u64> {
+        if let Self::IntLiteral(v) = self { Some(v) } else { None }
+    }
+}
+"#,
+            r#"
+enum Token<|> {
+    Eof,
+    IntLiteral(u64),
+}
+
+impl Token {
+    fn is_eof(&self) -> bool {
+        matches!(self, Self::Eof)
+    }
+
+    fn as_int_literal(&self) -> Option<&u64> {
+        if let Self::IntLiteral(v) = self { Some(v) } else { None }
+    }
+
+    fn is_int_literal(&self) -> bool {
+        matches!(self, Self::IntLiteral(..))
+    }
+
+    fn try_into_int_literal(self) -> Result<u64, Self> {
+        if let Self::IntLiteral(v) = self {
+            Ok(v)
+        } else {
+            Err(self)
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_add_enum_accessors_target() {
+        check_assist_target(add_enum_accessors, "enum E<|> { A }", "enum E { A }");
+    }
+
+    #[test]
+    fn not_applicable_when_all_accessors_exist() {
+        check_assist_not_applicable(
+            add_enum_accessors,
+            r#"
+enum E<|> { A }
+
+impl E {
+    fn is_a(&self) -> bool { matches!(self, Self::A) }
+}
+"#,
+        );
+    }
+}
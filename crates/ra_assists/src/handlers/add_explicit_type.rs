@@ -4,7 +4,7 @@ use ra_syntax::{
     TextRange,
 };
 
-use crate::{Assist, AssistCtx, AssistId};
+use crate::{Assist, AssistCtx, AssistId, AssistKind};
 
 // Assist: add_explicit_type
 //
@@ -60,7 +60,7 @@ pub(crate) fn add_explicit_type(ctx: AssistCtx) -> Option<Assist> {
     let db = ctx.db;
     let new_type_string = ty.display_truncated(db, None).to_string();
     ctx.add_assist(
-        AssistId("add_explicit_type"),
+        AssistId("add_explicit_type", AssistKind::RefactorRewrite),
         format!("Insert explicit type '{}'", new_type_string),
         |edit| {
             edit.target(pat_range);
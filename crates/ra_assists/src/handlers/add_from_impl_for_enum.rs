@@ -1,16 +1,16 @@
 use ra_ide_db::RootDatabase;
 use ra_syntax::{
-    ast::{self, AstNode, NameOwner},
+    ast::{self, AstNode, NameOwner, TypeParamsOwner},
     TextSize,
 };
-use stdx::format_to;
+use stdx::{format_to, SepBy};
 
-use crate::{utils::FamousDefs, Assist, AssistCtx, AssistId};
+use crate::{utils::FamousDefs, Assist, AssistCtx, AssistId, AssistKind};
 use test_utils::tested_by;
 
-// Assist add_from_impl_for_enum
+// Assist: add_from_impl_for_enum
 //
-// Adds a From impl for an enum variant with one tuple field
+// Adds a From impl for an enum variant with one tuple field.
 //
 // ```
 // enum A { <|>One(u32) }
@@ -21,14 +21,15 @@ use test_utils::tested_by;
 //
 // impl From<u32> for A {
 //     fn from(v: u32) -> Self {
-//         A::One(v)
+//         Self::One(v)
 //     }
 // }
 // ```
 pub(crate) fn add_from_impl_for_enum(ctx: AssistCtx) -> Option<Assist> {
     let variant = ctx.find_node_at_offset::<ast::EnumVariant>()?;
     let variant_name = variant.name()?;
-    let enum_name = variant.parent_enum().name()?;
+    let enum_ = variant.parent_enum();
+    let enum_name = enum_.name()?;
     let field_list = match variant.kind() {
         ast::StructKind::Tuple(field_list) => field_list,
         _ => return None,
@@ -37,10 +38,6 @@ pub(crate) fn add_from_impl_for_enum(ctx: AssistCtx) -> Option<Assist> {
         return None;
     }
     let field_type = field_list.fields().next()?.type_ref()?;
-    let path = match field_type {
-        ast::TypeRef::PathType(p) => p,
-        _ => return None,
-    };
 
     if existing_from_impl(ctx.sema, &variant).is_some() {
         tested_by!(test_add_from_impl_already_exists);
@@ -48,22 +45,37 @@ pub(crate) fn add_from_impl_for_enum(ctx: AssistCtx) -> Option<Assist> {
     }
 
     ctx.add_assist(
-        AssistId("add_from_impl_for_enum"),
+        AssistId("add_from_impl_for_enum", AssistKind::Generate),
         "Add From impl for this enum variant",
         |edit| {
-            let start_offset = variant.parent_enum().syntax().text_range().end();
+            let start_offset = enum_.syntax().text_range().end();
+            let (impl_params, enum_args) = match enum_.type_param_list() {
+                Some(params) => {
+                    let lifetime_params = params
+                        .lifetime_params()
+                        .filter_map(|it| it.lifetime_token())
+                        .map(|it| it.text().clone());
+                    let type_params =
+                        params.type_params().filter_map(|it| it.name()).map(|it| it.text().clone());
+                    let args = lifetime_params.chain(type_params).sep_by(", ");
+                    (params.syntax().to_string(), format!("<{}>", args))
+                }
+                None => (String::new(), String::new()),
+            };
             let mut buf = String::new();
             format_to!(
                 buf,
                 r#"
 
-impl From<{0}> for {1} {{
-    fn from(v: {0}) -> Self {{
-        {1}::{2}(v)
+impl{0} From<{1}> for {2}{3} {{
+    fn from(v: {1}) -> Self {{
+        Self::{4}(v)
     }}
 }}"#,
-                path.syntax(),
+                impl_params,
+                field_type.syntax(),
                 enum_name,
+                enum_args,
                 variant_name
             );
             edit.insert(start_offset, buf);
@@ -109,7 +121,7 @@ mod tests {
 
 <|>impl From<u32> for A {
     fn from(v: u32) -> Self {
-        A::One(v)
+        Self::One(v)
     }
 }"#,
         );
@@ -124,7 +136,22 @@ mod tests {
 
 <|>impl From<foo::bar::baz::Boo> for A {
     fn from(v: foo::bar::baz::Boo) -> Self {
-        A::One(v)
+        Self::One(v)
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn test_add_from_impl_for_generic_enum() {
+        check_assist(
+            add_from_impl_for_enum,
+            "enum E<'a, T: Clone> { <|>Ref(&'a T), Other }",
+            r#"enum E<'a, T: Clone> { Ref(&'a T), Other }
+
+<|>impl<'a, T: Clone> From<&'a T> for E<'a, T> {
+    fn from(v: &'a T) -> Self {
+        Self::Ref(v)
     }
 }"#,
         );
@@ -186,7 +213,7 @@ pub trait From<T> {
 
 <|>impl From<u32> for A {
     fn from(v: u32) -> Self {
-        A::One(v)
+        Self::One(v)
     }
 }
 
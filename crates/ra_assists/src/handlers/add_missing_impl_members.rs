@@ -11,7 +11,7 @@ use ra_syntax::{
 use crate::{
     ast_transform::{self, AstTransform, QualifyPaths, SubstituteTypeParams},
     utils::{get_missing_assoc_items, resolve_target_trait},
-    Assist, AssistCtx, AssistId,
+    Assist, AssistCtx, AssistId, AssistKind,
 };
 
 #[derive(PartialEq)]
@@ -144,7 +144,7 @@ fn add_missing_impl_members_inner(
 
     let sema = ctx.sema;
 
-    ctx.add_assist(AssistId(assist_id), label, |edit| {
+    ctx.add_assist(AssistId(assist_id, AssistKind::QuickFix), label, |edit| {
         let n_existing_items = impl_item_list.assoc_items().count();
         let source_scope = sema.scope_for_def(trait_);
         let target_scope = sema.scope(impl_item_list.syntax());
@@ -528,6 +528,78 @@ impl foo::Foo for S {
         );
     }
 
+    #[test]
+    fn fill_in_default_type_params() {
+        check_assist(
+            add_missing_impl_members,
+            r#"
+trait Foo<T, U = Vec<T>> { fn foo(&self, t: T) -> U; }
+struct S;
+impl Foo<u32> for S { <|> }"#,
+            r#"
+trait Foo<T, U = Vec<T>> { fn foo(&self, t: T) -> U; }
+struct S;
+impl Foo<u32> for S {
+    <|>fn foo(&self, t: u32) -> Vec<u32> {
+        todo!()
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn fill_in_self_default_type_param() {
+        check_assist(
+            add_missing_impl_members,
+            r#"
+trait Add<Rhs = Self> { fn add(self, rhs: Rhs) -> Self; }
+struct S;
+impl Add for S { <|> }"#,
+            r#"
+trait Add<Rhs = Self> { fn add(self, rhs: Rhs) -> Self; }
+struct S;
+impl Add for S {
+    <|>fn add(self, rhs: Self) -> Self {
+        todo!()
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn copy_where_clauses() {
+        check_assist(
+            add_missing_impl_members,
+            r#"
+trait Clone {}
+trait Foo<T> {
+    fn foo<U>(&self, t: T, u: U)
+    where
+        U: Clone,
+        T: Clone;
+}
+struct S;
+impl Foo<u32> for S { <|> }"#,
+            r#"
+trait Clone {}
+trait Foo<T> {
+    fn foo<U>(&self, t: T, u: U)
+    where
+        U: Clone,
+        T: Clone;
+}
+struct S;
+impl Foo<u32> for S {
+    <|>fn foo<U>(&self, t: u32, u: U)
+    where
+        U: Clone,
+        u32: Clone {
+        todo!()
+    }
+}"#,
+        );
+    }
+
     #[test]
     fn test_empty_trait() {
         check_assist_not_applicable(
@@ -614,6 +686,27 @@ trait Foo {
 struct S;
 impl Foo for S {
     <|>fn valid(some: u32) -> bool { false }
+}"#,
+        )
+    }
+
+    #[test]
+    fn test_default_methods_substitute_type_params() {
+        check_assist(
+            add_missing_default_members,
+            r#"
+trait Foo<T> {
+    fn foo(&self, t: T) -> Option<T> { Some(t) }
+}
+struct S;
+impl Foo<u32> for S { <|> }"#,
+            r#"
+trait Foo<T> {
+    fn foo(&self, t: T) -> Option<T> { Some(t) }
+}
+struct S;
+impl Foo<u32> for S {
+    <|>fn foo(&self, t: u32) -> Option<u32> { Some(t) }
 }"#,
         )
     }
@@ -1,13 +1,13 @@
-use hir::Adt;
 use ra_syntax::{
-    ast::{
-        self, AstNode, NameOwner, StructKind, TypeAscriptionOwner, TypeParamsOwner, VisibilityOwner,
-    },
+    ast::{self, AstNode, NameOwner, StructKind, TypeAscriptionOwner, VisibilityOwner},
     TextSize, T,
 };
 use stdx::{format_to, SepBy};
 
-use crate::{Assist, AssistCtx, AssistId};
+use crate::{
+    utils::{find_struct_impl, generate_impl_text},
+    Assist, AssistCtx, AssistId, AssistKind,
+};
 
 // Assist: add_new
 //
@@ -39,9 +39,9 @@ pub(crate) fn add_new(ctx: AssistCtx) -> Option<Assist> {
     };
 
     // Return early if we've found an existing new fn
-    let impl_def = find_struct_impl(&ctx, &strukt)?;
+    let impl_def = find_struct_impl(ctx.sema, &strukt, "new")?;
 
-    ctx.add_assist(AssistId("add_new"), "Add default constructor", |edit| {
+    ctx.add_assist(AssistId("add_new", AssistKind::Generate), "Add default constructor", |edit| {
         edit.target(strukt.syntax().text_range());
 
         let mut buf = String::with_capacity(512);
@@ -91,91 +91,6 @@ pub(crate) fn add_new(ctx: AssistCtx) -> Option<Assist> {
     })
 }
 
-// Generates the surrounding `impl Type { <code> }` including type and lifetime
-// parameters
-fn generate_impl_text(strukt: &ast::StructDef, code: &str) -> String {
-    let type_params = strukt.type_param_list();
-    let mut buf = String::with_capacity(code.len());
-    buf.push_str("\n\nimpl");
-    if let Some(type_params) = &type_params {
-        format_to!(buf, "{}", type_params.syntax());
-    }
-    buf.push_str(" ");
-    buf.push_str(strukt.name().unwrap().text().as_str());
-    if let Some(type_params) = type_params {
-        let lifetime_params = type_params
-            .lifetime_params()
-            .filter_map(|it| it.lifetime_token())
-            .map(|it| it.text().clone());
-        let type_params =
-            type_params.type_params().filter_map(|it| it.name()).map(|it| it.text().clone());
-        format_to!(buf, "<{}>", lifetime_params.chain(type_params).sep_by(", "))
-    }
-
-    format_to!(buf, " {{\n{}\n}}\n", code);
-
-    buf
-}
-
-// Uses a syntax-driven approach to find any impl blocks for the struct that
-// exist within the module/file
-//
-// Returns `None` if we've found an existing `new` fn
-//
-// FIXME: change the new fn checking to a more semantic approach when that's more
-// viable (e.g. we process proc macros, etc)
-fn find_struct_impl(ctx: &AssistCtx, strukt: &ast::StructDef) -> Option<Option<ast::ImplDef>> {
-    let db = ctx.db;
-    let module = strukt.syntax().ancestors().find(|node| {
-        ast::Module::can_cast(node.kind()) || ast::SourceFile::can_cast(node.kind())
-    })?;
-
-    let struct_def = ctx.sema.to_def(strukt)?;
-
-    let block = module.descendants().filter_map(ast::ImplDef::cast).find_map(|impl_blk| {
-        let blk = ctx.sema.to_def(&impl_blk)?;
-
-        // FIXME: handle e.g. `struct S<T>; impl<U> S<U> {}`
-        // (we currently use the wrong type parameter)
-        // also we wouldn't want to use e.g. `impl S<u32>`
-        let same_ty = match blk.target_ty(db).as_adt() {
-            Some(def) => def == Adt::Struct(struct_def),
-            None => false,
-        };
-        let not_trait_impl = blk.target_trait(db).is_none();
-
-        if !(same_ty && not_trait_impl) {
-            None
-        } else {
-            Some(impl_blk)
-        }
-    });
-
-    if let Some(ref impl_blk) = block {
-        if has_new_fn(impl_blk) {
-            return None;
-        }
-    }
-
-    Some(block)
-}
-
-fn has_new_fn(imp: &ast::ImplDef) -> bool {
-    if let Some(il) = imp.item_list() {
-        for item in il.assoc_items() {
-            if let ast::AssocItem::FnDef(f) = item {
-                if let Some(name) = f.name() {
-                    if name.text().eq_ignore_ascii_case("new") {
-                        return true;
-                    }
-                }
-            }
-        }
-    }
-
-    false
-}
-
 #[cfg(test)]
 mod tests {
     use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
@@ -355,6 +270,42 @@ impl Foo {
         );
     }
 
+    #[test]
+    #[rustfmt::skip]
+    fn add_new_skips_impls_for_other_params() {
+        check_assist(
+            add_new,
+"impl Foo<u32> {}
+
+struct Foo<T> {<|>}",
+"impl Foo<u32> {}
+
+struct Foo<T> {}
+
+impl<T> Foo<T> {
+    fn new() -> Self { Self {  } }<|>
+}
+",
+        );
+        check_assist(
+            add_new,
+"struct Foo<'a, T> {<|>}
+
+impl Foo<'static, u32> {}
+
+impl<'a, T> Foo<'a, T> {}
+",
+"struct Foo<'a, T> {}
+
+impl Foo<'static, u32> {}
+
+impl<'a, T> Foo<'a, T> {
+    fn new() -> Self { Self {  } }<|>
+}
+",
+        );
+    }
+
     #[test]
     fn add_new_target() {
         check_assist_target(
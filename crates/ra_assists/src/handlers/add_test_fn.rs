@@ -0,0 +1,259 @@
+use std::iter;
+
+use ra_syntax::{
+    ast::{self, make, AstNode, AttrsOwner, ModuleItemOwner, NameOwner},
+    SyntaxKind::WHITESPACE,
+    TextSize,
+};
+use stdx::format_to;
+
+use crate::{Assist, AssistCtx, AssistId, AssistKind};
+
+// Assist: add_test_fn
+//
+// Adds a test calling the function under the cursor to the `tests` module at
+// the bottom of the file, creating the module if needed.
+//
+// ```
+// fn add<|>(a: i32, b: i32) -> i32 {
+//     a + b
+// }
+// ```
+// ->
+// ```
+// fn add(a: i32, b: i32) -> i32 {
+//     a + b
+// }
+//
+// #[cfg(test)]
+// mod tests {
+//     use super::*;
+//
+//     #[test]
+//     fn test_add() {
+//         add(todo!(), todo!());
+//     }
+// }
+// ```
+pub(crate) fn add_test_fn(ctx: AssistCtx) -> Option<Assist> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    // Only the functions at the top of the file can be reached from the tests
+    // module through `use super::*`.
+    let source_file = fn_def.syntax().parent().and_then(ast::SourceFile::cast)?;
+    if let Some(body) = fn_def.body() {
+        if body.syntax().text_range().contains_range(ctx.frange.range) {
+            return None;
+        }
+    }
+    if fn_def.attrs().any(|it| it.simple_name().map_or(false, |name| name == "test")) {
+        return None;
+    }
+    let name = fn_def.name()?;
+    let test_name = format!("test_{}", name.text());
+
+    let tests_module = source_file.items().find_map(|item| match item {
+        ast::ModuleItem::Module(it) if is_tests_module(&it) => it.item_list(),
+        _ => None,
+    });
+    if let Some(item_list) = &tests_module {
+        let has_test = item_list.items().any(|item| match item {
+            ast::ModuleItem::FnDef(it) => {
+                it.name().map_or(false, |it| it.text().as_str() == test_name)
+            }
+            _ => false,
+        });
+        if has_test {
+            return None;
+        }
+    }
+
+    let n_params = fn_def.param_list().map_or(0, |it| it.params().count());
+    let callee =
+        make::expr_path(make::path_unqualified(make::path_segment(make::name_ref(name.text()))));
+    let args = make::arg_list(iter::repeat_with(make::expr_todo).take(n_params));
+    let call = format!("{};", make::expr_call(callee, args));
+    let mut test_fn = String::new();
+    format_to!(test_fn, "    #[test]\n    fn {}() {{\n", test_name);
+    let call_offset = TextSize::of(&test_fn) + TextSize::of("        ");
+    format_to!(test_fn, "        {}\n    }}", call);
+
+    ctx.add_assist(AssistId("add_test_fn", AssistKind::Generate), "Add a test", |edit| {
+        edit.target(fn_def.syntax().text_range());
+        match tests_module.as_ref().and_then(|it| Some((it, it.r_curly_token()?))) {
+            Some((item_list, r_curly)) => {
+                let whitespace = r_curly.prev_token().filter(|it| it.kind() == WHITESPACE);
+                let offset = whitespace.as_ref().map_or(r_curly.text_range(), |it| it.text_range());
+                let mut buf = String::new();
+                buf.push_str(if item_list.items().next().is_some() { "\n\n" } else { "\n" });
+                let cursor = offset.start() + TextSize::of(&buf) + call_offset;
+                buf.push_str(&test_fn);
+                if whitespace.is_none() {
+                    buf.push('\n');
+                }
+                edit.insert(offset.start(), buf);
+                edit.set_cursor(cursor);
+            }
+            None => {
+                let offset = source_file.syntax().text_range().end();
+                let mut buf = String::new();
+                buf.push_str(if source_file.syntax().text().to_string().ends_with('\n') {
+                    "\n"
+                } else {
+                    "\n\n"
+                });
+                buf.push_str("#[cfg(test)]\nmod tests {\n    use super::*;\n\n");
+                let cursor = offset + TextSize::of(&buf) + call_offset;
+                buf.push_str(&test_fn);
+                buf.push_str("\n}\n");
+                edit.insert(offset, buf);
+                edit.set_cursor(cursor);
+            }
+        }
+    })
+}
+
+fn is_tests_module(module: &ast::Module) -> bool {
+    module.attrs().any(|attr| match attr.as_simple_call() {
+        Some((name, args)) => name == "cfg" && args.syntax().text() == "(test)",
+        None => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn add_test_fn_creates_module() {
+        check_assist(
+            add_test_fn,
+            r#"
+fn foo<|>() {}
+"#,
+            r#"
+fn foo() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_foo() {
+        <|>foo();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_test_fn_appends_to_module() {
+        check_assist(
+            add_test_fn,
+            r#"
+fn foo() {}
+
+pub fn <|>bar(x: u32, y: &str) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_foo() {
+        foo();
+    }
+}
+"#,
+            r#"
+fn foo() {}
+
+pub fn bar(x: u32, y: &str) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_foo() {
+        foo();
+    }
+
+    #[test]
+    fn test_bar() {
+        <|>bar(todo!(), todo!());
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_test_fn_to_empty_module() {
+        check_assist(
+            add_test_fn,
+            r#"
+fn <|>foo() {}
+
+#[cfg(test)]
+mod tests {}
+"#,
+            r#"
+fn foo() {}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_foo() {
+        <|>foo();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_test_fn_target() {
+        check_assist_target(add_test_fn, "fn <|>foo() {}", "fn foo() {}");
+    }
+
+    #[test]
+    fn not_applicable_when_test_exists() {
+        check_assist_not_applicable(
+            add_test_fn,
+            r#"
+fn <|>foo() {}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_foo() {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_methods() {
+        check_assist_not_applicable(
+            add_test_fn,
+            r#"
+struct S;
+impl S {
+    fn <|>foo(&self) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_body() {
+        check_assist_not_applicable(add_test_fn, "fn foo() { <|>bar(); }");
+    }
+}
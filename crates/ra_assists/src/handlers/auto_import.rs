@@ -1,8 +1,8 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use hir::{
-    AsAssocItem, AssocItemContainer, ModPath, Module, ModuleDef, PathResolution, Semantics, Trait,
-    Type,
+    AsAssocItem, AssocItemContainer, MacroDef, ModPath, Module, ModuleDef, PathResolution,
+    Semantics, Trait, Type,
 };
 use ra_ide_db::{imports_locator::ImportsLocator, RootDatabase};
 use ra_prof::profile;
@@ -15,7 +15,7 @@ use rustc_hash::FxHashSet;
 use crate::{
     assist_ctx::{Assist, AssistCtx},
     utils::insert_use_statement,
-    AssistId,
+    AssistId, AssistKind,
 };
 use either::Either;
 
@@ -48,49 +48,71 @@ pub(crate) fn auto_import(ctx: AssistCtx) -> Option<Assist> {
     let range = ctx.sema.original_range(&auto_import_assets.syntax_under_caret).range;
     let mut group = ctx.add_assist_group(auto_import_assets.get_import_group_message());
     for import in proposed_imports {
-        group.add_assist(AssistId("auto_import"), format!("Import `{}`", &import), |edit| {
-            edit.target(range);
-            insert_use_statement(&auto_import_assets.syntax_under_caret, &import, edit);
-        });
+        group.add_assist(
+            AssistId("auto_import", AssistKind::QuickFix),
+            format!("Import `{}`", &import),
+            |edit| {
+                edit.target(range);
+                insert_use_statement(&auto_import_assets.syntax_under_caret, &import, edit);
+            },
+        );
     }
     group.finish()
 }
 
+/// Returns the paths which could be imported to make the unresolved `path`
+/// resolve.
+pub(crate) fn find_imports_for_path(
+    sema: &Semantics<RootDatabase>,
+    path: &ast::Path,
+) -> BTreeSet<ModPath> {
+    match AutoImportAssets::for_regular_path(path.clone(), sema) {
+        Some(assets) => assets.search_for_imports(sema.db),
+        None => BTreeSet::new(),
+    }
+}
+
 #[derive(Debug)]
-struct AutoImportAssets {
-    import_candidate: ImportCandidate,
+pub(crate) struct AutoImportAssets {
+    pub(crate) import_candidate: ImportCandidate,
     module_with_name_to_import: Module,
-    syntax_under_caret: SyntaxNode,
+    pub(crate) syntax_under_caret: SyntaxNode,
 }
 
 impl AutoImportAssets {
-    fn new(ctx: &AssistCtx) -> Option<Self> {
+    pub(crate) fn new(ctx: &AssistCtx) -> Option<Self> {
         if let Some(path_under_caret) = ctx.find_node_at_offset_with_descend::<ast::Path>() {
-            Self::for_regular_path(path_under_caret, &ctx)
+            Self::for_regular_path(path_under_caret, &ctx.sema)
         } else {
-            Self::for_method_call(ctx.find_node_at_offset_with_descend()?, &ctx)
+            Self::for_method_call(ctx.find_node_at_offset_with_descend()?, &ctx.sema)
         }
     }
 
-    fn for_method_call(method_call: ast::MethodCallExpr, ctx: &AssistCtx) -> Option<Self> {
+    fn for_method_call(
+        method_call: ast::MethodCallExpr,
+        sema: &Semantics<RootDatabase>,
+    ) -> Option<Self> {
         let syntax_under_caret = method_call.syntax().to_owned();
-        let module_with_name_to_import = ctx.sema.scope(&syntax_under_caret).module()?;
+        let module_with_name_to_import = sema.scope(&syntax_under_caret).module()?;
         Some(Self {
-            import_candidate: ImportCandidate::for_method_call(&ctx.sema, &method_call)?,
+            import_candidate: ImportCandidate::for_method_call(sema, &method_call)?,
             module_with_name_to_import,
             syntax_under_caret,
         })
     }
 
-    fn for_regular_path(path_under_caret: ast::Path, ctx: &AssistCtx) -> Option<Self> {
+    fn for_regular_path(
+        path_under_caret: ast::Path,
+        sema: &Semantics<RootDatabase>,
+    ) -> Option<Self> {
         let syntax_under_caret = path_under_caret.syntax().to_owned();
         if syntax_under_caret.ancestors().find_map(ast::UseItem::cast).is_some() {
             return None;
         }
 
-        let module_with_name_to_import = ctx.sema.scope(&syntax_under_caret).module()?;
+        let module_with_name_to_import = sema.scope(&syntax_under_caret).module()?;
         Some(Self {
-            import_candidate: ImportCandidate::for_regular_path(&ctx.sema, &path_under_caret)?,
+            import_candidate: ImportCandidate::for_regular_path(sema, &path_under_caret)?,
             module_with_name_to_import,
             syntax_under_caret,
         })
@@ -120,7 +142,31 @@ impl AutoImportAssets {
         }
     }
 
+    pub(crate) fn get_qualify_group_message(&self) -> String {
+        match &self.import_candidate {
+            ImportCandidate::UnqualifiedName(name) => format!("Qualify {}", name),
+            ImportCandidate::QualifierStart(qualifier_start) => {
+                format!("Qualify {}", qualifier_start)
+            }
+            ImportCandidate::TraitAssocItem(_, trait_assoc_item_name) => {
+                format!("Qualify with a trait for item {}", trait_assoc_item_name)
+            }
+            ImportCandidate::TraitMethod(_, trait_method_name) => {
+                format!("Qualify with a trait for method {}", trait_method_name)
+            }
+        }
+    }
+
     fn search_for_imports(&self, db: &RootDatabase) -> BTreeSet<ModPath> {
+        self.search_for_items(db).into_iter().map(|(path, _)| path).collect()
+    }
+
+    /// Returns the paths which could be imported, along with the items they
+    /// refer to. For trait items, these are the paths of the traits.
+    pub(crate) fn search_for_items(
+        &self,
+        db: &RootDatabase,
+    ) -> BTreeMap<ModPath, Either<ModuleDef, MacroDef>> {
         let _p = profile("auto_import::search_for_imports");
         let current_crate = self.module_with_name_to_import.krate();
         ImportsLocator::new(db)
@@ -183,17 +229,20 @@ impl AutoImportAssets {
                 }
                 _ => Some(candidate),
             })
-            .filter_map(|candidate| match candidate {
-                Either::Left(module_def) => {
-                    self.module_with_name_to_import.find_use_path(db, module_def)
-                }
-                Either::Right(macro_def) => {
-                    self.module_with_name_to_import.find_use_path(db, macro_def)
-                }
+            .filter_map(|candidate| {
+                let use_path = match candidate {
+                    Either::Left(module_def) => {
+                        self.module_with_name_to_import.find_use_path(db, module_def)
+                    }
+                    Either::Right(macro_def) => {
+                        self.module_with_name_to_import.find_use_path(db, macro_def)
+                    }
+                }?;
+                Some((use_path, candidate))
             })
-            .filter(|use_path| !use_path.segments.is_empty())
+            .filter(|(use_path, _)| !use_path.segments.is_empty())
             .take(20)
-            .collect::<BTreeSet<_>>()
+            .collect::<BTreeMap<_, _>>()
     }
 
     fn assoc_to_trait(assoc: AssocItemContainer) -> Option<Trait> {
@@ -206,7 +255,7 @@ impl AutoImportAssets {
 }
 
 #[derive(Debug)]
-enum ImportCandidate {
+pub(crate) enum ImportCandidate {
     /// Simple name like 'HashMap'
     UnqualifiedName(String),
     /// First part of the qualified name.
@@ -0,0 +1,490 @@
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, make, ArgListOwner, LoopBodyOwner, TypeAscriptionOwner},
+    AstNode, SyntaxKind, SyntaxNode, T,
+};
+
+use crate::{utils::FamousDefs, Assist, AssistCtx, AssistId, AssistKind};
+
+// Assist: convert_for_to_iter_chain
+//
+// Converts a `for` loop pushing to a vector or with a single `if` in its body
+// to an iterator chain. This assist can only be applied with the cursor on
+// `for`.
+//
+// ```
+// fn main() {
+//     let xs = vec![1, 2, 3];
+//     let mut squares = Vec::new();
+//     for<|> x in &xs {
+//         squares.push(x * x);
+//     }
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let xs = vec![1, 2, 3];
+//     let mut squares: Vec<_> = xs.iter().map(|x| x * x).collect();
+// }
+// ```
+pub(crate) fn convert_for_to_iter_chain(ctx: AssistCtx) -> Option<Assist> {
+    let for_keyword = ctx.find_token_at_offset(T![for])?;
+    let for_expr = ast::ForExpr::cast(for_keyword.parent())?;
+    if !for_keyword.text_range().contains_range(ctx.frange.range) || for_expr.label().is_some() {
+        return None;
+    }
+    let pat = for_expr.pat()?;
+    let body = for_expr.loop_body()?;
+    if has_control_flow(body.syntax()) {
+        return None;
+    }
+    let iter = iter_chain_start(&ctx, &for_expr, &for_expr.iterable()?)?;
+
+    // The loop is either the tail expression of its block, or a statement.
+    let stmt = match for_expr.syntax().parent() {
+        Some(parent) if ast::ExprStmt::can_cast(parent.kind()) => parent,
+        _ => for_expr.syntax().clone(),
+    };
+    let mut range = stmt.text_range();
+    let chain = match single_expr(&body)? {
+        ast::Expr::MethodCallExpr(call) if is_method(&call, "push") => {
+            let receiver = call.expr()?;
+            let arg = single_arg(&call)?;
+            let arg = IndentLevel(1).decrease_indent(arg);
+            let map = format!("{}.map(|{}| {})", iter, pat, arg);
+            // `let mut v = Vec::new();` right before the loop can be merged with it.
+            let let_stmt = stmt
+                .prev_sibling()
+                .and_then(ast::LetStmt::cast)
+                .filter(|it| is_empty_vec_init(it, &receiver.syntax().to_string()));
+            match let_stmt {
+                Some(let_stmt) => {
+                    range = range.cover(let_stmt.syntax().text_range());
+                    let ty = let_stmt
+                        .ascribed_type()
+                        .map_or_else(|| "Vec<_>".to_string(), |it| it.to_string());
+                    format!("let {}: {} = {}.collect();", let_stmt.pat()?, ty, map)
+                }
+                None => format!("{}.extend({});", receiver, map),
+            }
+        }
+        ast::Expr::IfExpr(if_expr) if if_expr.else_branch().is_none() => {
+            let condition = if_expr.condition()?;
+            if condition.pat().is_some() {
+                return None;
+            }
+            let then_branch = IndentLevel(1).decrease_indent(if_expr.then_branch()?);
+            format!(
+                "{}.filter(|{}| {}).for_each(|{}| {});",
+                iter,
+                pat,
+                condition.expr()?,
+                pat,
+                then_branch
+            )
+        }
+        _ => return None,
+    };
+
+    ctx.add_assist(
+        AssistId("convert_for_to_iter_chain", AssistKind::RefactorRewrite),
+        "Convert to an iterator chain",
+        |edit| {
+            edit.target(for_expr.syntax().text_range());
+            edit.set_cursor(range.start());
+            edit.replace(range, chain);
+        },
+    )
+}
+
+// Assist: convert_for_each_to_for_loop
+//
+// Converts a `for_each` call to a `for` loop.
+//
+// ```
+// fn main() {
+//     let xs = vec![1, 2, 3];
+//     xs.iter().for_each<|>(|x| {
+//         println!("{}", x);
+//     });
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let xs = vec![1, 2, 3];
+//     for x in &xs {
+//         println!("{}", x);
+//     }
+// }
+// ```
+pub(crate) fn convert_for_each_to_for_loop(ctx: AssistCtx) -> Option<Assist> {
+    let name_ref = ctx.find_node_at_offset::<ast::NameRef>()?;
+    let call = ast::MethodCallExpr::cast(name_ref.syntax().parent()?)?;
+    if !is_method(&call, "for_each") {
+        return None;
+    }
+    // The value of the call can't be replaced by a loop.
+    let stmt = ast::ExprStmt::cast(call.syntax().parent()?)?;
+    let closure = match single_arg(&call)? {
+        ast::Expr::LambdaExpr(it) => it,
+        _ => return None,
+    };
+    let mut params = closure.param_list()?.params();
+    let pat = params.next()?.pat()?;
+    if params.next().is_some() {
+        return None;
+    }
+    let body = closure.body()?;
+    // `return` and `?` leave the closure, but would leave the function in a loop.
+    if body
+        .syntax()
+        .descendants()
+        .any(|it| it.kind() == SyntaxKind::RETURN_EXPR || it.kind() == SyntaxKind::TRY_EXPR)
+    {
+        return None;
+    }
+    let body = match body {
+        ast::Expr::BlockExpr(it) => it,
+        expr => {
+            let block = make::block_expr(Some(ast::Stmt::ExprStmt(make::expr_stmt(expr))), None);
+            IndentLevel::from_node(stmt.syntax()).increase_indent(block)
+        }
+    };
+    let iterable = iterable_from_chain(call.expr()?);
+
+    ctx.add_assist(
+        AssistId("convert_for_each_to_for_loop", AssistKind::RefactorRewrite),
+        "Convert to a for loop",
+        |edit| {
+            let range = stmt.syntax().text_range();
+            edit.target(call.syntax().text_range());
+            edit.set_cursor(range.start());
+            edit.replace(range, format!("for {} in {} {}", pat, iterable, body));
+        },
+    )
+}
+
+/// Returns the start of an iterator chain iterating over `iterable` like
+/// `for_expr` does.
+fn iter_chain_start(
+    ctx: &AssistCtx,
+    for_expr: &ast::ForExpr,
+    iterable: &ast::Expr,
+) -> Option<String> {
+    if let ast::Expr::RefExpr(ref_expr) = iterable {
+        let method = if ref_expr.mut_token().is_some() { "iter_mut" } else { "iter" };
+        return Some(format!("{}.{}()", receiver_text(&ref_expr.expr()?), method));
+    }
+
+    let krate = ctx.sema.scope(for_expr.syntax()).module()?.krate();
+    let is_iterator = FamousDefs(ctx.sema, krate).core_iter_Iterator().map_or(false, |iterator| {
+        ctx.sema.type_of_expr(iterable).map_or(false, |ty| ty.impls_trait(ctx.db, iterator, &[]))
+    });
+    let receiver = receiver_text(iterable);
+    if is_iterator {
+        Some(receiver)
+    } else {
+        Some(format!("{}.into_iter()", receiver))
+    }
+}
+
+/// Turns `xs.iter()` into `&xs`, the way it would be written in a `for` loop.
+fn iterable_from_chain(receiver: ast::Expr) -> String {
+    if let ast::Expr::MethodCallExpr(call) = &receiver {
+        let no_args = call.arg_list().map_or(false, |it| it.args().next().is_none());
+        if let (Some(expr), true) = (call.expr(), no_args) {
+            if is_method(call, "iter") {
+                return format!("&{}", expr);
+            }
+            if is_method(call, "iter_mut") {
+                return format!("&mut {}", expr);
+            }
+            if is_method(call, "into_iter") {
+                return expr.to_string();
+            }
+        }
+    }
+    receiver.to_string()
+}
+
+fn receiver_text(expr: &ast::Expr) -> String {
+    match expr {
+        ast::Expr::BinExpr(_)
+        | ast::Expr::CastExpr(_)
+        | ast::Expr::RangeExpr(_)
+        | ast::Expr::RefExpr(_)
+        | ast::Expr::PrefixExpr(_) => format!("({})", expr),
+        _ => expr.to_string(),
+    }
+}
+
+/// Whether `node` contains control flow which would behave differently inside
+/// of a closure.
+fn has_control_flow(node: &SyntaxNode) -> bool {
+    node.descendants().any(|it| {
+        matches!(
+            it.kind(),
+            SyntaxKind::BREAK_EXPR
+                | SyntaxKind::CONTINUE_EXPR
+                | SyntaxKind::RETURN_EXPR
+                | SyntaxKind::TRY_EXPR
+                | SyntaxKind::AWAIT_EXPR
+        )
+    })
+}
+
+/// Returns the only expression of `block`, if it has nothing else.
+fn single_expr(block: &ast::BlockExpr) -> Option<ast::Expr> {
+    if block.syntax().children_with_tokens().any(|it| it.kind() == SyntaxKind::COMMENT) {
+        return None;
+    }
+    let mut statements = block.statements();
+    match (statements.next(), statements.next(), block.expr()) {
+        (Some(ast::Stmt::ExprStmt(stmt)), None, None) => stmt.expr(),
+        (None, None, Some(expr)) => Some(expr),
+        _ => None,
+    }
+}
+
+fn single_arg(call: &ast::MethodCallExpr) -> Option<ast::Expr> {
+    let mut args = call.arg_list()?.args();
+    let arg = args.next()?;
+    if args.next().is_some() {
+        return None;
+    }
+    Some(arg)
+}
+
+fn is_method(call: &ast::MethodCallExpr, name: &str) -> bool {
+    call.name_ref().map_or(false, |it| it.text() == name)
+}
+
+fn is_empty_vec_init(let_stmt: &ast::LetStmt, name: &str) -> bool {
+    let is_binding = match let_stmt.pat() {
+        Some(ast::Pat::BindPat(pat)) => pat.name().map_or(false, |it| it.text() == name),
+        _ => false,
+    };
+    let is_empty_vec = let_stmt.initializer().map_or(false, |it| {
+        let text = it.syntax().to_string();
+        text == "Vec::new()" || text == "vec![]"
+    });
+    is_binding && is_empty_vec
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn convert_push_loop_to_collect() {
+        check_assist(
+            convert_for_to_iter_chain,
+            r#"
+fn main() {
+    let xs = vec![1, 2, 3];
+    let mut squares = Vec::new();
+    for<|> x in &xs {
+        squares.push(x * x);
+    }
+}
+"#,
+            r#"
+fn main() {
+    let xs = vec![1, 2, 3];
+    <|>let mut squares: Vec<_> = xs.iter().map(|x| x * x).collect();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_push_loop_to_extend() {
+        check_assist(
+            convert_for_to_iter_chain,
+            r#"
+fn main() {
+    let mut squares = vec![0];
+    for<|> x in 1..4 {
+        squares.push(x * x)
+    }
+}
+"#,
+            r#"
+fn main() {
+    let mut squares = vec![0];
+    <|>squares.extend((1..4).into_iter().map(|x| x * x));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_if_loop_to_filter() {
+        check_assist(
+            convert_for_to_iter_chain,
+            r#"
+fn main() {
+    for<|> x in &mut xs {
+        if x.is_odd() {
+            x.double();
+            x.print();
+        }
+    }
+}
+"#,
+            r#"
+fn main() {
+    <|>xs.iter_mut().filter(|x| x.is_odd()).for_each(|x| {
+        x.double();
+        x.print();
+    });
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_loop_over_iterator() {
+        check_assist(
+            convert_for_to_iter_chain,
+            &format!(
+                "//- main.rs crate:main deps:core\n{}\n{}",
+                r#"
+struct Counter;
+impl Iterator for Counter { type Item = u32; }
+
+fn main(counter: Counter, mut v: Vec<u32>) {
+    for<|> x in counter {
+        v.push(x);
+    }
+}
+"#,
+                FamousDefs::FIXTURE
+            ),
+            r#"
+struct Counter;
+impl Iterator for Counter { type Item = u32; }
+
+fn main(counter: Counter, mut v: Vec<u32>) {
+    <|>v.extend(counter.map(|x| x));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_for_to_iter_chain_target() {
+        check_assist_target(
+            convert_for_to_iter_chain,
+            "fn f() { for<|> x in xs { v.push(x) } }",
+            "for x in xs { v.push(x) }",
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_break() {
+        check_assist_not_applicable(
+            convert_for_to_iter_chain,
+            r#"
+fn main() {
+    for<|> x in &xs {
+        if x.is_odd() {
+            break;
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_several_statements() {
+        check_assist_not_applicable(
+            convert_for_to_iter_chain,
+            r#"
+fn main() {
+    for<|> x in &xs {
+        v.push(x);
+        w.push(x);
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_for_each_with_block() {
+        check_assist(
+            convert_for_each_to_for_loop,
+            r#"
+fn main() {
+    xs.iter_mut().for_each<|>(|x| {
+        x.double();
+    });
+}
+"#,
+            r#"
+fn main() {
+    <|>for x in &mut xs {
+        x.double();
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_for_each_with_expr() {
+        check_assist(
+            convert_for_each_to_for_loop,
+            r#"
+fn main() {
+    xs.iter().filter(|x| x.is_odd()).for_each<|>(|x| print(x));
+}
+"#,
+            r#"
+fn main() {
+    <|>for x in xs.iter().filter(|x| x.is_odd()) {
+        print(x);
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_for_each_to_for_loop_target() {
+        check_assist_target(
+            convert_for_each_to_for_loop,
+            "fn f() { xs.into_iter().for_each<|>(|x| print(x)); }",
+            "xs.into_iter().for_each(|x| print(x))",
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_for_each_with_return() {
+        check_assist_not_applicable(
+            convert_for_each_to_for_loop,
+            r#"
+fn main() {
+    xs.iter().for_each<|>(|x| {
+        if x.is_odd() {
+            return;
+        }
+        print(x);
+    });
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_for_each_value() {
+        check_assist_not_applicable(
+            convert_for_each_to_for_loop,
+            "fn f() -> () { xs.iter().for_each<|>(|x| print(x)) }",
+        );
+    }
+}
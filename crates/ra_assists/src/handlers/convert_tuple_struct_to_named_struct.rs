@@ -0,0 +1,399 @@
+use hir::{Adt, ModuleDef, Semantics};
+use ra_ide_db::{defs::Definition, RootDatabase};
+use ra_syntax::{
+    algo::find_node_at_offset,
+    ast::{self, AstNode, TypeParamsOwner},
+    SyntaxKind::WHITESPACE,
+    SyntaxToken, TextRange,
+};
+use ra_text_edit::TextEditBuilder;
+use stdx::SepBy;
+
+use crate::{assist_ctx::ActionBuilder, Assist, AssistCtx, AssistId, AssistKind};
+
+// Assist: convert_tuple_struct_to_named_struct
+//
+// Converts a tuple struct to a struct with named fields, and updates its
+// constructors, patterns and field accesses.
+//
+// ```
+// struct Point<|>(f32, f32);
+//
+// fn origin() -> Point {
+//     Point(0.0, 0.0)
+// }
+//
+// fn x(p: &Point) -> f32 {
+//     p.0
+// }
+// ```
+// ->
+// ```
+// struct Point { field0: f32, field1: f32 }
+//
+// fn origin() -> Point {
+//     Point { field0: 0.0, field1: 0.0 }
+// }
+//
+// fn x(p: &Point) -> f32 {
+//     p.field0
+// }
+// ```
+pub(crate) fn convert_tuple_struct_to_named_struct(ctx: AssistCtx) -> Option<Assist> {
+    let strukt = ctx.find_node_at_offset::<ast::StructDef>()?;
+    let field_list = match strukt.kind() {
+        ast::StructKind::Tuple(it) => it,
+        _ => return None,
+    };
+    let struct_def = ctx.sema.to_def(&strukt)?;
+
+    let sema = ctx.sema;
+    let db = ctx.db;
+    let file_id = ctx.frange.file_id;
+    ctx.add_assist(
+        AssistId("convert_tuple_struct_to_named_struct", AssistKind::RefactorRewrite),
+        "Convert to named struct",
+        |edit| {
+            edit.target(strukt.syntax().text_range());
+
+            let names = (0..field_list.fields().count())
+                .map(|idx| format!("field{}", idx))
+                .collect::<Vec<_>>();
+            edit_struct_def(edit.file_edit_builder(file_id), &strukt, &field_list, &names);
+            edit_struct_usages(edit, sema, struct_def, &names);
+            edit_field_usages(edit, db, struct_def, &names);
+        },
+    )
+}
+
+fn edit_struct_def(
+    builder: &mut TextEditBuilder,
+    strukt: &ast::StructDef,
+    field_list: &ast::TupleFieldDefList,
+    names: &[String],
+) {
+    let fields = field_list
+        .fields()
+        .zip(names)
+        .map(|(field, name)| {
+            // Keep the attributes and the visibility of the field.
+            let text = field.syntax().text().to_string();
+            let ty_offset = match field.type_ref() {
+                Some(ty) => usize::from(
+                    ty.syntax().text_range().start() - field.syntax().text_range().start(),
+                ),
+                None => text.len(),
+            };
+            format!("{}{}: {}", &text[..ty_offset], name, &text[ty_offset..])
+        })
+        .sep_by(", ")
+        .to_string();
+    let record = if fields.is_empty() { " {}".to_string() } else { format!(" {{ {} }}", fields) };
+
+    let field_list_range = field_list.syntax().text_range();
+    match (strukt.where_clause(), strukt.semicolon_token()) {
+        (Some(_), Some(semicolon)) => {
+            builder.delete(field_list_range);
+            builder.replace(semicolon.text_range(), record);
+        }
+        (Some(where_clause), None) => {
+            builder.delete(field_list_range);
+            builder.insert(where_clause.syntax().text_range().end(), record);
+        }
+        (None, semicolon) => {
+            let end = semicolon.map_or(field_list_range.end(), |it| it.text_range().end());
+            builder.replace(TextRange::new(field_list_range.start(), end), record);
+        }
+    }
+}
+
+/// Rewrites `S(a, b)` constructors and patterns into `S { field0: a, field1: b }`.
+// FIXME: `Self(a, b)` isn't found, as usages are searched by name.
+fn edit_struct_usages(
+    edit: &mut ActionBuilder,
+    sema: &Semantics<RootDatabase>,
+    struct_def: hir::Struct,
+    names: &[String],
+) {
+    let def = Definition::ModuleDef(ModuleDef::Adt(Adt::Struct(struct_def)));
+    for reference in def.find_usages(sema.db, None) {
+        let file_id = reference.file_range.file_id;
+        let source_file = sema.parse(file_id);
+        let name_ref: ast::NameRef =
+            match find_node_at_offset(source_file.syntax(), reference.file_range.range.start()) {
+                Some(it) => it,
+                None => continue,
+            };
+        let path = match name_ref.syntax().parent().and_then(|it| it.parent()) {
+            Some(it) => it,
+            None => continue,
+        };
+        let parent = match path.parent() {
+            Some(it) => it,
+            None => continue,
+        };
+
+        if let Some(pat) = ast::TupleStructPat::cast(parent.clone()) {
+            let (l_paren, r_paren) = match (pat.l_paren_token(), pat.r_paren_token()) {
+                (Some(l), Some(r)) => (l, r),
+                _ => continue,
+            };
+            let args = pat.args().collect::<Vec<_>>();
+            // Patterns after `..` match the trailing fields.
+            let rest = args.iter().position(|it| matches!(it, ast::Pat::DotDotPat(_)));
+            let items = args
+                .iter()
+                .enumerate()
+                .map(|(idx, arg)| {
+                    let name = match rest {
+                        Some(rest) if idx == rest => None,
+                        Some(rest) if idx > rest => {
+                            names.get(names.len().checked_sub(args.len() - idx)?)
+                        }
+                        _ => names.get(idx),
+                    };
+                    Some((name, arg.syntax().text_range()))
+                })
+                .collect::<Option<Vec<_>>>();
+            let items = match items {
+                Some(it) => it,
+                None => continue,
+            };
+            let builder = edit.file_edit_builder(file_id);
+            parens_to_braces(builder, l_paren, r_paren, &items);
+            // `..` has to come last in record patterns.
+            if let (Some(rest), Some(last)) = (rest, args.last()) {
+                if rest + 1 < args.len() {
+                    let next = args[rest + 1].syntax().text_range().start();
+                    builder.delete(TextRange::new(args[rest].syntax().text_range().start(), next));
+                    builder.insert(last.syntax().text_range().end(), ", ..".to_string());
+                }
+            }
+        } else if let Some(path_expr) = ast::PathExpr::cast(parent) {
+            let call = match path_expr.syntax().parent().and_then(ast::CallExpr::cast) {
+                Some(it) => it,
+                None => continue,
+            };
+            let arg_list = match call.arg_list() {
+                Some(it) => it,
+                None => continue,
+            };
+            let (l_paren, r_paren) = match (arg_list.l_paren_token(), arg_list.r_paren_token()) {
+                (Some(l), Some(r)) => (l, r),
+                _ => continue,
+            };
+            let items = arg_list
+                .args()
+                .zip(names)
+                .map(|(arg, name)| (Some(name), arg.syntax().text_range()))
+                .collect::<Vec<_>>();
+            parens_to_braces(edit.file_edit_builder(file_id), l_paren, r_paren, &items);
+        }
+    }
+}
+
+/// Rewrites `.0` field accesses into `.field0`.
+fn edit_field_usages(
+    edit: &mut ActionBuilder,
+    db: &RootDatabase,
+    struct_def: hir::Struct,
+    names: &[String],
+) {
+    for (field, name) in struct_def.fields(db).into_iter().zip(names) {
+        for reference in Definition::Field(field).find_usages(db, None) {
+            let file_range = reference.file_range;
+            edit.file_edit_builder(file_range.file_id).replace(file_range.range, name.clone());
+        }
+    }
+}
+
+/// Replaces the parentheses around `items` with braces, and prefixes each item
+/// with its field name, if any.
+fn parens_to_braces(
+    builder: &mut TextEditBuilder,
+    l_paren: SyntaxToken,
+    r_paren: SyntaxToken,
+    items: &[(Option<&String>, TextRange)],
+) {
+    if items.is_empty() {
+        builder.replace(
+            TextRange::new(l_paren.text_range().start(), r_paren.text_range().end()),
+            " {}".to_string(),
+        );
+        return;
+    }
+
+    let l_curly = match l_paren.next_token() {
+        Some(it) if it.kind() == WHITESPACE => " {",
+        _ => " { ",
+    };
+    builder.replace(l_paren.text_range(), l_curly.to_string());
+    for (name, range) in items {
+        if let Some(name) = name {
+            builder.insert(range.start(), format!("{}: ", name));
+        }
+    }
+    let r_curly = match r_paren.prev_token() {
+        Some(it) if it.kind() == WHITESPACE => "}",
+        _ => " }",
+    };
+    builder.replace(r_paren.text_range(), r_curly.to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use ra_db::{fixture::WithFixture, FileRange, SourceDatabaseExt};
+    use ra_ide_db::symbol_index::SymbolsDatabase;
+    use test_utils::assert_eq_text;
+
+    use crate::{
+        helpers::{check_assist, check_assist_not_applicable},
+        AssistConfig,
+    };
+
+    use super::*;
+
+    #[test]
+    fn convert_simple_struct() {
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+struct Inner;
+struct A<|>(pub Inner, #[allow(unused)] u32);
+
+impl A {
+    fn new(inner: Inner) -> A {
+        A(inner, 92)
+    }
+
+    fn into_inner(self) -> Inner {
+        self.0
+    }
+}
+"#,
+            r#"
+struct Inner;
+struct A<|> { pub field0: Inner, #[allow(unused)] field1: u32 }
+
+impl A {
+    fn new(inner: Inner) -> A {
+        A { field0: inner, field1: 92 }
+    }
+
+    fn into_inner(self) -> Inner {
+        self.field0
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_struct_with_where_clause() {
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+struct Wrap<|><T>(T) where T: Copy;
+"#,
+            r#"
+struct Wrap<|><T> where T: Copy { field0: T }
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_patterns() {
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+struct S<|>(u32, u32, u32);
+
+fn f(s: S) -> u32 {
+    let S(a, ..) = s;
+    match s {
+        S(.., c) => a + c,
+    }
+}
+"#,
+            r#"
+struct S<|> { field0: u32, field1: u32, field2: u32 }
+
+fn f(s: S) -> u32 {
+    let S { field0: a, .. } = s;
+    match s {
+        S { field2: c, .. } => a + c,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_multiline_constructor() {
+        check_assist(
+            convert_tuple_struct_to_named_struct,
+            r#"
+struct S<|>(u32, u32);
+
+fn f(s: S) -> S {
+    S(
+        s.1,
+        S(s.0, 0).0,
+    )
+}
+"#,
+            r#"
+struct S<|> { field0: u32, field1: u32 }
+
+fn f(s: S) -> S {
+    S {
+        field0: s.field1,
+        field1: S { field0: s.field0, field1: 0 }.field0,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn convert_usages_in_other_files() {
+        let (mut db, position) = RootDatabase::with_position(
+            r#"
+//- /main.rs
+mod foo;
+pub struct S<|>(pub u32);
+//- /foo.rs
+fn f() -> u32 {
+    crate::S(92).0
+}
+"#,
+        );
+        db.set_local_roots(Arc::new(vec![db.file_source_root(position.file_id)]));
+        let sema = Semantics::new(&db);
+        let frange =
+            FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) };
+        let assist = convert_tuple_struct_to_named_struct(AssistCtx::new(
+            &sema,
+            &AssistConfig::default(),
+            frange,
+            true,
+        ))
+        .expect("assist is not applicable");
+        let action = assist.0[0].action.clone().unwrap();
+
+        assert_eq!(action.other_file_edits.len(), 1);
+        let (file_id, edit) = &action.other_file_edits[0];
+        let actual = edit.apply(&db.file_text(*file_id));
+        assert_eq_text!("fn f() -> u32 {\n    crate::S { field0: 92 }.field0\n}\n", &actual);
+    }
+
+    #[test]
+    fn not_applicable_to_record_struct() {
+        check_assist_not_applicable(
+            convert_tuple_struct_to_named_struct,
+            "struct S<|> { field: u32 }",
+        );
+    }
+}
@@ -0,0 +1,268 @@
+use hir::{Adt, HasVisibility, ModuleDef, StructKind};
+use ra_ide_db::defs::Definition;
+use ra_syntax::{
+    algo::find_node_at_offset,
+    ast::{self, NameOwner},
+    AstNode,
+};
+use stdx::SepBy;
+
+use crate::{Assist, AssistCtx, AssistId, AssistKind};
+
+// Assist: destructure_binding
+//
+// Replaces a binding of a tuple or a struct with a destructuring pattern, and
+// its field accesses with the new bindings.
+//
+// ```
+// fn main() {
+//     let <|>pair = (1, 2);
+//     let sum = pair.0 + pair.1;
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let (pair0, pair1) = (1, 2);
+//     let sum = pair0 + pair1;
+// }
+// ```
+pub(crate) fn destructure_binding(ctx: AssistCtx) -> Option<Assist> {
+    let bind_pat = ctx.find_node_at_offset::<ast::BindPat>()?;
+    if bind_pat.pat().is_some() {
+        return None;
+    }
+    let parent = bind_pat.syntax().parent()?;
+    if !ast::LetStmt::can_cast(parent.kind()) && !ast::Param::can_cast(parent.kind()) {
+        return None;
+    }
+    let name = bind_pat.name()?.text().to_string();
+    let local = ctx.sema.to_def(&bind_pat)?;
+    let db = ctx.db;
+    let ty = local.ty(db);
+
+    // Pairs of field names and of the bindings replacing them.
+    let (pat_path, kind, fields) = match ty.tuple_fields(db).len() {
+        0 => {
+            let strukt = match ty.as_adt()? {
+                Adt::Struct(it) => it,
+                _ => return None,
+            };
+            let module = ctx.sema.scope(bind_pat.syntax()).module()?;
+            let fields = strukt.fields(db);
+            if fields.is_empty() || !fields.iter().all(|it| it.is_visible_from(db, module)) {
+                return None;
+            }
+            let path = module.find_use_path(db, ModuleDef::Adt(Adt::Struct(strukt)))?;
+            let kind = strukt.kind(db);
+            let fields = fields
+                .iter()
+                .map(|field| {
+                    let field_name = field.name(db).to_string();
+                    let binding = match kind {
+                        StructKind::Record => field_name.clone(),
+                        _ => format!("{}{}", name, field_name),
+                    };
+                    (field_name, binding)
+                })
+                .collect::<Vec<_>>();
+            (Some(path), kind, fields)
+        }
+        len => {
+            let fields = (0..len).map(|idx| (idx.to_string(), format!("{}{}", name, idx)));
+            (None, StructKind::Tuple, fields.collect())
+        }
+    };
+
+    // Usages other than field accesses need the whole value.
+    let mut field_accesses = Vec::new();
+    for reference in Definition::Local(local).find_usages(db, None) {
+        let source_file = ctx.sema.parse(reference.file_range.file_id);
+        let name_ref: ast::NameRef =
+            find_node_at_offset(source_file.syntax(), reference.file_range.range.start())?;
+        let path_expr = name_ref.syntax().ancestors().find_map(ast::PathExpr::cast)?;
+        let field_expr = path_expr.syntax().parent().and_then(ast::FieldExpr::cast)?;
+        let field_name = field_expr.name_ref()?;
+        let (_, binding) =
+            fields.iter().find(|(it, _)| it.as_str() == field_name.text().as_str())?;
+        field_accesses.push((field_expr.syntax().text_range(), binding.clone()));
+    }
+
+    let modifiers = format!(
+        "{}{}",
+        bind_pat.ref_token().map_or("", |_| "ref "),
+        bind_pat.mut_token().map_or("", |_| "mut ")
+    );
+    let bindings = fields.iter().map(|(_, binding)| format!("{}{}", modifiers, binding));
+    let pat = match (pat_path, kind) {
+        (Some(path), StructKind::Record) => format!("{} {{ {} }}", path, bindings.sep_by(", ")),
+        (Some(path), _) => format!("{}({})", path, bindings.sep_by(", ")),
+        // A tuple with a single element needs a trailing comma.
+        (None, _) if fields.len() == 1 => format!("({},)", bindings.sep_by(", ")),
+        (None, _) => format!("({})", bindings.sep_by(", ")),
+    };
+
+    ctx.add_assist(
+        AssistId("destructure_binding", AssistKind::RefactorRewrite),
+        "Destructure binding",
+        |edit| {
+            let range = bind_pat.syntax().text_range();
+            edit.target(range);
+            edit.replace(range, pat);
+            for (range, binding) in field_accesses {
+                edit.replace(range, binding);
+            }
+            edit.set_cursor(range.start());
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn destructure_tuple() {
+        check_assist(
+            destructure_binding,
+            r#"
+fn main() {
+    let <|>pair = (1, "a");
+    let _ = pair.0;
+    foo(pair.1);
+}
+"#,
+            r#"
+fn main() {
+    let <|>(pair0, pair1) = (1, "a");
+    let _ = pair0;
+    foo(pair1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn destructure_single_element_tuple() {
+        check_assist(
+            destructure_binding,
+            r#"
+fn main() {
+    let <|>single = (1,);
+}
+"#,
+            r#"
+fn main() {
+    let <|>(single0,) = (1,);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn destructure_mutable_struct() {
+        check_assist(
+            destructure_binding,
+            r#"
+struct Point { x: i32, y: i32 }
+
+fn main() {
+    let mut <|>p = Point { x: 1, y: 2 };
+    p.x += 1;
+    foo(p.y);
+}
+"#,
+            r#"
+struct Point { x: i32, y: i32 }
+
+fn main() {
+    let <|>Point { mut x, mut y } = Point { x: 1, y: 2 };
+    x += 1;
+    foo(y);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn destructure_tuple_struct_param() {
+        check_assist(
+            destructure_binding,
+            r#"
+struct Meters(u32);
+
+fn double(<|>m: Meters) -> u32 {
+    m.0 * 2
+}
+"#,
+            r#"
+struct Meters(u32);
+
+fn double(<|>Meters(m0): Meters) -> u32 {
+    m0 * 2
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn destructure_struct_from_other_module() {
+        check_assist(
+            destructure_binding,
+            r#"
+mod geo {
+    pub struct Point { pub x: i32, pub y: i32 }
+}
+
+fn f(<|>p: geo::Point) -> i32 {
+    p.x
+}
+"#,
+            r#"
+mod geo {
+    pub struct Point { pub x: i32, pub y: i32 }
+}
+
+fn f(<|>geo::Point { x, y }: geo::Point) -> i32 {
+    x
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn destructure_binding_target() {
+        check_assist_target(destructure_binding, "fn f() { let mut <|>p = (1, 2); }", "mut p");
+    }
+
+    #[test]
+    fn not_applicable_when_used_as_a_whole() {
+        check_assist_not_applicable(
+            destructure_binding,
+            r#"
+fn main() {
+    let <|>pair = (1, 2);
+    foo(pair);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_private_fields() {
+        check_assist_not_applicable(
+            destructure_binding,
+            r#"
+mod geo {
+    pub struct Point { pub x: i32, y: i32 }
+}
+
+fn f(<|>p: geo::Point) -> i32 {
+    p.x
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use ra_db::{RelativePathBuf, SourceDatabaseExt};
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner},
+    TextRange,
+};
+
+use crate::{Assist, AssistCtx, AssistId, AssistKind};
+
+// Assist: extract_module_to_file
+//
+// Moves the body of an inline module into a file of its own. The module keeps
+// its place in the module tree, so paths inside it, including `super::` ones,
+// stay valid.
+//
+// ```
+// mod <|>foo {
+//     fn bar() {}
+// }
+// ```
+// ->
+// ```
+// mod foo;
+// ```
+pub(crate) fn extract_module_to_file(ctx: AssistCtx) -> Option<Assist> {
+    let module = ctx.find_node_at_offset::<ast::Module>()?;
+    let name = module.name()?;
+    let item_list = module.item_list()?;
+    item_list.r_curly_token()?;
+
+    let header = TextRange::new(
+        module.syntax().text_range().start(),
+        item_list.syntax().text_range().start(),
+    );
+    if !header.contains_range(ctx.frange.range) {
+        return None;
+    }
+    // Modules declared inside of functions can't be loaded from files.
+    if module.syntax().ancestors().any(|it| ast::BlockExpr::can_cast(it.kind())) {
+        return None;
+    }
+    if module.attrs().any(|attr| attr.simple_name().as_deref() == Some("path")) {
+        return None;
+    }
+
+    let path = module_file_path(&ctx, &module, &name)?;
+    let contents = dedent_item_list(&item_list);
+
+    ctx.add_assist(
+        AssistId("extract_module_to_file", AssistKind::RefactorExtract),
+        format!("Extract module to `{}`", path),
+        |edit| {
+            edit.target(header);
+            let body_range = TextRange::new(
+                name.syntax().text_range().end(),
+                item_list.syntax().text_range().end(),
+            );
+            edit.replace(body_range, ";");
+            edit.create_file(path, contents);
+        },
+    )
+}
+
+fn module_file_path(
+    ctx: &AssistCtx,
+    module: &ast::Module,
+    name: &ast::Name,
+) -> Option<RelativePathBuf> {
+    let file_id = ctx.frange.file_id;
+    let file_path = ctx.db.file_relative_path(file_id);
+    let file_stem = file_path.file_stem()?;
+    let is_crate_root = ctx.sema.to_module_def(file_id)?.parent(ctx.db).is_none();
+
+    // `mod.rs` files and crate roots own their directory, other files own a
+    // directory named after them.
+    let mut dir = file_path.parent().map(|it| it.to_relative_path_buf()).unwrap_or_default();
+    if file_stem != "mod" && !is_crate_root {
+        dir = dir.join(file_stem);
+    }
+
+    let mut parents = module
+        .syntax()
+        .ancestors()
+        .skip(1)
+        .filter_map(ast::Module::cast)
+        .map(|it| it.name().map(|name| name.text().to_string()))
+        .collect::<Option<Vec<_>>>()?;
+    parents.reverse();
+    for parent in parents {
+        dir = dir.join(parent);
+    }
+
+    Some(dir.join(format!("{}.rs", name.text())))
+}
+
+/// Returns the text between the braces of `item_list`, without the leading and
+/// trailing blank lines and without the indentation shared by all lines.
+fn dedent_item_list(item_list: &ast::ItemList) -> String {
+    let text = item_list.syntax().text().to_string();
+    let text = &text[1..text.len() - 1];
+    let lines = text
+        .lines()
+        .map(|line| line.trim_end())
+        .skip_while(|line| line.is_empty())
+        .collect::<Vec<_>>();
+    let len = lines.iter().rposition(|line| !line.is_empty()).map_or(0, |it| it + 1);
+    let lines = &lines[..len];
+
+    let indent = lines
+        .iter()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    let mut res = String::new();
+    for line in lines {
+        if !line.is_empty() {
+            res.push_str(&line[indent..]);
+        }
+        res.push('\n');
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use hir::Semantics;
+    use ra_db::{fixture::WithFixture, FileRange};
+    use ra_ide_db::{symbol_index::SymbolsDatabase, RootDatabase};
+
+    use crate::{
+        helpers::{check_assist, check_assist_not_applicable, check_assist_target},
+        AssistConfig,
+    };
+
+    use super::*;
+
+    fn check_new_file(ra_fixture: &str, path: &str, contents: &str) {
+        let (mut db, position) = RootDatabase::with_position(ra_fixture);
+        db.set_local_roots(Arc::new(vec![db.file_source_root(position.file_id)]));
+        let sema = Semantics::new(&db);
+        let frange =
+            FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) };
+        let assist =
+            extract_module_to_file(AssistCtx::new(&sema, &AssistConfig::default(), frange, true))
+                .expect("assist is not applicable");
+        let action = assist.0[0].action.clone().unwrap();
+        assert_eq!(action.new_files, vec![(RelativePathBuf::from(path), contents.to_string())]);
+    }
+
+    #[test]
+    fn extract_module() {
+        check_assist(
+            extract_module_to_file,
+            r#"
+mod <|>foo {
+    fn bar() {}
+}
+"#,
+            r#"
+mod <|>foo;
+"#,
+        );
+    }
+
+    #[test]
+    fn extract_module_target() {
+        check_assist_target(
+            extract_module_to_file,
+            r#"
+pub(crate) mod <|>foo {
+    fn bar() {}
+}
+"#,
+            "pub(crate) mod foo ",
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_module_body() {
+        check_assist_not_applicable(
+            extract_module_to_file,
+            r#"
+mod foo {
+    fn <|>bar() {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_module_declaration() {
+        check_assist_not_applicable(extract_module_to_file, "mod <|>foo;");
+    }
+
+    #[test]
+    fn not_applicable_in_function() {
+        check_assist_not_applicable(
+            extract_module_to_file,
+            r#"
+fn main() {
+    mod <|>foo {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn new_file_in_crate_root_directory() {
+        check_new_file(
+            r#"
+//- /main.rs
+mod <|>foo {
+    use super::Bar;
+
+    fn bar() {
+        let x = 92;
+    }
+}
+struct Bar;
+"#,
+            "foo.rs",
+            "use super::Bar;\n\nfn bar() {\n    let x = 92;\n}\n",
+        );
+    }
+
+    #[test]
+    fn new_file_in_module_directory() {
+        check_new_file(
+            r#"
+//- /main.rs
+mod bar;
+//- /bar.rs
+mod baz {
+    mod <|>foo {
+        fn foo() {}
+    }
+}
+"#,
+            "bar/baz/foo.rs",
+            "fn foo() {}\n",
+        );
+    }
+
+    #[test]
+    fn new_file_next_to_mod_rs() {
+        check_new_file(
+            r#"
+//- /main.rs
+mod bar;
+//- /bar/mod.rs
+mod <|>foo {}
+"#,
+            "bar/foo.rs",
+            "",
+        );
+    }
+}
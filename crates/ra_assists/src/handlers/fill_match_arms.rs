@@ -1,11 +1,11 @@
 use std::iter;
 
-use hir::{Adt, HasSource, ModuleDef, Semantics};
+use hir::{Adt, Semantics};
 use itertools::Itertools;
 use ra_ide_db::RootDatabase;
 use ra_syntax::ast::{self, make, AstNode, MatchArm, NameOwner, Pat};
 
-use crate::{Assist, AssistCtx, AssistId};
+use crate::{utils::variant_pat, Assist, AssistCtx, AssistId, AssistKind};
 
 // Assist: fill_match_arms
 //
@@ -51,7 +51,7 @@ pub(crate) fn fill_match_arms(ctx: AssistCtx) -> Option<Assist> {
 
         variants
             .into_iter()
-            .filter_map(|variant| build_pat(ctx.db, module, variant))
+            .filter_map(|variant| variant_pat(ctx.db, module, variant))
             .filter(|variant_pat| is_variant_missing(&mut arms, variant_pat))
             .map(|pat| make::match_arm(iter::once(pat), make::expr_empty_block()))
             .collect()
@@ -78,7 +78,7 @@ pub(crate) fn fill_match_arms(ctx: AssistCtx) -> Option<Assist> {
             .multi_cartesian_product()
             .map(|variants| {
                 let patterns =
-                    variants.into_iter().filter_map(|variant| build_pat(ctx.db, module, variant));
+                    variants.into_iter().filter_map(|variant| variant_pat(ctx.db, module, variant));
                 ast::Pat::from(make::tuple_pat(patterns))
             })
             .filter(|variant_pat| is_variant_missing(&mut arms, variant_pat))
@@ -92,7 +92,7 @@ pub(crate) fn fill_match_arms(ctx: AssistCtx) -> Option<Assist> {
         return None;
     }
 
-    ctx.add_assist(AssistId("fill_match_arms"), "Fill match arms", |edit| {
+    ctx.add_assist(AssistId("fill_match_arms", AssistKind::QuickFix), "Fill match arms", |edit| {
         let new_arm_list = match_arm_list.remove_placeholder().append_arms(missing_arms);
 
         edit.target(match_expr.syntax().text_range());
@@ -146,26 +146,6 @@ fn resolve_tuple_of_enum_def(
         .collect()
 }
 
-fn build_pat(db: &RootDatabase, module: hir::Module, var: hir::EnumVariant) -> Option<ast::Pat> {
-    let path = crate::ast_transform::path_to_ast(module.find_use_path(db, ModuleDef::from(var))?);
-
-    // FIXME: use HIR for this; it doesn't currently expose struct vs. tuple vs. unit variants though
-    let pat: ast::Pat = match var.source(db).value.kind() {
-        ast::StructKind::Tuple(field_list) => {
-            let pats =
-                iter::repeat(make::placeholder_pat().into()).take(field_list.fields().count());
-            make::tuple_struct_pat(path, pats).into()
-        }
-        ast::StructKind::Record(field_list) => {
-            let pats = field_list.fields().map(|f| make::bind_pat(f.name().unwrap()).into());
-            make::record_pat(path, pats).into()
-        }
-        ast::StructKind::Unit => make::path_pat(path),
-    };
-
-    Some(pat)
-}
-
 #[cfg(test)]
 mod tests {
     use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
@@ -0,0 +1,126 @@
+use ra_syntax::{
+    ast::{self, edit::IndentLevel},
+    AstNode,
+    SyntaxKind::COMMENT,
+};
+use stdx::SepBy;
+
+use crate::{utils::organize_imports::flatten_use_tree, Assist, AssistCtx, AssistId, AssistKind};
+
+// Assist: flatten_imports
+//
+// Splits a nested import into one import per path.
+//
+// ```
+// use std::{fmt::{Debug, Display}, <|>io};
+// ```
+// ->
+// ```
+// use std::fmt::Debug;
+// use std::fmt::Display;
+// use std::io;
+// ```
+pub(crate) fn flatten_imports(ctx: AssistCtx) -> Option<Assist> {
+    let use_item = ctx.find_node_at_offset::<ast::UseItem>()?;
+    let tree = use_item.use_tree()?;
+    if !tree.syntax().descendants().any(|it| ast::UseTreeList::can_cast(it.kind())) {
+        return None;
+    }
+    // Comments can't be attributed to any of the new imports.
+    if use_item.syntax().descendants_with_tokens().any(|it| it.kind() == COMMENT) {
+        return None;
+    }
+    let imports = flatten_use_tree(&tree)?;
+    if imports.is_empty() {
+        return None;
+    }
+
+    // Attributes and visibility are repeated for each import.
+    let item_range = use_item.syntax().text_range();
+    let header = use_item.syntax().text().to_string()
+        [..usize::from(tree.syntax().text_range().start() - item_range.start())]
+        .to_string();
+    let indent = " ".repeat(IndentLevel::from_node(use_item.syntax()).0 as usize * 4);
+
+    ctx.add_assist(
+        AssistId("flatten_imports", AssistKind::RefactorRewrite),
+        "Split into flat imports",
+        |edit| {
+            edit.target(item_range);
+            let text = imports
+                .iter()
+                .map(|import| format!("{}{};", header, import))
+                .sep_by(&format!("\n{}", indent))
+                .to_string();
+            edit.replace(item_range, text);
+            edit.set_cursor(item_range.start());
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn flatten_nested_imports() {
+        check_assist(
+            flatten_imports,
+            r"
+use std::{fmt::{self, Debug}, <|>io::*};
+",
+            r"
+<|>use std::fmt;
+use std::fmt::Debug;
+use std::io::*;
+",
+        );
+    }
+
+    #[test]
+    fn flatten_keeps_visibility_attributes_and_aliases() {
+        check_assist(
+            flatten_imports,
+            r"
+mod foo {
+    #[cfg(test)]
+    pub(crate) use crate::{Foo as <|>Bar, Baz};
+}
+",
+            r"
+mod foo {
+    <|>#[cfg(test)]
+    pub(crate) use crate::Foo as Bar;
+    #[cfg(test)]
+    pub(crate) use crate::Baz;
+}
+",
+        );
+    }
+
+    #[test]
+    fn flatten_imports_target() {
+        check_assist_target(flatten_imports, "use std::{<|>fmt, io};", "use std::{fmt, io};");
+    }
+
+    #[test]
+    fn not_applicable_to_flat_import() {
+        check_assist_not_applicable(flatten_imports, "use std::<|>fmt::Debug;");
+    }
+
+    #[test]
+    fn not_applicable_with_comments() {
+        check_assist_not_applicable(
+            flatten_imports,
+            r"
+use std::{
+    // Formatting
+    <|>fmt,
+    io,
+};
+",
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use ra_syntax::ast::{AstNode, BinExpr, BinOp};
 
-use crate::{Assist, AssistCtx, AssistId};
+use crate::{Assist, AssistCtx, AssistId, AssistKind};
 
 // Assist: flip_binexpr
 //
@@ -33,14 +33,18 @@ pub(crate) fn flip_binexpr(ctx: AssistCtx) -> Option<Assist> {
         return None;
     }
 
-    ctx.add_assist(AssistId("flip_binexpr"), "Flip binary expression", |edit| {
-        edit.target(op_range);
-        if let FlipAction::FlipAndReplaceOp(new_op) = action {
-            edit.replace(op_range, new_op);
-        }
-        edit.replace(lhs.text_range(), rhs.text());
-        edit.replace(rhs.text_range(), lhs.text());
-    })
+    ctx.add_assist(
+        AssistId("flip_binexpr", AssistKind::RefactorRewrite),
+        "Flip binary expression",
+        |edit| {
+            edit.target(op_range);
+            if let FlipAction::FlipAndReplaceOp(new_op) = action {
+                edit.replace(op_range, new_op);
+            }
+            edit.replace(lhs.text_range(), rhs.text());
+            edit.replace(rhs.text_range(), lhs.text());
+        },
+    )
 }
 
 enum FlipAction {
@@ -117,6 +121,15 @@ mod tests {
         )
     }
 
+    #[test]
+    fn flip_binexpr_preserves_comments() {
+        check_assist(
+            flip_binexpr,
+            "fn f() { let res = a /* lhs */ +<|> /* rhs */ (b - 1); }",
+            "fn f() { let res = (b - 1) /* lhs */ +<|> /* rhs */ a; }",
+        )
+    }
+
     #[test]
     fn flip_binexpr_works_inside_match() {
         check_assist(
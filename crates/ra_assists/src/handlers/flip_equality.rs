@@ -0,0 +1,111 @@
+use ra_syntax::{
+    ast::{self, AstNode},
+    SyntaxToken,
+};
+
+use crate::{Assist, AssistCtx, AssistId, AssistKind};
+
+// Assist: flip_equality
+//
+// Turns `==` into `!=` and vice versa, negating the comparison so that its
+// value stays the same. A negation of the comparison is removed instead of
+// being doubled.
+//
+// ```
+// fn main() {
+//     let _ = x =<|>= 2;
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let _ = !(x != 2);
+// }
+// ```
+pub(crate) fn flip_equality(ctx: AssistCtx) -> Option<Assist> {
+    let expr = ctx.find_node_at_offset::<ast::BinExpr>()?;
+    let op_range = expr.op_token()?.text_range();
+    if !op_range.contains_range(ctx.frange.range) {
+        return None;
+    }
+    let (old_op, new_op) = match expr.op_kind()? {
+        ast::BinOp::EqualityTest => ("==", "!="),
+        ast::BinOp::NegatedEqualityTest => ("!=", "=="),
+        _ => return None,
+    };
+    let negation = negation_of(&expr);
+
+    ctx.add_assist(
+        AssistId("flip_equality", AssistKind::RefactorRewrite),
+        format!("Flip `{}` to `{}`", old_op, new_op),
+        |edit| {
+            edit.target(op_range);
+            edit.replace(op_range, new_op);
+            let range = expr.syntax().text_range();
+            match negation {
+                // Only the tokens are deleted, to keep the comments inside the
+                // parentheses.
+                Some(tokens) => tokens.iter().for_each(|it| edit.delete(it.text_range())),
+                None => {
+                    edit.insert(range.start(), "!(");
+                    edit.insert(range.end(), ")");
+                }
+            }
+        },
+    )
+}
+
+/// Returns the `!`, `(` and `)` tokens negating `expr`, if any.
+fn negation_of(expr: &ast::BinExpr) -> Option<[SyntaxToken; 3]> {
+    let paren = expr.syntax().parent().and_then(ast::ParenExpr::cast)?;
+    let prefix = paren.syntax().parent().and_then(ast::PrefixExpr::cast)?;
+    if prefix.op_kind()? != ast::PrefixOp::Not {
+        return None;
+    }
+    Some([prefix.op_token()?, paren.l_paren_token()?, paren.r_paren_token()?])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn flip_equality_negates() {
+        check_assist(
+            flip_equality,
+            "fn f() { let _ = a ==<|> b; }",
+            "fn f() { let _ = !(a !=<|> b); }",
+        )
+    }
+
+    #[test]
+    fn flip_equality_removes_negation() {
+        check_assist(flip_equality, "fn f() { if !(a !=<|> b) {} }", "fn f() { if a ==<|> b {} }")
+    }
+
+    #[test]
+    fn flip_equality_preserves_comments() {
+        check_assist(
+            flip_equality,
+            "fn f() { let _ = !(/* lhs */ a !=<|> b /* rhs */); }",
+            "fn f() { let _ = /* lhs */ a ==<|> b /* rhs */; }",
+        )
+    }
+
+    #[test]
+    fn flip_equality_target() {
+        check_assist_target(flip_equality, "fn f() { let _ = a !<|>= b; }", "!=")
+    }
+
+    #[test]
+    fn flip_equality_not_applicable_for_other_operators() {
+        check_assist_not_applicable(flip_equality, "fn f() { let _ = a <<|>= b; }")
+    }
+
+    #[test]
+    fn flip_equality_not_applicable_outside_of_operator() {
+        check_assist_not_applicable(flip_equality, "fn f() { let _ = a<|> == b; }")
+    }
+}
@@ -5,7 +5,7 @@ use ra_syntax::{
 };
 use test_utils::tested_by;
 
-use crate::{assist_ctx::ActionBuilder, Assist, AssistCtx, AssistId};
+use crate::{assist_ctx::ActionBuilder, Assist, AssistCtx, AssistId, AssistKind};
 
 // Assist: inline_local_variable
 //
@@ -107,7 +107,7 @@ pub(crate) fn inline_local_variable(ctx: AssistCtx) -> Option<Assist> {
     let init_in_paren = format!("({})", &init_str);
 
     ctx.add_assist(
-        AssistId("inline_local_variable"),
+        AssistId("inline_local_variable", AssistKind::RefactorInline),
         "Inline variable",
         move |edit: &mut ActionBuilder| {
             edit.delete(delete_range);
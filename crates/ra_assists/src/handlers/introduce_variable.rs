@@ -9,7 +9,7 @@ use ra_syntax::{
 use stdx::format_to;
 use test_utils::tested_by;
 
-use crate::{Assist, AssistCtx, AssistId};
+use crate::{Assist, AssistCtx, AssistId, AssistKind};
 
 // Assist: introduce_variable
 //
@@ -42,52 +42,56 @@ pub(crate) fn introduce_variable(ctx: AssistCtx) -> Option<Assist> {
     if indent.kind() != WHITESPACE {
         return None;
     }
-    ctx.add_assist(AssistId("introduce_variable"), "Extract into variable", move |edit| {
-        let mut buf = String::new();
-
-        let cursor_offset = if wrap_in_block {
-            buf.push_str("{ let var_name = ");
-            TextSize::of("{ let ")
-        } else {
-            buf.push_str("let var_name = ");
-            TextSize::of("let ")
-        };
-        format_to!(buf, "{}", expr.syntax());
-        let full_stmt = ast::ExprStmt::cast(anchor_stmt.clone());
-        let is_full_stmt = if let Some(expr_stmt) = &full_stmt {
-            Some(expr.syntax().clone()) == expr_stmt.expr().map(|e| e.syntax().clone())
-        } else {
-            false
-        };
-        if is_full_stmt {
-            tested_by!(test_introduce_var_expr_stmt);
-            if full_stmt.unwrap().semicolon_token().is_none() {
-                buf.push_str(";");
-            }
-            edit.replace(expr.syntax().text_range(), buf);
-        } else {
-            buf.push_str(";");
-
-            // We want to maintain the indent level,
-            // but we do not want to duplicate possible
-            // extra newlines in the indent block
-            let text = indent.text();
-            if text.starts_with('\n') {
-                buf.push_str("\n");
-                buf.push_str(text.trim_start_matches('\n'));
+    ctx.add_assist(
+        AssistId("introduce_variable", AssistKind::RefactorExtract),
+        "Extract into variable",
+        move |edit| {
+            let mut buf = String::new();
+
+            let cursor_offset = if wrap_in_block {
+                buf.push_str("{ let var_name = ");
+                TextSize::of("{ let ")
             } else {
-                buf.push_str(text);
-            }
+                buf.push_str("let var_name = ");
+                TextSize::of("let ")
+            };
+            format_to!(buf, "{}", expr.syntax());
+            let full_stmt = ast::ExprStmt::cast(anchor_stmt.clone());
+            let is_full_stmt = if let Some(expr_stmt) = &full_stmt {
+                Some(expr.syntax().clone()) == expr_stmt.expr().map(|e| e.syntax().clone())
+            } else {
+                false
+            };
+            if is_full_stmt {
+                tested_by!(test_introduce_var_expr_stmt);
+                if full_stmt.unwrap().semicolon_token().is_none() {
+                    buf.push_str(";");
+                }
+                edit.replace(expr.syntax().text_range(), buf);
+            } else {
+                buf.push_str(";");
 
-            edit.target(expr.syntax().text_range());
-            edit.replace(expr.syntax().text_range(), "var_name".to_string());
-            edit.insert(anchor_stmt.text_range().start(), buf);
-            if wrap_in_block {
-                edit.insert(anchor_stmt.text_range().end(), " }");
+                // We want to maintain the indent level,
+                // but we do not want to duplicate possible
+                // extra newlines in the indent block
+                let text = indent.text();
+                if text.starts_with('\n') {
+                    buf.push_str("\n");
+                    buf.push_str(text.trim_start_matches('\n'));
+                } else {
+                    buf.push_str(text);
+                }
+
+                edit.target(expr.syntax().text_range());
+                edit.replace(expr.syntax().text_range(), "var_name".to_string());
+                edit.insert(anchor_stmt.text_range().start(), buf);
+                if wrap_in_block {
+                    edit.insert(anchor_stmt.text_range().end(), " }");
+                }
             }
-        }
-        edit.set_cursor(anchor_stmt.text_range().start() + cursor_offset);
-    })
+            edit.set_cursor(anchor_stmt.text_range().start() + cursor_offset);
+        },
+    )
 }
 
 /// Check whether the node is a valid expression which can be extracted to a variable.
@@ -3,14 +3,14 @@ use ra_syntax::{
     T,
 };
 
-use crate::{utils::invert_boolean_expression, Assist, AssistCtx, AssistId};
+use crate::{utils::invert_boolean_expression, Assist, AssistCtx, AssistId, AssistKind};
 
 // Assist: invert_if
 //
 // Apply invert_if
 // This transforms if expressions of the form `if !x {A} else {B}` into `if x {B} else {A}`
-// This also works with `!=`. This assist can only be applied with the cursor
-// on `if`.
+// This also works with `!=`, and `&&` and `||` conditions are negated with De
+// Morgan's laws. This assist can only be applied with the cursor on `if`.
 //
 // ```
 // fn main() {
@@ -43,16 +43,24 @@ pub(crate) fn invert_if(ctx: AssistCtx) -> Option<Assist> {
 
     if let ast::ElseBranch::Block(else_block) = expr.else_branch()? {
         let cond_range = cond.syntax().text_range();
-        let flip_cond = invert_boolean_expression(cond);
+        let flip_cond = match invert_boolean_expression(cond) {
+            // `if !(a && b)` becomes `if a && b`, not `if (a && b)`
+            ast::Expr::ParenExpr(it) => it.expr()?,
+            it => it,
+        };
         let else_node = else_block.syntax();
         let else_range = else_node.text_range();
         let then_range = then_node.text_range();
-        return ctx.add_assist(AssistId("invert_if"), "Invert if", |edit| {
-            edit.target(if_range);
-            edit.replace(cond_range, flip_cond.syntax().text());
-            edit.replace(else_range, then_node.text());
-            edit.replace(then_range, else_node.text());
-        });
+        return ctx.add_assist(
+            AssistId("invert_if", AssistKind::RefactorRewrite),
+            "Invert if",
+            |edit| {
+                edit.target(if_range);
+                edit.replace(cond_range, flip_cond.syntax().text());
+                edit.replace(else_range, then_node.text());
+                edit.replace(then_range, else_node.text());
+            },
+        );
     }
 
     None
@@ -91,6 +99,67 @@ mod tests {
         )
     }
 
+    #[test]
+    fn invert_if_applies_de_morgan() {
+        check_assist(
+            invert_if,
+            "fn f() { i<|>f a && (b || !c) { 1 } else { 2 } }",
+            "fn f() { i<|>f !a || (!b && c) { 2 } else { 1 } }",
+        );
+        check_assist(
+            invert_if,
+            "fn f() { i<|>f a || b && c != 1 { 1 } else { 2 } }",
+            "fn f() { i<|>f !a && (!b || c == 1) { 2 } else { 1 } }",
+        )
+    }
+
+    #[test]
+    fn invert_if_removes_parens_of_negation() {
+        check_assist(
+            invert_if,
+            "fn f() { i<|>f !(a && b) { 1 } else { 2 } }",
+            "fn f() { i<|>f a && b { 2 } else { 1 } }",
+        )
+    }
+
+    #[test]
+    fn invert_if_wraps_comparisons() {
+        check_assist(
+            invert_if,
+            "fn f() { i<|>f x < 3 { 1 } else { 2 } }",
+            "fn f() { i<|>f !(x < 3) { 2 } else { 1 } }",
+        )
+    }
+
+    #[test]
+    fn invert_if_preserves_comments() {
+        check_assist(
+            invert_if,
+            r#"
+fn f() {
+    i<|>f a /* first */ && /* second */ !b {
+        // then
+        1
+    } else {
+        // else
+        2
+    }
+}
+"#,
+            r#"
+fn f() {
+    i<|>f !a /* first */ || /* second */ b {
+        // else
+        2
+    } else {
+        // then
+        1
+    }
+}
+"#,
+        )
+    }
+
     #[test]
     fn invert_if_doesnt_apply_with_cursor_not_on_if() {
         check_assist_not_applicable(invert_if, "fn f() { if !<|>cond { 3 * 2 } else { 1 } }")
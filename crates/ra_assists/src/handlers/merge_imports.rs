@@ -6,11 +6,12 @@ use ra_syntax::{
     AstNode, Direction, InsertPosition, SyntaxElement, T,
 };
 
-use crate::{Assist, AssistCtx, AssistId};
+use crate::{utils::MergeBehaviour, Assist, AssistCtx, AssistId, AssistKind};
 
 // Assist: merge_imports
 //
-// Merges two imports with a common prefix.
+// Merges two imports with a common prefix. Only imports from the same module
+// are merged if the `Last` merge behaviour is configured.
 //
 // ```
 // use std::<|>fmt::Formatter;
@@ -22,6 +23,7 @@ use crate::{Assist, AssistCtx, AssistId};
 // ```
 pub(crate) fn merge_imports(ctx: AssistCtx) -> Option<Assist> {
     let tree: ast::UseTree = ctx.find_node_at_offset()?;
+    let behaviour = ctx.config.insert_use.merge.unwrap_or(MergeBehaviour::Full);
     let mut rewriter = SyntaxRewriter::default();
     let mut offset = ctx.frange.range.start();
 
@@ -30,7 +32,7 @@ pub(crate) fn merge_imports(ctx: AssistCtx) -> Option<Assist> {
             .filter_map(|dir| neighbor(&use_item, dir))
             .filter_map(|it| Some((it.clone(), it.use_tree()?)))
             .find_map(|(use_item, use_tree)| {
-                Some((try_merge_trees(&tree, &use_tree)?, use_item))
+                Some((try_merge_trees(&tree, &use_tree, behaviour)?, use_item))
             })?;
 
         rewriter.replace_ast(&tree, &merged);
@@ -40,9 +42,10 @@ pub(crate) fn merge_imports(ctx: AssistCtx) -> Option<Assist> {
             offset -= to_delete.syntax().text_range().len();
         }
     } else {
-        let (merged, to_delete) = next_prev()
-            .filter_map(|dir| neighbor(&tree, dir))
-            .find_map(|use_tree| Some((try_merge_trees(&tree, &use_tree)?, use_tree.clone())))?;
+        let (merged, to_delete) =
+            next_prev().filter_map(|dir| neighbor(&tree, dir)).find_map(|use_tree| {
+                Some((try_merge_trees(&tree, &use_tree, behaviour)?, use_tree.clone()))
+            })?;
 
         rewriter.replace_ast(&tree, &merged);
         rewriter += to_delete.remove();
@@ -52,22 +55,35 @@ pub(crate) fn merge_imports(ctx: AssistCtx) -> Option<Assist> {
         }
     };
 
-    ctx.add_assist(AssistId("merge_imports"), "Merge imports", |edit| {
-        edit.rewrite(rewriter);
-        // FIXME: we only need because our diff is imprecise
-        edit.set_cursor(offset);
-    })
+    ctx.add_assist(
+        AssistId("merge_imports", AssistKind::RefactorRewrite),
+        "Merge imports",
+        |edit| {
+            edit.rewrite(rewriter);
+            // FIXME: we only need because our diff is imprecise
+            edit.set_cursor(offset);
+        },
+    )
 }
 
 fn next_prev() -> impl Iterator<Item = Direction> {
     [Direction::Next, Direction::Prev].iter().copied()
 }
 
-fn try_merge_trees(old: &ast::UseTree, new: &ast::UseTree) -> Option<ast::UseTree> {
+fn try_merge_trees(
+    old: &ast::UseTree,
+    new: &ast::UseTree,
+    behaviour: MergeBehaviour,
+) -> Option<ast::UseTree> {
     let lhs_path = old.path()?;
     let rhs_path = new.path()?;
 
     let (lhs_prefix, rhs_prefix) = common_prefix(&lhs_path, &rhs_path)?;
+    if behaviour == MergeBehaviour::Last
+        && !(imports_from(old, &lhs_path, &lhs_prefix) && imports_from(new, &rhs_path, &rhs_prefix))
+    {
+        return None;
+    }
 
     let lhs = old.split_prefix(&lhs_prefix);
     let rhs = new.split_prefix(&rhs_prefix);
@@ -96,6 +112,15 @@ fn try_merge_trees(old: &ast::UseTree, new: &ast::UseTree) -> Option<ast::UseTre
     Some(lhs.with_use_tree_list(use_tree_list))
 }
 
+/// Whether `tree` imports items of the `prefix` module itself, rather than of
+/// its submodules.
+fn imports_from(tree: &ast::UseTree, path: &ast::Path, prefix: &ast::Path) -> bool {
+    match tree.use_tree_list() {
+        Some(_) => path == prefix,
+        None => path.qualifier().as_ref() == Some(prefix),
+    }
+}
+
 fn common_prefix(lhs: &ast::Path, rhs: &ast::Path) -> Option<(ast::Path, ast::Path)> {
     let mut res = None;
     let mut lhs_curr = first_path(&lhs);
@@ -125,10 +150,22 @@ fn first_path(path: &ast::Path) -> ast::Path {
 
 #[cfg(test)]
 mod tests {
-    use crate::helpers::check_assist;
+    use crate::{
+        helpers::{
+            check_assist, check_assist_not_applicable_with_config, check_assist_with_config,
+        },
+        AssistConfig, InsertUseConfig,
+    };
 
     use super::*;
 
+    fn last_level_config() -> AssistConfig {
+        AssistConfig {
+            insert_use: InsertUseConfig { merge: Some(MergeBehaviour::Last), group: true },
+            ..AssistConfig::default()
+        }
+    }
+
     #[test]
     fn test_merge_first() {
         check_assist(
@@ -157,6 +194,33 @@ use std::fmt:<|>:{Display, Debug};
         );
     }
 
+    #[test]
+    fn test_merge_last_level() {
+        check_assist_with_config(
+            merge_imports,
+            last_level_config(),
+            r"
+use std::fmt<|>::Debug;
+use std::fmt::{Display, Write};
+",
+            r"
+use std::fmt<|>::{Debug, Display, Write};
+",
+        );
+    }
+
+    #[test]
+    fn test_merge_last_level_skips_submodules() {
+        check_assist_not_applicable_with_config(
+            merge_imports,
+            last_level_config(),
+            r"
+use std::fmt<|>::Debug;
+use std::io;
+",
+        );
+    }
+
     #[test]
     fn test_merge_nested() {
         check_assist(
@@ -4,7 +4,7 @@ use ra_syntax::{
     TextSize,
 };
 
-use crate::{Assist, AssistCtx, AssistId};
+use crate::{Assist, AssistCtx, AssistId, AssistKind};
 
 // Assist: move_guard_to_arm_body
 //
@@ -40,27 +40,31 @@ pub(crate) fn move_guard_to_arm_body(ctx: AssistCtx) -> Option<Assist> {
     let arm_expr = match_arm.expr()?;
     let buf = format!("if {} {{ {} }}", guard_conditions.syntax().text(), arm_expr.syntax().text());
 
-    ctx.add_assist(AssistId("move_guard_to_arm_body"), "Move guard to arm body", |edit| {
-        edit.target(guard.syntax().text_range());
-        let offseting_amount = match space_before_guard.and_then(|it| it.into_token()) {
-            Some(tok) => {
-                if ast::Whitespace::cast(tok.clone()).is_some() {
-                    let ele = tok.text_range();
-                    edit.delete(ele);
-                    ele.len()
-                } else {
-                    TextSize::from(0)
+    ctx.add_assist(
+        AssistId("move_guard_to_arm_body", AssistKind::RefactorRewrite),
+        "Move guard to arm body",
+        |edit| {
+            edit.target(guard.syntax().text_range());
+            let offseting_amount = match space_before_guard.and_then(|it| it.into_token()) {
+                Some(tok) => {
+                    if ast::Whitespace::cast(tok.clone()).is_some() {
+                        let ele = tok.text_range();
+                        edit.delete(ele);
+                        ele.len()
+                    } else {
+                        TextSize::from(0)
+                    }
                 }
-            }
-            _ => TextSize::from(0),
-        };
+                _ => TextSize::from(0),
+            };
 
-        edit.delete(guard.syntax().text_range());
-        edit.replace_node_and_indent(arm_expr.syntax(), buf);
-        edit.set_cursor(
-            arm_expr.syntax().text_range().start() + TextSize::from(3) - offseting_amount,
-        );
-    })
+            edit.delete(guard.syntax().text_range());
+            edit.replace_node_and_indent(arm_expr.syntax(), buf);
+            edit.set_cursor(
+                arm_expr.syntax().text_range().start() + TextSize::from(3) - offseting_amount,
+            );
+        },
+    )
 }
 
 // Assist: move_arm_cond_to_match_guard
@@ -109,7 +113,7 @@ pub(crate) fn move_arm_cond_to_match_guard(ctx: AssistCtx) -> Option<Assist> {
     let buf = format!(" if {}", cond.syntax().text());
 
     ctx.add_assist(
-        AssistId("move_arm_cond_to_match_guard"),
+        AssistId("move_arm_cond_to_match_guard", AssistKind::RefactorRewrite),
         "Move condition to match guard",
         |edit| {
             edit.target(if_expr.syntax().text_range());
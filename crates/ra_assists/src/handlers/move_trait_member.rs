@@ -0,0 +1,460 @@
+use hir::{AssocItem, HasSource, ImplDef};
+use ra_syntax::{
+    algo::find_node_at_offset,
+    ast::{self, edit, edit::IndentLevel, AstNode, NameOwner},
+    SyntaxKind::{ATTR, COMMENT, WHITESPACE},
+    TextRange, TextSize,
+};
+
+use crate::{
+    ast_transform::{self, AstTransform, QualifyPaths, SubstituteTypeParams},
+    utils::resolve_target_trait,
+    Assist, AssistCtx, AssistId, AssistKind,
+};
+
+// Assist: pull_up_to_trait
+//
+// Moves a method of a trait impl into the trait, as its default
+// implementation.
+//
+// ```
+// trait Greet {
+//     fn greet(&self) -> String;
+// }
+//
+// struct Person;
+//
+// impl Greet for Person {
+//     fn greet<|>(&self) -> String {
+//         "Hello".to_string()
+//     }
+// }
+// ```
+// ->
+// ```
+// trait Greet {
+//     fn greet(&self) -> String {
+//         "Hello".to_string()
+//     }
+// }
+//
+// struct Person;
+//
+// impl Greet for Person {}
+// ```
+pub(crate) fn pull_up_to_trait(ctx: AssistCtx) -> Option<Assist> {
+    let fn_def = method_at_cursor(&ctx)?;
+    let impl_def = fn_def.syntax().parent()?.parent().and_then(ast::ImplDef::cast)?;
+    let db = ctx.db;
+    let trait_ = resolve_target_trait(&ctx.sema, &impl_def)?;
+    // Only traits of the current crate can be edited.
+    if trait_.module(db).krate() != ctx.sema.to_def(&impl_def)?.krate(db) {
+        return None;
+    }
+    let trait_source = trait_.source(db);
+    if trait_source.file_id.is_macro_file() {
+        return None;
+    }
+    let trait_file = trait_source.file_id.original_file(db);
+    let trait_def: ast::TraitDef = find_node_at_offset(
+        ctx.sema.parse(trait_file).syntax(),
+        trait_source.value.syntax().text_range().start(),
+    )?;
+
+    // Only required methods can be pulled up.
+    let name = fn_def.name()?;
+    let trait_fn = trait_def.item_list()?.assoc_items().find_map(|item| match item {
+        ast::AssocItem::FnDef(it) if it.name().map_or(false, |it| it.text() == name.text()) => {
+            Some(it)
+        }
+        _ => None,
+    })?;
+    if trait_fn.body().is_some() {
+        return None;
+    }
+
+    let source_scope = ctx.sema.scope(fn_def.syntax());
+    let target_scope = ctx.sema.scope_for_def(trait_);
+    let transform = QualifyPaths::new(&target_scope, &source_scope);
+    let new_fn = edit::remove_attrs_and_docs(&ast_transform::apply(&transform, fn_def.clone()));
+    let new_fn = reindent(
+        new_fn,
+        IndentLevel::from_node(fn_def.syntax()),
+        IndentLevel::from_node(trait_fn.syntax()),
+    )
+    .to_string();
+    // The docs of the declaration are kept.
+    let trait_fn_range =
+        TextRange::new(signature_start(&trait_fn)?, trait_fn.syntax().text_range().end());
+    let file_id = ctx.frange.file_id;
+
+    let label = format!("Pull `{}` up to `{}`", name, trait_.name(db));
+    ctx.add_assist(AssistId("pull_up_to_trait", AssistKind::RefactorRewrite), label, |edit| {
+        edit.target(fn_def.syntax().text_range());
+
+        let removed = match impl_def.item_list() {
+            Some(item_list) if item_list.assoc_items().count() == 1 => {
+                let range = item_list.syntax().text_range();
+                edit.replace(range, "{}");
+                range
+            }
+            _ => {
+                let start = match fn_def.syntax().prev_sibling_or_token() {
+                    Some(ws) if ws.kind() == WHITESPACE => ws.text_range().start(),
+                    _ => fn_def.syntax().text_range().start(),
+                };
+                let range = TextRange::new(start, fn_def.syntax().text_range().end());
+                edit.delete(range);
+                range
+            }
+        };
+        // The cursor is put where the method was, which moves if the trait
+        // comes before it in the file.
+        let mut cursor = removed.start();
+        if trait_file == file_id && trait_fn_range.end() <= cursor {
+            cursor = cursor + TextSize::of(&new_fn) - trait_fn_range.len();
+        }
+        edit.set_cursor(cursor);
+        edit.file_edit_builder(trait_file).replace(trait_fn_range, new_fn);
+    })
+}
+
+// Assist: push_down_to_impl
+//
+// Copies a default method of a trait into an impl of the trait, so that it can
+// be overridden.
+//
+// ```
+// trait Greet {
+//     fn greet<|>(&self) -> String {
+//         "Hello".to_string()
+//     }
+// }
+//
+// struct Person;
+//
+// impl Greet for Person {}
+// ```
+// ->
+// ```
+// trait Greet {
+//     fn greet(&self) -> String {
+//         "Hello".to_string()
+//     }
+// }
+//
+// struct Person;
+//
+// impl Greet for Person {
+//     fn greet(&self) -> String {
+//         "Hello".to_string()
+//     }
+// }
+// ```
+pub(crate) fn push_down_to_impl(ctx: AssistCtx) -> Option<Assist> {
+    let fn_def = method_at_cursor(&ctx)?;
+    if fn_def.body().is_none() {
+        return None;
+    }
+    let trait_def = fn_def.syntax().parent()?.parent().and_then(ast::TraitDef::cast)?;
+    let name = fn_def.name()?;
+    let db = ctx.db;
+    let sema = ctx.sema;
+    let trait_ = sema.to_def(&trait_def)?;
+    let source_scope = sema.scope_for_def(trait_);
+
+    // The impls which don't override the method yet, and the method as it
+    // should be written in each of them.
+    let mut targets = Vec::new();
+    for impl_ in ImplDef::for_trait(db, trait_.module(db).krate(), trait_) {
+        let overrides = impl_.items(db).into_iter().any(|item| match item {
+            AssocItem::Function(it) => it.name(db).to_string() == name.text().as_str(),
+            _ => false,
+        });
+        let source = impl_.source(db);
+        if overrides || source.file_id.is_macro_file() {
+            continue;
+        }
+        let file_id = source.file_id.original_file(db);
+        let impl_def: ast::ImplDef = match find_node_at_offset(
+            sema.parse(file_id).syntax(),
+            source.value.syntax().text_range().start(),
+        ) {
+            Some(it) => it,
+            None => continue,
+        };
+        let (item_list, target_type) = match (impl_def.item_list(), impl_def.target_type()) {
+            (Some(item_list), Some(target_type)) => (item_list, target_type),
+            _ => continue,
+        };
+
+        let target_scope = sema.scope(impl_def.syntax());
+        let transform = QualifyPaths::new(&target_scope, &source_scope)
+            .or(SubstituteTypeParams::for_trait_impl(&source_scope, trait_, impl_def.clone()));
+        let new_fn =
+            edit::remove_attrs_and_docs(&ast_transform::apply(&*transform, fn_def.clone()));
+        let indent = match item_list.assoc_items().last() {
+            Some(it) => IndentLevel::from_node(it.syntax()),
+            None => IndentLevel(IndentLevel::from_node(impl_def.syntax()).0 + 1),
+        };
+        let new_fn = reindent(new_fn, IndentLevel::from_node(fn_def.syntax()), indent);
+        let new_item_list = item_list.append_items(Some(ast::AssocItem::FnDef(new_fn)));
+        targets.push((file_id, item_list, new_item_list, target_type));
+    }
+    if targets.is_empty() {
+        return None;
+    }
+
+    let trait_name = trait_.name(db);
+    let mut group = ctx.add_assist_group(format!("Push `{}` down to an impl", name));
+    for (file_id, item_list, new_item_list, target_type) in targets {
+        let label = format!("Push `{}` down to `impl {} for {}`", name, trait_name, target_type);
+        group.add_assist(
+            AssistId("push_down_to_impl", AssistKind::RefactorRewrite),
+            label,
+            |edit| {
+                edit.target(fn_def.syntax().text_range());
+                edit.file_edit_builder(file_id)
+                    .replace(item_list.syntax().text_range(), new_item_list.to_string());
+            },
+        );
+    }
+    group.finish()
+}
+
+/// Returns the method whose signature is under the cursor.
+fn method_at_cursor(ctx: &AssistCtx) -> Option<ast::FnDef> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let in_body =
+        fn_def.body().map_or(false, |it| it.syntax().text_range().contains_range(ctx.frange.range));
+    if in_body {
+        return None;
+    }
+    Some(fn_def)
+}
+
+fn reindent(fn_def: ast::FnDef, from: IndentLevel, to: IndentLevel) -> ast::FnDef {
+    if to.0 > from.0 {
+        IndentLevel(to.0 - from.0).increase_indent(fn_def)
+    } else {
+        IndentLevel(from.0 - to.0).decrease_indent(fn_def)
+    }
+}
+
+/// Returns where `fn_def` starts, after its docs and attributes.
+fn signature_start(fn_def: &ast::FnDef) -> Option<TextSize> {
+    fn_def
+        .syntax()
+        .children_with_tokens()
+        .find(|it| !matches!(it.kind(), ATTR | COMMENT | WHITESPACE))
+        .map(|it| it.text_range().start())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn pull_up_method() {
+        check_assist(
+            pull_up_to_trait,
+            r#"
+mod foo {
+    pub struct Bar;
+    pub trait Foo {
+        /// Does things.
+        fn foo(&self) -> Bar;
+        fn baz(&self);
+    }
+}
+struct S;
+impl foo::Foo for S {
+    fn foo<|>(&self) -> foo::Bar {
+        foo::Bar
+    }
+    fn baz(&self) {}
+}"#,
+            r#"
+mod foo {
+    pub struct Bar;
+    pub trait Foo {
+        /// Does things.
+        fn foo(&self) -> Bar {
+            Bar
+        }
+        fn baz(&self);
+    }
+}
+struct S;
+impl foo::Foo for S {<|>
+    fn baz(&self) {}
+}"#,
+        );
+    }
+
+    #[test]
+    fn pull_up_only_method() {
+        check_assist(
+            pull_up_to_trait,
+            r#"
+trait Foo {
+    fn foo(&self);
+}
+struct S;
+impl Foo for S {
+    fn <|>foo(&self) {
+        println!("foo");
+    }
+}"#,
+            r#"
+trait Foo {
+    fn foo(&self) {
+        println!("foo");
+    }
+}
+struct S;
+impl Foo for S <|>{}"#,
+        );
+    }
+
+    #[test]
+    fn pull_up_to_trait_target() {
+        check_assist_target(
+            pull_up_to_trait,
+            "trait Foo { fn foo(&self); } impl Foo for () { fn foo<|>(&self) {} }",
+            "fn foo(&self) {}",
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_default_methods() {
+        check_assist_not_applicable(
+            pull_up_to_trait,
+            r#"
+trait Foo {
+    fn foo(&self) {}
+}
+impl Foo for () {
+    fn foo<|>(&self) {}
+}"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_inherent_impls() {
+        check_assist_not_applicable(
+            pull_up_to_trait,
+            r#"
+struct S;
+impl S {
+    fn foo<|>(&self) {}
+}"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_body() {
+        check_assist_not_applicable(
+            pull_up_to_trait,
+            r#"
+trait Foo {
+    fn foo(&self);
+}
+impl Foo for () {
+    fn foo(&self) { <|> }
+}"#,
+        );
+    }
+
+    #[test]
+    fn push_down_method() {
+        check_assist(
+            push_down_to_impl,
+            r#"
+mod foo {
+    pub struct Bar;
+    pub trait Foo {
+        /// Does things.
+        fn foo<|>(&self) -> Bar {
+            Bar
+        }
+    }
+}
+struct S;
+impl foo::Foo for S {
+    fn baz(&self) {}
+}"#,
+            r#"
+mod foo {
+    pub struct Bar;
+    pub trait Foo {
+        /// Does things.
+        fn foo<|>(&self) -> Bar {
+            Bar
+        }
+    }
+}
+struct S;
+impl foo::Foo for S {
+    fn baz(&self) {}
+    fn foo(&self) -> foo::Bar {
+        foo::Bar
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn push_down_with_type_params() {
+        check_assist(
+            push_down_to_impl,
+            r#"
+trait Convert<T> {
+    fn convert<|>(&self) -> Option<T> {
+        None
+    }
+}
+struct S;
+impl Convert<u32> for S {}"#,
+            r#"
+trait Convert<T> {
+    fn convert<|>(&self) -> Option<T> {
+        None
+    }
+}
+struct S;
+impl Convert<u32> for S {
+    fn convert(&self) -> Option<u32> {
+        None
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_overridden() {
+        check_assist_not_applicable(
+            push_down_to_impl,
+            r#"
+trait Foo {
+    fn foo<|>(&self) {}
+}
+impl Foo for () {
+    fn foo(&self) {}
+}"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_required_methods() {
+        check_assist_not_applicable(
+            push_down_to_impl,
+            r#"
+trait Foo {
+    fn foo<|>(&self);
+}
+impl Foo for () {}"#,
+        );
+    }
+}
@@ -0,0 +1,324 @@
+use std::iter;
+
+use hir::{AssocItem, HasSource, MacroDef, ModPath, ModuleDef};
+use ra_ide_db::RootDatabase;
+use ra_syntax::{ast, AstNode, SyntaxNode, TextRange};
+use stdx::SepBy;
+
+use crate::{
+    assist_ctx::{Assist, AssistCtx},
+    handlers::auto_import::{AutoImportAssets, ImportCandidate},
+    AssistId, AssistKind,
+};
+use either::Either;
+
+// Assist: qualify_path
+//
+// If the name is unresolved, provides all possible qualified paths for it.
+//
+// ```
+// fn main() {
+//     let map = HashMap<|>::new();
+// }
+// # pub mod std { pub mod collections { pub struct HashMap { } } }
+// ```
+// ->
+// ```
+// fn main() {
+//     let map = std::collections::HashMap::new();
+// }
+// # pub mod std { pub mod collections { pub struct HashMap { } } }
+// ```
+pub(crate) fn qualify_path(ctx: AssistCtx) -> Option<Assist> {
+    let auto_import_assets = AutoImportAssets::new(&ctx)?;
+    let syntax = &auto_import_assets.syntax_under_caret;
+    // Paths inside of macro calls can't be rewritten in place.
+    if !ast::SourceFile::can_cast(syntax.ancestors().last()?.kind()) {
+        return None;
+    }
+
+    let db = ctx.db;
+    let candidate = &auto_import_assets.import_candidate;
+    let qualified_paths = auto_import_assets
+        .search_for_items(db)
+        .into_iter()
+        .filter_map(|(import, def)| {
+            let (range, text) = qualify(db, candidate, syntax, &import, def)?;
+            Some((import, range, text))
+        })
+        .collect::<Vec<_>>();
+    if qualified_paths.is_empty() {
+        return None;
+    }
+
+    let target = syntax.text_range();
+    let mut group = ctx.add_assist_group(auto_import_assets.get_qualify_group_message());
+    for (import, range, text) in qualified_paths {
+        group.add_assist(
+            AssistId("qualify_path", AssistKind::QuickFix),
+            format!("Qualify as `{}`", import),
+            |edit| {
+                edit.target(target);
+                edit.replace(range, text);
+                edit.set_cursor(range.start());
+            },
+        );
+    }
+    group.finish()
+}
+
+/// Returns the range to replace to qualify `syntax` with `import`, and the
+/// replacement.
+fn qualify(
+    db: &RootDatabase,
+    candidate: &ImportCandidate,
+    syntax: &SyntaxNode,
+    import: &ModPath,
+    def: Either<ModuleDef, MacroDef>,
+) -> Option<(TextRange, String)> {
+    match candidate {
+        ImportCandidate::UnqualifiedName(_) => {
+            let name_ref = ast::Path::cast(syntax.clone())?.segment()?.name_ref()?;
+            Some((name_ref.syntax().text_range(), import.to_string()))
+        }
+        ImportCandidate::QualifierStart(_) => {
+            let path = ast::Path::cast(syntax.clone())?;
+            let first = iter::successors(Some(path), |it| it.qualifier()).last()?;
+            let name_ref = first.segment()?.name_ref()?;
+            Some((name_ref.syntax().text_range(), import.to_string()))
+        }
+        // `Struct::assoc_fn` becomes `<Struct as Trait>::assoc_fn`.
+        ImportCandidate::TraitAssocItem(..) => {
+            let qualifier = ast::Path::cast(syntax.clone())?.qualifier()?;
+            Some((qualifier.syntax().text_range(), format!("<{} as {}>", qualifier, import)))
+        }
+        // `value.method(arg)` becomes `Trait::method(&value, arg)`.
+        ImportCandidate::TraitMethod(_, method_name) => {
+            let method_call = ast::MethodCallExpr::cast(syntax.clone())?;
+            let trait_ = match def {
+                Either::Left(ModuleDef::Trait(it)) => it,
+                _ => return None,
+            };
+            let function = trait_.items(db).into_iter().find_map(|item| match item {
+                AssocItem::Function(it) if it.name(db).to_string() == *method_name => Some(it),
+                _ => None,
+            })?;
+            let self_param = function.source(db).value.param_list()?.self_param()?;
+
+            let receiver = method_call.expr()?;
+            let receiver = match receiver {
+                ast::Expr::BinExpr(_) | ast::Expr::CastExpr(_) | ast::Expr::RangeExpr(_) => {
+                    format!("({})", receiver)
+                }
+                _ => receiver.to_string(),
+            };
+            let receiver = match self_param.kind() {
+                ast::SelfParamKind::Owned => receiver,
+                ast::SelfParamKind::Ref => format!("&{}", receiver),
+                ast::SelfParamKind::MutRef => format!("&mut {}", receiver),
+            };
+            let args = iter::once(receiver)
+                .chain(method_call.arg_list()?.args().map(|it| it.to_string()))
+                .sep_by(", ");
+            let type_args =
+                method_call.type_arg_list().map_or(String::new(), |it| format!("::{}", it));
+            Some((
+                method_call.syntax().text_range(),
+                format!("{}::{}{}({})", import, method_name, type_args, args),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn qualify_unqualified_name() {
+        check_assist(
+            qualify_path,
+            r"
+            <|>PubStruct
+
+            pub mod PubMod {
+                pub struct PubStruct;
+            }
+            ",
+            r"
+            <|>PubMod::PubStruct
+
+            pub mod PubMod {
+                pub struct PubStruct;
+            }
+            ",
+        );
+    }
+
+    #[test]
+    fn qualify_qualifier_start() {
+        check_assist(
+            qualify_path,
+            r"
+            fn main() {
+                let _ = <|>collections::HashMap::new();
+            }
+
+            pub mod std { pub mod collections { pub struct HashMap; } }
+            ",
+            r"
+            fn main() {
+                let _ = <|>std::collections::HashMap::new();
+            }
+
+            pub mod std { pub mod collections { pub struct HashMap; } }
+            ",
+        );
+    }
+
+    #[test]
+    fn qualify_with_generic_args() {
+        check_assist(
+            qualify_path,
+            r"
+            fn main() {
+                let _: <|>Wrapper<u32>;
+            }
+
+            pub mod wrap { pub struct Wrapper<T>(T); }
+            ",
+            r"
+            fn main() {
+                let _: <|>wrap::Wrapper<u32>;
+            }
+
+            pub mod wrap { pub struct Wrapper<T>(T); }
+            ",
+        );
+    }
+
+    #[test]
+    fn qualify_trait_assoc_function() {
+        check_assist(
+            qualify_path,
+            r"
+            mod test_mod {
+                pub trait TestTrait {
+                    fn test_function();
+                }
+                pub struct TestStruct {}
+                impl TestTrait for TestStruct {
+                    fn test_function() {}
+                }
+            }
+
+            fn main() {
+                test_mod::TestStruct::test_function<|>
+            }
+            ",
+            r"
+            mod test_mod {
+                pub trait TestTrait {
+                    fn test_function();
+                }
+                pub struct TestStruct {}
+                impl TestTrait for TestStruct {
+                    fn test_function() {}
+                }
+            }
+
+            fn main() {
+                <|><test_mod::TestStruct as test_mod::TestTrait>::test_function
+            }
+            ",
+        );
+    }
+
+    #[test]
+    fn qualify_trait_method() {
+        check_assist(
+            qualify_path,
+            r"
+            mod test_mod {
+                pub trait TestTrait {
+                    fn test_method(&self, x: u32);
+                }
+                pub struct TestStruct {}
+                impl TestTrait for TestStruct {
+                    fn test_method(&self, x: u32) {}
+                }
+            }
+
+            fn main() {
+                let test_struct = test_mod::TestStruct {};
+                test_struct.test_meth<|>od(92)
+            }
+            ",
+            r"
+            mod test_mod {
+                pub trait TestTrait {
+                    fn test_method(&self, x: u32);
+                }
+                pub struct TestStruct {}
+                impl TestTrait for TestStruct {
+                    fn test_method(&self, x: u32) {}
+                }
+            }
+
+            fn main() {
+                let test_struct = test_mod::TestStruct {};
+                <|>test_mod::TestTrait::test_method(&test_struct, 92)
+            }
+            ",
+        );
+    }
+
+    #[test]
+    fn qualify_path_target() {
+        check_assist_target(
+            qualify_path,
+            r"
+            struct AssistInfo {
+                group_label: Option<<|>GroupLabel>,
+            }
+
+            mod m { pub struct GroupLabel; }
+            ",
+            "GroupLabel",
+        )
+    }
+
+    #[test]
+    fn not_applicable_when_resolved() {
+        check_assist_not_applicable(
+            qualify_path,
+            r"
+            use PubMod::PubStruct;
+
+            PubStruct<|>
+
+            pub mod PubMod {
+                pub struct PubStruct;
+            }
+            ",
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_macros() {
+        check_assist_not_applicable(
+            qualify_path,
+            r"
+            macro_rules! foo {
+                ($i:ident) => { fn foo(a: $i) {} }
+            }
+            foo!(Pub<|>Struct);
+
+            pub mod PubMod {
+                pub struct PubStruct;
+            }
+            ",
+        );
+    }
+}
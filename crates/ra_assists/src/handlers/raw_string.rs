@@ -5,7 +5,7 @@ use ra_syntax::{
     TextSize,
 };
 
-use crate::{Assist, AssistCtx, AssistId};
+use crate::{Assist, AssistCtx, AssistId, AssistKind};
 
 // Assist: make_raw_string
 //
@@ -25,15 +25,22 @@ use crate::{Assist, AssistCtx, AssistId};
 pub(crate) fn make_raw_string(ctx: AssistCtx) -> Option<Assist> {
     let token = ctx.find_token_at_offset(STRING).and_then(ast::String::cast)?;
     let value = token.value()?;
-    ctx.add_assist(AssistId("make_raw_string"), "Rewrite as raw string", |edit| {
-        edit.target(token.syntax().text_range());
-        let max_hash_streak = count_hashes(&value);
-        let mut hashes = String::with_capacity(max_hash_streak + 1);
-        for _ in 0..hashes.capacity() {
-            hashes.push('#');
-        }
-        edit.replace(token.syntax().text_range(), format!("r{}\"{}\"{}", hashes, value, hashes));
-    })
+    ctx.add_assist(
+        AssistId("make_raw_string", AssistKind::RefactorRewrite),
+        "Rewrite as raw string",
+        |edit| {
+            edit.target(token.syntax().text_range());
+            let max_hash_streak = count_hashes(&value);
+            let mut hashes = String::with_capacity(max_hash_streak + 1);
+            for _ in 0..hashes.capacity() {
+                hashes.push('#');
+            }
+            edit.replace(
+                token.syntax().text_range(),
+                format!("r{}\"{}\"{}", hashes, value, hashes),
+            );
+        },
+    )
 }
 
 // Assist: make_usual_string
@@ -54,12 +61,16 @@ pub(crate) fn make_raw_string(ctx: AssistCtx) -> Option<Assist> {
 pub(crate) fn make_usual_string(ctx: AssistCtx) -> Option<Assist> {
     let token = ctx.find_token_at_offset(RAW_STRING).and_then(ast::RawString::cast)?;
     let value = token.value()?;
-    ctx.add_assist(AssistId("make_usual_string"), "Rewrite as regular string", |edit| {
-        edit.target(token.syntax().text_range());
-        // parse inside string to escape `"`
-        let escaped = value.escape_default().to_string();
-        edit.replace(token.syntax().text_range(), format!("\"{}\"", escaped));
-    })
+    ctx.add_assist(
+        AssistId("make_usual_string", AssistKind::RefactorRewrite),
+        "Rewrite as regular string",
+        |edit| {
+            edit.target(token.syntax().text_range());
+            // parse inside string to escape `"`
+            let escaped = value.escape_default().to_string();
+            edit.replace(token.syntax().text_range(), format!("\"{}\"", escaped));
+        },
+    )
 }
 
 // Assist: add_hash
@@ -79,11 +90,15 @@ pub(crate) fn make_usual_string(ctx: AssistCtx) -> Option<Assist> {
 // ```
 pub(crate) fn add_hash(ctx: AssistCtx) -> Option<Assist> {
     let token = ctx.find_token_at_offset(RAW_STRING)?;
-    ctx.add_assist(AssistId("add_hash"), "Add # to raw string", |edit| {
-        edit.target(token.text_range());
-        edit.insert(token.text_range().start() + TextSize::of('r'), "#");
-        edit.insert(token.text_range().end(), "#");
-    })
+    ctx.add_assist(
+        AssistId("add_hash", AssistKind::RefactorRewrite),
+        "Add # to raw string",
+        |edit| {
+            edit.target(token.text_range());
+            edit.insert(token.text_range().start() + TextSize::of('r'), "#");
+            edit.insert(token.text_range().end(), "#");
+        },
+    )
 }
 
 // Assist: remove_hash
@@ -108,19 +123,23 @@ pub(crate) fn remove_hash(ctx: AssistCtx) -> Option<Assist> {
         // no hash to remove
         return None;
     }
-    ctx.add_assist(AssistId("remove_hash"), "Remove hash from raw string", |edit| {
-        edit.target(token.text_range());
-        let result = &text[2..text.len() - 1];
-        let result = if result.starts_with('\"') {
-            // FIXME: this logic is wrong, not only the last has has to handled specially
-            // no more hash, escape
-            let internal_str = &result[1..result.len() - 1];
-            format!("\"{}\"", internal_str.escape_default().to_string())
-        } else {
-            result.to_owned()
-        };
-        edit.replace(token.text_range(), format!("r{}", result));
-    })
+    ctx.add_assist(
+        AssistId("remove_hash", AssistKind::RefactorRewrite),
+        "Remove hash from raw string",
+        |edit| {
+            edit.target(token.text_range());
+            let result = &text[2..text.len() - 1];
+            let result = if result.starts_with('\"') {
+                // FIXME: this logic is wrong, not only the last has has to handled specially
+                // no more hash, escape
+                let internal_str = &result[1..result.len() - 1];
+                format!("\"{}\"", internal_str.escape_default().to_string())
+            } else {
+                result.to_owned()
+            };
+            edit.replace(token.text_range(), format!("r{}", result));
+        },
+    )
 }
 
 fn count_hashes(s: &str) -> usize {
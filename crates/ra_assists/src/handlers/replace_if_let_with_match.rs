@@ -2,13 +2,15 @@ use ra_fmt::unwrap_trivial_block;
 use ra_syntax::{
     ast::{self, edit::IndentLevel, make},
     AstNode,
+    SyntaxKind::COMMENT,
 };
 
-use crate::{utils::TryEnum, Assist, AssistCtx, AssistId};
+use crate::{utils::TryEnum, Assist, AssistCtx, AssistId, AssistKind};
 
 // Assist: replace_if_let_with_match
 //
-// Replaces `if let` with an else branch with a `match` expression.
+// Replaces `if let` with an else branch with a `match` expression. `else if`
+// branches become guarded arms.
 //
 // ```
 // enum Action { Move { distance: u32 }, Stop }
@@ -38,43 +40,98 @@ pub(crate) fn replace_if_let_with_match(ctx: AssistCtx) -> Option<Assist> {
     let pat = cond.pat()?;
     let expr = cond.expr()?;
     let then_block = if_expr.then_branch()?;
-    let else_block = match if_expr.else_branch()? {
-        ast::ElseBranch::Block(it) => it,
-        ast::ElseBranch::IfExpr(_) => return None,
-    };
+
+    let mut branches = Vec::new();
+    let mut else_block = None;
+    let mut curr = if_expr.clone();
+    loop {
+        // Comments between the branches would be lost.
+        if curr.syntax().children_with_tokens().any(|it| it.kind() == COMMENT) {
+            return None;
+        }
+        match curr.else_branch() {
+            Some(ast::ElseBranch::Block(it)) => {
+                else_block = Some(it);
+                break;
+            }
+            Some(ast::ElseBranch::IfExpr(it)) => {
+                let cond = it.condition()?;
+                let cond_expr = cond.expr()?;
+                let branch = match cond.pat() {
+                    Some(pat) if cond_expr.syntax().text() == expr.syntax().text() => {
+                        Branch::Pat(pat)
+                    }
+                    Some(_) => return None,
+                    None => Branch::Guard(cond_expr),
+                };
+                branches.push((branch, it.then_branch()?));
+                curr = it;
+            }
+            None => break,
+        }
+    }
+    if branches.is_empty() && else_block.is_none() {
+        return None;
+    }
 
     let sema = ctx.sema;
-    ctx.add_assist(AssistId("replace_if_let_with_match"), "Replace with match", move |edit| {
-        let match_expr = {
-            let then_arm = {
-                let then_expr = unwrap_trivial_block(then_block);
-                make::match_arm(vec![pat.clone()], then_expr)
+    ctx.add_assist(
+        AssistId("replace_if_let_with_match", AssistKind::RefactorRewrite),
+        "Replace with match",
+        move |edit| {
+            let match_expr = {
+                let then_arm = {
+                    let then_expr = unwrap_trivial_block(then_block);
+                    make::match_arm(vec![pat.clone()], then_expr)
+                };
+                let branch_arms = branches.into_iter().map(|(branch, block)| {
+                    let expr = unwrap_trivial_block(block);
+                    match branch {
+                        Branch::Pat(pat) => make::match_arm(vec![pat], expr),
+                        Branch::Guard(guard) => make::match_arm_with_guard(
+                            vec![make::placeholder_pat().into()],
+                            guard,
+                            expr,
+                        ),
+                    }
+                });
+                let else_arm = {
+                    let pattern = sema
+                        .type_of_pat(&pat)
+                        .and_then(|ty| TryEnum::from_ty(sema, &ty))
+                        .map(|it| it.sad_pattern())
+                        .unwrap_or_else(|| make::placeholder_pat().into());
+                    let else_expr = match else_block {
+                        Some(block) => unwrap_trivial_block(block),
+                        None => make::expr_empty_block(),
+                    };
+                    make::match_arm(vec![pattern], else_expr)
+                };
+                let arms = Some(then_arm).into_iter().chain(branch_arms).chain(Some(else_arm));
+                make::expr_match(expr, make::match_arm_list(arms))
             };
-            let else_arm = {
-                let pattern = sema
-                    .type_of_pat(&pat)
-                    .and_then(|ty| TryEnum::from_ty(sema, &ty))
-                    .map(|it| it.sad_pattern())
-                    .unwrap_or_else(|| make::placeholder_pat().into());
-                let else_expr = unwrap_trivial_block(else_block);
-                make::match_arm(vec![pattern], else_expr)
-            };
-            make::expr_match(expr, make::match_arm_list(vec![then_arm, else_arm]))
-        };
 
-        let match_expr = IndentLevel::from_node(if_expr.syntax()).increase_indent(match_expr);
+            let match_expr = IndentLevel::from_node(if_expr.syntax()).increase_indent(match_expr);
+
+            edit.target(if_expr.syntax().text_range());
+            edit.set_cursor(if_expr.syntax().text_range().start());
+            edit.replace_ast::<ast::Expr>(if_expr.into(), match_expr);
+        },
+    )
+}
 
-        edit.target(if_expr.syntax().text_range());
-        edit.set_cursor(if_expr.syntax().text_range().start());
-        edit.replace_ast::<ast::Expr>(if_expr.into(), match_expr);
-    })
+enum Branch {
+    /// `else if let` on the same expression, which becomes an arm of its own.
+    Pat(ast::Pat),
+    /// `else if`, which becomes a guarded arm.
+    Guard(ast::Expr),
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use crate::helpers::{check_assist, check_assist_target};
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
 
     #[test]
     fn test_replace_if_let_with_match_unwraps_simple_expressions() {
@@ -211,4 +268,109 @@ fn foo(x: Result<i32, ()>) {
            "#,
         );
     }
+
+    #[test]
+    fn else_if_branches_become_arms() {
+        check_assist(
+            replace_if_let_with_match,
+            r#"
+fn foo(x: Option<i32>, y: bool) {
+    <|>if let Some(0) = x {
+        zero()
+    } else if let Some(1) = x {
+        one()
+    } else if y {
+        other()
+    }
+}
+"#,
+            r#"
+fn foo(x: Option<i32>, y: bool) {
+    <|>match x {
+        Some(0) => zero(),
+        Some(1) => one(),
+        _ if y => other(),
+        _ => {}
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn keeps_comments_in_branches() {
+        check_assist(
+            replace_if_let_with_match,
+            r#"
+fn foo(x: Option<i32>) {
+    <|>if let Some(x) = x {
+        // Print the value.
+        println!("{}", x)
+    } else {
+        ()
+    }
+}
+"#,
+            r#"
+fn foo(x: Option<i32>) {
+    <|>match x {
+        Some(x) => {
+            // Print the value.
+            println!("{}", x)
+        }
+        _ => (),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_comments_between_branches() {
+        check_assist_not_applicable(
+            replace_if_let_with_match,
+            r#"
+fn foo(x: Option<i32>) {
+    <|>if let Some(x) = x {
+        bar(x)
+    } // Nothing to do.
+    else {
+        ()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_else_if_let_on_other_expr() {
+        check_assist_not_applicable(
+            replace_if_let_with_match,
+            r#"
+fn foo(x: Option<i32>, y: Option<i32>) {
+    <|>if let Some(x) = x {
+        bar(x)
+    } else if let Some(y) = y {
+        bar(y)
+    } else {
+        ()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_else() {
+        check_assist_not_applicable(
+            replace_if_let_with_match,
+            r#"
+fn foo(x: Option<i32>) {
+    <|>if let Some(x) = x {
+        bar(x)
+    }
+}
+"#,
+        );
+    }
 }
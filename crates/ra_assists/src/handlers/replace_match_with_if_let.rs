@@ -0,0 +1,254 @@
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, make},
+    AstNode,
+    SyntaxKind::COMMENT,
+};
+use stdx::format_to;
+
+use crate::{utils::TryEnum, Assist, AssistCtx, AssistId, AssistKind};
+
+// Assist: replace_match_with_if_let
+//
+// Replaces a `match` with a single non-trivial arm with an `if let`. Guarded
+// wildcard arms become `else if` branches.
+//
+// ```
+// enum Action { Move { distance: u32 }, Stop }
+//
+// fn handle(action: Action) {
+//     <|>match action {
+//         Action::Move { distance } => foo(distance),
+//         _ => bar(),
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Action { Move { distance: u32 }, Stop }
+//
+// fn handle(action: Action) {
+//     if let Action::Move { distance } = action {
+//         foo(distance)
+//     } else {
+//         bar()
+//     }
+// }
+// ```
+pub(crate) fn replace_match_with_if_let(ctx: AssistCtx) -> Option<Assist> {
+    let match_expr: ast::MatchExpr = ctx.find_node_at_offset()?;
+    let expr = match_expr.expr()?;
+    let arm_list = match_expr.match_arm_list()?;
+    let arms = arm_list.arms().collect::<Vec<_>>();
+    // Comments between the arms would be lost.
+    if arm_list.syntax().children_with_tokens().any(|it| it.kind() == COMMENT)
+        || arms.iter().any(|arm| arm.syntax().children_with_tokens().any(|it| it.kind() == COMMENT))
+    {
+        return None;
+    }
+
+    let is_trivial = |arm: &ast::MatchArm| match arm.pat() {
+        Some(ast::Pat::PlaceholderPat(_)) => true,
+        Some(pat) => ctx
+            .sema
+            .type_of_pat(&pat)
+            .and_then(|ty| TryEnum::from_ty(ctx.sema, &ty))
+            .map_or(false, |it| it.sad_pattern().syntax().text() == pat.syntax().text()),
+        None => false,
+    };
+    let (then_arm, guarded_arms, else_arm) = match arms.as_slice() {
+        // `None => .., Some(x) => ..`
+        [first, second] if first.guard().is_none() && is_trivial(first) && !is_trivial(second) => {
+            (second, &[][..], first)
+        }
+        [first, rest @ .., last] => (first, rest, last),
+        _ => return None,
+    };
+    if then_arm.guard().is_some() || is_trivial(then_arm) {
+        return None;
+    }
+    if else_arm.guard().is_some() || !is_trivial(else_arm) {
+        return None;
+    }
+    if !guarded_arms.iter().all(|arm| arm.guard().is_some() && is_trivial(arm)) {
+        return None;
+    }
+
+    let pat = then_arm.pat()?;
+    let indent = IndentLevel::from_node(match_expr.syntax());
+    let then_block = arm_block(then_arm, indent)?;
+    let else_ifs = guarded_arms
+        .iter()
+        .map(|arm| Some((arm.guard()?.expr()?, arm_block(arm, indent)?)))
+        .collect::<Option<Vec<_>>>()?;
+    let else_expr = else_arm.expr()?;
+    let else_block = match else_expr.syntax().text().to_string().as_str() {
+        "()" | "{}" => None,
+        _ => Some(arm_block(else_arm, indent)?),
+    };
+
+    ctx.add_assist(
+        AssistId("replace_match_with_if_let", AssistKind::RefactorRewrite),
+        "Replace with if let",
+        move |edit| {
+            let mut if_let = format!("if let {} = {} {}", pat, expr, then_block);
+            for (guard, block) in else_ifs {
+                format_to!(if_let, " else if {} {}", guard, block);
+            }
+            if let Some(block) = else_block {
+                format_to!(if_let, " else {}", block);
+            }
+
+            let range = match_expr.syntax().text_range();
+            edit.target(range);
+            edit.set_cursor(range.start());
+            edit.replace(range, if_let);
+        },
+    )
+}
+
+/// Returns the body of `arm` as a block, indented to `indent`.
+fn arm_block(arm: &ast::MatchArm, indent: IndentLevel) -> Option<ast::BlockExpr> {
+    let arm_indent = IndentLevel::from_node(arm.syntax());
+    let block = match arm_indent.decrease_indent(arm.expr()?) {
+        ast::Expr::BlockExpr(it) => it,
+        expr => make::block_expr(None, Some(IndentLevel(1).increase_indent(expr))),
+    };
+    Some(indent.increase_indent(block))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn replace_match_with_if_let_simple() {
+        check_assist(
+            replace_match_with_if_let,
+            r#"
+fn foo(x: Option<i32>) {
+    <|>match x {
+        Some(x) => bar(x),
+        _ => baz(),
+    }
+}
+"#,
+            r#"
+fn foo(x: Option<i32>) {
+    <|>if let Some(x) = x {
+        bar(x)
+    } else {
+        baz()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_match_with_if_let_trivial_arm_first() {
+        check_assist(
+            replace_match_with_if_let,
+            r#"
+enum Option<T> { Some(T), None }
+use Option::*;
+
+fn foo(x: Option<i32>) {
+    <|>match x {
+        None => {}
+        Some(x) => {
+            // Print the value.
+            println!("{}", x)
+        }
+    }
+}
+"#,
+            r#"
+enum Option<T> { Some(T), None }
+use Option::*;
+
+fn foo(x: Option<i32>) {
+    <|>if let Some(x) = x {
+        // Print the value.
+        println!("{}", x)
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_match_with_if_let_guards() {
+        check_assist(
+            replace_match_with_if_let,
+            r#"
+fn foo(x: Option<i32>, y: bool) {
+    <|>match x {
+        Some(0) => zero(),
+        _ if y => other(),
+        _ => (),
+    }
+}
+"#,
+            r#"
+fn foo(x: Option<i32>, y: bool) {
+    <|>if let Some(0) = x {
+        zero()
+    } else if y {
+        other()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_match_with_if_let_target() {
+        check_assist_target(
+            replace_match_with_if_let,
+            r#"
+fn foo(x: Option<i32>) {
+    <|>match x {
+        Some(x) => bar(x),
+        _ => (),
+    }
+}
+"#,
+            r#"match x {
+        Some(x) => bar(x),
+        _ => (),
+    }"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_two_non_trivial_arms() {
+        check_assist_not_applicable(
+            replace_match_with_if_let,
+            r#"
+fn foo(x: Option<i32>) {
+    <|>match x {
+        Some(0) => zero(),
+        Some(_) => other(),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_guard_on_first_arm() {
+        check_assist_not_applicable(
+            replace_match_with_if_let,
+            r#"
+fn foo(x: Option<i32>) {
+    <|>match x {
+        Some(x) if x > 0 => bar(x),
+        _ => (),
+    }
+}
+"#,
+        );
+    }
+}
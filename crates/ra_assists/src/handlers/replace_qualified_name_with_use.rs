@@ -4,7 +4,7 @@ use ra_syntax::{ast, AstNode, SmolStr, TextRange};
 use crate::{
     assist_ctx::{Assist, AssistCtx},
     utils::insert_use_statement,
-    AssistId,
+    AssistId, AssistKind,
 };
 
 // Assist: replace_qualified_name_with_use
@@ -34,7 +34,7 @@ pub(crate) fn replace_qualified_name_with_use(ctx: AssistCtx) -> Option<Assist>
     }
 
     ctx.add_assist(
-        AssistId("replace_qualified_name_with_use"),
+        AssistId("replace_qualified_name_with_use", AssistKind::RefactorRewrite),
         "Replace qualified path with use",
         |edit| {
             let path_to_import = hir_path.mod_path().clone();
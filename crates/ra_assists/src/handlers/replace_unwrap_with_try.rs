@@ -0,0 +1,394 @@
+use hir::{HirDisplay, Type};
+use ra_syntax::{
+    ast::{self, edit::IndentLevel},
+    AstNode, SyntaxNode, TextRange,
+};
+
+use crate::{
+    utils::{FamousDefs, TryEnum},
+    Assist, AssistCtx, AssistId, AssistKind,
+};
+
+// Assist: replace_unwrap_with_try
+//
+// Replaces `unwrap` with the `?` operator, if the function returns a
+// compatible `Result` or `Option`.
+//
+// ```
+// enum Result<T, E> { Ok(T), Err(E) }
+// fn parse() -> Result<i32, ()> { Result::Ok(92) }
+// fn double() -> Result<i32, ()> {
+//     let x = parse().<|>unwrap();
+//     Result::Ok(x * 2)
+// }
+// ```
+// ->
+// ```
+// enum Result<T, E> { Ok(T), Err(E) }
+// fn parse() -> Result<i32, ()> { Result::Ok(92) }
+// fn double() -> Result<i32, ()> {
+//     let x = parse()?;
+//     Result::Ok(x * 2)
+// }
+// ```
+pub(crate) fn replace_unwrap_with_try(ctx: AssistCtx) -> Option<Assist> {
+    let (method_call, try_enum, receiver_ty) = unwrap_call(&ctx)?;
+    let fn_def = enclosing_fn(method_call.syntax())?;
+    let ret_ty = ctx.sema.to_def(&fn_def)?.ret_type(ctx.db);
+    match (try_enum, TryEnum::from_ty(ctx.sema, &ret_ty)?) {
+        (TryEnum::Option, TryEnum::Option) => (),
+        (TryEnum::Result, TryEnum::Result) => {
+            let err_ty = receiver_ty.type_parameters().get(1)?.clone();
+            let ret_err_ty = ret_ty.type_parameters().get(1)?.clone();
+            if err_ty != ret_err_ty && !is_convertible(&ctx, &fn_def, &err_ty, &ret_err_ty) {
+                return None;
+            }
+        }
+        _ => return None,
+    }
+    let unwrap_range = unwrap_range(&method_call)?;
+
+    ctx.add_assist(
+        AssistId("replace_unwrap_with_try", AssistKind::RefactorRewrite),
+        "Replace unwrap with `?`",
+        |edit| {
+            edit.target(method_call.syntax().text_range());
+            edit.replace(unwrap_range, "?");
+        },
+    )
+}
+
+// Assist: replace_unwrap_with_try_returning_result
+//
+// Replaces `unwrap` with the `?` operator in a function returning `()`, and
+// changes the function to return a `Result` instead.
+//
+// ```
+// enum Result<T, E> { Ok(T), Err(E) }
+// fn parse() -> Result<i32, ()> { Result::Ok(92) }
+// fn main() {
+//     let x = parse().<|>unwrap();
+// }
+// ```
+// ->
+// ```
+// enum Result<T, E> { Ok(T), Err(E) }
+// fn parse() -> Result<i32, ()> { Result::Ok(92) }
+// fn main() -> Result<(), ()> {
+//     let x = parse()?;
+//     Ok(())
+// }
+// ```
+pub(crate) fn replace_unwrap_with_try_returning_result(ctx: AssistCtx) -> Option<Assist> {
+    let (method_call, try_enum, receiver_ty) = unwrap_call(&ctx)?;
+    if !matches!(try_enum, TryEnum::Result) {
+        return None;
+    }
+    let fn_def = enclosing_fn(method_call.syntax())?;
+    if !ctx.sema.to_def(&fn_def)?.ret_type(ctx.db).is_unit() {
+        return None;
+    }
+    let err_ty = receiver_ty.type_parameters().get(1)?.clone();
+    if err_ty.contains_unknown() {
+        return None;
+    }
+    let err_ty = err_ty.display_truncated(ctx.db, None).to_string();
+    let body = fn_def.body()?;
+    let last = match body.expr() {
+        Some(it) => it.syntax().clone(),
+        None => body.statements().last()?.syntax().clone(),
+    };
+    let unwrap_range = unwrap_range(&method_call)?;
+
+    ctx.add_assist(
+        AssistId("replace_unwrap_with_try_returning_result", AssistKind::RefactorRewrite),
+        "Replace unwrap with `?` and return `Result`",
+        |edit| {
+            edit.target(method_call.syntax().text_range());
+            edit.replace(unwrap_range, "?");
+
+            let ret_type = format!("-> Result<(), {}>", err_ty);
+            match (fn_def.ret_type(), fn_def.param_list()) {
+                (Some(it), _) => edit.replace(it.syntax().text_range(), ret_type),
+                (None, Some(it)) => {
+                    edit.insert(it.syntax().text_range().end(), format!(" {}", ret_type))
+                }
+                (None, None) => (),
+            }
+
+            let indent = " ".repeat((IndentLevel::from_node(fn_def.syntax()).0 as usize + 1) * 4);
+            let mut tail = String::new();
+            if let Some(expr) = body.expr() {
+                if !expr.is_block_like() {
+                    tail.push(';');
+                }
+            }
+            tail.push_str(&format!("\n{}Ok(())", indent));
+            edit.insert(last.text_range().end(), tail);
+        },
+    )
+}
+
+fn unwrap_call(ctx: &AssistCtx) -> Option<(ast::MethodCallExpr, TryEnum, Type)> {
+    let method_call: ast::MethodCallExpr = ctx.find_node_at_offset()?;
+    let name = method_call.name_ref()?;
+    if name.text() != "unwrap" || method_call.arg_list()?.args().next().is_some() {
+        return None;
+    }
+    let receiver_ty = ctx.sema.type_of_expr(&method_call.expr()?)?;
+    let try_enum = TryEnum::from_ty(ctx.sema, &receiver_ty)?;
+    Some((method_call, try_enum, receiver_ty))
+}
+
+/// The range of `.unwrap()` in `foo.unwrap()`.
+fn unwrap_range(method_call: &ast::MethodCallExpr) -> Option<TextRange> {
+    let receiver = method_call.expr()?;
+    Some(TextRange::new(
+        receiver.syntax().text_range().end(),
+        method_call.syntax().text_range().end(),
+    ))
+}
+
+/// The function `?` would return from. Closures have their own return type,
+/// so we bail out on them.
+fn enclosing_fn(node: &SyntaxNode) -> Option<ast::FnDef> {
+    for node in node.ancestors() {
+        if ast::LambdaExpr::can_cast(node.kind()) {
+            return None;
+        }
+        if let Some(fn_def) = ast::FnDef::cast(node) {
+            return Some(fn_def);
+        }
+    }
+    None
+}
+
+/// Whether `?` can convert `from` into `into`, with a `From` impl.
+fn is_convertible(ctx: &AssistCtx, fn_def: &ast::FnDef, from: &Type, into: &Type) -> bool {
+    let krate = match ctx.sema.scope(fn_def.syntax()).module() {
+        Some(it) => it.krate(),
+        None => return false,
+    };
+    FamousDefs(ctx.sema, krate)
+        .core_convert_From()
+        .map_or(false, |from_trait| into.impls_trait(ctx.db, from_trait, &[from.clone()]))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    use super::*;
+
+    #[test]
+    fn replace_result_unwrap() {
+        check_assist(
+            replace_unwrap_with_try,
+            r#"
+enum Result<T, E> { Ok(T), Err(E) }
+fn foo() -> Result<u32, ()> { Result::Ok(92) }
+fn bar() -> Result<u32, ()> {
+    let x = foo().<|>unwrap();
+    Result::Ok(x)
+}
+"#,
+            r#"
+enum Result<T, E> { Ok(T), Err(E) }
+fn foo() -> Result<u32, ()> { Result::Ok(92) }
+fn bar() -> Result<u32, ()> {
+    let x = foo()<|>?;
+    Result::Ok(x)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_option_unwrap() {
+        check_assist(
+            replace_unwrap_with_try,
+            r#"
+enum Option<T> { Some(T), None }
+fn foo() -> Option<u32> { Option::None }
+fn bar() -> Option<u32> {
+    foo().<|>unwrap().checked_add(1)
+}
+"#,
+            r#"
+enum Option<T> { Some(T), None }
+fn foo() -> Option<u32> { Option::None }
+fn bar() -> Option<u32> {
+    foo()<|>?.checked_add(1)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_unwrap_with_from_conversion() {
+        let before = r#"enum Result<T, E> { Ok(T), Err(E) }
+struct Error;
+impl From<()> for Error { fn from(_: ()) -> Error { Error } }
+fn foo() -> Result<u32, ()> { Result::Ok(92) }
+fn bar() -> Result<u32, Error> {
+    Result::Ok(foo().<|>unwrap())
+}"#;
+        let after = r#"enum Result<T, E> { Ok(T), Err(E) }
+struct Error;
+impl From<()> for Error { fn from(_: ()) -> Error { Error } }
+fn foo() -> Result<u32, ()> { Result::Ok(92) }
+fn bar() -> Result<u32, Error> {
+    Result::Ok(foo()<|>?)
+}"#;
+        check_assist(
+            replace_unwrap_with_try,
+            &format!("//- main.rs crate:main deps:core\n{}\n{}", before, FamousDefs::FIXTURE),
+            &format!("{}\n\n", after),
+        );
+    }
+
+    #[test]
+    fn replace_unwrap_target() {
+        check_assist_target(
+            replace_unwrap_with_try,
+            r#"
+enum Option<T> { Some(T), None }
+fn foo(x: Option<u32>) -> Option<u32> {
+    let y = x.<|>unwrap();
+    Option::Some(y)
+}
+"#,
+            "x.unwrap()",
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_incompatible_error() {
+        check_assist_not_applicable(
+            replace_unwrap_with_try,
+            r#"
+enum Result<T, E> { Ok(T), Err(E) }
+struct Error;
+fn foo() -> Result<u32, ()> { Result::Ok(92) }
+fn bar() -> Result<u32, Error> {
+    Result::Ok(foo().<|>unwrap())
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_option_in_result_fn() {
+        check_assist_not_applicable(
+            replace_unwrap_with_try,
+            r#"
+enum Result<T, E> { Ok(T), Err(E) }
+enum Option<T> { Some(T), None }
+fn bar(x: Option<u32>) -> Result<u32, ()> {
+    Result::Ok(x.<|>unwrap())
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_closure() {
+        check_assist_not_applicable(
+            replace_unwrap_with_try,
+            r#"
+enum Option<T> { Some(T), None }
+fn bar(x: Option<u32>) -> Option<u32> {
+    let f = || x.<|>unwrap();
+    Option::Some(f())
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_unwrap_returning_result() {
+        check_assist(
+            replace_unwrap_with_try_returning_result,
+            r#"
+enum Result<T, E> { Ok(T), Err(E) }
+fn foo() -> Result<u32, ()> { Result::Ok(92) }
+fn consume(_: u32) {}
+fn main() {
+    let x = foo().<|>unwrap();
+    consume(x)
+}
+"#,
+            r#"
+enum Result<T, E> { Ok(T), Err(E) }
+fn foo() -> Result<u32, ()> { Result::Ok(92) }
+fn consume(_: u32) {}
+fn main() -> Result<(), ()> {
+    let x = foo()<|>?;
+    consume(x);
+    Ok(())
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_unwrap_returning_result_with_explicit_unit() {
+        check_assist(
+            replace_unwrap_with_try_returning_result,
+            r#"
+enum Result<T, E> { Ok(T), Err(E) }
+struct S;
+impl S {
+    fn foo(&self) -> Result<u32, u8> { Result::Err(0) }
+
+    fn run(&self) -> () {
+        if true {
+            self.foo().<|>unwrap();
+        }
+    }
+}
+"#,
+            r#"
+enum Result<T, E> { Ok(T), Err(E) }
+struct S;
+impl S {
+    fn foo(&self) -> Result<u32, u8> { Result::Err(0) }
+
+    fn run(&self) -> Result<(), u8> {
+        if true {
+            self.foo()<|>?;
+        }
+        Ok(())
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_returning_result_in_non_unit_fn() {
+        check_assist_not_applicable(
+            replace_unwrap_with_try_returning_result,
+            r#"
+enum Result<T, E> { Ok(T), Err(E) }
+fn foo() -> Result<u32, ()> { Result::Ok(92) }
+fn bar() -> u32 {
+    foo().<|>unwrap()
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_returning_result_for_option() {
+        check_assist_not_applicable(
+            replace_unwrap_with_try_returning_result,
+            r#"
+enum Option<T> { Some(T), None }
+fn main() {
+    let x = Option::Some(92).<|>unwrap();
+}
+"#,
+        );
+    }
+}
@@ -2,7 +2,7 @@ use std::iter::successors;
 
 use ra_syntax::{ast, AstNode, T};
 
-use crate::{Assist, AssistCtx, AssistId};
+use crate::{Assist, AssistCtx, AssistId, AssistKind};
 
 // Assist: split_import
 //
@@ -28,7 +28,7 @@ pub(crate) fn split_import(ctx: AssistCtx) -> Option<Assist> {
     }
     let cursor = ctx.frange.range.start();
 
-    ctx.add_assist(AssistId("split_import"), "Split import", |edit| {
+    ctx.add_assist(AssistId("split_import", AssistKind::RefactorRewrite), "Split import", |edit| {
         edit.target(colon_colon.text_range());
         edit.replace_ast(use_tree, new_tree);
         edit.set_cursor(cursor);
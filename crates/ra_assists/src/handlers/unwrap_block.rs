@@ -1,4 +1,4 @@
-use crate::{Assist, AssistCtx, AssistId};
+use crate::{Assist, AssistCtx, AssistId, AssistKind};
 
 use ast::LoopBodyOwner;
 use ra_fmt::unwrap_trivial_block;
@@ -57,7 +57,7 @@ pub(crate) fn unwrap_block(ctx: AssistCtx) -> Option<Assist> {
         }
     };
 
-    ctx.add_assist(AssistId("unwrap_block"), "Unwrap block", |edit| {
+    ctx.add_assist(AssistId("unwrap_block", AssistKind::RefactorRewrite), "Unwrap block", |edit| {
         edit.set_cursor(expr.syntax().text_range().start());
         edit.target(expr_to_unwrap.syntax().text_range());
 
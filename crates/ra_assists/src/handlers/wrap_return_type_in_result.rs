@@ -0,0 +1,258 @@
+use ra_syntax::ast::{self, AstNode};
+
+use crate::{Assist, AssistCtx, AssistId, AssistKind};
+
+// Assist: wrap_return_type_in_result
+//
+// Wraps the return type of a function in a `Result`, and the returned values
+// in `Ok`.
+//
+// ```
+// fn parse(s: &str) -> <|>i32 {
+//     if s.is_empty() {
+//         return 0;
+//     }
+//     s.len() as i32
+// }
+// ```
+// ->
+// ```
+// fn parse(s: &str) -> Result<i32, ${0:Error}> {
+//     if s.is_empty() {
+//         return Ok(0);
+//     }
+//     Ok(s.len() as i32)
+// }
+// ```
+pub(crate) fn wrap_return_type_in_result(ctx: AssistCtx) -> Option<Assist> {
+    let ret_type = ctx.find_node_at_offset::<ast::RetType>()?;
+    let fn_def = ret_type.syntax().parent().and_then(ast::FnDef::cast)?;
+    let type_ref = ret_type.type_ref()?;
+    if is_result(&type_ref) {
+        return None;
+    }
+    let body = fn_def.body()?;
+
+    let mut returned = Vec::new();
+    if let Some(tail) = body.expr() {
+        collect_tail_exprs(tail, &mut returned);
+    }
+    // `return`s of closures and nested functions don't return from this one.
+    let returns = body.syntax().descendants().filter_map(ast::ReturnExpr::cast).filter(|it| {
+        it.syntax().ancestors().take_while(|node| node != body.syntax()).all(|node| {
+            !ast::LambdaExpr::can_cast(node.kind()) && !ast::FnDef::can_cast(node.kind())
+        })
+    });
+    let mut unit_returns = Vec::new();
+    for return_expr in returns {
+        match return_expr.expr() {
+            Some(expr) => returned.push(expr),
+            None => unit_returns.push(return_expr),
+        }
+    }
+
+    let snippet_cap = ctx.config.snippet_cap;
+    ctx.add_assist(
+        AssistId("wrap_return_type_in_result", AssistKind::RefactorRewrite),
+        "Wrap return type in Result",
+        |edit| {
+            let range = type_ref.syntax().text_range();
+            edit.target(range);
+            match snippet_cap {
+                Some(cap) => {
+                    edit.replace_snippet(cap, range, format!("Result<{}, ${{0:Error}}>", type_ref))
+                }
+                None => edit.replace(range, format!("Result<{}, Error>", type_ref)),
+            }
+            for expr in returned {
+                let range = expr.syntax().text_range();
+                edit.insert(range.start(), "Ok(");
+                edit.insert(range.end(), ")");
+            }
+            for return_expr in unit_returns {
+                let offset = return_expr.syntax().text_range().end();
+                edit.insert(offset, " Ok(())");
+            }
+            edit.set_cursor(range.start());
+        },
+    )
+}
+
+/// Collects the expressions whose value is returned when `expr` is the tail
+/// expression of the function.
+fn collect_tail_exprs(expr: ast::Expr, acc: &mut Vec<ast::Expr>) {
+    match expr {
+        ast::Expr::BlockExpr(block) => {
+            if let Some(tail) = block.expr() {
+                collect_tail_exprs(tail, acc);
+            }
+        }
+        ast::Expr::IfExpr(if_expr) => {
+            if let Some(then_branch) = if_expr.then_branch() {
+                collect_tail_exprs(ast::Expr::BlockExpr(then_branch), acc);
+            }
+            match if_expr.else_branch() {
+                Some(ast::ElseBranch::Block(block)) => {
+                    collect_tail_exprs(ast::Expr::BlockExpr(block), acc)
+                }
+                Some(ast::ElseBranch::IfExpr(if_expr)) => {
+                    collect_tail_exprs(ast::Expr::IfExpr(if_expr), acc)
+                }
+                None => (),
+            }
+        }
+        ast::Expr::MatchExpr(match_expr) => {
+            let arms = match_expr.match_arm_list().into_iter().flat_map(|it| it.arms());
+            for arm in arms {
+                if let Some(expr) = arm.expr() {
+                    collect_tail_exprs(expr, acc);
+                }
+            }
+        }
+        // Returned values are wrapped separately.
+        ast::Expr::ReturnExpr(_) => (),
+        expr => acc.push(expr),
+    }
+}
+
+fn is_result(type_ref: &ast::TypeRef) -> bool {
+    match type_ref {
+        ast::TypeRef::PathType(it) => it
+            .path()
+            .and_then(|it| it.segment())
+            .and_then(|it| it.name_ref())
+            .map_or(false, |it| it.text() == "Result"),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        helpers::{
+            check_assist, check_assist_not_applicable, check_assist_target,
+            check_assist_with_config,
+        },
+        AssistConfig,
+    };
+
+    #[test]
+    fn wrap_tail_expr() {
+        check_assist(
+            wrap_return_type_in_result,
+            r#"
+fn foo() -> i3<|>2 {
+    let x = 42;
+    x
+}
+"#,
+            r#"
+fn foo() -> <|>Result<i32, ${0:Error}> {
+    let x = 42;
+    Ok(x)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn wrap_branches_and_returns() {
+        check_assist(
+            wrap_return_type_in_result,
+            r#"
+fn foo(x: Option<u32>) -> <|>u32 {
+    if x.is_none() {
+        return 0;
+    }
+    let f = || { return 1; };
+    match x {
+        Some(0) => 1,
+        Some(x) => {
+            let y = x * 2;
+            y
+        }
+        None => return 2,
+    }
+}
+"#,
+            r#"
+fn foo(x: Option<u32>) -> <|>Result<u32, ${0:Error}> {
+    if x.is_none() {
+        return Ok(0);
+    }
+    let f = || { return 1; };
+    match x {
+        Some(0) => Ok(1),
+        Some(x) => {
+            let y = x * 2;
+            Ok(y)
+        }
+        None => return Ok(2),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn wrap_unit_return() {
+        check_assist(
+            wrap_return_type_in_result,
+            r#"
+fn foo(x: bool) -> <|>() {
+    if x {
+        return;
+    }
+    bar()
+}
+"#,
+            r#"
+fn foo(x: bool) -> <|>Result<(), ${0:Error}> {
+    if x {
+        return Ok(());
+    }
+    Ok(bar())
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn wrap_without_snippets() {
+        let mut config = AssistConfig::default();
+        config.allow_snippets(false);
+        check_assist_with_config(
+            wrap_return_type_in_result,
+            config,
+            r#"
+fn foo() -> <|>i32 {
+    if true { 1 } else { 2 }
+}
+"#,
+            r#"
+fn foo() -> <|>Result<i32, Error> {
+    if true { Ok(1) } else { Ok(2) }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn wrap_return_type_in_result_target() {
+        check_assist_target(wrap_return_type_in_result, "fn foo() -> <|>i32 { 0 }", "i32");
+    }
+
+    #[test]
+    fn not_applicable_to_result() {
+        check_assist_not_applicable(
+            wrap_return_type_in_result,
+            "fn foo() -> <|>Result<i32, String> { Ok(0) }",
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_return_type() {
+        check_assist_not_applicable(wrap_return_type_in_result, "fn foo() { <|>0; }");
+    }
+}
@@ -17,18 +17,64 @@ mod doc_tests;
 pub mod utils;
 pub mod ast_transform;
 
-use hir::Semantics;
-use ra_db::{FileId, FileRange};
+use hir::{ModPath, Semantics};
+use ra_db::{FileId, FileRange, RelativePathBuf};
 use ra_ide_db::RootDatabase;
-use ra_syntax::{TextRange, TextSize};
-use ra_text_edit::TextEdit;
+use ra_syntax::{
+    ast::{self, AstNode},
+    SyntaxNode, TextRange, TextSize,
+};
+use ra_text_edit::{TextEdit, TextEditBuilder};
 
 pub(crate) use crate::assist_ctx::{Assist, AssistCtx, AssistHandler};
+pub use crate::utils::{InsertUseConfig, MergeBehaviour};
+
+/// Configures the behavior of the assists.
+#[derive(Debug, Clone)]
+pub struct AssistConfig {
+    pub insert_use: InsertUseConfig,
+    pub snippet_cap: Option<SnippetCap>,
+}
+
+impl AssistConfig {
+    pub fn allow_snippets(&mut self, yes: bool) {
+        self.snippet_cap = if yes { Some(SnippetCap { _private: () }) } else { None }
+    }
+}
+
+impl Default for AssistConfig {
+    fn default() -> Self {
+        AssistConfig {
+            insert_use: InsertUseConfig::default(),
+            snippet_cap: Some(SnippetCap { _private: () }),
+        }
+    }
+}
+
+/// Allows assists to produce snippets, which can only be done if the client
+/// supports them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SnippetCap {
+    _private: (),
+}
 
 /// Unique identifier of the assist, should not be shown to the user
 /// directly.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct AssistId(pub &'static str);
+pub struct AssistId(pub &'static str, pub AssistKind);
+
+/// The category of an assist, which lets the clients filter the assists and
+/// present them in dedicated menus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssistKind {
+    None,
+    QuickFix,
+    Generate,
+    Refactor,
+    RefactorExtract,
+    RefactorInline,
+    RefactorRewrite,
+}
 
 #[derive(Debug, Clone)]
 pub struct AssistLabel {
@@ -56,6 +102,13 @@ pub struct AssistAction {
     // FIXME: This belongs to `AssistLabel`
     pub target: Option<TextRange>,
     pub file: AssistFile,
+    /// Files to create, with their contents. The paths are relative to the
+    /// source root of the file the assist is applied to.
+    pub new_files: Vec<(RelativePathBuf, String)>,
+    /// Edits to files other than the one `edit` applies to.
+    pub other_file_edits: Vec<(FileId, TextEdit)>,
+    /// Whether `edit` contains snippet tabstops like `$0` or `${0:Error}`.
+    pub is_snippet: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -80,9 +133,13 @@ impl Default for AssistFile {
 ///
 /// Assists are returned in the "unresolved" state, that is only labels are
 /// returned, without actual edits.
-pub fn unresolved_assists(db: &RootDatabase, range: FileRange) -> Vec<AssistLabel> {
+pub fn unresolved_assists(
+    db: &RootDatabase,
+    config: &AssistConfig,
+    range: FileRange,
+) -> Vec<AssistLabel> {
     let sema = Semantics::new(db);
-    let ctx = AssistCtx::new(&sema, range, false);
+    let ctx = AssistCtx::new(&sema, config, range, false);
     handlers::all()
         .iter()
         .filter_map(|f| f(ctx.clone()))
@@ -91,13 +148,39 @@ pub fn unresolved_assists(db: &RootDatabase, range: FileRange) -> Vec<AssistLabe
         .collect()
 }
 
+/// Computes the edit of a single assist, identified by the `id` and `label`
+/// returned from `unresolved_assists`.
+///
+/// Only the handler offering the assist computes its edit, so this is much
+/// cheaper than `resolved_assists` when just one assist is applied.
+pub fn resolve_assist(
+    db: &RootDatabase,
+    config: &AssistConfig,
+    range: FileRange,
+    id: &str,
+    label: &str,
+) -> Option<ResolvedAssist> {
+    let sema = Semantics::new(db);
+    let is_requested = |it: &AssistLabel| it.id.0 == id && it.label == label;
+    let ctx = AssistCtx::new(&sema, config, range, false);
+    let handler = handlers::all().iter().find(|f| {
+        f(ctx.clone()).map_or(false, |it| it.0.iter().any(|info| is_requested(&info.label)))
+    })?;
+    let ctx = AssistCtx::new(&sema, config, range, true);
+    handler(ctx)?.0.into_iter().find(|info| is_requested(&info.label))?.into_resolved()
+}
+
 /// Return all the assists applicable at the given position.
 ///
 /// Assists are returned in the "resolved" state, that is with edit fully
 /// computed.
-pub fn resolved_assists(db: &RootDatabase, range: FileRange) -> Vec<ResolvedAssist> {
+pub fn resolved_assists(
+    db: &RootDatabase,
+    config: &AssistConfig,
+    range: FileRange,
+) -> Vec<ResolvedAssist> {
     let sema = Semantics::new(db);
-    let ctx = AssistCtx::new(&sema, range, true);
+    let ctx = AssistCtx::new(&sema, config, range, true);
     let mut a = handlers::all()
         .iter()
         .filter_map(|f| f(ctx.clone()))
@@ -108,23 +191,69 @@ pub fn resolved_assists(db: &RootDatabase, range: FileRange) -> Vec<ResolvedAssi
     a
 }
 
+/// An import which makes an unresolved path resolve.
+#[derive(Debug, Clone)]
+pub struct ImportFix {
+    /// The path the item is imported by.
+    pub import: ModPath,
+    /// Adds the `use` item.
+    pub edit: TextEdit,
+}
+
+/// Returns the imports which would make the unresolved `path` resolve, e.g.
+/// to offer them as fixes for an "unresolved name" diagnostic.
+pub fn import_fixes(sema: &Semantics<RootDatabase>, path: &ast::Path) -> Vec<ImportFix> {
+    handlers::find_imports_for_path(sema, path)
+        .into_iter()
+        .map(|import| {
+            let edit = insert_use(sema, path.syntax(), &import);
+            ImportFix { import, edit }
+        })
+        .collect()
+}
+
+/// Returns the edit adding a `use` item for `path_to_import`, in the scope
+/// most appropriate for `position`, merged with the existing imports.
+pub fn insert_use(
+    sema: &Semantics<RootDatabase>,
+    position: &SyntaxNode,
+    path_to_import: &ModPath,
+) -> TextEdit {
+    let mut builder = TextEditBuilder::default();
+    utils::insert_use_statement_with_builder(sema, position, path_to_import, &mut builder);
+    builder.finish()
+}
+
 mod handlers {
     use crate::AssistHandler;
 
+    pub(crate) use self::auto_import::find_imports_for_path;
+
+    mod add_accessors;
     mod add_custom_impl;
     mod add_derive;
+    mod add_doc_comment;
+    mod add_display_impl;
+    mod add_enum_accessors;
     mod add_explicit_type;
     mod add_function;
     mod add_impl;
     mod add_missing_impl_members;
     mod add_new;
+    mod add_test_fn;
     mod apply_demorgan;
     mod auto_import;
     mod change_visibility;
+    mod convert_for_loop;
+    mod convert_tuple_struct_to_named_struct;
+    mod destructure_binding;
     mod early_return;
+    mod extract_module_to_file;
     mod fill_match_arms;
+    mod flatten_imports;
     mod flip_binexpr;
     mod flip_comma;
+    mod flip_equality;
     mod flip_trait_bound;
     mod inline_local_variable;
     mod introduce_variable;
@@ -133,34 +262,52 @@ mod handlers {
     mod merge_match_arms;
     mod move_bounds;
     mod move_guard;
+    mod move_trait_member;
+    mod qualify_path;
     mod raw_string;
     mod remove_dbg;
     mod remove_mut;
     mod replace_if_let_with_match;
     mod replace_let_with_if_let;
+    mod replace_match_with_if_let;
     mod replace_qualified_name_with_use;
     mod replace_unwrap_with_match;
+    mod replace_unwrap_with_try;
     mod split_import;
     mod add_from_impl_for_enum;
     mod reorder_fields;
     mod unwrap_block;
+    mod wrap_return_type_in_result;
 
     pub(crate) fn all() -> &'static [AssistHandler] {
         &[
             // These are alphabetic for the foolish consistency
+            add_accessors::add_getter,
+            add_accessors::add_setter,
             add_custom_impl::add_custom_impl,
             add_derive::add_derive,
+            add_doc_comment::add_doc_comment,
+            add_display_impl::add_display_impl,
+            add_enum_accessors::add_enum_accessors,
             add_explicit_type::add_explicit_type,
             add_function::add_function,
             add_impl::add_impl,
             add_new::add_new,
+            add_test_fn::add_test_fn,
             apply_demorgan::apply_demorgan,
             auto_import::auto_import,
             change_visibility::change_visibility,
+            convert_for_loop::convert_for_each_to_for_loop,
+            convert_for_loop::convert_for_to_iter_chain,
+            convert_tuple_struct_to_named_struct::convert_tuple_struct_to_named_struct,
+            destructure_binding::destructure_binding,
             early_return::convert_to_guarded_return,
+            extract_module_to_file::extract_module_to_file,
             fill_match_arms::fill_match_arms,
+            flatten_imports::flatten_imports,
             flip_binexpr::flip_binexpr,
             flip_comma::flip_comma,
+            flip_equality::flip_equality,
             flip_trait_bound::flip_trait_bound,
             inline_local_variable::inline_local_variable,
             introduce_variable::introduce_variable,
@@ -170,6 +317,9 @@ mod handlers {
             move_bounds::move_bounds_to_where_clause,
             move_guard::move_arm_cond_to_match_guard,
             move_guard::move_guard_to_arm_body,
+            move_trait_member::pull_up_to_trait,
+            move_trait_member::push_down_to_impl,
+            qualify_path::qualify_path,
             raw_string::add_hash,
             raw_string::make_raw_string,
             raw_string::make_usual_string,
@@ -178,11 +328,15 @@ mod handlers {
             remove_mut::remove_mut,
             replace_if_let_with_match::replace_if_let_with_match,
             replace_let_with_if_let::replace_let_with_if_let,
+            replace_match_with_if_let::replace_match_with_if_let,
             replace_qualified_name_with_use::replace_qualified_name_with_use,
             replace_unwrap_with_match::replace_unwrap_with_match,
+            replace_unwrap_with_try::replace_unwrap_with_try,
+            replace_unwrap_with_try::replace_unwrap_with_try_returning_result,
             split_import::split_import,
             add_from_impl_for_enum::add_from_impl_for_enum,
             unwrap_block::unwrap_block,
+            wrap_return_type_in_result::wrap_return_type_in_result,
             // These are manually sorted for better priorities
             add_missing_impl_members::add_missing_impl_members,
             add_missing_impl_members::add_missing_default_members,
@@ -199,7 +353,7 @@ mod helpers {
     use ra_ide_db::{symbol_index::SymbolsDatabase, RootDatabase};
     use test_utils::{add_cursor, assert_eq_text, extract_range_or_offset, RangeOrOffset};
 
-    use crate::{AssistCtx, AssistFile, AssistHandler};
+    use crate::{AssistConfig, AssistCtx, AssistFile, AssistHandler};
     use hir::Semantics;
 
     pub(crate) fn with_single_file(text: &str) -> (RootDatabase, FileId) {
@@ -215,18 +369,40 @@ mod helpers {
         ra_fixture_before: &str,
         ra_fixture_after: &str,
     ) {
-        check(assist, ra_fixture_before, ExpectedResult::After(ra_fixture_after));
+        check(
+            assist,
+            &AssistConfig::default(),
+            ra_fixture_before,
+            ExpectedResult::After(ra_fixture_after),
+        );
+    }
+
+    pub(crate) fn check_assist_with_config(
+        assist: AssistHandler,
+        config: AssistConfig,
+        ra_fixture_before: &str,
+        ra_fixture_after: &str,
+    ) {
+        check(assist, &config, ra_fixture_before, ExpectedResult::After(ra_fixture_after));
     }
 
     // FIXME: instead of having a separate function here, maybe use
     // `extract_ranges` and mark the target as `<target> </target>` in the
     // fixuture?
     pub(crate) fn check_assist_target(assist: AssistHandler, ra_fixture: &str, target: &str) {
-        check(assist, ra_fixture, ExpectedResult::Target(target));
+        check(assist, &AssistConfig::default(), ra_fixture, ExpectedResult::Target(target));
     }
 
     pub(crate) fn check_assist_not_applicable(assist: AssistHandler, ra_fixture: &str) {
-        check(assist, ra_fixture, ExpectedResult::NotApplicable);
+        check(assist, &AssistConfig::default(), ra_fixture, ExpectedResult::NotApplicable);
+    }
+
+    pub(crate) fn check_assist_not_applicable_with_config(
+        assist: AssistHandler,
+        config: AssistConfig,
+        ra_fixture: &str,
+    ) {
+        check(assist, &config, ra_fixture, ExpectedResult::NotApplicable);
     }
 
     enum ExpectedResult<'a> {
@@ -235,7 +411,7 @@ mod helpers {
         Target(&'a str),
     }
 
-    fn check(assist: AssistHandler, before: &str, expected: ExpectedResult) {
+    fn check(assist: AssistHandler, config: &AssistConfig, before: &str, expected: ExpectedResult) {
         let (text_without_caret, file_with_caret_id, range_or_offset, db) =
             if before.contains("//-") {
                 let (mut db, position) = RootDatabase::with_position(before);
@@ -255,7 +431,7 @@ mod helpers {
         let frange = FileRange { file_id: file_with_caret_id, range: range_or_offset.into() };
 
         let sema = Semantics::new(&db);
-        let assist_ctx = AssistCtx::new(&sema, frange, true);
+        let assist_ctx = AssistCtx::new(&sema, config, frange, true);
 
         match (assist(assist_ctx), expected) {
             (Some(assist), ExpectedResult::After(after)) => {
@@ -303,7 +479,7 @@ mod tests {
     use ra_syntax::TextRange;
     use test_utils::{extract_offset, extract_range};
 
-    use crate::{helpers, resolved_assists};
+    use crate::{helpers, resolve_assist, resolved_assists, unresolved_assists, AssistConfig};
 
     #[test]
     fn assist_order_field_struct() {
@@ -311,7 +487,7 @@ mod tests {
         let (before_cursor_pos, before) = extract_offset(before);
         let (db, file_id) = helpers::with_single_file(&before);
         let frange = FileRange { file_id, range: TextRange::empty(before_cursor_pos) };
-        let assists = resolved_assists(&db, frange);
+        let assists = resolved_assists(&db, &AssistConfig::default(), frange);
         let mut assists = assists.iter();
 
         assert_eq!(
@@ -334,10 +510,37 @@ mod tests {
         let (range, before) = extract_range(before);
         let (db, file_id) = helpers::with_single_file(&before);
         let frange = FileRange { file_id, range };
-        let assists = resolved_assists(&db, frange);
+        let assists = resolved_assists(&db, &AssistConfig::default(), frange);
         let mut assists = assists.iter();
 
         assert_eq!(assists.next().expect("expected assist").label.label, "Extract into variable");
         assert_eq!(assists.next().expect("expected assist").label.label, "Replace with match");
     }
+
+    #[test]
+    fn resolve_assist_by_id_and_label() {
+        let before = "struct Foo { <|>bar: u32 }";
+        let (before_cursor_pos, before) = extract_offset(before);
+        let (db, file_id) = helpers::with_single_file(&before);
+        let frange = FileRange { file_id, range: TextRange::empty(before_cursor_pos) };
+        let config = AssistConfig::default();
+
+        let labels = unresolved_assists(&db, &config, frange);
+        assert!(!labels.is_empty());
+        for resolved in resolved_assists(&db, &config, frange) {
+            let label = &resolved.label;
+            let assist = resolve_assist(&db, &config, frange, label.id.0, &label.label)
+                .expect("expected assist");
+            assert_eq!(assist.label.id, label.id);
+            assert_eq!(assist.label.label, label.label);
+            assert_eq!(
+                assist.action.edit.apply(before.clone()),
+                resolved.action.edit.apply(before.clone())
+            );
+        }
+
+        let label = &labels[0];
+        assert!(resolve_assist(&db, &config, frange, label.id.0, "no such label").is_none());
+        assert!(resolve_assist(&db, &config, frange, "no_such_id", &label.label).is_none());
+    }
 }
@@ -1,17 +1,23 @@
 //! Assorted functions shared by several assists.
 pub(crate) mod insert_use;
+pub(crate) mod organize_imports;
 
 use std::iter;
 
-use hir::{Adt, Crate, Semantics, Trait, Type};
+use hir::{Adt, Crate, HasSource, ModuleDef, Semantics, Struct, Trait, Type};
 use ra_ide_db::RootDatabase;
 use ra_syntax::{
-    ast::{self, make, NameOwner},
-    AstNode, T,
+    ast::{self, edit::AstNodeEdit, make, NameOwner, TypeParamsOwner},
+    AstNode, SyntaxKind, TextSize, T,
 };
 use rustc_hash::FxHashSet;
+use stdx::{format_to, SepBy};
 
-pub(crate) use insert_use::insert_use_statement;
+use crate::{assist_ctx::ActionBuilder, ast_transform::path_to_ast};
+
+pub(crate) use insert_use::{insert_use_statement, insert_use_statement_with_builder};
+pub use insert_use::{InsertUseConfig, MergeBehaviour};
+pub use organize_imports::organize_imports;
 
 pub fn get_missing_assoc_items(
     sema: &Semantics<RootDatabase>,
@@ -65,6 +71,31 @@ pub fn get_missing_assoc_items(
     })
 }
 
+/// A pattern matching `var`, with its path as seen from `module`.
+pub fn variant_pat(
+    db: &RootDatabase,
+    module: hir::Module,
+    var: hir::EnumVariant,
+) -> Option<ast::Pat> {
+    let path = path_to_ast(module.find_use_path(db, ModuleDef::from(var))?);
+
+    // FIXME: use HIR for this; it doesn't currently expose struct vs. tuple vs. unit variants though
+    let pat: ast::Pat = match var.source(db).value.kind() {
+        ast::StructKind::Tuple(field_list) => {
+            let pats =
+                iter::repeat(make::placeholder_pat().into()).take(field_list.fields().count());
+            make::tuple_struct_pat(path, pats).into()
+        }
+        ast::StructKind::Record(field_list) => {
+            let pats = field_list.fields().filter_map(|f| Some(make::bind_pat(f.name()?).into()));
+            make::record_pat(path, pats).into()
+        }
+        ast::StructKind::Unit => make::path_pat(path),
+    };
+
+    Some(pat)
+}
+
 pub(crate) fn resolve_target_trait(
     sema: &Semantics<RootDatabase>,
     impl_def: &ast::ImplDef,
@@ -81,11 +112,188 @@ pub(crate) fn resolve_target_trait(
     }
 }
 
+// Generates the surrounding `impl Type { <code> }` including type and lifetime
+// parameters
+pub(crate) fn generate_impl_text(adt: &(impl NameOwner + TypeParamsOwner), code: &str) -> String {
+    generate_impl_text_inner(adt, None, code)
+}
+
+/// Like `generate_impl_text`, but generates an impl of `trait_text` for `adt`.
+pub(crate) fn generate_trait_impl_text(
+    adt: &ast::NominalDef,
+    trait_text: &str,
+    code: &str,
+) -> String {
+    generate_impl_text_inner(adt, Some(trait_text), code)
+}
+
+fn generate_impl_text_inner(
+    adt: &(impl NameOwner + TypeParamsOwner),
+    trait_text: Option<&str>,
+    code: &str,
+) -> String {
+    let type_params = adt.type_param_list();
+    let mut buf = String::with_capacity(code.len());
+    buf.push_str("\n\nimpl");
+    if let Some(type_params) = &type_params {
+        format_to!(buf, "{}", type_params.syntax());
+    }
+    buf.push_str(" ");
+    if let Some(trait_text) = trait_text {
+        format_to!(buf, "{} for ", trait_text);
+    }
+    buf.push_str(adt.name().unwrap().text().as_str());
+    if let Some(type_params) = type_params {
+        let lifetime_params = type_params
+            .lifetime_params()
+            .filter_map(|it| it.lifetime_token())
+            .map(|it| it.text().clone());
+        let type_params =
+            type_params.type_params().filter_map(|it| it.name()).map(|it| it.text().clone());
+        format_to!(buf, "<{}>", lifetime_params.chain(type_params).sep_by(", "))
+    }
+
+    format_to!(buf, " {{\n{}\n}}\n", code);
+
+    buf
+}
+
+// Uses a syntax-driven approach to find any impl blocks for the struct that
+// exist within the module/file
+//
+// Returns `None` if we've found an existing fn named `fn_name`
+//
+// FIXME: change the fn checking to a more semantic approach when that's more
+// viable (e.g. we process proc macros, etc)
+pub(crate) fn find_struct_impl(
+    sema: &Semantics<RootDatabase>,
+    strukt: &ast::StructDef,
+    fn_name: &str,
+) -> Option<Option<ast::ImplDef>> {
+    let block = find_adt_impl(sema, &strukt.clone().into())?;
+
+    if let Some(ref impl_blk) = block {
+        if has_fn(impl_blk, fn_name) {
+            return None;
+        }
+    }
+
+    Some(block)
+}
+
+// Like `find_struct_impl`, but for any ADT, and without looking for a fn.
+pub(crate) fn find_adt_impl(
+    sema: &Semantics<RootDatabase>,
+    adt: &ast::NominalDef,
+) -> Option<Option<ast::ImplDef>> {
+    let db = sema.db;
+    let module = adt.syntax().ancestors().find(|node| {
+        ast::Module::can_cast(node.kind()) || ast::SourceFile::can_cast(node.kind())
+    })?;
+
+    let adt_def = match adt {
+        ast::NominalDef::StructDef(it) => Adt::Struct(sema.to_def(it)?),
+        ast::NominalDef::EnumDef(it) => Adt::Enum(sema.to_def(it)?),
+        ast::NominalDef::UnionDef(it) => Adt::Union(sema.to_def(it)?),
+    };
+
+    let block = module.descendants().filter_map(ast::ImplDef::cast).find_map(|impl_blk| {
+        let blk = sema.to_def(&impl_blk)?;
+
+        // FIXME: handle e.g. `struct S<T>; impl<U> S<U> {}`, which is skipped
+        // as we'd use the wrong type parameter
+        let same_ty = match blk.target_ty(db).as_adt() {
+            Some(def) => def == adt_def,
+            None => false,
+        };
+        let not_trait_impl = blk.target_trait(db).is_none();
+
+        if !(same_ty && not_trait_impl && is_generic_over_adt_params(&impl_blk, adt)) {
+            None
+        } else {
+            Some(impl_blk)
+        }
+    });
+
+    Some(block)
+}
+
+// Checks that the impl block is for the type applied to its own parameters,
+// so that e.g. `impl Foo<u32>` isn't reused for `struct Foo<T>`
+fn is_generic_over_adt_params(impl_blk: &ast::ImplDef, adt: &ast::NominalDef) -> bool {
+    let params = match adt.type_param_list() {
+        Some(it) => it,
+        None => return true,
+    };
+    let expected = params
+        .lifetime_params()
+        .filter_map(|it| it.lifetime_token())
+        .map(|it| it.text().to_string())
+        .chain(params.type_params().filter_map(|it| it.name()).map(|it| it.text().to_string()))
+        .collect::<Vec<_>>();
+
+    let path = match impl_blk.target_type() {
+        Some(ast::TypeRef::PathType(it)) => it.path(),
+        _ => None,
+    };
+    let args = match path.and_then(|it| it.segment()).and_then(|it| it.type_arg_list()) {
+        Some(it) => it,
+        None => return expected.is_empty(),
+    };
+    let actual = args.syntax().children().map(|it| it.text().to_string()).collect::<Vec<_>>();
+    actual == expected
+}
+
+// Appends `code` to the existing impl block, or adds a new impl block after
+// the type
+pub(crate) fn insert_fn_in_impl(
+    edit: &mut ActionBuilder,
+    adt: &(impl AstNode + NameOwner + TypeParamsOwner),
+    impl_def: Option<ast::ImplDef>,
+    code: String,
+) {
+    let item_list = impl_def.and_then(|it| it.item_list());
+    match item_list {
+        Some(item_list) => match item_list.assoc_items().last() {
+            Some(last) => edit.insert(last.syntax().text_range().end(), format!("\n\n{}", code)),
+            None => {
+                let offset = match item_list.l_curly_token() {
+                    Some(it) => it.text_range().end(),
+                    None => item_list.syntax().text_range().start() + TextSize::of("{"),
+                };
+                edit.insert(offset, format!("\n{}\n", code))
+            }
+        },
+        None => edit.insert(adt.syntax().text_range().end(), generate_impl_text(adt, &code)),
+    }
+}
+
+pub(crate) fn has_fn(imp: &ast::ImplDef, fn_name: &str) -> bool {
+    if let Some(il) = imp.item_list() {
+        for item in il.assoc_items() {
+            if let ast::AssocItem::FnDef(f) = item {
+                if let Some(name) = f.name() {
+                    if name.text().eq_ignore_ascii_case(fn_name) {
+                        return true;
+                    }
+                }
+            }
+        }
+    }
+
+    false
+}
+
 pub(crate) fn invert_boolean_expression(expr: ast::Expr) -> ast::Expr {
     if let Some(expr) = invert_special_case(&expr) {
         return expr;
     }
-    make::expr_prefix(T![!], expr)
+    match expr {
+        ast::Expr::BinExpr(_) | ast::Expr::CastExpr(_) | ast::Expr::RangeExpr(_) => {
+            make::expr_prefix(T![!], make::expr_paren(expr))
+        }
+        _ => make::expr_prefix(T![!], expr),
+    }
 }
 
 fn invert_special_case(expr: &ast::Expr) -> Option<ast::Expr> {
@@ -93,15 +301,46 @@ fn invert_special_case(expr: &ast::Expr) -> Option<ast::Expr> {
         ast::Expr::BinExpr(bin) => match bin.op_kind()? {
             ast::BinOp::NegatedEqualityTest => bin.replace_op(T![==]).map(|it| it.into()),
             ast::BinOp::EqualityTest => bin.replace_op(T![!=]).map(|it| it.into()),
+            // De Morgan's laws. The operands are replaced in place, so that the
+            // comments around them survive.
+            ast::BinOp::BooleanAnd => invert_logic_op(bin, T![||]),
+            ast::BinOp::BooleanOr => invert_logic_op(bin, T![&&]),
             _ => None,
         },
         ast::Expr::PrefixExpr(pe) if pe.op_kind()? == ast::PrefixOp::Not => pe.expr(),
-        // FIXME:
-        // ast::Expr::Literal(true | false )
+        ast::Expr::ParenExpr(pe) => invert_special_case(&pe.expr()?).map(make::expr_paren),
+        ast::Expr::Literal(lit) => match lit.kind() {
+            ast::LiteralKind::Bool(value) => {
+                Some(make::expr_literal(if value { "false" } else { "true" }).into())
+            }
+            _ => None,
+        },
         _ => None,
     }
 }
 
+fn invert_logic_op(bin: &ast::BinExpr, new_op: SyntaxKind) -> Option<ast::Expr> {
+    let invert_operand = |operand: ast::Expr| {
+        let inverted = invert_boolean_expression(operand);
+        // `||` binds looser than `&&`
+        let needs_parens = match &inverted {
+            ast::Expr::BinExpr(it) => {
+                new_op == T![&&] && it.op_kind() == Some(ast::BinOp::BooleanOr)
+            }
+            _ => false,
+        };
+        if needs_parens {
+            make::expr_paren(inverted)
+        } else {
+            inverted
+        }
+    };
+    let lhs = bin.lhs()?;
+    let rhs = bin.rhs()?;
+    let replacements = vec![(lhs.clone(), invert_operand(lhs)), (rhs.clone(), invert_operand(rhs))];
+    bin.replace_descendants(replacements).replace_op(new_op).map(|it| it.into())
+}
+
 #[derive(Clone, Copy)]
 pub(crate) enum TryEnum {
     Result,
@@ -154,7 +393,7 @@ impl TryEnum {
 /// somewhat similar to the known paths infra inside hir, but it different; We
 /// want to make sure that IDE specific paths don't become interesting inside
 /// the compiler itself as well.
-pub(crate) struct FamousDefs<'a, 'b>(pub(crate) &'a Semantics<'b, RootDatabase>, pub(crate) Crate);
+pub struct FamousDefs<'a, 'b>(pub &'a Semantics<'b, RootDatabase>, pub Crate);
 
 #[allow(non_snake_case)]
 impl FamousDefs<'_, '_> {
@@ -167,19 +406,60 @@ pub mod convert{
     }
 }
 
-pub mod prelude { pub use crate::convert::From }
+pub mod iter {
+    pub trait Iterator {
+        type Item;
+    }
+}
+
+pub mod marker {
+    pub trait Copy {}
+    impl Copy for u32 {}
+}
+
+pub mod prelude { pub use crate::{convert::From, iter::Iterator, marker::Copy} }
 #[prelude_import]
 pub use prelude::*;
 "#;
 
-    pub(crate) fn core_convert_From(&self) -> Option<Trait> {
+    pub fn core_convert_From(&self) -> Option<Trait> {
         self.find_trait("core:convert:From")
     }
 
+    pub fn core_iter_Iterator(&self) -> Option<Trait> {
+        self.find_trait("core:iter:Iterator")
+    }
+
+    pub fn core_marker_Copy(&self) -> Option<Trait> {
+        self.find_trait("core:marker:Copy")
+    }
+
+    pub fn core_default_Default(&self) -> Option<Trait> {
+        self.find_trait("core:default:Default")
+    }
+
+    pub fn core_ops_Try(&self) -> Option<Trait> {
+        self.find_trait("core:ops:Try")
+    }
+
+    pub fn alloc_string_String(&self) -> Option<Struct> {
+        match self.find_def("alloc:string:String")? {
+            hir::ScopeDef::ModuleDef(hir::ModuleDef::Adt(Adt::Struct(it))) => Some(it),
+            _ => None,
+        }
+    }
+
     fn find_trait(&self, path: &str) -> Option<Trait> {
+        match self.find_def(path)? {
+            hir::ScopeDef::ModuleDef(hir::ModuleDef::Trait(it)) => Some(it),
+            _ => None,
+        }
+    }
+
+    fn find_def(&self, path: &str) -> Option<hir::ScopeDef> {
         let db = self.0.db;
         let mut path = path.split(':');
-        let trait_ = path.next_back()?;
+        let def_name = path.next_back()?;
         let std_crate = path.next()?;
         let std_crate = self
             .1
@@ -199,11 +479,10 @@ pub use prelude::*;
                 }
             })?;
         }
-        let def =
-            module.scope(db, None).into_iter().find(|(name, _def)| &name.to_string() == trait_)?.1;
-        match def {
-            hir::ScopeDef::ModuleDef(hir::ModuleDef::Trait(it)) => Some(it),
-            _ => None,
-        }
+        module
+            .scope(db, None)
+            .into_iter()
+            .find(|(name, _def)| &name.to_string() == def_name)
+            .map(|(_name, def)| def)
     }
 }
@@ -3,7 +3,8 @@
 // https://github.com/rust-analyzer/rust-analyzer/issues/3301#issuecomment-592931553
 
 use crate::assist_ctx::ActionBuilder;
-use hir::{self, ModPath};
+use hir::{self, ModPath, Semantics};
+use ra_ide_db::RootDatabase;
 use ra_syntax::{
     ast::{self, NameOwner},
     AstNode, Direction, SmolStr,
@@ -12,6 +13,32 @@ use ra_syntax::{
 };
 use ra_text_edit::TextEditBuilder;
 
+/// How `use` items sharing a prefix are merged together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeBehaviour {
+    /// Merge into a single nested tree, like `use std::{fmt::Debug, io};`.
+    Full,
+    /// Only merge imports from the same module, like
+    /// `use std::fmt::{Debug, Display};`.
+    Last,
+}
+
+/// Configures the shape and the order of `use` items.
+#[derive(Debug, Clone)]
+pub struct InsertUseConfig {
+    /// How to merge imports, `None` leaves them as they are.
+    pub merge: Option<MergeBehaviour>,
+    /// Whether to split imports into `std`, external and crate-local groups,
+    /// separated by blank lines.
+    pub group: bool,
+}
+
+impl Default for InsertUseConfig {
+    fn default() -> Self {
+        InsertUseConfig { merge: Some(MergeBehaviour::Full), group: true }
+    }
+}
+
 /// Creates and inserts a use statement for the given path to import.
 /// The use statement is inserted in the scope most appropriate to the
 /// the cursor position given, additionally merged with the existing use imports.
@@ -20,9 +47,21 @@ pub(crate) fn insert_use_statement(
     position: &SyntaxNode,
     path_to_import: &ModPath,
     edit: &mut ActionBuilder,
+) {
+    let sema = &edit.ctx().sema;
+    insert_use_statement_with_builder(sema, position, path_to_import, edit.text_edit_builder())
+}
+
+/// Like `insert_use_statement`, but records the edit into a plain
+/// `TextEditBuilder`, for use outside of assists.
+pub(crate) fn insert_use_statement_with_builder(
+    sema: &Semantics<RootDatabase>,
+    position: &SyntaxNode,
+    path_to_import: &ModPath,
+    edit: &mut TextEditBuilder,
 ) {
     let target = path_to_import.to_string().split("::").map(SmolStr::new).collect::<Vec<_>>();
-    let container = edit.ctx().sema.ancestors_with_macros(position.clone()).find_map(|n| {
+    let container = sema.ancestors_with_macros(position.clone()).find_map(|n| {
         if let Some(module) = ast::Module::cast(n.clone()) {
             return module.item_list().map(|it| it.syntax().clone());
         }
@@ -31,7 +70,7 @@ pub(crate) fn insert_use_statement(
 
     if let Some(container) = container {
         let action = best_action_for_target(container, position.clone(), &target);
-        make_assist(&action, &target, edit.text_edit_builder());
+        make_assist(&action, &target, edit);
     }
 }
 
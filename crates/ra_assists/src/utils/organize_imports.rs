@@ -0,0 +1,457 @@
+//! Rewrites the `use` items of a module into a canonical shape: merged
+//! according to `InsertUseConfig`, sorted and split into groups.
+
+use std::{collections::BTreeMap, fmt, iter::successors};
+
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AttrsOwner, VisibilityOwner},
+    AstNode, SmolStr,
+    SyntaxKind::{COMMENT, ERROR, ITEM_LIST, MODULE, SOURCE_FILE, WHITESPACE},
+    SyntaxNode, TextRange, T,
+};
+use ra_text_edit::{TextEdit, TextEditBuilder};
+use stdx::SepBy;
+
+use super::insert_use::{InsertUseConfig, MergeBehaviour};
+
+/// A single imported path, like `std::fmt::Debug` or `std::io::*`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FlatImport {
+    pub(crate) segments: Vec<SmolStr>,
+    /// The `as name` suffix, if any.
+    pub(crate) alias: Option<SmolStr>,
+}
+
+impl fmt::Display for FlatImport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.segments.iter().sep_by("::"))?;
+        if let Some(alias) = &self.alias {
+            write!(f, " {}", alias)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the paths imported by `tree`, or `None` if the tree is incomplete.
+pub(crate) fn flatten_use_tree(tree: &ast::UseTree) -> Option<Vec<FlatImport>> {
+    let mut res = Vec::new();
+    flatten_rec(&mut Vec::new(), tree, &mut res)?;
+    Some(res)
+}
+
+fn flatten_rec(
+    prefix: &mut Vec<SmolStr>,
+    tree: &ast::UseTree,
+    acc: &mut Vec<FlatImport>,
+) -> Option<()> {
+    let len = prefix.len();
+    if let Some(path) = tree.path() {
+        prefix.extend(path_segments(&path)?);
+    }
+    if let Some(list) = tree.use_tree_list() {
+        for tree in list.use_trees() {
+            flatten_rec(prefix, &tree, acc)?;
+        }
+    } else if tree.star_token().is_some() {
+        let mut segments = prefix.clone();
+        segments.push("*".into());
+        acc.push(FlatImport { segments, alias: None });
+    } else {
+        let mut segments = prefix.clone();
+        // `self` in `use std::io::{self}` stands for the module itself.
+        if segments.len() > 1 && segments.last().map(|it| it.as_str()) == Some("self") {
+            segments.pop();
+        }
+        if segments.is_empty() {
+            return None;
+        }
+        let alias = tree.alias().map(|it| SmolStr::new(it.syntax().text().to_string()));
+        acc.push(FlatImport { segments, alias });
+    }
+    prefix.truncate(len);
+    Some(())
+}
+
+fn path_segments(path: &ast::Path) -> Option<Vec<SmolStr>> {
+    let mut res = successors(Some(path.clone()), |it| it.qualifier())
+        .map(|it| it.segment().map(|segment| SmolStr::new(segment.syntax().text().to_string())))
+        .collect::<Option<Vec<_>>>()?;
+    res.reverse();
+    Some(res)
+}
+
+/// A trie of imported paths.
+#[derive(Debug, Default)]
+struct UseTreeNode {
+    /// The aliases the path of this node is imported with, `None` standing for
+    /// no alias.
+    imports: Vec<Option<SmolStr>>,
+    children: BTreeMap<SmolStr, UseTreeNode>,
+}
+
+impl UseTreeNode {
+    fn insert(&mut self, segments: &[SmolStr], alias: Option<SmolStr>) {
+        let node = segments
+            .iter()
+            .fold(self, |node, segment| node.children.entry(segment.clone()).or_default());
+        if !node.imports.contains(&alias) {
+            node.imports.push(alias);
+            node.imports.sort();
+        }
+    }
+
+    /// The items of the use tree list importing this node and its children.
+    fn items(&self) -> Vec<String> {
+        let mut res = self
+            .imports
+            .iter()
+            .map(|alias| match alias {
+                Some(alias) => format!("self {}", alias),
+                None => "self".to_string(),
+            })
+            .collect::<Vec<_>>();
+        res.extend(self.children.iter().map(|(name, child)| child.render(name)));
+        res
+    }
+
+    fn render(&self, name: &str) -> String {
+        let items = self.items();
+        match (self.imports.as_slice(), items.len()) {
+            ([Some(alias)], 1) => format!("{} {}", name, alias),
+            ([None], 1) => name.to_string(),
+            ([], 1) => format!("{}::{}", name, items[0]),
+            _ => format!("{}::{{{}}}", name, items.join(", ")),
+        }
+    }
+}
+
+/// Merges `imports` into as few use trees as `behaviour` allows, and returns
+/// the text of the trees.
+fn merge_imports(imports: &[FlatImport], behaviour: MergeBehaviour) -> Vec<String> {
+    match behaviour {
+        MergeBehaviour::Full => {
+            let mut root = UseTreeNode::default();
+            for import in imports {
+                root.insert(&import.segments, import.alias.clone());
+            }
+            root.items()
+        }
+        MergeBehaviour::Last => {
+            let mut modules: BTreeMap<&[SmolStr], UseTreeNode> = BTreeMap::new();
+            for import in imports {
+                if let Some((last, module)) = import.segments.split_last() {
+                    modules
+                        .entry(module)
+                        .or_default()
+                        .insert(std::slice::from_ref(last), import.alias.clone());
+                }
+            }
+            let mut res = Vec::new();
+            for (module, node) in modules {
+                let items = node.items();
+                if module.is_empty() {
+                    res.extend(items);
+                } else if items.len() == 1 {
+                    res.push(format!("{}::{}", module.iter().sep_by("::"), items[0]));
+                } else {
+                    res.push(format!("{}::{{{}}}", module.iter().sep_by("::"), items.join(", ")));
+                }
+            }
+            res
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum ImportGroup {
+    Std,
+    External,
+    Local,
+}
+
+impl ImportGroup {
+    fn new(config: &InsertUseConfig, first_segment: &str) -> ImportGroup {
+        if !config.group {
+            return ImportGroup::External;
+        }
+        match first_segment.trim_start_matches("::") {
+            "std" | "core" | "alloc" | "proc_macro" | "test" => ImportGroup::Std,
+            "crate" | "self" | "super" => ImportGroup::Local,
+            _ => ImportGroup::External,
+        }
+    }
+}
+
+/// Organizes the `use` items of every module in `file`. Returns `None` if
+/// there is nothing to change.
+///
+/// `use` items with attributes or comments are left as they are.
+pub fn organize_imports(file: &ast::SourceFile, config: &InsertUseConfig) -> Option<TextEdit> {
+    let mut builder = TextEditBuilder::default();
+    let mut changed = false;
+    let containers = file.syntax().descendants().filter(|it| match it.kind() {
+        SOURCE_FILE => true,
+        ITEM_LIST => it.parent().map_or(false, |it| it.kind() == MODULE),
+        _ => false,
+    });
+    for container in containers {
+        changed |= organize_items(&container, config, &mut builder);
+    }
+    if changed {
+        Some(builder.finish())
+    } else {
+        None
+    }
+}
+
+fn organize_items(
+    container: &SyntaxNode,
+    config: &InsertUseConfig,
+    builder: &mut TextEditBuilder,
+) -> bool {
+    let use_items = container
+        .children()
+        .filter_map(ast::UseItem::cast)
+        .filter_map(|item| {
+            let imports = flatten_use_tree(&item.use_tree()?)?;
+            let has_semicolon = item.syntax().last_token().map(|it| it.kind()) == Some(T![;]);
+            let is_plain = item.attrs().next().is_none()
+                && !item
+                    .syntax()
+                    .descendants_with_tokens()
+                    .any(|it| it.kind() == COMMENT || it.kind() == ERROR);
+            if has_semicolon && is_plain && !imports.is_empty() {
+                Some((item, imports))
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    let (first, last) = match (use_items.first(), use_items.last()) {
+        (Some((first, _)), Some((last, _))) => (first.clone(), last.clone()),
+        _ => return false,
+    };
+
+    // Trees are sorted by their text, and then by their visibility.
+    let mut groups: BTreeMap<ImportGroup, Vec<(String, String)>> = BTreeMap::new();
+    match config.merge {
+        None => {
+            for (item, imports) in &use_items {
+                let tree = match item.use_tree() {
+                    Some(it) => it.syntax().to_string(),
+                    None => continue,
+                };
+                let group = ImportGroup::new(config, &imports[0].segments[0]);
+                groups.entry(group).or_default().push((tree, vis(item)));
+            }
+        }
+        Some(behaviour) => {
+            let mut by_vis: BTreeMap<(ImportGroup, String), Vec<FlatImport>> = BTreeMap::new();
+            for (item, imports) in &use_items {
+                for import in imports {
+                    let group = ImportGroup::new(config, &import.segments[0]);
+                    by_vis.entry((group, vis(item))).or_default().push(import.clone());
+                }
+            }
+            for ((group, vis), imports) in by_vis {
+                let trees = merge_imports(&imports, behaviour);
+                groups
+                    .entry(group)
+                    .or_default()
+                    .extend(trees.into_iter().map(|it| (it, vis.clone())));
+            }
+        }
+    }
+
+    let indent = " ".repeat(IndentLevel::from_node(first.syntax()).0 as usize * 4);
+    let line_sep = format!("\n{}", indent);
+    let group_sep = format!("\n{}", line_sep);
+    let text = groups
+        .values_mut()
+        .map(|trees| {
+            trees.sort();
+            trees.dedup();
+            trees
+                .iter()
+                .map(|(tree, vis)| format!("{}use {};", vis, tree))
+                .sep_by(&line_sep)
+                .to_string()
+        })
+        .sep_by(&group_sep)
+        .to_string();
+
+    let range = first.syntax().text_range().cover(last.syntax().text_range());
+    let container_text = container.text().to_string();
+    let offset = container.text_range().start();
+    let old_text =
+        &container_text[usize::from(range.start() - offset)..usize::from(range.end() - offset)];
+    if old_text == text {
+        return false;
+    }
+
+    builder.replace(first.syntax().text_range(), text);
+    for (item, _) in &use_items[1..] {
+        let range = item.syntax().text_range();
+        let start = match item.syntax().prev_sibling_or_token() {
+            Some(ws) if ws.kind() == WHITESPACE => ws.text_range().start(),
+            _ => range.start(),
+        };
+        builder.delete(TextRange::new(start, range.end()));
+    }
+    true
+}
+
+fn vis(item: &ast::UseItem) -> String {
+    item.visibility().map(|it| format!("{} ", it.syntax())).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use ra_syntax::SourceFile;
+    use test_utils::assert_eq_text;
+
+    use super::*;
+
+    fn check(config: InsertUseConfig, before: &str, after: &str) {
+        let file = SourceFile::parse(before).tree();
+        let edit = organize_imports(&file, &config).expect("imports are organized already");
+        assert_eq_text!(after, &edit.apply(before));
+    }
+
+    fn check_full(before: &str, after: &str) {
+        check(InsertUseConfig::default(), before, after);
+    }
+
+    #[test]
+    fn merges_and_groups_imports() {
+        check_full(
+            r#"
+use crate::foo::Bar;
+use std::fmt::Debug;
+use serde::Serialize;
+use std::{io, fmt::Display};
+use super::baz;
+
+fn main() {}
+"#,
+            r#"
+use std::{fmt::{Debug, Display}, io};
+
+use serde::Serialize;
+
+use crate::foo::Bar;
+use super::baz;
+
+fn main() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn keeps_self_aliases_and_globs() {
+        check_full(
+            r#"
+use std::io::Write;
+use std::io;
+use std::fmt::*;
+use std::fmt::Result as FmtResult;
+"#,
+            r#"
+use std::{fmt::{*, Result as FmtResult}, io::{self, Write}};
+"#,
+        );
+    }
+
+    #[test]
+    fn merges_last_level_only() {
+        check(
+            InsertUseConfig { merge: Some(MergeBehaviour::Last), group: true },
+            r#"
+use std::{fmt::Debug, io};
+use std::fmt::Display;
+"#,
+            r#"
+use std::fmt::{Debug, Display};
+use std::io;
+"#,
+        );
+    }
+
+    #[test]
+    fn sorts_without_merging_or_grouping() {
+        check(
+            InsertUseConfig { merge: None, group: false },
+            r#"
+use std::io;
+use crate::foo::{Bar, Baz};
+use serde::Serialize;
+"#,
+            r#"
+use crate::foo::{Bar, Baz};
+use serde::Serialize;
+use std::io;
+"#,
+        );
+    }
+
+    #[test]
+    fn does_not_merge_different_visibilities() {
+        check_full(
+            r#"
+mod foo {
+    use crate::Bar;
+    pub use crate::Baz;
+    use crate::Foo;
+}
+"#,
+            r#"
+mod foo {
+    pub use crate::Baz;
+    use crate::{Bar, Foo};
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn moves_imports_between_items() {
+        check_full(
+            r#"
+use std::fmt;
+mod foo;
+use std::io;
+
+fn main() {}
+"#,
+            r#"
+use std::{fmt, io};
+mod foo;
+
+fn main() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn skips_imports_with_attributes() {
+        check_full(
+            r#"
+use std::io;
+#[cfg(test)]
+use std::fmt;
+use std::env;
+"#,
+            r#"
+use std::{env, io};
+#[cfg(test)]
+use std::fmt;
+"#,
+        );
+    }
+
+    #[test]
+    fn organized_imports_are_unchanged() {
+        let file = SourceFile::parse("use std::{fmt, io};\n\nuse crate::foo;\n").tree();
+        assert!(organize_imports(&file, &InsertUseConfig::default()).is_none());
+    }
+}
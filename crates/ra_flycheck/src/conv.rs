@@ -136,14 +136,17 @@ fn map_rust_child_diagnostic(
     }
 
     let mut edit_map: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+    let mut is_machine_applicable = true;
     for &span in &spans {
-        match (&span.suggestion_applicability, &span.suggested_replacement) {
-            (Some(Applicability::MachineApplicable), Some(suggested_replacement)) => {
-                let location = map_span_to_location(span, workspace_root);
-                let edit = TextEdit::new(location.range, suggested_replacement.clone());
-                edit_map.entry(location.uri).or_default().push(edit);
+        if let Some(suggested_replacement) = &span.suggested_replacement {
+            match span.suggestion_applicability {
+                Some(Applicability::MachineApplicable) => {}
+                Some(Applicability::MaybeIncorrect) => is_machine_applicable = false,
+                _ => continue,
             }
-            _ => {}
+            let location = map_span_to_location(span, workspace_root);
+            let edit = TextEdit::new(location.range, suggested_replacement.clone());
+            edit_map.entry(location.uri).or_default().push(edit);
         }
     }
 
@@ -154,7 +157,8 @@ fn map_rust_child_diagnostic(
             diagnostics: None,
             edit: Some(WorkspaceEdit::new(edit_map)),
             command: None,
-            is_preferred: None,
+            // Only suggestions rustc is sure about are offered as the default fix
+            is_preferred: if is_machine_applicable { Some(true) } else { None },
         })
     } else {
         MappedRustChildDiagnostic::Related(DiagnosticRelatedInformation {
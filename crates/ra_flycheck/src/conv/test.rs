@@ -145,6 +145,89 @@ fn snap_rustc_unused_variable() {
     insta::assert_debug_snapshot!(diag);
 }
 
+#[test]
+#[cfg(not(windows))]
+fn snap_rustc_unused_variable_maybe_incorrect() {
+    let diag = parse_diagnostic(
+        r##"{
+"message": "unused variable: `foo`",
+"code": {
+    "code": "unused_variables",
+    "explanation": null
+},
+"level": "warning",
+"spans": [
+    {
+        "file_name": "driver/subcommand/repl.rs",
+        "byte_start": 9228,
+        "byte_end": 9231,
+        "line_start": 291,
+        "line_end": 291,
+        "column_start": 9,
+        "column_end": 12,
+        "is_primary": true,
+        "text": [
+            {
+                "text": "    let foo = 42;",
+                "highlight_start": 9,
+                "highlight_end": 12
+            }
+        ],
+        "label": null,
+        "suggested_replacement": null,
+        "suggestion_applicability": null,
+        "expansion": null
+    }
+],
+"children": [
+    {
+        "message": "#[warn(unused_variables)] on by default",
+        "code": null,
+        "level": "note",
+        "spans": [],
+        "children": [],
+        "rendered": null
+    },
+    {
+        "message": "consider prefixing with an underscore",
+        "code": null,
+        "level": "help",
+        "spans": [
+            {
+                "file_name": "driver/subcommand/repl.rs",
+                "byte_start": 9228,
+                "byte_end": 9231,
+                "line_start": 291,
+                "line_end": 291,
+                "column_start": 9,
+                "column_end": 12,
+                "is_primary": true,
+                "text": [
+                    {
+                        "text": "    let foo = 42;",
+                        "highlight_start": 9,
+                        "highlight_end": 12
+                    }
+                ],
+                "label": null,
+                "suggested_replacement": "_foo",
+                "suggestion_applicability": "MaybeIncorrect",
+                "expansion": null
+            }
+        ],
+        "children": [],
+        "rendered": null
+    }
+],
+"rendered": "warning: unused variable: `foo`\n   --> driver/subcommand/repl.rs:291:9\n    |\n291 |     let foo = 42;\n    |         ^^^ help: consider prefixing with an underscore: `_foo`\n    |\n    = note: #[warn(unused_variables)] on by default\n\n"
+}"##,
+    );
+
+    let workspace_root = PathBuf::from("/test/");
+    let diag = map_rust_diagnostic_to_lsp(&diag, &workspace_root);
+    insta::assert_debug_snapshot!(diag);
+}
+
 #[test]
 #[cfg(not(windows))]
 fn snap_rustc_wrong_number_of_parameters() {
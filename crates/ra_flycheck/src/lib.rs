@@ -4,28 +4,39 @@
 mod conv;
 
 use std::{
-    env,
     io::{self, BufRead, BufReader},
     path::PathBuf,
     process::{Command, Stdio},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
-use cargo_metadata::Message;
-use crossbeam_channel::{never, select, unbounded, Receiver, RecvError, Sender};
+use cargo_metadata::{diagnostic::Diagnostic as RustDiagnostic, Message};
+use crossbeam_channel::{at, never, select, unbounded, Receiver, RecvError, Sender};
 use lsp_types::{
     CodeAction, CodeActionOrCommand, Diagnostic, Url, WorkDoneProgress, WorkDoneProgressBegin,
     WorkDoneProgressEnd, WorkDoneProgressReport,
 };
 
+use ra_project_model::cargo_binary;
+use rustc_hash::FxHashMap;
+
 use crate::conv::{map_rust_diagnostic_to_lsp, MappedRustDiagnostic};
 
 pub use crate::conv::url_from_path_with_drive_lowercasing;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum FlycheckConfig {
-    CargoCommand { command: String, all_targets: bool, extra_args: Vec<String> },
-    CustomCommand { command: String, args: Vec<String> },
+    CargoCommand {
+        command: String,
+        all_targets: bool,
+        extra_args: Vec<String>,
+        extra_env: FxHashMap<String, String>,
+    },
+    CustomCommand {
+        command: String,
+        args: Vec<String>,
+        extra_env: FxHashMap<String, String>,
+    },
 }
 
 /// Flycheck wraps the shared state and communication machinery used for
@@ -73,6 +84,10 @@ pub enum CheckCommand {
     Update,
 }
 
+/// Saves which follow each other within this interval, like when the client
+/// saves all open files at once, only trigger a single check.
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(100);
+
 struct FlycheckThread {
     config: FlycheckConfig,
     workspace_root: PathBuf,
@@ -103,6 +118,7 @@ impl FlycheckThread {
         self.clean_previous_results(task_send);
 
         loop {
+            let update_timer = self.update_timer();
             select! {
                 recv(&cmd_recv) -> cmd => match cmd {
                     Ok(cmd) => self.handle_command(cmd),
@@ -119,14 +135,13 @@ impl FlycheckThread {
                         self.message_recv = never();
                         self.check_process = None;
                     },
+                },
+                recv(update_timer) -> _ => {
+                    self.last_update_req = None;
+                    task_send.send(CheckTask::ClearDiagnostics).unwrap();
+                    self.restart_check_process();
                 }
             };
-
-            if self.should_recheck() {
-                self.last_update_req = None;
-                task_send.send(CheckTask::ClearDiagnostics).unwrap();
-                self.restart_check_process();
-            }
         }
     }
 
@@ -137,14 +152,19 @@ impl FlycheckThread {
             .unwrap();
     }
 
-    fn should_recheck(&mut self) -> bool {
-        if let Some(_last_update_req) = &self.last_update_req {
-            // We currently only request an update on save, as we need up to
-            // date source on disk for cargo check to do it's magic, so we
-            // don't really need to debounce the requests at this point.
-            return true;
+    /// Fires once no update has been requested for `UPDATE_DEBOUNCE`.
+    fn update_timer(&self) -> Receiver<Instant> {
+        match self.last_update_req {
+            Some(last_update_req) => at(last_update_req + UPDATE_DEBOUNCE),
+            None => never(),
+        }
+    }
+
+    fn progress_title(&self) -> String {
+        match &self.config {
+            FlycheckConfig::CargoCommand { command, .. } => format!("Running 'cargo {}'", command),
+            FlycheckConfig::CustomCommand { command, .. } => format!("Running '{}'", command),
         }
-        false
     }
 
     fn handle_command(&mut self, cmd: CheckCommand) {
@@ -158,7 +178,7 @@ impl FlycheckThread {
             CheckEvent::Begin => {
                 task_send
                     .send(CheckTask::Status(WorkDoneProgress::Begin(WorkDoneProgressBegin {
-                        title: "Running 'cargo check'".to_string(),
+                        title: self.progress_title(),
                         cancellable: Some(false),
                         message: None,
                         percentage: None,
@@ -185,23 +205,11 @@ impl FlycheckThread {
             }
 
             CheckEvent::Msg(Message::CompilerMessage(msg)) => {
-                let map_result = map_rust_diagnostic_to_lsp(&msg.message, &self.workspace_root);
-                if map_result.is_empty() {
-                    return;
-                }
+                self.add_diagnostic(&msg.message, task_send);
+            }
 
-                for MappedRustDiagnostic { location, diagnostic, fixes } in map_result {
-                    let fixes = fixes
-                        .into_iter()
-                        .map(|fix| {
-                            CodeAction { diagnostics: Some(vec![diagnostic.clone()]), ..fix }.into()
-                        })
-                        .collect();
-
-                    task_send
-                        .send(CheckTask::AddDiagnostic { url: location.uri, diagnostic, fixes })
-                        .unwrap();
-                }
+            CheckEvent::Diagnostic(diagnostic) => {
+                self.add_diagnostic(&diagnostic, task_send);
             }
 
             CheckEvent::Msg(Message::BuildScriptExecuted(_msg)) => {}
@@ -209,14 +217,29 @@ impl FlycheckThread {
         }
     }
 
+    fn add_diagnostic(&self, diagnostic: &RustDiagnostic, task_send: &Sender<CheckTask>) {
+        let map_result = map_rust_diagnostic_to_lsp(diagnostic, &self.workspace_root);
+        for MappedRustDiagnostic { location, diagnostic, fixes } in map_result {
+            let fixes = fixes
+                .into_iter()
+                .map(|fix| CodeAction { diagnostics: Some(vec![diagnostic.clone()]), ..fix }.into())
+                .collect();
+
+            task_send
+                .send(CheckTask::AddDiagnostic { url: location.uri, diagnostic, fixes })
+                .unwrap();
+        }
+    }
+
     fn restart_check_process(&mut self) {
         // First, clear and cancel the old thread
         self.message_recv = never();
         self.check_process = None;
 
         let mut cmd = match &self.config {
-            FlycheckConfig::CargoCommand { command, all_targets, extra_args } => {
-                let mut cmd = Command::new(cargo_binary());
+            FlycheckConfig::CargoCommand { command, all_targets, extra_args, extra_env } => {
+                let mut cmd = Command::new(cargo_binary(extra_env));
+                cmd.envs(extra_env);
                 cmd.arg(command);
                 cmd.args(&["--workspace", "--message-format=json", "--manifest-path"]);
                 cmd.arg(self.workspace_root.join("Cargo.toml"));
@@ -226,8 +249,9 @@ impl FlycheckThread {
                 cmd.args(extra_args);
                 cmd
             }
-            FlycheckConfig::CustomCommand { command, args } => {
+            FlycheckConfig::CustomCommand { command, args, extra_env } => {
                 let mut cmd = Command::new(command);
+                cmd.envs(extra_env);
                 cmd.args(args);
                 cmd
             }
@@ -241,17 +265,19 @@ impl FlycheckThread {
             // which will break out of the loop, and continue the shutdown
             let _ = message_send.send(CheckEvent::Begin);
 
-            let res = run_cargo(cmd, &mut |message| {
+            let res = run_cargo(cmd, &mut |event| {
                 // Skip certain kinds of messages to only spend time on what's useful
-                match &message {
-                    Message::CompilerArtifact(artifact) if artifact.fresh => return true,
-                    Message::BuildScriptExecuted(_) => return true,
-                    Message::Unknown => return true,
+                match &event {
+                    CheckEvent::Msg(Message::CompilerArtifact(artifact)) if artifact.fresh => {
+                        return true
+                    }
+                    CheckEvent::Msg(Message::BuildScriptExecuted(_)) => return true,
+                    CheckEvent::Msg(Message::Unknown) => return true,
                     _ => {}
                 }
 
                 // if the send channel was closed, we want to shutdown
-                message_send.send(CheckEvent::Msg(message)).is_ok()
+                message_send.send(event).is_ok()
             });
 
             if let Err(err) = res {
@@ -276,12 +302,15 @@ pub struct DiagnosticWithFixes {
 enum CheckEvent {
     Begin,
     Msg(cargo_metadata::Message),
+    /// A plain rustc diagnostic, emitted by custom check commands which don't
+    /// wrap their output into cargo messages.
+    Diagnostic(RustDiagnostic),
     End,
 }
 
 fn run_cargo(
     mut command: Command,
-    on_message: &mut dyn FnMut(cargo_metadata::Message) -> bool,
+    on_message: &mut dyn FnMut(CheckEvent) -> bool,
 ) -> io::Result<()> {
     let mut child =
         command.stdout(Stdio::piped()).stderr(Stdio::null()).stdin(Stdio::null()).spawn()?;
@@ -300,13 +329,15 @@ fn run_cargo(
     for line in stdout.lines() {
         let line = line?;
 
-        let message = serde_json::from_str::<cargo_metadata::Message>(&line);
-        let message = match message {
-            Ok(message) => message,
-            Err(err) => {
-                log::error!("Invalid json from cargo check, ignoring ({}): {:?} ", err, line);
-                continue;
-            }
+        let message = match serde_json::from_str::<cargo_metadata::Message>(&line) {
+            Ok(message) => CheckEvent::Msg(message),
+            Err(err) => match serde_json::from_str::<RustDiagnostic>(&line) {
+                Ok(diagnostic) => CheckEvent::Diagnostic(diagnostic),
+                Err(_) => {
+                    log::error!("Invalid json from cargo check, ignoring ({}): {:?} ", err, line);
+                    continue;
+                }
+            },
         };
 
         read_at_least_one_message = true;
@@ -334,7 +365,3 @@ fn run_cargo(
 
     Ok(())
 }
-
-fn cargo_binary() -> String {
-    env::var("CARGO").unwrap_or_else(|_| "cargo".to_string())
-}
@@ -8,7 +8,7 @@ use hir_def::{
     adt::VariantData,
     builtin_type::BuiltinType,
     docs::Documentation,
-    expr::{BindingAnnotation, Pat, PatId},
+    expr::{BindingAnnotation, ExprId, Pat, PatId},
     per_ns::PerNs,
     resolver::{HasResolver, Resolver},
     type_ref::{Mutability, TypeRef},
@@ -22,8 +22,14 @@ use hir_expand::{
     MacroDefId, MacroDefKind,
 };
 use hir_ty::{
-    autoderef, display::HirFormatter, expr::ExprValidator, method_resolution, ApplicationTy,
-    Canonical, InEnvironment, Substs, TraitEnvironment, Ty, TyDefId, TypeCtor,
+    autoderef,
+    closure::{self, CaptureKind, ClosureKind},
+    consteval::{ComputedExpr, ConstEvalError},
+    display::HirFormatter,
+    expr::ExprValidator,
+    layout::{layout_of_ty, Layout, LayoutError},
+    method_resolution, ApplicationTy, Canonical, InEnvironment, Substs, TraitEnvironment, Ty,
+    TyDefId, TypeCtor,
 };
 use ra_db::{CrateId, CrateName, Edition, FileId};
 use ra_prof::profile;
@@ -66,7 +72,6 @@ impl Crate {
             .collect()
     }
 
-    // FIXME: add `transitive_reverse_dependencies`.
     pub fn reverse_dependencies(self, db: &dyn HirDatabase) -> Vec<Crate> {
         let crate_graph = db.crate_graph();
         crate_graph
@@ -78,6 +83,21 @@ impl Crate {
             .collect()
     }
 
+    /// This crate and all the crates that depend on it, directly or not.
+    pub fn transitive_reverse_dependencies(self, db: &dyn HirDatabase) -> Vec<Crate> {
+        let mut res = vec![self];
+        let mut idx = 0;
+        while idx < res.len() {
+            for krate in res[idx].reverse_dependencies(db) {
+                if !res.contains(&krate) {
+                    res.push(krate);
+                }
+            }
+            idx += 1;
+        }
+        res
+    }
+
     pub fn root_module(self, db: &dyn HirDatabase) -> Option<Module> {
         let module_id = db.crate_def_map(self.id).root;
         Some(Module::new(self, module_id))
@@ -329,6 +349,24 @@ impl Field {
     pub fn parent_def(&self, _db: &dyn HirDatabase) -> VariantDef {
         self.parent
     }
+
+    /// The memory layout of the type of this field.
+    pub fn layout(&self, db: &dyn HirDatabase) -> Result<Layout, LayoutError> {
+        let ty = self.signature_ty(db);
+        layout_of_ty(db, &ty.ty.value, ty.krate)
+    }
+
+    /// The offset of this field in its parent, if it has a known layout.
+    pub fn offset(&self, db: &dyn HirDatabase) -> Option<u64> {
+        let adt = match self.parent {
+            VariantDef::Struct(it) => Adt::Struct(it),
+            VariantDef::Union(_) => return Some(0),
+            VariantDef::EnumVariant(_) => return None,
+        };
+        let layout = adt.layout(db).ok()?;
+        let idx = self.parent.variant_data(db).fields().iter().position(|(id, _)| id == self.id)?;
+        layout.field_offsets.get(idx).copied()
+    }
 }
 
 impl HasVisibility for Field {
@@ -371,6 +409,10 @@ impl Struct {
         Type::from_def(db, self.id.lookup(db.upcast()).container.module(db.upcast()).krate, self.id)
     }
 
+    pub fn kind(self, db: &dyn HirDatabase) -> StructKind {
+        self.variant_data(db).kind()
+    }
+
     fn variant_data(self, db: &dyn HirDatabase) -> Arc<VariantData> {
         db.struct_data(self.id).variant_data.clone()
     }
@@ -469,6 +511,11 @@ impl EnumVariant {
         self.variant_data(db).kind()
     }
 
+    /// The value of the discriminant of this variant, explicit or implicit.
+    pub fn discriminant(self, db: &dyn HirDatabase) -> Result<i128, ConstEvalError> {
+        db.enum_discriminant(self.into())
+    }
+
     pub(crate) fn variant_data(self, db: &dyn HirDatabase) -> Arc<VariantData> {
         db.enum_data(self.parent.id).variants[self.id].variant_data.clone()
     }
@@ -489,6 +536,11 @@ impl Adt {
         subst.iter().any(|ty| ty == &Ty::Unknown)
     }
 
+    /// The memory layout of this ADT, if it doesn't depend on type parameters.
+    pub fn layout(self, db: &dyn HirDatabase) -> Result<Arc<Layout>, LayoutError> {
+        db.adt_layout(self.into(), Substs::type_params(db, self))
+    }
+
     /// Turns this ADT into a type. Any type parameters of the ADT will be
     /// turned into unknown types, which is good for e.g. finding the most
     /// general set of completions, but will not look very nice when printed.
@@ -610,12 +662,25 @@ impl Function {
         db.function_data(self.id).has_self_param
     }
 
+    pub fn is_const(self, db: &dyn HirDatabase) -> bool {
+        db.function_data(self.id).is_const
+    }
+
     pub fn params(self, db: &dyn HirDatabase) -> Vec<TypeRef> {
         db.function_data(self.id).params.clone()
     }
 
+    pub fn ret_type(self, db: &dyn HirDatabase) -> Type {
+        let substs = Substs::type_params(db, self.id);
+        let sig = db.callable_item_signature(self.id.into()).subst(&substs);
+        let krate = self.id.lookup(db.upcast()).module(db.upcast()).krate;
+        Type::new(db, krate, self.id, sig.ret().clone())
+    }
+
     pub fn diagnostics(self, db: &dyn HirDatabase, sink: &mut DiagnosticSink) {
         let _p = profile("Function::diagnostics");
+        let (_, source_map) = db.body_with_source_map(self.id.into());
+        source_map.add_diagnostics(sink);
         let infer = db.infer(self.id.into());
         infer.add_diagnostics(db, self.id, sink);
         let mut validator = ExprValidator::new(self.id, infer, sink);
@@ -648,6 +713,10 @@ impl Const {
     pub fn name(self, db: &dyn HirDatabase) -> Option<Name> {
         db.const_data(self.id).name.clone()
     }
+
+    pub fn eval(self, db: &dyn HirDatabase) -> Result<ComputedExpr, ConstEvalError> {
+        db.const_eval(self.id)
+    }
 }
 
 impl HasVisibility for Const {
@@ -698,6 +767,17 @@ impl Trait {
     pub fn is_auto(self, db: &dyn HirDatabase) -> bool {
         db.trait_data(self.id).auto
     }
+
+    /// The trait with the lang item `name`, e.g. `Add` for `add`, as seen from
+    /// `krate`.
+    pub fn lang(db: &dyn HirDatabase, krate: Crate, name: &str) -> Option<Trait> {
+        db.lang_item(krate.id, name.into())?.as_trait().map(Trait::from)
+    }
+
+    /// The direct supertraits of the trait, e.g. `Eq` for `trait Ord: Eq`.
+    pub fn super_traits(self, db: &dyn HirDatabase) -> Vec<Trait> {
+        hir_ty::direct_super_traits(db.upcast(), self.id).into_iter().map(Trait::from).collect()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -757,18 +837,32 @@ impl MacroDef {
 
     /// XXX: this parses the file
     pub fn name(self, db: &dyn HirDatabase) -> Option<Name> {
-        self.source(db).value.name().map(|it| it.as_name())
+        let name = match self.source(db).value {
+            Either::Left(it) => it.name(),
+            Either::Right(it) => it.name(),
+        };
+        name.map(|it| it.as_name())
     }
 
     /// Indicate it is a proc-macro
     pub fn is_proc_macro(&self) -> bool {
-        matches!(self.id.kind, MacroDefKind::CustomDerive(_))
+        matches!(
+            self.id.kind,
+            MacroDefKind::CustomDerive(_)
+                | MacroDefKind::ProcMacroAttr(_)
+                | MacroDefKind::ProcMacro(_)
+        )
     }
 
     /// Indicate it is a derive macro
     pub fn is_derive_macro(&self) -> bool {
         matches!(self.id.kind, MacroDefKind::CustomDerive(_) | MacroDefKind::BuiltInDerive(_))
     }
+
+    /// Indicate it is an attribute proc-macro
+    pub fn is_attr_macro(&self) -> bool {
+        matches!(self.id.kind, MacroDefKind::ProcMacroAttr(_))
+    }
 }
 
 /// Invariant: `inner.as_assoc_item(db).is_some()`
@@ -876,6 +970,9 @@ impl GenericDef {
         generics
             .types
             .iter()
+            .filter(|(_, param)| {
+                param.provenance != hir_def::generics::TypeParamProvenance::ConstParam
+            })
             .map(|(local_id, _)| TypeParam { id: TypeParamId { parent: self.into(), local_id } })
             .collect()
     }
@@ -984,6 +1081,11 @@ impl ImplDef {
         db.impl_data(self.id).target_trait.clone()
     }
 
+    /// The trait this impl implements, if it is a trait impl.
+    pub fn trait_(&self, db: &dyn HirDatabase) -> Option<Trait> {
+        db.impl_trait(self.id).map(|it| it.value.trait_.into())
+    }
+
     pub fn target_type(&self, db: &dyn HirDatabase) -> TypeRef {
         db.impl_data(self.id).target_type.clone()
     }
@@ -1079,10 +1181,21 @@ impl Type {
         Type::new(db, krate, def, ty)
     }
 
+    pub fn is_unit(&self) -> bool {
+        matches!(
+            self.ty.value,
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::Tuple { cardinality: 0 }, .. })
+        )
+    }
+
     pub fn is_bool(&self) -> bool {
         matches!(self.ty.value, Ty::Apply(ApplicationTy { ctor: TypeCtor::Bool, .. }))
     }
 
+    pub fn is_integral(&self) -> bool {
+        matches!(self.ty.value, Ty::Apply(ApplicationTy { ctor: TypeCtor::Int(_), .. }))
+    }
+
     pub fn is_mutable_reference(&self) -> bool {
         matches!(
             self.ty.value,
@@ -1145,10 +1258,20 @@ impl Type {
         matches!(&self.ty.value, Ty::Apply(ApplicationTy { ctor: TypeCtor::Closure { .. }, .. }))
     }
 
+    pub fn as_closure(&self) -> Option<Closure> {
+        match &self.ty.value {
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::Closure { def, expr }, .. }) => {
+                Some(Closure { owner: *def, expr: *expr, ty: self.clone() })
+            }
+            _ => None,
+        }
+    }
+
     pub fn is_fn(&self) -> bool {
-        matches!(&self.ty.value,
-            Ty::Apply(ApplicationTy { ctor: TypeCtor::FnDef(..), .. }) |
-            Ty::Apply(ApplicationTy { ctor: TypeCtor::FnPtr { .. }, .. })
+        matches!(
+            &self.ty.value,
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::FnDef(..), .. })
+                | Ty::Apply(ApplicationTy { ctor: TypeCtor::FnPtr { .. }, .. })
         )
     }
 
@@ -1198,6 +1321,15 @@ impl Type {
         res
     }
 
+    /// Returns the generic arguments of the type, like `T` and `E` in
+    /// `Result<T, E>`.
+    pub fn type_parameters(&self) -> Vec<Type> {
+        match &self.ty.value {
+            Ty::Apply(a_ty) => a_ty.parameters.iter().map(|ty| self.derived(ty.clone())).collect(),
+            _ => Vec::new(),
+        }
+    }
+
     pub fn autoderef<'a>(&'a self, db: &'a dyn HirDatabase) -> impl Iterator<Item = Type> + 'a {
         // There should be no inference vars in types passed here
         // FIXME check that?
@@ -1324,6 +1456,52 @@ impl HirDisplay for Type {
     }
 }
 
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Closure {
+    owner: DefWithBodyId,
+    expr: ExprId,
+    ty: Type,
+}
+
+impl Closure {
+    pub fn kind(&self, db: &dyn HirDatabase) -> ClosureKind {
+        closure::closure_kind(db, self.owner, self.expr)
+    }
+
+    pub fn captures(&self, db: &dyn HirDatabase) -> Vec<ClosureCapture> {
+        closure::closure_captures(db, self.owner, self.expr)
+            .into_iter()
+            .map(|it| ClosureCapture {
+                local: Local { parent: self.owner, pat_id: it.local },
+                kind: it.kind,
+            })
+            .collect()
+    }
+
+    /// The signature of the closure, written as the closure trait it
+    /// implements, like `impl Fn(u32) -> String`.
+    pub fn display_signature(&self, db: &dyn HirDatabase) -> String {
+        let mut res = format!("impl {}(", self.kind(db));
+        let sig = match self.ty.ty.value.callable_sig(db) {
+            Some(it) => it,
+            None => return res + ")",
+        };
+        let params = sig.params().iter().map(|it| it.display(db).to_string()).collect::<Vec<_>>();
+        res.push_str(&params.join(", "));
+        res.push(')');
+        if !self.ty.derived(sig.ret().clone()).is_unit() {
+            res.push_str(&format!(" -> {}", sig.ret().display(db)));
+        }
+        res
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ClosureCapture {
+    pub local: Local,
+    pub kind: CaptureKind,
+}
+
 /// For IDE only
 pub enum ScopeDef {
     ModuleDef(ModuleDef),
@@ -103,11 +103,11 @@ impl HasSource for TypeAlias {
     }
 }
 impl HasSource for MacroDef {
-    type Ast = ast::MacroCall;
-    fn source(self, db: &dyn HirDatabase) -> InFile<ast::MacroCall> {
-        InFile {
-            file_id: self.id.ast_id.expect("MacroDef without ast_id").file_id,
-            value: self.id.ast_id.expect("MacroDef without ast_id").to_node(db.upcast()),
+    type Ast = Either<ast::MacroCall, ast::MacroDef>;
+    fn source(self, db: &dyn HirDatabase) -> InFile<Either<ast::MacroCall, ast::MacroDef>> {
+        match self.id.ast_id.expect("MacroDef without ast_id") {
+            Either::Left(it) => InFile::new(it.file_id, Either::Left(it.to_node(db.upcast()))),
+            Either::Right(it) => InFile::new(it.file_id, Either::Right(it.to_node(db.upcast()))),
         }
     }
 }
@@ -51,10 +51,10 @@ mod has_source;
 
 pub use crate::{
     code_model::{
-        Adt, AsAssocItem, AssocItem, AssocItemContainer, AttrDef, Const, Crate, CrateDependency,
-        DefWithBody, Docs, Enum, EnumVariant, Field, FieldSource, Function, GenericDef, HasAttrs,
-        HasVisibility, ImplDef, Local, MacroDef, Module, ModuleDef, ScopeDef, Static, Struct,
-        Trait, Type, TypeAlias, TypeParam, Union, VariantDef, Visibility,
+        Adt, AsAssocItem, AssocItem, AssocItemContainer, AttrDef, Closure, ClosureCapture, Const,
+        Crate, CrateDependency, DefWithBody, Docs, Enum, EnumVariant, Field, FieldSource, Function,
+        GenericDef, HasAttrs, HasVisibility, ImplDef, Local, MacroDef, Module, ModuleDef, ScopeDef,
+        Static, Struct, Trait, Type, TypeAlias, TypeParam, Union, VariantDef, Visibility,
     },
     has_source::HasSource,
     semantics::{original_range, PathResolution, Semantics, SemanticsScope},
@@ -65,7 +65,7 @@ pub use hir_def::{
     body::scope::ExprScopes,
     builtin_type::BuiltinType,
     docs::Documentation,
-    nameres::ModuleSource,
+    nameres::{ModuleSource, RawItems},
     path::{ModPath, Path, PathKind},
     type_ref::Mutability,
 };
@@ -73,4 +73,11 @@ pub use hir_expand::{
     hygiene::Hygiene, name::Name, HirFileId, InFile, MacroCallId, MacroCallLoc, MacroDefId,
     MacroFile, Origin,
 };
-pub use hir_ty::{display::HirDisplay, CallableDef};
+pub use hir_ty::{
+    closure::{CaptureKind, ClosureKind},
+    consteval::{ComputedExpr, ConstEvalError},
+    display::HirDisplay,
+    layout::{Layout, LayoutError},
+    traits::TraitSolverLimits,
+    CallableDef,
+};
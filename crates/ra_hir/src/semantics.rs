@@ -6,9 +6,13 @@ use std::{cell::RefCell, fmt, iter::successors};
 
 use hir_def::{
     resolver::{self, HasResolver, Resolver},
+    type_ref::Mutability,
     AsMacroCall, TraitId,
 };
-use hir_expand::{hygiene::Hygiene, ExpansionInfo};
+use hir_expand::{
+    hygiene::{Hygiene, SyntaxContext},
+    ExpansionInfo,
+};
 use hir_ty::associated_type_shorthand_candidates;
 use itertools::Itertools;
 use ra_db::{FileId, FileRange};
@@ -24,8 +28,8 @@ use crate::{
     diagnostics::Diagnostic,
     semantics::source_to_def::{ChildContainer, SourceToDefCache, SourceToDefCtx},
     source_analyzer::{resolve_hir_path, SourceAnalyzer},
-    AssocItem, Field, Function, HirFileId, ImplDef, InFile, Local, MacroDef, Module, ModuleDef,
-    Name, Origin, Path, ScopeDef, Trait, Type, TypeAlias, TypeParam,
+    AssocItem, ComputedExpr, Field, Function, HirFileId, ImplDef, InFile, Local, MacroDef, Module,
+    ModuleDef, Name, Origin, Path, ScopeDef, Trait, Type, TypeAlias, TypeParam,
 };
 use resolver::TypeNs;
 
@@ -216,6 +220,11 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
         self.analyze(expr.syntax()).type_of(self.db, &expr)
     }
 
+    /// The value of `expr`, if it can be evaluated at compile time.
+    pub fn eval_expr(&self, expr: &ast::Expr) -> Option<ComputedExpr> {
+        self.analyze(expr.syntax()).eval_expr(self.db, &expr)
+    }
+
     pub fn type_of_pat(&self, pat: &ast::Pat) -> Option<Type> {
         self.analyze(pat.syntax()).type_of_pat(self.db, &pat)
     }
@@ -224,6 +233,12 @@ impl<'db, DB: HirDatabase> Semantics<'db, DB> {
         self.analyze(call.syntax()).resolve_method_call(self.db, call)
     }
 
+    /// The reference the receiver of `call` is implicitly borrowed as, if any,
+    /// like the `&mut` of `v.push(1)`.
+    pub fn method_receiver_autoref(&self, call: &ast::MethodCallExpr) -> Option<Mutability> {
+        self.analyze(call.syntax()).method_receiver_autoref(self.db, call)
+    }
+
     pub fn resolve_field(&self, field: &ast::FieldExpr) -> Option<Field> {
         self.analyze(field.syntax()).resolve_field(self.db, field)
     }
@@ -400,6 +415,7 @@ to_def_impls![
     (crate::EnumVariant, ast::EnumVariant, enum_variant_to_def),
     (crate::TypeParam, ast::TypeParam, type_param_to_def),
     (crate::MacroDef, ast::MacroCall, macro_call_to_def), // this one is dubious, not all calls are macros
+    (crate::MacroDef, ast::MacroDef, macro_def_to_def),
     (crate::Local, ast::BindPat, bind_pat_to_def),
 ];
 
@@ -449,7 +465,7 @@ impl<'a, DB: HirDatabase> SemanticsScope<'a, DB> {
     }
 
     pub fn resolve_hir_path(&self, path: &Path) -> Option<PathResolution> {
-        resolve_hir_path(self.db, &self.resolver, path)
+        resolve_hir_path(self.db, &self.resolver, path, SyntaxContext::ROOT)
     }
 }
 
@@ -1,5 +1,6 @@
 //! Maps *syntax* of various definitions to their semantic ids.
 
+use either::Either;
 use hir_def::{
     child_by_source::ChildBySource,
     dyn_map::DynMap,
@@ -150,7 +151,16 @@ impl SourceToDefCtx<'_, '_> {
         let file_id = src.file_id.original_file(self.db.upcast());
         let krate = self.file_to_def(file_id)?.krate;
         let file_ast_id = self.db.ast_id_map(src.file_id).ast_id(&src.value);
-        let ast_id = Some(AstId::new(src.file_id, file_ast_id));
+        let ast_id = Some(Either::Left(AstId::new(src.file_id, file_ast_id)));
+        Some(MacroDefId { krate: Some(krate), ast_id, kind, local_inner: false })
+    }
+
+    pub(super) fn macro_def_to_def(&mut self, src: InFile<ast::MacroDef>) -> Option<MacroDefId> {
+        let kind = MacroDefKind::Declarative;
+        let file_id = src.file_id.original_file(self.db.upcast());
+        let krate = self.file_to_def(file_id)?.krate;
+        let file_ast_id = self.db.ast_id_map(src.file_id).ast_id(&src.value);
+        let ast_id = Some(Either::Right(AstId::new(src.file_id, file_ast_id)));
         Some(MacroDefId { krate: Some(krate), ast_id, kind, local_inner: false })
     }
 
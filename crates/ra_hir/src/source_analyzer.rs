@@ -13,11 +13,18 @@ use hir_def::{
         Body, BodySourceMap,
     },
     expr::{ExprId, Pat, PatId},
+    generics::TypeParamProvenance,
     resolver::{resolver_for_scope, Resolver, TypeNs, ValueNs},
+    type_ref::Mutability,
     AsMacroCall, DefWithBodyId, FieldId, LocalFieldId, VariantId,
 };
-use hir_expand::{hygiene::Hygiene, name::AsName, HirFileId, InFile};
+use hir_expand::{
+    hygiene::{Hygiene, SyntaxContext},
+    name::AsName,
+    HirFileId, InFile,
+};
 use hir_ty::{
+    consteval::{self, ComputedExpr},
     expr::{record_literal_missing_fields, record_pattern_missing_fields},
     InferenceResult, Substs, Ty,
 };
@@ -115,6 +122,12 @@ impl SourceAnalyzer {
         Some(res)
     }
 
+    pub(crate) fn eval_expr(&self, db: &dyn HirDatabase, expr: &ast::Expr) -> Option<ComputedExpr> {
+        let expr_id = self.expr_id(db, expr)?;
+        let owner = self.resolver.body_owner()?;
+        consteval::eval_expr(db, owner, expr_id).ok()
+    }
+
     pub(crate) fn type_of(&self, db: &dyn HirDatabase, expr: &ast::Expr) -> Option<Type> {
         let expr_id = self.expr_id(db, expr)?;
         let ty = self.infer.as_ref()?[expr_id].clone();
@@ -136,6 +149,15 @@ impl SourceAnalyzer {
         self.infer.as_ref()?.method_resolution(expr_id).map(Function::from)
     }
 
+    pub(crate) fn method_receiver_autoref(
+        &self,
+        db: &dyn HirDatabase,
+        call: &ast::MethodCallExpr,
+    ) -> Option<Mutability> {
+        let expr_id = self.expr_id(db, &call.clone().into())?;
+        self.infer.as_ref()?.receiver_adjustments(expr_id)?.autoref
+    }
+
     pub(crate) fn resolve_field(
         &self,
         db: &dyn HirDatabase,
@@ -157,7 +179,8 @@ impl SourceAnalyzer {
         } else {
             let local_name = field.field_name()?.as_name();
             let path = ModPath::from_segments(PathKind::Plain, once(local_name));
-            match self.resolver.resolve_path_in_value_ns_fully(db.upcast(), &path) {
+            let hygiene = self.body.as_ref()?.expr_hygiene(expr_id);
+            match self.resolver.resolve_path_in_value_ns_fully(db.upcast(), &path, hygiene) {
                 Some(ValueNs::LocalBinding(pat_id)) => {
                     Some(Local { pat_id, parent: self.resolver.body_owner()? })
                 }
@@ -199,7 +222,7 @@ impl SourceAnalyzer {
             Pat::Path(path) => path,
             _ => return None,
         };
-        let res = resolve_hir_path(db, &self.resolver, &path)?;
+        let res = resolve_hir_path(db, &self.resolver, &path, SyntaxContext::ROOT)?;
         match res {
             PathResolution::Def(def) => Some(def),
             _ => None,
@@ -226,7 +249,11 @@ impl SourceAnalyzer {
         // This must be a normal source file rather than macro file.
         let hir_path =
             crate::Path::from_src(path.clone(), &Hygiene::new(db.upcast(), self.file_id))?;
-        resolve_hir_path(db, &self.resolver, &hir_path)
+        let hygiene = match path.syntax().first_token() {
+            Some(token) => SyntaxContext::of_token(db.upcast(), InFile::new(self.file_id, token)),
+            None => SyntaxContext::ROOT,
+        };
+        resolve_hir_path(db, &self.resolver, &hir_path, hygiene)
     }
 
     pub(crate) fn record_literal_missing_fields(
@@ -391,9 +418,19 @@ pub(crate) fn resolve_hir_path(
     db: &dyn HirDatabase,
     resolver: &Resolver,
     path: &crate::Path,
+    hygiene: SyntaxContext,
 ) -> Option<PathResolution> {
-    let types =
-        resolver.resolve_path_in_type_ns_fully(db.upcast(), path.mod_path()).map(|ty| match ty {
+    let types = resolver
+        .resolve_path_in_type_ns_fully(db.upcast(), path.mod_path())
+        // FIXME: expose const generic parameters in the IDE layer
+        .filter(|ty| match ty {
+            TypeNs::GenericParam(id) => {
+                db.generic_params(id.parent).types[id.local_id].provenance
+                    != TypeParamProvenance::ConstParam
+            }
+            _ => true,
+        })
+        .map(|ty| match ty {
             TypeNs::SelfType(it) => PathResolution::SelfType(it.into()),
             TypeNs::GenericParam(id) => PathResolution::TypeParam(TypeParam { id }),
             TypeNs::AdtSelfType(it) | TypeNs::AdtId(it) => {
@@ -405,8 +442,9 @@ pub(crate) fn resolve_hir_path(
             TypeNs::TraitId(it) => PathResolution::Def(Trait::from(it).into()),
         });
     let body_owner = resolver.body_owner();
-    let values =
-        resolver.resolve_path_in_value_ns_fully(db.upcast(), path.mod_path()).and_then(|val| {
+    let values = resolver
+        .resolve_path_in_value_ns_fully(db.upcast(), path.mod_path(), hygiene)
+        .and_then(|val| {
             let res = match val {
                 ValueNs::LocalBinding(pat_id) => {
                     let var = Local { parent: body_owner?.into(), pat_id };
@@ -417,6 +455,7 @@ pub(crate) fn resolve_hir_path(
                 ValueNs::StaticId(it) => PathResolution::Def(Static::from(it).into()),
                 ValueNs::StructId(it) => PathResolution::Def(Struct::from(it).into()),
                 ValueNs::EnumVariantId(it) => PathResolution::Def(EnumVariant::from(it).into()),
+                ValueNs::GenericParam(_) => return None,
             };
             Some(res)
         });
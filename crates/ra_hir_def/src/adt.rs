@@ -17,7 +17,7 @@ use crate::{
     src::HasChildSource,
     src::HasSource,
     trace::Trace,
-    type_ref::TypeRef,
+    type_ref::{ConstRef, TypeRef},
     visibility::RawVisibility,
     EnumId, HasModule, LocalEnumVariantId, LocalFieldId, Lookup, ModuleId, StructId, UnionId,
     VariantId,
@@ -40,6 +40,8 @@ pub struct EnumData {
 pub struct EnumVariantData {
     pub name: Name,
     pub variant_data: Arc<VariantData>,
+    /// The explicit discriminant, if any.
+    pub discriminant: Option<ConstRef>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -118,6 +120,7 @@ fn lower_enum(
     module_id: ModuleId,
 ) {
     let expander = CfgExpander::new(db, ast.file_id, module_id.krate);
+    let ctx = LowerCtx::new(db, ast.file_id);
     let variants = ast
         .value
         .variant_list()
@@ -130,6 +133,7 @@ fn lower_enum(
             || EnumVariantData {
                 name: var.name().map_or_else(Name::missing, |it| it.as_name()),
                 variant_data: Arc::new(VariantData::new(db, ast.with_value(var.kind()), module_id)),
+                discriminant: var.expr().map(|it| ConstRef::from_ast(&ctx, it)),
             },
         );
     }
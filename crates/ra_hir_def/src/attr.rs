@@ -70,9 +70,11 @@ impl Attrs {
                 AdtId::UnionId(it) => attrs_from_loc(it.lookup(db), db),
             },
             AttrDefId::TraitId(it) => attrs_from_loc(it.lookup(db), db),
-            AttrDefId::MacroDefId(it) => {
-                it.ast_id.map_or_else(Default::default, |ast_id| attrs_from_ast(ast_id, db))
-            }
+            AttrDefId::MacroDefId(it) => match it.ast_id {
+                Some(Either::Left(ast_id)) => attrs_from_ast(ast_id, db),
+                Some(Either::Right(ast_id)) => attrs_from_ast(ast_id, db),
+                None => Attrs::default(),
+            },
             AttrDefId::ImplId(it) => attrs_from_loc(it.lookup(db), db),
             AttrDefId::ConstId(it) => attrs_from_loc(it.lookup(db), db),
             AttrDefId::StaticId(it) => attrs_from_loc(it.lookup(db), db),
@@ -97,6 +99,11 @@ impl Attrs {
         Attrs { entries }
     }
 
+    pub(crate) fn from_entries(entries: Vec<Attr>) -> Attrs {
+        let entries = if entries.is_empty() { None } else { Some(entries.into()) };
+        Attrs { entries }
+    }
+
     pub fn by_key(&self, key: &'static str) -> AttrQuery<'_> {
         AttrQuery { attrs: self, key }
     }
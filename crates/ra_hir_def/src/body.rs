@@ -1,5 +1,6 @@
 //! Defines `Body`: a lowered representation of bodies of functions, statics and
 //! consts.
+mod diagnostics;
 mod lower;
 pub mod scope;
 
@@ -7,16 +8,22 @@ use std::{mem, ops::Index, sync::Arc};
 
 use drop_bomb::DropBomb;
 use either::Either;
-use hir_expand::{ast_id_map::AstIdMap, hygiene::Hygiene, AstId, HirFileId, InFile, MacroDefId};
+use hir_expand::{
+    ast_id_map::AstIdMap,
+    diagnostics::DiagnosticSink,
+    hygiene::{Hygiene, SyntaxContext},
+    AstId, HirFileId, InFile, MacroDefId,
+};
 use ra_arena::{map::ArenaMap, Arena};
 use ra_cfg::CfgOptions;
 use ra_db::CrateId;
 use ra_prof::profile;
-use ra_syntax::{ast, AstNode, AstPtr};
+use ra_syntax::{ast, AstNode, AstPtr, SyntaxToken};
 use rustc_hash::FxHashMap;
 
 pub(crate) use lower::LowerCtx;
 
+use self::diagnostics::BodyDiagnostic;
 use crate::{
     attr::Attrs,
     db::DefDatabase,
@@ -90,44 +97,62 @@ impl Expander {
         db: &dyn DefDatabase,
         local_scope: Option<&ItemScope>,
         macro_call: ast::MacroCall,
-    ) -> Option<(Mark, T)> {
+    ) -> Result<Option<(Mark, T)>, UnresolvedMacro> {
         if self.recursive_limit > 1024 {
-            return None;
+            return Ok(None);
         }
 
         let macro_call = InFile::new(self.current_file_id, &macro_call);
 
-        if let Some(call_id) = macro_call.as_call_id(db, |path| {
+        let resolve = |path: ModPath| {
             if let Some(local_scope) = local_scope {
-                if let Some(def) = path.as_ident().and_then(|n| local_scope.get_legacy_macro(n)) {
-                    return Some(def);
+                if let Some(name) = path.as_ident() {
+                    let def = local_scope
+                        .get_legacy_macro(name)
+                        .or_else(|| local_scope.get(name).take_macros());
+                    if let Some(def) = def {
+                        return Some(def);
+                    }
                 }
             }
             self.resolve_path_as_macro(db, &path)
-        }) {
-            let file_id = call_id.as_file();
-            if let Some(node) = db.parse_or_expand(file_id) {
-                if let Some(expr) = T::cast(node) {
-                    log::debug!("macro expansion {:#?}", expr.syntax());
-
-                    let mark = Mark {
-                        file_id: self.current_file_id,
-                        ast_id_map: mem::take(&mut self.ast_id_map),
-                        bomb: DropBomb::new("expansion mark dropped"),
-                    };
-                    self.cfg_expander.hygiene = Hygiene::new(db.upcast(), file_id);
-                    self.current_file_id = file_id;
-                    self.ast_id_map = db.ast_id_map(file_id);
-                    self.recursive_limit += 1;
-
-                    return Some((mark, expr));
-                }
+        };
+        let call_id = match macro_call.as_call_id(db, &resolve) {
+            Some(it) => it,
+            None => {
+                // Only a path that doesn't resolve is an error, a call whose
+                // eager expansion failed is not.
+                let unresolved = macro_call
+                    .value
+                    .path()
+                    .and_then(|path| ModPath::from_src(path, &self.cfg_expander.hygiene))
+                    .map_or(false, |path| resolve(path).is_none());
+                return if unresolved { Err(UnresolvedMacro) } else { Ok(None) };
+            }
+        };
+
+        let file_id = call_id.as_file();
+        if let Some(node) = db.parse_or_expand(file_id) {
+            if let Some(expr) = T::cast(node) {
+                log::debug!("macro expansion {:#?}", expr.syntax());
+
+                let mark = Mark {
+                    file_id: self.current_file_id,
+                    ast_id_map: mem::take(&mut self.ast_id_map),
+                    bomb: DropBomb::new("expansion mark dropped"),
+                };
+                self.cfg_expander.hygiene = Hygiene::new(db.upcast(), file_id);
+                self.current_file_id = file_id;
+                self.ast_id_map = db.ast_id_map(file_id);
+                self.recursive_limit += 1;
+
+                return Ok(Some((mark, expr)));
             }
         }
 
         // FIXME: Instead of just dropping the error from expansion
         // report it
-        None
+        Ok(None)
     }
 
     pub(crate) fn exit(&mut self, db: &dyn DefDatabase, mut mark: Mark) {
@@ -150,6 +175,15 @@ impl Expander {
         Path::from_src(path, &self.cfg_expander.hygiene)
     }
 
+    fn syntax_context(&self, db: &dyn DefDatabase, token: Option<SyntaxToken>) -> SyntaxContext {
+        match token {
+            Some(token) if self.current_file_id.is_macro_file() => {
+                SyntaxContext::of_token(db.upcast(), InFile::new(self.current_file_id, token))
+            }
+            _ => SyntaxContext::ROOT,
+        }
+    }
+
     fn resolve_path_as_macro(&self, db: &dyn DefDatabase, path: &ModPath) -> Option<MacroDefId> {
         self.crate_def_map
             .resolve_path(db, self.module.local_id, path, BuiltinShadowMode::Other)
@@ -163,6 +197,10 @@ impl Expander {
     }
 }
 
+/// The path of a macro call didn't resolve to a macro.
+#[derive(Debug)]
+pub(crate) struct UnresolvedMacro;
+
 pub(crate) struct Mark {
     file_id: HirFileId,
     ast_id_map: Arc<AstIdMap>,
@@ -184,6 +222,11 @@ pub struct Body {
     /// The `ExprId` of the actual body expression.
     pub body_expr: ExprId,
     pub item_scope: ItemScope,
+    /// Syntax contexts of bindings and single-segment path expressions that
+    /// were introduced by a macro expansion. Everything else is in the root
+    /// context.
+    expr_hygiene: FxHashMap<ExprId, SyntaxContext>,
+    pat_hygiene: FxHashMap<PatId, SyntaxContext>,
 }
 
 pub type ExprPtr = AstPtr<ast::Expr>;
@@ -211,6 +254,9 @@ pub struct BodySourceMap {
     pat_map_back: ArenaMap<PatId, Result<PatSource, SyntheticSyntax>>,
     field_map: FxHashMap<(ExprId, usize), InFile<AstPtr<ast::RecordField>>>,
     expansions: FxHashMap<InFile<AstPtr<ast::MacroCall>>, HirFileId>,
+    /// Whether some macro calls in the body couldn't be expanded.
+    has_unexpanded_macros: bool,
+    diagnostics: Vec<BodyDiagnostic>,
 }
 
 #[derive(Default, Debug, Eq, PartialEq, Clone, Copy)]
@@ -251,6 +297,17 @@ impl Body {
         db.body_with_source_map(def).0
     }
 
+    /// The syntax context of the path in a path expression, which determines
+    /// which local bindings it can refer to.
+    pub fn expr_hygiene(&self, expr: ExprId) -> SyntaxContext {
+        self.expr_hygiene.get(&expr).copied().unwrap_or(SyntaxContext::ROOT)
+    }
+
+    /// The syntax context of the name of a binding pattern.
+    pub fn pat_hygiene(&self, pat: PatId) -> SyntaxContext {
+        self.pat_hygiene.get(&pat).copied().unwrap_or(SyntaxContext::ROOT)
+    }
+
     fn new(
         db: &dyn DefDatabase,
         def: DefWithBodyId,
@@ -305,4 +362,14 @@ impl BodySourceMap {
     pub fn field_syntax(&self, expr: ExprId, field: usize) -> InFile<AstPtr<ast::RecordField>> {
         self.field_map[&(expr, field)].clone()
     }
+
+    /// Whether some macro calls in the body couldn't be expanded, so that it
+    /// may be missing code, like the bindings those macros would define.
+    pub fn has_unexpanded_macros(&self) -> bool {
+        self.has_unexpanded_macros
+    }
+
+    pub fn add_diagnostics(&self, sink: &mut DiagnosticSink) {
+        self.diagnostics.iter().for_each(|it| it.add_to(sink))
+    }
 }
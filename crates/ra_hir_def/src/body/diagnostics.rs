@@ -0,0 +1,18 @@
+//! Diagnostics emitted while lowering bodies.
+
+use hir_expand::diagnostics::DiagnosticSink;
+
+use crate::diagnostics::UnresolvedMacroCall;
+
+#[derive(Debug, Eq, PartialEq)]
+pub(super) enum BodyDiagnostic {
+    UnresolvedMacroCall(UnresolvedMacroCall),
+}
+
+impl BodyDiagnostic {
+    pub(super) fn add_to(&self, sink: &mut DiagnosticSink) {
+        match self {
+            BodyDiagnostic::UnresolvedMacroCall(diagnostic) => sink.push(diagnostic.clone()),
+        }
+    }
+}
@@ -3,15 +3,15 @@
 
 use either::Either;
 use hir_expand::{
-    hygiene::Hygiene,
+    hygiene::{Hygiene, SyntaxContext},
     name::{name, AsName, Name},
     HirFileId, MacroDefId, MacroDefKind,
 };
 use ra_arena::Arena;
 use ra_syntax::{
     ast::{
-        self, ArgListOwner, ArrayExprKind, LiteralKind, LoopBodyOwner, ModuleItemOwner, NameOwner,
-        SlicePatComponents, TypeAscriptionOwner,
+        self, ArgListOwner, ArrayExprKind, AstToken, HasStringValue, LiteralKind, LoopBodyOwner,
+        ModuleItemOwner, NameOwner, SlicePatComponents, TypeAscriptionOwner,
     },
     AstNode, AstPtr,
 };
@@ -19,9 +19,13 @@ use test_utils::tested_by;
 
 use crate::{
     adt::StructKind,
-    body::{Body, BodySourceMap, Expander, PatPtr, SyntheticSyntax},
+    body::{
+        diagnostics::BodyDiagnostic, Body, BodySourceMap, Expander, PatPtr, SyntheticSyntax,
+        UnresolvedMacro,
+    },
     builtin_type::{BuiltinFloat, BuiltinInt},
     db::DefDatabase,
+    diagnostics::UnresolvedMacroCall,
     expr::{
         dummy_expr_id, ArithOp, Array, BinaryOp, BindingAnnotation, CmpOp, Expr, ExprId, Literal,
         LogicOp, MatchArm, Ordering, Pat, PatId, RecordFieldPat, RecordLitField, Statement,
@@ -74,6 +78,8 @@ pub(super) fn lower(
             params: Vec::new(),
             body_expr: dummy_expr_id(),
             item_scope: Default::default(),
+            expr_hygiene: Default::default(),
+            pat_hygiene: Default::default(),
         },
     }
     .collect(params, body)
@@ -207,8 +213,12 @@ impl ExprCollector<'_> {
                     let body = self.collect_block_opt(e.block_expr());
                     self.alloc_expr(Expr::TryBlock { body }, syntax_ptr)
                 }
+                ast::Effect::Async(_) => {
+                    let body = self.collect_block_opt(e.block_expr());
+                    self.alloc_expr(Expr::Async { body }, syntax_ptr)
+                }
                 // FIXME: we need to record these effects somewhere...
-                ast::Effect::Async(_) | ast::Effect::Label(_) | ast::Effect::Unsafe(_) => {
+                ast::Effect::Label(_) | ast::Effect::Unsafe(_) | ast::Effect::Const(_) => {
                     self.collect_block_opt(e.block_expr())
                 }
             },
@@ -294,12 +304,21 @@ impl ExprCollector<'_> {
                 self.alloc_expr(Expr::Match { expr, arms }, syntax_ptr)
             }
             ast::Expr::PathExpr(e) => {
-                let path = e
-                    .path()
+                let ast_path = e.path();
+                let path = ast_path
+                    .clone()
                     .and_then(|path| self.expander.parse_path(path))
                     .map(Expr::Path)
                     .unwrap_or(Expr::Missing);
-                self.alloc_expr(path, syntax_ptr)
+                let ident = ast_path
+                    .filter(|it| it.qualifier().is_none())
+                    .and_then(|it| it.segment()?.name_ref()?.syntax().first_token());
+                let ctx = self.expander.syntax_context(self.db, ident);
+                let id = self.alloc_expr(path, syntax_ptr);
+                if ctx != SyntaxContext::ROOT {
+                    self.body.expr_hygiene.insert(id, ctx);
+                }
+                id
             }
             ast::Expr::ContinueExpr(_e) => {
                 // FIXME: labels
@@ -406,7 +425,18 @@ impl ExprCollector<'_> {
                     .and_then(|r| r.type_ref())
                     .map(|it| TypeRef::from_ast(&self.ctx, it));
                 let body = self.collect_expr_opt(e.body());
-                self.alloc_expr(Expr::Lambda { args, arg_types, ret_type, body }, syntax_ptr)
+                let (ret_type, body) = if e.async_token().is_some() {
+                    // `async |x| body` is sugar for `|x| async move { body }`
+                    // FIXME: the return type annotation is the output of the future
+                    (None, self.alloc_expr_desugared(Expr::Async { body }))
+                } else {
+                    (ret_type, body)
+                };
+                let is_move = e.move_token().is_some();
+                self.alloc_expr(
+                    Expr::Lambda { args, arg_types, ret_type, body, is_move },
+                    syntax_ptr,
+                )
             }
             ast::Expr::BinExpr(e) => {
                 let lhs = self.collect_expr_opt(e.lhs());
@@ -442,7 +472,7 @@ impl ExprCollector<'_> {
                 }
             }
 
-            ast::Expr::Literal(e) => self.alloc_expr(Expr::Literal(e.kind().into()), syntax_ptr),
+            ast::Expr::Literal(e) => self.alloc_expr(Expr::Literal(e.into()), syntax_ptr),
             ast::Expr::IndexExpr(e) => {
                 let base = self.collect_expr_opt(e.base());
                 let index = self.collect_expr_opt(e.index());
@@ -462,7 +492,7 @@ impl ExprCollector<'_> {
                 if let Some(name) = e.is_macro_rules().map(|it| it.as_name()) {
                     let mac = MacroDefId {
                         krate: Some(self.expander.module.krate),
-                        ast_id: Some(self.expander.ast_id(&e)),
+                        ast_id: Some(Either::Left(self.expander.ast_id(&e))),
                         kind: MacroDefKind::Declarative,
                         local_inner: false,
                     };
@@ -472,8 +502,9 @@ impl ExprCollector<'_> {
                     self.alloc_expr(Expr::Missing, syntax_ptr)
                 } else {
                     let macro_call = self.expander.to_source(AstPtr::new(&e));
+                    let path = e.path();
                     match self.expander.enter_expand(self.db, Some(&self.body.item_scope), e) {
-                        Some((mark, expansion)) => {
+                        Ok(Some((mark, expansion))) => {
                             self.source_map
                                 .expansions
                                 .insert(macro_call, self.expander.current_file_id);
@@ -481,7 +512,23 @@ impl ExprCollector<'_> {
                             self.expander.exit(self.db, mark);
                             id
                         }
-                        None => self.alloc_expr(Expr::Missing, syntax_ptr),
+                        Ok(None) => {
+                            self.source_map.has_unexpanded_macros = true;
+                            self.alloc_expr(Expr::Missing, syntax_ptr)
+                        }
+                        Err(UnresolvedMacro) => {
+                            self.source_map.has_unexpanded_macros = true;
+                            if let Some(path) = path {
+                                self.source_map.diagnostics.push(
+                                    BodyDiagnostic::UnresolvedMacroCall(UnresolvedMacroCall {
+                                        file: macro_call.file_id,
+                                        path: AstPtr::new(&path),
+                                        name: path.syntax().text().to_string(),
+                                    }),
+                                );
+                            }
+                            self.alloc_expr(Expr::Missing, syntax_ptr)
+                        }
                     }
                 }
             }
@@ -500,6 +547,7 @@ impl ExprCollector<'_> {
     }
 
     fn collect_block(&mut self, block: ast::BlockExpr) -> ExprId {
+        self.db.check_canceled();
         let syntax_node_ptr = AstPtr::new(&block.clone().into());
         self.collect_block_items(&block);
         let statements = block
@@ -563,6 +611,21 @@ impl ExprCollector<'_> {
                     let ast_id = self.expander.ast_id(&def);
                     (TraitLoc { container, ast_id }.intern(self.db).into(), def.name())
                 }
+                ast::ModuleItem::MacroDef(def) => {
+                    if let Some(name) = def.name() {
+                        let mac = MacroDefId {
+                            krate: Some(self.expander.module.krate),
+                            ast_id: Some(Either::Right(self.expander.ast_id(&def))),
+                            kind: MacroDefKind::Declarative,
+                            local_inner: false,
+                        };
+                        let vis = crate::visibility::Visibility::Public;
+                        self.body
+                            .item_scope
+                            .push_res(name.as_name(), crate::per_ns::PerNs::macros(mac, vis));
+                    }
+                    continue;
+                }
                 ast::ModuleItem::ExternBlock(_) => continue, // FIXME: collect from extern blocks
                 ast::ModuleItem::ImplDef(_)
                 | ast::ModuleItem::UseItem(_)
@@ -596,9 +659,13 @@ impl ExprCollector<'_> {
     }
 
     fn collect_pat(&mut self, pat: ast::Pat) -> PatId {
+        let mut ctx = SyntaxContext::ROOT;
         let pattern = match &pat {
             ast::Pat::BindPat(bp) => {
                 let name = bp.name().map(|nr| nr.as_name()).unwrap_or_else(Name::missing);
+                ctx = self
+                    .expander
+                    .syntax_context(self.db, bp.name().and_then(|it| it.syntax().first_token()));
                 let annotation =
                     BindingAnnotation::new(bp.mut_token().is_some(), bp.ref_token().is_some());
                 let subpat = bp.pat().map(|subpat| self.collect_pat(subpat));
@@ -694,14 +761,27 @@ impl ExprCollector<'_> {
                     suffix: suffix.into_iter().map(|p| self.collect_pat(p)).collect(),
                 }
             }
-            ast::Pat::LiteralPat(lit) => {
-                if let Some(ast_lit) = lit.literal() {
-                    let expr = Expr::Literal(ast_lit.kind().into());
-                    let expr_ptr = AstPtr::new(&ast::Expr::Literal(ast_lit));
-                    let expr_id = self.alloc_expr(expr, expr_ptr);
-                    Pat::Lit(expr_id)
-                } else {
-                    Pat::Missing
+            ast::Pat::LiteralPat(lit) => match self.collect_literal_pat(lit) {
+                Some(expr_id) => Pat::Lit(expr_id),
+                None => Pat::Missing,
+            },
+            ast::Pat::RangePat(p) => {
+                let range_end = |this: &mut Self, end: Option<ast::Pat>| match end? {
+                    ast::Pat::LiteralPat(lit) => this.collect_literal_pat(&lit),
+                    ast::Pat::PathPat(path_pat) => {
+                        let path = path_pat.path().and_then(|it| this.expander.parse_path(it))?;
+                        Some(this.alloc_expr_desugared(Expr::Path(path)))
+                    }
+                    _ => None,
+                };
+                let start = range_end(self, p.start());
+                let end = range_end(self, p.end());
+                match (start, end, p.op_kind()) {
+                    (Some(start), Some(end), Some(range_type)) => {
+                        Pat::Range { start, end, range_type }
+                    }
+                    // FIXME: half-open range patterns
+                    _ => Pat::Missing,
                 }
             }
             ast::Pat::DotDotPat(_) => {
@@ -714,10 +794,14 @@ impl ExprCollector<'_> {
                 Pat::Missing
             }
             // FIXME: implement
-            ast::Pat::BoxPat(_) | ast::Pat::RangePat(_) | ast::Pat::MacroPat(_) => Pat::Missing,
+            ast::Pat::BoxPat(_) | ast::Pat::MacroPat(_) => Pat::Missing,
         };
         let ptr = AstPtr::new(&pat);
-        self.alloc_pat(pattern, Either::Left(ptr))
+        let id = self.alloc_pat(pattern, Either::Left(ptr));
+        if ctx != SyntaxContext::ROOT {
+            self.body.pat_hygiene.insert(id, ctx);
+        }
+        id
     }
 
     fn collect_pat_opt(&mut self, pat: Option<ast::Pat>) -> PatId {
@@ -728,6 +812,18 @@ impl ExprCollector<'_> {
         }
     }
 
+    fn collect_literal_pat(&mut self, lit: &ast::LiteralPat) -> Option<ExprId> {
+        let ast_lit = lit.literal()?;
+        let expr = Expr::Literal(ast_lit.clone().into());
+        let expr_ptr = AstPtr::new(&ast::Expr::Literal(ast_lit));
+        let expr_id = self.alloc_expr(expr, expr_ptr);
+        if lit.is_negated() {
+            Some(self.alloc_expr_desugared(Expr::UnaryOp { expr: expr_id, op: UnaryOp::Neg }))
+        } else {
+            Some(expr_id)
+        }
+    }
+
     fn collect_tuple_pat(&mut self, args: AstChildren<ast::Pat>) -> (Vec<PatId>, Option<usize>) {
         // Find the location of the `..`, if there is one. Note that we do not
         // consider the possiblity of there being multiple `..` here.
@@ -786,13 +882,13 @@ impl From<ast::BinOp> for BinaryOp {
     }
 }
 
-impl From<ast::LiteralKind> for Literal {
-    fn from(ast_lit_kind: ast::LiteralKind) -> Self {
-        match ast_lit_kind {
+impl From<ast::Literal> for Literal {
+    fn from(ast_lit: ast::Literal) -> Self {
+        match ast_lit.kind() {
             LiteralKind::IntNumber { suffix } => {
                 let known_name = suffix.and_then(|it| BuiltinInt::from_suffix(&it));
 
-                Literal::Int(Default::default(), known_name)
+                Literal::Int(ast_lit.int_value().unwrap_or_default(), known_name)
             }
             LiteralKind::FloatNumber { suffix } => {
                 let known_name = suffix.and_then(|it| BuiltinFloat::from_suffix(&it));
@@ -800,10 +896,19 @@ impl From<ast::LiteralKind> for Literal {
                 Literal::Float(Default::default(), known_name)
             }
             LiteralKind::ByteString => Literal::ByteString(Default::default()),
-            LiteralKind::String => Literal::String(Default::default()),
-            LiteralKind::Byte => Literal::Int(Default::default(), Some(BuiltinInt::U8)),
+            LiteralKind::String => {
+                let token = ast_lit.token();
+                let value = match ast::String::cast(token.clone()) {
+                    Some(it) => it.value(),
+                    None => ast::RawString::cast(token).and_then(|it| it.value()),
+                };
+                Literal::String(value.unwrap_or_default())
+            }
+            LiteralKind::Byte => {
+                Literal::Int(ast_lit.int_value().unwrap_or_default(), Some(BuiltinInt::U8))
+            }
             LiteralKind::Bool(val) => Literal::Bool(val),
-            LiteralKind::Char => Literal::Char(Default::default()),
+            LiteralKind::Char => Literal::Char(ast_lit.char_value().unwrap_or_default()),
         }
     }
 }
@@ -1,7 +1,7 @@
 //! Name resolution for expressions.
 use std::sync::Arc;
 
-use hir_expand::name::Name;
+use hir_expand::{hygiene::SyntaxContext, name::Name};
 use ra_arena::{Arena, Idx};
 use rustc_hash::FxHashMap;
 
@@ -24,6 +24,7 @@ pub struct ExprScopes {
 pub struct ScopeEntry {
     name: Name,
     pat: PatId,
+    hygiene: SyntaxContext,
 }
 
 impl ScopeEntry {
@@ -34,6 +35,10 @@ impl ScopeEntry {
     pub fn pat(&self) -> PatId {
         self.pat
     }
+
+    pub fn hygiene(&self) -> SyntaxContext {
+        self.hygiene
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -91,7 +96,7 @@ impl ExprScopes {
             Pat::Bind { name, .. } => {
                 // bind can have a sub pattern, but it's actually not allowed
                 // to bind to things in there
-                let entry = ScopeEntry { name: name.clone(), pat };
+                let entry = ScopeEntry { name: name.clone(), pat, hygiene: body.pat_hygiene(pat) };
                 self.scopes[scope].entries.push(entry)
             }
             p => p.walk_child_pats(|pat| self.add_bindings(body, scope, pat)),
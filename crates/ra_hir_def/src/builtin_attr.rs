@@ -0,0 +1,73 @@
+//! Builtin attributes.
+//!
+//! These are attributes the compiler handles itself. An item annotated with
+//! anything else might be an attribute macro invocation, which we have to try
+//! to resolve and expand before we know what the item defines.
+
+use crate::path::{ModPath, PathKind};
+
+/// Attributes that are always handled by the compiler.
+const INERT_ATTRIBUTES: &[&str] = &[
+    "allow",
+    "automatically_derived",
+    "bench",
+    "cfg",
+    "cfg_attr",
+    "cold",
+    "deny",
+    "deprecated",
+    "derive",
+    "doc",
+    "export_name",
+    "forbid",
+    "fundamental",
+    "global_allocator",
+    "ignore",
+    "inline",
+    "lang",
+    "link",
+    "link_name",
+    "link_section",
+    "macro_export",
+    "macro_use",
+    "marker",
+    "must_use",
+    "no_mangle",
+    "non_exhaustive",
+    "panic_handler",
+    "path",
+    "prelude_import",
+    "proc_macro",
+    "proc_macro_attribute",
+    "proc_macro_derive",
+    "repr",
+    "should_panic",
+    "stable",
+    "target_feature",
+    "test",
+    "test_case",
+    "track_caller",
+    "unstable",
+    "used",
+    "warn",
+];
+
+/// Tools whose attributes (like `#[rustfmt::skip]`) are inert as well.
+const TOOL_MODULES: &[&str] = &["rustfmt", "clippy"];
+
+/// Whether an attribute with the given path is handled by the compiler, and
+/// thus can't be an attribute macro.
+pub(crate) fn is_builtin_attr(path: &ModPath) -> bool {
+    if path.kind != PathKind::Plain {
+        return false;
+    }
+    let first = match path.segments.first() {
+        Some(it) => it.to_string(),
+        None => return true,
+    };
+    if path.segments.len() == 1 {
+        INERT_ATTRIBUTES.contains(&first.as_str()) || first.starts_with("rustc_")
+    } else {
+        TOOL_MODULES.contains(&first.as_str())
+    }
+}
@@ -34,6 +34,10 @@ pub struct FunctionData {
     /// True if the first param is `self`. This is relevant to decide whether this
     /// can be called as a method.
     pub has_self_param: bool,
+    /// True for `async fn`s; `ret_type` is then already desugared to
+    /// `impl Future<Output = ...>`.
+    pub is_async: bool,
+    pub is_const: bool,
     pub visibility: RawVisibility,
 }
 
@@ -77,7 +81,9 @@ impl FunctionData {
             TypeRef::unit()
         };
 
-        let ret_type = if src.value.async_token().is_some() {
+        let is_async = src.value.async_token().is_some();
+        let is_const = src.value.const_token().is_some();
+        let ret_type = if is_async {
             let future_impl = desugar_future_path(ret_type);
             let ty_bound = TypeBound::Path(future_impl);
             TypeRef::ImplTrait(vec![ty_bound])
@@ -89,7 +95,16 @@ impl FunctionData {
         let visibility =
             RawVisibility::from_ast_with_default(db, vis_default, src.map(|s| s.visibility()));
 
-        let sig = FunctionData { name, params, ret_type, has_self_param, visibility, attrs };
+        let sig = FunctionData {
+            name,
+            params,
+            ret_type,
+            has_self_param,
+            is_async,
+            is_const,
+            visibility,
+            attrs,
+        };
         Arc::new(sig)
     }
 }
@@ -299,7 +314,7 @@ fn collect_items_in_macro(
         return Vec::new();
     }
 
-    if let Some((mark, items)) = expander.enter_expand(db, None, m) {
+    if let Ok(Some((mark, items))) = expander.enter_expand(db, None, m) {
         let items: InFile<ast::MacroItems> = expander.to_source(items);
         let mut res = collect_items(
             db,
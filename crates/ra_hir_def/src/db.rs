@@ -2,9 +2,10 @@
 use std::sync::Arc;
 
 use hir_expand::{db::AstDatabase, HirFileId};
-use ra_db::{salsa, CrateId, SourceDatabase, Upcast};
+use ra_db::{salsa, CrateId, FileId, SourceDatabase, Upcast};
 use ra_prof::profile;
 use ra_syntax::SmolStr;
+use rustc_hash::FxHashMap;
 
 use crate::{
     adt::{EnumData, StructData},
@@ -47,6 +48,14 @@ pub trait DefDatabase: InternDatabase + AstDatabase + Upcast<dyn AstDatabase> {
     #[salsa::invoke(RawItems::raw_items_query)]
     fn raw_items(&self, file_id: HirFileId) -> Arc<RawItems>;
 
+    /// The raw items of library files that were loaded from the on-disk cache
+    /// instead of being collected from the source.
+    #[salsa::input]
+    fn library_item_trees(&self) -> Arc<FxHashMap<FileId, Arc<RawItems>>>;
+
+    #[salsa::invoke(RawItems::cached_raw_items_query)]
+    fn cached_raw_items(&self, file_id: FileId) -> Option<Arc<RawItems>>;
+
     #[salsa::invoke(crate_def_map_wait)]
     #[salsa::transparent]
     fn crate_def_map(&self, krate: CrateId) -> Arc<CrateDefMap>;
@@ -12,10 +12,15 @@ use hir_expand::{HirFileId, InFile};
 pub struct UnresolvedModule {
     pub file: HirFileId,
     pub decl: AstPtr<ast::Module>,
-    pub candidate: RelativePathBuf,
+    /// Paths of the files the module could be declared in, relative to the
+    /// directory of the declaring file.
+    pub candidates: Vec<RelativePathBuf>,
 }
 
 impl Diagnostic for UnresolvedModule {
+    fn code(&self) -> &'static str {
+        "unresolved-module"
+    }
     fn message(&self) -> String {
         "unresolved module".to_string()
     }
@@ -26,3 +31,50 @@ impl Diagnostic for UnresolvedModule {
         self
     }
 }
+
+/// An item which is not compiled because it's disabled by a `#[cfg]`
+/// attribute.
+#[derive(Debug)]
+pub struct InactiveCode {
+    pub file: HirFileId,
+    pub item: AstPtr<ast::ModuleItem>,
+}
+
+impl Diagnostic for InactiveCode {
+    fn code(&self) -> &'static str {
+        "inactive-code"
+    }
+    fn message(&self) -> String {
+        "code is inactive due to #[cfg] directives".to_string()
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.item.clone().into())
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+/// A macro call whose path doesn't resolve to a macro.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnresolvedMacroCall {
+    pub file: HirFileId,
+    pub path: AstPtr<ast::Path>,
+    /// The path of the macro, as written.
+    pub name: String,
+}
+
+impl Diagnostic for UnresolvedMacroCall {
+    fn code(&self) -> &'static str {
+        "unresolved-macro-call"
+    }
+    fn message(&self) -> String {
+        format!("unresolved macro `{}!`", self.name)
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.path.clone().into())
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
@@ -60,7 +60,10 @@ impl Documentation {
                 docs_from_ast(&src.value[it.local_id])
             }
             AttrDefId::TraitId(it) => docs_from_ast(&it.lookup(db).source(db).value),
-            AttrDefId::MacroDefId(it) => docs_from_ast(&it.ast_id?.to_node(db.upcast())),
+            AttrDefId::MacroDefId(it) => match it.ast_id? {
+                Either::Left(ast_id) => docs_from_ast(&ast_id.to_node(db.upcast())),
+                Either::Right(ast_id) => docs_from_ast(&ast_id.to_node(db.upcast())),
+            },
             AttrDefId::ConstId(it) => docs_from_ast(&it.lookup(db).source(db).value),
             AttrDefId::StaticId(it) => docs_from_ast(&it.lookup(db).source(db).value),
             AttrDefId::FunctionId(it) => docs_from_ast(&it.lookup(db).source(db).value),
@@ -23,7 +23,7 @@ use crate::{
     src::HasChildSource,
     src::HasSource,
     type_ref::{TypeBound, TypeRef},
-    AdtId, GenericDefId, LocalTypeParamId, Lookup, TypeParamId,
+    AdtId, GenericDefId, LocalConstParamId, LocalTypeParamId, Lookup, TypeParamId,
 };
 
 /// Data about a generic parameter (to a function, struct, impl, ...).
@@ -34,17 +34,29 @@ pub struct TypeParamData {
     pub provenance: TypeParamProvenance,
 }
 
+/// Data about a const generic parameter, like `N` in `struct Foo<const N: usize>`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ConstParamData {
+    pub name: Name,
+    pub ty: TypeRef,
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum TypeParamProvenance {
     TypeParamList,
     TraitSelf,
     ArgumentImplTrait,
+    /// A const generic parameter. These are also recorded as type parameters,
+    /// so that their arguments take part in substitutions and get unified
+    /// like types; the type of the constant itself is in `GenericParams::consts`.
+    ConstParam,
 }
 
 /// Data about the generic parameters of a function, struct, impl, etc.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct GenericParams {
     pub types: Arena<TypeParamData>,
+    pub consts: Arena<ConstParamData>,
     // lifetimes: Arena<LocalLifetimeParamId, LifetimeParamData>,
     pub where_predicates: Vec<WherePredicate>,
 }
@@ -79,7 +91,11 @@ impl GenericParams {
     }
 
     fn new(db: &dyn DefDatabase, def: GenericDefId) -> (GenericParams, InFile<SourceMap>) {
-        let mut generics = GenericParams { types: Arena::default(), where_predicates: Vec::new() };
+        let mut generics = GenericParams {
+            types: Arena::default(),
+            consts: Arena::default(),
+            where_predicates: Vec::new(),
+        };
         let mut sm = ArenaMap::default();
 
         // FIXME: add `: Sized` bound for everything except for `Self` in traits
@@ -184,20 +200,36 @@ impl GenericParams {
         sm: &mut SourceMap,
         params: ast::TypeParamList,
     ) {
-        for type_param in params.type_params() {
-            let name = type_param.name().map_or_else(Name::missing, |it| it.as_name());
-            // FIXME: Use `Path::from_src`
-            let default = type_param.default_type().map(|it| TypeRef::from_ast(lower_ctx, it));
-            let param = TypeParamData {
-                name: Some(name.clone()),
-                default,
-                provenance: TypeParamProvenance::TypeParamList,
-            };
-            let param_id = self.types.alloc(param);
-            sm.insert(param_id, Either::Right(type_param.clone()));
+        for param in params.generic_params() {
+            match param {
+                ast::GenericParam::TypeParam(type_param) => {
+                    let name = type_param.name().map_or_else(Name::missing, |it| it.as_name());
+                    // FIXME: Use `Path::from_src`
+                    let default =
+                        type_param.default_type().map(|it| TypeRef::from_ast(lower_ctx, it));
+                    let param = TypeParamData {
+                        name: Some(name.clone()),
+                        default,
+                        provenance: TypeParamProvenance::TypeParamList,
+                    };
+                    let param_id = self.types.alloc(param);
+                    sm.insert(param_id, Either::Right(type_param.clone()));
 
-            let type_ref = TypeRef::Path(name.into());
-            self.fill_bounds(&lower_ctx, &type_param, type_ref);
+                    let type_ref = TypeRef::Path(name.into());
+                    self.fill_bounds(&lower_ctx, &type_param, type_ref);
+                }
+                ast::GenericParam::ConstParam(const_param) => {
+                    let name = const_param.name().map_or_else(Name::missing, |it| it.as_name());
+                    let ty = TypeRef::from_ast_opt(lower_ctx, const_param.ascribed_type());
+                    self.types.alloc(TypeParamData {
+                        name: Some(name.clone()),
+                        default: None,
+                        provenance: TypeParamProvenance::ConstParam,
+                    });
+                    self.consts.alloc(ConstParamData { name, ty });
+                }
+                ast::GenericParam::LifetimeParam(_) => {}
+            }
         }
     }
 
@@ -254,6 +286,10 @@ impl GenericParams {
             .find_map(|(id, p)| if p.name.as_ref() == Some(name) { Some(id) } else { None })
     }
 
+    pub fn find_const_by_name(&self, name: &Name) -> Option<LocalConstParamId> {
+        self.consts.iter().find_map(|(id, p)| if &p.name == name { Some(id) } else { None })
+    }
+
     pub fn find_trait_self_param(&self) -> Option<LocalTypeParamId> {
         self.types.iter().find_map(|(id, p)| {
             if p.provenance == TypeParamProvenance::TraitSelf {
@@ -3,7 +3,7 @@
 
 use hir_expand::name::Name;
 use once_cell::sync::Lazy;
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::{
     per_ns::PerNs, visibility::Visibility, AdtId, BuiltinType, ImplId, MacroDefId, ModuleDefId,
@@ -28,6 +28,12 @@ pub struct ItemScope {
     // FIXME: Macro shadowing in one module is not properly handled. Non-item place macros will
     // be all resolved to the last one defined if shadowing happens.
     legacy_macros: FxHashMap<Name, MacroDefId>,
+    /// Names of items in this scope which are disabled by `cfg`.
+    inactive_names: FxHashSet<Name>,
+    /// Whether this scope may contain items we don't know the names of, because
+    /// they come from macro calls or glob imports which couldn't be resolved
+    /// or are disabled by `cfg`.
+    incomplete: bool,
 }
 
 pub(crate) static BUILTIN_SCOPE: Lazy<FxHashMap<Name, PerNs>> = Lazy::new(|| {
@@ -117,6 +123,20 @@ impl ItemScope {
         self.impls.push(imp)
     }
 
+    pub(crate) fn define_inactive(&mut self, name: Name) {
+        self.inactive_names.insert(name);
+    }
+
+    pub(crate) fn mark_incomplete(&mut self) {
+        self.incomplete = true;
+    }
+
+    /// Whether `name` may refer to an item of this scope that name resolution
+    /// doesn't know about, so that it not resolving isn't necessarily an error.
+    pub fn may_contain_unknown(&self, name: &Name) -> bool {
+        self.incomplete || self.inactive_names.contains(name)
+    }
+
     pub(crate) fn define_legacy_macro(&mut self, name: Name, mac: MacroDefId) {
         self.legacy_macros.insert(name, mac);
     }
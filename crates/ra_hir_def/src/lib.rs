@@ -15,6 +15,7 @@ macro_rules! eprintln {
 pub mod db;
 
 pub mod attr;
+mod builtin_attr;
 pub mod path;
 pub mod type_ref;
 pub mod builtin_type;
@@ -173,6 +174,14 @@ pub struct TypeParamId {
 
 pub type LocalTypeParamId = Idx<generics::TypeParamData>;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConstParamId {
+    pub parent: GenericDefId,
+    pub local_id: LocalConstParamId,
+}
+
+pub type LocalConstParamId = Idx<generics::ConstParamData>;
+
 macro_rules! impl_froms {
     ($e:ident: $($v:ident $(($($sv:ident),*))?),*) => {
         $(
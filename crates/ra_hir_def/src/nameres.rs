@@ -74,6 +74,8 @@ use crate::{
     AstId, LocalModuleId, ModuleDefId, ModuleId,
 };
 
+pub use self::raw::RawItems;
+
 /// Contains all top-level defs from a macro-expanded crate
 #[derive(Debug, PartialEq, Eq)]
 pub struct CrateDefMap {
@@ -297,16 +299,29 @@ pub enum ModuleSource {
 mod diagnostics {
     use hir_expand::diagnostics::DiagnosticSink;
     use ra_db::RelativePathBuf;
-    use ra_syntax::{ast, AstPtr};
+    use ra_syntax::{ast, AstNode, AstPtr};
 
-    use crate::{db::DefDatabase, diagnostics::UnresolvedModule, nameres::LocalModuleId, AstId};
+    use crate::{
+        db::DefDatabase,
+        diagnostics::{InactiveCode, UnresolvedMacroCall, UnresolvedModule},
+        nameres::LocalModuleId,
+        AstId,
+    };
 
     #[derive(Debug, PartialEq, Eq)]
     pub(super) enum DefDiagnostic {
         UnresolvedModule {
             module: LocalModuleId,
             declaration: AstId<ast::Module>,
-            candidate: RelativePathBuf,
+            candidates: Vec<RelativePathBuf>,
+        },
+        InactiveCode {
+            module: LocalModuleId,
+            item: AstId<ast::ModuleItem>,
+        },
+        UnresolvedMacroCall {
+            module: LocalModuleId,
+            ast_id: AstId<ast::MacroCall>,
         },
     }
 
@@ -318,7 +333,7 @@ mod diagnostics {
             sink: &mut DiagnosticSink,
         ) {
             match self {
-                DefDiagnostic::UnresolvedModule { module, declaration, candidate } => {
+                DefDiagnostic::UnresolvedModule { module, declaration, candidates } => {
                     if *module != target_module {
                         return;
                     }
@@ -326,7 +341,28 @@ mod diagnostics {
                     sink.push(UnresolvedModule {
                         file: declaration.file_id,
                         decl: AstPtr::new(&decl),
-                        candidate: candidate.clone(),
+                        candidates: candidates.clone(),
+                    })
+                }
+                DefDiagnostic::InactiveCode { module, item } => {
+                    if *module != target_module {
+                        return;
+                    }
+                    let node = item.to_node(db.upcast());
+                    sink.push(InactiveCode { file: item.file_id, item: AstPtr::new(&node) })
+                }
+                DefDiagnostic::UnresolvedMacroCall { module, ast_id } => {
+                    if *module != target_module {
+                        return;
+                    }
+                    let path = match ast_id.to_node(db.upcast()).path() {
+                        Some(it) => it,
+                        None => return,
+                    };
+                    sink.push(UnresolvedMacroCall {
+                        file: ast_id.file_id,
+                        path: AstPtr::new(&path),
+                        name: path.syntax().text().to_string(),
                     })
                 }
             }
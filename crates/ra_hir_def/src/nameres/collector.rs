@@ -3,6 +3,7 @@
 //! `DefCollector::collect` contains the fixed-point iteration loop which
 //! resolves imports and expands macros.
 
+use either::Either;
 use hir_expand::{
     builtin_derive::find_builtin_derive,
     builtin_macro::find_builtin_macro,
@@ -10,14 +11,16 @@ use hir_expand::{
     proc_macro::ProcMacroExpander,
     HirFileId, MacroCallId, MacroDefId, MacroDefKind,
 };
+use ra_arena::Idx;
 use ra_cfg::CfgOptions;
-use ra_db::{CrateId, FileId, ProcMacroId};
+use ra_db::{CrateId, FileId, ProcMacroId, ProcMacroKind};
 use ra_syntax::ast;
 use rustc_hash::FxHashMap;
 use test_utils::tested_by;
 
 use crate::{
     attr::Attrs,
+    builtin_attr::is_builtin_attr,
     db::DefDatabase,
     nameres::{
         diagnostics::DefDiagnostic, mod_resolution::ModDir, path_resolution::ReachedFixedPoint,
@@ -60,7 +63,13 @@ pub(super) fn collect_defs(db: &dyn DefDatabase, mut def_map: CrateDefMap) -> Cr
         .map(|(idx, it)| {
             // FIXME: a hacky way to create a Name from string.
             let name = tt::Ident { text: it.name.clone(), id: tt::TokenId::unspecified() };
-            (name.as_name(), ProcMacroExpander::new(def_map.krate, ProcMacroId(idx as u32)))
+            let expander = ProcMacroExpander::new(def_map.krate, ProcMacroId(idx as u32));
+            let kind = match it.kind {
+                ProcMacroKind::CustomDerive => MacroDefKind::CustomDerive(expander),
+                ProcMacroKind::Attr => MacroDefKind::ProcMacroAttr(expander),
+                ProcMacroKind::FuncLike => MacroDefKind::ProcMacro(expander),
+            };
+            (name.as_name(), kind)
         })
         .collect();
 
@@ -73,6 +82,7 @@ pub(super) fn collect_defs(db: &dyn DefDatabase, mut def_map: CrateDefMap) -> Cr
 
         unexpanded_macros: Vec::new(),
         unexpanded_attribute_macros: Vec::new(),
+        unexpanded_attrs: Vec::new(),
         mod_dirs: FxHashMap::default(),
         cfg_options,
         proc_macros,
@@ -123,6 +133,37 @@ struct DeriveDirective {
     ast_id: AstIdWithPath<ast::ModuleItem>,
 }
 
+/// An item annotated with an attribute that might be an attribute macro.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct AttrDirective {
+    module_id: LocalModuleId,
+    /// The item, with the path of the attribute.
+    ast_id: AstIdWithPath<ast::ModuleItem>,
+    def: Idx<raw::DefData>,
+    attrs: Attrs,
+    /// The index of the attribute in `attrs`.
+    attr_idx: usize,
+    depth: usize,
+}
+
+impl AttrDirective {
+    /// Returns the directive for the next attribute of the item that might be
+    /// an attribute macro, if there is one.
+    fn next_attr(&self) -> Option<AttrDirective> {
+        let (attr_idx, attr) = self
+            .attrs
+            .iter()
+            .enumerate()
+            .skip(self.attr_idx + 1)
+            .find(|(_, attr)| !is_builtin_attr(&attr.path))?;
+        Some(AttrDirective {
+            ast_id: AstIdWithPath { ast_id: self.ast_id.ast_id, path: attr.path.clone() },
+            attr_idx,
+            ..self.clone()
+        })
+    }
+}
+
 /// Walks the tree of module recursively
 struct DefCollector<'a> {
     db: &'a dyn DefDatabase,
@@ -132,9 +173,10 @@ struct DefCollector<'a> {
     resolved_imports: Vec<ImportDirective>,
     unexpanded_macros: Vec<MacroDirective>,
     unexpanded_attribute_macros: Vec<DeriveDirective>,
+    unexpanded_attrs: Vec<AttrDirective>,
     mod_dirs: FxHashMap<LocalModuleId, ModDir>,
     cfg_options: &'a CfgOptions,
-    proc_macros: Vec<(Name, ProcMacroExpander)>,
+    proc_macros: Vec<(Name, MacroDefKind)>,
 }
 
 impl DefCollector<'_> {
@@ -160,7 +202,19 @@ impl DefCollector<'_> {
             self.resolve_imports();
 
             match self.resolve_macros() {
-                ReachedFixedPoint::Yes => break,
+                ReachedFixedPoint::Yes => {
+                    if self.unexpanded_attrs.is_empty() {
+                        break;
+                    }
+                    // The remaining attributes didn't resolve to any macro, so
+                    // we move on to the next attribute of their items. Items
+                    // without further attributes are collected as-is, which
+                    // might in turn unblock other imports and macros.
+                    for directive in std::mem::take(&mut self.unexpanded_attrs) {
+                        self.skip_attr(directive);
+                    }
+                    i += 1;
+                }
                 ReachedFixedPoint::No => i += 1,
             }
             if i == 10000 {
@@ -188,24 +242,37 @@ impl DefCollector<'_> {
         let unresolved_imports = std::mem::replace(&mut self.unresolved_imports, Vec::new());
         // show unresolved imports in completion, etc
         for directive in unresolved_imports {
+            if directive.import.is_glob {
+                self.def_map.modules[directive.module_id].scope.mark_incomplete();
+            }
             self.record_resolved_import(&directive)
         }
 
         // Record proc-macros
         self.collect_proc_macro();
+
+        // Only the macro calls left whose path doesn't resolve are errors, the
+        // others resolved but failed to expand (e.g. an eager macro with a bad
+        // argument).
+        for directive in std::mem::take(&mut self.unexpanded_macros) {
+            self.def_map.modules[directive.module_id].scope.mark_incomplete();
+            if self.resolve_macro_path(directive.module_id, &directive.ast_id.path).is_some() {
+                continue;
+            }
+            self.def_map.diagnostics.push(DefDiagnostic::UnresolvedMacroCall {
+                module: directive.module_id,
+                ast_id: directive.ast_id.ast_id,
+            });
+        }
     }
 
     fn collect_proc_macro(&mut self) {
         let proc_macros = std::mem::take(&mut self.proc_macros);
-        for (name, expander) in proc_macros {
+        for (name, kind) in proc_macros {
             let krate = self.def_map.krate;
 
-            let macro_id = MacroDefId {
-                ast_id: None,
-                krate: Some(krate),
-                kind: MacroDefKind::CustomDerive(expander),
-                local_inner: false,
-            };
+            let macro_id =
+                MacroDefId { ast_id: None, krate: Some(krate), kind, local_inner: false };
 
             self.define_proc_macro(name.clone(), macro_id);
         }
@@ -571,16 +638,10 @@ impl DefCollector<'_> {
                 return false;
             }
 
-            if let Some(call_id) = directive.ast_id.as_call_id(self.db, |path| {
-                let resolved_res = self.def_map.resolve_path_fp_with_macro(
-                    self.db,
-                    ResolveMode::Other,
-                    directive.module_id,
-                    &path,
-                    BuiltinShadowMode::Module,
-                );
-                resolved_res.resolved_def.take_macros()
-            }) {
+            if let Some(call_id) = directive
+                .ast_id
+                .as_call_id(self.db, |path| self.resolve_macro_path(directive.module_id, &path))
+            {
                 resolved.push((directive.module_id, call_id, directive.depth));
                 res = ReachedFixedPoint::No;
                 return false;
@@ -589,10 +650,9 @@ impl DefCollector<'_> {
             true
         });
         attribute_macros.retain(|directive| {
-            if let Some(call_id) = directive
-                .ast_id
-                .as_call_id(self.db, |path| self.resolve_attribute_macro(&directive, &path))
-            {
+            if let Some(call_id) = directive.ast_id.as_call_id(self.db, |path| {
+                self.resolve_attribute_macro(directive.module_id, &path)
+            }) {
                 resolved.push((directive.module_id, call_id, 0));
                 res = ReachedFixedPoint::No;
                 return false;
@@ -600,9 +660,35 @@ impl DefCollector<'_> {
 
             true
         });
+        let mut attrs = std::mem::replace(&mut self.unexpanded_attrs, Vec::new());
+        let mut not_attr_macros = Vec::new();
+        attrs.retain(|directive| {
+            let def =
+                match self.resolve_attribute_macro(directive.module_id, &directive.ast_id.path) {
+                    Some(it) => it,
+                    None => return true,
+                };
+            res = ReachedFixedPoint::No;
+            if let MacroDefKind::ProcMacroAttr(_) = def.kind {
+                if let Some(call_id) = directive.ast_id.as_call_id(self.db, |_| Some(def)) {
+                    // If the expansion fails, we fall back to the unexpanded item
+                    if let (Some(_), None) = self.db.macro_expand(call_id) {
+                        resolved.push((directive.module_id, call_id, directive.depth + 1));
+                        return false;
+                    }
+                }
+            }
+            not_attr_macros.push(directive.clone());
+            false
+        });
 
         self.unexpanded_macros = macros;
         self.unexpanded_attribute_macros = attribute_macros;
+        self.unexpanded_attrs = attrs;
+
+        for directive in not_attr_macros {
+            self.skip_attr(directive);
+        }
 
         for (module_id, macro_call_id, depth) in resolved {
             if depth > 1024 {
@@ -617,7 +703,7 @@ impl DefCollector<'_> {
 
     fn resolve_attribute_macro(
         &self,
-        directive: &DeriveDirective,
+        module_id: LocalModuleId,
         path: &ModPath,
     ) -> Option<MacroDefId> {
         if let Some(name) = path.as_ident() {
@@ -628,17 +714,46 @@ impl DefCollector<'_> {
                 return Some(def_id);
             }
         }
+        self.resolve_macro_path(module_id, path)
+    }
+
+    fn resolve_macro_path(&self, module_id: LocalModuleId, path: &ModPath) -> Option<MacroDefId> {
         let resolved_res = self.def_map.resolve_path_fp_with_macro(
             self.db,
             ResolveMode::Other,
-            directive.module_id,
-            &path,
+            module_id,
+            path,
             BuiltinShadowMode::Module,
         );
-
         resolved_res.resolved_def.take_macros()
     }
 
+    /// Ignores an attribute that is not an attribute macro, or whose expansion
+    /// failed, and continues with the next attribute of the item.
+    fn skip_attr(&mut self, directive: AttrDirective) {
+        match directive.next_attr() {
+            Some(next) => self.unexpanded_attrs.push(next),
+            None => self.collect_item_as_is(directive),
+        }
+    }
+
+    /// Defines an item whose attributes are not attribute macros, ignoring
+    /// them.
+    fn collect_item_as_is(&mut self, directive: AttrDirective) {
+        let file_id = directive.ast_id.ast_id.file_id;
+        let raw_items = self.db.raw_items(file_id);
+        let mod_dir = self.mod_dirs[&directive.module_id].clone();
+        ModCollector {
+            def_collector: &mut *self,
+            macro_depth: directive.depth,
+            module_id: directive.module_id,
+            file_id,
+            raw_items: &raw_items,
+            mod_dir,
+        }
+        .define_def_as_is(&raw_items[directive.def], &directive.attrs);
+    }
+
     fn collect_macro_expansion(
         &mut self,
         module_id: LocalModuleId,
@@ -716,10 +831,9 @@ impl ModCollector<'_, '_> {
                             status: PartialResolvedImport::Unresolved,
                         })
                     }
-                    raw::RawItemKind::Def(def) => {
-                        self.define_def(&self.raw_items[def], &item.attrs)
-                    }
+                    raw::RawItemKind::Def(def) => self.define_def(def, &item.attrs),
                     raw::RawItemKind::Macro(mac) => self.collect_macro(&self.raw_items[mac]),
+                    raw::RawItemKind::MacroDef(mac) => self.collect_macro_def(&self.raw_items[mac]),
                     raw::RawItemKind::Impl(imp) => {
                         let module = ModuleId {
                             krate: self.def_collector.def_map.krate,
@@ -735,7 +849,43 @@ impl ModCollector<'_, '_> {
                             .define_impl(impl_id)
                     }
                 }
+            } else {
+                self.record_inactive_item(item);
+                if let Some(ast_id) = self.raw_items.item_ast_id(item) {
+                    self.def_collector.def_map.diagnostics.push(DefDiagnostic::InactiveCode {
+                        module: self.module_id,
+                        item: AstId::new(self.file_id, ast_id),
+                    })
+                }
+            }
+        }
+    }
+
+    /// Remembers what a `cfg`-disabled item would have defined, so that paths
+    /// to it aren't reported as unresolved.
+    fn record_inactive_item(&mut self, item: &raw::RawItem) {
+        let name = match item.kind {
+            raw::RawItemKind::Module(it) => match &self.raw_items[it] {
+                raw::ModuleData::Declaration { name, .. }
+                | raw::ModuleData::Definition { name, .. } => Some(name.clone()),
+            },
+            raw::RawItemKind::Import(it) => {
+                let import = &self.raw_items[it];
+                match &import.alias {
+                    _ if import.is_glob => None,
+                    Some(ImportAlias::Alias(name)) => Some(name.clone()),
+                    Some(ImportAlias::Underscore) => return,
+                    None => import.path.segments.last().cloned(),
+                }
             }
+            raw::RawItemKind::Def(it) => Some(self.raw_items[it].name.clone()),
+            raw::RawItemKind::Macro(_) => None,
+            raw::RawItemKind::MacroDef(_) | raw::RawItemKind::Impl(_) => return,
+        };
+        let scope = &mut self.def_collector.def_map.modules[self.module_id].scope;
+        match name {
+            Some(name) => scope.define_inactive(name),
+            None => scope.mark_incomplete(),
         }
     }
 
@@ -795,11 +945,11 @@ impl ModCollector<'_, '_> {
                             self.import_all_legacy_macros(module_id);
                         }
                     }
-                    Err(candidate) => self.def_collector.def_map.diagnostics.push(
+                    Err(candidates) => self.def_collector.def_map.diagnostics.push(
                         DefDiagnostic::UnresolvedModule {
                             module: self.module_id,
                             declaration: ast_id,
-                            candidate,
+                            candidates,
                         },
                     ),
                 };
@@ -834,11 +984,33 @@ impl ModCollector<'_, '_> {
         res
     }
 
-    fn define_def(&mut self, def: &raw::DefData, attrs: &Attrs) {
+    fn define_def(&mut self, def: Idx<raw::DefData>, attrs: &Attrs) {
+        // If the item has an attribute that might be an attribute macro, we
+        // wait until we know what it resolves to: attribute macros replace
+        // the item with their expansion.
+        if let Some((attr_idx, attr)) =
+            attrs.iter().enumerate().find(|(_, attr)| !is_builtin_attr(&attr.path))
+        {
+            let ast_id = AstIdWithPath::new(
+                self.file_id,
+                self.raw_items[def].kind.ast_id(),
+                attr.path.clone(),
+            );
+            self.def_collector.unexpanded_attrs.push(AttrDirective {
+                module_id: self.module_id,
+                ast_id,
+                def,
+                attrs: attrs.clone(),
+                attr_idx,
+                depth: self.macro_depth,
+            });
+            return;
+        }
+        self.define_def_as_is(&self.raw_items[def], attrs);
+    }
+
+    fn define_def_as_is(&mut self, def: &raw::DefData, attrs: &Attrs) {
         let module = ModuleId { krate: self.def_collector.def_map.krate, local_id: self.module_id };
-        // FIXME: check attrs to see if this is an attribute macro invocation;
-        // in which case we don't add the invocation, just a single attribute
-        // macro invocation
         self.collect_derives(attrs, def);
 
         let name = def.name.clone();
@@ -930,7 +1102,8 @@ impl ModCollector<'_, '_> {
         if mac.builtin {
             if let Some(name) = &mac.name {
                 let krate = self.def_collector.def_map.krate;
-                if let Some(macro_id) = find_builtin_macro(name, krate, ast_id.ast_id) {
+                if let Some(macro_id) = find_builtin_macro(name, krate, Either::Left(ast_id.ast_id))
+                {
                     self.def_collector.define_macro(
                         self.module_id,
                         name.clone(),
@@ -946,7 +1119,7 @@ impl ModCollector<'_, '_> {
         if is_macro_rules(&mac.path) {
             if let Some(name) = &mac.name {
                 let macro_id = MacroDefId {
-                    ast_id: Some(ast_id.ast_id),
+                    ast_id: Some(Either::Left(ast_id.ast_id)),
                     krate: Some(self.def_collector.def_map.krate),
                     kind: MacroDefKind::Declarative,
                     local_inner: mac.local_inner,
@@ -986,6 +1159,31 @@ impl ModCollector<'_, '_> {
         });
     }
 
+    /// Define a macros 2.0 `macro` item. These are scoped like any other
+    /// item, so they are neither textually scoped nor affected by
+    /// `#[macro_export]`.
+    fn collect_macro_def(&mut self, mac: &raw::MacroDefData) {
+        let krate = self.def_collector.def_map.krate;
+        let ast_id = Either::Right(AstId::new(self.file_id, mac.ast_id));
+        let builtin = if mac.builtin { find_builtin_macro(&mac.name, krate, ast_id) } else { None };
+        let macro_id = builtin.unwrap_or(MacroDefId {
+            ast_id: Some(ast_id),
+            krate: Some(krate),
+            kind: MacroDefKind::Declarative,
+            local_inner: false,
+        });
+        let vis = self
+            .def_collector
+            .def_map
+            .resolve_visibility(self.def_collector.db, self.module_id, &mac.visibility)
+            .unwrap_or(Visibility::Public);
+        self.def_collector.update(
+            self.module_id,
+            &[(mac.name.clone(), PerNs::macros(macro_id, vis))],
+            vis,
+        );
+    }
+
     fn import_all_legacy_macros(&mut self, module_id: LocalModuleId) {
         let macros = self.def_collector.def_map[module_id].scope.collect_legacy_macros();
         for (name, macro_) in macros {
@@ -1019,6 +1217,7 @@ mod tests {
             resolved_imports: Vec::new(),
             unexpanded_macros: Vec::new(),
             unexpanded_attribute_macros: Vec::new(),
+            unexpanded_attrs: Vec::new(),
             mod_dirs: FxHashMap::default(),
             cfg_options: &CfgOptions::default(),
             proc_macros: Default::default(),
@@ -44,7 +44,7 @@ impl ModDir {
         file_id: HirFileId,
         name: &Name,
         attr_path: Option<&SmolStr>,
-    ) -> Result<(FileId, ModDir), RelativePathBuf> {
+    ) -> Result<(FileId, ModDir), Vec<RelativePathBuf>> {
         let file_id = file_id.original_file(db.upcast());
 
         let mut candidate_files = Vec::new();
@@ -71,7 +71,7 @@ impl ModDir {
                 return Ok((file_id, ModDir { path: mod_path, root_non_dir_owner }));
             }
         }
-        Err(candidate_files.remove(0))
+        Err(candidate_files)
     }
 }
 
@@ -5,6 +5,8 @@
 //! they don't change with trivial source code edits, making them a great tool
 //! for building salsa recomputation firewalls.
 
+mod encoding;
+
 use std::{ops::Index, sync::Arc};
 
 use hir_expand::{
@@ -13,6 +15,7 @@ use hir_expand::{
     name::{AsName, Name},
 };
 use ra_arena::{Arena, Idx};
+use ra_db::FileId;
 use ra_prof::profile;
 use ra_syntax::{
     ast::{self, AttrsOwner, NameOwner, VisibilityOwner},
@@ -38,6 +41,7 @@ pub struct RawItems {
     imports: Arena<ImportData>,
     defs: Arena<DefData>,
     macros: Arena<MacroData>,
+    macro_defs: Arena<MacroDefData>,
     impls: Arena<ImplData>,
     /// items for top-level module
     items: Vec<RawItem>,
@@ -46,6 +50,11 @@ pub struct RawItems {
 impl RawItems {
     pub(crate) fn raw_items_query(db: &dyn DefDatabase, file_id: HirFileId) -> Arc<RawItems> {
         let _p = profile("raw_items_query");
+        if !file_id.is_macro_file() {
+            if let Some(it) = db.cached_raw_items(file_id.original_file(db.upcast())) {
+                return it;
+            }
+        }
         let mut collector = RawItemsCollector {
             raw_items: RawItems::default(),
             source_ast_id_map: db.ast_id_map(file_id),
@@ -63,6 +72,41 @@ impl RawItems {
         Arc::new(raw_items)
     }
 
+    /// Looks up the raw items of a file in `library_item_trees`. This is a
+    /// separate query so that changes to the cached item trees of other files
+    /// don't invalidate `raw_items`.
+    pub(crate) fn cached_raw_items_query(
+        db: &dyn DefDatabase,
+        file_id: FileId,
+    ) -> Option<Arc<RawItems>> {
+        db.library_item_trees().get(&file_id).cloned()
+    }
+
+    /// Collects the raw items of a source file outside of the database, e.g.
+    /// to store them on disk. The result is the same as what `raw_items`
+    /// computes for the file.
+    pub fn from_source_file(file_id: FileId, source_file: ast::SourceFile) -> RawItems {
+        let mut collector = RawItemsCollector {
+            raw_items: RawItems::default(),
+            source_ast_id_map: Arc::new(AstIdMap::from_source(source_file.syntax())),
+            file_id: file_id.into(),
+            hygiene: Hygiene::new_unhygienic(),
+        };
+        collector.process_module(None, source_file);
+        collector.raw_items
+    }
+
+    /// Serializes the raw items into a compact binary form. Returns `None` if
+    /// they contain something that can't be stored, like `$crate` paths.
+    pub fn encode(&self) -> Option<Vec<u8>> {
+        encoding::encode(self)
+    }
+
+    /// The inverse of `encode`. Returns `None` if `bytes` are not valid.
+    pub fn decode(bytes: &[u8]) -> Option<RawItems> {
+        encoding::decode(bytes)
+    }
+
     pub(super) fn items(&self) -> &[RawItem] {
         &self.items
     }
@@ -96,6 +140,13 @@ impl Index<Idx<MacroData>> for RawItems {
     }
 }
 
+impl Index<Idx<MacroDefData>> for RawItems {
+    type Output = MacroDefData;
+    fn index(&self, idx: Idx<MacroDefData>) -> &MacroDefData {
+        &self.macro_defs[idx]
+    }
+}
+
 impl Index<Idx<ImplData>> for RawItems {
     type Output = ImplData;
     fn index(&self, idx: Idx<ImplData>) -> &ImplData {
@@ -109,12 +160,32 @@ pub(super) struct RawItem {
     pub(super) kind: RawItemKind,
 }
 
+impl RawItems {
+    /// Returns the id of the syntax node of `item`, unless it's an import.
+    pub(super) fn item_ast_id(&self, item: &RawItem) -> Option<FileAstId<ast::ModuleItem>> {
+        let ast_id = match item.kind {
+            RawItemKind::Module(it) => match &self[it] {
+                ModuleData::Declaration { ast_id, .. } | ModuleData::Definition { ast_id, .. } => {
+                    ast_id.upcast()
+                }
+            },
+            RawItemKind::Import(_) => return None,
+            RawItemKind::Def(it) => self[it].kind.ast_id(),
+            RawItemKind::Macro(it) => self[it].ast_id.upcast(),
+            RawItemKind::MacroDef(it) => self[it].ast_id.upcast(),
+            RawItemKind::Impl(it) => self[it].ast_id.upcast(),
+        };
+        Some(ast_id)
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub(super) enum RawItemKind {
     Module(Idx<ModuleData>),
     Import(Import),
     Def(Idx<DefData>),
     Macro(Idx<MacroData>),
+    MacroDef(Idx<MacroDefData>),
     Impl(Idx<ImplData>),
 }
 
@@ -199,6 +270,16 @@ pub(super) struct MacroData {
     pub(super) builtin: bool,
 }
 
+/// A macros 2.0 `macro` item. Unlike `macro_rules!`, it is scoped like any
+/// other item.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct MacroDefData {
+    pub(super) ast_id: FileAstId<ast::MacroDef>,
+    pub(super) name: Name,
+    pub(super) visibility: RawVisibility,
+    pub(super) builtin: bool,
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub(super) struct ImplData {
     pub(super) ast_id: FileAstId<ast::ImplDef>,
@@ -279,6 +360,10 @@ impl RawItemsCollector {
                 self.add_macro(current_module, it);
                 return;
             }
+            ast::ModuleItem::MacroDef(it) => {
+                self.add_macro_def(current_module, it, visibility);
+                return;
+            }
             ast::ModuleItem::ExternBlock(it) => {
                 self.add_extern_block(current_module, it);
                 return;
@@ -443,6 +528,24 @@ impl RawItemsCollector {
         self.push_item(current_module, attrs, RawItemKind::Macro(m));
     }
 
+    fn add_macro_def(
+        &mut self,
+        current_module: Option<Idx<ModuleData>>,
+        m: ast::MacroDef,
+        visibility: RawVisibility,
+    ) {
+        let attrs = self.parse_attrs(&m);
+        let name = match m.name() {
+            Some(it) => it.as_name(),
+            None => return,
+        };
+        let ast_id = self.source_ast_id_map.ast_id(&m);
+        let builtin = attrs.by_key("rustc_builtin_macro").exists();
+
+        let m = self.raw_items.macro_defs.alloc(MacroDefData { ast_id, name, visibility, builtin });
+        self.push_item(current_module, attrs, RawItemKind::MacroDef(m));
+    }
+
     fn add_impl(&mut self, current_module: Option<Idx<ModuleData>>, imp: ast::ImplDef) {
         let attrs = self.parse_attrs(&imp);
         let ast_id = self.source_ast_id_map.ast_id(&imp);
@@ -0,0 +1,532 @@
+//! A compact binary encoding of `RawItems`, used to store the item trees of
+//! libraries on disk.
+//!
+//! `FileAstId`s are stored as their raw indices, so an encoding is only valid
+//! for the exact text it was created from.
+
+use hir_expand::name::Name;
+use ra_arena::{Arena, Idx};
+use ra_syntax::{AstNode, SmolStr};
+
+use super::{
+    DefData, DefKind, ImplData, ImportData, MacroData, MacroDefData, ModuleData, RawItem,
+    RawItemKind, RawItems, StructDefKind,
+};
+use crate::{
+    attr::{Attr, AttrInput, Attrs},
+    path::{ImportAlias, ModPath, PathKind},
+    visibility::RawVisibility,
+    FileAstId,
+};
+
+pub(super) fn encode(raw_items: &RawItems) -> Option<Vec<u8>> {
+    let mut w = Writer { buf: Vec::new() };
+
+    w.u32(raw_items.modules.len() as u32);
+    for (_, module) in raw_items.modules.iter() {
+        match module {
+            ModuleData::Declaration { name, visibility, ast_id } => {
+                w.u8(0);
+                w.name(name);
+                w.visibility(visibility)?;
+                w.ast_id(*ast_id);
+            }
+            ModuleData::Definition { name, visibility, ast_id, items } => {
+                w.u8(1);
+                w.name(name);
+                w.visibility(visibility)?;
+                w.ast_id(*ast_id);
+                w.items(items)?;
+            }
+        }
+    }
+
+    w.u32(raw_items.imports.len() as u32);
+    for (_, import) in raw_items.imports.iter() {
+        w.path(&import.path)?;
+        match &import.alias {
+            None => w.u8(0),
+            Some(ImportAlias::Underscore) => w.u8(1),
+            Some(ImportAlias::Alias(name)) => {
+                w.u8(2);
+                w.name(name);
+            }
+        }
+        w.flag(import.is_glob);
+        w.flag(import.is_prelude);
+        w.flag(import.is_extern_crate);
+        w.flag(import.is_macro_use);
+        w.visibility(&import.visibility)?;
+    }
+
+    w.u32(raw_items.defs.len() as u32);
+    for (_, def) in raw_items.defs.iter() {
+        w.name(&def.name);
+        match def.kind {
+            DefKind::Function(it) => w.tagged_ast_id(0, it),
+            DefKind::Struct(it, kind) => {
+                w.tagged_ast_id(1, it);
+                w.u8(match kind {
+                    StructDefKind::Record => 0,
+                    StructDefKind::Tuple => 1,
+                    StructDefKind::Unit => 2,
+                });
+            }
+            DefKind::Union(it) => w.tagged_ast_id(2, it),
+            DefKind::Enum(it) => w.tagged_ast_id(3, it),
+            DefKind::Const(it) => w.tagged_ast_id(4, it),
+            DefKind::Static(it) => w.tagged_ast_id(5, it),
+            DefKind::Trait(it) => w.tagged_ast_id(6, it),
+            DefKind::TypeAlias(it) => w.tagged_ast_id(7, it),
+        }
+        w.visibility(&def.visibility)?;
+    }
+
+    w.u32(raw_items.macros.len() as u32);
+    for (_, mac) in raw_items.macros.iter() {
+        w.ast_id(mac.ast_id);
+        w.path(&mac.path)?;
+        match &mac.name {
+            Some(name) => {
+                w.flag(true);
+                w.name(name);
+            }
+            None => w.flag(false),
+        }
+        w.flag(mac.export);
+        w.flag(mac.local_inner);
+        w.flag(mac.builtin);
+    }
+
+    w.u32(raw_items.macro_defs.len() as u32);
+    for (_, mac) in raw_items.macro_defs.iter() {
+        w.ast_id(mac.ast_id);
+        w.name(&mac.name);
+        w.visibility(&mac.visibility)?;
+        w.flag(mac.builtin);
+    }
+
+    w.u32(raw_items.impls.len() as u32);
+    for (_, imp) in raw_items.impls.iter() {
+        w.ast_id(imp.ast_id);
+    }
+
+    w.items(&raw_items.items)?;
+    Some(w.buf)
+}
+
+pub(super) fn decode(bytes: &[u8]) -> Option<RawItems> {
+    let mut r = Reader { bytes };
+    let mut res = RawItems::default();
+
+    for _ in 0..r.u32()? {
+        let module = match r.u8()? {
+            0 => ModuleData::Declaration {
+                name: r.name()?,
+                visibility: r.visibility()?,
+                ast_id: r.ast_id()?,
+            },
+            1 => ModuleData::Definition {
+                name: r.name()?,
+                visibility: r.visibility()?,
+                ast_id: r.ast_id()?,
+                items: r.items()?,
+            },
+            _ => return None,
+        };
+        res.modules.alloc(module);
+    }
+
+    for _ in 0..r.u32()? {
+        let path = r.path()?;
+        let alias = match r.u8()? {
+            0 => None,
+            1 => Some(ImportAlias::Underscore),
+            2 => Some(ImportAlias::Alias(r.name()?)),
+            _ => return None,
+        };
+        res.imports.alloc(ImportData {
+            path,
+            alias,
+            is_glob: r.flag()?,
+            is_prelude: r.flag()?,
+            is_extern_crate: r.flag()?,
+            is_macro_use: r.flag()?,
+            visibility: r.visibility()?,
+        });
+    }
+
+    for _ in 0..r.u32()? {
+        let name = r.name()?;
+        let kind = match r.u8()? {
+            0 => DefKind::Function(r.ast_id()?),
+            1 => {
+                let ast_id = r.ast_id()?;
+                let kind = match r.u8()? {
+                    0 => StructDefKind::Record,
+                    1 => StructDefKind::Tuple,
+                    2 => StructDefKind::Unit,
+                    _ => return None,
+                };
+                DefKind::Struct(ast_id, kind)
+            }
+            2 => DefKind::Union(r.ast_id()?),
+            3 => DefKind::Enum(r.ast_id()?),
+            4 => DefKind::Const(r.ast_id()?),
+            5 => DefKind::Static(r.ast_id()?),
+            6 => DefKind::Trait(r.ast_id()?),
+            7 => DefKind::TypeAlias(r.ast_id()?),
+            _ => return None,
+        };
+        res.defs.alloc(DefData { name, kind, visibility: r.visibility()? });
+    }
+
+    for _ in 0..r.u32()? {
+        res.macros.alloc(MacroData {
+            ast_id: r.ast_id()?,
+            path: r.path()?,
+            name: if r.flag()? { Some(r.name()?) } else { None },
+            export: r.flag()?,
+            local_inner: r.flag()?,
+            builtin: r.flag()?,
+        });
+    }
+
+    for _ in 0..r.u32()? {
+        res.macro_defs.alloc(MacroDefData {
+            ast_id: r.ast_id()?,
+            name: r.name()?,
+            visibility: r.visibility()?,
+            builtin: r.flag()?,
+        });
+    }
+
+    for _ in 0..r.u32()? {
+        res.impls.alloc(ImplData { ast_id: r.ast_id()? });
+    }
+
+    res.items = r.items()?;
+    if !r.bytes.is_empty() {
+        return None;
+    }
+
+    // Make sure all items point into the arenas, so that indexing with them
+    // can't panic later.
+    let is_valid = |item: &RawItem| match item.kind {
+        RawItemKind::Module(it) => in_arena(&res.modules, it),
+        RawItemKind::Import(it) => in_arena(&res.imports, it),
+        RawItemKind::Def(it) => in_arena(&res.defs, it),
+        RawItemKind::Macro(it) => in_arena(&res.macros, it),
+        RawItemKind::MacroDef(it) => in_arena(&res.macro_defs, it),
+        RawItemKind::Impl(it) => in_arena(&res.impls, it),
+    };
+    let module_items = res.modules.iter().flat_map(|(_, module)| match module {
+        ModuleData::Definition { items, .. } => items.as_slice(),
+        ModuleData::Declaration { .. } => &[],
+    });
+    if !res.items.iter().chain(module_items).all(is_valid) {
+        return None;
+    }
+    Some(res)
+}
+
+fn in_arena<T>(arena: &Arena<T>, idx: Idx<T>) -> bool {
+    (u32::from(idx.into_raw()) as usize) < arena.len()
+}
+
+struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    fn u8(&mut self, value: u8) {
+        self.buf.push(value);
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn flag(&mut self, value: bool) {
+        self.u8(value as u8);
+    }
+
+    fn str(&mut self, text: &str) {
+        self.u32(text.len() as u32);
+        self.buf.extend_from_slice(text.as_bytes());
+    }
+
+    fn ast_id<N: AstNode>(&mut self, ast_id: FileAstId<N>) {
+        self.u32(ast_id.into_raw());
+    }
+
+    fn tagged_ast_id<N: AstNode>(&mut self, tag: u8, ast_id: FileAstId<N>) {
+        self.u8(tag);
+        self.ast_id(ast_id);
+    }
+
+    fn idx<T>(&mut self, tag: u8, idx: Idx<T>) {
+        self.u8(tag);
+        self.u32(idx.into_raw().into());
+    }
+
+    fn name(&mut self, name: &Name) {
+        match name.as_tuple_index() {
+            Some(idx) => {
+                self.u8(1);
+                self.u32(idx as u32);
+            }
+            None => {
+                self.u8(0);
+                self.str(&name.to_string());
+            }
+        }
+    }
+
+    fn path(&mut self, path: &ModPath) -> Option<()> {
+        match path.kind {
+            PathKind::Plain => self.u8(0),
+            PathKind::Super(n) => {
+                self.u8(1);
+                self.u8(n);
+            }
+            PathKind::Crate => self.u8(2),
+            PathKind::Abs => self.u8(3),
+            // Only occurs in macro expansions, which we don't store.
+            PathKind::DollarCrate(_) => return None,
+        }
+        self.u32(path.segments.len() as u32);
+        for segment in &path.segments {
+            self.name(segment);
+        }
+        Some(())
+    }
+
+    fn visibility(&mut self, visibility: &RawVisibility) -> Option<()> {
+        match visibility {
+            RawVisibility::Public => self.u8(0),
+            RawVisibility::Module(path) => {
+                self.u8(1);
+                self.path(path)?;
+            }
+        }
+        Some(())
+    }
+
+    fn attrs(&mut self, attrs: &Attrs) -> Option<()> {
+        self.u32(attrs.len() as u32);
+        for attr in attrs.iter() {
+            self.path(&attr.path)?;
+            match &attr.input {
+                None => self.u8(0),
+                Some(AttrInput::Literal(text)) => {
+                    self.u8(1);
+                    self.str(text);
+                }
+                Some(AttrInput::TokenTree(subtree)) => {
+                    self.u8(2);
+                    self.subtree(subtree);
+                }
+            }
+        }
+        Some(())
+    }
+
+    fn subtree(&mut self, subtree: &tt::Subtree) {
+        match subtree.delimiter {
+            None => self.u8(0),
+            Some(delimiter) => {
+                self.u8(match delimiter.kind {
+                    tt::DelimiterKind::Parenthesis => 1,
+                    tt::DelimiterKind::Brace => 2,
+                    tt::DelimiterKind::Bracket => 3,
+                });
+                self.u32(delimiter.id.0);
+            }
+        }
+        self.u32(subtree.token_trees.len() as u32);
+        for tt in &subtree.token_trees {
+            match tt {
+                tt::TokenTree::Leaf(tt::Leaf::Literal(it)) => {
+                    self.u8(0);
+                    self.str(&it.text);
+                    self.u32(it.id.0);
+                }
+                tt::TokenTree::Leaf(tt::Leaf::Punct(it)) => {
+                    self.u8(1);
+                    self.u32(it.char as u32);
+                    self.flag(it.spacing == tt::Spacing::Joint);
+                    self.u32(it.id.0);
+                }
+                tt::TokenTree::Leaf(tt::Leaf::Ident(it)) => {
+                    self.u8(2);
+                    self.str(&it.text);
+                    self.u32(it.id.0);
+                }
+                tt::TokenTree::Subtree(it) => {
+                    self.u8(3);
+                    self.subtree(it);
+                }
+            }
+        }
+    }
+
+    fn items(&mut self, items: &[RawItem]) -> Option<()> {
+        self.u32(items.len() as u32);
+        for item in items {
+            self.attrs(&item.attrs)?;
+            match item.kind {
+                RawItemKind::Module(it) => self.idx(0, it),
+                RawItemKind::Import(it) => self.idx(1, it),
+                RawItemKind::Def(it) => self.idx(2, it),
+                RawItemKind::Macro(it) => self.idx(3, it),
+                RawItemKind::MacroDef(it) => self.idx(4, it),
+                RawItemKind::Impl(it) => self.idx(5, it),
+            }
+        }
+        Some(())
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.bytes.len() < len {
+            return None;
+        }
+        let (res, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Some(res)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.bytes(1)?[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let mut buf = [0; 4];
+        buf.copy_from_slice(self.bytes(4)?);
+        Some(u32::from_le_bytes(buf))
+    }
+
+    fn flag(&mut self) -> Option<bool> {
+        match self.u8()? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+
+    fn str(&mut self) -> Option<SmolStr> {
+        let len = self.u32()? as usize;
+        let text = std::str::from_utf8(self.bytes(len)?).ok()?;
+        Some(SmolStr::new(text))
+    }
+
+    fn ast_id<N: AstNode>(&mut self) -> Option<FileAstId<N>> {
+        Some(FileAstId::from_raw(self.u32()?))
+    }
+
+    fn name(&mut self) -> Option<Name> {
+        match self.u8()? {
+            0 => Some(Name::from_stored_text(self.str()?)),
+            1 => Some(Name::new_tuple_field(self.u32()? as usize)),
+            _ => None,
+        }
+    }
+
+    fn path(&mut self) -> Option<ModPath> {
+        let kind = match self.u8()? {
+            0 => PathKind::Plain,
+            1 => PathKind::Super(self.u8()?),
+            2 => PathKind::Crate,
+            3 => PathKind::Abs,
+            _ => return None,
+        };
+        let segments = (0..self.u32()?).map(|_| self.name()).collect::<Option<_>>()?;
+        Some(ModPath { kind, segments })
+    }
+
+    fn visibility(&mut self) -> Option<RawVisibility> {
+        match self.u8()? {
+            0 => Some(RawVisibility::Public),
+            1 => Some(RawVisibility::Module(self.path()?)),
+            _ => None,
+        }
+    }
+
+    fn attrs(&mut self) -> Option<Attrs> {
+        let mut entries = Vec::new();
+        for _ in 0..self.u32()? {
+            let path = self.path()?;
+            let input = match self.u8()? {
+                0 => None,
+                1 => Some(AttrInput::Literal(self.str()?)),
+                2 => Some(AttrInput::TokenTree(self.subtree()?)),
+                _ => return None,
+            };
+            entries.push(Attr { path, input });
+        }
+        Some(Attrs::from_entries(entries))
+    }
+
+    fn subtree(&mut self) -> Option<tt::Subtree> {
+        let delimiter_kind = match self.u8()? {
+            0 => None,
+            1 => Some(tt::DelimiterKind::Parenthesis),
+            2 => Some(tt::DelimiterKind::Brace),
+            3 => Some(tt::DelimiterKind::Bracket),
+            _ => return None,
+        };
+        let delimiter = match delimiter_kind {
+            Some(kind) => Some(tt::Delimiter { id: tt::TokenId(self.u32()?), kind }),
+            None => None,
+        };
+        let mut token_trees = Vec::new();
+        for _ in 0..self.u32()? {
+            let tt = match self.u8()? {
+                0 => {
+                    let literal = tt::Literal { text: self.str()?, id: tt::TokenId(self.u32()?) };
+                    tt::Leaf::from(literal).into()
+                }
+                1 => {
+                    let char = std::char::from_u32(self.u32()?)?;
+                    let spacing =
+                        if self.flag()? { tt::Spacing::Joint } else { tt::Spacing::Alone };
+                    tt::Leaf::from(tt::Punct { char, spacing, id: tt::TokenId(self.u32()?) }).into()
+                }
+                2 => {
+                    let ident = tt::Ident { text: self.str()?, id: tt::TokenId(self.u32()?) };
+                    tt::Leaf::from(ident).into()
+                }
+                3 => self.subtree()?.into(),
+                _ => return None,
+            };
+            token_trees.push(tt);
+        }
+        Some(tt::Subtree { delimiter, token_trees })
+    }
+
+    fn items(&mut self) -> Option<Vec<RawItem>> {
+        let mut items = Vec::new();
+        for _ in 0..self.u32()? {
+            let attrs = self.attrs()?;
+            let tag = self.u8()?;
+            let idx = self.u32()?;
+            let kind = match tag {
+                0 => RawItemKind::Module(Idx::from_raw(idx.into())),
+                1 => RawItemKind::Import(Idx::from_raw(idx.into())),
+                2 => RawItemKind::Def(Idx::from_raw(idx.into())),
+                3 => RawItemKind::Macro(Idx::from_raw(idx.into())),
+                4 => RawItemKind::MacroDef(Idx::from_raw(idx.into())),
+                5 => RawItemKind::Impl(Idx::from_raw(idx.into())),
+                _ => return None,
+            };
+            items.push(RawItem { attrs, kind });
+        }
+        Some(items)
+    }
+}
@@ -7,7 +7,11 @@ mod primitives;
 use std::sync::Arc;
 
 use insta::assert_snapshot;
-use ra_db::{fixture::WithFixture, SourceDatabase};
+use ra_cfg::CfgOptions;
+use ra_db::{
+    fixture::WithFixture, CrateGraph, CrateName, Edition, Env, FileId, ProcMacro, ProcMacroKind,
+    SourceDatabase,
+};
 use test_utils::covers;
 
 use crate::{db::DefDatabase, nameres::*, test_db::TestDB};
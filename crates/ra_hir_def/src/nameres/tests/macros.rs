@@ -661,3 +661,233 @@ fn expand_multiple_derive() {
     );
     assert_eq!(map.modules[map.root].scope.impls().len(), 2);
 }
+
+#[test]
+fn macro_def_is_scoped_like_an_item() {
+    let map = def_map(
+        r"
+        //- /lib.rs
+        mod m {
+            pub macro structs($($i:ident),*) {
+                $(struct $i;)*
+            }
+            macro private {
+                () => {},
+                ($i:ident) => { struct $i; }
+            }
+            private!(Private);
+        }
+        use m::structs;
+        structs!(Foo, Bar);
+        m::structs!(Baz);
+        ",
+    );
+    assert_snapshot!(map, @r###"
+        ⋮crate
+        ⋮Bar: t v
+        ⋮Baz: t v
+        ⋮Foo: t v
+        ⋮m: t
+        ⋮structs: m
+        ⋮
+        ⋮crate::m
+        ⋮Private: t v
+        ⋮private: m
+        ⋮structs: m
+    "###);
+}
+
+#[derive(Debug)]
+struct AppendFn;
+
+impl tt::TokenExpander for AppendFn {
+    fn expand(
+        &self,
+        subtree: &tt::Subtree,
+        attrs: Option<&tt::Subtree>,
+    ) -> Result<tt::Subtree, tt::ExpansionError> {
+        // `#[append_fn(name)] item` expands to `item fn name() {}`
+        let name = attrs.map(|it| it.to_string()).unwrap_or_default();
+        let text = format!("{} fn {}() {{}}", subtree, name);
+        mbe::parse_to_token_tree(&text)
+            .map(|(it, _)| it)
+            .ok_or_else(|| tt::ExpansionError::Unknown("parse error".into()))
+    }
+}
+
+#[derive(Debug)]
+struct Identity;
+
+impl tt::TokenExpander for Identity {
+    fn expand(
+        &self,
+        subtree: &tt::Subtree,
+        _attrs: Option<&tt::Subtree>,
+    ) -> Result<tt::Subtree, tt::ExpansionError> {
+        Ok(tt::Subtree { delimiter: None, token_trees: subtree.token_trees.clone() })
+    }
+}
+
+#[derive(Debug)]
+struct AlwaysFail;
+
+impl tt::TokenExpander for AlwaysFail {
+    fn expand(
+        &self,
+        _subtree: &tt::Subtree,
+        _attrs: Option<&tt::Subtree>,
+    ) -> Result<tt::Subtree, tt::ExpansionError> {
+        Err(tt::ExpansionError::Unknown("always fails".into()))
+    }
+}
+
+/// Computes the def map of `/main.rs`, which depends on a `procs` crate with
+/// root `/procs.rs` providing the given proc macros.
+fn def_map_with_proc_macros(fixture: &str, proc_macros: Vec<ProcMacro>) -> String {
+    let mut db = TestDB::with_files(fixture);
+    let mut crate_graph = CrateGraph::default();
+    let main = crate_graph.add_crate_root(
+        FileId(0),
+        Edition::Edition2018,
+        None,
+        CfgOptions::default(),
+        Env::default(),
+        Default::default(),
+        Vec::new(),
+    );
+    let procs = crate_graph.add_crate_root(
+        FileId(1),
+        Edition::Edition2018,
+        CrateName::new("procs").ok(),
+        CfgOptions::default(),
+        Env::default(),
+        Default::default(),
+        proc_macros,
+    );
+    crate_graph.add_dep(main, CrateName::new("procs").unwrap(), procs).unwrap();
+    db.set_crate_graph(Arc::new(crate_graph));
+    db.crate_def_map(main).dump()
+}
+
+#[test]
+fn expand_attribute_proc_macro() {
+    let map = def_map_with_proc_macros(
+        r"
+        //- /main.rs
+        #[procs::append_fn(generated)]
+        struct S;
+
+        use procs::append_fn;
+        #[append_fn(imported)]
+        #[derive(Clone)]
+        struct T;
+
+        //- /procs.rs
+        ",
+        vec![ProcMacro {
+            name: "append_fn".into(),
+            kind: ProcMacroKind::Attr,
+            expander: Arc::new(AppendFn),
+        }],
+    );
+    assert_snapshot!(map, @r###"
+        ⋮crate
+        ⋮S: t v
+        ⋮T: t v
+        ⋮append_fn: m
+        ⋮generated: v
+        ⋮imported: v
+    "###);
+}
+
+#[test]
+fn unexpanded_attribute_items_are_collected_as_is() {
+    let map = def_map_with_proc_macros(
+        r"
+        //- /main.rs
+        #[procs::always_fail]
+        struct S;
+
+        #[serde(default)]
+        struct T;
+        use self::T as U;
+
+        #[rustfmt::skip]
+        fn f() {}
+
+        //- /procs.rs
+        ",
+        vec![ProcMacro {
+            name: "always_fail".into(),
+            kind: ProcMacroKind::Attr,
+            expander: Arc::new(AlwaysFail),
+        }],
+    );
+    assert_snapshot!(map, @r###"
+        ⋮crate
+        ⋮S: t v
+        ⋮T: t v
+        ⋮U: t v
+        ⋮f: v
+    "###);
+}
+
+#[test]
+fn expand_attribute_proc_macro_after_other_attributes() {
+    let map = def_map_with_proc_macros(
+        r"
+        //- /main.rs
+        #[serde(default)]
+        #[procs::append_fn(after_unresolved)]
+        struct S;
+
+        #[procs::always_fail]
+        #[procs::append_fn(after_failed)]
+        struct T;
+
+        //- /procs.rs
+        ",
+        vec![
+            ProcMacro {
+                name: "append_fn".into(),
+                kind: ProcMacroKind::Attr,
+                expander: Arc::new(AppendFn),
+            },
+            ProcMacro {
+                name: "always_fail".into(),
+                kind: ProcMacroKind::Attr,
+                expander: Arc::new(AlwaysFail),
+            },
+        ],
+    );
+    assert_snapshot!(map, @r###"
+        ⋮crate
+        ⋮S: t v
+        ⋮T: t v
+        ⋮after_failed: v
+        ⋮after_unresolved: v
+    "###);
+}
+
+#[test]
+fn expand_function_like_proc_macro() {
+    let map = def_map_with_proc_macros(
+        r"
+        //- /main.rs
+        procs::identity! {
+            struct S;
+        }
+
+        //- /procs.rs
+        ",
+        vec![ProcMacro {
+            name: "identity".into(),
+            kind: ProcMacroKind::FuncLike,
+            expander: Arc::new(Identity),
+        }],
+    );
+    assert_snapshot!(map, @r###"
+        ⋮crate
+        ⋮S: t v
+    "###);
+}
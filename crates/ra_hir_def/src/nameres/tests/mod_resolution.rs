@@ -724,7 +724,10 @@ fn unresolved_module_diagnostics() {
                     _ty: PhantomData,
                 },
             },
-            candidate: "bar.rs",
+            candidates: [
+                "bar.rs",
+                "bar/mod.rs",
+            ],
         },
     ]
     "###
@@ -16,7 +16,7 @@ use ra_db::CrateId;
 use ra_syntax::ast;
 
 use crate::{
-    type_ref::{TypeBound, TypeRef},
+    type_ref::{ConstRef, TypeBound, TypeRef},
     InFile,
 };
 
@@ -136,6 +136,9 @@ pub struct AssociatedTypeBinding {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum GenericArg {
     Type(TypeRef),
+    /// A literal or block argument for a const parameter, like `3` in `Foo<3>`.
+    /// Plain paths like `N` are always lowered as types.
+    Const(ConstRef),
     // or lifetime...
 }
 
@@ -15,7 +15,7 @@ use super::AssociatedTypeBinding;
 use crate::{
     body::LowerCtx,
     path::{GenericArg, GenericArgs, ModPath, Path, PathKind},
-    type_ref::{TypeBound, TypeRef},
+    type_ref::{ConstRef, TypeBound, TypeRef},
 };
 
 pub(super) use lower_use::lower_use_tree;
@@ -151,9 +151,24 @@ pub(super) fn lower_generic_args(
     node: ast::TypeArgList,
 ) -> Option<GenericArgs> {
     let mut args = Vec::new();
-    for type_arg in node.type_args() {
-        let type_ref = TypeRef::from_ast_opt(lower_ctx, type_arg.type_ref());
-        args.push(GenericArg::Type(type_ref));
+    for generic_arg in node.generic_args() {
+        match generic_arg {
+            ast::GenericArg::TypeArg(type_arg) => {
+                let type_ref = TypeRef::from_ast_opt(lower_ctx, type_arg.type_ref());
+                args.push(GenericArg::Type(type_ref));
+            }
+            ast::GenericArg::ConstArg(const_arg) => {
+                let expr = match (const_arg.literal(), const_arg.block_expr()) {
+                    (Some(literal), _) => Some(ast::Expr::Literal(literal)),
+                    (None, Some(block)) => Some(ast::Expr::BlockExpr(block)),
+                    (None, None) => None,
+                };
+                let const_ref =
+                    expr.map_or(ConstRef::Unknown, |expr| ConstRef::from_ast(lower_ctx, expr));
+                args.push(GenericArg::Const(const_ref));
+            }
+            ast::GenericArg::LifetimeArg(_) | ast::GenericArg::AssocTypeArg(_) => {}
+        }
     }
     // lifetimes ignored for now
     let mut bindings = Vec::new();
@@ -2,6 +2,7 @@
 use std::sync::Arc;
 
 use hir_expand::{
+    hygiene::SyntaxContext,
     name::{name, Name},
     MacroDefId,
 };
@@ -14,15 +15,15 @@ use crate::{
     builtin_type::BuiltinType,
     db::DefDatabase,
     expr::{ExprId, PatId},
-    generics::GenericParams,
+    generics::{GenericParams, TypeParamProvenance},
     item_scope::{BuiltinShadowMode, BUILTIN_SCOPE},
     nameres::CrateDefMap,
     path::{ModPath, PathKind},
     per_ns::PerNs,
     visibility::{RawVisibility, Visibility},
-    AdtId, AssocContainerId, ConstId, ContainerId, DefWithBodyId, EnumId, EnumVariantId,
-    FunctionId, GenericDefId, HasModule, ImplId, LocalModuleId, Lookup, ModuleDefId, ModuleId,
-    StaticId, StructId, TraitId, TypeAliasId, TypeParamId, VariantId,
+    AdtId, AssocContainerId, ConstId, ConstParamId, ContainerId, DefWithBodyId, EnumId,
+    EnumVariantId, FunctionId, GenericDefId, HasModule, ImplId, LocalModuleId, Lookup, ModuleDefId,
+    ModuleId, StaticId, StructId, TraitId, TypeAliasId, TypeParamId, VariantId,
 };
 
 #[derive(Debug, Clone, Default)]
@@ -92,6 +93,7 @@ pub enum ValueNs {
     StaticId(StaticId),
     StructId(StructId),
     EnumVariantId(EnumVariantId),
+    GenericParam(ConstParamId),
 }
 
 impl Resolver {
@@ -249,10 +251,13 @@ impl Resolver {
         }
     }
 
+    /// Resolves `path` in the value namespace. Local bindings are only found
+    /// if they are visible from the syntax context `hygiene` of the path.
     pub fn resolve_path_in_value_ns(
         &self,
         db: &dyn DefDatabase,
         path: &ModPath,
+        hygiene: SyntaxContext,
     ) -> Option<ResolveValueResult> {
         let n_segments = path.segments.len();
         let tmp = name![self];
@@ -271,11 +276,9 @@ impl Resolver {
                 }
 
                 Scope::ExprScope(scope) if n_segments <= 1 => {
-                    let entry = scope
-                        .expr_scopes
-                        .entries(scope.scope_id)
-                        .iter()
-                        .find(|entry| entry.name() == first_name);
+                    let entry = scope.expr_scopes.entries(scope.scope_id).iter().find(|entry| {
+                        entry.name() == first_name && hygiene.can_see(db.upcast(), entry.hygiene())
+                    });
 
                     if let Some(e) = entry {
                         return Some(ResolveValueResult::ValueNs(ValueNs::LocalBinding(e.pat())));
@@ -289,7 +292,12 @@ impl Resolver {
                         return Some(ResolveValueResult::Partial(ty, 1));
                     }
                 }
-                Scope::GenericParams { .. } => continue,
+                Scope::GenericParams { params, def } => {
+                    if let Some(local_id) = params.find_const_by_name(first_name) {
+                        let value = ValueNs::GenericParam(ConstParamId { local_id, parent: *def });
+                        return Some(ResolveValueResult::ValueNs(value));
+                    }
+                }
 
                 Scope::ImplDefScope(impl_) if n_segments > 1 => {
                     if first_name == &name![Self] {
@@ -369,8 +377,9 @@ impl Resolver {
         &self,
         db: &dyn DefDatabase,
         path: &ModPath,
+        hygiene: SyntaxContext,
     ) -> Option<ValueNs> {
-        match self.resolve_path_in_value_ns(db, path)? {
+        match self.resolve_path_in_value_ns(db, path, hygiene)? {
             ResolveValueResult::ValueNs(it) => Some(it),
             ResolveValueResult::Partial(..) => None,
         }
@@ -510,6 +519,9 @@ impl Scope {
             }
             Scope::GenericParams { params, def } => {
                 for (local_id, param) in params.types.iter() {
+                    if param.provenance == TypeParamProvenance::ConstParam {
+                        continue;
+                    }
                     if let Some(name) = &param.name {
                         f(
                             name.clone(),
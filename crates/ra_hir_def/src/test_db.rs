@@ -19,12 +19,20 @@ use crate::db::DefDatabase;
     crate::db::InternDatabaseStorage,
     crate::db::DefDatabaseStorage
 )]
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct TestDB {
     runtime: salsa::Runtime<TestDB>,
     events: Mutex<Option<Vec<salsa::Event<TestDB>>>>,
 }
 
+impl Default for TestDB {
+    fn default() -> Self {
+        let mut db = TestDB { runtime: Default::default(), events: Default::default() };
+        db.set_library_item_trees(Default::default());
+        db
+    }
+}
+
 impl Upcast<dyn AstDatabase> for TestDB {
     fn upcast(&self) -> &(dyn AstDatabase + 'static) {
         &*self
@@ -1,7 +1,7 @@
 //! HIR for references to types. Paths in these are not yet resolved. They can
 //! be directly created from an ast::TypeRef, without further queries.
 
-use ra_syntax::ast::{self, TypeAscriptionOwner, TypeBoundsOwner};
+use ra_syntax::ast::{self, PrefixOp, TypeAscriptionOwner, TypeBoundsOwner};
 
 use crate::{body::LowerCtx, path::Path};
 
@@ -44,7 +44,7 @@ pub enum TypeRef {
     Path(Path),
     RawPtr(Box<TypeRef>, Mutability),
     Reference(Box<TypeRef>, Mutability),
-    Array(Box<TypeRef> /*, Expr*/),
+    Array(Box<TypeRef>, ConstRef),
     Slice(Box<TypeRef>),
     /// A fn pointer. Last element of the vector is the return type.
     Fn(Vec<TypeRef>),
@@ -85,7 +85,8 @@ impl TypeRef {
                 TypeRef::RawPtr(Box::new(inner_ty), mutability)
             }
             ast::TypeRef::ArrayType(inner) => {
-                TypeRef::Array(Box::new(TypeRef::from_ast_opt(&ctx, inner.type_ref())))
+                let len = inner.expr().map_or(ConstRef::Unknown, |it| ConstRef::from_ast(ctx, it));
+                TypeRef::Array(Box::new(TypeRef::from_ast_opt(&ctx, inner.type_ref())), len)
             }
             ast::TypeRef::SliceType(inner) => {
                 TypeRef::Slice(Box::new(TypeRef::from_ast_opt(&ctx, inner.type_ref())))
@@ -145,7 +146,7 @@ impl TypeRef {
                 TypeRef::Fn(types) | TypeRef::Tuple(types) => types.iter().for_each(|t| go(t, f)),
                 TypeRef::RawPtr(type_ref, _)
                 | TypeRef::Reference(type_ref, _)
-                | TypeRef::Array(type_ref)
+                | TypeRef::Array(type_ref, _)
                 | TypeRef::Slice(type_ref) => go(&type_ref, f),
                 TypeRef::ImplTrait(bounds) | TypeRef::DynTrait(bounds) => {
                     for bound in bounds {
@@ -167,8 +168,9 @@ impl TypeRef {
             for segment in path.segments().iter() {
                 if let Some(args_and_bindings) = segment.args_and_bindings {
                     for arg in &args_and_bindings.args {
-                        let crate::path::GenericArg::Type(type_ref) = arg;
-                        go(type_ref, f);
+                        if let crate::path::GenericArg::Type(type_ref) = arg {
+                            go(type_ref, f);
+                        }
                     }
                     for binding in &args_and_bindings.bindings {
                         if let Some(type_ref) = &binding.type_ref {
@@ -224,3 +226,41 @@ impl TypeBound {
         }
     }
 }
+
+/// A constant expression outside of a body, like an enum discriminant. These
+/// aren't lowered into a `Body`, so we only support literals and paths to
+/// other constants here.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ConstRef {
+    Scalar(i128),
+    Path(Path),
+    Unknown,
+}
+
+impl ConstRef {
+    pub(crate) fn from_ast(ctx: &LowerCtx, expr: ast::Expr) -> Self {
+        match expr {
+            ast::Expr::Literal(lit) => {
+                lit.int_value().map_or(ConstRef::Unknown, |it| ConstRef::Scalar(it as i128))
+            }
+            ast::Expr::PrefixExpr(e) if e.op_kind() == Some(PrefixOp::Neg) => {
+                match e.expr().map(|inner| ConstRef::from_ast(ctx, inner)) {
+                    Some(ConstRef::Scalar(it)) => {
+                        it.checked_neg().map_or(ConstRef::Unknown, ConstRef::Scalar)
+                    }
+                    _ => ConstRef::Unknown,
+                }
+            }
+            ast::Expr::ParenExpr(e) => {
+                e.expr().map_or(ConstRef::Unknown, |inner| ConstRef::from_ast(ctx, inner))
+            }
+            ast::Expr::BlockExpr(e) if e.statements().next().is_none() => {
+                e.expr().map_or(ConstRef::Unknown, |inner| ConstRef::from_ast(ctx, inner))
+            }
+            ast::Expr::PathExpr(e) => {
+                e.path().and_then(|it| ctx.lower_path(it)).map_or(ConstRef::Unknown, ConstRef::Path)
+            }
+            _ => ConstRef::Unknown,
+        }
+    }
+}
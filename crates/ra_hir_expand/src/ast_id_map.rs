@@ -47,6 +47,17 @@ impl<N: AstNode> FileAstId<N> {
     {
         FileAstId { raw: self.raw, _ty: PhantomData }
     }
+
+    /// Returns the raw index of the node, e.g. to store the id on disk.
+    pub fn into_raw(self) -> u32 {
+        self.raw.into_raw().into()
+    }
+
+    /// The inverse of `into_raw`. The id is only meaningful in the file it was
+    /// created for.
+    pub fn from_raw(raw: u32) -> FileAstId<N> {
+        FileAstId { raw: Idx::from_raw(raw.into()), _ty: PhantomData }
+    }
 }
 
 type ErasedFileAstId = Idx<SyntaxNodePtr>;
@@ -58,7 +69,7 @@ pub struct AstIdMap {
 }
 
 impl AstIdMap {
-    pub(crate) fn from_source(node: &SyntaxNode) -> AstIdMap {
+    pub fn from_source(node: &SyntaxNode) -> AstIdMap {
         assert!(node.parent().is_none());
         let mut res = AstIdMap { arena: Arena::default() };
         // By walking the tree in breadth-first order we make sure that parents
@@ -64,7 +64,7 @@ macro_rules! register_builtin {
 pub fn find_builtin_macro(
     ident: &name::Name,
     krate: CrateId,
-    ast_id: AstId<ast::MacroCall>,
+    ast_id: Either<AstId<ast::MacroCall>, AstId<ast::MacroDef>>,
 ) -> Option<MacroDefId> {
     let kind = find_by_name(ident)?;
 
@@ -100,17 +100,27 @@ register_builtin! {
     EAGER:
     (concat, Concat) => concat_expand,
     (include, Include) => include_expand,
+    (include_str, IncludeStr) => include_str_expand,
     (env, Env) => env_expand,
     (option_env, OptionEnv) => option_env_expand
 }
 
 fn line_expand(
-    _db: &dyn AstDatabase,
-    _id: LazyMacroId,
+    db: &dyn AstDatabase,
+    id: LazyMacroId,
     _tt: &tt::Subtree,
 ) -> Result<tt::Subtree, mbe::ExpandError> {
-    // dummy implementation for type-checking purposes
-    let line_num = 0;
+    let loc = db.lookup_intern_macro(id);
+
+    // Like rustc, report the line of the outermost macro call for calls
+    // produced by other macros.
+    let mut call = loc.kind.node(db);
+    while let Some(outer) = call.file_id.call_node(db) {
+        call = outer;
+    }
+    let file_text = db.file_text(call.file_id.original_file(db));
+    let offset: usize = call.value.text_range().start().into();
+    let line_num = file_text.get(..offset).map_or(0, |text| text.matches('\n').count()) + 1;
     let expanded = quote! {
         #line_num
     };
@@ -283,7 +293,18 @@ fn concat_expand(
     for (i, t) in tt.token_trees.iter().enumerate() {
         match t {
             tt::TokenTree::Leaf(tt::Leaf::Literal(it)) if i % 2 == 0 => {
-                text += &unquote_str(&it).ok_or_else(|| mbe::ExpandError::ConversionError)?;
+                // concat works with string and char literals, so remove any quotes.
+                // It also works with integer, float and boolean literals, so just use the rest
+                // as-is.
+                match unquote_str(&it) {
+                    Some(s) => text += &s,
+                    None => text += it.text.trim_matches('\''),
+                }
+            }
+            tt::TokenTree::Leaf(tt::Leaf::Ident(id))
+                if i % 2 == 0 && (id.text == "true" || id.text == "false") =>
+            {
+                text += id.text.as_str();
             }
             tt::TokenTree::Leaf(tt::Leaf::Punct(punct)) if i % 2 == 1 && punct.char == ',' => (),
             _ => return Err(mbe::ExpandError::UnexpectedToken),
@@ -338,6 +359,24 @@ fn include_expand(
     Ok((res, FragmentKind::Items))
 }
 
+fn include_str_expand(
+    db: &dyn AstDatabase,
+    arg_id: EagerMacroId,
+    tt: &tt::Subtree,
+) -> Result<(tt::Subtree, FragmentKind), mbe::ExpandError> {
+    let path = parse_string(tt)?;
+
+    // Files outside of the source roots we know about can't be loaded, so we
+    // fall back to an empty string to still get the `&str` type right.
+    let text = match relative_file(db, arg_id.into(), &path) {
+        Some(file_id) => db.file_text(file_id).to_string(),
+        None => String::new(),
+    };
+    let expanded = quote! { #text };
+
+    Ok((expanded, FragmentKind::Expr))
+}
+
 fn get_env_inner(db: &dyn AstDatabase, arg_id: EagerMacroId, key: &str) -> Option<String> {
     let call_id: MacroCallId = arg_id.into();
     let original_file = call_id.as_file().original_file(db);
@@ -406,7 +445,10 @@ mod tests {
                 // the first one should be a macro_rules
                 let def = MacroDefId {
                     krate: Some(CrateId(0)),
-                    ast_id: Some(AstId::new(file_id.into(), ast_id_map.ast_id(&macro_calls[0]))),
+                    ast_id: Some(Either::Left(AstId::new(
+                        file_id.into(),
+                        ast_id_map.ast_id(&macro_calls[0]),
+                    ))),
                     kind: MacroDefKind::BuiltIn(expander),
                     local_inner: false,
                 };
@@ -426,7 +468,10 @@ mod tests {
                 // the first one should be a macro_rules
                 let def = MacroDefId {
                     krate: Some(CrateId(0)),
-                    ast_id: Some(AstId::new(file_id.into(), ast_id_map.ast_id(&macro_calls[0]))),
+                    ast_id: Some(Either::Left(AstId::new(
+                        file_id.into(),
+                        ast_id_map.ast_id(&macro_calls[0]),
+                    ))),
                     kind: MacroDefKind::BuiltInEager(expander),
                     local_inner: false,
                 };
@@ -482,7 +527,7 @@ mod tests {
             "#,
         );
 
-        assert_eq!(expanded, "0");
+        assert_eq!(expanded, "4");
     }
 
     #[test]
@@ -524,6 +569,19 @@ mod tests {
         assert_eq!(expanded, "std::option::Option::None:: < &str>");
     }
 
+    #[test]
+    fn test_concat_expand() {
+        let expanded = expand_builtin_macro(
+            r#"
+            #[rustc_builtin_macro]
+            macro_rules! concat {() => {}}
+            concat!("foo", 0, 'r', true, false, 1.5, "bar",)
+            "#,
+        );
+
+        assert_eq!(expanded, "\"foo0rtruefalse1.5bar\"");
+    }
+
     #[test]
     fn test_file_expand() {
         let expanded = expand_builtin_macro(
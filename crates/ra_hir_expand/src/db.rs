@@ -2,6 +2,7 @@
 
 use std::sync::Arc;
 
+use either::Either;
 use mbe::{ExpandResult, MacroRules};
 use ra_db::{salsa, SourceDatabase};
 use ra_parser::FragmentKind;
@@ -113,13 +114,16 @@ pub(crate) fn macro_def(
 ) -> Option<Arc<(TokenExpander, mbe::TokenMap)>> {
     match id.kind {
         MacroDefKind::Declarative => {
-            let macro_call = id.ast_id?.to_node(db);
-            let arg = macro_call.token_tree()?;
+            let arg = id.def_token_tree(db)?.value;
             let (tt, tmap) = mbe::ast_to_token_tree(&arg).or_else(|| {
                 log::warn!("fail on macro_def to token tree: {:#?}", arg);
                 None
             })?;
-            let rules = match MacroRules::parse(&tt) {
+            let rules = match id.ast_id? {
+                Either::Left(_) => MacroRules::parse(&tt),
+                Either::Right(_) => MacroRules::parse_macro2(&tt),
+            };
+            let rules = match rules {
                 Ok(it) => it,
                 Err(err) => {
                     log::warn!("fail on macro_def parse: error: {:#?} {:#?}", err, tt);
@@ -135,7 +139,9 @@ pub(crate) fn macro_def(
             Some(Arc::new((TokenExpander::BuiltinDerive(expander), mbe::TokenMap::default())))
         }
         MacroDefKind::BuiltInEager(_) => None,
-        MacroDefKind::CustomDerive(expander) => {
+        MacroDefKind::CustomDerive(expander)
+        | MacroDefKind::ProcMacroAttr(expander)
+        | MacroDefKind::ProcMacro(expander) => {
             Some(Arc::new((TokenExpander::ProcMacro(expander), mbe::TokenMap::default())))
         }
     }
@@ -21,6 +21,9 @@ use ra_syntax::{SyntaxNode, SyntaxNodePtr};
 use crate::{db::AstDatabase, InFile};
 
 pub trait Diagnostic: Any + Send + Sync + fmt::Debug + 'static {
+    /// A stable, kebab-case name of the kind of this diagnostic, which users
+    /// can refer to, for example to disable it.
+    fn code(&self) -> &'static str;
     fn message(&self) -> String;
     fn source(&self) -> InFile<SyntaxNodePtr>;
     fn as_any(&self) -> &(dyn Any + Send + 'static);
@@ -1,15 +1,17 @@
 //! This modules handles hygiene information.
 //!
-//! Specifically, `ast` + `Hygiene` allows you to create a `Name`. Note that, at
-//! this moment, this is horribly incomplete and handles only `$crate`.
+//! Specifically, `ast` + `Hygiene` allows you to create a `Name`, resolving
+//! `$crate` along the way. Local bindings are kept apart using
+//! `SyntaxContext`, which records the macro expansion an identifier was
+//! introduced by.
 use either::Either;
 use ra_db::CrateId;
-use ra_syntax::ast;
+use ra_syntax::{ast, SyntaxToken};
 
 use crate::{
     db::AstDatabase,
     name::{AsName, Name},
-    HirFileId, HirFileIdRepr, MacroCallId, MacroDefKind,
+    HirFileId, HirFileIdRepr, InFile, MacroCallId, MacroDefKind, Origin,
 };
 
 #[derive(Clone, Debug)]
@@ -34,6 +36,8 @@ impl Hygiene {
                         MacroDefKind::BuiltInDerive(_) => (None, false),
                         MacroDefKind::BuiltInEager(_) => (None, false),
                         MacroDefKind::CustomDerive(_) => (None, false),
+                        MacroDefKind::ProcMacroAttr(_) => (None, false),
+                        MacroDefKind::ProcMacro(_) => (None, false),
                     }
                 }
                 MacroCallId::EagerMacro(_id) => (None, false),
@@ -64,3 +68,69 @@ impl Hygiene {
         }
     }
 }
+
+/// The macro expansion an identifier was introduced by.
+///
+/// Identifiers written in a macro definition belong to the expansion of each
+/// call of that macro, while identifiers passed as macro arguments keep the
+/// context of the place they were written in. An identifier can only refer to
+/// local bindings from its own context or from one of the contexts it is
+/// nested in, so a `let x` in a macro body doesn't capture an `x` from the call
+/// site, and vice versa.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SyntaxContext(Option<MacroCallId>);
+
+impl SyntaxContext {
+    /// The context of identifiers written directly in a source file.
+    pub const ROOT: SyntaxContext = SyntaxContext(None);
+
+    pub fn of_token(db: &dyn AstDatabase, token: InFile<SyntaxToken>) -> SyntaxContext {
+        let mut token = token;
+        loop {
+            let macro_file = match token.file_id.0 {
+                HirFileIdRepr::FileId(_) => return SyntaxContext::ROOT,
+                HirFileIdRepr::MacroFile(it) => it,
+            };
+            let ctx = SyntaxContext(Some(macro_file.macro_call_id));
+            let mapped =
+                token.file_id.expansion_info(db).and_then(|info| info.map_token_up(token.as_ref()));
+            match mapped {
+                Some((call_site, Origin::Call)) => token = call_site,
+                Some((_, Origin::Def)) => return ctx,
+                // Tokens we can't trace back, like the ones created by
+                // builtin macros, behave as if they were written at the call
+                // site.
+                None => return ctx.parent(db),
+            }
+        }
+    }
+
+    /// The context of the macro call which created this context.
+    pub fn parent(self, db: &dyn AstDatabase) -> SyntaxContext {
+        match self.0 {
+            Some(MacroCallId::LazyMacro(id)) => {
+                let call = db.lookup_intern_macro(id).kind.node(db);
+                match call.value.first_token() {
+                    Some(token) => SyntaxContext::of_token(db, call.with_value(token)),
+                    None => SyntaxContext::ROOT,
+                }
+            }
+            Some(MacroCallId::EagerMacro(_)) | None => SyntaxContext::ROOT,
+        }
+    }
+
+    /// Whether an identifier in this context can refer to a binding
+    /// introduced in `binding`.
+    pub fn can_see(self, db: &dyn AstDatabase, binding: SyntaxContext) -> bool {
+        let mut ctx = self;
+        loop {
+            if ctx == binding {
+                return true;
+            }
+            if ctx == SyntaxContext::ROOT {
+                return false;
+            }
+            ctx = ctx.parent(db);
+        }
+    }
+}
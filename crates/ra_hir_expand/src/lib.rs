@@ -18,6 +18,7 @@ pub mod eager;
 use std::hash::Hash;
 use std::sync::Arc;
 
+use either::Either;
 use ra_db::{impl_intern_key, salsa, CrateId, FileId};
 use ra_syntax::{
     algo,
@@ -106,6 +107,10 @@ impl HirFileId {
         }
     }
 
+    pub fn is_macro_file(self) -> bool {
+        matches!(self.0, HirFileIdRepr::MacroFile(_))
+    }
+
     /// Return expansion information if it is a macro-expansion file
     pub fn expansion_info(self, db: &dyn db::AstDatabase) -> Option<ExpansionInfo> {
         match self.0 {
@@ -121,7 +126,7 @@ impl HirFileId {
                 let loc: MacroCallLoc = db.lookup_intern_macro(lazy_id);
 
                 let arg_tt = loc.kind.arg(db)?;
-                let def_tt = loc.def.ast_id?.to_node(db).token_tree()?;
+                let def = loc.def.def_token_tree(db)?;
 
                 let macro_def = db.macro_def(loc.def)?;
                 let (parse, exp_map) = db.parse_macro(macro_file)?;
@@ -130,7 +135,7 @@ impl HirFileId {
                 Some(ExpansionInfo {
                     expanded: InFile::new(self, parse.syntax_node()),
                     arg: InFile::new(loc.kind.file_id(), arg_tt),
-                    def: InFile::new(loc.def.ast_id?.file_id, def_tt),
+                    def,
                     macro_arg,
                     macro_def,
                     exp_map,
@@ -198,11 +203,12 @@ pub struct MacroDefId {
     // FIXME: krate and ast_id are currently optional because we don't have a
     // definition location for built-in derives. There is one, though: the
     // standard library defines them. The problem is that it uses the new
-    // `macro` syntax for this, which we don't support yet. As soon as we do
-    // (which will probably require touching this code), we can instead use
-    // that (and also remove the hacks for resolving built-in derives).
+    // `macro` syntax for this, and we don't resolve derives through those
+    // definitions yet. As soon as we do, we can instead use that (and also
+    // remove the hacks for resolving built-in derives).
     pub krate: Option<CrateId>,
-    pub ast_id: Option<AstId<ast::MacroCall>>,
+    /// Either a `macro_rules!` call or a macros 2.0 `macro` item.
+    pub ast_id: Option<Either<AstId<ast::MacroCall>, AstId<ast::MacroDef>>>,
     pub kind: MacroDefKind,
 
     pub local_inner: bool,
@@ -212,6 +218,15 @@ impl MacroDefId {
     pub fn as_lazy_macro(self, db: &dyn db::AstDatabase, kind: MacroCallKind) -> LazyMacroId {
         db.intern_macro(MacroCallLoc { def: self, kind })
     }
+
+    /// The token tree containing the rules of this macro, if it has a source.
+    pub fn def_token_tree(&self, db: &dyn db::AstDatabase) -> Option<InFile<ast::TokenTree>> {
+        let tt = match self.ast_id? {
+            Either::Left(ast_id) => ast_id.with_value(ast_id.to_node(db).token_tree()?),
+            Either::Right(ast_id) => ast_id.with_value(ast_id.to_node(db).token_tree()?),
+        };
+        Some(tt)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -222,6 +237,8 @@ pub enum MacroDefKind {
     BuiltInDerive(BuiltinDeriveExpander),
     BuiltInEager(EagerExpander),
     CustomDerive(ProcMacroExpander),
+    ProcMacroAttr(ProcMacroExpander),
+    ProcMacro(ProcMacroExpander),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
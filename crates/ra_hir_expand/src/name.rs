@@ -37,6 +37,13 @@ impl Name {
         Name(Repr::TupleField(idx))
     }
 
+    /// Recreates a name from the text it displays as, e.g. when loading a name
+    /// that was stored on disk. Unlike names created from tokens, `r#` is not
+    /// stripped.
+    pub fn from_stored_text(text: SmolStr) -> Name {
+        Name::new_text(text)
+    }
+
     /// Shortcut to create inline plain text name
     const fn new_inline_ascii(text: &[u8]) -> Name {
         Name::new_text(SmolStr::new_inline_from_ascii(text.len(), text))
@@ -185,6 +192,7 @@ pub mod known {
         stringify,
         concat,
         include,
+        include_str,
         format_args,
         format_args_nl,
         env,
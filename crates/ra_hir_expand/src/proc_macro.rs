@@ -1,7 +1,7 @@
 //! Proc Macro Expander stub
 
-use crate::{db::AstDatabase, LazyMacroId};
-use ra_db::{CrateId, ProcMacroId};
+use crate::{db::AstDatabase, LazyMacroId, MacroCallKind};
+use ra_db::{CrateId, ProcMacroId, ProcMacroKind};
 use tt::buffer::{Cursor, TokenBuffer};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
@@ -27,7 +27,7 @@ impl ProcMacroExpander {
     pub fn expand(
         &self,
         db: &dyn AstDatabase,
-        _id: LazyMacroId,
+        id: LazyMacroId,
         tt: &tt::Subtree,
     ) -> Result<tt::Subtree, mbe::ExpandError> {
         let krate_graph = db.crate_graph();
@@ -35,12 +35,29 @@ impl ProcMacroExpander {
             .proc_macro
             .get(self.proc_macro_id.0 as usize)
             .clone()
-            .ok_or_else(|| err!("No derive macro found."))?;
-
-        let tt = remove_derive_attrs(tt)
-            .ok_or_else(|| err!("Fail to remove derive for custom derive"))?;
+            .ok_or_else(|| err!("No proc macro found."))?;
+
+        match proc_macro.kind {
+            ProcMacroKind::Attr => {
+                let attr_name = match db.lookup_intern_macro(id).kind {
+                    MacroCallKind::Attr(_, name) => name,
+                    MacroCallKind::FnLike(_) => return Err(err!("Attribute macro used as bang")),
+                };
+                let (tt, attr) = remove_attr_invocation(tt, &attr_name)
+                    .ok_or_else(|| err!("Fail to find attribute {}", attr_name))?;
+
+                proc_macro.expander.expand(&tt, Some(&attr)).map_err(mbe::ExpandError::from)
+            }
+            ProcMacroKind::CustomDerive => {
+                let tt = remove_derive_attrs(tt)
+                    .ok_or_else(|| err!("Fail to remove derive for custom derive"))?;
 
-        proc_macro.expander.expand(&tt, None).map_err(mbe::ExpandError::from)
+                proc_macro.expander.expand(&tt, None).map_err(mbe::ExpandError::from)
+            }
+            ProcMacroKind::FuncLike => {
+                proc_macro.expander.expand(tt, None).map_err(mbe::ExpandError::from)
+            }
+        }
     }
 }
 
@@ -100,6 +117,58 @@ fn remove_derive_attrs(tt: &tt::Subtree) -> Option<tt::Subtree> {
     Some(result)
 }
 
+/// Removes the first `#[name]` or `#[path::to::name(args)]` attribute from
+/// `tt`, returning the rest of the item and the `args` of the attribute.
+fn remove_attr_invocation(tt: &tt::Subtree, name: &str) -> Option<(tt::Subtree, tt::Subtree)> {
+    let mut result = tt::Subtree::default();
+    let mut attr_args = None;
+
+    let mut i = 0;
+    while i < tt.token_trees.len() {
+        if attr_args.is_none() {
+            if let Some(args) = attr_invocation_args(&tt.token_trees[i..], name) {
+                attr_args = Some(args);
+                // skip both the `#` and the `[...]`
+                i += 2;
+                continue;
+            }
+        }
+        result.token_trees.push(tt.token_trees[i].clone());
+        i += 1;
+    }
+
+    Some((result, attr_args?))
+}
+
+/// If `tokens` start with `#[...name(args)]`, returns `args`.
+fn attr_invocation_args(tokens: &[tt::TokenTree], name: &str) -> Option<tt::Subtree> {
+    match tokens {
+        [tt::TokenTree::Leaf(tt::Leaf::Punct(punct)), tt::TokenTree::Subtree(attr), ..]
+            if punct.char == '#' && attr.delimiter_kind() == Some(tt::DelimiterKind::Bracket) =>
+        {
+            let path_len = attr
+                .token_trees
+                .iter()
+                .take_while(|it| match it {
+                    tt::TokenTree::Leaf(tt::Leaf::Ident(_)) => true,
+                    tt::TokenTree::Leaf(tt::Leaf::Punct(p)) => p.char == ':',
+                    _ => false,
+                })
+                .count();
+            match attr.token_trees[..path_len].last()? {
+                tt::TokenTree::Leaf(tt::Leaf::Ident(ident)) if ident.text == name => {}
+                _ => return None,
+            }
+            let args = match attr.token_trees.get(path_len) {
+                Some(tt::TokenTree::Subtree(args)) => args.token_trees.clone(),
+                _ => Vec::new(),
+            };
+            Some(tt::Subtree { delimiter: None, token_trees: args })
+        }
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -140,4 +209,21 @@ SUBTREE $
             .trim()
         );
     }
+
+    #[test]
+    fn test_remove_attr_invocation() {
+        let tt = mbe::parse_to_token_tree(
+            r#"
+    #[allow(unused)]
+    #[tokio::main(basic_scheduler)]
+    async fn main() {}
+"#,
+        )
+        .unwrap()
+        .0;
+        let (item, args) = remove_attr_invocation(&tt, "main").unwrap();
+
+        assert_eq!(item.to_string(), "# [allow (unused)] async fn main () {}");
+        assert_eq!(args.to_string(), "basic_scheduler");
+    }
 }
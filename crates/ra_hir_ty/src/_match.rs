@@ -187,17 +187,27 @@
 //!         - If `p_1 == r_1 | r_2`, then the usefulness depends on each `r_i` separately:
 //!           `U(P, p) := U(P, (r_1, p_2, .., p_n))
 //!                    || U(P, (r_2, p_2, .., p_n))`
-use std::sync::Arc;
+use std::{iter, sync::Arc};
 
 use smallvec::{smallvec, SmallVec};
 
 use crate::{
+    consteval::{int_max, int_min, ComputedExpr},
     db::HirDatabase,
-    expr::{Body, Expr, Literal, Pat, PatId},
+    expr::{Body, Expr, ExprId, Literal, Pat, PatId, UnaryOp},
+    primitive::{IntBitness, IntTy, Signedness, Uncertain},
+    utils::variant_data,
     ApplicationTy, InferenceResult, Ty, TypeCtor,
 };
-use hir_def::{adt::VariantData, AdtId, EnumVariantId, VariantId};
+use hir_def::{
+    adt::VariantData,
+    path::Path,
+    resolver::{resolver_for_expr, ValueNs},
+    AdtId, DefWithBodyId, EnumVariantId, HasModule, StructId, VariantId,
+};
+use hir_expand::hygiene::SyntaxContext;
 use ra_arena::Idx;
+use ra_syntax::ast::RangeOp;
 
 #[derive(Debug, Clone, Copy)]
 /// Either a pattern from the source code being analyzed, represented as
@@ -214,15 +224,28 @@ enum PatIdOrWild {
 }
 
 impl PatIdOrWild {
-    fn as_pat(self, cx: &MatchCheckCtx) -> Pat {
+    /// Looks through bindings: a plain binding `x` matches like `_`, and
+    /// `x @ p` matches like `p`.
+    fn normalize(self, cx: &MatchCheckCtx) -> PatIdOrWild {
         match self {
+            PatIdOrWild::PatId(id) => match cx.body.pats[id] {
+                Pat::Bind { subpat: None, .. } => PatIdOrWild::Wild,
+                Pat::Bind { subpat: Some(subpat), .. } => PatIdOrWild::PatId(subpat).normalize(cx),
+                _ => self,
+            },
+            PatIdOrWild::Wild => self,
+        }
+    }
+
+    fn as_pat(self, cx: &MatchCheckCtx) -> Pat {
+        match self.normalize(cx) {
             PatIdOrWild::PatId(id) => cx.body.pats[id].clone(),
             PatIdOrWild::Wild => Pat::Wild,
         }
     }
 
-    fn as_id(self) -> Option<PatId> {
-        match self {
+    fn as_id(self, cx: &MatchCheckCtx) -> Option<PatId> {
+        match self.normalize(cx) {
             PatIdOrWild::PatId(id) => Some(id),
             PatIdOrWild::Wild => None,
         }
@@ -334,117 +357,97 @@ impl PatStack {
         constructor: &Constructor,
     ) -> MatchCheckResult<Option<PatStack>> {
         let result = match (self.head().as_pat(cx), constructor) {
-            (Pat::Tuple { args: ref pat_ids, ellipsis }, Constructor::Tuple { arity: _ }) => {
-                if ellipsis.is_some() {
-                    // If there are ellipsis here, we should add the correct number of
-                    // Pat::Wild patterns to `pat_ids`. We should be able to use the
-                    // constructors arity for this, but at the time of writing we aren't
-                    // correctly calculating this arity when ellipsis are present.
-                    return Err(MatchCheckErr::NotImplemented);
-                }
-
-                Some(self.replace_head_with(pat_ids.iter()))
-            }
-            (Pat::Lit(lit_expr), Constructor::Bool(constructor_val)) => {
-                match cx.body.exprs[lit_expr] {
-                    Expr::Literal(Literal::Bool(pat_val)) if *constructor_val == pat_val => {
+            (Pat::Wild, constructor) => Some(self.expand_wildcard(cx, constructor)?),
+            (Pat::Tuple { args: ref pat_ids, ellipsis }, Constructor::Tuple { arity }) => {
+                let patterns = expand_ellipsis(pat_ids, ellipsis, *arity)?;
+                Some(self.replace_head_with(patterns.into_iter()))
+            }
+            (Pat::Ref { pat, .. }, Constructor::Ref) => {
+                Some(self.replace_head_with(iter::once(pat)))
+            }
+            // A non-reference pattern matched against a reference is
+            // implicitly dereferenced, so it keeps matching the pointee.
+            (_, Constructor::Ref) => Some(self.replace_head_with(iter::once(self.head()))),
+            (_, Constructor::Bool(_)) | (_, Constructor::IntRange(_)) => {
+                // Literals, ranges and constants all produce a constructor
+                // carrying their value(s), so we compare those directly.
+                match pat_constructor(cx, self.head())? {
+                    Some(pat_constructor) if pat_constructor.covers(constructor) => {
                         Some(self.to_tail())
                     }
-                    // it was a bool but the value doesn't match
-                    Expr::Literal(Literal::Bool(_)) => None,
-                    // perhaps this is actually unreachable given we have
-                    // already checked that these match arms have the appropriate type?
+                    Some(Constructor::Bool(_)) | Some(Constructor::IntRange(_)) => None,
                     _ => return Err(MatchCheckErr::NotImplemented),
                 }
             }
-            (Pat::Wild, constructor) => Some(self.expand_wildcard(cx, constructor)?),
-            (Pat::Path(_), Constructor::Enum(constructor)) => {
-                // unit enum variants become `Pat::Path`
-                let pat_id = self.head().as_id().expect("we know this isn't a wild");
-                if !enum_variant_matches(cx, pat_id, *constructor) {
-                    None
-                } else {
-                    Some(self.to_tail())
-                }
+            (_, Constructor::Enum(_)) | (_, Constructor::Struct(_)) => {
+                self.specialize_variant(cx, constructor)?
             }
-            (
-                Pat::TupleStruct { args: ref pat_ids, ellipsis, .. },
-                Constructor::Enum(enum_constructor),
-            ) => {
-                let pat_id = self.head().as_id().expect("we know this isn't a wild");
-                if !enum_variant_matches(cx, pat_id, *enum_constructor) {
+            (Pat::Slice { ref prefix, slice, ref suffix }, Constructor::Slice(constructor)) => {
+                let kind = SliceKind::of_pat(prefix, slice, suffix);
+                if !kind.covers(constructor.kind) {
                     None
                 } else {
-                    let constructor_arity = constructor.arity(cx)?;
-                    if let Some(ellipsis_position) = ellipsis {
-                        // If there are ellipsis in the pattern, the ellipsis must take the place
-                        // of at least one sub-pattern, so `pat_ids` should be smaller than the
-                        // constructor arity.
-                        if pat_ids.len() < constructor_arity {
-                            let mut new_patterns: Vec<PatIdOrWild> = vec![];
-
-                            for pat_id in &pat_ids[0..ellipsis_position] {
-                                new_patterns.push((*pat_id).into());
-                            }
-
-                            for _ in 0..(constructor_arity - pat_ids.len()) {
-                                new_patterns.push(PatIdOrWild::Wild);
-                            }
-
-                            for pat_id in &pat_ids[ellipsis_position..pat_ids.len()] {
-                                new_patterns.push((*pat_id).into());
-                            }
-
-                            Some(self.replace_head_with(new_patterns.into_iter()))
-                        } else {
-                            return Err(MatchCheckErr::MalformedMatchArm);
-                        }
-                    } else {
-                        // If there is no ellipsis in the tuple pattern, the number
-                        // of patterns must equal the constructor arity.
-                        if pat_ids.len() == constructor_arity {
-                            Some(self.replace_head_with(pat_ids.into_iter()))
-                        } else {
-                            return Err(MatchCheckErr::MalformedMatchArm);
-                        }
-                    }
-                }
-            }
-            (Pat::Record { args: ref arg_patterns, .. }, Constructor::Enum(e)) => {
-                let pat_id = self.head().as_id().expect("we know this isn't a wild");
-                if !enum_variant_matches(cx, pat_id, *e) {
-                    None
-                } else {
-                    match cx.db.enum_data(e.parent).variants[e.local_id].variant_data.as_ref() {
-                        VariantData::Record(struct_field_arena) => {
-                            // Here we treat any missing fields in the record as the wild pattern, as
-                            // if the record has ellipsis. We want to do this here even if the
-                            // record does not contain ellipsis, because it allows us to continue
-                            // enforcing exhaustiveness for the rest of the match statement.
-                            //
-                            // Creating the diagnostic for the missing field in the pattern
-                            // should be done in a different diagnostic.
-                            let patterns = struct_field_arena.iter().map(|(_, struct_field)| {
-                                arg_patterns
-                                    .iter()
-                                    .find(|pat| pat.name == struct_field.name)
-                                    .map(|pat| PatIdOrWild::from(pat.pat))
-                                    .unwrap_or(PatIdOrWild::Wild)
-                            });
-
-                            Some(self.replace_head_with(patterns))
-                        }
-                        _ => return Err(MatchCheckErr::Unknown),
-                    }
+                    let wild_count = constructor.kind.arity() - prefix.len() - suffix.len();
+                    let patterns = prefix
+                        .iter()
+                        .map(PatIdOrWild::from)
+                        .chain(iter::repeat(PatIdOrWild::Wild).take(wild_count))
+                        .chain(suffix.iter().map(PatIdOrWild::from));
+                    Some(self.replace_head_with(patterns))
                 }
             }
-            (Pat::Or(_), _) => return Err(MatchCheckErr::NotImplemented),
             (_, _) => return Err(MatchCheckErr::NotImplemented),
         };
 
         Ok(result)
     }
 
+    /// `S(constructor, self)` for struct and enum variant constructors.
+    fn specialize_variant(
+        &self,
+        cx: &MatchCheckCtx,
+        constructor: &Constructor,
+    ) -> MatchCheckResult<Option<PatStack>> {
+        let pat_id = self.head().as_id(cx).expect("we know this isn't a wild");
+        let variant_id = constructor.variant_id().expect("only called for variants");
+        if cx.infer.variant_resolution_for_pat(pat_id) != Some(variant_id) {
+            return Ok(None);
+        }
+        let variant_data = variant_data(cx.db.upcast(), variant_id);
+        let constructor_arity = variant_data.fields().len();
+
+        let result = match (&cx.body.pats[pat_id], variant_data.as_ref()) {
+            // unit variants and structs become `Pat::Path`
+            (Pat::Path(_), _) if constructor_arity == 0 => self.to_tail(),
+            (Pat::TupleStruct { args: pat_ids, ellipsis, .. }, _) => {
+                let patterns = expand_ellipsis(pat_ids, *ellipsis, constructor_arity)?;
+                self.replace_head_with(patterns.into_iter())
+            }
+            (Pat::Record { args: arg_patterns, .. }, VariantData::Record(struct_field_arena)) => {
+                // Here we treat any missing fields in the record as the wild pattern, as
+                // if the record has ellipsis. We want to do this here even if the
+                // record does not contain ellipsis, because it allows us to continue
+                // enforcing exhaustiveness for the rest of the match statement.
+                //
+                // Creating the diagnostic for the missing field in the pattern
+                // should be done in a different diagnostic.
+                let patterns = struct_field_arena.iter().map(|(_, struct_field)| {
+                    arg_patterns
+                        .iter()
+                        .find(|pat| pat.name == struct_field.name)
+                        .map(|pat| PatIdOrWild::from(pat.pat))
+                        .unwrap_or(PatIdOrWild::Wild)
+                });
+
+                self.replace_head_with(patterns)
+            }
+            (Pat::Record { .. }, _) => return Err(MatchCheckErr::Unknown),
+            _ => return Err(MatchCheckErr::MalformedMatchArm),
+        };
+
+        Ok(Some(result))
+    }
+
     /// A special case of `specialize_constructor` where the head of the pattern stack
     /// is a Wild pattern.
     ///
@@ -475,6 +478,31 @@ impl PatStack {
     }
 }
 
+/// Replaces the `..` in a tuple or tuple struct pattern with as many wild
+/// patterns as needed to reach `arity` sub-patterns.
+fn expand_ellipsis(
+    pat_ids: &[PatId],
+    ellipsis: Option<usize>,
+    arity: usize,
+) -> MatchCheckResult<Vec<PatIdOrWild>> {
+    match ellipsis {
+        // The ellipsis can stand for zero or more sub-patterns, so there
+        // can't be more patterns than the constructor arity.
+        Some(ellipsis_position) if pat_ids.len() <= arity => {
+            let (prefix, suffix) = pat_ids.split_at(ellipsis_position);
+            Ok(prefix
+                .iter()
+                .map(PatIdOrWild::from)
+                .chain(iter::repeat(PatIdOrWild::Wild).take(arity - pat_ids.len()))
+                .chain(suffix.iter().map(PatIdOrWild::from))
+                .collect())
+        }
+        // Without ellipsis, the number of patterns must equal the constructor arity.
+        None if pat_ids.len() == arity => Ok(pat_ids.iter().map(PatIdOrWild::from).collect()),
+        _ => Err(MatchCheckErr::MalformedMatchArm),
+    }
+}
+
 #[derive(Debug)]
 /// A collection of PatStack.
 ///
@@ -488,9 +516,9 @@ impl Matrix {
 
     pub(crate) fn push(&mut self, cx: &MatchCheckCtx, row: PatStack) {
         if let Some(Pat::Or(pat_ids)) = row.get_head().map(|pat_id| pat_id.as_pat(cx)) {
-            // Or patterns are expanded here
+            // Or patterns are expanded here, keeping the rest of the row intact
             for pat_id in pat_ids {
-                self.0.push(PatStack::from_pattern(pat_id));
+                self.push(cx, row.replace_head_with(iter::once(pat_id)));
             }
         } else {
             self.0.push(row);
@@ -554,6 +582,7 @@ pub enum Usefulness {
 }
 
 pub struct MatchCheckCtx<'a> {
+    pub owner: DefWithBodyId,
     pub match_expr: Idx<Expr>,
     pub body: Arc<Body>,
     pub infer: Arc<InferenceResult>,
@@ -592,7 +621,7 @@ pub(crate) fn is_useful(
     if let Pat::Or(pat_ids) = v.head().as_pat(cx) {
         let mut found_unimplemented = false;
         let any_useful = pat_ids.iter().any(|&pat_id| {
-            let v = PatStack::from_pattern(pat_id);
+            let v = v.replace_head_with(iter::once(pat_id));
 
             match is_useful(cx, matrix, &v) {
                 Ok(Usefulness::Useful) => true,
@@ -613,30 +642,47 @@ pub(crate) fn is_useful(
         };
     }
 
+    let mut used_constructors: Vec<Constructor> = vec![];
+    for pat in matrix.heads() {
+        if let Some(constructor) = pat_constructor(cx, pat)? {
+            used_constructors.push(constructor);
+        }
+    }
+
     if let Some(constructor) = pat_constructor(cx, v.head())? {
-        let matrix = matrix.specialize_constructor(&cx, &constructor)?;
-        let v = v
-            .specialize_constructor(&cx, &constructor)?
-            .expect("we know this can't fail because we get the constructor from `v.head()` above");
+        // Constructors which stand for many values, like integer ranges or
+        // variable length slices, are split into pieces which are each either
+        // fully covered by or disjoint from every constructor in the matrix.
+        let constructors = constructor.split(cx, &used_constructors)?;
 
-        is_useful(&cx, &matrix, &v)
+        is_useful_specialized(cx, matrix, v, constructors)
     } else {
         // expanding wildcard
-        let mut used_constructors: Vec<Constructor> = vec![];
-        for pat in matrix.heads() {
-            if let Some(constructor) = pat_constructor(cx, pat)? {
-                used_constructors.push(constructor);
-            }
-        }
 
         // We assume here that the first constructor is the "correct" type. Since we
         // only care about the "type" of the constructor (i.e. if it is a bool we
         // don't care about the value), this assumption should be valid as long as
         // the match statement is well formed. We currently uphold this invariant by
         // filtering match arms before calling `is_useful`, only passing in match arms
-        // whose type matches the type of the match expression.
-        match &used_constructors.first() {
-            Some(constructor) if all_constructors_covered(&cx, constructor, &used_constructors) => {
+        // whose type matches the type of the match expression. The one exception are
+        // reference patterns, which may be mixed with implicitly dereferencing ones.
+        let constructor = used_constructors
+            .iter()
+            .find(|constructor| matches!(constructor, Constructor::Ref))
+            .or_else(|| used_constructors.first());
+        let all_constructors = match constructor {
+            Some(constructor) if !constructor.is_non_exhaustive(cx) => {
+                Some(constructor.all_constructors(cx, &used_constructors)?)
+            }
+            _ => None,
+        };
+
+        match all_constructors {
+            Some(all_constructors)
+                if all_constructors.iter().all(|constructor| {
+                    used_constructors.iter().any(|used| used.covers(constructor))
+                }) =>
+            {
                 // If all constructors are covered, then we need to consider whether
                 // any values are covered by this wildcard.
                 //
@@ -647,23 +693,7 @@ pub(crate) fn is_useful(
                 //
                 // Here we create a constructor for each variant and then check
                 // usefulness after specializing for that constructor.
-                let mut found_unimplemented = false;
-                for constructor in constructor.all_constructors(cx) {
-                    let matrix = matrix.specialize_constructor(&cx, &constructor)?;
-                    let v = v.expand_wildcard(&cx, &constructor)?;
-
-                    match is_useful(&cx, &matrix, &v) {
-                        Ok(Usefulness::Useful) => return Ok(Usefulness::Useful),
-                        Ok(Usefulness::NotUseful) => continue,
-                        _ => found_unimplemented = true,
-                    };
-                }
-
-                if found_unimplemented {
-                    Err(MatchCheckErr::NotImplemented)
-                } else {
-                    Ok(Usefulness::NotUseful)
-                }
+                is_useful_specialized(cx, matrix, v, all_constructors)
             }
             _ => {
                 // Either not all constructors are covered, or the only other arms
@@ -678,7 +708,36 @@ pub(crate) fn is_useful(
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Computes `∃(k ϵ constructors) U(S(k, P), S(k, v))`.
+fn is_useful_specialized(
+    cx: &MatchCheckCtx,
+    matrix: &Matrix,
+    v: &PatStack,
+    constructors: Vec<Constructor>,
+) -> MatchCheckResult<Usefulness> {
+    let mut found_unimplemented = false;
+    for constructor in constructors {
+        let matrix = matrix.specialize_constructor(&cx, &constructor)?;
+        let v = match v.specialize_constructor(&cx, &constructor)? {
+            Some(v) => v,
+            None => continue,
+        };
+
+        match is_useful(&cx, &matrix, &v) {
+            Ok(Usefulness::Useful) => return Ok(Usefulness::Useful),
+            Ok(Usefulness::NotUseful) => continue,
+            _ => found_unimplemented = true,
+        };
+    }
+
+    if found_unimplemented {
+        Err(MatchCheckErr::NotImplemented)
+    } else {
+        Ok(Usefulness::NotUseful)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 /// Similar to TypeCtor, but includes additional information about the specific
 /// value being instantiated. For example, TypeCtor::Bool doesn't contain the
 /// boolean value.
@@ -686,29 +745,74 @@ enum Constructor {
     Bool(bool),
     Tuple { arity: usize },
     Enum(EnumVariantId),
+    Struct(StructId),
+    Ref,
+    IntRange(IntRange),
+    Slice(Slice),
 }
 
 impl Constructor {
     fn arity(&self, cx: &MatchCheckCtx) -> MatchCheckResult<usize> {
         let arity = match self {
-            Constructor::Bool(_) => 0,
+            Constructor::Bool(_) | Constructor::IntRange(_) => 0,
             Constructor::Tuple { arity } => *arity,
-            Constructor::Enum(e) => {
-                match cx.db.enum_data(e.parent).variants[e.local_id].variant_data.as_ref() {
-                    VariantData::Tuple(struct_field_data) => struct_field_data.len(),
-                    VariantData::Record(struct_field_data) => struct_field_data.len(),
-                    VariantData::Unit => 0,
-                }
+            Constructor::Ref => 1,
+            Constructor::Enum(_) | Constructor::Struct(_) => {
+                let variant_id = self.variant_id().expect("we know this is a variant");
+                variant_data(cx.db.upcast(), variant_id).fields().len()
             }
+            Constructor::Slice(slice) => slice.kind.arity(),
         };
 
         Ok(arity)
     }
 
-    fn all_constructors(&self, cx: &MatchCheckCtx) -> Vec<Constructor> {
+    fn variant_id(&self) -> Option<VariantId> {
         match self {
+            Constructor::Enum(e) => Some((*e).into()),
+            Constructor::Struct(s) => Some((*s).into()),
+            _ => None,
+        }
+    }
+
+    /// Whether every value matched by `other` is also matched by `self`.
+    fn covers(&self, other: &Constructor) -> bool {
+        match (self, other) {
+            (Constructor::Bool(a), Constructor::Bool(b)) => a == b,
+            (Constructor::Tuple { .. }, Constructor::Tuple { .. }) => true,
+            (Constructor::Enum(a), Constructor::Enum(b)) => a == b,
+            (Constructor::Struct(a), Constructor::Struct(b)) => a == b,
+            (Constructor::Ref, Constructor::Ref) => true,
+            (Constructor::IntRange(a), Constructor::IntRange(b)) => a.lo <= b.lo && b.hi <= a.hi,
+            (Constructor::Slice(a), Constructor::Slice(b)) => a.kind.covers(b.kind),
+            _ => false,
+        }
+    }
+
+    /// Enums marked `#[non_exhaustive]` can only be matched exhaustively
+    /// with a wildcard outside of the crate defining them.
+    fn is_non_exhaustive(&self, cx: &MatchCheckCtx) -> bool {
+        match self {
+            Constructor::Enum(e) => {
+                let adt = AdtId::EnumId(e.parent);
+                adt.module(cx.db.upcast()).krate != cx.owner.module(cx.db.upcast()).krate
+                    && cx.db.attrs(adt.into()).by_key("non_exhaustive").exists()
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns all constructors of the type of this constructor, split so
+    /// that each one is either covered by or disjoint from every constructor
+    /// in `used_constructors`.
+    fn all_constructors(
+        &self,
+        cx: &MatchCheckCtx,
+        used_constructors: &[Constructor],
+    ) -> MatchCheckResult<Vec<Constructor>> {
+        let constructors = match self {
             Constructor::Bool(_) => vec![Constructor::Bool(true), Constructor::Bool(false)],
-            Constructor::Tuple { .. } => vec![*self],
+            Constructor::Tuple { .. } | Constructor::Struct(_) | Constructor::Ref => vec![*self],
             Constructor::Enum(e) => cx
                 .db
                 .enum_data(e.parent)
@@ -718,33 +822,285 @@ impl Constructor {
                     Constructor::Enum(EnumVariantId { parent: e.parent, local_id })
                 })
                 .collect(),
+            Constructor::IntRange(range) => range
+                .ty
+                .full_ranges()
+                .into_iter()
+                .flat_map(|(lo, hi)| {
+                    IntRange { lo, hi, ty: range.ty }.split(used_constructors).into_iter()
+                })
+                .map(Constructor::IntRange)
+                .collect(),
+            Constructor::Slice(slice) => {
+                slice.all_slices(used_constructors)?.into_iter().map(Constructor::Slice).collect()
+            }
+        };
+
+        Ok(constructors)
+    }
+
+    /// Splits this constructor into the constructors it covers, such that each
+    /// of them is either covered by or disjoint from every constructor in
+    /// `used_constructors`.
+    fn split(
+        &self,
+        cx: &MatchCheckCtx,
+        used_constructors: &[Constructor],
+    ) -> MatchCheckResult<Vec<Constructor>> {
+        let constructors = match self {
+            Constructor::IntRange(range) => {
+                range.split(used_constructors).into_iter().map(Constructor::IntRange).collect()
+            }
+            Constructor::Slice(_) => {
+                let mut used_constructors = used_constructors.to_vec();
+                used_constructors.push(*self);
+                self.all_constructors(cx, &used_constructors)?
+                    .into_iter()
+                    .filter(|constructor| self.covers(constructor))
+                    .collect()
+            }
+            _ => vec![*self],
+        };
+
+        Ok(constructors)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum IntRangeTy {
+    Int(IntTy),
+    Char,
+}
+
+impl IntRangeTy {
+    fn of_pat(cx: &MatchCheckCtx, pat_id: PatId) -> MatchCheckResult<IntRangeTy> {
+        match cx.infer.type_of_pat.get(pat_id) {
+            Some(Ty::Apply(ApplicationTy {
+                ctor: TypeCtor::Int(Uncertain::Known(ty)), ..
+            })) => Ok(IntRangeTy::Int(*ty)),
+            Some(Ty::Apply(ApplicationTy { ctor: TypeCtor::Char, .. })) => Ok(IntRangeTy::Char),
+            _ => Err(MatchCheckErr::Unknown),
+        }
+    }
+
+    /// Maps a value of this type to `u128`, preserving the order of values.
+    /// Signed values get their sign bit flipped to achieve this.
+    fn encode(self, value: i128) -> u128 {
+        match self {
+            IntRangeTy::Int(IntTy { signedness: Signedness::Signed, .. }) => {
+                (value as u128) ^ (1 << 127)
+            }
+            _ => value as u128,
+        }
+    }
+
+    /// All values of the type, as encoded inclusive ranges.
+    fn full_ranges(self) -> Vec<(u128, u128)> {
+        match self {
+            IntRangeTy::Int(IntTy {
+                signedness: Signedness::Unsigned,
+                bitness: IntBitness::X128,
+            }) => {
+                vec![(0, u128::max_value())]
+            }
+            IntRangeTy::Int(ty) => vec![(self.encode(int_min(ty)), self.encode(int_max(ty)))],
+            // `char` excludes the surrogate code points.
+            IntRangeTy::Char => vec![(0, 0xD7FF), (0xE000, 0x10FFFF)],
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// An inclusive range of values of an integer type or `char`, encoded with
+/// `IntRangeTy::encode`. Single values are ranges with `lo == hi`.
+struct IntRange {
+    lo: u128,
+    hi: u128,
+    ty: IntRangeTy,
+}
+
+impl IntRange {
+    /// Splits this range at the boundaries of every integer range in `used_constructors`.
+    fn split(&self, used_constructors: &[Constructor]) -> Vec<IntRange> {
+        let mut borders = vec![self.lo];
+        for constructor in used_constructors {
+            if let Constructor::IntRange(used) = constructor {
+                for border in [Some(used.lo), used.hi.checked_add(1)].iter().flatten() {
+                    if self.lo < *border && *border <= self.hi {
+                        borders.push(*border);
+                    }
+                }
+            }
+        }
+        borders.sort();
+        borders.dedup();
+
+        (0..borders.len())
+            .map(|i| IntRange {
+                lo: borders[i],
+                hi: borders.get(i + 1).map_or(self.hi, |next| next - 1),
+                ty: self.ty,
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Slice {
+    /// Arrays only have a single length, slices have all of them.
+    is_array: bool,
+    kind: SliceKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SliceKind {
+    /// `[a, b, c]`, matching slices of exactly this length.
+    FixedLen(usize),
+    /// `[a, .., b]`, matching slices of at least `prefix + suffix` elements.
+    VarLen(usize, usize),
+}
+
+impl SliceKind {
+    fn of_pat(prefix: &[PatId], slice: Option<PatId>, suffix: &[PatId]) -> SliceKind {
+        match slice {
+            Some(_) => SliceKind::VarLen(prefix.len(), suffix.len()),
+            None => SliceKind::FixedLen(prefix.len() + suffix.len()),
+        }
+    }
+
+    fn arity(self) -> usize {
+        match self {
+            SliceKind::FixedLen(len) => len,
+            SliceKind::VarLen(prefix, suffix) => prefix + suffix,
+        }
+    }
+
+    fn covers(self, other: SliceKind) -> bool {
+        match (self, other) {
+            (SliceKind::FixedLen(len), SliceKind::FixedLen(other_len)) => len == other_len,
+            (SliceKind::VarLen(prefix, suffix), SliceKind::FixedLen(len)) => prefix + suffix <= len,
+            (SliceKind::VarLen(prefix, suffix), SliceKind::VarLen(other_prefix, other_suffix)) => {
+                prefix <= other_prefix && suffix <= other_suffix
+            }
+            (SliceKind::FixedLen(_), SliceKind::VarLen(..)) => false,
+        }
+    }
+}
+
+impl Slice {
+    /// Returns the lengths a slice can have, grouped so that every length
+    /// matched by some pattern in `used_constructors` gets its own constructor,
+    /// and all the longer slices share a single variable length constructor.
+    fn all_slices(&self, used_constructors: &[Constructor]) -> MatchCheckResult<Vec<Slice>> {
+        let used_kinds = used_constructors.iter().filter_map(|constructor| match constructor {
+            Constructor::Slice(slice) => Some(slice.kind),
+            _ => None,
+        });
+
+        if self.is_array {
+            // FIXME: take the length from the array type once we have it.
+            return used_kinds
+                .filter_map(|kind| match kind {
+                    SliceKind::FixedLen(_) => Some(vec![Slice { kind, ..*self }]),
+                    SliceKind::VarLen(..) => None,
+                })
+                .next()
+                .ok_or(MatchCheckErr::NotImplemented);
+        }
+
+        let (mut max_fixed_len, mut max_prefix, mut max_suffix) = (0, 0, 0);
+        for kind in used_kinds {
+            match kind {
+                SliceKind::FixedLen(len) => max_fixed_len = max_fixed_len.max(len),
+                SliceKind::VarLen(prefix, suffix) => {
+                    max_prefix = max_prefix.max(prefix);
+                    max_suffix = max_suffix.max(suffix);
+                }
+            }
+        }
+        // The variable length constructor must be longer than any fixed length one.
+        if max_prefix + max_suffix <= max_fixed_len {
+            max_suffix = max_fixed_len + 1 - max_prefix;
+        }
+
+        let slices = (0..max_prefix + max_suffix)
+            .map(SliceKind::FixedLen)
+            .chain(iter::once(SliceKind::VarLen(max_prefix, max_suffix)))
+            .map(|kind| Slice { kind, ..*self })
+            .collect();
+
+        Ok(slices)
+    }
+}
+
 /// Returns the constructor for the given pattern. Should only return None
 /// in the case of a Wild pattern.
 fn pat_constructor(cx: &MatchCheckCtx, pat: PatIdOrWild) -> MatchCheckResult<Option<Constructor>> {
-    let res = match pat.as_pat(cx) {
+    let pat_id = match pat.as_id(cx) {
+        Some(pat_id) => pat_id,
+        None => return Ok(None),
+    };
+    let res = match &cx.body.pats[pat_id] {
         Pat::Wild => None,
-        // FIXME somehow create the Tuple constructor with the proper arity. If there are
-        // ellipsis, the arity is not equal to the number of patterns.
-        Pat::Tuple { args: pats, ellipsis } if ellipsis.is_none() => {
-            Some(Constructor::Tuple { arity: pats.len() })
+        Pat::Tuple { args: pats, ellipsis } => {
+            let arity = match cx.infer.type_of_pat.get(pat_id) {
+                Some(Ty::Apply(ApplicationTy {
+                    ctor: TypeCtor::Tuple { cardinality }, ..
+                })) => *cardinality as usize,
+                _ if ellipsis.is_none() => pats.len(),
+                _ => return Err(MatchCheckErr::Unknown),
+            };
+            Some(Constructor::Tuple { arity })
         }
-        Pat::Lit(lit_expr) => match cx.body.exprs[lit_expr] {
+        Pat::Ref { .. } => Some(Constructor::Ref),
+        Pat::Lit(lit_expr) => match cx.body.exprs[*lit_expr] {
             Expr::Literal(Literal::Bool(val)) => Some(Constructor::Bool(val)),
-            _ => return Err(MatchCheckErr::NotImplemented),
+            _ => {
+                let ty = IntRangeTy::of_pat(cx, pat_id)?;
+                let value = ty.encode(expr_int_value(cx, *lit_expr)?);
+                Some(Constructor::IntRange(IntRange { lo: value, hi: value, ty }))
+            }
         },
+        Pat::Range { start, end, range_type } => {
+            let ty = IntRangeTy::of_pat(cx, pat_id)?;
+            let lo = ty.encode(expr_int_value(cx, *start)?);
+            let hi = ty.encode(expr_int_value(cx, *end)?);
+            let hi = match range_type {
+                RangeOp::Inclusive if lo <= hi => hi,
+                RangeOp::Exclusive if lo < hi => hi - 1,
+                // lower range bound must be less than or equal to upper
+                _ => return Err(MatchCheckErr::MalformedMatchArm),
+            };
+            Some(Constructor::IntRange(IntRange { lo, hi, ty }))
+        }
+        Pat::Slice { prefix, slice, suffix } => {
+            let is_array = match cx.infer.type_of_pat.get(pat_id) {
+                Some(Ty::Apply(ApplicationTy { ctor: TypeCtor::Array, .. })) => true,
+                Some(Ty::Apply(ApplicationTy { ctor: TypeCtor::Slice, .. })) => false,
+                _ => return Err(MatchCheckErr::Unknown),
+            };
+            let kind = SliceKind::of_pat(prefix, *slice, suffix);
+            Some(Constructor::Slice(Slice { is_array, kind }))
+        }
         Pat::TupleStruct { .. } | Pat::Path(_) | Pat::Record { .. } => {
-            let pat_id = pat.as_id().expect("we already know this pattern is not a wild");
-            let variant_id =
-                cx.infer.variant_resolution_for_pat(pat_id).ok_or(MatchCheckErr::Unknown)?;
-            match variant_id {
-                VariantId::EnumVariantId(enum_variant_id) => {
+            match cx.infer.variant_resolution_for_pat(pat_id) {
+                Some(VariantId::EnumVariantId(enum_variant_id)) => {
                     Some(Constructor::Enum(enum_variant_id))
                 }
-                _ => return Err(MatchCheckErr::NotImplemented),
+                Some(VariantId::StructId(struct_id)) => Some(Constructor::Struct(struct_id)),
+                Some(VariantId::UnionId(_)) => return Err(MatchCheckErr::NotImplemented),
+                None => match &cx.body.pats[pat_id] {
+                    // This might be a constant.
+                    Pat::Path(path) => match resolve_const(cx, path, cx.match_expr)? {
+                        ComputedExpr::Bool(val) => Some(Constructor::Bool(val)),
+                        computed => {
+                            let ty = IntRangeTy::of_pat(cx, pat_id)?;
+                            let value = ty.encode(computed_int_value(computed)?);
+                            Some(Constructor::IntRange(IntRange { lo: value, hi: value, ty }))
+                        }
+                    },
+                    _ => return Err(MatchCheckErr::Unknown),
+                },
             }
         }
         _ => return Err(MatchCheckErr::NotImplemented),
@@ -753,46 +1109,32 @@ fn pat_constructor(cx: &MatchCheckCtx, pat: PatIdOrWild) -> MatchCheckResult<Opt
     Ok(res)
 }
 
-fn all_constructors_covered(
-    cx: &MatchCheckCtx,
-    constructor: &Constructor,
-    used_constructors: &[Constructor],
-) -> bool {
-    match constructor {
-        Constructor::Tuple { arity } => {
-            used_constructors.iter().any(|constructor| match constructor {
-                Constructor::Tuple { arity: used_arity } => arity == used_arity,
-                _ => false,
-            })
-        }
-        Constructor::Bool(_) => {
-            if used_constructors.is_empty() {
-                return false;
-            }
-
-            let covers_true =
-                used_constructors.iter().any(|c| matches!(c, Constructor::Bool(true)));
-            let covers_false =
-                used_constructors.iter().any(|c| matches!(c, Constructor::Bool(false)));
-
-            covers_true && covers_false
-        }
-        Constructor::Enum(e) => cx.db.enum_data(e.parent).variants.iter().all(|(id, _)| {
-            for constructor in used_constructors {
-                if let Constructor::Enum(e) = constructor {
-                    if id == e.local_id {
-                        return true;
-                    }
-                }
-            }
+/// Evaluates a literal pattern or a bound of a range pattern.
+fn expr_int_value(cx: &MatchCheckCtx, expr: ExprId) -> MatchCheckResult<i128> {
+    match &cx.body.exprs[expr] {
+        Expr::Literal(Literal::Int(value, _)) => Ok(*value as i128),
+        Expr::Literal(Literal::Char(c)) => Ok(*c as i128),
+        Expr::UnaryOp { expr, op: UnaryOp::Neg } => Ok(expr_int_value(cx, *expr)?.wrapping_neg()),
+        Expr::Path(path) => computed_int_value(resolve_const(cx, path, expr)?),
+        _ => Err(MatchCheckErr::NotImplemented),
+    }
+}
 
-            false
-        }),
+fn computed_int_value(computed: ComputedExpr) -> MatchCheckResult<i128> {
+    match computed {
+        ComputedExpr::Int(value) => Ok(value),
+        ComputedExpr::Char(c) => Ok(c as i128),
+        _ => Err(MatchCheckErr::NotImplemented),
     }
 }
 
-fn enum_variant_matches(cx: &MatchCheckCtx, pat_id: PatId, enum_variant_id: EnumVariantId) -> bool {
-    Some(enum_variant_id.into()) == cx.infer.variant_resolution_for_pat(pat_id)
+fn resolve_const(cx: &MatchCheckCtx, path: &Path, expr: ExprId) -> MatchCheckResult<ComputedExpr> {
+    let resolver = resolver_for_expr(cx.db.upcast(), cx.owner, expr);
+    let hygiene = SyntaxContext::ROOT;
+    match resolver.resolve_path_in_value_ns_fully(cx.db.upcast(), path.mod_path(), hygiene) {
+        Some(ValueNs::ConstId(it)) => cx.db.const_eval(it).map_err(|_| MatchCheckErr::Unknown),
+        _ => Err(MatchCheckErr::Unknown),
+    }
 }
 
 #[cfg(test)]
@@ -1553,6 +1895,23 @@ mod tests {
         check_no_diagnostic(content);
     }
 
+    #[test]
+    fn expr_loop_missing_arm() {
+        let content = r"
+            enum Either {
+                A,
+                B,
+            }
+            fn test_fn() {
+                match loop { break Either::A } {
+                    Either::A => (),
+                }
+            }
+        ";
+
+        check_diagnostic(content);
+    }
+
     #[test]
     fn expr_partially_diverges() {
         let content = r"
@@ -1929,40 +2288,50 @@ mod tests {
 
         check_no_diagnostic(content);
     }
-}
 
-#[cfg(test)]
-mod false_negatives {
-    //! The implementation of match checking here is a work in progress. As we roll this out, we
-    //! prefer false negatives to false positives (ideally there would be no false positives). This
-    //! test module should document known false negatives. Eventually we will have a complete
-    //! implementation of match checking and this module will be empty.
-    //!
-    //! The reasons for documenting known false negatives:
-    //!
-    //!   1. It acts as a backlog of work that can be done to improve the behavior of the system.
-    //!   2. It ensures the code doesn't panic when handling these cases.
+    #[test]
+    fn tuple_of_bools_with_ellipsis_at_end_missing_arm() {
+        let content = r"
+            fn test_fn() {
+                match (false, true, false) {
+                    (false, ..) => {},
+                }
+            }
+        ";
 
-    use super::tests::*;
+        check_diagnostic(content);
+    }
 
     #[test]
-    fn integers() {
+    fn tuple_of_bools_with_ellipsis_at_beginning_missing_arm() {
         let content = r"
             fn test_fn() {
-                match 5 {
-                    10 => (),
-                    11..20 => (),
+                match (false, true, false) {
+                    (.., false) => {},
                 }
             }
         ";
 
-        // This is a false negative.
-        // We don't currently check integer exhaustiveness.
-        check_no_diagnostic(content);
+        check_diagnostic(content);
+    }
+
+    #[test]
+    fn tuple_of_bools_with_ellipsis_in_middle_missing_arm() {
+        let content = r"
+            fn test_fn() {
+                match (false, true, false) {
+                    (true, .., true) => {},
+                    (false, ..) => {},
+                    (.., false) => {},
+                }
+            }
+        ";
+
+        check_diagnostic(content);
     }
 
     #[test]
-    fn internal_or() {
+    fn internal_or_missing_arm() {
         let content = r"
             fn test_fn() {
                 enum Either {
@@ -1975,80 +2344,63 @@ mod false_negatives {
             }
         ";
 
-        // This is a false negative.
-        // We do not currently handle patterns with internal `or`s.
-        check_no_diagnostic(content);
+        check_diagnostic(content);
     }
 
     #[test]
-    fn expr_diverges_missing_arm() {
+    fn internal_or_no_diagnostic() {
         let content = r"
-            enum Either {
-                A,
-                B,
-            }
             fn test_fn() {
-                match loop {} {
-                    Either::A => (),
+                enum Either {
+                    A(bool),
+                    B,
+                }
+                match Either::B {
+                    Either::A(true | false) => (),
+                    Either::B => (),
                 }
             }
         ";
 
-        // This is a false negative.
-        // Even though the match expression diverges, rustc fails
-        // to compile here since `Either::B` is missing.
         check_no_diagnostic(content);
     }
 
     #[test]
-    fn expr_loop_missing_arm() {
+    fn or_keeps_rest_of_row() {
         let content = r"
-            enum Either {
-                A,
-                B,
-            }
             fn test_fn() {
-                match loop { break Foo::A } {
-                    Either::A => (),
+                match (false, true) {
+                    (true | false, true) => (),
                 }
             }
         ";
 
-        // This is a false negative.
-        // We currently infer the type of `loop { break Foo::A }` to `!`, which
-        // causes us to skip the diagnostic since `Either::A` doesn't type check
-        // with `!`.
-        check_no_diagnostic(content);
+        check_diagnostic(content);
     }
 
     #[test]
-    fn tuple_of_bools_with_ellipsis_at_end_missing_arm() {
+    fn bindings_with_subpatterns() {
         let content = r"
             fn test_fn() {
-                match (false, true, false) {
-                    (false, ..) => {},
+                match (false, true) {
+                    (x @ true, _) => (),
+                    (false, y @ _) => (),
                 }
             }
         ";
 
-        // This is a false negative.
-        // We don't currently handle tuple patterns with ellipsis.
         check_no_diagnostic(content);
-    }
 
-    #[test]
-    fn tuple_of_bools_with_ellipsis_at_beginning_missing_arm() {
         let content = r"
             fn test_fn() {
-                match (false, true, false) {
-                    (.., false) => {},
+                match (false, true) {
+                    (x @ true, _) => (),
+                    (false, y @ true) => (),
                 }
             }
         ";
 
-        // This is a false negative.
-        // We don't currently handle tuple patterns with ellipsis.
-        check_no_diagnostic(content);
+        check_diagnostic(content);
     }
 
     #[test]
@@ -2064,8 +2416,274 @@ mod false_negatives {
             }
         ";
 
+        check_diagnostic(content);
+    }
+
+    #[test]
+    fn struct_no_diagnostic() {
+        let content = r"
+            struct Foo {
+                a: bool,
+                b: bool,
+            }
+            struct Bar(bool);
+            fn test_fn(f: Foo, b: Bar) {
+                match f {
+                    Foo { a: true, .. } => {},
+                    Foo { b: false, .. } => {},
+                    Foo { a: false, b: true } => {},
+                }
+                match b {
+                    Bar(true) => {},
+                    Bar(false) => {},
+                }
+            }
+        ";
+
+        check_no_diagnostic(content);
+    }
+
+    #[test]
+    fn references() {
+        let content = r"
+            fn test_fn(x: &bool) {
+                match x {
+                    &true => {},
+                }
+            }
+        ";
+
+        check_diagnostic(content);
+
+        let content = r"
+            fn test_fn(x: &bool) {
+                match x {
+                    &true => {},
+                    &false => {},
+                }
+            }
+        ";
+
+        check_no_diagnostic(content);
+    }
+
+    #[test]
+    fn integers() {
+        let content = r"
+            fn test_fn() {
+                match 5 {
+                    10 => (),
+                    11..20 => (),
+                }
+            }
+        ";
+
+        check_diagnostic(content);
+    }
+
+    #[test]
+    fn integer_ranges_no_diagnostic() {
+        let content = r"
+            fn test_fn(a: u8, b: i8, c: (bool, u16)) {
+                match a {
+                    0 => (),
+                    1..=100 => (),
+                    101..255 => (),
+                    255 => (),
+                }
+                match b {
+                    -128..=-1 => (),
+                    0 | 1 => (),
+                    2..=limits::I8_MAX => (),
+                }
+                match c {
+                    (true, _) => (),
+                    (false, 0..=0xFFFF) => (),
+                }
+            }
+            mod limits {
+                pub const I8_MAX: i8 = 127;
+            }
+        ";
+
+        check_no_diagnostic(content);
+    }
+
+    #[test]
+    fn integer_ranges_missing_arm() {
+        let content = r"
+            fn test_fn(b: i8) {
+                match b {
+                    -128..=-2 => (),
+                    0..=127 => (),
+                }
+            }
+        ";
+
+        check_diagnostic(content);
+    }
+
+    #[test]
+    fn integer_constants() {
+        let content = r"
+            const LIMIT: u8 = 100;
+            fn test_fn(a: u8) {
+                match a {
+                    0..=LIMIT => (),
+                    101..=255 => (),
+                }
+            }
+        ";
+
+        check_no_diagnostic(content);
+
+        let content = r"
+            const LIMIT: u8 = 100;
+            fn test_fn(a: u8) {
+                match a {
+                    0..LIMIT => (),
+                    101..=255 => (),
+                }
+            }
+        ";
+
+        check_diagnostic(content);
+    }
+
+    #[test]
+    fn chars() {
+        let content = r"
+            fn test_fn(c: char) {
+                match c {
+                    '\u{0}'..='\u{D7FF}' => (),
+                    '\u{E000}'..='\u{10FFFF}' => (),
+                }
+            }
+        ";
+
+        check_no_diagnostic(content);
+
+        let content = r"
+            fn test_fn(c: char) {
+                match c {
+                    'a'..='z' => (),
+                }
+            }
+        ";
+
+        check_diagnostic(content);
+    }
+
+    #[test]
+    fn slices() {
+        let content = r"
+            fn test_fn(s: &[bool]) {
+                match s {
+                    [] => (),
+                    [true, ..] => (),
+                    [.., false] => (),
+                    [false, .., true] => (),
+                }
+            }
+        ";
+
+        check_no_diagnostic(content);
+
+        let content = r"
+            fn test_fn(s: &[bool]) {
+                match s {
+                    [] => (),
+                    [_] => (),
+                    [true, ..] => (),
+                    [false, .., true] => (),
+                }
+            }
+        ";
+
+        check_diagnostic(content);
+    }
+
+    #[test]
+    fn arrays() {
+        let content = r"
+            fn test_fn(a: [bool; 2]) {
+                match a {
+                    [true, _] => (),
+                    [false, ..] => (),
+                }
+            }
+        ";
+
+        check_no_diagnostic(content);
+
+        let content = r"
+            fn test_fn(a: [bool; 2]) {
+                match a {
+                    [true, _] => (),
+                    [.., true] => (),
+                }
+            }
+        ";
+
+        check_diagnostic(content);
+    }
+
+    #[test]
+    fn non_exhaustive_enum() {
+        let content = r"
+            //- /main.rs crate:main deps:lib
+            fn test_fn(e: lib::E) {
+                match e {
+                    lib::E::A => (),
+                    lib::E::B => (),
+                }
+            }
+            //- /lib.rs crate:lib
+            #[non_exhaustive]
+            pub enum E { A, B }
+            fn local(e: E) {
+                match e {
+                    E::A => (),
+                    E::B => (),
+                }
+            }
+        ";
+
+        assert_eq!(1, TestDB::with_files(content).diagnostic::<MissingMatchArms>().1);
+    }
+}
+
+#[cfg(test)]
+mod false_negatives {
+    //! The implementation of match checking here is a work in progress. As we roll this out, we
+    //! prefer false negatives to false positives (ideally there would be no false positives). This
+    //! test module should document known false negatives. Eventually we will have a complete
+    //! implementation of match checking and this module will be empty.
+    //!
+    //! The reasons for documenting known false negatives:
+    //!
+    //!   1. It acts as a backlog of work that can be done to improve the behavior of the system.
+    //!   2. It ensures the code doesn't panic when handling these cases.
+
+    use super::tests::*;
+
+    #[test]
+    fn expr_diverges_missing_arm() {
+        let content = r"
+            enum Either {
+                A,
+                B,
+            }
+            fn test_fn() {
+                match loop {} {
+                    Either::A => (),
+                }
+            }
+        ";
+
         // This is a false negative.
-        // We don't currently handle structs.
+        // Even though the match expression diverges, rustc fails
+        // to compile here since `Either::B` is missing.
         check_no_diagnostic(content);
     }
 }
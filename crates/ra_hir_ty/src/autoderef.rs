@@ -5,7 +5,7 @@
 
 use std::iter::successors;
 
-use hir_def::lang_item::LangItemTarget;
+use hir_def::{lang_item::LangItemTarget, AdtId};
 use hir_expand::name::name;
 use log::{info, warn};
 use ra_db::CrateId;
@@ -14,7 +14,7 @@ use crate::{
     db::HirDatabase,
     traits::{InEnvironment, Solution},
     utils::generics,
-    BoundVar, Canonical, DebruijnIndex, Obligation, Substs, TraitRef, Ty,
+    ApplicationTy, BoundVar, Canonical, DebruijnIndex, Obligation, Substs, TraitRef, Ty, TypeCtor,
 };
 
 const AUTODEREF_RECURSION_LIMIT: usize = 10;
@@ -38,11 +38,30 @@ pub(crate) fn deref(
 ) -> Option<Canonical<Ty>> {
     if let Some(derefed) = ty.value.value.builtin_deref() {
         Some(Canonical { value: derefed, num_vars: ty.value.num_vars })
+    } else if let Some(derefed) = deref_box(db, krate, &ty.value.value) {
+        Some(Canonical { value: derefed, num_vars: ty.value.num_vars })
     } else {
         deref_by_trait(db, krate, ty)
     }
 }
 
+/// `Box` derefs to its contents even without a `Deref` impl in sight, just like
+/// rustc treats it as a builtin.
+fn deref_box(db: &dyn HirDatabase, krate: CrateId, ty: &Ty) -> Option<Ty> {
+    let box_struct = match db.lang_item(krate, "owned_box".into())? {
+        LangItemTarget::StructId(it) => it,
+        _ => return None,
+    };
+    match ty {
+        Ty::Apply(ApplicationTy { ctor: TypeCtor::Adt(AdtId::StructId(s)), parameters })
+            if *s == box_struct =>
+        {
+            parameters.0.first().cloned()
+        }
+        _ => None,
+    }
+}
+
 fn deref_by_trait(
     db: &dyn HirDatabase,
     krate: CrateId,
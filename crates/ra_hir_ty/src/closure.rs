@@ -0,0 +1,276 @@
+//! Computation of the variables captured by closures, and of how they are
+//! captured.
+//!
+//! This is an approximation of what rustc does: a variable is captured by
+//! mutable reference if the closure mutates it, by value if the closure moves
+//! it (or if the closure is a `move` closure), and by reference otherwise.
+//! Captures of fields (`|| a.b`) are treated as captures of the whole variable.
+
+use std::{fmt, sync::Arc};
+
+use hir_def::{
+    body::Body,
+    expr::{BinaryOp, Expr, ExprId, PatId, UnaryOp},
+    resolver::{resolver_for_expr, HasResolver, ValueNs},
+    type_ref::{Mutability, TypeRef},
+    DefWithBodyId, HasModule,
+};
+use hir_expand::hygiene::SyntaxContext;
+use ra_db::CrateId;
+
+use crate::{
+    db::HirDatabase, method_resolution::implements_trait, ApplicationTy, Canonical,
+    InferenceResult, TraitEnvironment, Ty, TypeCtor,
+};
+
+/// How a closure captures a variable, from the weakest to the strongest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CaptureKind {
+    ByRef,
+    ByMutRef,
+    ByValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capture {
+    /// The binding of the captured variable.
+    pub local: PatId,
+    pub kind: CaptureKind,
+}
+
+/// The closure traits, from the most to the least permissive for the caller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ClosureKind {
+    Fn,
+    FnMut,
+    FnOnce,
+}
+
+impl fmt::Display for ClosureKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ClosureKind::Fn => "Fn",
+            ClosureKind::FnMut => "FnMut",
+            ClosureKind::FnOnce => "FnOnce",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The variables captured by the closure `closure` of the body of `owner`, in
+/// the order of their first use.
+pub fn closure_captures(
+    db: &dyn HirDatabase,
+    owner: DefWithBodyId,
+    closure: ExprId,
+) -> Vec<Capture> {
+    let (mut captures, is_move) = match uses_in_closure(db, owner, closure) {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    if is_move {
+        captures.iter_mut().for_each(|it| it.kind = CaptureKind::ByValue);
+    }
+    captures
+}
+
+/// The most permissive closure trait the closure implements. This depends on
+/// how the captured variables are used, not on how they are captured: a
+/// `move` closure that only reads its captures is still `Fn`.
+pub fn closure_kind(db: &dyn HirDatabase, owner: DefWithBodyId, closure: ExprId) -> ClosureKind {
+    let (uses, _) = match uses_in_closure(db, owner, closure) {
+        Some(it) => it,
+        None => return ClosureKind::Fn,
+    };
+    match uses.iter().map(|it| it.kind).max() {
+        Some(CaptureKind::ByValue) => ClosureKind::FnOnce,
+        Some(CaptureKind::ByMutRef) => ClosureKind::FnMut,
+        Some(CaptureKind::ByRef) | None => ClosureKind::Fn,
+    }
+}
+
+/// How the closure uses the variables it captures, and whether it is a `move`
+/// closure.
+fn uses_in_closure(
+    db: &dyn HirDatabase,
+    owner: DefWithBodyId,
+    closure: ExprId,
+) -> Option<(Vec<Capture>, bool)> {
+    let body = db.body(owner);
+    let (closure_body, is_move) = match &body[closure] {
+        Expr::Lambda { body, is_move, .. } => (*body, *is_move),
+        _ => return None,
+    };
+    let mut ctx = CaptureCtx {
+        db,
+        owner,
+        closure,
+        krate: owner.module(db.upcast()).krate,
+        infer: db.infer(owner),
+        body,
+        captures: Vec::new(),
+    };
+    ctx.walk(closure_body, Use::Value);
+    Some((ctx.captures, is_move))
+}
+
+/// How an expression is used by its parent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Use {
+    Ref,
+    MutRef,
+    Value,
+}
+
+struct CaptureCtx<'a> {
+    db: &'a dyn HirDatabase,
+    owner: DefWithBodyId,
+    closure: ExprId,
+    krate: CrateId,
+    infer: Arc<InferenceResult>,
+    body: Arc<Body>,
+    captures: Vec<Capture>,
+}
+
+impl<'a> CaptureCtx<'a> {
+    fn walk(&mut self, expr: ExprId, use_: Use) {
+        let body = Arc::clone(&self.body);
+        match &body[expr] {
+            Expr::Path(path) => {
+                if let Some(local) = self.captured_local(expr, path) {
+                    let kind = match use_ {
+                        Use::Ref => CaptureKind::ByRef,
+                        Use::MutRef => CaptureKind::ByMutRef,
+                        Use::Value if self.is_copy(&self.infer[expr]) => CaptureKind::ByRef,
+                        Use::Value => CaptureKind::ByValue,
+                    };
+                    self.add_capture(local, kind);
+                }
+            }
+            Expr::Ref { expr, mutability } => {
+                let use_ = match mutability {
+                    Mutability::Mut => Use::MutRef,
+                    Mutability::Shared => Use::Ref,
+                };
+                self.walk(*expr, use_);
+            }
+            Expr::BinaryOp { lhs, rhs, op: Some(BinaryOp::Assignment { .. }) } => {
+                self.walk(*lhs, Use::MutRef);
+                self.walk(*rhs, Use::Value);
+            }
+            Expr::BinaryOp { lhs, rhs, op: Some(BinaryOp::CmpOp(_)) } => {
+                self.walk(*lhs, Use::Ref);
+                self.walk(*rhs, Use::Ref);
+            }
+            // Places: using a field or an element uses the base the same way.
+            Expr::Field { expr, .. } => {
+                let use_ = if use_ == Use::Value && self.is_copy(&self.infer[*expr]) {
+                    Use::Ref
+                } else {
+                    use_
+                };
+                self.walk(*expr, use_);
+            }
+            Expr::Index { base, index } => {
+                self.walk(*base, if use_ == Use::MutRef { Use::MutRef } else { Use::Ref });
+                self.walk(*index, Use::Value);
+            }
+            Expr::UnaryOp { expr, op: UnaryOp::Deref } => {
+                self.walk(*expr, if use_ == Use::MutRef { Use::MutRef } else { Use::Ref });
+            }
+            Expr::MethodCall { receiver, args, .. } => {
+                self.walk(*receiver, self.receiver_use(expr));
+                args.iter().for_each(|&arg| self.walk(arg, Use::Value));
+            }
+            // The scrutinee is only borrowed, unless the patterns bind by value.
+            Expr::Match { expr, arms } => {
+                self.walk(*expr, Use::Ref);
+                for arm in arms {
+                    arm.guard.iter().for_each(|&guard| self.walk(guard, Use::Value));
+                    self.walk(arm.expr, Use::Value);
+                }
+            }
+            expr => expr.walk_child_exprs(|child| self.walk(child, Use::Value)),
+        }
+    }
+
+    /// The local `path` refers to, if it is declared outside of the closure.
+    fn captured_local(&self, expr: ExprId, path: &hir_def::path::Path) -> Option<PatId> {
+        let resolve = |at: ExprId| {
+            let resolver = resolver_for_expr(self.db.upcast(), self.owner, at);
+            match resolver.resolve_path_in_value_ns_fully(
+                self.db.upcast(),
+                path.mod_path(),
+                SyntaxContext::ROOT,
+            )? {
+                ValueNs::LocalBinding(pat) => Some(pat),
+                _ => None,
+            }
+        };
+        let local = resolve(expr)?;
+        // Locals declared in the closure, which shadow outer ones, resolve
+        // differently at the closure itself.
+        if resolve(self.closure) == Some(local) {
+            Some(local)
+        } else {
+            None
+        }
+    }
+
+    fn add_capture(&mut self, local: PatId, kind: CaptureKind) {
+        match self.captures.iter_mut().find(|it| it.local == local) {
+            Some(capture) => capture.kind = capture.kind.max(kind),
+            None => self.captures.push(Capture { local, kind }),
+        }
+    }
+
+    /// How a method call uses its receiver, according to the `self` parameter
+    /// of the method.
+    fn receiver_use(&self, call: ExprId) -> Use {
+        let func = match self.infer.method_resolution(call) {
+            Some(it) => it,
+            None => return Use::Ref,
+        };
+        let data = self.db.function_data(func);
+        match data.params.first() {
+            Some(TypeRef::Reference(_, Mutability::Mut)) => Use::MutRef,
+            Some(TypeRef::Reference(_, Mutability::Shared)) => Use::Ref,
+            Some(_) if data.has_self_param => Use::Value,
+            _ => Use::Ref,
+        }
+    }
+
+    fn is_copy(&self, ty: &Ty) -> bool {
+        let a_ty = match ty {
+            Ty::Apply(it) => it,
+            _ => return false,
+        };
+        match a_ty.ctor {
+            TypeCtor::Bool
+            | TypeCtor::Char
+            | TypeCtor::Int(_)
+            | TypeCtor::Float(_)
+            | TypeCtor::Never
+            | TypeCtor::RawPtr(_)
+            | TypeCtor::FnPtr { .. }
+            | TypeCtor::FnDef(_)
+            | TypeCtor::Ref(Mutability::Shared) => true,
+            TypeCtor::Tuple { .. } => a_ty.parameters.iter().all(|it| self.is_copy(it)),
+            TypeCtor::Array => self.is_copy(&a_ty.parameters[0]),
+            TypeCtor::Adt(_) => self.implements_copy(a_ty),
+            _ => false,
+        }
+    }
+
+    fn implements_copy(&self, a_ty: &ApplicationTy) -> bool {
+        let copy_trait =
+            match self.db.lang_item(self.krate, "copy".into()).and_then(|it| it.as_trait()) {
+                Some(it) => it,
+                None => return false,
+            };
+        let resolver = self.owner.resolver(self.db.upcast());
+        let env = TraitEnvironment::lower(self.db, &resolver);
+        let ty = Canonical { value: Ty::Apply(a_ty.clone()), num_vars: 0 };
+        implements_trait(&ty, self.db, env, self.krate, copy_trait)
+    }
+}
@@ -0,0 +1,550 @@
+//! Constant evaluation.
+//!
+//! This is a small interpreter over the HIR of constant bodies. It handles
+//! literals, integer arithmetic, references to other constants, enum
+//! discriminants and calls to simple `const fn`s, which covers most of the
+//! constants people actually write.
+
+use std::{fmt, sync::Arc};
+
+use hir_def::{
+    body::Body,
+    expr::{
+        ArithOp, BinaryOp, CmpOp, Expr, ExprId, Literal, LogicOp, Ordering, Pat, PatId, Statement,
+        UnaryOp,
+    },
+    resolver::{resolver_for_expr, HasResolver, Resolver, ValueNs},
+    type_ref::ConstRef,
+    AssocItemId, ConstId, DefWithBodyId, EnumVariantId, FunctionId,
+};
+use hir_expand::hygiene::SyntaxContext;
+use rustc_hash::FxHashMap;
+
+use crate::{
+    db::HirDatabase,
+    primitive::{IntBitness, IntTy, Signedness, Uncertain},
+    InferenceResult, Ty, TypeCtor,
+};
+
+/// How deep `const fn` calls may nest before we give up.
+const CALL_DEPTH_LIMIT: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComputedExpr {
+    Int(i128),
+    Bool(bool),
+    Char(char),
+    Str(String),
+    Tuple(Box<[ComputedExpr]>),
+}
+
+impl fmt::Display for ComputedExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ComputedExpr::Int(it) => write!(f, "{}", it),
+            ComputedExpr::Bool(it) => write!(f, "{}", it),
+            ComputedExpr::Char(it) => write!(f, "{:?}", it),
+            ComputedExpr::Str(it) => write!(f, "{:?}", it),
+            ComputedExpr::Tuple(fields) => {
+                write!(f, "(")?;
+                for (idx, field) in fields.iter().enumerate() {
+                    if idx != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", field)?;
+                }
+                if fields.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// A constant value that can appear at the type level, as the argument of a
+/// const generic parameter.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub enum ConstScalar {
+    Int(i128),
+    Bool(bool),
+    Char(char),
+}
+
+impl ConstScalar {
+    pub fn from_computed(value: &ComputedExpr) -> Option<ConstScalar> {
+        match *value {
+            ComputedExpr::Int(it) => Some(ConstScalar::Int(it)),
+            ComputedExpr::Bool(it) => Some(ConstScalar::Bool(it)),
+            ComputedExpr::Char(it) => Some(ConstScalar::Char(it)),
+            ComputedExpr::Str(_) | ComputedExpr::Tuple(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for ConstScalar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConstScalar::Int(it) => write!(f, "{}", it),
+            ConstScalar::Bool(it) => write!(f, "{}", it),
+            ConstScalar::Char(it) => write!(f, "{:?}", it),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConstEvalError {
+    /// The expression uses something the evaluator doesn't understand (yet).
+    NotSupported(&'static str),
+    /// An operation was applied to values of the wrong kind.
+    TypeError,
+    /// The expression is incomplete, e.g. because of a syntax error.
+    IncompleteExpr,
+    /// Evaluation would panic at compile time, e.g. because of an overflow.
+    Panic(String),
+    /// The constant refers to itself.
+    Cycle,
+}
+
+impl fmt::Display for ConstEvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConstEvalError::NotSupported(what) => write!(f, "unsupported {}", what),
+            ConstEvalError::TypeError => write!(f, "type error"),
+            ConstEvalError::IncompleteExpr => write!(f, "incomplete expression"),
+            ConstEvalError::Panic(msg) => write!(f, "evaluation panicked: {}", msg),
+            ConstEvalError::Cycle => write!(f, "cycle in constant evaluation"),
+        }
+    }
+}
+
+pub(crate) fn const_eval_query(
+    db: &dyn HirDatabase,
+    def: ConstId,
+) -> Result<ComputedExpr, ConstEvalError> {
+    let ctx = ConstEvalCtx::new(db, def.into(), 0);
+    ctx.eval_body()
+}
+
+pub(crate) fn const_eval_recover(
+    _db: &dyn HirDatabase,
+    _cycle: &[String],
+    _def: &ConstId,
+) -> Result<ComputedExpr, ConstEvalError> {
+    Err(ConstEvalError::Cycle)
+}
+
+pub(crate) fn enum_discriminant_query(
+    db: &dyn HirDatabase,
+    def: EnumVariantId,
+) -> Result<i128, ConstEvalError> {
+    let enum_data = db.enum_data(def.parent);
+    let resolver = def.parent.resolver(db.upcast());
+    let mut prev: Option<i128> = None;
+    for (local_id, variant) in enum_data.variants.iter() {
+        let value = match &variant.discriminant {
+            Some(const_ref) => eval_const_ref(db, &resolver, const_ref)?,
+            None => match prev {
+                Some(prev) => prev.checked_add(1).ok_or_else(overflow)?,
+                None => 0,
+            },
+        };
+        if local_id == def.local_id {
+            return Ok(value);
+        }
+        prev = Some(value);
+    }
+    Err(ConstEvalError::IncompleteExpr)
+}
+
+pub(crate) fn enum_discriminant_recover(
+    _db: &dyn HirDatabase,
+    _cycle: &[String],
+    _def: &EnumVariantId,
+) -> Result<i128, ConstEvalError> {
+    Err(ConstEvalError::Cycle)
+}
+
+/// Evaluates `expr`, an expression of the body of `owner`. This fails when
+/// the expression depends on runtime values, like local variables.
+pub fn eval_expr(
+    db: &dyn HirDatabase,
+    owner: DefWithBodyId,
+    expr: ExprId,
+) -> Result<ComputedExpr, ConstEvalError> {
+    ConstEvalCtx::new(db, owner, 0).eval(expr)
+}
+
+/// Evaluates a constant that lives outside of a body, like an enum
+/// discriminant, to an integer.
+pub fn eval_const_ref(
+    db: &dyn HirDatabase,
+    resolver: &Resolver,
+    const_ref: &ConstRef,
+) -> Result<i128, ConstEvalError> {
+    match const_ref {
+        ConstRef::Scalar(it) => Ok(*it),
+        ConstRef::Path(path) => {
+            let hygiene = SyntaxContext::ROOT;
+            match resolver.resolve_path_in_value_ns_fully(db.upcast(), path.mod_path(), hygiene) {
+                Some(ValueNs::ConstId(it)) => match db.const_eval(it)? {
+                    ComputedExpr::Int(it) => Ok(it),
+                    _ => Err(ConstEvalError::TypeError),
+                },
+                Some(_) => Err(ConstEvalError::NotSupported("path")),
+                None => Err(ConstEvalError::IncompleteExpr),
+            }
+        }
+        ConstRef::Unknown => Err(ConstEvalError::NotSupported("expression")),
+    }
+}
+
+struct ConstEvalCtx<'a> {
+    db: &'a dyn HirDatabase,
+    owner: DefWithBodyId,
+    body: Arc<Body>,
+    infer: Arc<InferenceResult>,
+    locals: FxHashMap<PatId, ComputedExpr>,
+    depth: usize,
+}
+
+impl<'a> ConstEvalCtx<'a> {
+    fn new(db: &'a dyn HirDatabase, owner: DefWithBodyId, depth: usize) -> ConstEvalCtx<'a> {
+        ConstEvalCtx {
+            db,
+            owner,
+            body: db.body(owner),
+            infer: db.infer(owner),
+            locals: FxHashMap::default(),
+            depth,
+        }
+    }
+
+    fn eval_body(mut self) -> Result<ComputedExpr, ConstEvalError> {
+        let body_expr = self.body.body_expr;
+        self.eval(body_expr)
+    }
+
+    fn eval(&mut self, expr: ExprId) -> Result<ComputedExpr, ConstEvalError> {
+        let body = Arc::clone(&self.body);
+        match &body[expr] {
+            Expr::Missing => Err(ConstEvalError::IncompleteExpr),
+            Expr::Literal(literal) => match literal {
+                Literal::Int(it, _) => self.check_int(expr, int_literal(*it)?),
+                Literal::Bool(it) => Ok(ComputedExpr::Bool(*it)),
+                Literal::Char(it) => Ok(ComputedExpr::Char(*it)),
+                Literal::String(it) => Ok(ComputedExpr::Str(it.clone())),
+                Literal::ByteString(_) => Err(ConstEvalError::NotSupported("byte string")),
+                Literal::Float(..) => Err(ConstEvalError::NotSupported("float")),
+            },
+            Expr::Path(path) => {
+                if let Some(AssocItemId::ConstId(it)) = self.infer.assoc_resolutions_for_expr(expr)
+                {
+                    return self.db.const_eval(it);
+                }
+                match self.resolve_value(expr, path)? {
+                    ValueNs::LocalBinding(pat) => {
+                        self.locals.get(&pat).cloned().ok_or(ConstEvalError::NotSupported("local"))
+                    }
+                    ValueNs::ConstId(it) => self.db.const_eval(it),
+                    ValueNs::StaticId(_) => Err(ConstEvalError::NotSupported("static")),
+                    _ => Err(ConstEvalError::NotSupported("path")),
+                }
+            }
+            Expr::Block { statements, tail } => {
+                for statement in statements {
+                    match statement {
+                        Statement::Let { pat, initializer, .. } => {
+                            let initializer =
+                                initializer.ok_or(ConstEvalError::NotSupported("uninit let"))?;
+                            let value = self.eval(initializer)?;
+                            self.bind(*pat, value)?;
+                        }
+                        Statement::Expr(it) => {
+                            self.eval(*it)?;
+                        }
+                    }
+                }
+                match tail {
+                    Some(tail) => self.eval(*tail),
+                    None => Ok(ComputedExpr::Tuple(Box::new([]))),
+                }
+            }
+            Expr::If { condition, then_branch, else_branch } => match self.eval(*condition)? {
+                ComputedExpr::Bool(true) => self.eval(*then_branch),
+                ComputedExpr::Bool(false) => match else_branch {
+                    Some(it) => self.eval(*it),
+                    None => Ok(ComputedExpr::Tuple(Box::new([]))),
+                },
+                _ => Err(ConstEvalError::TypeError),
+            },
+            Expr::Tuple { exprs } => {
+                let fields =
+                    exprs.iter().map(|it| self.eval(*it)).collect::<Result<Vec<_>, _>>()?;
+                Ok(ComputedExpr::Tuple(fields.into()))
+            }
+            Expr::UnaryOp { expr: inner, op } => {
+                // A negated literal is range checked only once it is negated,
+                // so that e.g. `-128i8` fits.
+                let value = match (op, &body[*inner]) {
+                    (UnaryOp::Neg, Expr::Literal(Literal::Int(it, _))) => {
+                        ComputedExpr::Int(int_literal(*it)?)
+                    }
+                    _ => self.eval(*inner)?,
+                };
+                match (op, value) {
+                    (UnaryOp::Neg, ComputedExpr::Int(it)) => {
+                        self.check_int(expr, it.checked_neg().ok_or_else(overflow)?)
+                    }
+                    (UnaryOp::Not, ComputedExpr::Bool(it)) => Ok(ComputedExpr::Bool(!it)),
+                    (UnaryOp::Not, ComputedExpr::Int(it)) => {
+                        Ok(ComputedExpr::Int(wrap_int(!it, &self.infer[expr])))
+                    }
+                    (UnaryOp::Deref, _) => Err(ConstEvalError::NotSupported("deref")),
+                    _ => Err(ConstEvalError::TypeError),
+                }
+            }
+            Expr::BinaryOp { lhs, rhs, op } => {
+                let op = op.ok_or(ConstEvalError::IncompleteExpr)?;
+                self.eval_binary_op(expr, *lhs, *rhs, op)
+            }
+            Expr::Cast { expr: inner, .. } => {
+                let value = match &body[*inner] {
+                    Expr::Path(path) => match self.resolve_value(*inner, path)? {
+                        ValueNs::EnumVariantId(it) => {
+                            ComputedExpr::Int(self.db.enum_discriminant(it)?)
+                        }
+                        _ => self.eval(*inner)?,
+                    },
+                    _ => self.eval(*inner)?,
+                };
+                let value = match value {
+                    ComputedExpr::Int(it) => it,
+                    ComputedExpr::Bool(it) => it as i128,
+                    ComputedExpr::Char(it) => it as i128,
+                    _ => return Err(ConstEvalError::NotSupported("cast")),
+                };
+                Ok(ComputedExpr::Int(wrap_int(value, &self.infer[expr])))
+            }
+            Expr::Call { callee, args } => {
+                let func = match &body[*callee] {
+                    Expr::Path(path) => match self.resolve_value(*callee, path)? {
+                        ValueNs::FunctionId(it) => it,
+                        _ => return Err(ConstEvalError::NotSupported("call")),
+                    },
+                    _ => return Err(ConstEvalError::NotSupported("call")),
+                };
+                let args = args.iter().map(|it| self.eval(*it)).collect::<Result<Vec<_>, _>>()?;
+                self.eval_call(func, args)
+            }
+            _ => Err(ConstEvalError::NotSupported("expression")),
+        }
+    }
+
+    fn eval_binary_op(
+        &mut self,
+        expr: ExprId,
+        lhs: ExprId,
+        rhs: ExprId,
+        op: BinaryOp,
+    ) -> Result<ComputedExpr, ConstEvalError> {
+        if let BinaryOp::LogicOp(op) = op {
+            let lhs = self.eval_bool(lhs)?;
+            return match (op, lhs) {
+                (LogicOp::And, false) => Ok(ComputedExpr::Bool(false)),
+                (LogicOp::Or, true) => Ok(ComputedExpr::Bool(true)),
+                _ => self.eval_bool(rhs).map(ComputedExpr::Bool),
+            };
+        }
+
+        let lhs = self.eval(lhs)?;
+        let rhs = self.eval(rhs)?;
+        match op {
+            BinaryOp::CmpOp(CmpOp::Eq { negated }) => {
+                Ok(ComputedExpr::Bool((lhs == rhs) != negated))
+            }
+            BinaryOp::CmpOp(CmpOp::Ord { ordering, strict }) => {
+                let ord = match (lhs, rhs) {
+                    (ComputedExpr::Int(lhs), ComputedExpr::Int(rhs)) => lhs.cmp(&rhs),
+                    (ComputedExpr::Char(lhs), ComputedExpr::Char(rhs)) => lhs.cmp(&rhs),
+                    (ComputedExpr::Bool(lhs), ComputedExpr::Bool(rhs)) => lhs.cmp(&rhs),
+                    _ => return Err(ConstEvalError::TypeError),
+                };
+                let res = match (ordering, strict) {
+                    (Ordering::Less, true) => ord == std::cmp::Ordering::Less,
+                    (Ordering::Less, false) => ord != std::cmp::Ordering::Greater,
+                    (Ordering::Greater, true) => ord == std::cmp::Ordering::Greater,
+                    (Ordering::Greater, false) => ord != std::cmp::Ordering::Less,
+                };
+                Ok(ComputedExpr::Bool(res))
+            }
+            BinaryOp::ArithOp(op) => match (lhs, rhs) {
+                (ComputedExpr::Int(lhs), ComputedExpr::Int(rhs)) => {
+                    let value = match op {
+                        ArithOp::Add => lhs.checked_add(rhs).ok_or_else(overflow)?,
+                        ArithOp::Sub => lhs.checked_sub(rhs).ok_or_else(overflow)?,
+                        ArithOp::Mul => lhs.checked_mul(rhs).ok_or_else(overflow)?,
+                        ArithOp::Div => lhs.checked_div(rhs).ok_or_else(division_by_zero)?,
+                        ArithOp::Rem => lhs.checked_rem(rhs).ok_or_else(division_by_zero)?,
+                        ArithOp::Shl => {
+                            lhs.checked_shl(self.shift_amount(expr, rhs)?).ok_or_else(overflow)?
+                        }
+                        ArithOp::Shr => {
+                            lhs.checked_shr(self.shift_amount(expr, rhs)?).ok_or_else(overflow)?
+                        }
+                        ArithOp::BitXor => lhs ^ rhs,
+                        ArithOp::BitOr => lhs | rhs,
+                        ArithOp::BitAnd => lhs & rhs,
+                    };
+                    self.check_int(expr, value)
+                }
+                (ComputedExpr::Bool(lhs), ComputedExpr::Bool(rhs)) => match op {
+                    ArithOp::BitXor => Ok(ComputedExpr::Bool(lhs ^ rhs)),
+                    ArithOp::BitOr => Ok(ComputedExpr::Bool(lhs | rhs)),
+                    ArithOp::BitAnd => Ok(ComputedExpr::Bool(lhs & rhs)),
+                    _ => Err(ConstEvalError::TypeError),
+                },
+                _ => Err(ConstEvalError::TypeError),
+            },
+            BinaryOp::Assignment { .. } => Err(ConstEvalError::NotSupported("assignment")),
+            BinaryOp::LogicOp(_) => unreachable!(),
+        }
+    }
+
+    fn eval_bool(&mut self, expr: ExprId) -> Result<bool, ConstEvalError> {
+        match self.eval(expr)? {
+            ComputedExpr::Bool(it) => Ok(it),
+            _ => Err(ConstEvalError::TypeError),
+        }
+    }
+
+    fn eval_call(
+        &mut self,
+        func: FunctionId,
+        args: Vec<ComputedExpr>,
+    ) -> Result<ComputedExpr, ConstEvalError> {
+        if !self.db.function_data(func).is_const {
+            return Err(ConstEvalError::NotSupported("call to non-const fn"));
+        }
+        if self.depth >= CALL_DEPTH_LIMIT {
+            return Err(ConstEvalError::Panic("reached the recursion limit".to_string()));
+        }
+        let mut ctx = ConstEvalCtx::new(self.db, func.into(), self.depth + 1);
+        let params = ctx.body.params.clone();
+        if params.len() != args.len() {
+            return Err(ConstEvalError::IncompleteExpr);
+        }
+        for (pat, arg) in params.into_iter().zip(args) {
+            ctx.bind(pat, arg)?;
+        }
+        ctx.eval_body()
+    }
+
+    fn bind(&mut self, pat: PatId, value: ComputedExpr) -> Result<(), ConstEvalError> {
+        match &self.body[pat] {
+            Pat::Bind { subpat: None, .. } => {
+                self.locals.insert(pat, value);
+                Ok(())
+            }
+            Pat::Wild => Ok(()),
+            _ => Err(ConstEvalError::NotSupported("pattern")),
+        }
+    }
+
+    fn resolve_value(
+        &self,
+        expr: ExprId,
+        path: &hir_def::path::Path,
+    ) -> Result<ValueNs, ConstEvalError> {
+        let resolver = resolver_for_expr(self.db.upcast(), self.owner, expr);
+        let hygiene = self.body.expr_hygiene(expr);
+        resolver
+            .resolve_path_in_value_ns_fully(self.db.upcast(), path.mod_path(), hygiene)
+            .ok_or(ConstEvalError::IncompleteExpr)
+    }
+
+    /// Checks that a shift by `rhs` stays within the bits of the type of `expr`.
+    fn shift_amount(&self, expr: ExprId, rhs: i128) -> Result<u32, ConstEvalError> {
+        let bits = int_ty(&self.infer[expr]).map_or(128, int_bits);
+        if rhs < 0 || rhs >= bits as i128 {
+            return Err(overflow());
+        }
+        Ok(rhs as u32)
+    }
+
+    /// Checks that `value` fits into the integer type of `expr`.
+    fn check_int(&self, expr: ExprId, value: i128) -> Result<ComputedExpr, ConstEvalError> {
+        match int_ty(&self.infer[expr]) {
+            Some(ty) if value < int_min(ty) || value > int_max(ty) => Err(overflow()),
+            _ => Ok(ComputedExpr::Int(value)),
+        }
+    }
+}
+
+fn int_ty(ty: &Ty) -> Option<IntTy> {
+    match ty {
+        Ty::Apply(a_ty) => match a_ty.ctor {
+            TypeCtor::Int(Uncertain::Known(it)) => Some(it),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn int_bits(ty: IntTy) -> u32 {
+    match ty.bitness {
+        IntBitness::X8 => 8,
+        IntBitness::X16 => 16,
+        IntBitness::X32 => 32,
+        // FIXME: this depends on the target
+        IntBitness::X64 | IntBitness::Xsize => 64,
+        IntBitness::X128 => 128,
+    }
+}
+
+pub(crate) fn int_min(ty: IntTy) -> i128 {
+    match ty.signedness {
+        Signedness::Unsigned => 0,
+        Signedness::Signed => i128::min_value() >> (128 - int_bits(ty)),
+    }
+}
+
+pub(crate) fn int_max(ty: IntTy) -> i128 {
+    match (ty.signedness, int_bits(ty)) {
+        (_, 128) => i128::max_value(),
+        (Signedness::Unsigned, bits) => (1 << bits) - 1,
+        (Signedness::Signed, bits) => (1 << (bits - 1)) - 1,
+    }
+}
+
+/// Truncates `value` to the integer type `ty`, like an `as` cast does.
+fn wrap_int(value: i128, ty: &Ty) -> i128 {
+    let ty = match int_ty(ty) {
+        Some(it) => it,
+        None => return value,
+    };
+    let bits = int_bits(ty);
+    if bits == 128 {
+        return value;
+    }
+    let truncated = value & ((1 << bits) - 1);
+    match ty.signedness {
+        Signedness::Signed if truncated > int_max(ty) => truncated - (1 << bits),
+        _ => truncated,
+    }
+}
+
+fn int_literal(value: u128) -> Result<i128, ConstEvalError> {
+    if value > i128::max_value() as u128 {
+        return Err(ConstEvalError::NotSupported("u128 literal"));
+    }
+    Ok(value as i128)
+}
+
+fn overflow() -> ConstEvalError {
+    ConstEvalError::Panic("attempt to compute with overflow".to_string())
+}
+
+fn division_by_zero() -> ConstEvalError {
+    ConstEvalError::Panic("attempt to divide by zero".to_string())
+}
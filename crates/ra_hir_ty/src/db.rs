@@ -3,24 +3,30 @@
 use std::sync::Arc;
 
 use hir_def::{
-    db::DefDatabase, DefWithBodyId, GenericDefId, ImplId, LocalFieldId, TraitId, TypeParamId,
-    VariantId,
+    db::DefDatabase, AdtId, ConstId, ConstParamId, DefWithBodyId, EnumVariantId, GenericDefId,
+    ImplId, LocalFieldId, TraitId, TypeParamId, VariantId,
 };
 use ra_arena::map::ArenaMap;
 use ra_db::{impl_intern_key, salsa, CrateId, Upcast};
 use ra_prof::profile;
 
 use crate::{
-    method_resolution::{CrateImplDefs, TyFingerprint},
-    traits::{chalk, AssocTyValue, Impl},
-    Binders, CallableDef, GenericPredicate, InferenceResult, PolyFnSig, Substs, TraitRef, Ty,
-    TyDefId, TypeCtor, ValueTyDefId,
+    consteval::{ComputedExpr, ConstEvalError},
+    layout::{Layout, LayoutError},
+    method_resolution::{CrateImplDefs, MethodCandidate, TyFingerprint},
+    traits::{chalk, AssocTyValue, Impl, TraitSolverLimits},
+    Binders, CallableDef, Canonical, GenericPredicate, InferenceResult, PolyFnSig, Substs,
+    TraitEnvironment, TraitRef, Ty, TyDefId, TypeCtor, ValueTyDefId,
 };
 use hir_expand::name::Name;
 
 #[salsa::query_group(HirDatabaseStorage)]
 #[salsa::requires(salsa::Database)]
 pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
+    /// How much work the trait solver may do for a single goal.
+    #[salsa::input]
+    fn trait_solver_limits(&self) -> TraitSolverLimits;
+
     #[salsa::invoke(infer_wait)]
     #[salsa::transparent]
     fn infer(&self, def: DefWithBodyId) -> Arc<InferenceResult>;
@@ -42,6 +48,21 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
     #[salsa::invoke(crate::lower::impl_trait_query)]
     fn impl_trait(&self, def: ImplId) -> Option<Binders<TraitRef>>;
 
+    #[salsa::invoke(crate::lower::const_param_ty_query)]
+    fn const_param_ty(&self, def: ConstParamId) -> Ty;
+
+    #[salsa::invoke(crate::consteval::const_eval_query)]
+    #[salsa::cycle(crate::consteval::const_eval_recover)]
+    fn const_eval(&self, def: ConstId) -> Result<ComputedExpr, ConstEvalError>;
+
+    #[salsa::invoke(crate::consteval::enum_discriminant_query)]
+    #[salsa::cycle(crate::consteval::enum_discriminant_recover)]
+    fn enum_discriminant(&self, def: EnumVariantId) -> Result<i128, ConstEvalError>;
+
+    #[salsa::invoke(crate::layout::adt_layout_query)]
+    #[salsa::cycle(crate::layout::adt_layout_recover)]
+    fn adt_layout(&self, def: AdtId, substs: Substs) -> Result<Arc<Layout>, LayoutError>;
+
     #[salsa::invoke(crate::lower::field_types_query)]
     fn field_types(&self, var: VariantId) -> Arc<ArenaMap<LocalFieldId, Binders<Ty>>>;
 
@@ -64,6 +85,16 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
     #[salsa::invoke(crate::method_resolution::CrateImplDefs::impls_in_crate_query)]
     fn impls_in_crate(&self, krate: CrateId) -> Arc<CrateImplDefs>;
 
+    #[salsa::invoke(crate::method_resolution::lookup_method_query)]
+    fn lookup_method(
+        &self,
+        krate: CrateId,
+        ty: Canonical<Ty>,
+        env: Arc<TraitEnvironment>,
+        traits_in_scope: Arc<[TraitId]>,
+        name: Name,
+    ) -> Option<MethodCandidate>;
+
     #[salsa::invoke(crate::traits::impls_for_trait_query)]
     fn impls_for_trait(
         &self,
@@ -81,6 +112,11 @@ pub trait HirDatabase: DefDatabase + Upcast<dyn DefDatabase> {
     fn intern_chalk_impl(&self, impl_: Impl) -> crate::traits::GlobalImplId;
     #[salsa::interned]
     fn intern_assoc_ty_value(&self, assoc_ty_value: AssocTyValue) -> crate::traits::AssocTyValueId;
+    #[salsa::interned]
+    fn intern_opaque_bounds(
+        &self,
+        bounds: crate::traits::OpaqueBounds,
+    ) -> crate::traits::OpaqueBoundsId;
 
     #[salsa::invoke(chalk::associated_ty_data_query)]
     fn associated_ty_data(&self, id: chalk::AssocTypeId) -> Arc<chalk::AssociatedTyDatum>;
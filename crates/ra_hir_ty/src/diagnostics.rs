@@ -6,7 +6,10 @@ use hir_expand::{db::AstDatabase, name::Name, HirFileId, InFile};
 use ra_syntax::{ast, AstNode, AstPtr, SyntaxNodePtr};
 use stdx::format_to;
 
-pub use hir_def::{diagnostics::UnresolvedModule, expr::MatchArm};
+pub use hir_def::{
+    diagnostics::{InactiveCode, UnresolvedMacroCall, UnresolvedModule},
+    expr::MatchArm,
+};
 pub use hir_expand::diagnostics::{AstDiagnostic, Diagnostic, DiagnosticSink};
 
 #[derive(Debug)]
@@ -16,6 +19,10 @@ pub struct NoSuchField {
 }
 
 impl Diagnostic for NoSuchField {
+    fn code(&self) -> &'static str {
+        "no-such-field"
+    }
+
     fn message(&self) -> String {
         "no such field".to_string()
     }
@@ -29,6 +36,56 @@ impl Diagnostic for NoSuchField {
     }
 }
 
+#[derive(Debug)]
+pub struct UnresolvedName {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+    pub name: String,
+}
+
+impl Diagnostic for UnresolvedName {
+    fn code(&self) -> &'static str {
+        "unresolved-name"
+    }
+
+    fn message(&self) -> String {
+        format!("unresolved name `{}`", self.name)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.expr.clone().into())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct PrivateItem {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+    pub name: String,
+}
+
+impl Diagnostic for PrivateItem {
+    fn code(&self) -> &'static str {
+        "private-item"
+    }
+
+    fn message(&self) -> String {
+        format!("`{}` is private", self.name)
+    }
+
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile::new(self.file, self.expr.clone().into())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
 #[derive(Debug)]
 pub struct MissingFields {
     pub file: HirFileId,
@@ -37,6 +94,10 @@ pub struct MissingFields {
 }
 
 impl Diagnostic for MissingFields {
+    fn code(&self) -> &'static str {
+        "missing-structure-fields"
+    }
+
     fn message(&self) -> String {
         let mut buf = String::from("Missing structure fields:\n");
         for field in &self.missed_fields {
@@ -70,6 +131,10 @@ pub struct MissingPatFields {
 }
 
 impl Diagnostic for MissingPatFields {
+    fn code(&self) -> &'static str {
+        "missing-pat-fields"
+    }
+
     fn message(&self) -> String {
         let mut buf = String::from("Missing structure fields:\n");
         for field in &self.missed_fields {
@@ -93,6 +158,10 @@ pub struct MissingMatchArms {
 }
 
 impl Diagnostic for MissingMatchArms {
+    fn code(&self) -> &'static str {
+        "missing-match-arm"
+    }
+
     fn message(&self) -> String {
         String::from("Missing match arm")
     }
@@ -104,6 +173,30 @@ impl Diagnostic for MissingMatchArms {
     }
 }
 
+#[derive(Debug)]
+pub struct TypeMismatch {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl Diagnostic for TypeMismatch {
+    fn code(&self) -> &'static str {
+        "type-mismatch"
+    }
+
+    fn message(&self) -> String {
+        format!("Mismatched types: expected `{}`, found `{}`", self.expected, self.actual)
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
 #[derive(Debug)]
 pub struct MissingOkInTailExpr {
     pub file: HirFileId,
@@ -111,6 +204,10 @@ pub struct MissingOkInTailExpr {
 }
 
 impl Diagnostic for MissingOkInTailExpr {
+    fn code(&self) -> &'static str {
+        "missing-ok-in-tail-expr"
+    }
+
     fn message(&self) -> String {
         "wrap return expression in Ok".to_string()
     }
@@ -131,3 +228,35 @@ impl AstDiagnostic for MissingOkInTailExpr {
         ast::Expr::cast(node).unwrap()
     }
 }
+
+#[derive(Debug)]
+pub struct MissingSomeInTailExpr {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+}
+
+impl Diagnostic for MissingSomeInTailExpr {
+    fn code(&self) -> &'static str {
+        "missing-some-in-tail-expr"
+    }
+
+    fn message(&self) -> String {
+        "wrap return expression in Some".to_string()
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.clone().into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+impl AstDiagnostic for MissingSomeInTailExpr {
+    type AST = ast::Expr;
+
+    fn ast(&self, db: &impl AstDatabase) -> Self::AST {
+        let root = db.parse_or_expand(self.file).unwrap();
+        let node = self.source().value.to_node(&root);
+        ast::Expr::cast(node).unwrap()
+    }
+}
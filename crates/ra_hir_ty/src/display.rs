@@ -124,8 +124,12 @@ impl HirDisplay for ApplicationTy {
                 write!(f, "[{}]", t.display(f.db))?;
             }
             TypeCtor::Array => {
-                let t = self.parameters.as_single();
-                write!(f, "[{}; _]", t.display(f.db))?;
+                write!(f, "[{}; ", self.parameters[0].display(f.db))?;
+                match &self.parameters[1] {
+                    Ty::Unknown | Ty::Infer(_) => write!(f, "_")?,
+                    len => len.hir_fmt(f)?,
+                }
+                write!(f, "]")?;
             }
             TypeCtor::RawPtr(m) => {
                 let t = self.parameters.as_single();
@@ -141,6 +145,7 @@ impl HirDisplay for ApplicationTy {
                 write!(f, "&{}{}", m.as_keyword_for_ref(), ty_display)?;
             }
             TypeCtor::Never => write!(f, "!")?,
+            TypeCtor::Const(value) => write!(f, "{}", value)?,
             TypeCtor::Tuple { .. } => {
                 let ts = &self.parameters;
                 if ts.len() == 1 {
@@ -299,7 +304,9 @@ impl HirDisplay for Ty {
                 let generics = generics(f.db.upcast(), id.parent);
                 let param_data = &generics.params.types[id.local_id];
                 match param_data.provenance {
-                    TypeParamProvenance::TypeParamList | TypeParamProvenance::TraitSelf => {
+                    TypeParamProvenance::TypeParamList
+                    | TypeParamProvenance::TraitSelf
+                    | TypeParamProvenance::ConstParam => {
                         write!(f, "{}", param_data.name.clone().unwrap_or_else(Name::missing))?
                     }
                     TypeParamProvenance::ArgumentImplTrait => {
@@ -8,11 +8,15 @@ use ra_syntax::{ast, AstPtr};
 use rustc_hash::FxHashSet;
 
 use crate::{
+    _match::{is_useful, MatchCheckCtx, Matrix, PatStack, Usefulness},
     db::HirDatabase,
-    diagnostics::{MissingFields, MissingMatchArms, MissingOkInTailExpr, MissingPatFields},
+    diagnostics::{
+        MissingFields, MissingMatchArms, MissingOkInTailExpr, MissingPatFields,
+        MissingSomeInTailExpr, TypeMismatch,
+    },
+    display::HirDisplay,
     utils::variant_data,
     ApplicationTy, InferenceResult, Ty, TypeCtor,
-    _match::{is_useful, MatchCheckCtx, Matrix, PatStack, Usefulness},
 };
 
 pub use hir_def::{
@@ -46,6 +50,7 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
         let body = db.body(self.func.into());
 
         for (id, expr) in body.exprs.iter() {
+            db.check_canceled();
             if let Some((variant_def, missed_fields, true)) =
                 record_literal_missing_fields(db, &self.infer, id, expr)
             {
@@ -73,8 +78,38 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
             }
         }
         let body_expr = &body[body.body_expr];
+        let mut reported_mismatches = FxHashSet::default();
         if let Expr::Block { tail: Some(t), .. } = body_expr {
-            self.validate_results_in_tail_expr(body.body_expr, *t, db);
+            if self.validate_tail_expr_wrapping(body.body_expr, *t, db) {
+                reported_mismatches.insert(innermost_tail_expr(&body, body.body_expr));
+            }
+        }
+        self.validate_type_mismatches(&body, reported_mismatches, db);
+    }
+
+    fn validate_type_mismatches(
+        &mut self,
+        body: &Body,
+        mut reported: FxHashSet<ExprId>,
+        db: &dyn HirDatabase,
+    ) {
+        let (_, source_map) = db.body_with_source_map(self.func.into());
+        for (id, mismatch) in self.infer.expr_type_mismatches() {
+            // A block has the type of its tail expression, so that's where the
+            // mismatch is reported. The tail expression usually has a mismatch
+            // of its own, which is then reported only once.
+            let id = innermost_tail_expr(body, id);
+            if !reported.insert(id) {
+                continue;
+            }
+            if let Ok(source_ptr) = source_map.expr_syntax(id) {
+                self.sink.push(TypeMismatch {
+                    file: source_ptr.file_id,
+                    expr: source_ptr.value,
+                    expected: mismatch.expected.display(db).to_string(),
+                    actual: mismatch.actual.display(db).to_string(),
+                })
+            }
         }
     }
 
@@ -156,7 +191,8 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
             None => return,
         };
 
-        let cx = MatchCheckCtx { match_expr, body, infer: infer.clone(), db };
+        let cx =
+            MatchCheckCtx { owner: self.func.into(), match_expr, body, infer: infer.clone(), db };
         let pats = arms.iter().map(|arm| arm.pat);
 
         let mut seen = Matrix::empty();
@@ -219,36 +255,56 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
         }
     }
 
-    fn validate_results_in_tail_expr(&mut self, body_id: ExprId, id: ExprId, db: &dyn HirDatabase) {
+    /// Returns whether the type mismatch of the body was reported as a missing
+    /// `Ok` or `Some`.
+    fn validate_tail_expr_wrapping(
+        &mut self,
+        body_id: ExprId,
+        id: ExprId,
+        db: &dyn HirDatabase,
+    ) -> bool {
         // the mismatch will be on the whole block currently
         let mismatch = match self.infer.type_mismatch_for_expr(body_id) {
             Some(m) => m,
-            None => return,
+            None => return false,
         };
 
-        let std_result_path = path![std::result::Result];
+        let (enum_id, params) = match &mismatch.expected {
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::Adt(AdtId::EnumId(it)), parameters }) => {
+                (*it, parameters)
+            }
+            _ => return false,
+        };
+        if params.get(0) != Some(&mismatch.actual) {
+            return false;
+        }
 
         let resolver = self.func.resolver(db.upcast());
-        let std_result_enum = match resolver.resolve_known_enum(db.upcast(), &std_result_path) {
-            Some(it) => it,
-            _ => return,
-        };
+        let std_result_enum = resolver.resolve_known_enum(db.upcast(), &path![std::result::Result]);
+        let std_option_enum = resolver.resolve_known_enum(db.upcast(), &path![std::option::Option]);
 
-        let std_result_ctor = TypeCtor::Adt(AdtId::EnumId(std_result_enum));
-        let params = match &mismatch.expected {
-            Ty::Apply(ApplicationTy { ctor, parameters }) if ctor == &std_result_ctor => parameters,
-            _ => return,
+        let (_, source_map) = db.body_with_source_map(self.func.into());
+        let source_ptr = match source_map.expr_syntax(id) {
+            Ok(it) => it,
+            Err(_) => return false,
         };
-
-        if params.len() == 2 && params[0] == mismatch.actual {
-            let (_, source_map) = db.body_with_source_map(self.func.into());
-
-            if let Ok(source_ptr) = source_map.expr_syntax(id) {
-                self.sink
-                    .push(MissingOkInTailExpr { file: source_ptr.file_id, expr: source_ptr.value });
-            }
+        let (file, expr) = (source_ptr.file_id, source_ptr.value);
+        if Some(enum_id) == std_result_enum && params.len() == 2 {
+            self.sink.push(MissingOkInTailExpr { file, expr });
+        } else if Some(enum_id) == std_option_enum && params.len() == 1 {
+            self.sink.push(MissingSomeInTailExpr { file, expr });
+        } else {
+            return false;
         }
+        true
+    }
+}
+
+fn innermost_tail_expr(body: &Body, mut id: ExprId) -> ExprId {
+    while let Expr::Block { tail: Some(tail), .. } = &body[id] {
+        id = *tail;
     }
+    id
 }
 
 pub fn record_literal_missing_fields(
@@ -15,7 +15,7 @@
 
 use std::borrow::Cow;
 use std::mem;
-use std::ops::Index;
+use std::ops::{BitAnd, BitAndAssign, BitOr, Index};
 use std::sync::Arc;
 
 use rustc_hash::FxHashMap;
@@ -38,11 +38,12 @@ use ra_syntax::SmolStr;
 use super::{
     primitive::{FloatTy, IntTy},
     traits::{Guidance, Obligation, ProjectionPredicate, Solution},
-    ApplicationTy, GenericPredicate, InEnvironment, ProjectionTy, Substs, TraitEnvironment,
-    TraitRef, Ty, TypeCtor, TypeWalk, Uncertain,
+    ApplicationTy, BoundVar, DebruijnIndex, GenericPredicate, InEnvironment, ProjectionTy, Substs,
+    TraitEnvironment, TraitRef, Ty, TypeCtor, TypeWalk, Uncertain,
 };
 use crate::{
     db::HirDatabase, infer::diagnostics::InferenceDiagnostic, lower::ImplTraitLoweringMode,
+    method_resolution::ReceiverAdjustments,
 };
 
 pub(crate) use unify::unify;
@@ -63,6 +64,11 @@ mod pat;
 mod coerce;
 
 /// The entry point of type inference.
+///
+/// A body is inferred as a whole: inference checks for cancellation between
+/// statements, but a cancelled run starts over from the beginning, reusing only
+/// memoized queries like `lookup_method`.
+// FIXME: split inference of large bodies into per-block queries.
 pub(crate) fn infer_query(db: &dyn HirDatabase, def: DefWithBodyId) -> Arc<InferenceResult> {
     let _p = profile("infer_query");
     let resolver = def.resolver(db.upcast());
@@ -75,6 +81,7 @@ pub(crate) fn infer_query(db: &dyn HirDatabase, def: DefWithBodyId) -> Arc<Infer
     }
 
     ctx.infer_body();
+    ctx.apply_fallback();
 
     Arc::new(ctx.resolve_all())
 }
@@ -123,6 +130,9 @@ pub struct TypeMismatch {
 pub struct InferenceResult {
     /// For each method call expr, records the function it resolves to.
     method_resolutions: FxHashMap<ExprId, FunctionId>,
+    /// For each method call expr, records the autoderefs and autoref applied
+    /// to its receiver.
+    receiver_adjustments: FxHashMap<ExprId, ReceiverAdjustments>,
     /// For each field access expr, records the field it resolves to.
     field_resolutions: FxHashMap<ExprId, FieldId>,
     /// For each field in record literal, records the field it resolves to.
@@ -142,6 +152,9 @@ impl InferenceResult {
     pub fn method_resolution(&self, expr: ExprId) -> Option<FunctionId> {
         self.method_resolutions.get(&expr).copied()
     }
+    pub fn receiver_adjustments(&self, expr: ExprId) -> Option<ReceiverAdjustments> {
+        self.receiver_adjustments.get(&expr).copied()
+    }
     pub fn field_resolution(&self, expr: ExprId) -> Option<FieldId> {
         self.field_resolutions.get(&expr).copied()
     }
@@ -166,6 +179,9 @@ impl InferenceResult {
     pub fn type_mismatch_for_expr(&self, expr: ExprId) -> Option<&TypeMismatch> {
         self.type_mismatches.get(expr)
     }
+    pub fn expr_type_mismatches(&self) -> impl Iterator<Item = (ExprId, &TypeMismatch)> {
+        self.type_mismatches.iter()
+    }
     pub fn add_diagnostics(
         &self,
         db: &dyn HirDatabase,
@@ -210,6 +226,19 @@ struct InferenceContext<'a> {
     /// closures, but currently this is the only field that will change there,
     /// so it doesn't make sense.
     return_ty: Ty,
+    /// Whether the expressions inferred so far along the current control flow
+    /// path are guaranteed to diverge, e.g. because they contain a `return`.
+    diverges: Diverges,
+    /// The loops we're currently inside of, innermost last.
+    breakables: Vec<BreakableContext>,
+}
+
+#[derive(Clone, Debug)]
+struct BreakableContext {
+    /// Whether the loop is exited by some `break`.
+    may_break: bool,
+    /// The type of the values passed to `break`, which is the type of the loop.
+    break_ty: Ty,
 }
 
 impl<'a> InferenceContext<'a> {
@@ -219,6 +248,8 @@ impl<'a> InferenceContext<'a> {
             table: unify::InferenceTable::new(),
             obligations: Vec::default(),
             return_ty: Ty::Unknown, // set in collect_fn_signature
+            diverges: Diverges::Maybe,
+            breakables: Vec::new(),
             trait_env: TraitEnvironment::lower(db, &resolver),
             db,
             owner,
@@ -227,6 +258,16 @@ impl<'a> InferenceContext<'a> {
         }
     }
 
+    /// Applies never-type fallback: the types of diverging expressions that
+    /// were not constrained by anything become `!`. Obligations are resolved
+    /// again afterwards, since they might only be solvable now, e.g. a
+    /// projection on the result of `foo(return)`.
+    fn apply_fallback(&mut self) {
+        if self.table.fallback_diverging_type_vars() {
+            self.resolve_obligations_as_possible();
+        }
+    }
+
     fn resolve_all(mut self) -> InferenceResult {
         // FIXME resolve obligations as well (use Guidance if necessary)
         let mut result = std::mem::take(&mut self.result);
@@ -249,6 +290,10 @@ impl<'a> InferenceContext<'a> {
         self.result.method_resolutions.insert(expr, func);
     }
 
+    fn write_receiver_adjustments(&mut self, expr: ExprId, adjustments: ReceiverAdjustments) {
+        self.result.receiver_adjustments.insert(expr, adjustments);
+    }
+
     fn write_field_resolution(&mut self, expr: ExprId, field: FieldId) {
         self.result.field_resolutions.insert(expr, field);
     }
@@ -307,6 +352,7 @@ impl<'a> InferenceContext<'a> {
     fn resolve_obligations_as_possible(&mut self) {
         let obligations = mem::replace(&mut self.obligations, Vec::new());
         for obligation in obligations {
+            self.db.check_canceled();
             let in_env = InEnvironment::new(self.trait_env.clone(), obligation.clone());
             let canonicalized = self.canonicalizer().canonicalize_obligation(in_env);
             let solution =
@@ -370,11 +416,12 @@ impl<'a> InferenceContext<'a> {
                     for p in predicates.iter() {
                         if let GenericPredicate::Projection(projection) = p {
                             if projection.projection_ty.associated_ty == res_assoc_ty {
-                                if let ty_app!(_, params) = &projection.ty {
-                                    if params.len() == 0 {
-                                        return projection.ty.clone();
-                                    }
-                                }
+                                // the predicates are bound with the opaque type itself as
+                                // the only bound variable
+                                return projection
+                                    .ty
+                                    .clone()
+                                    .subst_bound_vars(&Substs::single(inner_ty.clone()));
                             }
                         }
                     }
@@ -488,10 +535,37 @@ impl<'a> InferenceContext<'a> {
 
             self.infer_pat(*pat, &ty, BindingMode::default());
         }
-        let return_ty = self.make_ty_with_mode(&data.ret_type, ImplTraitLoweringMode::Disallowed); // FIXME implement RPIT
+        let return_ty = if data.is_async {
+            // the body of an `async fn` evaluates to the output of the returned future
+            let future_ty = self.make_ty_with_mode(&data.ret_type, ImplTraitLoweringMode::Opaque);
+            self.resolve_associated_type(future_ty, self.resolve_future_future_output())
+        } else {
+            let return_ty = self.make_ty_with_mode(&data.ret_type, ImplTraitLoweringMode::Opaque);
+            self.insert_vars_for_impl_trait(return_ty)
+        };
         self.return_ty = return_ty;
     }
 
+    /// Replaces each `impl Trait` in the return type by a fresh type variable,
+    /// so the body can determine the hidden type, and registers the bounds as
+    /// obligations on it.
+    fn insert_vars_for_impl_trait(&mut self, ty: Ty) -> Ty {
+        ty.fold(&mut |ty| match ty {
+            Ty::Opaque(predicates) => {
+                let var = self.table.new_type_var();
+                let substs = Substs::single(var.clone());
+                for predicate in predicates.iter() {
+                    let predicate = predicate.clone().subst_bound_vars(&substs);
+                    if let Some(obligation) = Obligation::from_predicate(predicate) {
+                        self.obligations.push(obligation);
+                    }
+                }
+                var
+            }
+            _ => ty,
+        })
+    }
+
     fn infer_body(&mut self) {
         self.infer_expr_coerce(self.body.body_expr, &Expectation::has_type(self.return_ty.clone()));
     }
@@ -529,6 +603,32 @@ impl<'a> InferenceContext<'a> {
         self.db.trait_data(trait_).associated_type_by_name(&name![Output])
     }
 
+    /// Builds `impl Future<Output = inner_ty>`, the type of an async block.
+    fn make_future_ty(&self, inner_ty: Ty) -> Ty {
+        let future_trait = match self.resolve_lang_item("future_trait").and_then(|it| it.as_trait())
+        {
+            Some(it) => it,
+            None => return Ty::Unknown,
+        };
+        let self_ty = Ty::Bound(BoundVar::new(DebruijnIndex::INNERMOST, 0));
+        let mut predicates = vec![GenericPredicate::Implemented(TraitRef {
+            trait_: future_trait,
+            substs: Substs::single(self_ty.clone()),
+        })];
+        if let Some(output) =
+            self.db.trait_data(future_trait).associated_type_by_name(&name![Output])
+        {
+            predicates.push(GenericPredicate::Projection(ProjectionPredicate {
+                projection_ty: ProjectionTy {
+                    associated_ty: output,
+                    parameters: Substs::single(self_ty),
+                },
+                ty: inner_ty.shift_bound_vars(DebruijnIndex::ONE),
+            }));
+        }
+        Ty::Opaque(predicates.into())
+    }
+
     fn resolve_boxed_box(&self) -> Option<AdtId> {
         let struct_ = self.resolve_lang_item("owned_box")?.as_struct()?;
         Some(struct_.into())
@@ -612,6 +712,40 @@ impl InferTy {
     }
 }
 
+/// Whether an expression is known to diverge. Control flow joins combine
+/// these like booleans: `Always` is `true`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Diverges {
+    Maybe,
+    Always,
+}
+
+impl Diverges {
+    fn is_always(self) -> bool {
+        self == Diverges::Always
+    }
+}
+
+impl BitAnd for Diverges {
+    type Output = Self;
+    fn bitand(self, other: Self) -> Self {
+        std::cmp::min(self, other)
+    }
+}
+
+impl BitOr for Diverges {
+    type Output = Self;
+    fn bitor(self, other: Self) -> Self {
+        std::cmp::max(self, other)
+    }
+}
+
+impl BitAndAssign for Diverges {
+    fn bitand_assign(&mut self, other: Self) {
+        *self = *self & other;
+    }
+}
+
 /// When inferring an expression, we propagate downward whatever type hint we
 /// are able in the form of an `Expectation`.
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -667,14 +801,22 @@ impl Expectation {
 }
 
 mod diagnostics {
-    use hir_def::{expr::ExprId, FunctionId};
+    use hir_def::{
+        expr::{Expr, ExprId},
+        FunctionId,
+    };
     use hir_expand::diagnostics::DiagnosticSink;
 
-    use crate::{db::HirDatabase, diagnostics::NoSuchField};
+    use crate::{
+        db::HirDatabase,
+        diagnostics::{NoSuchField, PrivateItem, UnresolvedName},
+    };
 
     #[derive(Debug, PartialEq, Eq, Clone)]
     pub(super) enum InferenceDiagnostic {
         NoSuchField { expr: ExprId, field: usize },
+        UnresolvedPath { expr: ExprId },
+        PrivateValue { expr: ExprId },
     }
 
     impl InferenceDiagnostic {
@@ -690,6 +832,43 @@ mod diagnostics {
                     let field = source_map.field_syntax(*expr, *field);
                     sink.push(NoSuchField { file: field.file_id, field: field.value })
                 }
+                InferenceDiagnostic::UnresolvedPath { expr } => {
+                    let (body, source_map) = db.body_with_source_map(owner.into());
+                    // A macro that wasn't expanded might have defined the name.
+                    if source_map.has_unexpanded_macros() {
+                        return;
+                    }
+                    let path = match &body[*expr] {
+                        Expr::Path(path) => path,
+                        _ => return,
+                    };
+                    if let Ok(source) = source_map.expr_syntax(*expr) {
+                        // Hygiene isn't fully supported yet, so paths from macro
+                        // expansions may fail to resolve spuriously.
+                        if source.file_id.is_macro_file() {
+                            return;
+                        }
+                        sink.push(UnresolvedName {
+                            file: source.file_id,
+                            expr: source.value,
+                            name: path.mod_path().to_string(),
+                        })
+                    }
+                }
+                InferenceDiagnostic::PrivateValue { expr } => {
+                    let (body, source_map) = db.body_with_source_map(owner.into());
+                    let path = match &body[*expr] {
+                        Expr::Path(path) => path,
+                        _ => return,
+                    };
+                    if let Ok(source) = source_map.expr_syntax(*expr) {
+                        sink.push(PrivateItem {
+                            file: source.file_id,
+                            expr: source.value,
+                            name: path.mod_path().to_string(),
+                        })
+                    }
+                }
             }
         }
     }
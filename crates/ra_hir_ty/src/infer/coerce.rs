@@ -7,7 +7,11 @@
 use hir_def::{lang_item::LangItemTarget, type_ref::Mutability};
 use test_utils::tested_by;
 
-use crate::{autoderef, traits::Solution, Obligation, Substs, TraitRef, Ty, TypeCtor};
+use crate::{
+    autoderef,
+    traits::{Guidance, Solution},
+    Obligation, Substs, TraitRef, Ty, TypeCtor, TypeWalk,
+};
 
 use super::{unify::TypeVarValue, InEnvironment, InferTy, InferenceContext};
 
@@ -121,7 +125,7 @@ impl<'a> InferenceContext<'a> {
         let krate = self.resolver.krate().unwrap();
         let coerce_unsized_trait = match self.db.lang_item(krate, "coerce_unsized".into()) {
             Some(LangItemTarget::TraitId(trait_)) => trait_,
-            _ => return None,
+            _ => return self.try_builtin_unsize(from_ty, to_ty),
         };
 
         let generic_params = crate::utils::generics(self.db.upcast(), coerce_unsized_trait.into());
@@ -146,12 +150,65 @@ impl<'a> InferenceContext<'a> {
             Solution::Unique(v) => {
                 canonicalized.apply_solution(self, v.0);
             }
+            // The target's trait arguments may still be unknown (e.g. `&dyn Foo<_>`), but
+            // the solver already knows what they must be.
+            Solution::Ambig(Guidance::Definite(v)) => {
+                canonicalized.apply_solution(self, v.0);
+            }
             _ => return None,
         };
 
         Some(true)
     }
 
+    /// Performs the builtin unsizing coercions (`[T; N]` to `[T]` and `T` to `dyn Trait`
+    /// behind references, raw pointers and `Box`) when `CoerceUnsized` can't be found,
+    /// e.g. because the sysroot is missing.
+    fn try_builtin_unsize(&mut self, from_ty: &Ty, to_ty: &Ty) -> Option<bool> {
+        let box_struct = self.resolve_boxed_box();
+        let (from_inner, to_inner) = match (from_ty, to_ty) {
+            (ty_app!(TypeCtor::Ref(m1), st1), ty_app!(TypeCtor::Ref(m2), st2))
+            | (ty_app!(TypeCtor::RawPtr(m1), st1), ty_app!(TypeCtor::RawPtr(m2), st2))
+                if m1 == m2 =>
+            {
+                (st1.as_single().clone(), st2.as_single().clone())
+            }
+            (ty_app!(TypeCtor::Adt(a1), st1), ty_app!(TypeCtor::Adt(a2), st2))
+                if a1 == a2 && Some(*a1) == box_struct =>
+            {
+                (st1.0.first()?.clone(), st2.0.first()?.clone())
+            }
+            _ => return None,
+        };
+
+        let from_inner = self.resolve_ty_shallow(&from_inner).into_owned();
+        let to_inner = self.resolve_ty_shallow(&to_inner).into_owned();
+        match (&from_inner, &to_inner) {
+            (ty_app!(TypeCtor::Array, st1), ty_app!(TypeCtor::Slice, st2)) => {
+                Some(self.unify(&st1[0], &st2[0]))
+            }
+            // Upcasting between trait objects needs the solver.
+            (Ty::Dyn(_), _) | (Ty::Infer(_), _) => None,
+            (_, Ty::Dyn(_)) => {
+                let trait_ref = to_inner
+                    .dyn_trait_ref()?
+                    .clone()
+                    .subst_bound_vars(&Substs::single(from_inner.clone()));
+                let krate = self.resolver.krate()?;
+                let goal = InEnvironment::new(self.trait_env.clone(), Obligation::Trait(trait_ref));
+                let canonicalized = self.canonicalizer().canonicalize_obligation(goal);
+                match self.db.trait_solve(krate, canonicalized.value.clone())? {
+                    Solution::Unique(v) | Solution::Ambig(Guidance::Definite(v)) => {
+                        canonicalized.apply_solution(self, v.0);
+                    }
+                    Solution::Ambig(_) => {}
+                }
+                Some(true)
+            }
+            _ => None,
+        }
+    }
+
     /// Unify `from_ty` to `to_ty` with optional auto Deref
     ///
     /// Note that the parameters are already stripped the outer reference.
@@ -1,31 +1,41 @@
 //! Type inference for expressions.
 
 use std::iter::{repeat, repeat_with};
-use std::sync::Arc;
+use std::{mem, sync::Arc};
 
 use hir_def::{
     builtin_type::Signedness,
     expr::{Array, BinaryOp, Expr, ExprId, Literal, Statement, UnaryOp},
-    path::{GenericArg, GenericArgs},
+    path::GenericArgs,
     resolver::resolver_for_expr,
+    type_ref::ConstRef,
     AdtId, AssocContainerId, FieldId, Lookup,
 };
-use hir_expand::name::Name;
+use hir_expand::name::{name, Name};
 use ra_syntax::ast::RangeOp;
 
 use crate::{
-    autoderef, method_resolution, op,
+    autoderef,
+    consteval::ConstScalar,
+    method_resolution, op,
     traits::InEnvironment,
     utils::{generics, variant_data, Generics},
-    ApplicationTy, Binders, CallableDef, InferTy, IntTy, Mutability, Obligation, Substs, TraitRef,
-    Ty, TypeCtor, Uncertain,
+    ApplicationTy, Binders, CallableDef, InferTy, IntTy, Mutability, Obligation, ProjectionTy,
+    Substs, TraitRef, Ty, TypeCtor, Uncertain,
 };
 
-use super::{BindingMode, Expectation, InferenceContext, InferenceDiagnostic, TypeMismatch};
+use super::{
+    BindingMode, BreakableContext, Diverges, Expectation, InferenceContext, InferenceDiagnostic,
+    TypeMismatch,
+};
 
 impl<'a> InferenceContext<'a> {
     pub(super) fn infer_expr(&mut self, tgt_expr: ExprId, expected: &Expectation) -> Ty {
         let ty = self.infer_expr_inner(tgt_expr, expected);
+        if let ty_app!(TypeCtor::Never) = ty {
+            // A diverging expression can take any type.
+            return self.resolve_ty_as_possible(ty);
+        }
         let could_unify = self.unify(&ty, &expected.ty);
         if !could_unify {
             self.result.type_mismatches.insert(
@@ -64,11 +74,14 @@ impl<'a> InferenceContext<'a> {
                 // if let is desugared to match, so this is always simple if
                 self.infer_expr(*condition, &Expectation::has_type(Ty::simple(TypeCtor::Bool)));
 
+                let condition_diverges = mem::replace(&mut self.diverges, Diverges::Maybe);
                 let then_ty = self.infer_expr_inner(*then_branch, &expected);
+                let then_diverges = mem::replace(&mut self.diverges, Diverges::Maybe);
                 let else_ty = match else_branch {
                     Some(else_branch) => self.infer_expr_inner(*else_branch, &expected),
                     None => Ty::unit(),
                 };
+                self.diverges = condition_diverges | then_diverges & self.diverges;
 
                 self.coerce_merge_branch(&then_ty, &else_ty)
             }
@@ -78,28 +91,57 @@ impl<'a> InferenceContext<'a> {
                 // FIXME should be std::result::Result<{inner}, _>
                 Ty::Unknown
             }
+            Expr::Async { body } => {
+                // `return` inside an async block returns from the block, not
+                // from the enclosing function.
+                let ret_ty = self.table.new_type_var();
+                let prev_diverges = mem::replace(&mut self.diverges, Diverges::Maybe);
+                let prev_breakables = mem::take(&mut self.breakables);
+                let prev_ret_ty = mem::replace(&mut self.return_ty, ret_ty.clone());
+                let inner_ty = self.infer_expr_coerce(*body, &Expectation::has_type(ret_ty));
+                self.return_ty = prev_ret_ty;
+                self.breakables = prev_breakables;
+                self.diverges = prev_diverges;
+                self.make_future_ty(inner_ty)
+            }
             Expr::Loop { body } => {
+                self.breakables.push(BreakableContext {
+                    may_break: false,
+                    break_ty: self.table.new_type_var(),
+                });
                 self.infer_expr(*body, &Expectation::has_type(Ty::unit()));
-                // FIXME handle break with value
-                Ty::simple(TypeCtor::Never)
+
+                let ctxt = self.breakables.pop().expect("breakable stack broken");
+                if ctxt.may_break {
+                    self.diverges = Diverges::Maybe;
+                    ctxt.break_ty
+                } else {
+                    Ty::simple(TypeCtor::Never)
+                }
             }
             Expr::While { condition, body } => {
                 // while let is desugared to a match loop, so this is always simple while
                 self.infer_expr(*condition, &Expectation::has_type(Ty::simple(TypeCtor::Bool)));
-                self.infer_expr(*body, &Expectation::has_type(Ty::unit()));
+                let condition_diverges = self.diverges;
+                self.infer_loop_body(*body);
+                // the body might not be executed at all
+                self.diverges = condition_diverges;
                 Ty::unit()
             }
             Expr::For { iterable, body, pat } => {
                 let iterable_ty = self.infer_expr(*iterable, &Expectation::none());
+                let iterable_diverges = self.diverges;
 
                 let pat_ty =
                     self.resolve_associated_type(iterable_ty, self.resolve_into_iter_item());
 
                 self.infer_pat(*pat, &pat_ty, BindingMode::default());
-                self.infer_expr(*body, &Expectation::has_type(Ty::unit()));
+                self.infer_loop_body(*body);
+                // the body might not be executed at all
+                self.diverges = iterable_diverges;
                 Ty::unit()
             }
-            Expr::Lambda { body, args, ret_type, arg_types } => {
+            Expr::Lambda { body, args, ret_type, arg_types, .. } => {
                 assert_eq!(args.len(), arg_types.len());
 
                 let mut sig_tys = Vec::new();
@@ -132,24 +174,40 @@ impl<'a> InferenceContext<'a> {
                 // infer the body.
                 self.coerce(&closure_ty, &expected.ty);
 
-                let prev_ret_ty = std::mem::replace(&mut self.return_ty, ret_ty.clone());
+                let prev_diverges = mem::replace(&mut self.diverges, Diverges::Maybe);
+                let prev_breakables = mem::take(&mut self.breakables);
+                let prev_ret_ty = mem::replace(&mut self.return_ty, ret_ty.clone());
 
                 self.infer_expr_coerce(*body, &Expectation::has_type(ret_ty));
 
                 self.return_ty = prev_ret_ty;
+                self.breakables = prev_breakables;
+                self.diverges = prev_diverges;
 
                 closure_ty
             }
             Expr::Call { callee, args } => {
                 let callee_ty = self.infer_expr(*callee, &Expectation::none());
-                let (param_tys, ret_ty) = match callee_ty.callable_sig(self.db) {
-                    Some(sig) => (sig.params().to_vec(), sig.ret().clone()),
-                    None => {
-                        // Not callable
-                        // FIXME: report an error
-                        (Vec::new(), Ty::Unknown)
-                    }
-                };
+                let canonicalized = self.canonicalizer().canonicalize_ty(callee_ty.clone());
+                let mut derefs = autoderef::autoderef(
+                    self.db,
+                    self.resolver.krate(),
+                    InEnvironment {
+                        value: canonicalized.value.clone(),
+                        environment: self.trait_env.clone(),
+                    },
+                );
+                let (param_tys, ret_ty) = derefs
+                    .find_map(|callee_deref_ty| {
+                        let ty = canonicalized.decanonicalize_ty(callee_deref_ty.value);
+                        match ty.callable_sig(self.db) {
+                            Some(sig) => Some((sig.params().to_vec(), sig.ret().clone())),
+                            None => self.callable_sig_from_fn_trait(&ty, args.len()),
+                        }
+                    })
+                    // Not callable
+                    // FIXME: report an error
+                    .unwrap_or((Vec::new(), Ty::Unknown));
                 self.register_obligations_for_call(&callee_ty);
                 self.check_call_arguments(args, &param_tys);
                 self.normalize_associated_types_in(ret_ty)
@@ -165,7 +223,12 @@ impl<'a> InferenceContext<'a> {
                     self.table.new_type_var()
                 };
 
+                let matchee_diverges = self.diverges;
+                let mut all_arms_diverge = Diverges::Always;
+
                 for arm in arms {
+                    self.db.check_canceled();
+                    self.diverges = Diverges::Maybe;
                     let _pat_ty = self.infer_pat(arm.pat, &input_ty, BindingMode::default());
                     if let Some(guard_expr) = arm.guard {
                         self.infer_expr(
@@ -175,9 +238,12 @@ impl<'a> InferenceContext<'a> {
                     }
 
                     let arm_ty = self.infer_expr_inner(arm.expr, &expected);
+                    all_arms_diverge &= self.diverges;
                     result_ty = self.coerce_merge_branch(&result_ty, &arm_ty);
                 }
 
+                self.diverges = matchee_diverges | all_arms_diverge;
+
                 result_ty
             }
             Expr::Path(p) => {
@@ -187,9 +253,23 @@ impl<'a> InferenceContext<'a> {
             }
             Expr::Continue => Ty::simple(TypeCtor::Never),
             Expr::Break { expr } => {
-                if let Some(expr) = expr {
-                    // FIXME handle break with value
-                    self.infer_expr(*expr, &Expectation::none());
+                let last_ty = match self.breakables.last() {
+                    Some(ctxt) => ctxt.break_ty.clone(),
+                    None => Ty::Unknown,
+                };
+
+                let val_ty = match expr {
+                    Some(expr) => {
+                        self.infer_expr_inner(*expr, &Expectation::has_type(last_ty.clone()))
+                    }
+                    None => Ty::unit(),
+                };
+
+                let merged_ty = self.coerce_merge_branch(&last_ty, &val_ty);
+                // FIXME: report `break` outside of a loop
+                if let Some(ctxt) = self.breakables.last_mut() {
+                    ctxt.break_ty = merged_ty;
+                    ctxt.may_break = true;
                 }
                 Ty::simple(TypeCtor::Never)
             }
@@ -373,10 +453,15 @@ impl<'a> InferenceContext<'a> {
                         _ => Expectation::none(),
                     };
                     let lhs_ty = self.infer_expr(*lhs, &lhs_expectation);
+                    let lhs_diverges = self.diverges;
                     // FIXME: find implementation of trait corresponding to operation
                     // symbol and resolve associated `Output` type
                     let rhs_expectation = op::binary_op_rhs_expectation(*op, lhs_ty.clone());
                     let rhs_ty = self.infer_expr(*rhs, &Expectation::has_type(rhs_expectation));
+                    if let BinaryOp::LogicOp(_) = op {
+                        // the right-hand side of `&&` and `||` might not be evaluated
+                        self.diverges = lhs_diverges;
+                    }
 
                     // FIXME: similar as above, return ty is often associated trait type
                     op::binary_op_return_ty(*op, lhs_ty, rhs_ty)
@@ -466,17 +551,16 @@ impl<'a> InferenceContext<'a> {
             }
             Expr::Array(array) => {
                 let elem_ty = match &expected.ty {
-                    ty_app!(TypeCtor::Array, st) | ty_app!(TypeCtor::Slice, st) => {
-                        st.as_single().clone()
-                    }
+                    ty_app!(TypeCtor::Array, st) | ty_app!(TypeCtor::Slice, st) => st[0].clone(),
                     _ => self.table.new_type_var(),
                 };
 
-                match array {
+                let len = match array {
                     Array::ElementList(items) => {
                         for expr in items.iter() {
                             self.infer_expr_coerce(*expr, &Expectation::has_type(elem_ty.clone()));
                         }
+                        Ty::from_const_value(Some(ConstScalar::Int(items.len() as i128)))
                     }
                     Array::Repeat { initializer, repeat } => {
                         self.infer_expr_coerce(
@@ -489,10 +573,11 @@ impl<'a> InferenceContext<'a> {
                                 IntTy::usize(),
                             )))),
                         );
+                        self.infer_repeat_count(*repeat)
                     }
-                }
+                };
 
-                Ty::apply_one(TypeCtor::Array, elem_ty)
+                Ty::array(elem_ty, len)
             }
             Expr::Literal(lit) => match lit {
                 Literal::Bool(..) => Ty::simple(TypeCtor::Bool),
@@ -512,6 +597,9 @@ impl<'a> InferenceContext<'a> {
         // use a new type variable if we got Ty::Unknown here
         let ty = self.insert_type_vars_shallow(ty);
         let ty = self.resolve_ty_as_possible(ty);
+        if let ty_app!(TypeCtor::Never) = ty {
+            self.diverges = Diverges::Always;
+        }
         self.write_expr_ty(tgt_expr, ty.clone());
         ty
     }
@@ -522,8 +610,10 @@ impl<'a> InferenceContext<'a> {
         tail: Option<ExprId>,
         expected: &Expectation,
     ) -> Ty {
-        let mut diverges = false;
         for stmt in statements {
+            // Inference of a single huge body can take a while, so give way to
+            // pending changes between statements instead of blocking them.
+            self.db.check_canceled();
             match stmt {
                 Statement::Let { pat, type_ref, initializer } => {
                     let decl_ty =
@@ -544,26 +634,34 @@ impl<'a> InferenceContext<'a> {
                     self.infer_pat(*pat, &ty, BindingMode::default());
                 }
                 Statement::Expr(expr) => {
-                    if let ty_app!(TypeCtor::Never) = self.infer_expr(*expr, &Expectation::none()) {
-                        diverges = true;
-                    }
+                    self.infer_expr(*expr, &Expectation::none());
                 }
             }
         }
 
         let ty = if let Some(expr) = tail {
             self.infer_expr_coerce(expr, expected)
+        } else if self.diverges.is_always() {
+            // Without a tail expression, a diverging block doesn't produce
+            // the unit value, so there is nothing to coerce.
+            Ty::simple(TypeCtor::Never)
         } else {
             self.coerce(&Ty::unit(), expected.coercion_target());
             Ty::unit()
         };
-        if diverges {
+        if self.diverges.is_always() {
             Ty::simple(TypeCtor::Never)
         } else {
             ty
         }
     }
 
+    fn infer_loop_body(&mut self, body: ExprId) {
+        self.breakables.push(BreakableContext { may_break: false, break_ty: Ty::unit() });
+        self.infer_expr(body, &Expectation::has_type(Ty::unit()));
+        self.breakables.pop().expect("breakable stack broken");
+    }
+
     fn infer_method_call(
         &mut self,
         tgt_expr: ExprId,
@@ -575,25 +673,33 @@ impl<'a> InferenceContext<'a> {
         let receiver_ty = self.infer_expr(receiver, &Expectation::none());
         let canonicalized_receiver = self.canonicalizer().canonicalize_ty(receiver_ty.clone());
 
-        let traits_in_scope = self.resolver.traits_in_scope(self.db.upcast());
+        let traits_in_scope: Arc<[_]> =
+            self.resolver.traits_in_scope(self.db.upcast()).into_iter().collect();
 
         let resolved = self.resolver.krate().and_then(|krate| {
-            method_resolution::lookup_method(
-                &canonicalized_receiver.value,
-                self.db,
-                self.trait_env.clone(),
+            self.db.lookup_method(
                 krate,
-                &traits_in_scope,
-                method_name,
+                canonicalized_receiver.value.clone(),
+                self.trait_env.clone(),
+                traits_in_scope,
+                method_name.clone(),
             )
         });
-        let (derefed_receiver_ty, method_ty, def_generics) = match resolved {
-            Some((ty, func)) => {
-                let ty = canonicalized_receiver.decanonicalize_ty(ty);
+        let (derefed_receiver_ty, actual_receiver_ty, method_ty, def_generics) = match resolved {
+            Some(candidate) => {
+                let self_ty = canonicalized_receiver.decanonicalize_ty(candidate.self_ty);
+                let adjusted_ty = canonicalized_receiver.decanonicalize_ty(candidate.receiver_ty);
+                let func = candidate.func;
                 self.write_method_resolution(tgt_expr, func);
-                (ty, self.db.value_ty(func.into()), Some(generics(self.db.upcast(), func.into())))
+                self.write_receiver_adjustments(tgt_expr, candidate.adjustments);
+                (
+                    self_ty,
+                    adjusted_ty,
+                    self.db.value_ty(func.into()),
+                    Some(generics(self.db.upcast(), func.into())),
+                )
             }
-            None => (receiver_ty, Binders::new(0, Ty::Unknown), None),
+            None => (receiver_ty.clone(), receiver_ty, Binders::new(0, Ty::Unknown), None),
         };
         let substs = self.substs_for_method_call(def_generics, generic_args, &derefed_receiver_ty);
         let method_ty = method_ty.subst(&substs);
@@ -609,18 +715,51 @@ impl<'a> InferenceContext<'a> {
             }
             None => (Ty::Unknown, Vec::new(), Ty::Unknown),
         };
-        // Apply autoref so the below unification works correctly
-        // FIXME: return correct autorefs from lookup_method
-        let actual_receiver_ty = match expected_receiver_ty.as_reference() {
-            Some((_, mutability)) => Ty::apply_one(TypeCtor::Ref(mutability), derefed_receiver_ty),
-            _ => derefed_receiver_ty,
-        };
         self.unify(&expected_receiver_ty, &actual_receiver_ty);
 
         self.check_call_arguments(args, &param_tys);
         self.normalize_associated_types_in(ret_ty)
     }
 
+    /// Tries to call `ty` through the `FnOnce` trait, for type parameters,
+    /// `impl Fn` and `dyn Fn` types. Returns the parameter types and the
+    /// return type if `ty` implements it.
+    fn callable_sig_from_fn_trait(&mut self, ty: &Ty, num_args: usize) -> Option<(Vec<Ty>, Ty)> {
+        let krate = self.resolver.krate()?;
+        let fn_once_trait = self.resolve_lang_item("fn_once")?.as_trait()?;
+        let output_assoc_type =
+            self.db.trait_data(fn_once_trait).associated_type_by_name(&name![Output])?;
+        let generic_params = generics(self.db.upcast(), fn_once_trait.into());
+        if generic_params.len() != 2 {
+            return None;
+        }
+
+        let arg_tys: Vec<Ty> = (0..num_args).map(|_| self.table.new_type_var()).collect();
+        let arg_ty = Ty::apply(
+            TypeCtor::Tuple { cardinality: num_args as u16 },
+            Substs(arg_tys.iter().cloned().collect()),
+        );
+        let substs =
+            Substs::build_for_generics(&generic_params).push(ty.clone()).push(arg_ty).build();
+
+        let implements_fn_trait =
+            Obligation::Trait(TraitRef { trait_: fn_once_trait, substs: substs.clone() });
+        let goal = self.canonicalizer().canonicalize_obligation(InEnvironment {
+            value: implements_fn_trait.clone(),
+            environment: self.trait_env.clone(),
+        });
+        if self.db.trait_solve(krate, goal.value).is_none() {
+            return None;
+        }
+
+        self.obligations.push(implements_fn_trait);
+        let ret_ty = self.normalize_projection_ty(ProjectionTy {
+            associated_ty: output_assoc_type,
+            parameters: substs,
+        });
+        Some((arg_tys, ret_ty))
+    }
+
     fn check_call_arguments(&mut self, args: &[ExprId], param_tys: &[Ty]) {
         // Quoting https://github.com/rust-lang/rust/blob/6ef275e6c3cb1384ec78128eceeb4963ff788dca/src/librustc_typeck/check/mod.rs#L3325 --
         // We do this in a pretty awful way: first we type-check any arguments
@@ -645,6 +784,24 @@ impl<'a> InferenceContext<'a> {
         }
     }
 
+    /// Evaluates the repeat count of an array expression like `[0; N]`. We
+    /// can't use `consteval::eval_expr` here, as that needs the inference
+    /// result of the body we're inferring, so only literals and paths to
+    /// constants and const parameters are supported.
+    fn infer_repeat_count(&mut self, repeat: ExprId) -> Ty {
+        let len = match &self.body[repeat] {
+            Expr::Literal(Literal::Int(it, _)) => {
+                Ty::from_const_value(Some(ConstScalar::Int(*it as i128)))
+            }
+            Expr::Path(path) => {
+                let ctx = crate::lower::TyLoweringContext::new(self.db, &self.resolver);
+                Ty::from_const_ref(&ctx, &ConstRef::Path(path.clone()))
+            }
+            _ => Ty::Unknown,
+        };
+        self.insert_type_vars_shallow(len)
+    }
+
     fn substs_for_method_call(
         &mut self,
         def_generics: Option<Generics>,
@@ -669,13 +826,16 @@ impl<'a> InferenceContext<'a> {
         // handle provided type arguments
         if let Some(generic_args) = generic_args {
             // if args are provided, it should be all of them, but we can't rely on that
-            for arg in generic_args.args.iter().take(type_params) {
-                match arg {
-                    GenericArg::Type(type_ref) => {
-                        let ty = self.make_ty(type_ref);
-                        substs.push(ty);
-                    }
-                }
+            let ctx = crate::lower::TyLoweringContext::new(self.db, &self.resolver);
+            let tys: Vec<_> = generic_args
+                .args
+                .iter()
+                .take(type_params)
+                .map(|arg| Ty::from_generic_arg(&ctx, arg))
+                .collect();
+            for ty in tys {
+                let ty = self.insert_type_vars(ty);
+                substs.push(self.normalize_associated_types_in(ty));
             }
         };
         let supplied_params = substs.len();
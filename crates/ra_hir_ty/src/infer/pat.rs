@@ -196,20 +196,22 @@ impl<'a> InferenceContext<'a> {
                 return inner_ty;
             }
             Pat::Slice { prefix, slice: _slice, suffix } => {
-                let (container_ty, elem_ty) = match &expected {
-                    ty_app!(TypeCtor::Array, st) => (TypeCtor::Array, st.as_single().clone()),
-                    ty_app!(TypeCtor::Slice, st) => (TypeCtor::Slice, st.as_single().clone()),
-                    _ => (TypeCtor::Slice, Ty::Unknown),
+                let elem_ty = match &expected {
+                    ty_app!(TypeCtor::Array, st) | ty_app!(TypeCtor::Slice, st) => st[0].clone(),
+                    _ => Ty::Unknown,
                 };
 
                 for pat_id in prefix.iter().chain(suffix) {
                     self.infer_pat(*pat_id, &elem_ty, default_bm);
                 }
 
-                Ty::apply_one(container_ty, elem_ty)
+                match &expected {
+                    ty_app!(TypeCtor::Array, st) => Ty::apply(TypeCtor::Array, st.clone()),
+                    _ => Ty::apply_one(TypeCtor::Slice, elem_ty),
+                }
             }
             Pat::Wild => expected.clone(),
-            Pat::Range { start, end } => {
+            Pat::Range { start, end, .. } => {
                 let start_ty = self.infer_expr(*start, &Expectation::has_type(expected.clone()));
                 let end_ty = self.infer_expr(*end, &Expectation::has_type(start_ty));
                 end_ty
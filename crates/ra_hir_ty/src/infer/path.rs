@@ -3,15 +3,19 @@
 use std::iter;
 
 use hir_def::{
-    path::{Path, PathSegment},
-    resolver::{ResolveValueResult, Resolver, TypeNs, ValueNs},
-    AssocContainerId, AssocItemId, Lookup,
+    expr::ExprId,
+    path::{ModPath, Path, PathKind, PathSegment},
+    resolver::{HasResolver, ResolveValueResult, Resolver, TypeNs, ValueNs},
+    AssocContainerId, AssocItemId, Lookup, ModuleDefId,
+};
+use hir_expand::{
+    hygiene::SyntaxContext,
+    name::{name, Name},
 };
-use hir_expand::name::Name;
 
 use crate::{method_resolution, Substs, Ty, ValueTyDefId};
 
-use super::{ExprOrPatId, InferenceContext, TraitRef};
+use super::{ExprOrPatId, InferenceContext, InferenceDiagnostic, TraitRef};
 
 impl<'a> InferenceContext<'a> {
     pub(super) fn infer_path(
@@ -26,6 +30,36 @@ impl<'a> InferenceContext<'a> {
         Some(ty)
     }
 
+    /// Whether `path` may refer to an item that name resolution doesn't know
+    /// about, because it is disabled by `cfg` or comes from a macro call that
+    /// couldn't be expanded.
+    fn may_be_unknown_item(&self, resolver: &Resolver, path: &ModPath) -> bool {
+        let (name, prefix) = match path.segments.split_last() {
+            Some(it) => it,
+            None => return false,
+        };
+        let module = if prefix.is_empty() && path.kind == PathKind::Plain {
+            resolver.module()
+        } else {
+            let prefix = ModPath::from_segments(path.kind.clone(), prefix.iter().cloned());
+            match resolver.resolve_module_path_in_items(self.db.upcast(), &prefix).take_types() {
+                Some(ModuleDefId::ModuleId(it)) => Some(it),
+                _ => None,
+            }
+        };
+        let (module, name) = match module {
+            Some(module) => (module, name),
+            // The prefix doesn't resolve to a module, maybe because it is unknown itself.
+            None if path.kind == PathKind::Plain => match resolver.module() {
+                Some(module) => (module, &path.segments[0]),
+                None => return false,
+            },
+            None => return false,
+        };
+        let def_map = self.db.crate_def_map(module.krate);
+        def_map[module.local_id].scope.may_contain_unknown(name)
+    }
+
     fn resolve_value_path(
         &mut self,
         resolver: &Resolver,
@@ -47,11 +81,33 @@ impl<'a> InferenceContext<'a> {
                 id,
             )?
         } else {
-            let value_or_partial =
-                resolver.resolve_path_in_value_ns(self.db.upcast(), path.mod_path())?;
+            let hygiene = match id {
+                ExprOrPatId::ExprId(expr) => self.body.expr_hygiene(expr),
+                ExprOrPatId::PatId(_) => SyntaxContext::ROOT,
+            };
+            let res = resolver.resolve_path_in_value_ns(self.db.upcast(), path.mod_path(), hygiene);
+            let value_or_partial = match res {
+                Some(it) => it,
+                None => {
+                    // FIXME: a unit struct referred to as `Self` isn't resolved in the value
+                    // namespace yet, so don't report it.
+                    let is_self = path.mod_path().segments.first() == Some(&name![Self]);
+                    if let ExprOrPatId::ExprId(expr) = id {
+                        if !is_self && !self.may_be_unknown_item(resolver, path.mod_path()) {
+                            self.push_diagnostic(InferenceDiagnostic::UnresolvedPath { expr });
+                        }
+                    }
+                    return None;
+                }
+            };
 
             match value_or_partial {
-                ResolveValueResult::ValueNs(it) => (it, None),
+                ResolveValueResult::ValueNs(it) => {
+                    if let ExprOrPatId::ExprId(expr) = id {
+                        self.check_value_visibility(resolver, &it, expr);
+                    }
+                    (it, None)
+                }
                 ResolveValueResult::Partial(def, remaining_index) => {
                     self.resolve_assoc_item(def, path, remaining_index, id)?
                 }
@@ -64,6 +120,7 @@ impl<'a> InferenceContext<'a> {
                 let ty = self.resolve_ty_as_possible(ty);
                 return Some(ty);
             }
+            ValueNs::GenericParam(it) => return Some(self.db.const_param_ty(it)),
             ValueNs::FunctionId(it) => it.into(),
             ValueNs::ConstId(it) => it.into(),
             ValueNs::StaticId(it) => it.into(),
@@ -92,6 +149,29 @@ impl<'a> InferenceContext<'a> {
         Some(ty)
     }
 
+    /// Reports values which are resolved, but aren't visible from the module
+    /// of the expression referring to them.
+    // FIXME: statics and structs don't record their visibility yet.
+    fn check_value_visibility(&mut self, resolver: &Resolver, value: &ValueNs, expr: ExprId) {
+        let db = self.db.upcast();
+        let visibility = match value {
+            ValueNs::FunctionId(it) => {
+                self.db.function_data(*it).visibility.resolve(db, &it.resolver(db))
+            }
+            ValueNs::ConstId(it) => {
+                self.db.const_data(*it).visibility.resolve(db, &it.resolver(db))
+            }
+            _ => return,
+        };
+        let module = match resolver.module() {
+            Some(it) => it,
+            None => return,
+        };
+        if !visibility.is_visible_from(db, module) {
+            self.push_diagnostic(InferenceDiagnostic::PrivateValue { expr });
+        }
+    }
+
     fn resolve_assoc_item(
         &mut self,
         def: TypeNs,
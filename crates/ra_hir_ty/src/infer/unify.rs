@@ -171,11 +171,17 @@ pub fn unify(ty1: &Canonical<Ty>, ty2: &Canonical<Ty>) -> Option<Substs> {
 #[derive(Clone, Debug)]
 pub(crate) struct InferenceTable {
     pub(super) var_unification_table: InPlaceUnificationTable<TypeVarId>,
+    /// The variables created for the types of diverging expressions; these
+    /// fall back to `!` if nothing else constrains them.
+    diverging_type_vars: Vec<TypeVarId>,
 }
 
 impl InferenceTable {
     pub fn new() -> Self {
-        InferenceTable { var_unification_table: InPlaceUnificationTable::new() }
+        InferenceTable {
+            var_unification_table: InPlaceUnificationTable::new(),
+            diverging_type_vars: Vec::new(),
+        }
     }
 
     pub fn new_type_var(&mut self) -> Ty {
@@ -191,9 +197,23 @@ impl InferenceTable {
     }
 
     pub fn new_maybe_never_type_var(&mut self) -> Ty {
-        Ty::Infer(InferTy::MaybeNeverTypeVar(
-            self.var_unification_table.new_key(TypeVarValue::Unknown),
-        ))
+        let var = self.var_unification_table.new_key(TypeVarValue::Unknown);
+        self.diverging_type_vars.push(var);
+        Ty::Infer(InferTy::MaybeNeverTypeVar(var))
+    }
+
+    /// Resolves all diverging type variables that are still unconstrained to
+    /// `!`. Returns whether any variable was changed.
+    pub fn fallback_diverging_type_vars(&mut self) -> bool {
+        let mut changed = false;
+        for &var in &self.diverging_type_vars {
+            if self.var_unification_table.inlined_probe_value(var).known().is_none() {
+                let never = Ty::simple(TypeCtor::Never);
+                self.var_unification_table.union_value(var, TypeVarValue::Known(never));
+                changed = true;
+            }
+        }
+        changed
     }
 
     pub fn resolve_ty_completely(&mut self, ty: Ty) -> Ty {
@@ -0,0 +1,296 @@
+//! Computation of the memory layout of types: their size, their alignment and
+//! the offsets of their fields.
+//!
+//! This follows what rustc does for the common cases. The fields of
+//! `repr(Rust)` structs are sorted by decreasing alignment, which is the
+//! ordering rustc picks in practice. Enums with fields are not supported, as
+//! their layout depends on niche optimizations we don't model.
+
+use std::{convert::TryFrom, sync::Arc};
+
+use hir_def::{AdtId, AttrDefId, EnumId, EnumVariantId, HasModule, VariantId};
+use ra_cfg::CfgExpr;
+use ra_db::CrateId;
+
+use crate::{
+    consteval::ConstScalar,
+    db::HirDatabase,
+    primitive::{FloatBitness, IntBitness, Uncertain},
+    ApplicationTy, Substs, Ty, TypeCtor,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    pub size: u64,
+    pub align: u64,
+    /// The offsets of the fields of a struct or a union, in declaration order.
+    pub field_offsets: Vec<u64>,
+}
+
+impl Layout {
+    fn scalar(size: u64) -> Layout {
+        Layout { size, align: size, field_offsets: Vec::new() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+    /// The type has a layout we don't know how to compute (yet).
+    NotSupported(&'static str),
+    /// The layout depends on type parameters.
+    HasGenericParams,
+    /// The type is unsized, or not fully known.
+    Unknown,
+    /// The type contains itself without indirection.
+    Cycle,
+}
+
+pub(crate) fn adt_layout_query(
+    db: &dyn HirDatabase,
+    adt: AdtId,
+    substs: Substs,
+) -> Result<Arc<Layout>, LayoutError> {
+    let krate = adt.module(db.upcast()).krate;
+    let repr = Repr::of(db, adt);
+    let mut layout = match adt {
+        AdtId::StructId(it) => {
+            let fields = field_layouts(db, it.into(), &substs, krate)?;
+            struct_layout(&fields, &repr)
+        }
+        AdtId::UnionId(it) => {
+            let fields = field_layouts(db, it.into(), &substs, krate)?;
+            let size = fields.iter().map(|it| it.size).max().unwrap_or(0);
+            let align = fields.iter().map(|it| repr.field_align(it)).max().unwrap_or(1);
+            Layout { size: round_up(size, align), align, field_offsets: vec![0; fields.len()] }
+        }
+        AdtId::EnumId(it) => enum_layout(db, it, &repr)?,
+    };
+    if let Some(align) = repr.align {
+        layout.align = layout.align.max(align);
+        layout.size = round_up(layout.size, layout.align);
+    }
+    Ok(Arc::new(layout))
+}
+
+pub(crate) fn adt_layout_recover(
+    _db: &dyn HirDatabase,
+    _cycle: &[String],
+    _adt: &AdtId,
+    _substs: &Substs,
+) -> Result<Arc<Layout>, LayoutError> {
+    Err(LayoutError::Cycle)
+}
+
+/// The layout of `ty` when compiled for the target of `krate`.
+pub fn layout_of_ty(db: &dyn HirDatabase, ty: &Ty, krate: CrateId) -> Result<Layout, LayoutError> {
+    let pointer_size = pointer_size(db, krate);
+    let a_ty = match ty {
+        Ty::Apply(it) => it,
+        Ty::Placeholder(_) | Ty::Bound(_) => return Err(LayoutError::HasGenericParams),
+        _ => return Err(LayoutError::Unknown),
+    };
+    let layout = match a_ty.ctor {
+        TypeCtor::Bool => Layout::scalar(1),
+        TypeCtor::Char => Layout::scalar(4),
+        TypeCtor::Int(Uncertain::Known(it)) => Layout::scalar(match it.bitness {
+            IntBitness::Xsize => pointer_size,
+            IntBitness::X8 => 1,
+            IntBitness::X16 => 2,
+            IntBitness::X32 => 4,
+            IntBitness::X64 => 8,
+            IntBitness::X128 => 16,
+        }),
+        TypeCtor::Float(Uncertain::Known(it)) => Layout::scalar(match it.bitness {
+            FloatBitness::X32 => 4,
+            FloatBitness::X64 => 8,
+        }),
+        TypeCtor::Never | TypeCtor::FnDef(_) => {
+            Layout { size: 0, align: 1, field_offsets: Vec::new() }
+        }
+        TypeCtor::FnPtr { .. } => Layout::scalar(pointer_size),
+        TypeCtor::Ref(_) | TypeCtor::RawPtr(_) => {
+            if is_unsized(a_ty.parameters.as_single()) {
+                Layout { size: 2 * pointer_size, align: pointer_size, field_offsets: Vec::new() }
+            } else {
+                Layout::scalar(pointer_size)
+            }
+        }
+        TypeCtor::Tuple { .. } => {
+            let fields = a_ty
+                .parameters
+                .iter()
+                .map(|it| layout_of_ty(db, it, krate))
+                .collect::<Result<Vec<_>, _>>()?;
+            struct_layout(&fields, &Repr::default())
+        }
+        TypeCtor::Adt(adt) => (*db.adt_layout(adt, a_ty.parameters.clone())?).clone(),
+        TypeCtor::Array => {
+            let elem = layout_of_ty(db, &a_ty.parameters[0], krate)?;
+            let len = match &a_ty.parameters[1] {
+                Ty::Apply(ApplicationTy {
+                    ctor: TypeCtor::Const(ConstScalar::Int(it)), ..
+                }) => u64::try_from(*it).map_err(|_| LayoutError::Unknown)?,
+                Ty::Placeholder(_) | Ty::Bound(_) => return Err(LayoutError::HasGenericParams),
+                _ => return Err(LayoutError::Unknown),
+            };
+            let size = elem.size.checked_mul(len).ok_or(LayoutError::Unknown)?;
+            Layout { size, align: elem.align, field_offsets: Vec::new() }
+        }
+        TypeCtor::Closure { .. } => return Err(LayoutError::NotSupported("closures")),
+        _ => return Err(LayoutError::Unknown),
+    };
+    Ok(layout)
+}
+
+fn is_unsized(pointee: &Ty) -> bool {
+    match pointee {
+        Ty::Apply(ApplicationTy { ctor: TypeCtor::Str, .. })
+        | Ty::Apply(ApplicationTy { ctor: TypeCtor::Slice, .. })
+        | Ty::Dyn(_) => true,
+        _ => false,
+    }
+}
+
+fn pointer_size(db: &dyn HirDatabase, krate: CrateId) -> u64 {
+    let crate_graph = db.crate_graph();
+    let cfg_options = &crate_graph[krate].cfg_options;
+    for &(width, size) in &[("16", 2), ("32", 4)] {
+        let cfg = CfgExpr::KeyValue { key: "target_pointer_width".into(), value: width.into() };
+        if cfg_options.check(&cfg) == Some(true) {
+            return size;
+        }
+    }
+    8
+}
+
+fn field_layouts(
+    db: &dyn HirDatabase,
+    variant: VariantId,
+    substs: &Substs,
+    krate: CrateId,
+) -> Result<Vec<Layout>, LayoutError> {
+    db.field_types(variant)
+        .iter()
+        .map(|(_, ty)| {
+            if ty.num_binders != substs.len() {
+                return Err(LayoutError::Unknown);
+            }
+            layout_of_ty(db, &ty.clone().subst(substs), krate)
+        })
+        .collect()
+}
+
+fn struct_layout(fields: &[Layout], repr: &Repr) -> Layout {
+    let mut order: Vec<usize> = (0..fields.len()).collect();
+    if !repr.c {
+        // `sort_by_key` is stable: fields of the same alignment keep their order.
+        order.sort_by_key(|&idx| std::cmp::Reverse(repr.field_align(&fields[idx])));
+    }
+    let mut field_offsets = vec![0; fields.len()];
+    let mut size = 0;
+    let mut align = 1;
+    for idx in order {
+        let field_align = repr.field_align(&fields[idx]);
+        size = round_up(size, field_align);
+        field_offsets[idx] = size;
+        size += fields[idx].size;
+        align = align.max(field_align);
+    }
+    Layout { size: round_up(size, align), align, field_offsets }
+}
+
+fn enum_layout(db: &dyn HirDatabase, id: EnumId, repr: &Repr) -> Result<Layout, LayoutError> {
+    let enum_data = db.enum_data(id);
+    if enum_data.variants.iter().any(|(_, it)| !it.variant_data.fields().is_empty()) {
+        return Err(LayoutError::NotSupported("enums with fields"));
+    }
+    if let Some(size) = repr.int_size {
+        return Ok(Layout::scalar(size));
+    }
+    if repr.c {
+        // The size of a C `int`.
+        return Ok(Layout::scalar(4));
+    }
+    if enum_data.variants.len() <= 1 {
+        return Ok(Layout { size: 0, align: 1, field_offsets: Vec::new() });
+    }
+    let mut min = 0;
+    let mut max = 0;
+    for (local_id, _) in enum_data.variants.iter() {
+        let discriminant = db
+            .enum_discriminant(EnumVariantId { parent: id, local_id })
+            .map_err(|_| LayoutError::Unknown)?;
+        min = min.min(discriminant);
+        max = max.max(discriminant);
+    }
+    // The smallest integer which can hold all the discriminants.
+    let size = [1u64, 2, 4, 8]
+        .iter()
+        .copied()
+        .find(|&size| {
+            let bits = size * 8;
+            if min < 0 {
+                min >= -(1i128 << (bits - 1)) && max < (1i128 << (bits - 1))
+            } else {
+                max < (1i128 << bits)
+            }
+        })
+        .unwrap_or(16);
+    Ok(Layout::scalar(size))
+}
+
+fn round_up(size: u64, align: u64) -> u64 {
+    (size + align - 1) / align * align
+}
+
+/// The parts of a `#[repr(...)]` attribute which affect the layout.
+#[derive(Default)]
+struct Repr {
+    c: bool,
+    /// The maximal alignment of fields, `1` for a plain `packed`.
+    packed: Option<u64>,
+    /// The minimal alignment, from `align(N)`.
+    align: Option<u64>,
+    /// The size of the discriminant of enums with an integer repr.
+    int_size: Option<u64>,
+}
+
+impl Repr {
+    fn of(db: &dyn HirDatabase, adt: AdtId) -> Repr {
+        let mut res = Repr::default();
+        let attrs = db.attrs(AttrDefId::AdtId(adt));
+        for tt in attrs.by_key("repr").tt_values() {
+            let text = tt.to_string();
+            let mut words = text
+                .split(|c: char| !c.is_alphanumeric() && c != '_')
+                .filter(|it| !it.is_empty())
+                .peekable();
+            while let Some(word) = words.next() {
+                // The argument of `align(N)` or `packed(N)`, if any.
+                let arg = words.peek().and_then(|it| it.parse::<u64>().ok());
+                if arg.is_some() {
+                    words.next();
+                }
+                match word {
+                    "C" | "transparent" => res.c = true,
+                    "packed" => res.packed = Some(arg.unwrap_or(1)),
+                    "align" => res.align = arg,
+                    "u8" | "i8" => res.int_size = Some(1),
+                    "u16" | "i16" => res.int_size = Some(2),
+                    "u32" | "i32" => res.int_size = Some(4),
+                    "u64" | "i64" => res.int_size = Some(8),
+                    "u128" | "i128" => res.int_size = Some(16),
+                    _ => {}
+                }
+            }
+        }
+        res
+    }
+
+    fn field_align(&self, field: &Layout) -> u64 {
+        match self.packed {
+            Some(pack) => field.align.min(pack),
+            None => field.align,
+        }
+    }
+}
@@ -37,6 +37,9 @@ pub(crate) mod utils;
 pub mod db;
 pub mod diagnostics;
 pub mod expr;
+pub mod consteval;
+pub mod layout;
+pub mod closure;
 
 #[cfg(test)]
 mod tests;
@@ -56,6 +59,7 @@ use hir_def::{
 use ra_db::{impl_intern_key, salsa, CrateId};
 
 use crate::{
+    consteval::ConstScalar,
     db::HirDatabase,
     primitive::{FloatTy, IntTy, Uncertain},
     utils::{generics, make_mut_slice, Generics},
@@ -70,6 +74,7 @@ pub use lower::{
     TyLoweringContext, ValueTyDefId,
 };
 pub use traits::{InEnvironment, Obligation, ProjectionPredicate, TraitEnvironment};
+pub use utils::direct_super_traits;
 
 pub use chalk_ir::{BoundVar, DebruijnIndex};
 
@@ -100,7 +105,8 @@ pub enum TypeCtor {
     /// The pointee of an array slice.  Written as `[T]`.
     Slice,
 
-    /// An array with the given length. Written as `[T; n]`.
+    /// An array with the given length. Written as `[T; n]`. The second
+    /// parameter is the length, as a `TypeCtor::Const` if it is known.
     Array,
 
     /// A raw pointer. Written as `*mut T` or `*const T`
@@ -151,6 +157,12 @@ pub enum TypeCtor {
     /// The closure signature is stored in a `FnPtr` type in the first type
     /// parameter.
     Closure { def: DefWithBodyId, expr: ExprId },
+
+    /// The value of a const generic argument, like the `3` in `Foo<3>`.
+    /// Const parameters are treated like type parameters, so their arguments
+    /// are represented as types without parameters; this makes them get
+    /// unified like the other types in a substitution.
+    Const(ConstScalar),
 }
 
 /// This exists just for Chalk, because Chalk just has a single `StructId` where
@@ -168,9 +180,10 @@ impl TypeCtor {
             | TypeCtor::Int(_)
             | TypeCtor::Float(_)
             | TypeCtor::Str
-            | TypeCtor::Never => 0,
+            | TypeCtor::Never
+            | TypeCtor::Const(_) => 0,
+            TypeCtor::Array => 2,
             TypeCtor::Slice
-            | TypeCtor::Array
             | TypeCtor::RawPtr(_)
             | TypeCtor::Ref(_)
             | TypeCtor::Closure { .. } // 1 param representing the signature of the closure
@@ -205,7 +218,8 @@ impl TypeCtor {
             | TypeCtor::RawPtr(_)
             | TypeCtor::Ref(_)
             | TypeCtor::FnPtr { .. }
-            | TypeCtor::Tuple { .. } => None,
+            | TypeCtor::Tuple { .. }
+            | TypeCtor::Const(_) => None,
             // Closure's krate is irrelevant for coherence I would think?
             TypeCtor::Closure { .. } => None,
             TypeCtor::Adt(adt) => Some(adt.module(db.upcast()).krate),
@@ -230,7 +244,8 @@ impl TypeCtor {
             | TypeCtor::Ref(_)
             | TypeCtor::FnPtr { .. }
             | TypeCtor::Tuple { .. }
-            | TypeCtor::Closure { .. } => None,
+            | TypeCtor::Closure { .. }
+            | TypeCtor::Const(_) => None,
             TypeCtor::Adt(adt) => Some(adt.into()),
             TypeCtor::FnDef(callable) => Some(callable.into()),
             TypeCtor::AssociatedType(type_alias) => Some(type_alias.into()),
@@ -683,6 +698,11 @@ impl Ty {
     pub fn unit() -> Self {
         Ty::apply(TypeCtor::Tuple { cardinality: 0 }, Substs::empty())
     }
+    /// An array type. The length is a `TypeCtor::Const`, a const parameter or
+    /// unknown.
+    pub fn array(elem_ty: Ty, len: Ty) -> Ty {
+        Ty::apply(TypeCtor::Array, Substs::builder(2).push(elem_ty).push(len).build())
+    }
 
     pub fn as_reference(&self) -> Option<(&Ty, Mutability)> {
         match self {
@@ -752,7 +772,7 @@ impl Ty {
         }
     }
 
-    fn callable_sig(&self, db: &dyn HirDatabase) -> Option<FnSig> {
+    pub fn callable_sig(&self, db: &dyn HirDatabase) -> Option<FnSig> {
         match self {
             Ty::Apply(a_ty) => match a_ty.ctor {
                 TypeCtor::FnPtr { .. } => Some(FnSig::from_fn_ptr_substs(&a_ty.parameters)),
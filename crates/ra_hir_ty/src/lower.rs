@@ -15,16 +15,17 @@ use hir_def::{
     builtin_type::BuiltinType,
     generics::{TypeParamProvenance, WherePredicate, WherePredicateTarget},
     path::{GenericArg, Path, PathSegment, PathSegments},
-    resolver::{HasResolver, Resolver, TypeNs},
-    type_ref::{TypeBound, TypeRef},
-    AdtId, AssocContainerId, AssocItemId, ConstId, EnumId, EnumVariantId, FunctionId, GenericDefId,
-    HasModule, ImplId, LocalFieldId, Lookup, StaticId, StructId, TraitId, TypeAliasId, TypeParamId,
-    UnionId, VariantId,
+    resolver::{HasResolver, Resolver, TypeNs, ValueNs},
+    type_ref::{ConstRef, TypeBound, TypeRef},
+    AdtId, AssocContainerId, AssocItemId, ConstId, ConstParamId, EnumId, EnumVariantId, FunctionId,
+    GenericDefId, HasModule, ImplId, LocalFieldId, Lookup, StaticId, StructId, TraitId,
+    TypeAliasId, TypeParamId, UnionId, VariantId,
 };
 use ra_arena::map::ArenaMap;
 use ra_db::CrateId;
 
 use crate::{
+    consteval::{self, ConstScalar},
     db::HirDatabase,
     primitive::{FloatTy, IntTy},
     utils::{
@@ -34,7 +35,7 @@ use crate::{
     Binders, BoundVar, DebruijnIndex, FnSig, GenericPredicate, PolyFnSig, ProjectionPredicate,
     ProjectionTy, Substs, TraitEnvironment, TraitRef, Ty, TypeCtor, TypeWalk,
 };
-use hir_expand::name::Name;
+use hir_expand::{hygiene::SyntaxContext, name::Name};
 
 #[derive(Debug)]
 pub struct TyLoweringContext<'a> {
@@ -137,9 +138,9 @@ impl Ty {
                 let inner_ty = Ty::from_hir(ctx, inner);
                 Ty::apply_one(TypeCtor::RawPtr(*mutability), inner_ty)
             }
-            TypeRef::Array(inner) => {
+            TypeRef::Array(inner, len) => {
                 let inner_ty = Ty::from_hir(ctx, inner);
-                Ty::apply_one(TypeCtor::Array, inner_ty)
+                Ty::array(inner_ty, Ty::from_const_ref(ctx, len))
             }
             TypeRef::Slice(inner) => {
                 let inner_ty = Ty::from_hir(ctx, inner);
@@ -488,12 +489,7 @@ pub(super) fn substs_from_path_segment(
         let skip = if generic_args.has_self_type && self_params == 0 { 1 } else { 0 };
         // if args are provided, it should be all of them, but we can't rely on that
         for arg in generic_args.args.iter().skip(skip).take(expected_num) {
-            match arg {
-                GenericArg::Type(type_ref) => {
-                    let ty = Ty::from_hir(ctx, type_ref);
-                    substs.push(ty);
-                }
-            }
+            substs.push(Ty::from_generic_arg(ctx, arg));
         }
     }
     let total_len = parent_params + self_params + type_params + impl_trait_params;
@@ -518,6 +514,57 @@ pub(super) fn substs_from_path_segment(
     Substs(substs.into())
 }
 
+impl Ty {
+    /// Lowers a generic argument. Arguments for const parameters become
+    /// `TypeCtor::Const` types if we can evaluate them.
+    pub fn from_generic_arg(ctx: &TyLoweringContext<'_>, arg: &GenericArg) -> Ty {
+        match arg {
+            GenericArg::Type(type_ref) => {
+                if let TypeRef::Path(path) = type_ref {
+                    if let Some(ty) = const_arg_from_path(ctx, path) {
+                        return ty;
+                    }
+                }
+                Ty::from_hir(ctx, type_ref)
+            }
+            GenericArg::Const(const_ref) => Ty::from_const_ref(ctx, const_ref),
+        }
+    }
+
+    /// Lowers the value of a const parameter or an array length.
+    pub(crate) fn from_const_ref(ctx: &TyLoweringContext<'_>, const_ref: &ConstRef) -> Ty {
+        match const_ref {
+            // `{ N }` for a const parameter `N` resolves like a type parameter.
+            ConstRef::Path(path) => const_arg_from_path(ctx, path)
+                .unwrap_or_else(|| Ty::from_hir(ctx, &TypeRef::Path(path.clone()))),
+            _ => {
+                let value = consteval::eval_const_ref(ctx.db, ctx.resolver, const_ref).ok();
+                Ty::from_const_value(value.map(ConstScalar::Int))
+            }
+        }
+    }
+
+    pub(crate) fn from_const_value(value: Option<ConstScalar>) -> Ty {
+        value.map_or(Ty::Unknown, |it| Ty::simple(TypeCtor::Const(it)))
+    }
+}
+
+/// A plain name like `C` in `Foo<C>` is syntactically a type, but might refer
+/// to a constant instead.
+fn const_arg_from_path(ctx: &TyLoweringContext<'_>, path: &Path) -> Option<Ty> {
+    let path = path.mod_path();
+    if ctx.resolver.resolve_path_in_type_ns_fully(ctx.db.upcast(), path).is_some() {
+        return None;
+    }
+    match ctx.resolver.resolve_path_in_value_ns_fully(ctx.db.upcast(), path, SyntaxContext::ROOT)? {
+        ValueNs::ConstId(it) => {
+            let value = ctx.db.const_eval(it).ok();
+            Some(Ty::from_const_value(value.as_ref().and_then(ConstScalar::from_computed)))
+        }
+        _ => None,
+    }
+}
+
 impl TraitRef {
     fn from_path(
         ctx: &TyLoweringContext<'_>,
@@ -890,6 +937,15 @@ fn type_for_const(db: &dyn HirDatabase, def: ConstId) -> Binders<Ty> {
     Binders::new(generics.len(), Ty::from_hir(&ctx, &data.type_ref))
 }
 
+/// Build the type of a const generic parameter, i.e. `usize` for `const N: usize`.
+pub(crate) fn const_param_ty_query(db: &dyn HirDatabase, def: ConstParamId) -> Ty {
+    let params = db.generic_params(def.parent);
+    let resolver = def.parent.resolver(db.upcast());
+    let ctx = TyLoweringContext::new(db, &resolver);
+
+    Ty::from_hir(&ctx, &params.consts[def.local_id].ty)
+}
+
 /// Build the declared type of a static.
 fn type_for_static(db: &dyn HirDatabase, def: StaticId) -> Binders<Ty> {
     let data = db.static_data(def);
@@ -19,9 +19,9 @@ use crate::{
     autoderef,
     db::HirDatabase,
     primitive::{FloatBitness, Uncertain},
-    utils::all_super_traits,
-    ApplicationTy, Canonical, DebruijnIndex, InEnvironment, TraitEnvironment, TraitRef, Ty,
-    TypeCtor, TypeWalk,
+    utils::{all_super_traits, associated_type_bound_traits},
+    ApplicationTy, Canonical, DebruijnIndex, InEnvironment, ProjectionTy, TraitEnvironment,
+    TraitRef, Ty, TypeCtor, TypeWalk,
 };
 
 /// This is used as a key for indexing impls.
@@ -172,8 +172,44 @@ impl Ty {
         Some(res)
     }
 }
+/// The adjustments that have to be applied to a method call receiver to get
+/// the type the method's `self` parameter expects.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ReceiverAdjustments {
+    /// The number of autoderef steps, builtin or through `Deref`.
+    pub autoderefs: usize,
+    /// Whether an array was unsized to a slice after the autoderef steps.
+    pub unsize_array: bool,
+    /// The autoref applied at the end, if any.
+    pub autoref: Option<Mutability>,
+}
+
+/// The result of looking up a method call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodCandidate {
+    pub func: FunctionId,
+    /// The self type of the impl or trait the method was found in.
+    pub self_ty: Ty,
+    /// The receiver type after applying `adjustments`.
+    pub receiver_ty: Ty,
+    pub adjustments: ReceiverAdjustments,
+}
+
+/// Looks up a method like `lookup_method`, but memoized.
+pub(crate) fn lookup_method_query(
+    db: &dyn HirDatabase,
+    krate: CrateId,
+    ty: Canonical<Ty>,
+    env: Arc<TraitEnvironment>,
+    traits_in_scope: Arc<[TraitId]>,
+    name: Name,
+) -> Option<MethodCandidate> {
+    let traits_in_scope = traits_in_scope.iter().copied().collect();
+    lookup_method(&ty, db, env, krate, &traits_in_scope, &name)
+}
+
 /// Look up the method with the given name, returning the actual autoderefed
-/// receiver type (but without autoref applied yet).
+/// and autorefed receiver type along with the adjustments that lead to it.
 pub(crate) fn lookup_method(
     ty: &Canonical<Ty>,
     db: &dyn HirDatabase,
@@ -181,8 +217,8 @@ pub(crate) fn lookup_method(
     krate: CrateId,
     traits_in_scope: &FxHashSet<TraitId>,
     name: &Name,
-) -> Option<(Ty, FunctionId)> {
-    iterate_method_candidates(
+) -> Option<MethodCandidate> {
+    iterate_method_candidates_with_adjustments(
         ty,
         db,
         env,
@@ -190,8 +226,13 @@ pub(crate) fn lookup_method(
         &traits_in_scope,
         Some(name),
         LookupMode::MethodCall,
-        |ty, f| match f {
-            AssocItemId::FunctionId(f) => Some((ty.clone(), f)),
+        |self_ty, receiver_ty, adjustments, f| match f {
+            AssocItemId::FunctionId(func) => Some(MethodCandidate {
+                func,
+                self_ty: self_ty.clone(),
+                receiver_ty: receiver_ty.clone(),
+                adjustments,
+            }),
             _ => None,
         },
     )
@@ -221,6 +262,30 @@ pub fn iterate_method_candidates<T>(
     name: Option<&Name>,
     mode: LookupMode,
     mut callback: impl FnMut(&Ty, AssocItemId) -> Option<T>,
+) -> Option<T> {
+    iterate_method_candidates_with_adjustments(
+        ty,
+        db,
+        env,
+        krate,
+        traits_in_scope,
+        name,
+        mode,
+        |self_ty, _, _, item| callback(self_ty, item),
+    )
+}
+
+/// Like `iterate_method_candidates`, but also passes the adjusted receiver
+/// type and the adjustments leading to it to the callback.
+fn iterate_method_candidates_with_adjustments<T>(
+    ty: &Canonical<Ty>,
+    db: &dyn HirDatabase,
+    env: Arc<TraitEnvironment>,
+    krate: CrateId,
+    traits_in_scope: &FxHashSet<TraitId>,
+    name: Option<&Name>,
+    mode: LookupMode,
+    mut callback: impl FnMut(&Ty, &Ty, ReceiverAdjustments, AssocItemId) -> Option<T>,
 ) -> Option<T> {
     match mode {
         LookupMode::MethodCall => {
@@ -249,8 +314,16 @@ pub fn iterate_method_candidates<T>(
 
             let deref_chain = autoderef_method_receiver(db, krate, ty);
             for i in 0..deref_chain.len() {
+                // arrays don't deref, so anything after one is the unsizing step
+                let unsize_array = i > 0 && is_array(&deref_chain[i - 1].value);
+                let adjustments = ReceiverAdjustments {
+                    autoderefs: if unsize_array { i - 1 } else { i },
+                    unsize_array,
+                    autoref: None,
+                };
                 if let Some(result) = iterate_method_candidates_with_autoref(
                     &deref_chain[i..],
+                    adjustments,
                     db,
                     env.clone(),
                     krate,
@@ -272,7 +345,7 @@ pub fn iterate_method_candidates<T>(
                 krate,
                 traits_in_scope,
                 name,
-                &mut callback,
+                |self_ty, item| callback(self_ty, &ty.value, ReceiverAdjustments::default(), item),
             )
         }
     }
@@ -280,12 +353,13 @@ pub fn iterate_method_candidates<T>(
 
 fn iterate_method_candidates_with_autoref<T>(
     deref_chain: &[Canonical<Ty>],
+    adjustments: ReceiverAdjustments,
     db: &dyn HirDatabase,
     env: Arc<TraitEnvironment>,
     krate: CrateId,
     traits_in_scope: &FxHashSet<TraitId>,
     name: Option<&Name>,
-    mut callback: impl FnMut(&Ty, AssocItemId) -> Option<T>,
+    mut callback: impl FnMut(&Ty, &Ty, ReceiverAdjustments, AssocItemId) -> Option<T>,
 ) -> Option<T> {
     if let Some(result) = iterate_method_candidates_by_receiver(
         &deref_chain[0],
@@ -295,41 +369,28 @@ fn iterate_method_candidates_with_autoref<T>(
         krate,
         &traits_in_scope,
         name,
-        &mut callback,
+        |self_ty, item| callback(self_ty, &deref_chain[0].value, adjustments, item),
     ) {
         return Some(result);
     }
-    let refed = Canonical {
-        num_vars: deref_chain[0].num_vars,
-        value: Ty::apply_one(TypeCtor::Ref(Mutability::Shared), deref_chain[0].value.clone()),
-    };
-    if let Some(result) = iterate_method_candidates_by_receiver(
-        &refed,
-        deref_chain,
-        db,
-        env.clone(),
-        krate,
-        &traits_in_scope,
-        name,
-        &mut callback,
-    ) {
-        return Some(result);
-    }
-    let ref_muted = Canonical {
-        num_vars: deref_chain[0].num_vars,
-        value: Ty::apply_one(TypeCtor::Ref(Mutability::Mut), deref_chain[0].value.clone()),
-    };
-    if let Some(result) = iterate_method_candidates_by_receiver(
-        &ref_muted,
-        deref_chain,
-        db,
-        env,
-        krate,
-        &traits_in_scope,
-        name,
-        &mut callback,
-    ) {
-        return Some(result);
+    for &mutability in &[Mutability::Shared, Mutability::Mut] {
+        let refed = Canonical {
+            num_vars: deref_chain[0].num_vars,
+            value: Ty::apply_one(TypeCtor::Ref(mutability), deref_chain[0].value.clone()),
+        };
+        let adjustments = ReceiverAdjustments { autoref: Some(mutability), ..adjustments };
+        if let Some(result) = iterate_method_candidates_by_receiver(
+            &refed,
+            deref_chain,
+            db,
+            env.clone(),
+            krate,
+            &traits_in_scope,
+            name,
+            |self_ty, item| callback(self_ty, &refed.value, adjustments, item),
+        ) {
+            return Some(result);
+        }
     }
     None
 }
@@ -410,17 +471,37 @@ fn iterate_trait_method_candidates<T>(
 ) -> Option<T> {
     // if ty is `impl Trait` or `dyn Trait`, the trait doesn't need to be in scope
     let inherent_trait = self_ty.value.inherent_trait().into_iter();
-    let env_traits = if let Ty::Placeholder(_) = self_ty.value {
-        // if we have `T: Trait` in the param env, the trait doesn't need to be in scope
-        env.trait_predicates_for_self_ty(&self_ty.value)
+    // if we have `T: Trait` or `T::Item: Trait` in the param env, the trait
+    // doesn't need to be in scope; neither do the bounds declared on the
+    // associated type itself
+    let (env_self_ty, assoc_ty) = match &self_ty.value {
+        Ty::Placeholder(_) => (Some(self_ty.value.clone()), None),
+        Ty::Projection(proj) => (Some(self_ty.value.clone()), Some(proj.associated_ty)),
+        // a projection the solver couldn't normalize any further
+        Ty::Apply(ApplicationTy { ctor: TypeCtor::AssociatedType(type_alias), parameters }) => {
+            let proj = ProjectionTy { associated_ty: *type_alias, parameters: parameters.clone() };
+            (Some(Ty::Projection(proj)), Some(*type_alias))
+        }
+        _ => (None, None),
+    };
+    let env_traits: Vec<TraitId> = match env_self_ty {
+        Some(ty) => env
+            .trait_predicates_for_self_ty(&ty)
             .map(|tr| tr.trait_)
+            .chain(
+                assoc_ty.map_or_else(Vec::new, |it| associated_type_bound_traits(db.upcast(), it)),
+            )
             .flat_map(|t| all_super_traits(db.upcast(), t))
-            .collect()
-    } else {
-        Vec::new()
+            .collect(),
+        None => Vec::new(),
     };
-    let traits =
-        inherent_trait.chain(env_traits.into_iter()).chain(traits_in_scope.iter().copied());
+    // the same trait may be reachable in several ways, but we only want to
+    // report its methods once
+    let mut seen = FxHashSet::default();
+    let traits = inherent_trait
+        .chain(env_traits.into_iter())
+        .chain(traits_in_scope.iter().copied())
+        .filter(|t| seen.insert(*t));
     'traits: for t in traits {
         let data = db.trait_data(t);
 
@@ -642,8 +723,12 @@ fn autoderef_method_receiver(
         deref_chain.last().map(|ty| &ty.value)
     {
         let num_vars = deref_chain.last().unwrap().num_vars;
-        let unsized_ty = Ty::apply(TypeCtor::Slice, parameters.clone());
+        let unsized_ty = Ty::apply_one(TypeCtor::Slice, parameters[0].clone());
         deref_chain.push(Canonical { value: unsized_ty, num_vars })
     }
     deref_chain
 }
+
+fn is_array(ty: &Ty) -> bool {
+    matches!(ty, Ty::Apply(ApplicationTy { ctor: TypeCtor::Array, .. }))
+}
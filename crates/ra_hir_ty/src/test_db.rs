@@ -22,12 +22,21 @@ use crate::{db::HirDatabase, diagnostics::Diagnostic, expr::ExprValidator};
     hir_def::db::DefDatabaseStorage,
     crate::db::HirDatabaseStorage
 )]
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct TestDB {
     events: Mutex<Option<Vec<salsa::Event<TestDB>>>>,
     runtime: salsa::Runtime<TestDB>,
 }
 
+impl Default for TestDB {
+    fn default() -> Self {
+        let mut db = TestDB { events: Default::default(), runtime: Default::default() };
+        db.set_trait_solver_limits(Default::default());
+        db.set_library_item_trees(Default::default());
+        db
+    }
+}
+
 impl Upcast<dyn AstDatabase> for TestDB {
     fn upcast(&self) -> &(dyn AstDatabase + 'static) {
         &*self
@@ -6,6 +6,7 @@ mod patterns;
 mod traits;
 mod method_resolution;
 mod macros;
+mod consteval;
 
 use std::sync::Arc;
 
@@ -29,7 +30,8 @@ use ra_syntax::{
 use stdx::format_to;
 
 use crate::{
-    db::HirDatabase, display::HirDisplay, infer::TypeMismatch, test_db::TestDB, InferenceResult, Ty,
+    db::HirDatabase, diagnostics::UnresolvedName, display::HirDisplay, infer::TypeMismatch,
+    test_db::TestDB, InferenceResult, Ty,
 };
 
 // These tests compare the inference results for all expressions in a file
@@ -303,6 +305,55 @@ fn typing_whitespace_inside_a_function_should_not_invalidate_types() {
     }
 }
 
+#[test]
+fn changing_a_body_should_not_repeat_unchanged_method_lookups() {
+    let (mut db, pos) = TestDB::with_position(
+        "
+        //- /lib.rs
+        struct S;
+        impl S { fn foo(&self) -> u32 { 0 } }
+        fn bar() -> u32 {
+            <|>S.foo()
+        }
+    ",
+    );
+    {
+        let events = db.log_executed(|| {
+            let module = db.module_for_file(pos.file_id);
+            let crate_def_map = db.crate_def_map(module.krate);
+            visit_module(&db, &crate_def_map, module.local_id, &mut |def| {
+                db.infer(def);
+            });
+        });
+        assert!(format!("{:?}", events).contains("lookup_method"))
+    }
+
+    let new_text = "
+        struct S;
+        impl S { fn foo(&self) -> u32 { 0 } }
+        fn bar() -> u32 {
+            let x = 92;
+            S.foo()
+        }
+    "
+    .to_string();
+
+    db.query_mut(ra_db::FileTextQuery).set(pos.file_id, Arc::new(new_text));
+
+    {
+        let events = db.log_executed(|| {
+            let module = db.module_for_file(pos.file_id);
+            let crate_def_map = db.crate_def_map(module.krate);
+            visit_module(&db, &crate_def_map, module.local_id, &mut |def| {
+                db.infer(def);
+            });
+        });
+        let events = format!("{:?}", events);
+        assert!(events.contains("infer"), "{}", events);
+        assert!(!events.contains("lookup_method"), "{}", events);
+    }
+}
+
 #[test]
 fn no_such_field_diagnostics() {
     let diagnostics = TestDB::with_files(
@@ -479,6 +530,130 @@ fn no_such_field_with_feature_flag_diagnostics_on_struct_fields() {
     assert_snapshot!(diagnostics, @r###""###);
 }
 
+#[test]
+fn type_mismatch_diagnostics() {
+    let diagnostics = TestDB::with_files(
+        r"
+        //- /lib.rs
+        fn foo() -> u32 {
+            let x: bool = 92u32;
+            { false }
+        }
+        ",
+    )
+    .diagnostics()
+    .0;
+
+    assert_snapshot!(diagnostics, @r###"
+    "92u32": Mismatched types: expected `bool`, found `u32`
+    "false": Mismatched types: expected `u32`, found `bool`
+    "###
+    );
+}
+
+#[test]
+fn unresolved_name_diagnostics() {
+    let diagnostics = TestDB::with_files(
+        r"
+        //- /lib.rs
+        mod foo { pub fn bar() {} }
+        struct S;
+        impl S {
+            fn new() -> S { Self }
+        }
+        fn baz(x: u32) {
+            x;
+            foo::bar();
+            foo::qux();
+            quux;
+        }
+        ",
+    )
+    .diagnostics()
+    .0;
+
+    assert_snapshot!(diagnostics, @r###"
+    "foo::qux": unresolved name `foo::qux`
+    "quux": unresolved name `quux`
+    "###
+    );
+}
+
+#[test]
+fn unresolved_name_diagnostics_skip_unknown_items() {
+    let diagnostics = TestDB::with_files(
+        r"
+        //- /lib.rs
+        mod foo {
+            #[cfg(never)]
+            pub fn bar() {}
+        }
+        mod generated {
+            generate_items!();
+        }
+        #[cfg(never)]
+        fn qux() {}
+        macro_rules! id { ($($tt:tt)*) => { $($tt)* } }
+
+        fn baz() {
+            foo::bar();
+            generated::anything();
+            qux();
+            id!(quux);
+            nothing;
+        }
+        fn with_unresolved_macro() {
+            declare_x!();
+            x;
+        }
+        ",
+    )
+    .diagnostic::<UnresolvedName>()
+    .0;
+
+    assert_snapshot!(diagnostics, @r###"
+    "nothing": unresolved name `nothing`
+    "###
+    );
+}
+
+#[test]
+fn private_item_diagnostics() {
+    let diagnostics = TestDB::with_files(
+        r"
+        //- /lib.rs
+        mod foo {
+            pub fn bar() {}
+            fn baz() {}
+            pub(super) const C: u32 = 0;
+            const D: u32 = 0;
+            pub mod inner {
+                pub(super) fn f() {}
+            }
+            fn g() {
+                inner::f();
+            }
+        }
+        fn main() {
+            foo::bar();
+            foo::baz();
+            foo::C;
+            foo::D;
+            foo::inner::f();
+        }
+        ",
+    )
+    .diagnostics()
+    .0;
+
+    assert_snapshot!(diagnostics, @r###"
+    "foo::baz": `foo::baz` is private
+    "foo::D": `foo::D` is private
+    "foo::inner::f": `foo::inner::f` is private
+    "###
+    );
+}
+
 #[test]
 fn missing_record_pat_field_diagnostic() {
     let diagnostics = TestDB::with_files(
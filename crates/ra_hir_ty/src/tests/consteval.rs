@@ -0,0 +1,118 @@
+use hir_def::{db::DefDatabase, ModuleDefId};
+use ra_db::fixture::WithFixture;
+
+use crate::{
+    consteval::{ComputedExpr, ConstEvalError},
+    db::HirDatabase,
+    test_db::TestDB,
+};
+
+fn eval_goal(ra_fixture: &str) -> Result<ComputedExpr, ConstEvalError> {
+    let (db, file_id) = TestDB::with_single_file(ra_fixture);
+    let module = db.module_for_file(file_id);
+    let crate_def_map = db.crate_def_map(module.krate);
+    let goal = crate_def_map[module.local_id]
+        .scope
+        .declarations()
+        .find_map(|def| match def {
+            ModuleDefId::ConstId(it) => {
+                let name = db.const_data(it).name.clone()?;
+                if name.to_string() == "GOAL" {
+                    Some(it)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        })
+        .expect("no `GOAL` constant");
+    db.const_eval(goal)
+}
+
+fn check_number(ra_fixture: &str, answer: i128) {
+    assert_eq!(eval_goal(ra_fixture), Ok(ComputedExpr::Int(answer)));
+}
+
+#[test]
+fn literals_and_arithmetic() {
+    check_number("const GOAL: usize = 2 + 2 * 10 - 0x10;", 6);
+    check_number("const GOAL: i32 = -(7 % 4) << 2;", -12);
+    check_number("const GOAL: u8 = b'a' | 0b100_0000;", 97);
+    assert_eq!(eval_goal("const GOAL: bool = 2 < 3 && !false;"), Ok(ComputedExpr::Bool(true)));
+    assert_eq!(eval_goal("const GOAL: char = 'x';"), Ok(ComputedExpr::Char('x')));
+}
+
+#[test]
+fn overflow_and_division_by_zero() {
+    assert!(matches!(eval_goal("const GOAL: u8 = 255 + 1;"), Err(ConstEvalError::Panic(_))));
+    assert!(matches!(eval_goal("const GOAL: i32 = 1 / 0;"), Err(ConstEvalError::Panic(_))));
+    assert!(matches!(eval_goal("const GOAL: u8 = 1 << 8;"), Err(ConstEvalError::Panic(_))));
+    assert!(matches!(eval_goal("const GOAL: u8 = 16 << 4;"), Err(ConstEvalError::Panic(_))));
+    check_number("const GOAL: i8 = -128;", -128);
+    check_number("const GOAL: i32 = -2147483648i32;", -2147483648);
+    assert!(matches!(eval_goal("const GOAL: i8 = -129;"), Err(ConstEvalError::Panic(_))));
+}
+
+#[test]
+fn casts() {
+    check_number("const GOAL: u8 = 300 as u8;", 44);
+    check_number("const GOAL: i8 = 200u8 as i8;", -56);
+    check_number("const GOAL: u32 = 'a' as u32;", 97);
+}
+
+#[test]
+fn other_constants() {
+    check_number(
+        r#"
+const A: usize = 3;
+const GOAL: usize = A * A + B;
+const B: usize = { let x = A; x + 1 };
+"#,
+        13,
+    );
+    check_number(
+        r#"
+struct S;
+impl S {
+    const VALUE: i32 = 42;
+}
+const GOAL: i32 = S::VALUE;
+"#,
+        42,
+    );
+    assert_eq!(eval_goal("const GOAL: usize = GOAL + 1;"), Err(ConstEvalError::Cycle));
+}
+
+#[test]
+fn const_fn_calls() {
+    check_number(
+        r#"
+const fn fact(n: u64) -> u64 {
+    if n == 0 { 1 } else { n * fact(n - 1) }
+}
+const GOAL: u64 = fact(5);
+"#,
+        120,
+    );
+    assert_eq!(
+        eval_goal(
+            r#"
+fn not_const() -> u64 { 1 }
+const GOAL: u64 = not_const();
+"#
+        ),
+        Err(ConstEvalError::NotSupported("call to non-const fn"))
+    );
+}
+
+#[test]
+fn enum_discriminants() {
+    check_number(
+        r#"
+const BASE: isize = 10;
+enum E { A, B = BASE, C, D = -1, F }
+const GOAL: isize = E::A as isize + E::C as isize * 10 + E::F as isize * 100;
+"#,
+        110,
+    );
+}
@@ -465,7 +465,7 @@ fn main() {
 }
 "#),
         @r###"
-    !0..1 '0': i32
+    !0..1 '6': i32
     64..88 '{     ...!(); }': ()
     74..75 'x': i32
     "###
@@ -625,6 +625,52 @@ fn bar() -> u32 {0}
     assert_eq!("{unknown}", type_at_pos(&db, pos));
 }
 
+#[test]
+fn infer_builtin_macros_include_concat_with_literals() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs
+#[rustc_builtin_macro]
+macro_rules! include {() => {}}
+
+#[rustc_builtin_macro]
+macro_rules! concat {() => {}}
+
+mod generated {
+    include!(concat!("gen", 2, ".rs"));
+}
+
+fn main() {
+    generated::bar()<|>;
+}
+
+//- /gen2.rs
+pub fn bar() -> u32 {0}
+"#,
+    );
+    assert_eq!("u32", type_at_pos(&db, pos));
+}
+
+#[test]
+fn infer_builtin_macros_include_str() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs
+#[rustc_builtin_macro]
+macro_rules! include_str {() => {}}
+
+fn main() {
+    let x = include_str!("foo.txt");
+    x<|>;
+}
+
+//- /foo.txt
+hello
+"#,
+    );
+    assert_eq!("&str", type_at_pos(&db, pos));
+}
+
 #[test]
 fn infer_builtin_macros_include_itself_should_failed() {
     let (db, pos) = TestDB::with_position(
@@ -803,3 +849,82 @@ fn main() {
     "###
     );
 }
+
+#[test]
+fn infer_macro_def_item() {
+    let t = type_at(
+        r#"
+//- /main.rs
+mod m {
+    pub macro double($e:expr) { $e * 2 }
+    pub macro pair {
+        ($a:expr) => { ($a, $a) },
+        ($a:expr, $b:expr) => { ($a, $b) }
+    }
+}
+use m::double;
+
+fn main() {
+    let x = m::pair!(double!(3u32), "");
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "(u32, &str)");
+}
+
+#[test]
+fn macro_bindings_do_not_capture_call_site_names() {
+    let t = type_at(
+        r#"
+//- /main.rs
+macro_rules! with_x {
+    ($e:expr) => {{ let x = 1u8; $e }};
+}
+
+fn main() {
+    let x = "";
+    let y = with_x!(x);
+    y<|>;
+}
+"#,
+    );
+    assert_eq!(t, "&str");
+}
+
+#[test]
+fn macro_bindings_are_visible_within_the_macro() {
+    let t = type_at(
+        r#"
+//- /main.rs
+macro_rules! with_x {
+    ($e:expr) => {{ let x = $e; (x, 1u8) }};
+}
+
+fn main() {
+    let x = "";
+    let y = with_x!(0i32);
+    y<|>;
+}
+"#,
+    );
+    assert_eq!(t, "(i32, u8)");
+}
+
+#[test]
+fn macro_can_refer_to_bindings_around_its_definition() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn main() {
+    let x = 1u8;
+    macro_rules! get_x {
+        () => { x };
+    }
+    let y = get_x!();
+    y<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u8");
+}
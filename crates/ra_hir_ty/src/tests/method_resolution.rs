@@ -856,6 +856,96 @@ fn test() {
     assert_eq!(t, "usize");
 }
 
+#[test]
+fn method_resolution_through_deref_chain() {
+    let t = type_at(
+        r#"
+//- /main.rs
+#[lang = "deref"]
+trait Deref {
+    type Target;
+    fn deref(&self) -> &Self::Target;
+}
+#[lang = "owned_box"]
+struct Box<T>(*mut T);
+struct Vec<T>(*mut T);
+impl<T> Deref for Vec<T> {
+    type Target = [T];
+}
+struct Iter<T>(*const T);
+#[lang = "slice"]
+impl<T> [T] {
+    fn iter(&self) -> Iter<T> { loop {} }
+}
+fn test(v: Box<Vec<u32>>) {
+    v.iter()<|>;
+}
+"#,
+    );
+    assert_eq!(t, "Iter<u32>");
+}
+
+#[test]
+fn method_resolution_through_nested_smart_pointers() {
+    let t = type_at(
+        r#"
+//- /main.rs
+#[lang = "deref"]
+trait Deref {
+    type Target;
+    fn deref(&self) -> &Self::Target;
+}
+struct Rc<T>(*const T);
+impl<T> Rc<T> {
+    fn new(t: T) -> Rc<T> { loop {} }
+}
+impl<T> Deref for Rc<T> {
+    type Target = T;
+}
+struct Wrapper<T>(T);
+impl<T> Deref for Wrapper<T> {
+    type Target = Rc<T>;
+}
+struct S;
+impl S {
+    fn foo(&self) -> u128 { 0 }
+}
+fn test() {
+    let w = Wrapper(Rc::new(S));
+    w.foo()<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u128");
+}
+
+#[test]
+fn method_resolution_unsize_array_behind_deref() {
+    let t = type_at(
+        r#"
+//- /main.rs
+#[lang = "deref"]
+trait Deref {
+    type Target;
+    fn deref(&self) -> &Self::Target;
+}
+struct Rc<T>(*const T);
+impl<T> Deref for Rc<T> {
+    type Target = T;
+}
+#[lang = "slice"]
+impl<T> [T] {
+    fn first(&self) -> Option<&T> { loop {} }
+}
+enum Option<T> { Some(T), None }
+fn test(a: Rc<[i32; 3]>) {
+    a.first()<|>;
+}
+"#,
+    );
+    assert_eq!(t, "Option<&i32>");
+}
+
 #[test]
 fn method_resolution_trait_from_prelude() {
     let (db, pos) = TestDB::with_position(
@@ -981,6 +1071,61 @@ fn test() { S2.into()<|>; }
     assert_eq!(t, "{unknown}");
 }
 
+#[test]
+fn method_resolution_where_clause_on_projection() {
+    let t = type_at(
+        r#"
+//- /main.rs
+mod foo {
+    pub trait Trait2 { fn foo(&self) -> u32; }
+}
+trait Trait { type Item; }
+fn test<T: Trait>(x: T::Item) where T::Item: foo::Trait2 {
+    x.foo()<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u32");
+}
+
+#[test]
+fn method_resolution_assoc_type_bound_not_in_scope() {
+    let t = type_at(
+        r#"
+//- /main.rs
+mod foo {
+    pub trait Trait2 { fn foo(&self) -> u32; }
+    pub trait Trait3: Trait2 {}
+}
+trait Trait { type Item: foo::Trait3; }
+fn test<T: Trait>(x: T::Item) {
+    x.foo()<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u32");
+}
+
+#[test]
+fn method_resolution_impl_where_clause_not_in_scope() {
+    let t = type_at(
+        r#"
+//- /main.rs
+mod foo {
+    pub trait SuperTrait { fn foo(&self) -> u32; }
+    pub trait Trait: SuperTrait {}
+}
+struct S<T>(T);
+impl<T> S<T> where T: foo::Trait {
+    fn bar(&self, t: T) {
+        t.foo()<|>;
+    }
+}
+"#,
+    );
+    assert_eq!(t, "u32");
+}
+
 #[test]
 fn method_resolution_overloaded_method() {
     test_utils::covers!(impl_self_type_match_without_receiver);
@@ -1,4 +1,6 @@
-use super::type_at;
+use insta::assert_snapshot;
+
+use super::{infer_with_mismatches, type_at};
 
 #[test]
 fn infer_never1() {
@@ -261,3 +263,144 @@ fn test(a: i32) {
     );
     assert_eq!(t, "f64");
 }
+
+#[test]
+fn diverging_expressions_coerce_without_mismatches() {
+    assert_snapshot!(
+        infer_with_mismatches(r#"
+fn panic() -> ! { loop {} }
+fn test(c: bool) -> u32 {
+    let x: u32 = if c { 1 } else { panic() };
+    let y = c && panic();
+    x
+}
+"#, true),
+        @r###"
+    17..28 '{ loop {} }': !
+    19..26 'loop {}': !
+    24..26 '{}': ()
+    37..38 'c': bool
+    53..134 '{     ...   x }': u32
+    63..64 'x': u32
+    72..99 'if c {...ic() }': u32
+    75..76 'c': bool
+    77..82 '{ 1 }': u32
+    79..80 '1': u32
+    88..99 '{ panic() }': !
+    90..95 'panic': fn panic() -> !
+    90..97 'panic()': !
+    109..110 'y': bool
+    113..114 'c': bool
+    113..125 'c && panic()': bool
+    118..123 'panic': fn panic() -> !
+    118..125 'panic()': !
+    131..132 'x': u32
+    "###
+    );
+}
+
+#[test]
+fn diverging_let_makes_block_diverge() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test(a: bool) {
+    let i = {
+        let x = return;
+    };
+    i<|>;
+}
+"#,
+    );
+    assert_eq!(t, "!");
+}
+
+#[test]
+fn loop_break_value() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test(a: bool) {
+    let i = loop {
+        if a {
+            break 1u32;
+        }
+    };
+    i<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u32");
+}
+
+#[test]
+fn loop_break_without_value() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test() {
+    let i = loop {
+        break;
+    };
+    i<|>;
+}
+"#,
+    );
+    assert_eq!(t, "()");
+}
+
+#[test]
+fn break_in_nested_loop() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test(a: bool) {
+    let i = loop {
+        while a {
+            break;
+        }
+        break 1.0;
+    };
+    i<|>;
+}
+"#,
+    );
+    assert_eq!(t, "f64");
+}
+
+#[test]
+fn closure_body_does_not_diverge_outer_block() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test() {
+    let i = {
+        let f = || -> u32 { return 1; };
+        1u8
+    };
+    i<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u8");
+}
+
+#[test]
+fn never_type_fallback_resolves_obligations() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Foo { type Out; }
+impl Foo for ! { type Out = u32; }
+impl Foo for u8 { type Out = i64; }
+
+fn foo<T: Foo>(t: T) -> T::Out { loop {} }
+
+fn test() {
+    let x = foo(return);
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u32");
+}
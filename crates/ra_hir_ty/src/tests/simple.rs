@@ -28,7 +28,7 @@ mod boxed {
 
 "#,
     );
-    assert_eq!("(Box<i32>, Box<Box<i32>>, Box<&i32>, Box<[i32; _]>)", type_at_pos(&db, pos));
+    assert_eq!("(Box<i32>, Box<Box<i32>>, Box<&i32>, Box<[i32; 1]>)", type_at_pos(&db, pos));
 }
 
 #[test]
@@ -1104,55 +1104,55 @@ fn test(x: &str, y: isize) {
     9..10 'x': &str
     18..19 'y': isize
     28..293 '{     ... []; }': ()
-    38..39 'a': [&str; _]
-    42..45 '[x]': [&str; _]
+    38..39 'a': [&str; 1]
+    42..45 '[x]': [&str; 1]
     43..44 'x': &str
-    55..56 'b': [[&str; _]; _]
-    59..65 '[a, a]': [[&str; _]; _]
-    60..61 'a': [&str; _]
-    63..64 'a': [&str; _]
-    75..76 'c': [[[&str; _]; _]; _]
-    79..85 '[b, b]': [[[&str; _]; _]; _]
-    80..81 'b': [[&str; _]; _]
-    83..84 'b': [[&str; _]; _]
-    96..97 'd': [isize; _]
-    100..112 '[y, 1, 2, 3]': [isize; _]
+    55..56 'b': [[&str; 1]; 2]
+    59..65 '[a, a]': [[&str; 1]; 2]
+    60..61 'a': [&str; 1]
+    63..64 'a': [&str; 1]
+    75..76 'c': [[[&str; 1]; 2]; 2]
+    79..85 '[b, b]': [[[&str; 1]; 2]; 2]
+    80..81 'b': [[&str; 1]; 2]
+    83..84 'b': [[&str; 1]; 2]
+    96..97 'd': [isize; 4]
+    100..112 '[y, 1, 2, 3]': [isize; 4]
     101..102 'y': isize
     104..105 '1': isize
     107..108 '2': isize
     110..111 '3': isize
-    122..123 'd': [isize; _]
-    126..138 '[1, y, 2, 3]': [isize; _]
+    122..123 'd': [isize; 4]
+    126..138 '[1, y, 2, 3]': [isize; 4]
     127..128 '1': isize
     130..131 'y': isize
     133..134 '2': isize
     136..137 '3': isize
-    148..149 'e': [isize; _]
-    152..155 '[y]': [isize; _]
+    148..149 'e': [isize; 1]
+    152..155 '[y]': [isize; 1]
     153..154 'y': isize
-    165..166 'f': [[isize; _]; _]
-    169..175 '[d, d]': [[isize; _]; _]
-    170..171 'd': [isize; _]
-    173..174 'd': [isize; _]
-    185..186 'g': [[isize; _]; _]
-    189..195 '[e, e]': [[isize; _]; _]
-    190..191 'e': [isize; _]
-    193..194 'e': [isize; _]
-    206..207 'h': [i32; _]
-    210..216 '[1, 2]': [i32; _]
+    165..166 'f': [[isize; 4]; 2]
+    169..175 '[d, d]': [[isize; 4]; 2]
+    170..171 'd': [isize; 4]
+    173..174 'd': [isize; 4]
+    185..186 'g': [[isize; 1]; 2]
+    189..195 '[e, e]': [[isize; 1]; 2]
+    190..191 'e': [isize; 1]
+    193..194 'e': [isize; 1]
+    206..207 'h': [i32; 2]
+    210..216 '[1, 2]': [i32; 2]
     211..212 '1': i32
     214..215 '2': i32
-    226..227 'i': [&str; _]
-    230..240 '["a", "b"]': [&str; _]
+    226..227 'i': [&str; 2]
+    230..240 '["a", "b"]': [&str; 2]
     231..234 '"a"': &str
     236..239 '"b"': &str
-    251..252 'b': [[&str; _]; _]
-    255..265 '[a, ["b"]]': [[&str; _]; _]
-    256..257 'a': [&str; _]
-    259..264 '["b"]': [&str; _]
+    251..252 'b': [[&str; 1]; 2]
+    255..265 '[a, ["b"]]': [[&str; 1]; 2]
+    256..257 'a': [&str; 1]
+    259..264 '["b"]': [&str; 1]
     260..263 '"b"': &str
-    275..276 'x': [u8; _]
-    288..290 '[]': [u8; _]
+    275..276 'x': [u8; 0]
+    288..290 '[]': [u8; 0]
     "###
     );
 }
@@ -1675,6 +1675,88 @@ fn main() {
     assert_eq!(t, "u32");
 }
 
+#[test]
+fn const_generic_param_value() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn foo<const N: usize>() {
+    N<|>;
+}"#,
+    );
+    assert_eq!(t, "usize");
+}
+
+#[test]
+fn const_generic_args_are_substituted() {
+    let t = type_at(
+        r#"
+//- /main.rs
+struct Wrapper<const N: usize, T> { data: [T; N] }
+
+impl<const N: usize, T> Wrapper<N, T> {
+    fn first(&self) -> &T { loop {} }
+}
+
+fn test(w: Wrapper<3, u8>) {
+    w.first()<|>;
+}"#,
+    );
+    assert_eq!(t, "&u8");
+}
+
+#[test]
+fn const_generic_args_are_unified() {
+    let t = type_at(
+        r#"
+//- /main.rs
+struct Wrapper<T, const N: usize>(T);
+
+impl<T> Wrapper<T, 3> {
+    fn three(&self) -> T { loop {} }
+}
+
+impl<T> Wrapper<T, { 4 }> {
+    fn four(&self) -> T { loop {} }
+}
+
+fn test(w: Wrapper<u8, 3>) {
+    (w.three(), w.four())<|>;
+}"#,
+    );
+    assert_eq!(t, "(u8, {unknown})");
+}
+
+#[test]
+fn const_generic_args_from_consts() {
+    let t = type_at(
+        r#"
+//- /main.rs
+const LEN: usize = 1 + 1;
+struct Wrapper<T, const N: usize>(T);
+
+fn test<const M: usize>(a: Wrapper<u8, LEN>, b: Wrapper<u8, { M }>) {
+    (a, b)<|>;
+}"#,
+    );
+    assert_eq!(t, "(Wrapper<u8, 2>, Wrapper<u8, M>)");
+}
+
+#[test]
+fn const_generic_struct_literal() {
+    let t = type_at(
+        r#"
+//- /main.rs
+struct Wrapper<T, const N: usize> { data: [T; N] }
+
+fn test() {
+    let w = Wrapper { data: [1u8, 2, 3] };
+    w<|>;
+}"#,
+    );
+    assert_eq!(t, "Wrapper<u8, 3>");
+}
+
 #[test]
 fn closure_return() {
     assert_snapshot!(
@@ -1773,7 +1855,9 @@ fn main() {
     32..38 '{ 92 }': i32
     34..36 '92': i32
     48..49 'y': {unknown}
+    52..80 'async ...wait }': {unknown}
     58..80 '{ asyn...wait }': {unknown}
+    60..72 'async { () }': {unknown}
     60..78 'async ....await': {unknown}
     66..72 '{ () }': ()
     68..70 '()': ()
@@ -67,6 +67,109 @@ mod future {
     assert_eq!("u64", type_at_pos(&db, pos));
 }
 
+#[test]
+fn infer_async_block() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs crate:main deps:std
+
+fn test() {
+    let r = async { 92u64 };
+    let v = r.await;
+    v<|>;
+}
+
+//- /std.rs crate:std
+#[prelude_import] use future::*;
+mod future {
+    #[lang = "future_trait"]
+    trait Future {
+        type Output;
+    }
+}
+
+"#,
+    );
+    assert_eq!("u64", type_at_pos(&db, pos));
+}
+
+#[test]
+fn infer_async_block_type() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs crate:main deps:std
+
+fn test() {
+    let r = async { 92u32 };
+    r<|>;
+}
+
+//- /std.rs crate:std
+#[prelude_import] use future::*;
+mod future {
+    #[lang = "future_trait"]
+    trait Future {
+        type Output;
+    }
+}
+
+"#,
+    );
+    assert_eq!("impl Future<Output = u32>", type_at_pos(&db, pos));
+}
+
+#[test]
+fn infer_async_closure() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs crate:main deps:std
+
+fn test() {
+    let f = async |x: u32| x;
+    let v = f(1).await;
+    v<|>;
+}
+
+//- /std.rs crate:std
+#[prelude_import] use future::*;
+mod future {
+    #[lang = "future_trait"]
+    trait Future {
+        type Output;
+    }
+}
+
+"#,
+    );
+    assert_eq!("u32", type_at_pos(&db, pos));
+}
+
+#[test]
+fn infer_async_fn_body() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs crate:main deps:std
+
+async fn foo() -> u64 {
+    let x = 1;
+    x<|>;
+    return x;
+}
+
+//- /std.rs crate:std
+#[prelude_import] use future::*;
+mod future {
+    #[lang = "future_trait"]
+    trait Future {
+        type Output;
+    }
+}
+
+"#,
+    );
+    assert_eq!("u64", type_at_pos(&db, pos));
+}
+
 #[test]
 fn infer_desugar_async() {
     let (db, pos) = TestDB::with_position(
@@ -525,7 +628,7 @@ fn indexing_arrays() {
         @r###"
     10..26 '{ &mut...[2]; }': ()
     12..23 '&mut [9][2]': &mut {unknown}
-    17..20 '[9]': [i32; _]
+    17..20 '[9]': [i32; 1]
     17..23 '[9][2]': {unknown}
     18..19 '9': i32
     21..22 '2': i32
@@ -1708,6 +1811,44 @@ fn test<F: FnOnce(u32) -> u64>(f: F) {
     );
 }
 
+#[test]
+fn call_through_fn_trait() {
+    let t = type_at(
+        r#"
+//- /main.rs
+#[lang = "fn_once"]
+trait FnOnce<Args> {
+    type Output;
+}
+
+fn test<F: FnOnce(u32) -> u64>(f: F, g: &dyn FnOnce(u32, u64) -> u128, h: impl FnOnce(u8) -> i8) {
+    (f(1), g(1, 2), h(3))<|>;
+}
+"#,
+    );
+    assert_eq!(t, "(u64, u128, i8)");
+}
+
+#[test]
+fn call_through_fn_trait_infers_args() {
+    let t = type_at(
+        r#"
+//- /main.rs
+#[lang = "fn_once"]
+trait FnOnce<Args> {
+    type Output;
+}
+
+fn test<F: FnOnce(u32) -> u64>(f: F) {
+    let x = 1;
+    f(x);
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u32");
+}
+
 #[test]
 fn closure_as_argument_inference_order() {
     assert_snapshot!(
@@ -2122,6 +2263,87 @@ impl TokenStream for Rustc {
     );
 }
 
+#[test]
+fn impl_trait_iterator_methods() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs crate:main deps:std
+fn numbers() -> impl Iterator<Item = u32> { loop {} }
+
+fn test() {
+    let mut it = numbers();
+    let x = it.next();
+    x<|>;
+}
+
+//- /std.rs crate:std
+#[prelude_import] use prelude::*;
+mod prelude {
+    pub enum Option<T> {
+        Some(T),
+        None,
+    }
+    pub trait Iterator {
+        type Item;
+        fn next(&mut self) -> Option<Self::Item>;
+    }
+}
+"#,
+    );
+    assert_eq!("Option<u32>", type_at_pos(&db, pos));
+}
+
+#[test]
+fn impl_trait_for_loop() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs crate:main deps:std
+fn numbers() -> impl Iterator<Item = u32> { loop {} }
+
+fn test() {
+    for x in numbers() {
+        x<|>;
+    }
+}
+
+//- /std.rs crate:std
+#[prelude_import] use iter::*;
+mod iter {
+    trait IntoIterator {
+        type Item;
+    }
+    trait Iterator {
+        type Item;
+    }
+    impl<T: Iterator> IntoIterator for T {
+        type Item = <T as Iterator>::Item;
+    }
+}
+"#,
+    );
+    assert_eq!("u32", type_at_pos(&db, pos));
+}
+
+#[test]
+fn impl_trait_round_trips_through_solver() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Trait<T> {}
+trait Wrap { type Out; }
+impl<T> Wrap for T { type Out = T; }
+fn out<W: Wrap>(w: W) -> W::Out { loop {} }
+fn foo<U>(u: U) -> impl Trait<U> { loop {} }
+
+fn test() {
+    let x = out(foo(1u8));
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "impl Trait<u8>");
+}
+
 #[test]
 fn unify_impl_trait() {
     assert_snapshot!(
@@ -2155,7 +2377,7 @@ fn test() -> impl Trait<i32> {
     172..183 '{ loop {} }': T
     174..181 'loop {}': !
     179..181 '{}': ()
-    214..310 '{     ...t()) }': S<{unknown}>
+    214..310 '{     ...t()) }': S<i32>
     224..226 's1': S<u32>
     229..230 'S': S<u32>(u32) -> S<u32>
     229..241 'S(default())': S<u32>
@@ -2171,10 +2393,10 @@ fn test() -> impl Trait<i32> {
     277..289 'S(default())': S<i32>
     279..286 'default': fn default<i32>() -> i32
     279..288 'default()': i32
-    296..297 'S': S<{unknown}>({unknown}) -> S<{unknown}>
-    296..308 'S(default())': S<{unknown}>
-    298..305 'default': fn default<{unknown}>() -> {unknown}
-    298..307 'default()': {unknown}
+    296..297 'S': S<i32>(i32) -> S<i32>
+    296..308 'S(default())': S<i32>
+    298..305 'default': fn default<i32>() -> i32
+    298..307 'default()': i32
     "###
     );
 }
@@ -23,7 +23,25 @@ mod builtin;
 // const CHALK_SOLVER_MAX_SIZE: usize = 10;
 
 /// This controls how much 'time' we give the Chalk solver before giving up.
-const CHALK_SOLVER_FUEL: i32 = 100;
+const CHALK_SOLVER_FUEL: u32 = 100;
+
+/// This controls how deeply Chalk may recurse into subgoals before it reports
+/// overflow.
+const CHALK_SOLVER_OVERFLOW_DEPTH: usize = 100;
+
+/// Limits on the work the trait solver may do for a single goal. Deeply
+/// generic code (e.g. type-level numbers) may need more than the defaults.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TraitSolverLimits {
+    pub overflow_depth: usize,
+    pub fuel: u32,
+}
+
+impl Default for TraitSolverLimits {
+    fn default() -> Self {
+        TraitSolverLimits { overflow_depth: CHALK_SOLVER_OVERFLOW_DEPTH, fuel: CHALK_SOLVER_FUEL }
+    }
+}
 
 #[derive(Debug, Copy, Clone)]
 struct ChalkContext<'a> {
@@ -31,8 +49,11 @@ struct ChalkContext<'a> {
     krate: CrateId,
 }
 
-fn create_chalk_solver() -> chalk_solve::Solver<Interner> {
-    let solver_choice = chalk_solve::SolverChoice::recursive();
+fn create_chalk_solver(limits: TraitSolverLimits) -> chalk_solve::Solver<Interner> {
+    let solver_choice = chalk_solve::SolverChoice::Recursive {
+        overflow_depth: limits.overflow_depth,
+        caching_enabled: true,
+    };
     solver_choice.into_solver()
 }
 
@@ -182,18 +203,22 @@ fn solve(
 ) -> Option<chalk_solve::Solution<Interner>> {
     let context = ChalkContext { db, krate };
     log::debug!("solve goal: {:?}", goal);
-    let mut solver = create_chalk_solver();
+    // The limits are an input, so changing them invalidates the solutions
+    // computed with the old ones.
+    let limits = db.trait_solver_limits();
+    let mut solver = create_chalk_solver(limits);
 
-    let fuel = std::cell::Cell::new(CHALK_SOLVER_FUEL);
+    let fuel = std::cell::Cell::new(limits.fuel);
 
     let should_continue = || {
         context.db.check_canceled();
         let remaining = fuel.get();
-        fuel.set(remaining - 1);
         if remaining == 0 {
             log::debug!("fuel exhausted");
+            return false;
         }
-        remaining > 0
+        fuel.set(remaining - 1);
+        true
     };
     let mut solve = || {
         let solution = solver.solve_limited(&context, goal, should_continue);
@@ -348,3 +373,18 @@ pub enum AssocTyValue {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct AssocTyValueId(salsa::InternId);
 impl_intern_key!(AssocTyValueId);
+
+/// The bounds of an `impl Trait` type, which Chalk sees as an opaque type. The
+/// types from outside of the bounds are replaced by `Ty::Bound` parameters,
+/// so that the same bounds get the same ID wherever they appear.
+/// (We only need this because Chalk wants a unique ID for each opaque type.)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OpaqueBounds {
+    /// Quantified over `Self` like the predicates of `Ty::Opaque`, with the
+    /// parameters bound one level further out.
+    pub(crate) predicates: Arc<[GenericPredicate]>,
+    pub(crate) num_params: usize,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OpaqueBoundsId(salsa::InternId);
+impl_intern_key!(OpaqueBoundsId);
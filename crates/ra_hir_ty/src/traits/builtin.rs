@@ -239,23 +239,23 @@ fn check_unsize_impl_prerequisites(db: &dyn HirDatabase, krate: CrateId) -> bool
 }
 
 fn array_unsize_impl_datum(db: &dyn HirDatabase, krate: CrateId) -> BuiltinImplData {
-    // impl<T> Unsize<[T]> for [T; _]
-    // (this can be a single impl because we don't distinguish array sizes currently)
+    // impl<T, const N: usize> Unsize<[T]> for [T; N]
 
     let trait_ = get_unsize_trait(db, krate) // get unsize trait
         // the existence of the Unsize trait has been checked before
         .expect("Unsize trait missing");
 
     let var = Ty::Bound(BoundVar::new(DebruijnIndex::INNERMOST, 0));
+    let len = Ty::Bound(BoundVar::new(DebruijnIndex::INNERMOST, 1));
     let substs = Substs::builder(2)
-        .push(Ty::apply_one(TypeCtor::Array, var.clone()))
+        .push(Ty::array(var.clone(), len))
         .push(Ty::apply_one(TypeCtor::Slice, var))
         .build();
 
     let trait_ref = TraitRef { trait_, substs };
 
     BuiltinImplData {
-        num_vars: 1,
+        num_vars: 2,
         trait_ref,
         where_clauses: Vec::new(),
         assoc_ty_values: Vec::new(),
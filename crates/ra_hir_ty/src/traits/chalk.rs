@@ -14,10 +14,11 @@ use ra_db::{
     CrateId,
 };
 
-use super::{builtin, AssocTyValue, Canonical, ChalkContext, Impl, Obligation};
+use super::{builtin, AssocTyValue, Canonical, ChalkContext, Impl, Obligation, OpaqueBounds};
 use crate::{
     db::HirDatabase, display::HirDisplay, method_resolution::TyFingerprint, utils::generics,
-    ApplicationTy, DebruijnIndex, GenericPredicate, ProjectionTy, Substs, TraitRef, Ty, TypeCtor,
+    ApplicationTy, BoundVar, DebruijnIndex, GenericPredicate, ProjectionTy, Substs, TraitRef, Ty,
+    TypeCtor, TypeWalk,
 };
 
 pub(super) mod tls;
@@ -336,6 +337,14 @@ impl ToChalk for Ty {
             }
             Ty::Bound(idx) => chalk_ir::TyData::BoundVar(idx).intern(&Interner),
             Ty::Infer(_infer_ty) => panic!("uncanonicalized infer ty"),
+            Ty::Opaque(predicates) => {
+                let (bounds, substs) = generalize_opaque_bounds(&predicates);
+                let opaque_ty_id = db.intern_opaque_bounds(bounds).into();
+                let substitution = substs.to_chalk(db);
+                chalk_ir::AliasTy::Opaque(chalk_ir::OpaqueTy { opaque_ty_id, substitution })
+                    .cast(&Interner)
+                    .intern(&Interner)
+            }
             Ty::Dyn(predicates) => {
                 let where_clauses = chalk_ir::QuantifiedWhereClauses::from(
                     &Interner,
@@ -344,7 +353,7 @@ impl ToChalk for Ty {
                 let bounded_ty = chalk_ir::DynTy { bounds: make_binders(where_clauses, 1) };
                 chalk_ir::TyData::Dyn(bounded_ty).intern(&Interner)
             }
-            Ty::Opaque(_) | Ty::Unknown => {
+            Ty::Unknown => {
                 let substitution = chalk_ir::Substitution::empty(&Interner);
                 let name = TypeName::Error;
                 chalk_ir::ApplicationTy { name, substitution }.cast(&Interner).intern(&Interner)
@@ -355,6 +364,9 @@ impl ToChalk for Ty {
         match chalk.data(&Interner).clone() {
             chalk_ir::TyData::Apply(apply_ty) => match apply_ty.name {
                 TypeName::Error => Ty::Unknown,
+                TypeName::OpaqueType(opaque_ty_id) => {
+                    opaque_from_chalk(db, opaque_ty_id, apply_ty.substitution)
+                }
                 _ => {
                     let ctor = from_chalk(db, apply_ty.name);
                     let parameters = from_chalk(db, apply_ty.substitution);
@@ -373,7 +385,9 @@ impl ToChalk for Ty {
                 let parameters = from_chalk(db, proj.substitution);
                 Ty::Projection(ProjectionTy { associated_ty, parameters })
             }
-            chalk_ir::TyData::Alias(chalk_ir::AliasTy::Opaque(_)) => unimplemented!(),
+            chalk_ir::TyData::Alias(chalk_ir::AliasTy::Opaque(opaque_ty)) => {
+                opaque_from_chalk(db, opaque_ty.opaque_ty_id, opaque_ty.substitution)
+            }
             chalk_ir::TyData::Function(_) => unimplemented!(),
             chalk_ir::TyData::BoundVar(idx) => Ty::Bound(idx),
             chalk_ir::TyData::InferenceVar(_iv) => Ty::Unknown,
@@ -391,6 +405,55 @@ impl ToChalk for Ty {
     }
 }
 
+/// Splits the predicates of `Ty::Opaque` into `OpaqueBounds`, which only refer
+/// to `Self` and their own parameters, and the types of those parameters.
+fn generalize_opaque_bounds(predicates: &[GenericPredicate]) -> (OpaqueBounds, Substs) {
+    let mut params: Vec<BoundVar> = Vec::new();
+    let predicates = predicates
+        .iter()
+        .cloned()
+        .map(|mut predicate| {
+            // The predicates are inside of the binder of `Self`.
+            predicate.walk_mut_binders(
+                &mut |ty, binders| {
+                    if let Ty::Bound(bound) = ty {
+                        if let Some(outer) = bound.shifted_out_to(binders) {
+                            let idx = match params.iter().position(|it| *it == outer) {
+                                Some(idx) => idx,
+                                None => {
+                                    params.push(outer);
+                                    params.len() - 1
+                                }
+                            };
+                            *ty = Ty::Bound(BoundVar::new(binders, idx));
+                        }
+                    }
+                },
+                DebruijnIndex::ONE,
+            );
+            predicate
+        })
+        .collect();
+    let substs = Substs(params.iter().map(|&it| Ty::Bound(it)).collect());
+    (OpaqueBounds { predicates, num_params: params.len() }, substs)
+}
+
+fn opaque_from_chalk(
+    db: &dyn HirDatabase,
+    opaque_ty_id: chalk_ir::OpaqueTyId<Interner>,
+    substitution: chalk_ir::Substitution<Interner>,
+) -> Ty {
+    let bounds = db.lookup_intern_opaque_bounds(opaque_ty_id.into());
+    let substs: Substs = from_chalk(db, substitution);
+    let predicates = bounds
+        .predicates
+        .iter()
+        .cloned()
+        .map(|it| it.subst_bound_vars_at_depth(&substs, DebruijnIndex::ONE))
+        .collect();
+    Ty::Opaque(predicates)
+}
+
 impl ToChalk for Substs {
     type Chalk = chalk_ir::Substitution<Interner>;
 
@@ -888,9 +951,25 @@ impl<'a> chalk_solve::RustIrDatabase<Interner> for ChalkContext<'a> {
 
     fn opaque_ty_data(
         &self,
-        _id: chalk_ir::OpaqueTyId<Interner>,
+        id: chalk_ir::OpaqueTyId<Interner>,
     ) -> Arc<chalk_rust_ir::OpaqueTyDatum<Interner>> {
-        unimplemented!()
+        let bounds = self.db.lookup_intern_opaque_bounds(id.into());
+        let where_clauses = bounds
+            .predicates
+            .iter()
+            .filter(|it| !it.is_error())
+            .cloned()
+            .map(|it| it.to_chalk(self.db))
+            .collect::<Vec<_>>();
+        let bound = chalk_rust_ir::OpaqueTyDatumBound {
+            // The hidden type is never revealed to the solver.
+            hidden_ty: Ty::Unknown.to_chalk(self.db),
+            bounds: make_binders(where_clauses, 1),
+        };
+        Arc::new(chalk_rust_ir::OpaqueTyDatum {
+            opaque_ty_id: id,
+            bound: make_binders(bound, bounds.num_params),
+        })
     }
 }
 
@@ -1150,6 +1229,18 @@ impl From<crate::traits::GlobalImplId> for ImplId {
     }
 }
 
+impl From<chalk_ir::OpaqueTyId<Interner>> for crate::traits::OpaqueBoundsId {
+    fn from(id: chalk_ir::OpaqueTyId<Interner>) -> Self {
+        Self::from_intern_id(id.0)
+    }
+}
+
+impl From<crate::traits::OpaqueBoundsId> for chalk_ir::OpaqueTyId<Interner> {
+    fn from(id: crate::traits::OpaqueBoundsId) -> Self {
+        chalk_ir::OpaqueTyId(id.as_intern_id())
+    }
+}
+
 impl From<chalk_rust_ir::AssociatedTyValueId<Interner>> for crate::traits::AssocTyValueId {
     fn from(id: chalk_rust_ir::AssociatedTyValueId<Interner>) -> Self {
         Self::from_intern_id(id.0)
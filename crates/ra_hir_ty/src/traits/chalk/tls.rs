@@ -30,6 +30,7 @@ impl DebugContext<'_> {
             TypeCtor::RawPtr(m) => write!(f, "*{}", m.as_keyword_for_ptr())?,
             TypeCtor::Ref(m) => write!(f, "&{}", m.as_keyword_for_ref())?,
             TypeCtor::Never => write!(f, "!")?,
+            TypeCtor::Const(value) => write!(f, "{{const {}}}", value)?,
             TypeCtor::Tuple { .. } => {
                 write!(f, "()")?;
             }
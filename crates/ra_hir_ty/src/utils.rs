@@ -16,7 +16,9 @@ use hir_expand::name::{name, Name};
 
 use crate::{db::HirDatabase, GenericPredicate, TraitRef};
 
-fn direct_super_traits(db: &dyn DefDatabase, trait_: TraitId) -> Vec<TraitId> {
+/// The traits listed as the bounds of `Self` of `trait_`, either as supertraits
+/// or in the where clause.
+pub fn direct_super_traits(db: &dyn DefDatabase, trait_: TraitId) -> Vec<TraitId> {
     let resolver = trait_.resolver(db);
     // returning the iterator directly doesn't easily work because of
     // lifetime problems, but since there usually shouldn't be more than a
@@ -86,6 +88,24 @@ pub(super) fn all_super_traits(db: &dyn DefDatabase, trait_: TraitId) -> Vec<Tra
     result
 }
 
+/// Returns the traits an associated type is declared to implement, e.g. `Trait`
+/// for `type Item: Trait;`.
+pub(super) fn associated_type_bound_traits(
+    db: &dyn DefDatabase,
+    type_alias: TypeAliasId,
+) -> Vec<TraitId> {
+    let resolver = type_alias.resolver(db);
+    db.type_alias_data(type_alias)
+        .bounds
+        .iter()
+        .filter_map(|bound| bound.as_path())
+        .filter_map(|path| match resolver.resolve_path_in_type_ns_fully(db, path.mod_path()) {
+            Some(TypeNs::TraitId(t)) => Some(t),
+            _ => None,
+        })
+        .collect()
+}
+
 /// Given a trait ref (`Self: Trait`), builds all the implied trait refs for
 /// super traits. The original trait ref will be included. So the difference to
 /// `all_super_traits` is that we keep track of type parameters; for example if
@@ -226,6 +246,8 @@ impl Generics {
     }
 
     /// (parent total, self param, type param list, impl trait)
+    ///
+    /// Const parameters are counted as part of the type param list.
     pub(crate) fn provenance_split(&self) -> (usize, usize, usize, usize) {
         let parent = self.parent_generics.as_ref().map_or(0, |p| p.len());
         let self_params = self
@@ -238,7 +260,10 @@ impl Generics {
             .params
             .types
             .iter()
-            .filter(|(_, p)| p.provenance == TypeParamProvenance::TypeParamList)
+            .filter(|(_, p)| {
+                p.provenance == TypeParamProvenance::TypeParamList
+                    || p.provenance == TypeParamProvenance::ConstParam
+            })
             .count();
         let impl_trait_params = self
             .params
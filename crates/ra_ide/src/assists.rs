@@ -1,42 +1,97 @@
 //! FIXME: write short doc here
 
-use ra_assists::{resolved_assists, AssistAction};
-use ra_db::{FilePosition, FileRange};
+use ra_assists::{resolve_assist, resolved_assists, unresolved_assists, AssistAction};
+use ra_db::{FilePosition, FileRange, SourceDatabase, SourceDatabaseExt};
 use ra_ide_db::RootDatabase;
 
-use crate::{FileId, SourceChange, SourceFileEdit};
+use crate::{FileId, FileSystemEdit, SourceChange, SourceFileEdit};
 
-pub use ra_assists::AssistId;
+pub use ra_assists::{AssistConfig, AssistId, AssistKind, InsertUseConfig, MergeBehaviour};
 
 #[derive(Debug)]
 pub struct Assist {
     pub id: AssistId,
     pub label: String,
     pub group_label: Option<String>,
-    pub source_change: SourceChange,
+    /// The edit of the assist, `None` if it was not asked to be computed.
+    pub source_change: Option<SourceChange>,
 }
 
-pub(crate) fn assists(db: &RootDatabase, frange: FileRange) -> Vec<Assist> {
-    resolved_assists(db, frange)
+pub(crate) fn assists(
+    db: &RootDatabase,
+    config: &AssistConfig,
+    resolve: bool,
+    frange: FileRange,
+) -> Vec<Assist> {
+    if !resolve {
+        return unresolved_assists(db, config, frange)
+            .into_iter()
+            .map(|label| Assist {
+                id: label.id,
+                label: label.label,
+                group_label: label.group.map(|it| it.0),
+                source_change: None,
+            })
+            .collect();
+    }
+    resolved_assists(db, config, frange)
         .into_iter()
-        .map(|assist| {
-            let file_id = frange.file_id;
-            Assist {
-                id: assist.label.id,
-                label: assist.label.label.clone(),
-                group_label: assist.label.group.map(|it| it.0),
-                source_change: action_to_edit(assist.action, file_id, assist.label.label.clone()),
-            }
-        })
+        .map(|assist| to_assist(db, assist, frange.file_id))
         .collect()
 }
 
-fn action_to_edit(action: AssistAction, file_id: FileId, label: String) -> SourceChange {
+pub(crate) fn resolve_assist_edit(
+    db: &RootDatabase,
+    config: &AssistConfig,
+    frange: FileRange,
+    id: &str,
+    label: &str,
+) -> Option<Assist> {
+    let assist = resolve_assist(db, config, frange, id, label)?;
+    Some(to_assist(db, assist, frange.file_id))
+}
+
+fn to_assist(db: &RootDatabase, assist: ra_assists::ResolvedAssist, file_id: FileId) -> Assist {
+    Assist {
+        id: assist.label.id,
+        label: assist.label.label.clone(),
+        group_label: assist.label.group.map(|it| it.0),
+        source_change: Some(action_to_edit(db, assist.action, file_id, assist.label.label)),
+    }
+}
+
+pub(crate) fn organize_imports(
+    db: &RootDatabase,
+    config: &InsertUseConfig,
+    file_id: FileId,
+) -> Option<SourceChange> {
+    let file = db.parse(file_id).tree();
+    let edit = ra_assists::utils::organize_imports(&file, config)?;
+    Some(SourceChange::source_file_edit_from("Organize imports", file_id, edit))
+}
+
+fn action_to_edit(
+    db: &RootDatabase,
+    action: AssistAction,
+    file_id: FileId,
+    label: String,
+) -> SourceChange {
+    let source_root = db.file_source_root(file_id);
+    let file_system_edits = action
+        .new_files
+        .into_iter()
+        .map(|(path, contents)| FileSystemEdit::CreateFile { source_root, path, contents })
+        .collect();
     let file_id = match action.file {
         ra_assists::AssistFile::TargetFile(it) => it,
         _ => file_id,
     };
-    let file_edit = SourceFileEdit { file_id, edit: action.edit };
-    SourceChange::source_file_edit(label, file_edit)
-        .with_cursor_opt(action.cursor_position.map(|offset| FilePosition { offset, file_id }))
+    let mut file_edits = vec![SourceFileEdit { file_id, edit: action.edit }];
+    file_edits.extend(
+        action.other_file_edits.into_iter().map(|(file_id, edit)| SourceFileEdit { file_id, edit }),
+    );
+    let mut change = SourceChange::from_edits(label, file_edits, file_system_edits)
+        .with_cursor_opt(action.cursor_position.map(|offset| FilePosition { offset, file_id }));
+    change.is_snippet = action.is_snippet;
+    change
 }
@@ -4,7 +4,7 @@ use indexmap::IndexMap;
 
 use hir::Semantics;
 use ra_ide_db::RootDatabase;
-use ra_syntax::{ast, match_ast, AstNode, TextRange};
+use ra_syntax::{ast, match_ast, AstNode, SyntaxKind::FN_DEF, TextRange};
 
 use crate::{
     call_info::FnCallNode, display::ToNav, goto_definition, references, FilePosition,
@@ -34,7 +34,13 @@ pub(crate) fn call_hierarchy(
     db: &RootDatabase,
     position: FilePosition,
 ) -> Option<RangeInfo<Vec<NavigationTarget>>> {
-    goto_definition::goto_definition(db, position)
+    let mut navs = goto_definition::goto_definition(db, position)?;
+    // Only functions take part in calls.
+    navs.info.retain(|nav| nav.kind() == FN_DEF);
+    if navs.info.is_empty() {
+        return None;
+    }
+    Some(navs)
 }
 
 pub(crate) fn incoming_calls(db: &RootDatabase, position: FilePosition) -> Option<Vec<CallItem>> {
@@ -50,10 +56,22 @@ pub(crate) fn incoming_calls(db: &RootDatabase, position: FilePosition) -> Optio
         let file_id = reference.file_range.file_id;
         let file = sema.parse(file_id);
         let file = file.syntax();
-        let token = file.token_at_offset(reference.file_range.range.start()).next()?;
-        let token = sema.descend_into_macros(token);
+        let token = match file.token_at_offset(reference.file_range.range.start()).next() {
+            Some(it) => sema.descend_into_macros(it),
+            None => continue,
+        };
         let syntax = token.parent();
 
+        // Skip the references which only name the function, like `let f = foo;`.
+        let is_callee = syntax
+            .ancestors()
+            .find_map(|node| FnCallNode::with_node_exact(&node))
+            .and_then(|call_node| call_node.name_ref())
+            .map_or(false, |name_ref| name_ref.syntax().text_range() == token.text_range());
+        if !is_callee {
+            continue;
+        }
+
         // This target is the containing function
         if let Some(nav) = syntax.ancestors().find_map(|node| {
             match_ast! {
@@ -81,11 +99,12 @@ pub(crate) fn outgoing_calls(db: &RootDatabase, position: FilePosition) -> Optio
     let file = file.syntax();
     let token = file.token_at_offset(position.offset).next()?;
     let token = sema.descend_into_macros(token);
-    let syntax = token.parent();
+    let fn_def = token.parent().ancestors().find_map(ast::FnDef::cast)?;
+    let body = fn_def.body()?;
 
     let mut calls = CallLocations::default();
 
-    syntax
+    body.syntax()
         .descendants()
         .filter_map(|node| FnCallNode::with_node_exact(&node))
         .filter_map(|call_node| {
@@ -326,4 +345,53 @@ mod tests {
             &["caller3 FN_DEF FileId(1) 64..80 67..74 : [51..58]"],
         );
     }
+
+    #[test]
+    fn test_call_hierarchy_skips_non_call_references() {
+        check_hierarchy(
+            r#"
+            //- /lib.rs
+            fn callee() {}
+            fn caller() {
+                let f = callee;
+                call<|>ee();
+            }
+            "#,
+            "callee FN_DEF FileId(1) 0..14 3..9",
+            &["caller FN_DEF FileId(1) 15..64 18..24 : [53..59]"],
+            &[],
+        );
+    }
+
+    #[test]
+    fn test_call_hierarchy_outgoing_from_name() {
+        let (analysis, pos) = analysis_and_position(
+            r#"
+            //- /lib.rs
+            fn callee() {}
+            fn cal<|>ler() {
+                callee();
+            }
+            "#,
+        );
+
+        let outgoing_calls = analysis.outgoing_calls(pos).unwrap().unwrap();
+        assert_eq!(outgoing_calls.len(), 1);
+        outgoing_calls[0].assert_match("callee FN_DEF FileId(1) 0..14 3..9 : [33..39]");
+    }
+
+    #[test]
+    fn test_call_hierarchy_not_on_fn() {
+        let (analysis, pos) = analysis_and_position(
+            r#"
+            //- /lib.rs
+            struct Foo;
+            fn caller() {
+                let foo = Fo<|>o;
+            }
+            "#,
+        );
+
+        assert!(analysis.call_hierarchy(pos).unwrap().is_none());
+    }
 }
@@ -1,5 +1,5 @@
 //! FIXME: write short doc here
-use hir::Semantics;
+use hir::{AssocItem, ModuleDef, PathResolution, Semantics};
 use ra_ide_db::RootDatabase;
 use ra_syntax::{
     ast::{self, ArgListOwner},
@@ -37,6 +37,10 @@ impl ActiveParameter {
 }
 
 fn call_info_for_token(sema: &Semantics<RootDatabase>, token: SyntaxToken) -> Option<CallInfo> {
+    if let Some(arg_list) = token.parent().ancestors().find_map(ast::TypeArgList::cast) {
+        return generic_args_info(sema, &arg_list, &token);
+    }
+
     // Find the calling expression and it's NameRef
     let calling_node = FnCallNode::with_node(&token.parent())?;
 
@@ -111,6 +115,42 @@ fn call_info_for_token(sema: &Semantics<RootDatabase>, token: SyntaxToken) -> Op
     Some(call_info)
 }
 
+/// Computes the generic parameters of the item whose generic arguments are
+/// being written, like `Foo::<|>` or `HashMap<String, <|>`.
+fn generic_args_info(
+    sema: &Semantics<RootDatabase>,
+    arg_list: &ast::TypeArgList,
+    token: &SyntaxToken,
+) -> Option<CallInfo> {
+    let parent = arg_list.syntax().parent()?;
+    let def: hir::GenericDef = match_ast! {
+        match parent {
+            ast::PathSegment(segment) => match sema.resolve_path(&segment.parent_path())? {
+                PathResolution::Def(ModuleDef::Adt(it)) => it.into(),
+                PathResolution::Def(ModuleDef::Function(it))
+                | PathResolution::AssocItem(AssocItem::Function(it)) => it.into(),
+                PathResolution::Def(ModuleDef::Trait(it)) => it.into(),
+                PathResolution::Def(ModuleDef::TypeAlias(it))
+                | PathResolution::AssocItem(AssocItem::TypeAlias(it)) => it.into(),
+                _ => return None,
+            },
+            ast::MethodCallExpr(call) => sema.resolve_method_call(&call)?.into(),
+            _ => return None,
+        }
+    };
+    let signature = FunctionSignature::from_generic_def(sema.db, def)?;
+    let mut active_parameter = arg_list
+        .generic_args()
+        .take_while(|arg| arg.syntax().text_range().end() <= token.text_range().start())
+        .count();
+    // Lifetime arguments may be elided, and lifetime parameters come first.
+    if arg_list.lifetime_args().next().is_none() {
+        active_parameter +=
+            signature.parameters.iter().take_while(|it| it.starts_with('\'')).count();
+    }
+    Some(CallInfo { signature, active_parameter: Some(active_parameter) })
+}
+
 #[derive(Debug)]
 pub(crate) enum FnCallNode {
     CallExpr(ast::CallExpr),
@@ -684,4 +724,55 @@ fn f() {
 
         assert_eq!(info.label(), "fn foo()");
     }
+
+    #[test]
+    fn generic_params_of_type_in_type_position() {
+        let info = call_info(
+            r#"
+            struct RandomState;
+            /// A hash map.
+            struct HashMap<K, V, S = RandomState> where K: Eq {}
+            fn foo(map: HashMap<u32, <|>>) {}
+            "#,
+        );
+
+        assert_eq!(info.parameters(), ["K", "V", "S = RandomState"]);
+        assert_eq!(info.active_parameter, Some(1));
+        assert_eq!(info.label(), "HashMap<K, V, S = RandomState>\nwhere K: Eq");
+        assert_eq!(info.doc().map(|it| it.into()), Some("A hash map.".to_string()));
+    }
+
+    #[test]
+    fn generic_params_of_fn_in_turbofish() {
+        let info = call_info(
+            r#"
+            fn parse<'a, T: Clone>(s: &'a str) -> T {}
+            fn main() {
+                parse::<<|>>("1");
+            }
+            "#,
+        );
+
+        assert_eq!(info.parameters(), ["'a", "T: Clone"]);
+        assert_eq!(info.active_parameter, Some(1));
+    }
+
+    #[test]
+    fn generic_params_of_method_in_turbofish() {
+        let info = call_info(
+            r#"
+            struct S;
+            impl S {
+                fn convert<T, U>(&self) {}
+            }
+            fn main() {
+                S.convert::<u32, <|>>();
+            }
+            "#,
+        );
+
+        assert_eq!(info.parameters(), ["T", "U"]);
+        assert_eq!(info.active_parameter, Some(1));
+        assert_eq!(info.label(), "convert<T, U>");
+    }
 }
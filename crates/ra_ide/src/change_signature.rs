@@ -0,0 +1,288 @@
+//! Changes the parameters of a function, and updates its call sites to match.
+
+use hir::Semantics;
+use ra_ide_db::{defs::Definition, RootDatabase};
+use ra_syntax::{
+    algo::find_node_at_offset,
+    ast::{self, make, ArgListOwner, AstNode},
+    SyntaxNode, TextRange,
+};
+use ra_text_edit::TextEditBuilder;
+use rustc_hash::FxHashMap;
+use stdx::SepBy;
+
+use crate::{FileId, FilePosition, SourceChange, SourceFileEdit};
+
+/// A parameter of the new signature of a function.
+#[derive(Debug, Clone)]
+pub enum SignatureParam {
+    /// The parameter at `index` in the old signature, not counting `self`.
+    Existing { index: usize },
+    /// A new parameter. Call sites pass `default`, or a placeholder without one.
+    New { pattern: String, ty: String, default: Option<String> },
+}
+
+struct CallSite {
+    arg_list: ast::ArgList,
+    /// Whether the first argument is the receiver, as in `Foo::method(foo)`.
+    has_self_arg: bool,
+}
+
+impl CallSite {
+    fn range(&self) -> TextRange {
+        self.arg_list.syntax().text_range()
+    }
+}
+
+// FIXME: the signatures of trait methods and of their implementations should
+// be changed together.
+pub(crate) fn change_signature(
+    db: &RootDatabase,
+    position: FilePosition,
+    params: &[SignatureParam],
+) -> Option<SourceChange> {
+    let sema = Semantics::new(db);
+    let source_file = sema.parse(position.file_id);
+    let fn_def = find_node_at_offset::<ast::FnDef>(source_file.syntax(), position.offset)?;
+    let param_list = fn_def.param_list()?;
+    let old_params = param_list.params().collect::<Vec<_>>();
+    let is_valid = params.iter().all(|param| match param {
+        SignatureParam::Existing { index } => *index < old_params.len(),
+        SignatureParam::New { .. } => true,
+    });
+    if !is_valid {
+        return None;
+    }
+    let function = sema.to_def(&fn_def)?;
+    let has_self_param = param_list.self_param().is_some();
+
+    let mut edits: FxHashMap<FileId, TextEditBuilder> = FxHashMap::default();
+
+    let new_params = param_list
+        .self_param()
+        .map(|it| it.syntax().to_string())
+        .into_iter()
+        .chain(params.iter().map(|param| match param {
+            SignatureParam::Existing { index } => old_params[*index].syntax().to_string(),
+            SignatureParam::New { pattern, ty, .. } => format!("{}: {}", pattern, ty),
+        }))
+        .sep_by(", ");
+    edits
+        .entry(position.file_id)
+        .or_default()
+        .replace(param_list.syntax().text_range(), format!("({})", new_params));
+
+    let mut call_sites: FxHashMap<FileId, Vec<CallSite>> = FxHashMap::default();
+    for reference in Definition::ModuleDef(function.into()).find_usages(db, None) {
+        let file_id = reference.file_range.file_id;
+        let source_file = sema.parse(file_id);
+        let name_ref: ast::NameRef =
+            match find_node_at_offset(source_file.syntax(), reference.file_range.range.start()) {
+                Some(it) => it,
+                None => continue,
+            };
+        if let Some(site) = call_site(&name_ref, has_self_param) {
+            call_sites.entry(file_id).or_default().push(site);
+        }
+    }
+
+    for (file_id, sites) in call_sites {
+        let builder = edits.entry(file_id).or_default();
+        let file_range = sema.parse(file_id).syntax().text_range();
+        for site in outermost(&sites, file_range) {
+            builder.replace(site.range(), render_args(site, &sites, params));
+        }
+    }
+
+    let mut edits = edits
+        .into_iter()
+        .map(|(file_id, edit)| SourceFileEdit { file_id, edit: edit.finish() })
+        .collect::<Vec<_>>();
+    edits.sort_by_key(|it| it.file_id);
+    Some(SourceChange::source_file_edits("Change signature", edits))
+}
+
+fn call_site(name_ref: &ast::NameRef, has_self_param: bool) -> Option<CallSite> {
+    let parent = name_ref.syntax().parent()?;
+    if let Some(method_call) = ast::MethodCallExpr::cast(parent.clone()) {
+        return Some(CallSite { arg_list: method_call.arg_list()?, has_self_arg: false });
+    }
+    let path = ast::PathSegment::cast(parent)?.syntax().parent().and_then(ast::Path::cast)?;
+    let path_expr = path.syntax().parent().and_then(ast::PathExpr::cast)?;
+    let call = path_expr.syntax().parent().and_then(ast::CallExpr::cast)?;
+    Some(CallSite { arg_list: call.arg_list()?, has_self_arg: has_self_param })
+}
+
+/// Call sites strictly inside of `range`, which aren't nested in another one.
+fn outermost(sites: &[CallSite], range: TextRange) -> impl Iterator<Item = &CallSite> {
+    let is_inside = move |site: &CallSite, range: TextRange| {
+        range != site.range() && range.contains_range(site.range())
+    };
+    sites.iter().filter(move |site| {
+        is_inside(site, range)
+            && !sites.iter().any(|other| is_inside(other, range) && is_inside(site, other.range()))
+    })
+}
+
+fn render_args(site: &CallSite, sites: &[CallSite], params: &[SignatureParam]) -> String {
+    let args = site.arg_list.args().collect::<Vec<_>>();
+    let (self_arg, args) = match args.split_first() {
+        Some((first, rest)) if site.has_self_arg => (Some(first), rest),
+        _ => (None, &args[..]),
+    };
+    let todo = || make::expr_todo().syntax().to_string();
+    let new_args = self_arg
+        .map(|it| render_node(it.syntax(), sites, params))
+        .into_iter()
+        .chain(params.iter().map(|param| match param {
+            SignatureParam::Existing { index } => {
+                args.get(*index).map_or_else(todo, |arg| render_node(arg.syntax(), sites, params))
+            }
+            SignatureParam::New { default, .. } => default.clone().unwrap_or_else(todo),
+        }))
+        .sep_by(", ");
+    format!("({})", new_args)
+}
+
+/// Returns the text of `node`, with the call sites inside of it updated.
+fn render_node(node: &SyntaxNode, sites: &[CallSite], params: &[SignatureParam]) -> String {
+    let range = node.text_range();
+    let mut text = node.text().to_string();
+    let mut inner = outermost(sites, range).collect::<Vec<_>>();
+    inner.sort_by_key(|site| site.range().start());
+    for site in inner.into_iter().rev() {
+        let start = usize::from(site.range().start() - range.start());
+        let end = usize::from(site.range().end() - range.start());
+        text.replace_range(start..end, &render_args(site, sites, params));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use test_utils::assert_eq_text;
+
+    use crate::mock_analysis::{analysis_and_position, single_file_with_position};
+
+    use super::*;
+
+    fn check(ra_fixture: &str, params: Vec<SignatureParam>, expected: &str) {
+        let (analysis, position) = single_file_with_position(ra_fixture);
+        let source_change = analysis.change_signature(position, params).unwrap().unwrap();
+        assert_eq!(source_change.source_file_edits.len(), 1);
+        let edit = &source_change.source_file_edits[0].edit;
+        let actual = edit.apply(&analysis.file_text(position.file_id).unwrap());
+        assert_eq_text!(expected, &actual);
+    }
+
+    fn existing(index: usize) -> SignatureParam {
+        SignatureParam::Existing { index }
+    }
+
+    fn new(pattern: &str, ty: &str, default: Option<&str>) -> SignatureParam {
+        SignatureParam::New {
+            pattern: pattern.to_string(),
+            ty: ty.to_string(),
+            default: default.map(|it| it.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_reorder_params() {
+        check(
+            r#"
+fn foo<|>(a: u32, b: &str) {}
+
+fn main() {
+    foo(1, "x");
+    foo(2, "y")
+}
+"#,
+            vec![existing(1), existing(0)],
+            r#"
+fn foo(b: &str, a: u32) {}
+
+fn main() {
+    foo("x", 1);
+    foo("y", 2)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_add_and_remove_params() {
+        check(
+            r#"
+fn foo<|>(a: u32, b: u32) -> u32 { a }
+
+fn main() {
+    foo(foo(1, 2), 3);
+}
+"#,
+            vec![existing(0), new("c", "bool", Some("false")), new("d", "u8", None)],
+            r#"
+fn foo(a: u32, c: bool, d: u8) -> u32 { a }
+
+fn main() {
+    foo(foo(1, false, todo!()), false, todo!());
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_method_call_sites() {
+        check(
+            r#"
+struct S;
+impl S {
+    fn foo<|>(&self, a: u32) {}
+}
+
+fn main() {
+    S.foo(1);
+    S::foo(&S, 2);
+}
+"#,
+            vec![new("b", "u32", Some("0")), existing(0)],
+            r#"
+struct S;
+impl S {
+    fn foo(&self, b: u32, a: u32) {}
+}
+
+fn main() {
+    S.foo(0, 1);
+    S::foo(&S, 0, 2);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_call_sites_in_other_files() {
+        let (analysis, position) = analysis_and_position(
+            r#"
+//- /lib.rs
+mod foo;
+pub fn bar<|>(a: u32) {}
+//- /foo.rs
+fn f() {
+    crate::bar(92);
+}
+"#,
+        );
+        let source_change = analysis.change_signature(position, vec![]).unwrap().unwrap();
+        assert_eq!(source_change.source_file_edits.len(), 2);
+        let edit = &source_change.source_file_edits[1];
+        let actual = edit.edit.apply(&analysis.file_text(edit.file_id).unwrap());
+        assert_eq_text!("fn f() {\n    crate::bar();\n}\n", &actual);
+    }
+
+    #[test]
+    fn test_invalid_param_index() {
+        let (analysis, position) = single_file_with_position("fn foo<|>(a: u32) {}");
+        assert!(analysis.change_signature(position, vec![existing(1)]).unwrap().is_none());
+    }
+}
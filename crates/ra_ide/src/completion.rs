@@ -4,8 +4,10 @@ mod completion_config;
 mod completion_item;
 mod completion_context;
 mod presentation;
+mod fuzzy;
 
 mod complete_attribute;
+mod complete_doc_link;
 mod complete_dot;
 mod complete_record;
 mod complete_pattern;
@@ -15,7 +17,11 @@ mod complete_snippet;
 mod complete_qualified_path;
 mod complete_unqualified_path;
 mod complete_postfix;
+mod complete_flyimport;
+mod complete_format_string;
+mod complete_lifetime;
 mod complete_macro_in_item_position;
+mod complete_mod;
 mod complete_trait_impl;
 #[cfg(test)]
 mod test_utils;
@@ -31,7 +37,7 @@ use crate::{
 };
 
 pub use crate::completion::{
-    completion_config::CompletionConfig,
+    completion_config::{CompletionConfig, Snippet, SnippetScope},
     completion_item::{CompletionItem, CompletionItemKind, CompletionScore, InsertTextFormat},
 };
 
@@ -55,8 +61,9 @@ pub use crate::completion::{
 /// ```
 ///
 /// `foo` *should* be present among the completion variants. Filtering by
-/// identifier prefix/fuzzy match should be done higher in the stack, together
-/// with ordering of completions (currently this is done by the client).
+/// identifier prefix/fuzzy match should be done higher in the stack (currently
+/// this is done by the client). The items only record how well they fuzzy match
+/// the identifier, which the client can use to order them.
 pub(crate) fn completions(
     db: &RootDatabase,
     position: FilePosition,
@@ -74,11 +81,22 @@ pub(crate) fn completions(
     complete_qualified_path::complete_qualified_path(&mut acc, &ctx);
     complete_unqualified_path::complete_unqualified_path(&mut acc, &ctx);
     complete_dot::complete_dot(&mut acc, &ctx);
+    complete_doc_link::complete_doc_link(&mut acc, &ctx);
     complete_record::complete_record(&mut acc, &ctx);
     complete_pattern::complete_pattern(&mut acc, &ctx);
     complete_postfix::complete_postfix(&mut acc, &ctx);
+    complete_flyimport::complete_flyimport(&mut acc, &ctx);
+    complete_format_string::complete_format_string(&mut acc, &ctx);
+    complete_lifetime::complete_lifetime(&mut acc, &ctx);
     complete_macro_in_item_position::complete_macro_in_item_position(&mut acc, &ctx);
+    complete_mod::complete_mod(&mut acc, &ctx);
     complete_trait_impl::complete_trait_impl(&mut acc, &ctx);
 
+    let source_range = ctx.source_range();
+    if !source_range.is_empty() {
+        let typed_len = ctx.offset.min(source_range.end()) - source_range.start();
+        acc.score_matches(&ctx.original_token.text().as_str()[..usize::from(typed_len)]);
+    }
+
     Some(acc)
 }
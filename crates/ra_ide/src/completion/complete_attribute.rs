@@ -1,7 +1,8 @@
 //! Completion for attributes
 //!
 //! This module uses a bit of static metadata to provide completions
-//! for built-in attributes.
+//! for built-in attributes. Attribute and derive macros are completed from the
+//! current scope and from the roots of the dependencies.
 
 use ra_syntax::{ast, AstNode, SyntaxKind};
 use rustc_hash::FxHashSet;
@@ -45,6 +46,13 @@ fn complete_attribute_start(acc: &mut Completions, ctx: &CompletionContext, attr
             acc.add(item);
         }
     }
+
+    for attr_macro_name in macro_names(ctx, hir::MacroDef::is_attr_macro) {
+        acc.add(
+            CompletionItem::new(CompletionKind::Attribute, ctx.source_range(), attr_macro_name)
+                .kind(CompletionItemKind::Attribute),
+        );
+    }
 }
 
 struct AttrCompletion {
@@ -151,7 +159,12 @@ fn complete_derive(acc: &mut Completions, ctx: &CompletionContext, derive_input:
             );
         }
 
-        for custom_derive_name in get_derive_names_in_scope(ctx).difference(&existing_derives) {
+        // The built-in derives are already completed along with their dependencies.
+        let custom_derive_names = macro_names(ctx, hir::MacroDef::is_derive_macro)
+            .into_iter()
+            .filter(|name| !existing_derives.contains(name))
+            .filter(|name| DEFAULT_DERIVE_COMPLETIONS.iter().all(|it| it.label != name.as_str()));
+        for custom_derive_name in custom_derive_names {
             acc.add(
                 CompletionItem::new(
                     CompletionKind::Attribute,
@@ -199,15 +212,38 @@ fn parse_derive_input(derive_input: ast::TokenTree) -> Result<FxHashSet<String>,
     }
 }
 
-fn get_derive_names_in_scope(ctx: &CompletionContext) -> FxHashSet<String> {
-    let mut result = FxHashSet::default();
+/// The names of the macros accepted by `filter` which are in scope, and the
+/// paths of the ones which are only exported by a dependency, like
+/// `serde::Serialize`.
+fn macro_names(ctx: &CompletionContext, filter: fn(&hir::MacroDef) -> bool) -> Vec<String> {
+    let mut in_scope = FxHashSet::default();
+    let mut result = Vec::new();
     ctx.scope().process_all_names(&mut |name, scope_def| {
         if let hir::ScopeDef::MacroDef(mac) = scope_def {
-            if mac.is_derive_macro() {
-                result.insert(name.to_string());
+            if filter(&mac) && in_scope.insert(mac) {
+                result.push(name.to_string());
             }
         }
     });
+
+    let krate = match ctx.krate {
+        Some(it) => it,
+        None => return result,
+    };
+    let module = ctx.scope().module();
+    for dep in krate.dependencies(ctx.db) {
+        let root = match dep.krate.root_module(ctx.db) {
+            Some(it) => it,
+            None => continue,
+        };
+        for (name, scope_def) in root.scope(ctx.db, module) {
+            if let hir::ScopeDef::MacroDef(mac) = scope_def {
+                if filter(&mac) && in_scope.insert(mac) {
+                    result.push(format!("{}::{}", dep.name, name));
+                }
+            }
+        }
+    }
     result
 }
 
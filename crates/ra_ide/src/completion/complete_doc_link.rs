@@ -0,0 +1,156 @@
+//! Completion of intra-doc links, e.g. `/// See [foo::Ba<|>]`.
+//!
+//! The path typed after `[` or `](` is resolved segment by segment from the
+//! scope of the documented item, and the items of the last module are
+//! completed.
+
+use hir::{Module, ScopeDef};
+use ra_syntax::{SyntaxKind::COMMENT, TextRange, TextSize};
+
+use crate::completion::{
+    complete_flyimport::completion_item_kind, CompletionContext, CompletionItem,
+    CompletionItemKind, CompletionKind, Completions,
+};
+
+pub(super) fn complete_doc_link(acc: &mut Completions, ctx: &CompletionContext) {
+    let comment = &ctx.original_token;
+    if comment.kind() != COMMENT {
+        return;
+    }
+    let text = comment.text();
+    if !(text.starts_with("///") || text.starts_with("//!")) {
+        return;
+    }
+    let before_cursor = &text[..usize::from(ctx.offset - comment.text_range().start())];
+    let typed = match link_path_prefix(before_cursor) {
+        Some(it) => it,
+        None => return,
+    };
+    let (qualifier, name_prefix) = match typed.rfind("::") {
+        Some(idx) => (Some(&typed[..idx]), &typed[idx + 2..]),
+        None => (None, typed),
+    };
+    let range = TextRange::new(ctx.offset - TextSize::of(name_prefix), ctx.offset);
+
+    let scope = ctx.scope();
+    let current_module = scope.module();
+    let mut add = |name: String, def: ScopeDef| {
+        let kind = match def {
+            ScopeDef::ModuleDef(hir::ModuleDef::BuiltinType(_)) => return,
+            ScopeDef::ModuleDef(def) => completion_item_kind(def),
+            ScopeDef::MacroDef(_) => CompletionItemKind::Macro,
+            _ => return,
+        };
+        CompletionItem::new(CompletionKind::Reference, range, name).kind(kind).add_to(acc);
+    };
+    match qualifier {
+        None => scope.process_all_names(&mut |name, def| add(name.to_string(), def)),
+        Some(qualifier) => {
+            let module = match resolve_module(ctx, current_module, qualifier) {
+                Some(it) => it,
+                None => return,
+            };
+            for (name, def) in module.scope(ctx.db, current_module) {
+                add(name.to_string(), def);
+            }
+        }
+    }
+}
+
+/// The path typed so far in an unfinished `[path` or `[text](path` link.
+fn link_path_prefix(text: &str) -> Option<&str> {
+    let open = text.rfind(|c| c == '[' || c == '(')?;
+    if text[open..].starts_with('(') && !text[..open].ends_with(']') {
+        return None;
+    }
+    let typed = text[open + 1..].trim_start_matches('`');
+    if typed.chars().all(|c| c.is_alphanumeric() || c == '_' || c == ':') {
+        Some(typed)
+    } else {
+        None
+    }
+}
+
+fn resolve_module(ctx: &CompletionContext, from: Option<Module>, path: &str) -> Option<Module> {
+    let mut segments = path.split("::");
+    let mut module = match segments.next()? {
+        "crate" => from?.krate().root_module(ctx.db)?,
+        "self" => from?,
+        "super" => from?.parent(ctx.db)?,
+        first => {
+            let mut res = None;
+            ctx.scope().process_all_names(&mut |name, def| {
+                if let ScopeDef::ModuleDef(hir::ModuleDef::Module(module)) = def {
+                    if name.to_string() == first {
+                        res = Some(module);
+                    }
+                }
+            });
+            res?
+        }
+    };
+    for segment in segments {
+        module = module.scope(ctx.db, from).into_iter().find_map(|(name, def)| match def {
+            ScopeDef::ModuleDef(hir::ModuleDef::Module(module)) if name.to_string() == segment => {
+                Some(module)
+            }
+            _ => None,
+        })?;
+    }
+    Some(module)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::completion::{test_utils::do_completion, CompletionKind};
+
+    fn doc_link_labels(code: &str) -> Vec<String> {
+        do_completion(code, CompletionKind::Reference)
+            .into_iter()
+            .map(|it| it.label().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn completes_names_in_scope() {
+        let labels = doc_link_labels(
+            r"
+            mod foo {}
+            struct Bar;
+            /// See [Ba<|>]
+            fn main() {}
+            ",
+        );
+        assert_eq!(labels, vec!["Bar", "foo", "main"]);
+    }
+
+    #[test]
+    fn completes_module_items() {
+        let labels = doc_link_labels(
+            r"
+            mod foo {
+                pub mod bar {
+                    pub struct Baz;
+                    pub fn quux() {}
+                    fn private() {}
+                }
+            }
+            /// See [the struct](crate::foo::bar::<|>)
+            fn main() {}
+            ",
+        );
+        assert_eq!(labels, vec!["Baz", "quux"]);
+    }
+
+    #[test]
+    fn does_not_complete_outside_of_links() {
+        for code in &[
+            "struct Bar;\n/// See Ba<|>\nfn main() {}",
+            "struct Bar;\n/// See [Bar] and (Ba<|>\nfn main() {}",
+            "struct Bar;\n// See [Ba<|>\nfn main() {}",
+        ] {
+            let labels = doc_link_labels(code);
+            assert!(labels.is_empty(), "{:?}", labels);
+        }
+    }
+}
@@ -5,7 +5,7 @@ use hir::{HasVisibility, Type};
 use crate::{
     completion::{
         completion_context::CompletionContext,
-        completion_item::{CompletionKind, Completions},
+        completion_item::{CompletionKind, CompletionScore, Completions},
     },
     CompletionItem,
 };
@@ -28,12 +28,32 @@ pub(super) fn complete_dot(acc: &mut Completions, ctx: &CompletionContext) {
     }
     complete_methods(acc, ctx, &receiver_ty);
 
-    // Suggest .await syntax for types that implement Future trait
+    // Suggest .await syntax for types that implement Future trait, ranked
+    // above the methods as awaiting is what is usually wanted.
     if receiver_ty.impls_future(ctx.db) {
         CompletionItem::new(CompletionKind::Keyword, ctx.source_range(), "await")
             .detail("expr.await")
             .insert_text("await")
+            .set_score(CompletionScore::TypeMatch)
             .add_to(acc);
+        if in_result_returning_fn(ctx) {
+            CompletionItem::new(CompletionKind::Keyword, ctx.source_range(), "await?")
+                .detail("expr.await?")
+                .insert_text("await?")
+                .set_score(CompletionScore::TypeMatch)
+                .add_to(acc);
+        }
+    }
+}
+
+fn in_result_returning_fn(ctx: &CompletionContext) -> bool {
+    let fn_def = match ctx.function_syntax.as_ref().and_then(|it| ctx.sema.to_def(it)) {
+        Some(it) => it,
+        None => return false,
+    };
+    match fn_def.ret_type(ctx.db).as_adt() {
+        Some(adt) => adt.name(ctx.db).to_string() == "Result",
+        None => false,
     }
 }
 
@@ -317,6 +337,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_method_completion_where_clause_not_in_scope() {
+        assert_debug_snapshot!(
+            do_ref_completion(
+                r"
+            mod m {
+                pub trait Trait {
+                    fn the_method(&self) {}
+                }
+            }
+            fn foo<T>(t: T) where T: m::Trait {
+               t.<|>
+            }
+            ",
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "the_method()",
+                source_range: 196..196,
+                delete: 196..196,
+                insert: "the_method()$0",
+                kind: Method,
+                lookup: "the_method",
+                detail: "fn the_method(&self)",
+            },
+        ]
+        "###
+        );
+    }
+
     #[test]
     fn test_method_completion_only_fitting_impls() {
         assert_debug_snapshot!(
@@ -648,12 +699,155 @@ mod tests {
                 delete: 74..74,
                 insert: "await",
                 detail: "expr.await",
+                score: TypeMatch,
+            },
+        ]
+        "###
+        )
+    }
+
+    #[test]
+    fn test_completion_await_question_mark_in_result_fn() {
+        assert_debug_snapshot!(
+        do_completion(
+            r###"
+            //- /main.rs
+            use std::future::*;
+            struct A {}
+            impl Future for A {}
+            enum Result<T, E> { Ok(T), Err(E) }
+            fn foo(a: A) -> Result<(), ()> {
+                a.<|>
+            }
+
+            //- /std/lib.rs
+            pub mod future {
+                #[lang = "future_trait"]
+                pub trait Future {}
+            }
+            "###, CompletionKind::Keyword),
+        @r###"
+        [
+            CompletionItem {
+                label: "await",
+                source_range: 128..128,
+                delete: 128..128,
+                insert: "await",
+                detail: "expr.await",
+                score: TypeMatch,
+            },
+            CompletionItem {
+                label: "await?",
+                source_range: 128..128,
+                delete: 128..128,
+                insert: "await?",
+                detail: "expr.await?",
+                score: TypeMatch,
             },
         ]
         "###
         )
     }
 
+    #[test]
+    fn test_method_completion_through_deref_chain() {
+        assert_debug_snapshot!(
+        do_ref_completion(
+            r###"
+            //- /main.rs
+            use std::ops::Deref;
+            struct Inner;
+            impl Inner {
+                fn inherent(&self) {}
+                fn inherent_mut(&mut self) {}
+            }
+            trait Tr {
+                fn trait_method(&self) {}
+            }
+            impl Tr for Inner {}
+            struct Smart(Inner);
+            impl Deref for Smart {
+                type Target = Inner;
+                fn deref(&self) -> &Inner { &self.0 }
+            }
+            struct Outer(Smart);
+            impl Deref for Outer {
+                type Target = Smart;
+                fn deref(&self) -> &Smart { &self.0 }
+            }
+            fn foo(o: Outer) {
+                o.<|>
+            }
+
+            //- /std/lib.rs
+            pub mod ops {
+                #[lang = "deref"]
+                pub trait Deref {
+                    type Target;
+                    fn deref(&self) -> &Self::Target;
+                }
+            }
+            "###,
+        ),
+        @r###"
+        [
+            CompletionItem {
+                label: "0",
+                source_range: 425..425,
+                delete: 425..425,
+                insert: "0",
+                kind: Field,
+                detail: "Smart",
+            },
+            CompletionItem {
+                label: "0",
+                source_range: 425..425,
+                delete: 425..425,
+                insert: "0",
+                kind: Field,
+                detail: "Inner",
+            },
+            CompletionItem {
+                label: "deref()",
+                source_range: 425..425,
+                delete: 425..425,
+                insert: "deref()$0",
+                kind: Method,
+                lookup: "deref",
+                detail: "fn deref(&self) -> &Self::Target",
+            },
+            CompletionItem {
+                label: "inherent()",
+                source_range: 425..425,
+                delete: 425..425,
+                insert: "inherent()$0",
+                kind: Method,
+                lookup: "inherent",
+                detail: "fn inherent(&self)",
+            },
+            CompletionItem {
+                label: "inherent_mut()",
+                source_range: 425..425,
+                delete: 425..425,
+                insert: "inherent_mut()$0",
+                kind: Method,
+                lookup: "inherent_mut",
+                detail: "fn inherent_mut(&mut self)",
+            },
+            CompletionItem {
+                label: "trait_method()",
+                source_range: 425..425,
+                delete: 425..425,
+                insert: "trait_method()$0",
+                kind: Method,
+                lookup: "trait_method",
+                detail: "fn trait_method(&self)",
+            },
+        ]
+        "###
+        );
+    }
+
     #[test]
     fn test_super_super_completion() {
         assert_debug_snapshot!(
@@ -749,7 +943,6 @@ mod tests {
 
     #[test]
     fn works_in_simple_macro_2() {
-        // this doesn't work yet because the macro doesn't expand without the token -- maybe it can be fixed with better recovery
         assert_debug_snapshot!(
             do_ref_completion(
                 r"
@@ -775,6 +968,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn completes_methods_in_simple_macro() {
+        assert_debug_snapshot!(
+            do_ref_completion(
+                r"
+                macro_rules! m { ($e:expr) => { $e } }
+                struct A;
+                impl A { fn the_method(&self) {} }
+                fn foo(a: A) {
+                    m!(a.<|>)
+                }
+                ",
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "the_method()",
+                source_range: 189..189,
+                delete: 189..189,
+                insert: "the_method()$0",
+                kind: Method,
+                lookup: "the_method",
+                detail: "fn the_method(&self)",
+            },
+        ]
+        "###
+        );
+    }
+
     #[test]
     fn works_in_simple_macro_recursive_1() {
         assert_debug_snapshot!(
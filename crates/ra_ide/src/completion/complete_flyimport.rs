@@ -0,0 +1,226 @@
+//! Completion of items which are not in scope yet, e.g. `HashMap` without a
+//! `use std::collections::HashMap;`.
+//!
+//! The candidates are looked up in the workspace symbol index by the text
+//! typed so far. Accepting such a completion also inserts the `use` of the
+//! item, which the client receives as an additional text edit.
+
+use either::Either;
+use hir::{Adt, ModuleDef};
+use ra_ide_db::imports_locator::ImportsLocator;
+use ra_syntax::SyntaxKind::IDENT;
+use ra_text_edit::TextEditBuilder;
+use rustc_hash::FxHashSet;
+
+use crate::completion::{
+    CompletionContext, CompletionItem, CompletionItemKind, CompletionKind, Completions,
+};
+
+pub(super) fn complete_flyimport(acc: &mut Completions, ctx: &CompletionContext) {
+    if !ctx.config.enable_autoimport_completions || !ctx.is_trivial_path {
+        return;
+    }
+    if ctx.use_item_syntax.is_some()
+        || ctx.is_new_item
+        || ctx.record_lit_syntax.is_some()
+        || ctx.record_pat_syntax.is_some()
+        || ctx.attribute_under_caret.is_some()
+    {
+        return;
+    }
+    // Without anything typed, the whole index would match.
+    if ctx.original_token.kind() != IDENT {
+        return;
+    }
+    let module = match ctx.scope().module() {
+        Some(it) => it,
+        None => return,
+    };
+
+    let mut names_in_scope = FxHashSet::default();
+    ctx.scope().process_all_names(&mut |name, _| {
+        names_in_scope.insert(name.to_string());
+    });
+
+    let potential_import_name = ctx.original_token.text().to_string();
+    let candidates = ImportsLocator::new(ctx.db).find_similar_imports(&potential_import_name, 40);
+    let mut seen = FxHashSet::default();
+    for candidate in candidates {
+        let (name, path, kind) = match candidate {
+            Either::Left(def) => {
+                (def.name(ctx.db), module.find_use_path(ctx.db, def), completion_item_kind(def))
+            }
+            Either::Right(mac) => {
+                (mac.name(ctx.db), module.find_use_path(ctx.db, mac), CompletionItemKind::Macro)
+            }
+        };
+        let (name, path) = match (name, path) {
+            (Some(name), Some(path)) => (name.to_string(), path),
+            _ => continue,
+        };
+        // Single segment paths are already in scope.
+        if path.segments.len() < 2 || names_in_scope.contains(&name) {
+            continue;
+        }
+        if !seen.insert(path.to_string()) {
+            continue;
+        }
+
+        let label = match kind {
+            CompletionItemKind::Macro => format!("{}!", name),
+            _ => name,
+        };
+        let mut builder = TextEditBuilder::default();
+        builder.replace(ctx.source_range(), label.clone());
+        let use_edit = ra_assists::insert_use(&ctx.sema, &ctx.original_token.parent(), &path);
+        for atom in use_edit.as_atoms() {
+            builder.replace(atom.delete, atom.insert.clone());
+        }
+        CompletionItem::new(CompletionKind::Magic, ctx.source_range(), label)
+            .kind(kind)
+            .detail(format!("use {}", path))
+            .text_edit(builder.finish())
+            .add_to(acc);
+    }
+}
+
+pub(super) fn completion_item_kind(def: ModuleDef) -> CompletionItemKind {
+    match def {
+        ModuleDef::Module(_) => CompletionItemKind::Module,
+        ModuleDef::Function(_) => CompletionItemKind::Function,
+        ModuleDef::Adt(Adt::Enum(_)) => CompletionItemKind::Enum,
+        ModuleDef::Adt(_) => CompletionItemKind::Struct,
+        ModuleDef::EnumVariant(_) => CompletionItemKind::EnumVariant,
+        ModuleDef::Const(_) => CompletionItemKind::Const,
+        ModuleDef::Static(_) => CompletionItemKind::Static,
+        ModuleDef::Trait(_) => CompletionItemKind::Trait,
+        ModuleDef::TypeAlias(_) => CompletionItemKind::TypeAlias,
+        ModuleDef::BuiltinType(_) => CompletionItemKind::BuiltinType,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_utils::{assert_eq_text, extract_offset};
+
+    use crate::completion::{
+        test_utils::{do_completion, do_completion_with_options},
+        CompletionConfig, CompletionItem, CompletionKind,
+    };
+
+    fn do_flyimport_completion(code: &str) -> Vec<CompletionItem> {
+        do_completion(code, CompletionKind::Magic)
+    }
+
+    fn check_edit(label: &str, before: &str, after: &str) {
+        let completions = do_flyimport_completion(before);
+        let item = completions
+            .iter()
+            .find(|it| it.label() == label)
+            .unwrap_or_else(|| panic!("no `{}` completion in {:#?}", label, completions));
+        let (_, text) = extract_offset(before);
+        let actual = item.text_edit().apply(&text);
+        assert_eq_text!(after, &actual);
+    }
+
+    #[test]
+    fn imports_struct_from_module() {
+        check_edit(
+            "Bar",
+            r"
+mod foo {
+    pub struct Bar;
+}
+
+fn main() {
+    Ba<|>
+}
+",
+            r"
+use foo::Bar;
+
+mod foo {
+    pub struct Bar;
+}
+
+fn main() {
+    Bar
+}
+",
+        );
+    }
+
+    #[test]
+    fn tags_item_with_its_path() {
+        let completions = do_flyimport_completion(
+            r"
+mod foo {
+    pub mod bar {
+        pub fn frobnicate() {}
+    }
+}
+
+fn main() {
+    frob<|>
+}
+",
+        );
+        assert_eq!(completions.len(), 1);
+        assert_eq!(completions[0].label(), "frobnicate");
+        assert_eq!(completions[0].detail(), Some("use foo::bar::frobnicate"));
+    }
+
+    #[test]
+    fn does_not_complete_items_in_scope() {
+        let completions = do_flyimport_completion(
+            r"
+mod foo {
+    pub struct Bar;
+}
+use foo::Bar;
+
+fn main() {
+    Ba<|>
+}
+",
+        );
+        assert!(completions.is_empty(), "{:#?}", completions);
+    }
+
+    #[test]
+    fn does_not_complete_without_prefix() {
+        let completions = do_flyimport_completion(
+            r"
+mod foo {
+    pub struct Bar;
+}
+
+fn main() {
+    <|>
+}
+",
+        );
+        assert!(completions.is_empty(), "{:#?}", completions);
+    }
+
+    #[test]
+    fn respects_config() {
+        let completions = do_completion_with_options(
+            r"
+mod foo {
+    pub struct Bar;
+}
+
+fn main() {
+    Ba<|>
+}
+",
+            CompletionKind::Magic,
+            &CompletionConfig {
+                enable_autoimport_completions: false,
+                ..CompletionConfig::default()
+            },
+        );
+        assert!(completions.is_empty(), "{:#?}", completions);
+    }
+}
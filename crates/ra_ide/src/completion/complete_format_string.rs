@@ -0,0 +1,206 @@
+//! Completion inside the format string of `format!` and friends, e.g.
+//! `format!("{<|>}")`.
+//!
+//! Between braces, the local variables are completed, as they can be captured
+//! by inline format arguments. After a `:`, the debug formatting specs are
+//! completed.
+
+use hir::ScopeDef;
+use ra_syntax::{
+    ast::{self, AstNode},
+    SyntaxKind::STRING,
+    SyntaxToken, TextRange, TextSize,
+};
+
+use crate::completion::{
+    CompletionContext, CompletionItem, CompletionItemKind, CompletionKind, Completions,
+};
+
+const FORMAT_MACROS: &[&str] = &[
+    "format",
+    "format_args",
+    "print",
+    "println",
+    "eprint",
+    "eprintln",
+    "panic",
+    "write",
+    "writeln",
+];
+
+pub(super) fn complete_format_string(acc: &mut Completions, ctx: &CompletionContext) {
+    let string = &ctx.original_token;
+    if string.kind() != STRING || !is_format_string(string) {
+        return;
+    }
+    let string_start = string.text_range().start();
+    let before_cursor = &string.text()[..usize::from(ctx.offset - string_start)];
+    let placeholder_start = match open_placeholder(before_cursor) {
+        Some(it) => it,
+        None => return,
+    };
+    let placeholder = &before_cursor[placeholder_start..];
+
+    match placeholder.find(':') {
+        Some(colon) => {
+            let spec = &placeholder[colon + 1..];
+            if !spec.is_empty() && spec != "#" {
+                return;
+            }
+            let range = TextRange::new(ctx.offset - TextSize::of(spec), ctx.offset);
+            for (label, detail) in &[("?", "Debug"), ("#?", "Debug, pretty-printed")] {
+                CompletionItem::new(CompletionKind::Magic, range, *label)
+                    .detail(*detail)
+                    .add_to(acc);
+            }
+        }
+        None => {
+            if !placeholder.chars().all(|c| c.is_alphanumeric() || c == '_')
+                || placeholder.starts_with(|c: char| c.is_ascii_digit())
+            {
+                return;
+            }
+            let range = TextRange::new(ctx.offset - TextSize::of(placeholder), ctx.offset);
+            let scope = ctx.sema.scope_at_offset(&string.parent(), ctx.offset);
+            scope.process_all_names(&mut |name, res| {
+                if let ScopeDef::Local(local) = res {
+                    let mut item =
+                        CompletionItem::new(CompletionKind::Reference, range, name.to_string())
+                            .kind(CompletionItemKind::Binding);
+                    let ty = local.ty(ctx.db);
+                    if !ty.is_unknown() {
+                        item = item.detail(ty.display(ctx.db).to_string());
+                    }
+                    item.add_to(acc);
+                }
+            });
+        }
+    }
+}
+
+/// Whether `string` is the first string literal passed to a formatting macro.
+fn is_format_string(string: &SyntaxToken) -> bool {
+    let macro_call = match string.parent().ancestors().find_map(ast::MacroCall::cast) {
+        Some(it) => it,
+        None => return false,
+    };
+    let is_format_macro = macro_call
+        .path()
+        .and_then(|path| path.segment())
+        .and_then(|segment| segment.name_ref())
+        .map_or(false, |name_ref| FORMAT_MACROS.contains(&name_ref.text().as_str()));
+    if !is_format_macro {
+        return false;
+    }
+    let first_string = macro_call
+        .token_tree()
+        .and_then(|tt| tt.syntax().descendants_with_tokens().find(|it| it.kind() == STRING));
+    first_string.and_then(|it| it.into_token()).as_ref() == Some(string)
+}
+
+/// The offset just after the `{` of the placeholder the end of `text` is in,
+/// skipping the `{{` and `}}` escapes.
+fn open_placeholder(text: &str) -> Option<usize> {
+    let mut open = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '{' if open.is_none() => {
+                if chars.peek().map(|&(_, next)| next) == Some('{') {
+                    chars.next();
+                } else {
+                    open = Some(idx + 1);
+                }
+            }
+            '}' => open = None,
+            _ => {}
+        }
+    }
+    open
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_debug_snapshot;
+
+    use crate::completion::{test_utils::do_completion, CompletionItem, CompletionKind};
+
+    fn do_reference_completion(code: &str) -> Vec<CompletionItem> {
+        do_completion(code, CompletionKind::Reference)
+    }
+
+    fn do_magic_completion(code: &str) -> Vec<CompletionItem> {
+        do_completion(code, CompletionKind::Magic)
+    }
+
+    #[test]
+    fn completes_locals_in_placeholder() {
+        assert_debug_snapshot!(
+            do_reference_completion(
+                r#"
+                macro_rules! format { ($($arg:tt)*) => {} }
+                fn main() {
+                    let foo = 92;
+                    format!("{f<|>}");
+                }
+                "#
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "foo",
+                source_range: 153..154,
+                delete: 153..154,
+                insert: "foo",
+                kind: Binding,
+                detail: "i32",
+            },
+        ]
+        "###
+        );
+    }
+
+    #[test]
+    fn completes_debug_specs() {
+        let completions = do_magic_completion(
+            r#"
+            macro_rules! println { ($($arg:tt)*) => {} }
+            fn main() {
+                let foo = 92;
+                println!("{foo:<|>}");
+            }
+            "#,
+        );
+        let labels: Vec<_> = completions.iter().map(|it| it.label()).collect();
+        assert_eq!(labels, vec!["#?", "?"]);
+    }
+
+    #[test]
+    fn does_not_complete_outside_of_placeholders() {
+        for code in &[
+            r#"
+            macro_rules! format { ($($arg:tt)*) => {} }
+            fn main() {
+                let foo = 92;
+                format!("{{f<|>}}");
+            }
+            "#,
+            r#"
+            macro_rules! format { ($($arg:tt)*) => {} }
+            fn main() {
+                let foo = 92;
+                format!("{}", "{f<|>}");
+            }
+            "#,
+            r#"
+            fn main() {
+                let foo = 92;
+                let s = "{f<|>}";
+            }
+            "#,
+        ] {
+            let completions = do_reference_completion(code);
+            assert!(completions.is_empty(), "{:#?}", completions);
+        }
+    }
+}
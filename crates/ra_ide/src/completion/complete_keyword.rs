@@ -28,7 +28,8 @@ pub(super) fn complete_use_tree_keyword(acc: &mut Completions, ctx: &CompletionC
                 .insert_text("super::")
                 .add_to(acc);
         }
-        (Some(_), Some(_)) => {
+        // `self` and `super` can't follow a leading `::`.
+        (Some(_), Some(prefix)) if !is_bare_coloncolon(prefix) => {
             CompletionItem::new(CompletionKind::Keyword, source_range, "self")
                 .kind(CompletionItemKind::Keyword)
                 .add_to(acc);
@@ -41,6 +42,10 @@ pub(super) fn complete_use_tree_keyword(acc: &mut Completions, ctx: &CompletionC
     }
 }
 
+fn is_bare_coloncolon(path: &hir::Path) -> bool {
+    *path.kind() == hir::PathKind::Abs && path.segments().is_empty()
+}
+
 fn keyword(ctx: &CompletionContext, kw: &str, snippet: &str) -> CompletionItem {
     let res = CompletionItem::new(CompletionKind::Keyword, ctx.source_range(), kw)
         .kind(CompletionItemKind::Keyword);
@@ -214,6 +219,15 @@ mod tests {
         ]
         "###
         );
+
+        assert_debug_snapshot!(
+            do_keyword_completion(
+                r"
+                use ::<|>
+                ",
+            ),
+            @r###"[]"###
+        );
     }
 
     #[test]
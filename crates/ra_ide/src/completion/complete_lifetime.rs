@@ -0,0 +1,163 @@
+//! Completion of lifetimes, e.g. `&'<|>`, and of loop labels, e.g.
+//! `break '<|>`.
+
+use ra_syntax::{
+    ast::{self, AstNode, LoopBodyOwner},
+    match_ast, SyntaxKind, SyntaxNode, TextRange,
+};
+use rustc_hash::FxHashSet;
+
+use crate::completion::{
+    CompletionContext, CompletionItem, CompletionItemKind, CompletionKind, Completions,
+};
+
+pub(super) fn complete_lifetime(acc: &mut Completions, ctx: &CompletionContext) {
+    let lifetime = match &ctx.lifetime_under_caret {
+        Some(it) => it,
+        None => return,
+    };
+    let parent = lifetime.parent();
+    let range = TextRange::new(lifetime.text_range().start(), ctx.offset);
+    let mut seen = FxHashSet::default();
+    match parent.kind() {
+        SyntaxKind::BREAK_EXPR | SyntaxKind::CONTINUE_EXPR => {
+            let is_break = parent.kind() == SyntaxKind::BREAK_EXPR;
+            for label in enclosing_labels(&parent, is_break) {
+                if seen.insert(label.clone()) {
+                    CompletionItem::new(CompletionKind::Reference, range, label).add_to(acc);
+                }
+            }
+        }
+        // Declarations of new lifetimes and labels.
+        SyntaxKind::LIFETIME_PARAM | SyntaxKind::LABEL => {}
+        _ => {
+            let lifetimes = enclosing_lifetime_params(&parent)
+                .into_iter()
+                .chain(std::iter::once("'static".to_string()));
+            for lifetime in lifetimes {
+                if seen.insert(lifetime.clone()) {
+                    CompletionItem::new(CompletionKind::Reference, range, lifetime)
+                        .kind(CompletionItemKind::TypeParam)
+                        .add_to(acc);
+                }
+            }
+        }
+    }
+}
+
+/// The lifetime parameters of the items around `node`, innermost first.
+fn enclosing_lifetime_params(node: &SyntaxNode) -> Vec<String> {
+    node.ancestors()
+        .flat_map(|ancestor| ancestor.children().filter_map(ast::TypeParamList::cast))
+        .flat_map(|params| params.lifetime_params())
+        .filter_map(|param| param.lifetime_token())
+        .map(|token| token.text().to_string())
+        .collect()
+}
+
+/// The labels of the loops around `node`, innermost first. `break` can also
+/// leave labeled blocks.
+fn enclosing_labels(node: &SyntaxNode, is_break: bool) -> Vec<String> {
+    let mut res = Vec::new();
+    for ancestor in node.ancestors() {
+        let label = match_ast! {
+            match ancestor {
+                ast::LoopExpr(it) => it.label(),
+                ast::WhileExpr(it) => it.label(),
+                ast::ForExpr(it) => it.label(),
+                ast::EffectExpr(it) => if is_break { it.label() } else { None },
+                // Labels can't be used across function and closure boundaries.
+                ast::FnDef(_it) => break,
+                ast::LambdaExpr(_it) => break,
+                _ => None,
+            }
+        };
+        if let Some(token) = label.and_then(|it| it.lifetime_token()) {
+            res.push(token.text().to_string());
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::completion::{test_utils::do_completion, CompletionKind};
+
+    fn lifetime_labels(code: &str) -> Vec<String> {
+        do_completion(code, CompletionKind::Reference)
+            .into_iter()
+            .map(|it| it.label().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn completes_lifetime_params() {
+        let labels = lifetime_labels(
+            r"
+            struct S<'s>(&'s str);
+            impl<'a> S<'a> {
+                fn foo<'b>(&self, x: &'<|>) {}
+            }
+            ",
+        );
+        assert_eq!(labels, vec!["'a", "'b", "'static"]);
+    }
+
+    #[test]
+    fn completes_lifetimes_in_bounds() {
+        let labels = lifetime_labels(
+            r"
+            fn foo<'a, T: 'a + 'b<|>>() {}
+            ",
+        );
+        assert_eq!(labels, vec!["'a", "'static"]);
+    }
+
+    #[test]
+    fn completes_loop_labels() {
+        let labels = lifetime_labels(
+            r"
+            fn foo() {
+                'outer: loop {
+                    'block: {
+                        'inner: for _ in 0..10 {
+                            break '<|>
+                        }
+                    }
+                }
+            }
+            ",
+        );
+        assert_eq!(labels, vec!["'block", "'inner", "'outer"]);
+    }
+
+    #[test]
+    fn does_not_complete_blocks_after_continue() {
+        let labels = lifetime_labels(
+            r"
+            fn foo() {
+                'outer: loop {
+                    'block: {
+                        continue '<|>
+                    }
+                }
+            }
+            ",
+        );
+        assert_eq!(labels, vec!["'outer"]);
+    }
+
+    #[test]
+    fn does_not_complete_labels_across_closures() {
+        let labels = lifetime_labels(
+            r"
+            fn foo() {
+                'outer: loop {
+                    let f = || loop { break '<|> };
+                }
+            }
+            ",
+        );
+        assert!(labels.is_empty(), "{:?}", labels);
+    }
+}
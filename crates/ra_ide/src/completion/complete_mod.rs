@@ -0,0 +1,173 @@
+//! Completes the names of the modules in `mod foo;` declarations.
+//!
+//! The candidates are the `.rs` files and the directories with a `mod.rs`
+//! which live in the directory of the current module, and which are not
+//! declared yet.
+
+use std::collections::BTreeSet;
+
+use ra_db::{FileId, RelativePath, SourceDatabaseExt};
+use ra_syntax::ast::{self, AstNode, ModuleItemOwner, NameOwner};
+use rustc_hash::FxHashSet;
+
+use crate::completion::{
+    CompletionContext, CompletionItem, CompletionItemKind, CompletionKind, Completions,
+};
+
+pub(super) fn complete_mod(acc: &mut Completions, ctx: &CompletionContext) {
+    let mod_under_caret = match &ctx.mod_declaration_under_caret {
+        Some(it) => it,
+        None => return,
+    };
+    // Modules declared inside of inline modules live in subdirectories, only
+    // handle the top level of the file.
+    let source_file = match mod_under_caret.syntax().parent().and_then(ast::SourceFile::cast) {
+        Some(it) => it,
+        None => return,
+    };
+    let module = match ctx.scope().module() {
+        Some(it) => it,
+        None => return,
+    };
+    let file_id = module.definition_source(ctx.db).file_id.original_file(ctx.db);
+    let file_path = ctx.db.file_relative_path(file_id);
+    let file_stem = match file_path.file_stem() {
+        Some(it) => it,
+        None => return,
+    };
+    let parent_dir = file_path.parent().unwrap_or_else(|| RelativePath::new(""));
+    // `foo.rs` declares the modules in `foo/`, while crate roots and `mod.rs`
+    // declare the ones next to them.
+    let module_dir = if module.parent(ctx.db).is_none() || file_stem == "mod" {
+        parent_dir.to_relative_path_buf()
+    } else {
+        parent_dir.join(file_stem)
+    };
+
+    let declared: FxHashSet<String> = source_file
+        .items()
+        .filter_map(|item| match item {
+            ast::ModuleItem::Module(it) => it.name(),
+            _ => None,
+        })
+        .map(|name| name.text().to_string())
+        .collect();
+    let crate_graph = ctx.db.crate_graph();
+    let crate_roots: FxHashSet<FileId> =
+        crate_graph.iter().map(|krate| crate_graph[krate].root_file_id).collect();
+
+    let source_root = ctx.db.source_root(ctx.db.file_source_root(file_id));
+    let mut candidates = BTreeSet::new();
+    for candidate in source_root.walk() {
+        if candidate == file_id || crate_roots.contains(&candidate) {
+            continue;
+        }
+        let path = ctx.db.file_relative_path(candidate);
+        if path.extension() != Some("rs") {
+            continue;
+        }
+        let (dir, stem) = match (path.parent(), path.file_stem()) {
+            (Some(dir), Some(stem)) => (dir, stem),
+            _ => continue,
+        };
+        let name = if stem == "mod" {
+            if dir.parent() != Some(module_dir.as_relative_path()) {
+                continue;
+            }
+            match dir.file_name() {
+                Some(it) => it,
+                None => continue,
+            }
+        } else {
+            if dir != module_dir.as_relative_path() {
+                continue;
+            }
+            stem
+        };
+        if is_identifier(name) && !declared.contains(name) {
+            candidates.insert(name.to_string());
+        }
+    }
+
+    for name in candidates {
+        CompletionItem::new(CompletionKind::Magic, ctx.source_range(), name)
+            .kind(CompletionItemKind::Module)
+            .add_to(acc);
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::completion::{test_utils::do_completion, CompletionKind};
+
+    fn mod_completion_labels(code: &str) -> Vec<String> {
+        do_completion(code, CompletionKind::Magic)
+            .into_iter()
+            .map(|it| it.label().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn completes_files_next_to_crate_root() {
+        let labels = mod_completion_labels(
+            r"
+            //- /lib.rs
+            mod foo;
+            mod <|>;
+            //- /foo.rs
+            fn foo() {}
+            //- /bar.rs
+            fn bar() {}
+            //- /baz/mod.rs
+            fn baz() {}
+            //- /baz/inner.rs
+            fn inner() {}
+            //- /not-an-ident.rs
+            fn nope() {}
+            ",
+        );
+        assert_eq!(labels, vec!["bar", "baz"]);
+    }
+
+    #[test]
+    fn completes_files_in_module_directory() {
+        let labels = mod_completion_labels(
+            r"
+            //- /lib.rs
+            mod foo;
+            mod baz;
+            //- /foo.rs
+            mod <|>;
+            //- /foo/bar.rs
+            fn bar() {}
+            //- /foo/qux/mod.rs
+            fn qux() {}
+            //- /baz.rs
+            fn baz() {}
+            ",
+        );
+        assert_eq!(labels, vec!["bar", "qux"]);
+    }
+
+    #[test]
+    fn does_not_complete_inline_modules() {
+        let labels = mod_completion_labels(
+            r"
+            //- /lib.rs
+            mod <|> {}
+            //- /foo.rs
+            fn foo() {}
+            ",
+        );
+        assert!(labels.is_empty(), "{:?}", labels);
+    }
+}
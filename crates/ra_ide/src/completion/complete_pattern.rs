@@ -11,9 +11,22 @@ pub(super) fn complete_pattern(acc: &mut Completions, ctx: &CompletionContext) {
         return;
     }
 
-    // FIXME: ideally, we should look at the type we are matching against and
-    // suggest variants + auto-imports
+    // When matching against an enum, only its variants are interesting. The
+    // variants which are not in scope are completed with their path in
+    // `complete_unqualified_path`.
+    let expected_enum = ctx.expected_type.as_ref().and_then(|ty| match ty.as_adt() {
+        Some(hir::Adt::Enum(it)) => Some(it),
+        _ => None,
+    });
     ctx.scope().process_all_names(&mut |name, res| {
+        if let Some(expected_enum) = expected_enum {
+            match &res {
+                hir::ScopeDef::ModuleDef(hir::ModuleDef::EnumVariant(variant))
+                    if variant.parent_enum(ctx.db) == expected_enum => {}
+                hir::ScopeDef::ModuleDef(hir::ModuleDef::Const(..)) => (),
+                _ => return,
+            }
+        }
         match &res {
             hir::ScopeDef::ModuleDef(def) => match def {
                 hir::ModuleDef::Adt(hir::Adt::Enum(..))
@@ -41,7 +54,7 @@ mod tests {
     }
 
     #[test]
-    fn completes_enum_variants_and_modules() {
+    fn completes_only_enum_variants_and_consts_of_matched_enum() {
         let completions = complete(
             r"
             enum E { X }
@@ -61,20 +74,6 @@ mod tests {
         );
         assert_debug_snapshot!(completions, @r###"
         [
-            CompletionItem {
-                label: "Bar",
-                source_range: 246..246,
-                delete: 246..246,
-                insert: "Bar",
-                kind: Struct,
-            },
-            CompletionItem {
-                label: "E",
-                source_range: 246..246,
-                delete: 246..246,
-                insert: "E",
-                kind: Enum,
-            },
             CompletionItem {
                 label: "X",
                 source_range: 246..246,
@@ -90,12 +89,53 @@ mod tests {
                 insert: "Z",
                 kind: Const,
             },
+        ]
+        "###);
+    }
+
+    #[test]
+    fn completes_variants_of_matched_enum_with_fields() {
+        let completions = complete(
+            r"
+            enum E { A, B { x: i32, y: u32 }, C(i32) }
+            struct S;
+
+            fn foo(e: E) {
+               match e {
+                   <|>
+               }
+            }
+            ",
+        );
+        assert_debug_snapshot!(completions, @r###"
+        [
             CompletionItem {
-                label: "m",
-                source_range: 246..246,
-                delete: 246..246,
-                insert: "m",
-                kind: Module,
+                label: "E::A",
+                source_range: 150..150,
+                delete: 150..150,
+                insert: "E::A",
+                kind: EnumVariant,
+                lookup: "A",
+                detail: "()",
+            },
+            CompletionItem {
+                label: "E::B {…}",
+                source_range: 150..150,
+                delete: 150..150,
+                insert: "E::B { ${1:x}, ${2:y} }$0",
+                kind: EnumVariant,
+                lookup: "E::B",
+                detail: "{ x: i32, y: u32 }",
+            },
+            CompletionItem {
+                label: "E::C(…)",
+                source_range: 150..150,
+                delete: 150..150,
+                insert: "E::C($0)",
+                kind: EnumVariant,
+                lookup: "E::C",
+                detail: "(i32)",
+                trigger_call_info: true,
             },
         ]
         "###);
@@ -1,4 +1,10 @@
-//! FIXME: write short doc here
+//! Postfix completions, like `expr.if` or `expr.dbg`, which wrap the
+//! expression before the dot in a snippet.
+//!
+//! The edit of each completion replaces the whole `expr.if` text. As LSP only
+//! allows the main edit of a completion to cover the typed identifier, the
+//! removal of `expr.` is sent as an additional edit, see the conversion of
+//! `CompletionItem` in the `rust-analyzer` crate.
 
 use ra_syntax::{
     ast::{self, AstNode},
@@ -1,6 +1,6 @@
 //! Completion of paths, i.e. `some::prefix::<|>`.
 
-use hir::{Adt, HasVisibility, PathResolution, ScopeDef};
+use hir::{Adt, HasVisibility, PathKind, PathResolution, ScopeDef};
 use ra_syntax::AstNode;
 use rustc_hash::FxHashSet;
 use test_utils::tested_by;
@@ -17,6 +17,22 @@ pub(super) fn complete_qualified_path(acc: &mut Completions, ctx: &CompletionCon
         return;
     }
 
+    // A bare `::` refers to the dependencies of the current crate.
+    if *path.kind() == PathKind::Abs && path.segments().is_empty() {
+        if let Some(krate) = ctx.krate {
+            for dep in krate.dependencies(ctx.db) {
+                if let Some(root) = dep.krate.root_module(ctx.db) {
+                    acc.add_resolution(
+                        ctx,
+                        dep.name.to_string(),
+                        &ScopeDef::ModuleDef(root.into()),
+                    );
+                }
+            }
+        }
+        return;
+    }
+
     let scope = ctx.scope();
     let context_module = scope.module();
 
@@ -1344,4 +1360,30 @@ mod tests {
             @r###"[]"###
         )
     }
+
+    #[test]
+    fn completes_dependencies_after_bare_coloncolon() {
+        assert_debug_snapshot!(
+            do_reference_completion(
+                r"
+                //- /lib.rs
+                use ::<|>;
+
+                //- /other_crate/lib.rs
+                pub struct Foo;
+                "
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "other_crate",
+                source_range: 6..6,
+                delete: 6..6,
+                insert: "other_crate",
+                kind: Module,
+            },
+        ]
+        "###
+        );
+    }
 }
@@ -1,5 +1,9 @@
 //! Complete fields in record literals and patterns.
-use crate::completion::{CompletionContext, Completions};
+use stdx::SepBy;
+
+use crate::completion::{
+    CompletionContext, CompletionItem, CompletionItemKind, CompletionKind, Completions,
+};
 
 pub(super) fn complete_record(acc: &mut Completions, ctx: &CompletionContext) -> Option<()> {
     let missing_fields = match (ctx.record_pat_syntax.as_ref(), ctx.record_lit_syntax.as_ref()) {
@@ -9,6 +13,31 @@ pub(super) fn complete_record(acc: &mut Completions, ctx: &CompletionContext) ->
         (_, Some(record_lit)) => ctx.sema.record_literal_missing_fields(record_lit),
     };
 
+    if ctx.record_lit_syntax.is_some() && missing_fields.len() > 1 {
+        if let Some(cap) = ctx.config.snippet_cap {
+            let fields = missing_fields.iter().map(|(field, ty)| {
+                (field.name(ctx.db).to_string(), ty.display(ctx.db).to_string())
+            });
+            let fields = fields.collect::<Vec<_>>();
+            let snippet = fields
+                .iter()
+                .enumerate()
+                .map(|(idx, (name, ty))| format!("{}: ${{{}:{}}}", name, idx + 1, ty))
+                .sep_by(", ")
+                .to_string();
+            let detail = fields
+                .iter()
+                .map(|(name, ty)| format!("{}: {}", name, ty))
+                .sep_by(", ")
+                .to_string();
+            CompletionItem::new(CompletionKind::Magic, ctx.source_range(), "fill all fields")
+                .kind(CompletionItemKind::Snippet)
+                .detail(detail)
+                .insert_snippet(cap, format!("{}$0", snippet))
+                .add_to(acc);
+        }
+    }
+
     for (field, ty) in missing_fields {
         acc.add_field(ctx, field, &ty)
     }
@@ -201,6 +230,31 @@ mod tests {
         "###);
         }
 
+        #[test]
+        fn test_record_literal_fill_all_fields() {
+            let completions = do_completion(
+                r"
+            struct S { foo: u32, bar: i32, baz: bool }
+            fn f() {
+               S { foo: 92, <|> };
+            }
+            ",
+                CompletionKind::Magic,
+            );
+            assert_debug_snapshot!(completions, @r###"
+        [
+            CompletionItem {
+                label: "fill all fields",
+                source_range: 105..105,
+                delete: 105..105,
+                insert: "bar: ${1:i32}, baz: ${2:bool}$0",
+                kind: Snippet,
+                detail: "bar: i32, baz: bool",
+            },
+        ]
+        "###);
+        }
+
         #[test]
         fn test_record_literal_field() {
             let completions = complete(
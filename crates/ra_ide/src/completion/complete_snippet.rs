@@ -2,7 +2,7 @@
 
 use crate::completion::{
     completion_config::SnippetCap, completion_item::Builder, CompletionContext, CompletionItem,
-    CompletionItemKind, CompletionKind, Completions,
+    CompletionItemKind, CompletionKind, Completions, SnippetScope,
 };
 
 fn snippet(ctx: &CompletionContext, cap: SnippetCap, label: &str, snippet: &str) -> Builder {
@@ -22,6 +22,7 @@ pub(super) fn complete_expr_snippet(acc: &mut Completions, ctx: &CompletionConte
 
     snippet(ctx, cap, "pd", "eprintln!(\"$0 = {:?}\", $0);").add_to(acc);
     snippet(ctx, cap, "ppd", "eprintln!(\"$0 = {:#?}\", $0);").add_to(acc);
+    complete_user_snippets(acc, ctx, cap, SnippetScope::Expr);
 }
 
 pub(super) fn complete_item_snippet(acc: &mut Completions, ctx: &CompletionContext) {
@@ -48,11 +49,26 @@ fn ${1:feature}() {
 
     snippet(ctx, cap, "macro_rules", "macro_rules! $1 {\n\t($2) => {\n\t\t$0\n\t};\n}").add_to(acc);
     snippet(ctx, cap, "pub(crate)", "pub(crate) $0").add_to(acc);
+    complete_user_snippets(acc, ctx, cap, SnippetScope::Item);
+}
+
+fn complete_user_snippets(
+    acc: &mut Completions,
+    ctx: &CompletionContext,
+    cap: SnippetCap,
+    scope: SnippetScope,
+) {
+    for user_snippet in ctx.config.snippets.iter().filter(|it| it.scope == scope) {
+        snippet(ctx, cap, &user_snippet.trigger, &user_snippet.body).add_to(acc);
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::completion::{test_utils::do_completion, CompletionItem, CompletionKind};
+    use crate::completion::{
+        test_utils::{do_completion, do_completion_with_options},
+        CompletionConfig, CompletionItem, CompletionKind, Snippet, SnippetScope,
+    };
     use insta::assert_debug_snapshot;
 
     fn do_snippet_completion(code: &str) -> Vec<CompletionItem> {
@@ -135,4 +151,37 @@ mod tests {
         "###
         );
     }
+
+    #[test]
+    fn completes_user_snippets_in_their_scope() {
+        let config = CompletionConfig {
+            snippets: vec![
+                Snippet {
+                    trigger: "ok".to_string(),
+                    body: "Ok($0)".to_string(),
+                    scope: SnippetScope::Expr,
+                },
+                Snippet {
+                    trigger: "tmain".to_string(),
+                    body: "#[tokio::main]\nasync fn main() {\n\t$0\n}".to_string(),
+                    scope: SnippetScope::Item,
+                },
+            ],
+            ..CompletionConfig::default()
+        };
+        let labels = |code: &str| -> Vec<String> {
+            do_completion_with_options(code, CompletionKind::Snippet, &config)
+                .into_iter()
+                .map(|it| it.label().to_string())
+                .collect()
+        };
+
+        let in_expr = labels(r"fn foo() { <|> }");
+        assert!(in_expr.contains(&"ok".to_string()), "{:?}", in_expr);
+        assert!(!in_expr.contains(&"tmain".to_string()), "{:?}", in_expr);
+
+        let in_item = labels(r"<|>");
+        assert!(in_item.contains(&"tmain".to_string()), "{:?}", in_item);
+        assert!(!in_item.contains(&"ok".to_string()), "{:?}", in_item);
+    }
 }
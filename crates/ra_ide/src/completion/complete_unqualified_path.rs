@@ -1163,13 +1163,6 @@ mod tests {
             ),
             @r###"
         [
-            CompletionItem {
-                label: "Foo",
-                source_range: 248..250,
-                delete: 248..250,
-                insert: "Foo",
-                kind: Enum,
-            },
             CompletionItem {
                 label: "Foo::Bar",
                 source_range: 248..250,
@@ -1224,13 +1217,6 @@ mod tests {
             ),
             @r###"
         [
-            CompletionItem {
-                label: "Foo",
-                source_range: 219..221,
-                delete: 219..221,
-                insert: "Foo",
-                kind: Enum,
-            },
             CompletionItem {
                 label: "Foo::Bar",
                 source_range: 219..221,
@@ -7,9 +7,11 @@
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct CompletionConfig {
     pub enable_postfix_completions: bool,
+    pub enable_autoimport_completions: bool,
     pub add_call_parenthesis: bool,
     pub add_call_argument_snippets: bool,
     pub snippet_cap: Option<SnippetCap>,
+    pub snippets: Vec<Snippet>,
 }
 
 impl CompletionConfig {
@@ -18,6 +20,25 @@ impl CompletionConfig {
     }
 }
 
+/// A snippet declared by the user, completed next to the built-in ones.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snippet {
+    /// The label of the completion.
+    pub trigger: String,
+    /// The text to insert, in the LSP snippet syntax.
+    pub body: String,
+    pub scope: SnippetScope,
+}
+
+/// Where a user snippet is completed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnippetScope {
+    /// Where an item can be declared, e.g. at the top of a module.
+    Item,
+    /// Inside of a function body.
+    Expr,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct SnippetCap {
     _private: (),
@@ -27,9 +48,11 @@ impl Default for CompletionConfig {
     fn default() -> Self {
         CompletionConfig {
             enable_postfix_completions: true,
+            enable_autoimport_completions: true,
             add_call_parenthesis: true,
             add_call_argument_snippets: true,
             snippet_cap: Some(SnippetCap { _private: () }),
+            snippets: Vec::new(),
         }
     }
 }
@@ -59,6 +59,13 @@ pub(crate) struct CompletionContext<'a> {
     pub(super) is_path_type: bool,
     pub(super) has_type_args: bool,
     pub(super) attribute_under_caret: Option<ast::Attr>,
+    /// If the path is evaluated at compile time, where only `const fn`s can be
+    /// called.
+    pub(super) is_const_context: bool,
+    /// The lifetime or label being typed, e.g. `&'a` or `break 'outer`.
+    pub(super) lifetime_under_caret: Option<SyntaxToken>,
+    /// The `mod foo;` declaration whose name is being typed.
+    pub(super) mod_declaration_under_caret: Option<ast::Module>,
 }
 
 impl<'a> CompletionContext<'a> {
@@ -117,6 +124,9 @@ impl<'a> CompletionContext<'a> {
             has_type_args: false,
             dot_receiver_is_ambiguous_float_literal: false,
             attribute_under_caret: None,
+            is_const_context: false,
+            lifetime_under_caret: None,
+            mod_declaration_under_caret: None,
         };
 
         let mut original_file = original_file.syntax().clone();
@@ -183,24 +193,25 @@ impl<'a> CompletionContext<'a> {
         file_with_fake_ident: SyntaxNode,
         offset: TextSize,
     ) {
-        // FIXME: this is wrong in at least two cases:
-        //  * when there's no token `foo(<|>)`
-        //  * when there is a token, but it happens to have type of it's own
-        self.expected_type = self
-            .token
-            .ancestors()
-            .find_map(|node| {
-                let ty = match_ast! {
-                    match node {
-                        ast::Pat(it) => self.sema.type_of_pat(&it),
-                        ast::Expr(it) => self.sema.type_of_expr(&it),
-                        _ => return None,
-                    }
-                };
-                Some(ty)
-            })
-            .flatten();
+        // FIXME: this is wrong when there's no token `foo(<|>)`
+        self.expected_type = self.expected_type_from_parent().or_else(|| {
+            self.token
+                .ancestors()
+                .find_map(|node| {
+                    let ty = match_ast! {
+                        match node {
+                            ast::Pat(it) => self.sema.type_of_pat(&it),
+                            ast::Expr(it) => self.sema.type_of_expr(&it),
+                            _ => return None,
+                        }
+                    };
+                    Some(ty)
+                })
+                .flatten()
+        });
         self.attribute_under_caret = find_node_at_offset(&file_with_fake_ident, offset);
+        self.lifetime_under_caret =
+            file_with_fake_ident.token_at_offset(offset).find(|it| it.kind() == LIFETIME);
 
         // First, let's try to complete a reference to some declaration.
         if let Some(name_ref) = find_node_at_offset::<ast::NameRef>(&file_with_fake_ident, offset) {
@@ -245,6 +256,12 @@ impl<'a> CompletionContext<'a> {
                 self.is_param = true;
                 return;
             }
+            if let Some(module) = name.syntax().parent().and_then(ast::Module::cast) {
+                if module.item_list().is_none() {
+                    self.mod_declaration_under_caret = Some(module);
+                }
+                return;
+            }
             // FIXME: remove this (^) duplication and make the check more precise
             if name.syntax().ancestors().find_map(ast::RecordFieldPatList::cast).is_some() {
                 self.record_pat_syntax =
@@ -253,6 +270,33 @@ impl<'a> CompletionContext<'a> {
         }
     }
 
+    /// The type of the `let` binding or of the record field initialized by
+    /// the expression at the cursor. The type of the expression itself is
+    /// usually unknown while it is being typed.
+    fn expected_type_from_parent(&self) -> Option<Type> {
+        let expr = self
+            .token
+            .ancestors()
+            .find(|it| ast::Pat::can_cast(it.kind()) || ast::Expr::can_cast(it.kind()))
+            .and_then(ast::Expr::cast)?;
+        let parent = expr.syntax().parent()?;
+        match_ast! {
+            match parent {
+                ast::LetStmt(it) => {
+                    if it.initializer()?.syntax() != expr.syntax() {
+                        return None;
+                    }
+                    self.sema.type_of_pat(&it.pat()?)
+                },
+                ast::RecordField(it) => {
+                    let (field, _local) = self.sema.resolve_record_field(&it)?;
+                    Some(field.signature_ty(self.db))
+                },
+                _ => None,
+            }
+        }
+    }
+
     fn classify_name_ref(
         &mut self,
         original_file: &SyntaxNode,
@@ -296,6 +340,7 @@ impl<'a> CompletionContext<'a> {
             .ancestors_with_macros(self.token.parent())
             .take_while(|it| it.kind() != SOURCE_FILE && it.kind() != MODULE)
             .find_map(ast::FnDef::cast);
+        self.is_const_context = is_in_const_context(name_ref.syntax());
 
         self.record_field_syntax = self
             .sema
@@ -399,6 +444,31 @@ fn find_node_with_range<N: AstNode>(syntax: &SyntaxNode, range: TextRange) -> Op
     find_covering_element(syntax, range).ancestors().find_map(N::cast)
 }
 
+/// Whether `node` is in the initializer of a `const` or `static`, in the body
+/// of a `const fn` or in the length of an array.
+fn is_in_const_context(node: &SyntaxNode) -> bool {
+    let contains_node = |expr: Option<ast::Expr>| {
+        expr.map_or(false, |expr| expr.syntax().text_range().contains_range(node.text_range()))
+    };
+    for ancestor in node.ancestors() {
+        match_ast! {
+            match ancestor {
+                ast::ConstDef(it) => return contains_node(it.body()),
+                ast::StaticDef(it) => return contains_node(it.body()),
+                ast::FnDef(it) => return it.const_token().is_some(),
+                ast::ArrayType(it) => if contains_node(it.expr()) { return true },
+                ast::ArrayExpr(it) => {
+                    if it.semicolon_token().is_some() && contains_node(it.exprs().nth(1)) {
+                        return true;
+                    }
+                },
+                _ => (),
+            }
+        }
+    }
+    false
+}
+
 fn is_node<N: AstNode>(node: &SyntaxNode) -> bool {
     match node.ancestors().find_map(N::cast) {
         None => false,
@@ -2,7 +2,7 @@
 
 use std::fmt;
 
-use super::completion_config::SnippetCap;
+use super::{completion_config::SnippetCap, fuzzy::fuzzy_match};
 use hir::Documentation;
 use ra_syntax::TextRange;
 use ra_text_edit::TextEdit;
@@ -55,6 +55,10 @@ pub struct CompletionItem {
 
     /// Score is useful to pre select or display in better order completion items
     score: Option<CompletionScore>,
+
+    /// How well `lookup` fuzzy matches the identifier typed so far, if it
+    /// matches at all. See `fuzzy::fuzzy_match`.
+    match_score: Option<u32>,
 }
 
 // We use custom debug for CompletionItem to make `insta`'s diffs more readable.
@@ -208,6 +212,10 @@ impl CompletionItem {
         self.score
     }
 
+    pub fn match_score(&self) -> Option<u32> {
+        self.match_score
+    }
+
     pub fn trigger_call_info(&self) -> bool {
         self.trigger_call_info
     }
@@ -259,6 +267,7 @@ impl Builder {
             deprecated: self.deprecated.unwrap_or(false),
             trigger_call_info: self.trigger_call_info.unwrap_or(false),
             score: self.score,
+            match_score: None,
         }
     }
     pub(crate) fn lookup_by(mut self, lookup: impl Into<String>) -> Builder {
@@ -346,6 +355,12 @@ impl Completions {
     {
         items.into_iter().for_each(|item| self.add(item.into()))
     }
+    /// Records how well each item matches the identifier typed so far.
+    pub(crate) fn score_matches(&mut self, typed: &str) {
+        for item in self.buf.iter_mut() {
+            item.match_score = fuzzy_match(typed, item.lookup());
+        }
+    }
 }
 
 impl Into<Vec<CompletionItem>> for Completions {
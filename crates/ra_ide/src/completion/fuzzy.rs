@@ -0,0 +1,88 @@
+//! Fuzzy matching of the completion items against the identifier typed so
+//! far, used to rank the items. The items which don't match are kept: the
+//! filtering is left to the client.
+
+/// Returns the score of `candidate` if each character of `pattern` appears in
+/// it, in order and ignoring case.
+///
+/// Characters starting a word score higher: the first one, the ones after `_`
+/// or `:` and the uppercase ones following a lowercase letter. So do
+/// consecutive matches, so that `hmp` prefers `HashMap::print` over `hump`.
+pub(crate) fn fuzzy_match(pattern: &str, candidate: &str) -> Option<u32> {
+    let mut score = 0;
+    let mut candidate_chars = candidate.chars();
+    let mut prev_char = None;
+    let mut prev_matched = false;
+    'pattern: for pattern_char in pattern.chars() {
+        while let Some(candidate_char) = candidate_chars.next() {
+            let is_word_start = match prev_char {
+                None => true,
+                Some(prev) => {
+                    prev == '_'
+                        || prev == ':'
+                        || (prev.is_lowercase() && candidate_char.is_uppercase())
+                }
+            };
+            prev_char = Some(candidate_char);
+            if candidate_char.to_lowercase().eq(pattern_char.to_lowercase()) {
+                score += 1;
+                if is_word_start {
+                    score += 3;
+                }
+                if prev_matched {
+                    score += 2;
+                }
+                prev_matched = true;
+                continue 'pattern;
+            }
+            prev_matched = false;
+        }
+        return None;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::completion::{test_utils::do_completion, CompletionKind};
+
+    use super::fuzzy_match;
+
+    #[test]
+    fn matches_subsequences() {
+        assert!(fuzzy_match("hmp", "HashMap::print").is_some());
+        assert!(fuzzy_match("HMP", "hash_map_print").is_some());
+        assert!(fuzzy_match("", "anything").is_some());
+        assert_eq!(fuzzy_match("hmx", "HashMap::print"), None);
+        assert_eq!(fuzzy_match("pam", "map"), None);
+    }
+
+    #[test]
+    fn prefers_word_starts() {
+        assert!(fuzzy_match("fb", "foo_bar") > fuzzy_match("fb", "foobar"));
+        assert!(fuzzy_match("hm", "HashMap") > fuzzy_match("hm", "hashmap"));
+        assert!(fuzzy_match("ma", "Map") > fuzzy_match("ma", "hashmap"));
+    }
+
+    #[test]
+    fn prefers_consecutive_matches() {
+        assert!(fuzzy_match("ret", "return") > fuzzy_match("ret", "rather_strict"));
+    }
+
+    #[test]
+    fn scores_completion_items() {
+        let completions = do_completion(
+            r"
+            fn hash_map_print() {}
+            fn hump() {}
+            fn main() { hmp<|> }
+            ",
+            CompletionKind::Reference,
+        );
+        let match_score = |lookup: &str| {
+            completions.iter().find(|it| it.lookup() == lookup).unwrap().match_score()
+        };
+        assert!(match_score("hash_map_print") > match_score("hump"));
+        assert_eq!(match_score("main"), None);
+    }
+}
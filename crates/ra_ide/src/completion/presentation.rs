@@ -103,7 +103,11 @@ impl Completions {
         if let ScopeDef::Local(local) = resolution {
             let ty = local.ty(ctx.db);
             if !ty.is_unknown() {
-                completion_item = completion_item.detail(ty.display(ctx.db).to_string());
+                let ty = ty.display(ctx.db).to_string();
+                if let Some(score) = compute_score(ctx, &ty, &local_name) {
+                    completion_item = completion_item.set_score(score);
+                }
+                completion_item = completion_item.detail(ty);
             }
         };
 
@@ -186,6 +190,8 @@ impl Completions {
         local_name: Option<String>,
     ) {
         let has_self_param = func.has_self_param(ctx.db);
+        // Calling a non-`const fn` would not compile here.
+        let is_unusable = ctx.is_const_context && !func.is_const(ctx.db);
 
         let name = local_name.unwrap_or_else(|| func.name(ctx.db).to_string());
         let ast_node = func.source(ctx.db).value;
@@ -199,14 +205,28 @@ impl Completions {
                     CompletionItemKind::Function
                 })
                 .set_documentation(func.docs(ctx.db))
-                .set_deprecated(is_deprecated(func, ctx.db))
+                .set_deprecated(is_unusable || is_deprecated(func, ctx.db))
                 .detail(function_signature.to_string());
 
+        let ret_type = func.ret_type(ctx.db).display(ctx.db).to_string();
+        if let Some(score) = compute_score(ctx, &ret_type, &name) {
+            builder = builder.set_score(score);
+        }
+
         let params = function_signature
             .parameter_names
             .iter()
+            .zip(function_signature.parameter_types.iter())
             .skip(if function_signature.has_self_param { 1 } else { 0 })
-            .cloned()
+            .map(|(name, ty)| {
+                // `_foo` is named `foo` in the snippet, and `_` by its type.
+                let name = name.trim_start_matches('_');
+                if name.is_empty() {
+                    ty.clone()
+                } else {
+                    name.to_string()
+                }
+            })
             .collect();
 
         builder = builder.add_call_parens(ctx, name, Params::Named(params));
@@ -311,6 +331,21 @@ impl Completions {
         if variant_kind == StructKind::Tuple {
             let params = Params::Anonymous(variant.fields(ctx.db).len());
             res = res.add_call_parens(ctx, qualified_name, params)
+        } else if variant_kind == StructKind::Record && ctx.is_pat_binding_or_const {
+            if let Some(cap) = ctx.config.snippet_cap {
+                let fields = variant
+                    .fields(ctx.db)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(idx, field)| format!("${{{}:{}}}", idx + 1, field.name(ctx.db)))
+                    .sep_by(", ")
+                    .surround_with(" { ", " }")
+                    .to_string();
+                res = res
+                    .lookup_by(qualified_name.clone())
+                    .label(format!("{} {{…}}", qualified_name))
+                    .insert_snippet(cap, format!("{}{}$0", qualified_name, fields));
+            }
         }
 
         res.add_to(self);
@@ -333,6 +368,14 @@ pub(crate) fn compute_score(
     } else if let Some(active_parameter) = &ctx.active_parameter {
         tested_by!(test_struct_field_completion_in_func_call);
         (active_parameter.name.clone(), active_parameter.ty.clone())
+    } else if let Some(expected_type) = &ctx.expected_type {
+        // Everything would match an unknown type, and `()` is expected by
+        // most of the statements.
+        if expected_type.contains_unknown() || expected_type.is_unit() {
+            return None;
+        }
+        tested_by!(test_completion_score_by_expected_type);
+        (String::new(), expected_type.display(ctx.db).to_string())
     } else {
         return None;
     };
@@ -691,6 +734,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn strips_underscores_from_call_argument_snippets() {
+        assert_debug_snapshot!(
+            do_reference_completion(
+                r"
+                fn with_ignored_args(_foo: i32, ___bar: bool, ho_ge_: String, _: u32) {}
+                fn main() { with_<|> }
+                "
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "main()",
+                source_range: 118..123,
+                delete: 118..123,
+                insert: "main()$0",
+                kind: Function,
+                lookup: "main",
+                detail: "fn main()",
+            },
+            CompletionItem {
+                label: "with_ignored_args(…)",
+                source_range: 118..123,
+                delete: 118..123,
+                insert: "with_ignored_args(${1:foo}, ${2:bar}, ${3:ho_ge_}, ${4:u32})$0",
+                kind: Function,
+                lookup: "with_ignored_args",
+                detail: "fn with_ignored_args(_foo: i32, ___bar: bool, ho_ge_: String, _: u32)",
+                trigger_call_info: true,
+            },
+        ]
+        "###
+        );
+    }
+
     #[test]
     fn inserts_parens_for_tuple_enums() {
         assert_debug_snapshot!(
@@ -1311,6 +1389,73 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_completion_score_by_expected_type() {
+        covers!(test_completion_score_by_expected_type);
+        assert_debug_snapshot!(
+        do_reference_completion(
+                r"
+                fn returns_u32() -> u32 { 0 }
+                fn returns_unit() {}
+                fn main() {
+                    let y: u32 = 1;
+                    let z = true;
+                    let x: u32 = r<|>
+                }
+                ",
+        ),
+            @r###"
+        [
+            CompletionItem {
+                label: "main()",
+                source_range: 215..216,
+                delete: 215..216,
+                insert: "main()$0",
+                kind: Function,
+                lookup: "main",
+                detail: "fn main()",
+            },
+            CompletionItem {
+                label: "returns_u32()",
+                source_range: 215..216,
+                delete: 215..216,
+                insert: "returns_u32()$0",
+                kind: Function,
+                lookup: "returns_u32",
+                detail: "fn returns_u32() -> u32",
+                score: TypeMatch,
+            },
+            CompletionItem {
+                label: "returns_unit()",
+                source_range: 215..216,
+                delete: 215..216,
+                insert: "returns_unit()$0",
+                kind: Function,
+                lookup: "returns_unit",
+                detail: "fn returns_unit()",
+            },
+            CompletionItem {
+                label: "y",
+                source_range: 215..216,
+                delete: 215..216,
+                insert: "y",
+                kind: Binding,
+                detail: "u32",
+                score: TypeMatch,
+            },
+            CompletionItem {
+                label: "z",
+                source_range: 215..216,
+                delete: 215..216,
+                insert: "z",
+                kind: Binding,
+                detail: "bool",
+            },
+        ]
+        "###
+        );
+    }
+
     #[test]
     fn test_struct_field_completion_in_record_lit_and_fn_call() {
         assert_debug_snapshot!(
@@ -1405,4 +1550,51 @@ mod tests {
         "###
         );
     }
+
+    #[test]
+    fn marks_non_const_fns_in_const_context() {
+        let deprecated = |code: &str| -> Vec<(String, bool)> {
+            do_reference_completion(code)
+                .into_iter()
+                .filter(|it| it.lookup() == "foo" || it.lookup() == "bar")
+                .map(|it| (it.lookup().to_string(), it.deprecated()))
+                .collect()
+        };
+        let expected = vec![("bar".to_string(), false), ("foo".to_string(), true)];
+
+        assert_eq!(
+            deprecated(
+                r"
+                fn foo() -> usize { 0 }
+                const fn bar() -> usize { 0 }
+                const X: usize = <|>;
+                "
+            ),
+            expected
+        );
+        assert_eq!(
+            deprecated(
+                r"
+                fn foo() -> usize { 0 }
+                const fn bar() -> usize { 0 }
+                fn main() {
+                    let a: [u8; <|>];
+                }
+                "
+            ),
+            expected
+        );
+        assert_eq!(
+            deprecated(
+                r"
+                fn foo() -> usize { 0 }
+                const fn bar() -> usize { 0 }
+                fn main() {
+                    let a = <|>;
+                }
+                "
+            ),
+            vec![("bar".to_string(), false), ("foo".to_string(), false)]
+        );
+    }
 }
@@ -4,32 +4,79 @@
 //! macro-expanded files, but we need to present them to the users in terms of
 //! original files. So we need to map the ranges.
 
-use std::cell::RefCell;
+use std::{cell::RefCell, collections::BTreeSet, iter, str::FromStr};
 
 use hir::{
     diagnostics::{AstDiagnostic, Diagnostic as _, DiagnosticSink},
-    Semantics,
+    original_range, Adt, Field, HasSource, HirDisplay, ModuleDef, PathResolution, ScopeDef,
+    Semantics, Struct, Trait, Type, VariantDef,
 };
 use itertools::Itertools;
+use ra_assists::{
+    import_fixes,
+    utils::{variant_pat, FamousDefs},
+};
 use ra_db::{RelativePath, SourceDatabase, SourceDatabaseExt};
 use ra_ide_db::RootDatabase;
 use ra_prof::profile;
 use ra_syntax::{
     algo,
-    ast::{self, make, AstNode},
+    ast::{
+        self,
+        edit::{IndentLevel, SyntaxEditor},
+        make, AstNode, NameOwner, TypeAscriptionOwner, VisibilityOwner,
+    },
+    Direction,
+    SyntaxKind::{ATTR, COMMENT, WHITESPACE},
     SyntaxNode, TextRange, T,
 };
-use ra_text_edit::{TextEdit, TextEditBuilder};
+use ra_text_edit::{AtomTextEdit, TextEdit, TextEditBuilder};
+use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::{Diagnostic, FileId, FileSystemEdit, SourceChange, SourceFileEdit};
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Severity {
     Error,
     WeakWarning,
+    Hint,
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Severity, String> {
+        match s {
+            "error" => Ok(Severity::Error),
+            "warning" => Ok(Severity::WeakWarning),
+            "hint" => Ok(Severity::Hint),
+            _ => Err(format!("unknown severity: {}", s)),
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct DiagnosticsConfig {
+    /// Codes of the diagnostics which are not reported.
+    pub disabled: FxHashSet<String>,
+    /// Severities to report the diagnostics with the given codes with, instead
+    /// of their default ones.
+    pub severity_overrides: FxHashMap<String, Severity>,
 }
 
-pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic> {
+impl DiagnosticsConfig {
+    /// Returns the severity a diagnostic with the given code and default
+    /// severity should be reported with.
+    pub fn severity(&self, code: &str, default: Severity) -> Severity {
+        self.severity_overrides.get(code).copied().unwrap_or(default)
+    }
+}
+
+pub(crate) fn diagnostics(
+    db: &RootDatabase,
+    config: &DiagnosticsConfig,
+    file_id: FileId,
+) -> Vec<Diagnostic> {
     let _p = profile("diagnostics");
     let sema = Semantics::new(db);
     let parse = db.parse(file_id);
@@ -39,12 +86,16 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
         range: err.range(),
         message: format!("Syntax Error: {}", err),
         severity: Severity::Error,
-        fix: None,
+        unused: false,
+        fixes: Vec::new(),
+        related: Vec::new(),
+        code: "syntax-error",
     }));
 
     for node in parse.tree().syntax().descendants() {
         check_unnecessary_braces_in_use_statement(&mut res, file_id, &node);
         check_struct_shorthand_initialization(&mut res, file_id, &node);
+        check_try_macro(&mut res, file_id, &node);
     }
     let res = RefCell::new(res);
     let mut sink = DiagnosticSink::new(|d| {
@@ -52,24 +103,47 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
             message: d.message(),
             range: sema.diagnostics_range(d).range,
             severity: Severity::Error,
-            fix: None,
+            unused: false,
+            fixes: Vec::new(),
+            related: Vec::new(),
+            code: d.code(),
         })
     })
     .on::<hir::diagnostics::UnresolvedModule, _>(|d| {
         let original_file = d.source().file_id.original_file(db);
         let source_root = db.file_source_root(original_file);
-        let path = db
-            .file_relative_path(original_file)
-            .parent()
-            .unwrap_or_else(|| RelativePath::new(""))
-            .join(&d.candidate);
-        let create_file = FileSystemEdit::CreateFile { source_root, path };
-        let fix = SourceChange::file_system_edit("Create module", create_file);
+        let file_path = db.file_relative_path(original_file);
+        let dir = file_path.parent().unwrap_or_else(|| RelativePath::new(""));
+        let fixes = d
+            .candidates
+            .iter()
+            .map(|candidate| {
+                let label = format!("Create module at `{}`", candidate);
+                let path = dir.join(candidate);
+                let create_file =
+                    FileSystemEdit::CreateFile { source_root, path, contents: String::new() };
+                SourceChange::file_system_edit(label, create_file)
+            })
+            .collect();
         res.borrow_mut().push(Diagnostic {
             range: sema.diagnostics_range(d).range,
             message: d.message(),
             severity: Severity::Error,
-            fix: Some(fix),
+            unused: false,
+            fixes,
+            related: Vec::new(),
+            code: d.code(),
+        })
+    })
+    .on::<hir::diagnostics::InactiveCode, _>(|d| {
+        res.borrow_mut().push(Diagnostic {
+            range: sema.diagnostics_range(d).range,
+            message: d.message(),
+            severity: Severity::Hint,
+            unused: true,
+            fixes: Vec::new(),
+            related: Vec::new(),
+            code: d.code(),
         })
     })
     .on::<hir::diagnostics::MissingFields, _>(|d| {
@@ -81,10 +155,19 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
         let fix = if d.missed_fields.iter().any(|it| it.as_tuple_index().is_some()) {
             None
         } else {
+            let field_types = missing_field_types(&sema, file_id, d);
+            let famous_defs = field_types.first().map(|(field, _)| {
+                let krate = field.parent_def(db).module(db).krate();
+                FamousDefs(&sema, krate)
+            });
+            let default_trait = famous_defs.as_ref().and_then(|it| it.core_default_Default());
+            let string_struct = famous_defs.as_ref().and_then(|it| it.alloc_string_String());
             let mut field_list = d.ast(db);
             for f in d.missed_fields.iter() {
-                let field =
-                    make::record_field(make::name_ref(&f.to_string()), Some(make::expr_unit()));
+                let ty = field_types.iter().find(|(field, _)| field.name(db) == *f);
+                let placeholder =
+                    field_placeholder(db, default_trait, string_struct, ty.map(|(_, ty)| ty));
+                let field = make::record_field(make::name_ref(&f.to_string()), Some(placeholder));
                 field_list = field_list.append_field(&field);
             }
 
@@ -102,7 +185,54 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
             range: sema.diagnostics_range(d).range,
             message: d.message(),
             severity: Severity::Error,
-            fix,
+            unused: false,
+            fixes: fix.into_iter().collect(),
+            related: missing_fields_related(&sema, file_id, d),
+            code: d.code(),
+        })
+    })
+    .on::<hir::diagnostics::NoSuchField, _>(|d| {
+        res.borrow_mut().push(Diagnostic {
+            range: sema.diagnostics_range(d).range,
+            message: d.message(),
+            severity: Severity::Error,
+            unused: false,
+            fixes: create_field_fix(&sema, file_id, d).into_iter().collect(),
+            related: Vec::new(),
+            code: d.code(),
+        })
+    })
+    .on::<hir::diagnostics::UnresolvedName, _>(|d| {
+        res.borrow_mut().push(Diagnostic {
+            range: sema.diagnostics_range(d).range,
+            message: d.message(),
+            severity: Severity::Error,
+            unused: false,
+            fixes: auto_import_fixes(&sema, file_id, d),
+            related: Vec::new(),
+            code: d.code(),
+        })
+    })
+    .on::<hir::diagnostics::PrivateItem, _>(|d| {
+        res.borrow_mut().push(Diagnostic {
+            range: sema.diagnostics_range(d).range,
+            message: d.message(),
+            severity: Severity::Error,
+            unused: false,
+            fixes: change_visibility_fix(&sema, file_id, d).into_iter().collect(),
+            related: Vec::new(),
+            code: d.code(),
+        })
+    })
+    .on::<hir::diagnostics::UnresolvedMacroCall, _>(|d| {
+        res.borrow_mut().push(Diagnostic {
+            range: sema.diagnostics_range(d).range,
+            message: d.message(),
+            severity: Severity::Error,
+            unused: false,
+            fixes: similar_macro_fixes(&sema, file_id, d),
+            related: Vec::new(),
+            code: d.code(),
         })
     })
     .on::<hir::diagnostics::MissingMatchArms, _>(|d| {
@@ -110,7 +240,23 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
             range: sema.diagnostics_range(d).range,
             message: d.message(),
             severity: Severity::Error,
-            fix: None,
+            unused: false,
+            fixes: fill_match_arms_fix(&sema, file_id, d).into_iter().collect(),
+            related: Vec::new(),
+            code: d.code(),
+        })
+    })
+    .on::<hir::diagnostics::TypeMismatch, _>(|d| {
+        res.borrow_mut().push(Diagnostic {
+            range: sema.diagnostics_range(d).range,
+            message: d.message(),
+            // Inference is still incomplete enough for this to have false
+            // positives, so it isn't reported as an error by default.
+            severity: Severity::WeakWarning,
+            unused: false,
+            fixes: Vec::new(),
+            related: type_mismatch_related(&sema, file_id, d),
+            code: d.code(),
         })
     })
     .on::<hir::diagnostics::MissingOkInTailExpr, _>(|d| {
@@ -122,14 +268,501 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
             range: sema.diagnostics_range(d).range,
             message: d.message(),
             severity: Severity::Error,
-            fix: Some(fix),
+            unused: false,
+            fixes: vec![fix],
+            related: Vec::new(),
+            code: d.code(),
+        })
+    })
+    .on::<hir::diagnostics::MissingSomeInTailExpr, _>(|d| {
+        let node = d.ast(db);
+        let replacement = format!("Some({})", node.syntax());
+        let edit = TextEdit::replace(node.syntax().text_range(), replacement);
+        let fix = SourceChange::source_file_edit_from("Wrap with Some", file_id, edit);
+        res.borrow_mut().push(Diagnostic {
+            range: sema.diagnostics_range(d).range,
+            message: d.message(),
+            severity: Severity::Error,
+            unused: false,
+            fixes: vec![fix],
+            related: Vec::new(),
+            code: d.code(),
         })
     });
     if let Some(m) = sema.to_module_def(file_id) {
         m.diagnostics(db, &mut sink);
     };
     drop(sink);
-    res.into_inner()
+    let mut res = res.into_inner();
+    res.retain(|it| !config.disabled.contains(it.code));
+    res
+}
+
+/// Merges the fixes which can be applied without asking the user into a single
+/// change. These are the fixes of diagnostics that have exactly one fix, which
+/// only edits this file. Fixes overlapping an already merged one are skipped.
+pub(crate) fn fix_all(
+    db: &RootDatabase,
+    config: &DiagnosticsConfig,
+    file_id: FileId,
+) -> Option<SourceChange> {
+    let _p = profile("fix_all");
+    let mut atoms: Vec<AtomTextEdit> = Vec::new();
+    for d in diagnostics(db, config, file_id) {
+        let mut fixes = d.fixes;
+        if fixes.len() != 1 {
+            continue;
+        }
+        let fix = fixes.pop().unwrap();
+        if !fix.file_system_edits.is_empty()
+            || fix.source_file_edits.iter().any(|it| it.file_id != file_id)
+        {
+            continue;
+        }
+        let fix_atoms = fix.source_file_edits.iter().flat_map(|it| it.edit.as_atoms());
+        if fix_atoms.clone().any(|new| atoms.iter().any(|old| overlaps(old, new))) {
+            continue;
+        }
+        atoms.extend(fix_atoms.cloned());
+    }
+    if atoms.is_empty() {
+        return None;
+    }
+
+    let mut builder = TextEditBuilder::default();
+    for atom in atoms {
+        builder.replace(atom.delete, atom.insert);
+    }
+    Some(SourceChange::source_file_edit_from("Fix all", file_id, builder.finish()))
+}
+
+fn overlaps(a: &AtomTextEdit, b: &AtomTextEdit) -> bool {
+    a.delete.start() < b.delete.end() && b.delete.start() < a.delete.end()
+}
+
+fn missing_field_types(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    d: &hir::diagnostics::MissingFields,
+) -> Vec<(Field, Type)> {
+    if d.file != file_id.into() {
+        return Vec::new();
+    }
+    let field_list = d.field_list.to_node(sema.parse(file_id).syntax());
+    match field_list.syntax().parent().and_then(ast::RecordLit::cast) {
+        Some(record_lit) => sema.record_literal_missing_fields(&record_lit),
+        None => Vec::new(),
+    }
+}
+
+/// Picks the value a missing field is initialized with: zero for integers, an
+/// empty `String`, `Default::default()` if the type implements `Default`, and
+/// `todo!()` otherwise.
+fn field_placeholder(
+    db: &RootDatabase,
+    default_trait: Option<Trait>,
+    string_struct: Option<Struct>,
+    ty: Option<&Type>,
+) -> ast::Expr {
+    let ty = match ty {
+        Some(ty) if !ty.is_unknown() => ty,
+        _ => return make::expr_todo(),
+    };
+    let call = |qualifier: &str, name: &str| {
+        let qualifier = make::path_unqualified(make::path_segment(make::name_ref(qualifier)));
+        let path = make::path_qualified(qualifier, make::path_segment(make::name_ref(name)));
+        make::expr_call(make::expr_path(path), make::arg_list(iter::empty()))
+    };
+    let is_string = match (ty.as_adt(), string_struct) {
+        (Some(Adt::Struct(it)), Some(string)) => it == string,
+        _ => false,
+    };
+    if ty.is_integral() {
+        make::expr_literal("0").into()
+    } else if is_string {
+        call("String", "new")
+    } else if default_trait.map_or(false, |it| ty.impls_trait(db, it, &[])) {
+        call("Default", "default")
+    } else {
+        make::expr_todo()
+    }
+}
+
+/// Points at the definition of the struct or variant whose fields are missing.
+fn missing_fields_related(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    d: &hir::diagnostics::MissingFields,
+) -> Vec<(FileId, TextRange, String)> {
+    let mut res = Vec::new();
+    if d.file != file_id.into() {
+        return res;
+    }
+    let db = sema.db;
+    let field_list = d.field_list.to_node(sema.parse(file_id).syntax());
+    let record_lit = match field_list.syntax().parent().and_then(ast::RecordLit::cast) {
+        Some(it) => it,
+        None => return res,
+    };
+    let variant = match sema.record_literal_missing_fields(&record_lit).first() {
+        Some((field, _)) => field.parent_def(db),
+        None => return res,
+    };
+    let src = match variant {
+        VariantDef::Struct(it) => it.source(db).map(|it| it.name()),
+        VariantDef::Union(it) => it.source(db).map(|it| it.name()),
+        VariantDef::EnumVariant(it) => it.source(db).map(|it| it.name()),
+    };
+    if let Some(name) = &src.value {
+        let range = original_range(db, src.with_value(name.syntax()));
+        let message = format!("`{}` defined here", variant.name(db));
+        res.push((range.file_id, range.range, message));
+    }
+    res
+}
+
+/// Points at the type annotation which the mismatched expression is checked
+/// against, if it's a `let` annotation or the return type of a function.
+fn type_mismatch_related(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    d: &hir::diagnostics::TypeMismatch,
+) -> Vec<(FileId, TextRange, String)> {
+    let mut res = Vec::new();
+    if d.file != file_id.into() {
+        return res;
+    }
+    let expr = d.expr.to_node(sema.parse(file_id).syntax());
+
+    if let Some(let_stmt) = expr.syntax().parent().and_then(ast::LetStmt::cast) {
+        if let (Some(init), Some(ty)) = (let_stmt.initializer(), let_stmt.ascribed_type()) {
+            if init.syntax() == expr.syntax() {
+                res.push((file_id, ty.syntax().text_range(), "expected due to this".to_string()));
+            }
+        }
+        return res;
+    }
+
+    let mut node = expr.syntax().clone();
+    while let Some(block) = node.parent().and_then(ast::BlockExpr::cast) {
+        match block.expr() {
+            Some(tail) if tail.syntax() == &node => node = block.syntax().clone(),
+            _ => break,
+        }
+    }
+    let is_returned = node.parent().and_then(ast::FnDef::cast).is_some()
+        || expr.syntax().parent().and_then(ast::ReturnExpr::cast).is_some();
+    if !is_returned {
+        return res;
+    }
+    let fn_def = expr
+        .syntax()
+        .ancestors()
+        .take_while(|it| ast::LambdaExpr::cast(it.clone()).is_none())
+        .find_map(ast::FnDef::cast);
+    if let Some(ret_type) = fn_def.and_then(|it| it.ret_type()) {
+        let message = format!("expected `{}` because of return type", d.expected);
+        res.push((file_id, ret_type.syntax().text_range(), message));
+    }
+    res
+}
+
+/// Adds the field a record literal initializes to the definition of the struct,
+/// with the type of the initializer.
+fn create_field_fix(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    d: &hir::diagnostics::NoSuchField,
+) -> Option<SourceChange> {
+    if d.file != file_id.into() {
+        return None;
+    }
+    let db = sema.db;
+    let record_field = d.field.to_node(sema.parse(file_id).syntax());
+    let record_lit = record_field.syntax().ancestors().find_map(ast::RecordLit::cast)?;
+    let strukt = match sema.resolve_path(&record_lit.path()?)? {
+        PathResolution::Def(ModuleDef::Adt(Adt::Struct(it))) => it,
+        PathResolution::SelfType(impl_def) => match impl_def.target_ty(db).as_adt()? {
+            Adt::Struct(it) => it,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let strukt_src = strukt.source(db);
+    if strukt_src.file_id.is_macro_file() {
+        return None;
+    }
+    let field_def_list = strukt_src.value.record_field_def_list()?;
+
+    let name = record_field.field_name()?;
+    let ty = match record_field.expr().and_then(|it| sema.type_of_expr(&it)) {
+        Some(ty) if !ty.is_unknown() => ty.display(db).to_string(),
+        _ => "()".to_string(),
+    };
+    let visibility = if sema.scope(record_lit.syntax()).module() == Some(strukt.module(db)) {
+        ""
+    } else {
+        "pub(crate) "
+    };
+    let new_field = format!("{}{}: {}", visibility, name, ty);
+
+    let edit = match field_def_list.fields().last() {
+        Some(last_field) => {
+            let indent = "    ".repeat(IndentLevel::from_node(last_field.syntax()).0 as usize);
+            let comma = last_field
+                .syntax()
+                .siblings_with_tokens(Direction::Next)
+                .skip(1)
+                .find(|it| !it.kind().is_trivia())
+                .filter(|it| it.kind() == T![,]);
+            match comma {
+                Some(comma) => TextEdit::insert(
+                    comma.text_range().end(),
+                    format!("\n{}{},", indent, new_field),
+                ),
+                None => TextEdit::insert(
+                    last_field.syntax().text_range().end(),
+                    format!(",\n{}{}", indent, new_field),
+                ),
+            }
+        }
+        None => {
+            let indent = IndentLevel::from_node(strukt_src.value.syntax()).0 as usize;
+            TextEdit::replace(
+                field_def_list.syntax().text_range(),
+                format!(
+                    "{{\n{}{},\n{}}}",
+                    "    ".repeat(indent + 1),
+                    new_field,
+                    "    ".repeat(indent)
+                ),
+            )
+        }
+    };
+    let def_file_id = strukt_src.file_id.original_file(db);
+    Some(SourceChange::source_file_edit_from("Create field", def_file_id, edit))
+}
+
+/// Offers to import each item found in the symbol index which the unresolved
+/// path could refer to.
+fn auto_import_fixes(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    d: &hir::diagnostics::UnresolvedName,
+) -> Vec<SourceChange> {
+    if d.file != file_id.into() {
+        return Vec::new();
+    }
+    let path = match d.expr.to_node(sema.parse(file_id).syntax()) {
+        ast::Expr::PathExpr(it) => it.path(),
+        _ => None,
+    };
+    let path = match path {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    import_fixes(sema, &path)
+        .into_iter()
+        .map(|fix| {
+            let label = format!("Import `{}`", fix.import);
+            SourceChange::source_file_edit_from(label, file_id, fix.edit)
+        })
+        .collect()
+}
+
+/// Replaces the path of the macro with the paths of similarly named macros,
+/// which are either in scope or exported by a dependency.
+fn similar_macro_fixes(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    d: &hir::diagnostics::UnresolvedMacroCall,
+) -> Vec<SourceChange> {
+    if d.file != file_id.into() {
+        return Vec::new();
+    }
+    let path = d.path.to_node(sema.parse(file_id).syntax());
+    let name = match path.segment().and_then(|it| it.name_ref()) {
+        Some(it) => it.text().to_string(),
+        None => return Vec::new(),
+    };
+    let is_similar =
+        |candidate: &str| edit_distance(&name, candidate) <= std::cmp::max(1, name.len() / 3);
+
+    let scope = sema.scope(path.syntax());
+    let mut candidates = BTreeSet::new();
+    scope.process_all_names(&mut |it, def| {
+        if let ScopeDef::MacroDef(_) = def {
+            if is_similar(&it.to_string()) {
+                candidates.insert(it.to_string());
+            }
+        }
+    });
+    if let Some(module) = scope.module() {
+        for dep in module.krate().dependencies(sema.db) {
+            let root = match dep.krate.root_module(sema.db) {
+                Some(it) => it,
+                None => continue,
+            };
+            for (it, def) in root.scope(sema.db, Some(module)) {
+                if let ScopeDef::MacroDef(_) = def {
+                    if is_similar(&it.to_string()) {
+                        candidates.insert(format!("{}::{}", dep.name, it));
+                    }
+                }
+            }
+        }
+    }
+
+    let range = path.syntax().text_range();
+    candidates
+        .into_iter()
+        .filter(|it| *it != d.name)
+        .map(|it| {
+            let label = format!("Replace with `{}`", it);
+            SourceChange::source_file_edit_from(label, file_id, TextEdit::replace(range, it))
+        })
+        .collect()
+}
+
+/// The number of single character insertions, deletions or substitutions
+/// needed to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut curr = vec![i + 1; b.len() + 1];
+        for (j, &b_char) in b.iter().enumerate() {
+            let substitution = prev[j] + if a_char == b_char { 0 } else { 1 };
+            curr[j + 1] = substitution.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        prev = curr;
+    }
+    prev[b.len()]
+}
+
+/// Makes the item referred to by the path visible in the whole crate. The item
+/// is usually defined in another file than the one with the diagnostic.
+fn change_visibility_fix(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    d: &hir::diagnostics::PrivateItem,
+) -> Option<SourceChange> {
+    if d.file != file_id.into() {
+        return None;
+    }
+    let path = match d.expr.to_node(sema.parse(file_id).syntax()) {
+        ast::Expr::PathExpr(it) => it.path()?,
+        _ => return None,
+    };
+    let def = match sema.resolve_path(&path)? {
+        PathResolution::Def(it) => it,
+        _ => return None,
+    };
+    // The visibility of items in other crates can't be fixed with `pub(crate)`.
+    if def.module(sema.db)?.krate() != sema.to_module_def(file_id)?.krate() {
+        return None;
+    }
+    let (item_file, vis, item) = match def {
+        ModuleDef::Function(it) => {
+            let src = it.source(sema.db);
+            (src.file_id, src.value.visibility(), src.value.syntax().clone())
+        }
+        ModuleDef::Const(it) => {
+            let src = it.source(sema.db);
+            (src.file_id, src.value.visibility(), src.value.syntax().clone())
+        }
+        _ => return None,
+    };
+    if item_file.is_macro_file() {
+        return None;
+    }
+
+    let edit = match vis {
+        Some(vis) => TextEdit::replace(vis.syntax().text_range(), "pub(crate)".to_string()),
+        None => {
+            let offset = item
+                .children_with_tokens()
+                .find(|it| !matches!(it.kind(), WHITESPACE | COMMENT | ATTR))?
+                .text_range()
+                .start();
+            TextEdit::insert(offset, "pub(crate) ".to_string())
+        }
+    };
+    Some(SourceChange::source_file_edit_from(
+        "Change visibility to pub(crate)",
+        item_file.original_file(sema.db),
+        edit,
+    ))
+}
+
+/// Appends an arm with a `todo!()` body for each variant of the matched enum
+/// which isn't matched by any of the existing arms.
+fn fill_match_arms_fix(
+    sema: &Semantics<RootDatabase>,
+    file_id: FileId,
+    d: &hir::diagnostics::MissingMatchArms,
+) -> Option<SourceChange> {
+    if d.file != file_id.into() {
+        return None;
+    }
+    let db = sema.db;
+    let match_arm_list = d.arms.to_node(sema.parse(file_id).syntax());
+    let match_expr = ast::MatchExpr::cast(match_arm_list.syntax().parent()?)?;
+    let expr = match_expr.expr()?;
+    let enum_def = sema.type_of_expr(&expr)?.autoderef(db).find_map(|ty| match ty.as_adt() {
+        Some(Adt::Enum(it)) => Some(it),
+        _ => None,
+    })?;
+    let module = sema.scope(expr.syntax()).module()?;
+
+    let matched_variants = match_arm_list
+        .arms()
+        .filter_map(|arm| arm.pat())
+        .flat_map(|pat| match pat {
+            ast::Pat::OrPat(pats) => pats.pats().collect(),
+            _ => vec![pat],
+        })
+        .filter_map(|pat| pat_variant(sema, &pat))
+        .collect::<FxHashSet<_>>();
+    let missing_arms = enum_def
+        .variants(db)
+        .into_iter()
+        .filter(|variant| !matched_variants.contains(variant))
+        .filter_map(|variant| variant_pat(db, module, variant))
+        .map(|pat| make::match_arm(iter::once(pat), make::expr_todo()))
+        .collect::<Vec<_>>();
+    if missing_arms.is_empty() {
+        return None;
+    }
+
+    let new_match_arm_list = match_arm_list.append_arms(missing_arms);
+    let mut builder = TextEditBuilder::default();
+    algo::diff(match_arm_list.syntax(), new_match_arm_list.syntax()).into_text_edit(&mut builder);
+    Some(SourceChange::source_file_edit_from("Fill match arms", file_id, builder.finish()))
+}
+
+/// The enum variant matched by the head of `pat`, however the variant is
+/// spelled there.
+fn pat_variant(sema: &Semantics<RootDatabase>, pat: &ast::Pat) -> Option<hir::EnumVariant> {
+    let def = match pat {
+        ast::Pat::BindPat(it) => sema.resolve_bind_pat_to_const(it)?,
+        _ => {
+            let path = match pat {
+                ast::Pat::PathPat(it) => it.path()?,
+                ast::Pat::TupleStructPat(it) => it.path()?,
+                ast::Pat::RecordPat(it) => it.path()?,
+                _ => return None,
+            };
+            match sema.resolve_path(&path)? {
+                PathResolution::Def(it) => it,
+                _ => return None,
+            }
+        }
+    };
+    match def {
+        ModuleDef::EnumVariant(it) => Some(it),
+        _ => None,
+    }
 }
 
 fn check_unnecessary_braces_in_use_statement(
@@ -140,41 +773,46 @@ fn check_unnecessary_braces_in_use_statement(
     let use_tree_list = ast::UseTreeList::cast(node.clone())?;
     if let Some((single_use_tree,)) = use_tree_list.use_trees().collect_tuple() {
         let range = use_tree_list.syntax().text_range();
-        let edit =
-            text_edit_for_remove_unnecessary_braces_with_self_in_use_statement(&single_use_tree)
-                .unwrap_or_else(|| {
-                    let to_replace = single_use_tree.syntax().text().to_string();
-                    let mut edit_builder = TextEditBuilder::default();
-                    edit_builder.delete(range);
-                    edit_builder.insert(range.start(), to_replace);
-                    edit_builder.finish()
-                });
+        let mut editor = SyntaxEditor::new(node.parent()?);
+        if !remove_unnecessary_braces_with_self_in_use_statement(&mut editor, &use_tree_list) {
+            editor.replace(use_tree_list.syntax().clone(), single_use_tree.syntax().clone());
+        }
 
         acc.push(Diagnostic {
             range,
             message: "Unnecessary braces in use statement".to_string(),
             severity: Severity::WeakWarning,
-            fix: Some(SourceChange::source_file_edit(
+            unused: false,
+            fixes: vec![SourceChange::source_file_edit(
                 "Remove unnecessary braces",
-                SourceFileEdit { file_id, edit },
-            )),
+                SourceFileEdit { file_id, edit: editor.into_text_edit() },
+            )],
+            related: Vec::new(),
+            code: "unnecessary-braces-in-use-statement",
         });
     }
 
     Some(())
 }
 
-fn text_edit_for_remove_unnecessary_braces_with_self_in_use_statement(
-    single_use_tree: &ast::UseTree,
-) -> Option<TextEdit> {
-    let use_tree_list_node = single_use_tree.syntax().parent()?;
-    if single_use_tree.path()?.segment()?.syntax().first_child_or_token()?.kind() == T![self] {
-        let start = use_tree_list_node.prev_sibling_or_token()?.text_range().start();
-        let end = use_tree_list_node.text_range().end();
-        let range = TextRange::new(start, end);
-        return Some(TextEdit::delete(range));
+/// Turns `use a::{self};` into `use a;`.
+fn remove_unnecessary_braces_with_self_in_use_statement(
+    editor: &mut SyntaxEditor,
+    use_tree_list: &ast::UseTreeList,
+) -> bool {
+    let is_self = |tree: ast::UseTree| -> Option<bool> {
+        Some(tree.path()?.segment()?.syntax().first_child_or_token()?.kind() == T![self])
+    };
+    let coloncolon = match use_tree_list.syntax().prev_sibling_or_token() {
+        Some(it) if it.kind() == T![::] => it,
+        _ => return false,
+    };
+    if use_tree_list.use_trees().next().and_then(is_self) != Some(true) {
+        return false;
     }
-    None
+    editor.detach(coloncolon);
+    editor.detach(use_tree_list.syntax().clone());
+    true
 }
 
 fn check_struct_shorthand_initialization(
@@ -198,10 +836,13 @@ fn check_struct_shorthand_initialization(
                     range: record_field.syntax().text_range(),
                     message: "Shorthand struct initialization".to_string(),
                     severity: Severity::WeakWarning,
-                    fix: Some(SourceChange::source_file_edit(
+                    unused: false,
+                    fixes: vec![SourceChange::source_file_edit(
                         "Use struct shorthand initialization",
                         SourceFileEdit { file_id, edit },
-                    )),
+                    )],
+                    related: Vec::new(),
+                    code: "struct-shorthand-initialization",
                 });
             }
         }
@@ -209,6 +850,90 @@ fn check_struct_shorthand_initialization(
     Some(())
 }
 
+fn check_try_macro(acc: &mut Vec<Diagnostic>, file_id: FileId, node: &SyntaxNode) -> Option<()> {
+    let macro_call = ast::MacroCall::cast(node.clone())?;
+    let replacement = try_macro_to_question_mark(&macro_call)?;
+    let range = macro_call.syntax().text_range();
+    acc.push(Diagnostic {
+        range,
+        message: "Use of deprecated `try!` macro".to_string(),
+        severity: Severity::WeakWarning,
+        unused: false,
+        fixes: vec![SourceChange::source_file_edit_from(
+            "Replace with `?`",
+            file_id,
+            TextEdit::replace(range, replacement),
+        )],
+        related: Vec::new(),
+        code: "deprecated-try-macro",
+    });
+    Some(())
+}
+
+/// Rewrites `try!(expr)` to `expr?`, also rewriting the `try!` calls nested in
+/// `expr`. Returns `None` if `macro_call` is not a well-formed `try!` call.
+fn try_macro_to_question_mark(macro_call: &ast::MacroCall) -> Option<String> {
+    let path = macro_call.path()?;
+    if path.qualifier().is_some() || macro_call.excl_token().is_none() {
+        return None;
+    }
+    let name = path.segment()?.name_ref()?;
+    if name.text() != "try" && name.text() != "r#try" {
+        return None;
+    }
+
+    let token_tree = macro_call.token_tree()?;
+    let l_delim = token_tree.syntax().first_token()?;
+    let r_delim = token_tree.syntax().last_token()?;
+    if !matches!(r_delim.kind(), T![')'] | T![']'] | T!['}']) || l_delim == r_delim {
+        return None;
+    }
+    let arg_range = TextRange::new(
+        l_delim.text_range().end() - token_tree.syntax().text_range().start(),
+        r_delim.text_range().start() - token_tree.syntax().text_range().start(),
+    );
+    let arg = token_tree.syntax().text().to_string()[arg_range].trim().to_string();
+    let expr = make::try_expr_from_text(&arg)?;
+    if expr.syntax().text() != arg.as_str() {
+        return None;
+    }
+
+    let expr_text = rewrite_nested_try_macros(&expr);
+    let res = match expr {
+        ast::Expr::PathExpr(_)
+        | ast::Expr::CallExpr(_)
+        | ast::Expr::MethodCallExpr(_)
+        | ast::Expr::FieldExpr(_)
+        | ast::Expr::IndexExpr(_)
+        | ast::Expr::TryExpr(_)
+        | ast::Expr::ParenExpr(_)
+        | ast::Expr::TupleExpr(_)
+        | ast::Expr::ArrayExpr(_)
+        | ast::Expr::Literal(_)
+        | ast::Expr::MacroCall(_) => format!("{}?", expr_text),
+        _ => format!("({})?", expr_text),
+    };
+    Some(res)
+}
+
+fn rewrite_nested_try_macros(expr: &ast::Expr) -> String {
+    let text = expr.syntax().text().to_string();
+    let start = expr.syntax().text_range().start();
+    let mut builder = TextEditBuilder::default();
+    let mut rewritten: Vec<TextRange> = Vec::new();
+    for macro_call in expr.syntax().descendants().filter_map(ast::MacroCall::cast) {
+        let range = macro_call.syntax().text_range();
+        if rewritten.iter().any(|it| it.contains_range(range)) {
+            continue;
+        }
+        if let Some(replacement) = try_macro_to_question_mark(&macro_call) {
+            builder.replace(range - start, replacement);
+            rewritten.push(range);
+        }
+    }
+    builder.finish().apply(&text)
+}
+
 #[cfg(test)]
 mod tests {
     use insta::assert_debug_snapshot;
@@ -237,9 +962,9 @@ mod tests {
         for node in parse.tree().syntax().descendants() {
             func(&mut diagnostics, FileId(0), &node);
         }
-        let diagnostic =
+        let mut diagnostic =
             diagnostics.pop().unwrap_or_else(|| panic!("no diagnostics for:\n{}\n", before));
-        let mut fix = diagnostic.fix.unwrap();
+        let mut fix = diagnostic.fixes.pop().unwrap();
         let edit = fix.source_file_edits.pop().unwrap().edit;
         let actual = edit.apply(&before);
         assert_eq_text!(after, &actual);
@@ -252,8 +977,12 @@ mod tests {
     ///  * that the contents of the file containing the cursor match `after` after the diagnostic fix is applied
     fn check_apply_diagnostic_fix_from_position(fixture: &str, after: &str) {
         let (analysis, file_position) = analysis_and_position(fixture);
-        let diagnostic = analysis.diagnostics(file_position.file_id).unwrap().pop().unwrap();
-        let mut fix = diagnostic.fix.unwrap();
+        let mut diagnostic = analysis
+            .diagnostics(&DiagnosticsConfig::default(), file_position.file_id)
+            .unwrap()
+            .pop()
+            .unwrap();
+        let mut fix = diagnostic.fixes.pop().unwrap();
         let edit = fix.source_file_edits.pop().unwrap().edit;
         let target_file_contents = analysis.file_text(file_position.file_id).unwrap();
         let actual = edit.apply(&target_file_contents);
@@ -285,24 +1014,28 @@ mod tests {
 
     fn check_apply_diagnostic_fix(before: &str, after: &str) {
         let (analysis, file_id) = single_file(before);
-        let diagnostic = analysis.diagnostics(file_id).unwrap().pop().unwrap();
-        let mut fix = diagnostic.fix.unwrap();
+        let mut diagnostic =
+            analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap().pop().unwrap();
+        let mut fix = diagnostic.fixes.pop().unwrap();
         let edit = fix.source_file_edits.pop().unwrap().edit;
         let actual = edit.apply(&before);
         assert_eq_text!(after, &actual);
     }
 
     /// Takes a multi-file input fixture with annotated cursor position and checks that no diagnostics
-    /// apply to the file containing the cursor.
-    fn check_no_diagnostic_for_target_file(fixture: &str) {
+    /// apply to the file containing the cursor, except for those with one of the `expected_codes`.
+    fn check_no_diagnostic_for_target_file(fixture: &str, expected_codes: &[&str]) {
         let (analysis, file_position) = analysis_and_position(fixture);
-        let diagnostics = analysis.diagnostics(file_position.file_id).unwrap();
-        assert_eq!(diagnostics.len(), 0);
+        let diagnostics =
+            analysis.diagnostics(&DiagnosticsConfig::default(), file_position.file_id).unwrap();
+        let unexpected =
+            diagnostics.iter().filter(|it| !expected_codes.contains(&it.code)).collect::<Vec<_>>();
+        assert_eq!(unexpected.len(), 0, "{:#?}", unexpected);
     }
 
     fn check_no_diagnostic(content: &str) {
         let (analysis, file_id) = single_file(content);
-        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        let diagnostics = analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap();
         assert_eq!(diagnostics.len(), 0, "expected no diagnostic, found one");
     }
 
@@ -408,6 +1141,37 @@ mod tests {
         check_apply_diagnostic_fix_from_position(before, after);
     }
 
+    #[test]
+    fn test_wrap_return_type_option() {
+        let before = r#"
+            //- /main.rs
+            use std::option::Option::{self, Some, None};
+
+            fn div(x: i32, y: i32) -> Option<i32> {
+                if y == 0 {
+                    return None;
+                }
+                x / y<|>
+            }
+
+            //- /std/lib.rs
+            pub mod option {
+                pub enum Option<T> { Some(T), None }
+            }
+        "#;
+        let after = r#"
+            use std::option::Option::{self, Some, None};
+
+            fn div(x: i32, y: i32) -> Option<i32> {
+                if y == 0 {
+                    return None;
+                }
+                Some(x / y)
+            }
+        "#;
+        check_apply_diagnostic_fix_from_position(before, after);
+    }
+
     #[test]
     fn test_wrap_return_type_not_applicable_when_expr_type_does_not_match_ok_type() {
         let content = r#"
@@ -426,7 +1190,7 @@ mod tests {
                 pub enum Result<T, E> { Ok(T), Err(E) }
             }
         "#;
-        check_no_diagnostic_for_target_file(content);
+        check_no_diagnostic_for_target_file(content, &["type-mismatch"]);
     }
 
     #[test]
@@ -452,7 +1216,268 @@ mod tests {
                 pub enum Result<T, E> { Ok(T), Err(E) }
             }
         "#;
-        check_no_diagnostic_for_target_file(content);
+        check_no_diagnostic_for_target_file(content, &["type-mismatch"]);
+    }
+
+    #[test]
+    fn test_type_mismatch_range_out_of_macro() {
+        let text = r"
+            macro_rules! id {
+                ($($tt:tt)*) => { $($tt)* };
+            }
+
+            fn main() {
+                let _x: bool = id![92u32];
+            }
+        ";
+        let (analysis, file_id) = single_file(text);
+        let diagnostics = analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "type-mismatch");
+        assert_eq!(diagnostics[0].message, "Mismatched types: expected `bool`, found `u32`");
+        assert_eq!(diagnostics[0].severity, Severity::WeakWarning);
+        assert_eq!(&text[diagnostics[0].range], "92u32");
+    }
+
+    #[test]
+    fn test_type_mismatch_related() {
+        let text = r"
+            fn foo() -> u32 {
+                let x: bool = 92u32;
+                { false }
+            }
+        ";
+        let (analysis, file_id) = single_file(text);
+        let diagnostics = analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap();
+        let related = diagnostics
+            .iter()
+            .flat_map(|d| d.related.iter())
+            .map(|(_, range, message)| (&text[*range], message.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            related,
+            vec![
+                ("bool", "expected due to this"),
+                ("-> u32", "expected `u32` because of return type"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_fields_related() {
+        let text = r"
+            struct S { foo: i32, bar: () }
+
+            fn main() {
+                S { foo: 92 };
+            }
+        ";
+        let (analysis, file_id) = single_file(text);
+        let diagnostics = analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        let (related_file_id, range, message) = &diagnostics[0].related[0];
+        assert_eq!(*related_file_id, file_id);
+        assert_eq!(&text[*range], "S");
+        assert_eq!(message, "`S` defined here");
+    }
+
+    #[test]
+    fn test_create_field() {
+        let before = r"
+            struct S {
+                foo: i32,
+            }
+
+            fn main() {
+                S { foo: 92, bar: 1u8 };
+            }
+        ";
+        let after = r"
+            struct S {
+                foo: i32,
+                bar: u8,
+            }
+
+            fn main() {
+                S { foo: 92, bar: 1u8 };
+            }
+        ";
+        check_apply_diagnostic_fix(before, after);
+    }
+
+    #[test]
+    fn test_create_field_in_other_file() {
+        let (analysis, file_position) = analysis_and_position(
+            r"
+            //- /main.rs
+            mod foo;
+
+            fn main() {
+                foo::S { <|>bar: true };
+            }
+
+            //- /foo.rs
+            pub struct S {}
+            ",
+        );
+        let mut diagnostic = analysis
+            .diagnostics(&DiagnosticsConfig::default(), file_position.file_id)
+            .unwrap()
+            .into_iter()
+            .find(|it| it.code == "no-such-field")
+            .unwrap();
+        let edit = diagnostic.fixes.pop().unwrap().source_file_edits.pop().unwrap();
+        assert_ne!(edit.file_id, file_position.file_id);
+        let actual = edit.edit.apply(&analysis.file_text(edit.file_id).unwrap());
+        assert_eq_text!("pub struct S {\n    pub(crate) bar: bool,\n}\n", &actual);
+    }
+
+    #[test]
+    fn test_auto_import() {
+        let before = r"
+            mod foo { pub fn bar() {} }
+
+            fn main() {
+                bar();
+            }
+        ";
+        let after = r"
+            use foo::bar;
+
+            mod foo { pub fn bar() {} }
+
+            fn main() {
+                bar();
+            }
+        ";
+        check_apply_diagnostic_fix(before, after);
+    }
+
+    #[test]
+    fn test_auto_import_merges_use_trees() {
+        let before = r"
+            use foo::baz;
+
+            mod foo { pub fn bar() {} pub fn baz() {} }
+
+            fn main() {
+                baz();
+                bar();
+            }
+        ";
+        let after = r"
+            use foo::{bar, baz};
+
+            mod foo { pub fn bar() {} pub fn baz() {} }
+
+            fn main() {
+                baz();
+                bar();
+            }
+        ";
+        check_apply_diagnostic_fix(before, after);
+    }
+
+    #[test]
+    fn test_auto_import_offers_each_candidate() {
+        let (analysis, file_id) = single_file(
+            r"
+            mod foo { pub struct S; }
+            mod bar { pub struct S; }
+
+            fn main() {
+                S;
+            }
+            ",
+        );
+        let diagnostics = analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "unresolved-name");
+        let labels = diagnostics[0].fixes.iter().map(|it| it.label.as_str()).collect::<Vec<_>>();
+        assert_eq!(labels, vec!["Import `bar::S`", "Import `foo::S`"]);
+    }
+
+    #[test]
+    fn test_fill_match_arms() {
+        let before = r"
+            enum E { A, B(i32, i32), C { x: u8 } }
+
+            fn main() {
+                match E::A {
+                    E::A => ()
+                }
+            }
+        ";
+        let after = r"
+            enum E { A, B(i32, i32), C { x: u8 } }
+
+            fn main() {
+                match E::A {
+                    E::A => (),
+                    E::B(_, _) => todo!(),
+                    E::C { x } => todo!(),
+                }
+            }
+        ";
+        check_apply_diagnostic_fix(before, after);
+    }
+
+    #[test]
+    fn test_fill_match_arms_or_pattern() {
+        let before = r"
+            enum E { A, B, C }
+
+            fn main() {
+                match E::A {
+                    E::A | E::C => {}
+                }
+            }
+        ";
+        let after = r"
+            enum E { A, B, C }
+
+            fn main() {
+                match E::A {
+                    E::A | E::C => {}
+                    E::B => todo!(),
+                }
+            }
+        ";
+        check_apply_diagnostic_fix(before, after);
+    }
+
+    #[test]
+    fn test_fill_match_arms_resolves_existing_variants() {
+        let before = r"
+            mod m {
+                pub enum E { A, B(i32), C }
+            }
+            use m::E as F;
+            use m::E::*;
+
+            fn main() {
+                match m::E::A {
+                    A => (),
+                    F::B(_) => (),
+                }
+            }
+        ";
+        let after = r"
+            mod m {
+                pub enum E { A, B(i32), C }
+            }
+            use m::E as F;
+            use m::E::*;
+
+            fn main() {
+                match m::E::A {
+                    A => (),
+                    F::B(_) => (),
+                    C => todo!(),
+                }
+            }
+        ";
+        check_apply_diagnostic_fix(before, after);
     }
 
     #[test]
@@ -474,7 +1499,7 @@ mod tests {
             }
 
             fn test_fn() {
-                let s = TestStruct{ one: (), two: ()};
+                let s = TestStruct{ one: 0, two: 0};
             }
         ";
         check_apply_diagnostic_fix(before, after);
@@ -500,7 +1525,7 @@ mod tests {
 
             impl TestStruct {
                 fn test_fn() {
-                    let s = Self { one: ()};
+                    let s = Self { one: 0};
                 }
             }
         ";
@@ -528,7 +1553,7 @@ mod tests {
 
             impl Expr {
                 fn new_bin(lhs: Box<Expr>, rhs: Box<Expr>) -> Expr {
-                    Expr::Bin { lhs: (), rhs: () <|> }
+                    Expr::Bin { lhs: todo!(), rhs: todo!() <|> }
                 }
             }
 
@@ -555,12 +1580,68 @@ mod tests {
             }
 
             fn test_fn() {
-                let s = TestStruct{ two: 2, one: () };
+                let s = TestStruct{ two: 2, one: 0 };
             }
         ";
         check_apply_diagnostic_fix(before, after);
     }
 
+    #[test]
+    fn test_fill_struct_fields_placeholders() {
+        let before = r"
+            //- /main.rs crate:main deps:core,alloc
+            use alloc::string::String;
+            use core::default::Default;
+
+            mod fake {
+                pub struct String;
+            }
+            struct Config;
+            impl Default for Config {
+                fn default() -> Config { Config }
+            }
+            struct Other;
+
+            struct S { count: u32, name: String, fake: fake::String, config: Config, other: Other }
+
+            fn main() {
+                S {<|>};
+            }
+
+            //- /core.rs crate:core
+            pub mod default {
+                pub trait Default {
+                    fn default() -> Self;
+                }
+            }
+
+            //- /alloc.rs crate:alloc
+            pub mod string {
+                pub struct String;
+            }
+        ";
+        let after = r"
+            use alloc::string::String;
+            use core::default::Default;
+
+            mod fake {
+                pub struct String;
+            }
+            struct Config;
+            impl Default for Config {
+                fn default() -> Config { Config }
+            }
+            struct Other;
+
+            struct S { count: u32, name: String, fake: fake::String, config: Config, other: Other }
+
+            fn main() {
+                S { count: 0, name: String::new(), fake: todo!(), config: Default::default(), other: todo!()};
+            }
+        ";
+        check_apply_diagnostic_fix_from_position(before, after);
+    }
+
     #[test]
     fn test_fill_struct_fields_no_diagnostic() {
         let content = r"
@@ -586,7 +1667,7 @@ mod tests {
                 two: i64,
             }
 
-            fn test_fn() {
+            fn test_fn(a: TestStruct) {
                 let one = 1;
                 let s = TestStruct{ ..a };
             }
@@ -598,15 +1679,15 @@ mod tests {
     #[test]
     fn test_unresolved_module_diagnostic() {
         let (analysis, file_id) = single_file("mod foo;");
-        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        let diagnostics = analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap();
         assert_debug_snapshot!(diagnostics, @r###"
         [
             Diagnostic {
                 message: "unresolved module",
                 range: 0..8,
-                fix: Some(
+                fixes: [
                     SourceChange {
-                        label: "Create module",
+                        label: "Create module at `foo.rs`",
                         source_file_edits: [],
                         file_system_edits: [
                             CreateFile {
@@ -614,12 +1695,101 @@ mod tests {
                                     0,
                                 ),
                                 path: "foo.rs",
+                                contents: "",
                             },
                         ],
                         cursor_position: None,
+                        is_snippet: false,
                     },
-                ),
+                    SourceChange {
+                        label: "Create module at `foo/mod.rs`",
+                        source_file_edits: [],
+                        file_system_edits: [
+                            CreateFile {
+                                source_root: SourceRootId(
+                                    0,
+                                ),
+                                path: "foo/mod.rs",
+                                contents: "",
+                            },
+                        ],
+                        cursor_position: None,
+                        is_snippet: false,
+                    },
+                ],
+                related: [],
                 severity: Error,
+                unused: false,
+                code: "unresolved-module",
+            },
+        ]
+        "###);
+    }
+
+    #[test]
+    fn test_fix_all() {
+        let before = r#"
+            use {foo};
+
+            struct A { a: &'static str }
+            struct B { b: u32 }
+            fn main() {
+                let a = "haha";
+                let x = A { a: a };
+                let y = B {};
+            }
+        "#;
+        let after = r#"
+            use foo;
+
+            struct A { a: &'static str }
+            struct B { b: u32 }
+            fn main() {
+                let a = "haha";
+                let x = A { a };
+                let y = B { b: 0};
+            }
+        "#;
+        let (analysis, file_id) = single_file(before);
+        let fix = analysis.fix_all(&DiagnosticsConfig::default(), file_id).unwrap().unwrap();
+        assert_eq!(fix.label, "Fix all");
+        let edit = &fix.source_file_edits[0].edit;
+        assert_eq_text!(after, &edit.apply(before));
+    }
+
+    #[test]
+    fn test_fix_all_without_fixes() {
+        let (analysis, file_id) = single_file(
+            r"
+            struct A { a: u32 }
+            fn main() { A { a: 92 }; }
+            ",
+        );
+        let fix = analysis.fix_all(&DiagnosticsConfig::default(), file_id).unwrap();
+        assert!(fix.is_none());
+    }
+
+    #[test]
+    fn test_inactive_code_diagnostic() {
+        let (analysis, file_id) = single_file(
+            r"
+            #[cfg(test)]
+            fn foo() {}
+
+            fn bar() {}
+            ",
+        );
+        let diagnostics = analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap();
+        assert_debug_snapshot!(diagnostics, @r###"
+        [
+            Diagnostic {
+                message: "code is inactive due to #[cfg] directives",
+                range: 13..49,
+                fixes: [],
+                related: [],
+                severity: Hint,
+                unused: true,
+                code: "inactive-code",
             },
         ]
         "###);
@@ -647,13 +1817,13 @@ mod tests {
             }
         ",
         );
-        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        let diagnostics = analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap();
         assert_debug_snapshot!(diagnostics, @r###"
         [
             Diagnostic {
                 message: "Missing structure fields:\n- b",
                 range: 224..233,
-                fix: Some(
+                fixes: [
                     SourceChange {
                         label: "Fill struct fields",
                         source_file_edits: [
@@ -665,7 +1835,7 @@ mod tests {
                                     atoms: [
                                         AtomTextEdit {
                                             delete: 3..9,
-                                            insert: "{a:42, b: ()}",
+                                            insert: "{a:42, b: todo!()}",
                                         },
                                     ],
                                 },
@@ -673,14 +1843,39 @@ mod tests {
                         ],
                         file_system_edits: [],
                         cursor_position: None,
+                        is_snippet: false,
                     },
-                ),
+                ],
+                related: [],
                 severity: Error,
+                unused: false,
+                code: "missing-structure-fields",
             },
         ]
         "###);
     }
 
+    #[test]
+    fn test_disabled_diagnostics() {
+        let (analysis, file_id) = single_file("use a::{b};\nmod foo;");
+        let codes = |config: &DiagnosticsConfig| {
+            analysis
+                .diagnostics(config, file_id)
+                .unwrap()
+                .iter()
+                .map(|d| d.code)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            codes(&DiagnosticsConfig::default()),
+            vec!["unnecessary-braces-in-use-statement", "unresolved-module"]
+        );
+
+        let mut config = DiagnosticsConfig::default();
+        config.disabled.insert("unnecessary-braces-in-use-statement".to_string());
+        assert_eq!(codes(&config), vec!["unresolved-module"]);
+    }
+
     #[test]
     fn test_check_unnecessary_braces_in_use_statement() {
         check_not_applicable(
@@ -700,6 +1895,183 @@ mod tests {
         );
     }
 
+    fn check_change_visibility_fix(fixture: &str, after: &str) {
+        let (analysis, file_position) = analysis_and_position(fixture);
+        let diagnostic = analysis
+            .diagnostics(&DiagnosticsConfig::default(), file_position.file_id)
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(diagnostic.code, "private-item");
+        let fix = &diagnostic.fixes[0];
+        assert_eq!(fix.label, "Change visibility to pub(crate)");
+        let edit = &fix.source_file_edits[0];
+        assert_ne!(edit.file_id, file_position.file_id);
+        let text = analysis.file_text(edit.file_id).unwrap();
+        assert_eq_text!(after, &edit.edit.apply(&text));
+    }
+
+    #[test]
+    fn test_change_visibility_fix() {
+        check_change_visibility_fix(
+            r"
+            //- /main.rs
+            mod foo;
+            fn main() { foo::bar<|>(); }
+            //- /foo.rs
+            #[inline]
+            fn bar() {}
+            ",
+            "#[inline]\npub(crate) fn bar() {}\n",
+        );
+        check_change_visibility_fix(
+            r"
+            //- /main.rs
+            mod foo;
+            fn main() { foo::C<|>; }
+            //- /foo.rs
+            pub(self) const C: u32 = 0;
+            ",
+            "pub(crate) const C: u32 = 0;\n",
+        );
+    }
+
+    #[test]
+    fn test_change_visibility_fix_not_applicable_in_other_crates() {
+        let (analysis, file_position) = analysis_and_position(
+            r"
+            //- /main.rs crate:main deps:foo
+            fn main() { foo::bar<|>(); }
+            //- /foo.rs crate:foo
+            fn bar() {}
+            ",
+        );
+        let diagnostic = analysis
+            .diagnostics(&DiagnosticsConfig::default(), file_position.file_id)
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(diagnostic.code, "private-item");
+        assert!(diagnostic.fixes.is_empty());
+    }
+
+    #[test]
+    fn test_unresolved_macro_call_diagnostics() {
+        let (analysis, file_id) = single_file(
+            r"
+            macro_rules! m { () => {} }
+            mis!();
+            fn f() { m!(); n!(); }
+            ",
+        );
+        let diagnostics = analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap();
+        let messages = diagnostics.iter().map(|it| it.message.as_str()).collect::<Vec<_>>();
+        assert_eq!(messages, ["unresolved macro `mis!`", "unresolved macro `n!`"]);
+    }
+
+    #[test]
+    fn test_unresolved_macro_call_ignores_failed_expansions() {
+        check_no_diagnostic(
+            r#"
+            #[rustc_builtin_macro]
+            macro_rules! include { () => {} }
+            #[rustc_builtin_macro]
+            macro_rules! concat { () => {} }
+
+            include!("missing.rs");
+            fn f() { let _ = concat!(1, include!("missing.rs")); }
+            "#,
+        );
+    }
+
+    #[test]
+    fn test_unresolved_macro_call_fix() {
+        let before = r"
+            //- /main.rs
+            macro_rules! foo_bar { () => { 0 } }
+            fn main() { let x = fo_bar<|>!(); }
+        ";
+        let after = r"
+            macro_rules! foo_bar { () => { 0 } }
+            fn main() { let x = foo_bar!(); }
+        ";
+        check_apply_diagnostic_fix_from_position(before, after);
+    }
+
+    #[test]
+    fn test_unresolved_macro_call_fix_from_dependency() {
+        let (analysis, file_position) = analysis_and_position(
+            r"
+            //- /main.rs crate:main deps:dep
+            fn main() { frobnicate<|>!(); }
+            //- /lib.rs crate:dep
+            #[macro_export]
+            macro_rules! frobnicate { () => {} }
+            ",
+        );
+        let diagnostic = analysis
+            .diagnostics(&DiagnosticsConfig::default(), file_position.file_id)
+            .unwrap()
+            .pop()
+            .unwrap();
+        let labels = diagnostic.fixes.iter().map(|it| it.label.as_str()).collect::<Vec<_>>();
+        assert_eq!(labels, ["Replace with `dep::frobnicate`"]);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("format", "format"), 0);
+        assert_eq!(edit_distance("fromat", "format"), 2);
+        assert_eq!(edit_distance("vec", "vecs"), 1);
+    }
+
+    #[test]
+    fn test_severity_overrides() {
+        let mut config = DiagnosticsConfig::default();
+        config
+            .severity_overrides
+            .insert("missing-match-arm".to_string(), "warning".parse().unwrap());
+        assert_eq!(config.severity("missing-match-arm", Severity::Error), Severity::WeakWarning);
+        assert_eq!(config.severity("type-mismatch", Severity::Error), Severity::Error);
+        assert!("fatal".parse::<Severity>().is_err());
+    }
+
+    #[test]
+    fn test_check_try_macro() {
+        check_not_applicable(
+            r#"
+            fn main() {
+                foo::try!(x);
+                r#try {};
+                try_it!(x);
+            }
+            "#,
+            check_try_macro,
+        );
+        check_apply(
+            "fn f() { let x = try!(foo()); }",
+            "fn f() { let x = foo()?; }",
+            check_try_macro,
+        );
+        check_apply(
+            "fn f() { let x = r#try!(foo()); }",
+            "fn f() { let x = foo()?; }",
+            check_try_macro,
+        );
+        check_apply(
+            "fn f() { let x = try!(a + b); }",
+            "fn f() { let x = (a + b)?; }",
+            check_try_macro,
+        );
+        check_apply("fn f() { try!(foo()).bar(); }", "fn f() { foo()?.bar(); }", check_try_macro);
+        check_apply(
+            "fn f() { let x = try!(try!(foo()).bar(try!(baz))); }",
+            "fn f() { let x = foo()?.bar(baz?)?; }",
+            check_try_macro,
+        );
+    }
+
     #[test]
     fn test_check_struct_shorthand_initialization() {
         check_not_applicable(
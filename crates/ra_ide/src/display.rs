@@ -8,8 +8,9 @@ mod short_label;
 
 use std::fmt::Display;
 
+use either::Either;
 use ra_syntax::{
-    ast::{self, AstNode, AttrsOwner, NameOwner, TypeParamsOwner},
+    ast::{self, AstNode, AttrsOwner, NameOwner, TypeParamsOwner, VisibilityOwner},
     SyntaxKind::{ATTR, COMMENT},
 };
 use stdx::format_to;
@@ -64,10 +65,19 @@ pub(crate) fn where_predicates<N: TypeParamsOwner>(node: &N) -> Vec<String> {
     res
 }
 
-pub(crate) fn macro_label(node: &ast::MacroCall) -> String {
-    let name = node.name().map(|name| name.syntax().text().to_string()).unwrap_or_default();
-    let vis = if node.has_atom_attr("macro_export") { "#[macro_export]\n" } else { "" };
-    format!("{}macro_rules! {}", vis, name)
+pub(crate) fn macro_label(node: &Either<ast::MacroCall, ast::MacroDef>) -> String {
+    match node {
+        Either::Left(node) => {
+            let name = node.name().map(|name| name.syntax().text().to_string()).unwrap_or_default();
+            let vis = if node.has_atom_attr("macro_export") { "#[macro_export]\n" } else { "" };
+            format!("{}macro_rules! {}", vis, name)
+        }
+        Either::Right(node) => {
+            let name = node.name().map(|name| name.syntax().text().to_string()).unwrap_or_default();
+            let vis = node.visibility().map(|v| format!("{} ", v.syntax())).unwrap_or_default();
+            format!("{}macro {}", vis, name)
+        }
+    }
 }
 
 pub(crate) fn rust_code_markup(code: &impl Display) -> String {
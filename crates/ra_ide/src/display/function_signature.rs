@@ -7,7 +7,7 @@ use std::{
 
 use hir::{Docs, Documentation, HasSource, HirDisplay};
 use ra_ide_db::RootDatabase;
-use ra_syntax::ast::{self, AstNode, NameOwner, VisibilityOwner};
+use ra_syntax::ast::{self, AstNode, NameOwner, TypeParamsOwner, VisibilityOwner};
 use stdx::SepBy;
 
 use crate::display::{generic_parameters, where_predicates};
@@ -18,6 +18,9 @@ pub enum CallableKind {
     StructConstructor,
     VariantConstructor,
     Macro,
+    /// The generic parameters of an item, whose parameters are written
+    /// between angle brackets.
+    GenericParams,
 }
 
 /// Contains information about a function signature
@@ -159,8 +162,6 @@ impl FunctionSignature {
     }
 
     pub(crate) fn from_macro(db: &RootDatabase, macro_def: hir::MacroDef) -> Option<Self> {
-        let node: ast::MacroCall = macro_def.source(db).value;
-
         let params = vec![];
 
         Some(
@@ -168,7 +169,7 @@ impl FunctionSignature {
                 kind: CallableKind::Macro,
                 visibility: None,
                 qualifier: Default::default(),
-                name: node.name().map(|n| n.text().to_string()),
+                name: macro_def.name(db).map(|n| n.to_string()),
                 ret_type: None,
                 parameters: params,
                 parameter_names: vec![],
@@ -181,6 +182,48 @@ impl FunctionSignature {
             .with_doc_opt(macro_def.docs(db)),
         )
     }
+
+    pub(crate) fn from_generic_def(db: &RootDatabase, def: hir::GenericDef) -> Option<Self> {
+        fn from_node<N: NameOwner + TypeParamsOwner>(
+            node: &N,
+            doc: Option<Documentation>,
+        ) -> FunctionSignature {
+            FunctionSignature {
+                kind: CallableKind::GenericParams,
+                visibility: None,
+                qualifier: Default::default(),
+                name: node.name().map(|n| n.text().to_string()),
+                ret_type: None,
+                parameters: generic_parameters(node),
+                parameter_names: vec![],
+                parameter_types: vec![],
+                generic_parameters: vec![],
+                where_predicates: where_predicates(node),
+                doc: None,
+                has_self_param: false,
+            }
+            .with_doc_opt(doc)
+        }
+
+        let res = match def {
+            hir::GenericDef::Function(it) => from_node(&it.source(db).value, it.docs(db)),
+            hir::GenericDef::Adt(hir::Adt::Struct(it)) => {
+                from_node(&it.source(db).value, it.docs(db))
+            }
+            hir::GenericDef::Adt(hir::Adt::Enum(it)) => {
+                from_node(&it.source(db).value, it.docs(db))
+            }
+            hir::GenericDef::Adt(hir::Adt::Union(it)) => {
+                from_node(&it.source(db).value, it.docs(db))
+            }
+            hir::GenericDef::Trait(it) => from_node(&it.source(db).value, it.docs(db)),
+            hir::GenericDef::TypeAlias(it) => from_node(&it.source(db).value, it.docs(db)),
+            hir::GenericDef::ImplDef(_)
+            | hir::GenericDef::EnumVariant(_)
+            | hir::GenericDef::Const(_) => return None,
+        };
+        Some(res)
+    }
 }
 
 impl From<&'_ ast::FnDef> for FunctionSignature {
@@ -292,6 +335,7 @@ impl Display for FunctionSignature {
                 CallableKind::StructConstructor => write!(f, "struct {}", name)?,
                 CallableKind::VariantConstructor => write!(f, "{}", name)?,
                 CallableKind::Macro => write!(f, "{}!", name)?,
+                CallableKind::GenericParams => write!(f, "{}", name)?,
             }
         }
 
@@ -299,7 +343,11 @@ impl Display for FunctionSignature {
             write!(f, "{}", self.generic_parameters.iter().sep_by(", ").surround_with("<", ">"))?;
         }
 
-        write!(f, "{}", self.parameters.iter().sep_by(", ").surround_with("(", ")"))?;
+        let (open, close) = match self.kind {
+            CallableKind::GenericParams => ("<", ">"),
+            _ => ("(", ")"),
+        };
+        write!(f, "{}", self.parameters.iter().sep_by(", ").surround_with(open, close))?;
 
         if let Some(t) = &self.ret_type {
             write!(f, " -> {}", t)?;
@@ -248,9 +248,10 @@ impl ToNav for hir::Module {
         let name = self.name(db).map(|it| it.to_string().into()).unwrap_or_default();
         let (syntax, focus) = match &src.value {
             ModuleSource::SourceFile(node) => (node.syntax(), None),
-            ModuleSource::Module(node) => {
-                (node.syntax(), node.name().map(|it| it.syntax().text_range()))
-            }
+            ModuleSource::Module(node) => (
+                node.syntax(),
+                node.name().map(|it| original_range(db, src.with_value(it.syntax())).range),
+            ),
         };
         let frange = original_range(db, src.with_value(syntax));
         NavigationTarget::from_syntax(
@@ -316,13 +317,12 @@ impl ToNav for hir::Field {
 impl ToNav for hir::MacroDef {
     fn to_nav(&self, db: &RootDatabase) -> NavigationTarget {
         let src = self.source(db);
-        log::debug!("nav target {:#?}", src.value.syntax());
-        NavigationTarget::from_named(
-            db,
-            src.as_ref().map(|it| it as &dyn ast::NameOwner),
-            src.value.doc_comment_text(),
-            None,
-        )
+        let (name_owner, docs) = match &src.value {
+            Either::Left(it) => (it as &dyn ast::NameOwner, it.doc_comment_text()),
+            Either::Right(it) => (it as &dyn ast::NameOwner, it.doc_comment_text()),
+        };
+        log::debug!("nav target {:#?}", name_owner.syntax());
+        NavigationTarget::from_named(db, src.with_value(name_owner), docs, None)
     }
 }
 
@@ -377,18 +377,20 @@ impl ToNav for hir::TypeParam {
     fn to_nav(&self, db: &RootDatabase) -> NavigationTarget {
         let src = self.source(db);
         let full_range = match &src.value {
-            Either::Left(it) => it.syntax().text_range(),
-            Either::Right(it) => it.syntax().text_range(),
+            Either::Left(it) => original_range(db, src.with_value(it.syntax())),
+            Either::Right(it) => original_range(db, src.with_value(it.syntax())),
         };
         let focus_range = match &src.value {
             Either::Left(_) => None,
-            Either::Right(it) => it.name().map(|it| it.syntax().text_range()),
+            Either::Right(it) => {
+                it.name().map(|it| original_range(db, src.with_value(it.syntax())).range)
+            }
         };
         NavigationTarget {
-            file_id: src.file_id.original_file(db),
+            file_id: full_range.file_id,
             name: self.name(db).to_string().into(),
             kind: TYPE_PARAM,
-            full_range,
+            full_range: full_range.range,
             focus_range,
             container_name: None,
             description: None,
@@ -0,0 +1,284 @@
+//! Resolution of intra-doc links, e.g. `[Vec::push]` or `[the docs](foo::Bar)`,
+//! to the documentation of the linked items on docs.rs.
+
+use hir::{Adt, AssocItem, Function, Module, ModuleDef, ScopeDef, Semantics};
+use ra_db::SourceDatabase;
+use ra_ide_db::{defs::Definition, RootDatabase};
+use ra_syntax::{
+    ast::{self, AstToken},
+    SyntaxToken, TextRange, TextSize,
+};
+
+/// Rewrites the intra-doc links of `markdown`, written in `module`, into
+/// links to docs.rs. The links which can't be resolved are left as is.
+pub(crate) fn rewrite_links(db: &RootDatabase, markdown: &str, module: Module) -> String {
+    let mut in_code_block = false;
+    markdown
+        .lines()
+        .map(|line| {
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+            }
+            if in_code_block {
+                line.to_string()
+            } else {
+                rewrite_line(line, &|link| resolve_link(db, module, link))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Rewrites `[target](path)` and `[path]` (but not the `[path][ref]` and
+/// `[ref]: url` reference links) with the URLs `resolve` returns.
+fn rewrite_line(line: &str, resolve: &dyn Fn(&str) -> Option<String>) -> String {
+    let mut res = String::new();
+    let mut rest = line;
+    while let Some(open) = rest.find('[') {
+        let close = match rest[open..].find(']') {
+            Some(it) => open + it,
+            None => break,
+        };
+        let text = &rest[open + 1..close];
+        let after = &rest[close + 1..];
+        res.push_str(&rest[..=close]);
+        rest = after;
+        if after.starts_with('(') {
+            if let Some(end) = after.find(')') {
+                let target = &after[1..end];
+                res.push('(');
+                res.push_str(&resolve(target).unwrap_or_else(|| target.to_string()));
+                res.push(')');
+                rest = &after[end + 1..];
+            }
+        } else if after.starts_with('[') {
+            // The reference of a `[text][ref]` link.
+            if let Some(end) = after.find(']') {
+                res.push_str(&after[..=end]);
+                rest = &after[end + 1..];
+            }
+        } else if !after.starts_with(':') {
+            if let Some(url) = resolve(text) {
+                res.push('(');
+                res.push_str(&url);
+                res.push(')');
+            }
+        }
+    }
+    res.push_str(rest);
+    res
+}
+
+/// The intra-doc link of the doc comment `token` at `offset`, as the range of
+/// its path and the item it links to.
+pub(crate) fn doc_link_at(
+    sema: &Semantics<RootDatabase>,
+    token: &SyntaxToken,
+    offset: TextSize,
+) -> Option<(TextRange, Definition)> {
+    let comment = ast::Comment::cast(token.clone())?;
+    comment.kind().doc?;
+    let start = token.text_range().start();
+    let (range, link) = link_at(comment.text(), usize::from(offset - start))?;
+    // Doc comments are attached to the item they document, so this is the
+    // scope of the item.
+    let module = sema.scope(&token.parent()).module()?;
+    let (def, method) = resolve_path(sema.db, module, link_path(link)?)?;
+    let def = match method {
+        Some(method) => ModuleDef::Function(find_method(sema.db, def, method)?),
+        None => def,
+    };
+    Some((range + start, Definition::ModuleDef(def)))
+}
+
+/// The link of `text` which contains `offset`, as the range and the text of
+/// the target of a `[text](target)` link or of the text of a `[text]` one.
+fn link_at(text: &str, offset: usize) -> Option<(TextRange, &str)> {
+    let mut pos = 0;
+    while let Some(open) = text[pos..].find('[').map(|it| pos + it) {
+        let close = open + text[open..].find(']')?;
+        let after = &text[close + 1..];
+        let (start, end) = if after.starts_with('(') {
+            (close + 2, close + 1 + after.find(')')?)
+        } else if after.starts_with('[') {
+            // The reference of a `[text][ref]` link.
+            pos = close + 1 + after.find(']')? + 1;
+            continue;
+        } else if after.starts_with(':') {
+            pos = close + 1;
+            continue;
+        } else {
+            (open + 1, close)
+        };
+        if open <= offset && offset <= end {
+            let range = TextRange::new((start as u32).into(), (end as u32).into());
+            return Some((range, &text[start..end]));
+        }
+        pos = end + 1;
+    }
+    None
+}
+
+fn link_path(link: &str) -> Option<&str> {
+    let path = link.trim_matches('`').trim_end_matches("()").trim_end_matches('!');
+    if path.is_empty() || !path.chars().all(|c| c.is_alphanumeric() || c == '_' || c == ':') {
+        return None;
+    }
+    Some(path)
+}
+
+fn resolve_link(db: &RootDatabase, module: Module, link: &str) -> Option<String> {
+    let path = link_path(link)?;
+    let (def, method) = resolve_path(db, module, path)?;
+    let mut url = doc_url(db, def)?;
+    if let Some(method) = method {
+        url.push_str(&format!("#method.{}", method));
+    }
+    Some(url)
+}
+
+/// Resolves `path` relative to `module`, along with the name of the method
+/// of the resolved type or trait it refers to, if any.
+fn resolve_path<'a>(
+    db: &RootDatabase,
+    module: Module,
+    path: &'a str,
+) -> Option<(ModuleDef, Option<&'a str>)> {
+    let mut segments = path.split("::").peekable();
+    let mut def = match segments.next()? {
+        "crate" => ModuleDef::Module(module.krate().root_module(db)?),
+        "self" => ModuleDef::Module(module),
+        "super" => ModuleDef::Module(module.parent(db)?),
+        first => match find_in_scope(db, module, first) {
+            Some(it) => it,
+            None => {
+                let dep = module
+                    .krate()
+                    .dependencies(db)
+                    .into_iter()
+                    .find(|dep| dep.name.to_string() == first)?;
+                ModuleDef::Module(dep.krate.root_module(db)?)
+            }
+        },
+    };
+    while let Some(segment) = segments.next() {
+        let is_last = segments.peek().is_none();
+        def = match def {
+            ModuleDef::Module(module) => find_in_scope(db, module, segment)?,
+            ModuleDef::Adt(Adt::Enum(it)) => {
+                match it.variants(db).into_iter().find(|v| v.name(db).to_string() == segment) {
+                    Some(variant) => ModuleDef::EnumVariant(variant),
+                    None if is_last => return Some((def, Some(segment))),
+                    None => return None,
+                }
+            }
+            ModuleDef::Adt(_) | ModuleDef::Trait(_) if is_last => {
+                return Some((def, Some(segment)));
+            }
+            _ => return None,
+        };
+    }
+    Some((def, None))
+}
+
+fn find_method(db: &RootDatabase, def: ModuleDef, name: &str) -> Option<Function> {
+    let is_named = |item: AssocItem| match item {
+        AssocItem::Function(it) if it.name(db).to_string() == name => Some(it),
+        _ => None,
+    };
+    match def {
+        ModuleDef::Trait(it) => it.items(db).into_iter().find_map(is_named),
+        ModuleDef::Adt(it) => it.ty(db).iterate_assoc_items(db, it.krate(db)?, is_named),
+        _ => None,
+    }
+}
+
+fn find_in_scope(db: &RootDatabase, module: Module, name: &str) -> Option<ModuleDef> {
+    module.scope(db, None).into_iter().find_map(|(it, def)| match def {
+        ScopeDef::ModuleDef(def) if it.to_string() == name => Some(def),
+        _ => None,
+    })
+}
+
+fn doc_url(db: &RootDatabase, def: ModuleDef) -> Option<String> {
+    let (module, file) = match def {
+        ModuleDef::Module(it) => (it, "index.html".to_string()),
+        ModuleDef::EnumVariant(it) => {
+            let enum_url = doc_url(db, ModuleDef::Adt(Adt::Enum(it.parent_enum(db))))?;
+            return Some(format!("{}#variant.{}", enum_url, it.name(db)));
+        }
+        ModuleDef::Function(it) => (it.module(db), format!("fn.{}.html", it.name(db))),
+        ModuleDef::Adt(Adt::Struct(it)) => (it.module(db), format!("struct.{}.html", it.name(db))),
+        ModuleDef::Adt(Adt::Union(it)) => (it.module(db), format!("union.{}.html", it.name(db))),
+        ModuleDef::Adt(Adt::Enum(it)) => (it.module(db), format!("enum.{}.html", it.name(db))),
+        ModuleDef::Const(it) => (it.module(db), format!("constant.{}.html", it.name(db)?)),
+        ModuleDef::Static(it) => (it.module(db), format!("static.{}.html", it.name(db)?)),
+        ModuleDef::Trait(it) => (it.module(db), format!("trait.{}.html", it.name(db))),
+        ModuleDef::TypeAlias(it) => (it.module(db), format!("type.{}.html", it.name(db))),
+        ModuleDef::BuiltinType(_) => return None,
+    };
+    let crate_name = db.crate_graph()[module.krate().into()].display_name.as_ref()?.to_string();
+    let mut url = format!("https://docs.rs/{}/*/{}/", crate_name, crate_name);
+    for module in module.path_to_root(db).into_iter().rev() {
+        if let Some(name) = module.name(db) {
+            url.push_str(&format!("{}/", name));
+        }
+    }
+    url.push_str(&file);
+    Some(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock_analysis::analysis_and_position;
+
+    fn check_hover_docs(fixture: &str, expected_docs: &str) {
+        let (analysis, position) = analysis_and_position(fixture);
+        let hover = analysis.hover(position).unwrap().unwrap();
+        let markup = hover.info.first().unwrap();
+        assert!(markup.ends_with(expected_docs), "{}", markup);
+    }
+
+    #[test]
+    fn rewrites_intra_doc_links() {
+        check_hover_docs(
+            r"
+            //- /lib.rs
+            use foo::Bar<|>;
+
+            //- /foo/lib.rs
+            /// Wraps a [Baz], see [`Baz::new`] and [the enum](inner::Quux).
+            pub struct Bar;
+            pub struct Baz;
+            pub mod inner {
+                pub enum Quux { A }
+            }
+            ",
+            "Wraps a [Baz](https://docs.rs/foo/*/foo/struct.Baz.html), \
+             see [`Baz::new`](https://docs.rs/foo/*/foo/struct.Baz.html#method.new) \
+             and [the enum](https://docs.rs/foo/*/foo/inner/enum.Quux.html).",
+        );
+    }
+
+    #[test]
+    fn leaves_other_links_alone() {
+        check_hover_docs(
+            r"
+            //- /lib.rs
+            use foo::Bar<|>;
+
+            //- /foo/lib.rs
+            /// See [Missing], [the book](https://doc.rust-lang.org/book/) and [ref][Baz].
+            /// ```
+            /// let a = [Baz];
+            /// ```
+            pub struct Bar;
+            pub struct Baz;
+            ",
+            "See [Missing], [the book](https://doc.rust-lang.org/book/) and [ref][Baz].\n\
+             ```\n\
+             let a = [Baz];\n\
+             ```",
+        );
+    }
+}
@@ -29,7 +29,7 @@ pub(crate) fn expand_macro(db: &RootDatabase, position: FilePosition) -> Option<
     Some(ExpandedMacro { name: name_ref.text().to_string(), expansion })
 }
 
-fn expand_macro_recur(
+pub(crate) fn expand_macro_recur(
     sema: &Semantics<RootDatabase>,
     macro_call: &ast::MacroCall,
 ) -> Option<SyntaxNode> {
@@ -57,7 +57,7 @@ fn expand_macro_recur(
 
 // FIXME: It would also be cool to share logic here and in the mbe tests,
 // which are pretty unreadable at the moment.
-fn insert_whitespaces(syn: SyntaxNode) -> String {
+pub(crate) fn insert_whitespaces(syn: SyntaxNode) -> String {
     use SyntaxKind::*;
 
     let mut res = String::new();
@@ -1,19 +1,21 @@
 //! FIXME: write short doc here
 
-use hir::Semantics;
+use hir::{ModuleDef, Semantics, Trait};
+use ra_assists::utils::FamousDefs;
 use ra_ide_db::{
-    defs::{classify_name, classify_name_ref},
-    symbol_index, RootDatabase,
+    defs::{classify_name, classify_name_ref, Definition},
+    impls, symbol_index, RootDatabase,
 };
 use ra_syntax::{
-    ast::{self},
+    ast::{self, BinOp},
     match_ast, AstNode,
     SyntaxKind::*,
-    SyntaxToken, TokenAtOffset,
+    SyntaxToken, TokenAtOffset, T,
 };
 
 use crate::{
     display::{ToNav, TryToNav},
+    doc_links::doc_link_at,
     FilePosition, NavigationTarget, RangeInfo,
 };
 
@@ -24,8 +26,15 @@ pub(crate) fn goto_definition(
     let sema = Semantics::new(db);
     let file = sema.parse(position.file_id).syntax().clone();
     let original_token = pick_best(file.token_at_offset(position.offset))?;
+    if let Some((range, def)) = doc_link_at(&sema, &original_token, position.offset) {
+        return Some(RangeInfo::new(range, vec![def.try_to_nav(db)?]));
+    }
     let token = sema.descend_into_macros(original_token.clone());
 
+    if let Some(nav_targets) = operator_definition(&sema, &token) {
+        return Some(RangeInfo::new(original_token.text_range(), nav_targets));
+    }
+
     let nav_targets = match_ast! {
         match (token.parent()) {
             ast::NameRef(name_ref) => {
@@ -33,8 +42,15 @@ pub(crate) fn goto_definition(
             },
             ast::Name(name) => {
                 let def = classify_name(&sema, &name)?.definition();
-                let nav = def.try_to_nav(sema.db)?;
-                vec![nav]
+                let mut navs = vec![def.try_to_nav(sema.db)?];
+                // The name of a method of a trait impl also leads to the
+                // method of the trait.
+                if let Definition::ModuleDef(ModuleDef::Function(func)) = def {
+                    if let Some(trait_method) = impls::trait_method_for_impl_method(db, func) {
+                        navs.push(trait_method.to_nav(sema.db));
+                    }
+                }
+                navs
             },
             _ => return None,
         }
@@ -43,6 +59,88 @@ pub(crate) fn goto_definition(
     Some(RangeInfo::new(original_token.text_range(), nav_targets))
 }
 
+/// The implementations of the operator `token`, e.g. the `add` methods of the
+/// `Add` impls for the type of the left operand of `+`.
+fn operator_definition(
+    sema: &Semantics<RootDatabase>,
+    token: &SyntaxToken,
+) -> Option<Vec<NavigationTarget>> {
+    let (operand, lang_item, method) = operator(token)?;
+    let ty = sema.type_of_expr(&operand)?;
+    let scope = sema.scope(&token.parent());
+    let trait_ = match lang_item {
+        // Unlike the other operator traits, `Try` isn't a lang item.
+        "try" => FamousDefs(sema, scope.module()?.krate()).core_ops_Try()?,
+        _ => Trait::lang(sema.db, scope.module()?.krate(), lang_item)?,
+    };
+    let navs = impls::trait_method_impls_for_type(sema.db, trait_, &ty, method)
+        .into_iter()
+        .map(|it| it.to_nav(sema.db))
+        .collect::<Vec<_>>();
+    if navs.is_empty() {
+        return None;
+    }
+    Some(navs)
+}
+
+/// The operand whose type selects the impl of the operator `token`, and the
+/// lang item of the trait and the method the operator calls.
+fn operator(token: &SyntaxToken) -> Option<(ast::Expr, &'static str, &'static str)> {
+    match_ast! {
+        match (token.parent()) {
+            ast::BinExpr(it) => {
+                let (op_token, op) = it.op_details()?;
+                if op_token != *token {
+                    return None;
+                }
+                let (lang_item, method) = match op {
+                    BinOp::EqualityTest => ("eq", "eq"),
+                    BinOp::NegatedEqualityTest => ("eq", "ne"),
+                    BinOp::LesserTest => ("partial_ord", "lt"),
+                    BinOp::LesserEqualTest => ("partial_ord", "le"),
+                    BinOp::GreaterTest => ("partial_ord", "gt"),
+                    BinOp::GreaterEqualTest => ("partial_ord", "ge"),
+                    BinOp::Addition => ("add", "add"),
+                    BinOp::Subtraction => ("sub", "sub"),
+                    BinOp::Multiplication => ("mul", "mul"),
+                    BinOp::Division => ("div", "div"),
+                    BinOp::Remainder => ("rem", "rem"),
+                    BinOp::LeftShift => ("shl", "shl"),
+                    BinOp::RightShift => ("shr", "shr"),
+                    BinOp::BitwiseXor => ("bitxor", "bitxor"),
+                    BinOp::BitwiseOr => ("bitor", "bitor"),
+                    BinOp::BitwiseAnd => ("bitand", "bitand"),
+                    BinOp::AddAssign => ("add_assign", "add_assign"),
+                    BinOp::SubAssign => ("sub_assign", "sub_assign"),
+                    BinOp::MulAssign => ("mul_assign", "mul_assign"),
+                    BinOp::DivAssign => ("div_assign", "div_assign"),
+                    BinOp::RemAssign => ("rem_assign", "rem_assign"),
+                    BinOp::ShlAssign => ("shl_assign", "shl_assign"),
+                    BinOp::ShrAssign => ("shr_assign", "shr_assign"),
+                    BinOp::BitXorAssign => ("bitxor_assign", "bitxor_assign"),
+                    BinOp::BitOrAssign => ("bitor_assign", "bitor_assign"),
+                    BinOp::BitAndAssign => ("bitand_assign", "bitand_assign"),
+                    BinOp::BooleanOr | BinOp::BooleanAnd | BinOp::Assignment => return None,
+                };
+                Some((it.lhs()?, lang_item, method))
+            },
+            ast::IndexExpr(it) => {
+                if token.kind() != T!['['] && token.kind() != T![']'] {
+                    return None;
+                }
+                Some((it.base()?, "index", "index"))
+            },
+            ast::TryExpr(it) => {
+                if token.kind() != T![?] {
+                    return None;
+                }
+                Some((it.expr()?, "try", "into_result"))
+            },
+            _ => None,
+        }
+    }
+}
+
 fn pick_best(tokens: TokenAtOffset<SyntaxToken>) -> Option<SyntaxToken> {
     return tokens.max_by_key(priority);
     fn priority(n: &SyntaxToken) -> usize {
@@ -312,6 +410,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn goto_def_for_macro_defined_mod_with_arg() {
+        check_goto(
+            "
+            //- /lib.rs
+            macro_rules! define_mod {
+                ($name:ident) => (mod $name {})
+            }
+
+            define_mod!(foo);
+
+            use crate::foo<|>;
+            ",
+            "foo MODULE FileId(1) 65..82 77..80",
+            "define_mod!(foo);|foo",
+        );
+    }
+
     #[test]
     fn goto_def_for_macro_defined_fn_no_arg() {
         check_goto(
@@ -887,4 +1003,158 @@ mod tests {
             "x",
         )
     }
+
+    #[test]
+    fn goto_def_for_intra_doc_link() {
+        check_goto(
+            "
+            //- /lib.rs
+            /// Calls [Foo::b<|>ar].
+            fn call() {}
+            struct Foo;
+            impl Foo {
+                fn bar(&self) {}
+            }
+            ",
+            "bar FN_DEF FileId(1) 62..78 65..68",
+            "fn bar(&self) {}|bar",
+        );
+    }
+
+    #[test]
+    fn goto_def_for_intra_doc_link_with_target() {
+        check_goto(
+            "
+            //- /lib.rs
+            /// Implements [the tr<|>ait](inner::Trait).
+            struct Foo;
+            mod inner {
+                pub trait Trait {}
+            }
+            ",
+            "Trait TRAIT_DEF FileId(1) 70..88 80..85",
+            "pub trait Trait {}|Trait",
+        );
+    }
+
+    #[test]
+    fn goto_def_for_method_of_trait_impl() {
+        let (analysis, pos) = analysis_and_position(
+            "
+            //- /lib.rs
+            trait Make {
+                fn make() -> Self;
+            }
+            struct Foo;
+            impl Make for Foo {
+                fn mak<|>e() -> Self { Foo }
+            }
+            ",
+        );
+
+        let navs = analysis.goto_definition(pos).unwrap().unwrap().info;
+        let navs: Vec<_> = navs.iter().map(|nav| nav.debug_render()).collect();
+        assert_eq!(
+            navs,
+            vec![
+                "make FN_DEF FileId(1) 74..99 77..81".to_string(),
+                "make FN_DEF FileId(1) 17..35 20..24".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn goto_def_for_binary_operator() {
+        check_goto(
+            "
+            //- /lib.rs
+            #[lang = \"add\"]
+            pub trait Add<Rhs = Self> {
+                type Output;
+                fn add(self, rhs: Rhs) -> Self::Output;
+            }
+            struct Foo;
+            impl Add for Foo {
+                type Output = Foo;
+                fn add(self, rhs: Foo) -> Foo { rhs }
+            }
+            fn f(a: Foo, b: Foo) {
+                a +<|> b;
+            }
+            ",
+            "add FN_DEF FileId(1) 165..202 168..171",
+            "fn add(self, rhs: Foo) -> Foo { rhs }|add",
+        );
+    }
+
+    #[test]
+    fn goto_def_for_operator_with_default_method() {
+        check_goto(
+            "
+            //- /lib.rs
+            #[lang = \"eq\"]
+            pub trait PartialEq<Rhs = Self> {
+                fn eq(&self, other: &Rhs) -> bool;
+                fn ne(&self, other: &Rhs) -> bool { !self.eq(other) }
+            }
+            struct Foo;
+            impl PartialEq for Foo {
+                fn eq(&self, other: &Foo) -> bool { true }
+            }
+            fn f(a: Foo, b: Foo) -> bool {
+                a <|>!= b
+            }
+            ",
+            "ne FN_DEF FileId(1) 92..145 95..97",
+            "fn ne(&self, other: &Rhs) -> bool { !self.eq(other) }|ne",
+        );
+    }
+
+    #[test]
+    fn goto_def_for_index_operator() {
+        check_goto(
+            "
+            //- /lib.rs
+            #[lang = \"index\"]
+            pub trait Index<Idx> {
+                type Output;
+                fn index(&self, index: Idx) -> &Self::Output;
+            }
+            struct Vec;
+            impl Index<u32> for Vec {
+                type Output = u32;
+                fn index(&self, index: u32) -> &u32 { &0 }
+            }
+            fn f(v: Vec) -> u32 {
+                v[0]<|>
+            }
+            ",
+            "index FN_DEF FileId(1) 175..217 178..183",
+            "fn index(&self, index: u32) -> &u32 { &0 }|index",
+        );
+    }
+
+    #[test]
+    fn goto_def_for_try_operator() {
+        check_goto(
+            "
+            //- /main.rs crate:main deps:core
+            struct Foo;
+            impl core::ops::Try for Foo {
+                fn into_result(self) {}
+            }
+            fn f(foo: Foo) -> Foo {
+                foo?<|>
+            }
+            //- /core.rs crate:core
+            pub mod ops {
+                pub trait Try {
+                    fn into_result(self);
+                }
+            }
+            ",
+            "into_result FN_DEF FileId(1) 46..69 49..60",
+            "fn into_result(self) {}|into_result",
+        );
+    }
 }
@@ -1,9 +1,10 @@
 //! Logic for computing info that is displayed when the user hovers over any
 //! source code items (e.g. function call, struct field, variable symbol...)
 
+use either::Either;
 use hir::{
-    Adt, AsAssocItem, AssocItemContainer, FieldSource, HasSource, HirDisplay, ModuleDef,
-    ModuleSource, Semantics,
+    Adt, AsAssocItem, AssocItemContainer, CaptureKind, ComputedExpr, Crate, Docs, FieldSource,
+    HasAttrs, HasSource, HirDisplay, ModuleDef, ModuleSource, Semantics,
 };
 use ra_db::SourceDatabase;
 use ra_ide_db::{
@@ -11,23 +12,41 @@ use ra_ide_db::{
     RootDatabase,
 };
 use ra_syntax::{
-    ast::{self, DocCommentsOwner},
+    algo::find_node_at_offset,
+    ast::{self, DocCommentsOwner, LiteralKind},
     match_ast, AstNode,
     SyntaxKind::*,
     SyntaxToken, TokenAtOffset,
 };
 
 use crate::{
-    display::{macro_label, rust_code_markup, rust_code_markup_with_doc, ShortLabel},
-    FilePosition, RangeInfo,
+    display::{macro_label, rust_code_markup, rust_code_markup_with_doc, ShortLabel, TryToNav},
+    doc_links::{doc_link_at, rewrite_links},
+    expand_macro::{expand_macro_recur, insert_whitespaces},
+    runnables::{runnable, Runnable},
+    FileId, FilePosition, RangeInfo,
 };
 use itertools::Itertools;
 use std::iter::once;
+use stdx::format_to;
+
+/// An action the user can take from a hover, rendered as a link below it.
+#[derive(Debug, Clone)]
+pub enum HoverAction {
+    /// Go to the implementations of a trait or a type declared at the
+    /// position.
+    Implementation(FilePosition),
+    /// Show the references to the item declared at the position.
+    Reference(FilePosition),
+    /// Run or debug a `main` function or a test.
+    Runnable(FileId, Runnable),
+}
 
 /// Contains the results when hovering over an item
 #[derive(Debug, Default)]
 pub struct HoverResult {
     results: Vec<String>,
+    actions: Vec<HoverAction>,
 }
 
 impl HoverResult {
@@ -55,6 +74,10 @@ impl HoverResult {
         &self.results
     }
 
+    pub fn actions(&self) -> &[HoverAction] {
+        &self.actions
+    }
+
     /// Returns the results converted into markup
     /// for displaying in a UI
     pub fn to_markup(&self) -> String {
@@ -74,6 +97,15 @@ fn hover_text(
     }
 }
 
+/// Puts the memory layout of a type, e.g. `size = 8, align = 4`, before its
+/// documentation.
+fn with_layout(layout: Option<String>, docs: Option<String>) -> Option<String> {
+    match (layout, docs) {
+        (Some(layout), Some(docs)) => Some(format!("{}\n\n{}", layout, docs)),
+        (layout, docs) => layout.or(docs),
+    }
+}
+
 fn definition_owner_name(db: &RootDatabase, def: &Definition) -> Option<String> {
     match def {
         Definition::Field(f) => Some(f.parent_def(db).name(db)),
@@ -109,18 +141,39 @@ fn determine_mod_path(db: &RootDatabase, def: &Definition) -> Option<String> {
     mod_path
 }
 
-fn hover_text_from_name_kind(db: &RootDatabase, def: Definition) -> Option<String> {
+fn hover_text_from_name_kind(
+    db: &RootDatabase,
+    def: Definition,
+    krate: Option<Crate>,
+) -> Option<String> {
     let mod_path = determine_mod_path(db, &def);
+    let module = def.module(db);
+    let docs = |docs: Option<String>| match (docs, module) {
+        (Some(docs), Some(module)) => Some(rewrite_links(db, &docs, module)),
+        (docs, _) => docs,
+    };
     return match def {
         Definition::Macro(it) => {
             let src = it.source(db);
-            hover_text(src.value.doc_comment_text(), Some(macro_label(&src.value)), mod_path)
+            let doc_comments = match &src.value {
+                Either::Left(it) => it.doc_comment_text(),
+                Either::Right(it) => it.doc_comment_text(),
+            };
+            hover_text(docs(doc_comments), Some(macro_label(&src.value)), mod_path)
         }
         Definition::Field(it) => {
             let src = it.source(db);
             match src.value {
-                FieldSource::Named(it) => {
-                    hover_text(it.doc_comment_text(), it.short_label(), mod_path)
+                FieldSource::Named(field) => {
+                    let layout = it.layout(db).ok().map(|layout| match it.offset(db) {
+                        Some(offset) => format!(
+                            "size = {}, align = {}, offset = {}",
+                            layout.size, layout.align, offset
+                        ),
+                        None => format!("size = {}, align = {}", layout.size, layout.align),
+                    });
+                    let docs = with_layout(layout, docs(field.doc_comment_text()));
+                    hover_text(docs, field.short_label(), mod_path)
                 }
                 _ => None,
             }
@@ -128,45 +181,92 @@ fn hover_text_from_name_kind(db: &RootDatabase, def: Definition) -> Option<Strin
         Definition::ModuleDef(it) => match it {
             ModuleDef::Module(it) => match it.definition_source(db).value {
                 ModuleSource::Module(it) => {
-                    hover_text(it.doc_comment_text(), it.short_label(), mod_path)
+                    hover_text(docs(it.doc_comment_text()), it.short_label(), mod_path)
                 }
                 _ => None,
             },
-            ModuleDef::Function(it) => from_def_source(db, it, mod_path),
-            ModuleDef::Adt(Adt::Struct(it)) => from_def_source(db, it, mod_path),
-            ModuleDef::Adt(Adt::Union(it)) => from_def_source(db, it, mod_path),
-            ModuleDef::Adt(Adt::Enum(it)) => from_def_source(db, it, mod_path),
-            ModuleDef::EnumVariant(it) => from_def_source(db, it, mod_path),
-            ModuleDef::Const(it) => from_def_source(db, it, mod_path),
-            ModuleDef::Static(it) => from_def_source(db, it, mod_path),
-            ModuleDef::Trait(it) => from_def_source(db, it, mod_path),
-            ModuleDef::TypeAlias(it) => from_def_source(db, it, mod_path),
-            ModuleDef::BuiltinType(it) => Some(it.to_string()),
+            ModuleDef::Function(it) => from_def_source(db, it, mod_path, &docs),
+            ModuleDef::Adt(adt) => {
+                let layout = adt
+                    .layout(db)
+                    .ok()
+                    .map(|it| format!("size = {}, align = {}", it.size, it.align));
+                let docs = |it: Option<String>| with_layout(layout.clone(), docs(it));
+                match adt {
+                    Adt::Struct(it) => from_def_source(db, it, mod_path, &docs),
+                    Adt::Union(it) => from_def_source(db, it, mod_path, &docs),
+                    Adt::Enum(it) => from_def_source(db, it, mod_path, &docs),
+                }
+            }
+            ModuleDef::EnumVariant(it) => {
+                let src = it.source(db);
+                let mut label = src.value.short_label();
+                if src.value.expr().is_some() {
+                    if let (Some(label), Ok(value)) = (&mut label, it.discriminant(db)) {
+                        format_to!(label, " = {}", render_value(&ComputedExpr::Int(value)));
+                    }
+                }
+                hover_text(docs(src.value.doc_comment_text()), label, mod_path)
+            }
+            ModuleDef::Const(it) => {
+                let src = it.source(db);
+                let mut label = src.value.short_label();
+                if let (Some(label), Ok(value)) = (&mut label, it.eval(db)) {
+                    format_to!(label, " = {}", render_value(&value));
+                }
+                hover_text(docs(src.value.doc_comment_text()), label, mod_path)
+            }
+            ModuleDef::Static(it) => from_def_source(db, it, mod_path, &docs),
+            ModuleDef::Trait(it) => from_def_source(db, it, mod_path, &docs),
+            ModuleDef::TypeAlias(it) => from_def_source(db, it, mod_path, &docs),
+            ModuleDef::BuiltinType(it) => {
+                let name = it.to_string();
+                let docs = krate.and_then(|krate| std_docs(db, krate, "primitive", &name));
+                hover_text(docs, Some(name), None)
+            }
         },
-        Definition::Local(it) => Some(rust_code_markup(&it.ty(db).display_truncated(db, None))),
+        Definition::Local(it) => {
+            let ty = it.ty(db);
+            Some(match ty.as_closure() {
+                Some(closure) => closure_text(db, &closure),
+                None => rust_code_markup(&ty.display_truncated(db, None)),
+            })
+        }
         Definition::TypeParam(_) | Definition::SelfType(_) => {
             // FIXME: Hover for generic param
             None
         }
     };
 
-    fn from_def_source<A, D>(db: &RootDatabase, def: D, mod_path: Option<String>) -> Option<String>
+    fn from_def_source<A, D>(
+        db: &RootDatabase,
+        def: D,
+        mod_path: Option<String>,
+        docs: &dyn Fn(Option<String>) -> Option<String>,
+    ) -> Option<String>
     where
         D: HasSource<Ast = A>,
         A: ast::DocCommentsOwner + ast::NameOwner + ShortLabel,
     {
         let src = def.source(db);
-        hover_text(src.value.doc_comment_text(), src.value.short_label(), mod_path)
+        hover_text(docs(src.value.doc_comment_text()), src.value.short_label(), mod_path)
     }
 }
 
 pub(crate) fn hover(db: &RootDatabase, position: FilePosition) -> Option<RangeInfo<HoverResult>> {
     let sema = Semantics::new(db);
     let file = sema.parse(position.file_id).syntax().clone();
-    let token = pick_best(file.token_at_offset(position.offset))?;
-    let token = sema.descend_into_macros(token);
+    let original_token = pick_best(file.token_at_offset(position.offset))?;
+    let token = sema.descend_into_macros(original_token.clone());
 
     let mut res = HoverResult::new();
+    let krate = sema.to_module_def(position.file_id).map(|it| it.krate());
+
+    if let Some((range, def)) = doc_link_at(&sema, &original_token, position.offset) {
+        res.actions = hover_actions(&sema, &def);
+        res.extend(hover_text_from_name_kind(db, def, krate));
+        return if res.is_empty() { None } else { Some(RangeInfo::new(range, res)) };
+    }
 
     if let Some((node, name_kind)) = match_ast! {
         match (token.parent()) {
@@ -180,13 +280,26 @@ pub(crate) fn hover(db: &RootDatabase, position: FilePosition) -> Option<RangeIn
         }
     } {
         let range = sema.original_range(&node).range;
-        res.extend(hover_text_from_name_kind(db, name_kind));
+        let actions = hover_actions(&sema, &name_kind);
+        res.extend(hover_text_from_name_kind(db, name_kind, krate));
 
         if !res.is_empty() {
+            if token != original_token {
+                res.extend(expansion_preview(&sema, &original_token));
+            }
+            res.actions = actions;
             return Some(RangeInfo::new(range, res));
         }
     }
 
+    if original_token.kind().is_keyword() {
+        let keyword = original_token.text();
+        if let Some(docs) = krate.and_then(|krate| std_docs(db, krate, "keyword", keyword)) {
+            res.extend(Some(rust_code_markup_with_doc(keyword, Some(&docs), None)));
+            return Some(RangeInfo::new(original_token.text_range(), res));
+        }
+    }
+
     let node = token
         .ancestors()
         .find(|n| ast::Expr::cast(n.clone()).is_some() || ast::Pat::cast(n.clone()).is_some())?;
@@ -208,11 +321,136 @@ pub(crate) fn hover(db: &RootDatabase, position: FilePosition) -> Option<RangeIn
         }
     }?;
 
-    res.extend(Some(rust_code_markup(&ty.display_truncated(db, None))));
+    let doc = match ast::Literal::cast(node.clone()) {
+        Some(literal) => literal_text(&literal),
+        // Only integers are worth showing: the value of other literals is
+        // already in the source.
+        None => match ast::Expr::cast(node.clone()).and_then(|it| sema.eval_expr(&it)) {
+            Some(value @ ComputedExpr::Int(_)) => Some(format!("value = {}", render_value(&value))),
+            _ => None,
+        },
+    };
+    res.extend(Some(match ty.as_closure() {
+        Some(closure) => closure_text(db, &closure),
+        None => rust_code_markup_with_doc(&ty.display_truncated(db, None), doc.as_deref(), None),
+    }));
     let range = sema.original_range(&node).range;
     Some(RangeInfo::new(range, res))
 }
 
+/// A collapsed, one-line preview of the expansion of the macro call `token`
+/// is written in.
+fn expansion_preview(sema: &Semantics<RootDatabase>, token: &SyntaxToken) -> Option<String> {
+    const PREVIEW_LEN: usize = 60;
+
+    let macro_call = token.parent().ancestors().find_map(ast::MacroCall::cast)?;
+    let name = macro_call.path()?.segment()?.name_ref()?;
+    let expansion = insert_whitespaces(expand_macro_recur(sema, &macro_call)?);
+    let mut preview = expansion.split_whitespace().join(" ");
+    if let Some((idx, _)) = preview.char_indices().nth(PREVIEW_LEN) {
+        preview.truncate(idx);
+        preview.push('…');
+    }
+    Some(rust_code_markup_with_doc(&preview, Some(&format!("Expanded from `{}!`", name)), None))
+}
+
+/// The signature of a closure, followed by the variables it captures.
+fn closure_text(db: &RootDatabase, closure: &hir::Closure) -> String {
+    let mut captures = String::new();
+    for capture in closure.captures(db) {
+        let name = match capture.local.name(db) {
+            Some(it) => it,
+            None => continue,
+        };
+        let kind = match capture.kind {
+            CaptureKind::ByRef => "by ref",
+            CaptureKind::ByMutRef => "by mut ref",
+            CaptureKind::ByValue => "by move",
+        };
+        format_to!(captures, "\n* `{}` {}", name, kind);
+    }
+    let captures =
+        if captures.is_empty() { None } else { Some(format!("## Captures{}", captures)) };
+    rust_code_markup_with_doc(&closure.display_signature(db), captures.as_deref(), None)
+}
+
+/// The documentation `std` has for a keyword or a primitive type, which is
+/// put on private modules like `#[doc(keyword = "async")] mod async_keyword {}`.
+fn std_docs(db: &RootDatabase, krate: Crate, kind: &str, name: &str) -> Option<String> {
+    let std_crate = if krate.display_name(db).map_or(false, |it| it.to_string() == "std") {
+        krate
+    } else {
+        krate.dependencies(db).into_iter().find(|dep| dep.name.to_string() == "std")?.krate
+    };
+    // The documentation modules are declared in `keyword_docs.rs` and
+    // `primitive_docs.rs`, right below the root.
+    let root = std_crate.root_module(db)?;
+    let module = root
+        .children(db)
+        .flat_map(|module| once(module).chain(module.children(db)))
+        .find(|&module| {
+            module.attrs(db).by_key("doc").tt_values().any(|tt| {
+                let text = tt.to_string();
+                let mut parts = text.trim_matches(|c| c == '(' || c == ')').splitn(2, '=');
+                parts.next().map(str::trim) == Some(kind)
+                    && parts.next().map(|it| it.trim().trim_matches('"')) == Some(name)
+            })
+        })?;
+    module.docs(db).map(Into::into)
+}
+
+fn hover_actions(sema: &Semantics<RootDatabase>, def: &Definition) -> Vec<HoverAction> {
+    let nav = match def.try_to_nav(sema.db) {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    let position = FilePosition {
+        file_id: nav.file_id(),
+        offset: nav.focus_range().unwrap_or_else(|| nav.range()).start(),
+    };
+    let mut res = Vec::new();
+    match def {
+        Definition::ModuleDef(ModuleDef::Adt(_)) | Definition::ModuleDef(ModuleDef::Trait(_)) => {
+            res.push(HoverAction::Implementation(position))
+        }
+        Definition::ModuleDef(ModuleDef::Function(_)) => {
+            let file = sema.parse(position.file_id);
+            let runnable = find_node_at_offset::<ast::FnDef>(file.syntax(), position.offset)
+                .and_then(|fn_def| runnable(sema, fn_def.syntax().clone()));
+            res.extend(runnable.map(|it| HoverAction::Runnable(position.file_id, it)));
+        }
+        _ => {}
+    }
+    res.push(HoverAction::Reference(position));
+    res
+}
+
+/// Renders a constant value, along with its hexadecimal form for
+/// non-negative integers, e.g. `42 (0x2A)`.
+fn render_value(value: &ComputedExpr) -> String {
+    match value {
+        ComputedExpr::Int(it) if *it >= 0 => format!("{} ({:#X})", it, it),
+        _ => value.to_string(),
+    }
+}
+
+/// The value of an integer literal in the usual bases, or the code point of a
+/// character or byte literal.
+fn literal_text(literal: &ast::Literal) -> Option<String> {
+    match literal.kind() {
+        LiteralKind::IntNumber { .. } => {
+            let value = literal.int_value()?;
+            Some(format!(
+                "* decimal: `{}`\n* hex: `{:#X}`\n* binary: `{:#b}`\n* octal: `{:#o}`",
+                value, value, value, value
+            ))
+        }
+        LiteralKind::Byte => Some(format!("code point = U+{:04X}", literal.int_value()?)),
+        LiteralKind::Char => Some(format!("code point = U+{:04X}", literal.char_value()? as u32)),
+        _ => None,
+    }
+}
+
 fn pick_best(tokens: TokenAtOffset<SyntaxToken>) -> Option<SyntaxToken> {
     return tokens.max_by_key(priority);
     fn priority(n: &SyntaxToken) -> usize {
@@ -230,7 +468,10 @@ mod tests {
     use ra_db::FileLoader;
     use ra_syntax::TextRange;
 
-    use crate::mock_analysis::{analysis_and_position, single_file_with_position};
+    use crate::{
+        mock_analysis::{analysis_and_position, single_file_with_position},
+        HoverAction, RunnableKind,
+    };
 
     fn trim_markup(s: &str) -> &str {
         s.trim_start_matches("```rust\n").trim_end_matches("\n```")
@@ -263,6 +504,38 @@ mod tests {
         assert!(analysis.hover(position).unwrap().is_none());
     }
 
+    fn action_labels(code: &str) -> Vec<String> {
+        let (analysis, position) = single_file_with_position(code);
+        let hover = analysis.hover(position).unwrap().unwrap();
+        hover
+            .info
+            .actions()
+            .iter()
+            .map(|action| match action {
+                HoverAction::Implementation(it) => format!("impls {:?}", it.offset),
+                HoverAction::Reference(it) => format!("refs {:?}", it.offset),
+                HoverAction::Runnable(_, it) => match &it.kind {
+                    RunnableKind::Test { test_id, .. } => format!("test {}", test_id),
+                    RunnableKind::Bin => "bin".to_string(),
+                    kind => format!("{:?}", kind),
+                },
+            })
+            .collect()
+    }
+
+    #[test]
+    fn hover_actions_of_traits_and_types() {
+        assert_eq!(action_labels("trait Foo<|> {}"), vec!["impls 6", "refs 6"]);
+        assert_eq!(action_labels("struct Foo;\nfn f(_: Foo<|>) {}"), vec!["impls 7", "refs 7"]);
+    }
+
+    #[test]
+    fn hover_actions_of_runnables() {
+        assert_eq!(action_labels("#[test]\nfn foo<|>() {}"), vec!["test foo", "refs 11"]);
+        assert_eq!(action_labels("fn main<|>() {}"), vec!["bin", "refs 3"]);
+        assert_eq!(action_labels("fn foo<|>() {}"), vec!["refs 3"]);
+    }
+
     #[test]
     fn hover_shows_type_of_an_expression() {
         let (analysis, position) = single_file_with_position(
@@ -364,7 +637,7 @@ mod tests {
                 };
             }
         "#,
-            &["Foo\nfield_a: u32"],
+            &["Foo\nfield_a: u32\n```\n\nsize = 4, align = 4, offset = 0"],
         );
 
         // Hovering over the field in the definition
@@ -381,7 +654,121 @@ mod tests {
                 };
             }
         "#,
-            &["Foo\nfield_a: u32"],
+            &["Foo\nfield_a: u32\n```\n\nsize = 4, align = 4, offset = 0"],
+        );
+    }
+
+    #[test]
+    fn hover_shows_struct_layout() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            struct Foo<|> { a: u8, b: u32, c: u16 }
+        "#,
+            &["struct Foo\n```\n\nsize = 8, align = 4"],
+        );
+        check_hover_result(
+            r#"
+            //- /main.rs
+            #[repr(C)]
+            struct Foo<|> { a: u8, b: u32, c: u16 }
+        "#,
+            &["struct Foo\n```\n\nsize = 12, align = 4"],
+        );
+    }
+
+    #[test]
+    fn hover_shows_field_offset() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            struct Foo { a<|>: u8, b: u32, c: u16 }
+        "#,
+            &["Foo\na: u8\n```\n\nsize = 1, align = 1, offset = 6"],
+        );
+        check_hover_result(
+            r#"
+            //- /main.rs
+            #[repr(C)]
+            struct Foo { a: u8, b: u32, c<|>: u16 }
+        "#,
+            &["Foo\nc: u16\n```\n\nsize = 2, align = 2, offset = 8"],
+        );
+    }
+
+    #[test]
+    fn hover_shows_enum_layout() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            /// Some docs
+            enum Foo<|> { A, B = 300 }
+        "#,
+            &["enum Foo\n```\n\nsize = 2, align = 2\n\nSome docs"],
+        );
+    }
+
+    #[test]
+    fn hover_omits_layout_of_generic_structs() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            struct Foo<|><T> { t: T }
+        "#,
+            &["struct Foo"],
+        );
+    }
+
+    #[test]
+    fn hover_shows_layout_of_generic_instantiations() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            struct Unique<T> { pointer: *const T }
+            struct RawVec<T> { ptr: Unique<T>, cap: usize }
+            struct Vec<T> { buf: RawVec<T>, len: usize }
+            struct String<|> { vec: Vec<u8> }
+        "#,
+            &["struct String\n```\n\nsize = 24, align = 8"],
+        );
+        check_hover_result(
+            r#"
+            //- /main.rs
+            struct Wrapper<T> { t: T }
+            struct Foo { a<|>: Wrapper<u16>, b: [u32; 3] }
+        "#,
+            &["Foo\na: Wrapper<u16>\n```\n\nsize = 2, align = 2, offset = 12"],
+        );
+    }
+
+    #[test]
+    fn hover_shows_layout_with_repr_align_and_packed() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            #[repr(C, align(8))]
+            struct Foo<|> { a: u8 }
+        "#,
+            &["struct Foo\n```\n\nsize = 8, align = 8"],
+        );
+        check_hover_result(
+            r#"
+            //- /main.rs
+            #[repr(packed(2))]
+            struct Foo<|> { a: u8, b: u32 }
+        "#,
+            &["struct Foo\n```\n\nsize = 6, align = 2"],
+        );
+    }
+
+    #[test]
+    fn hover_shows_array_layout() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            struct Foo<|> { a: [u16; 5], b: u8 }
+        "#,
+            &["struct Foo\n```\n\nsize = 12, align = 2"],
         );
     }
 
@@ -392,7 +779,7 @@ mod tests {
             //- /main.rs
             const foo<|>: u32 = 0;
         "#,
-            &["const foo: u32"],
+            &["const foo: u32 = 0 (0x0)"],
         );
 
         check_hover_result(
@@ -404,6 +791,209 @@ mod tests {
         );
     }
 
+    #[test]
+    fn hover_shows_const_value() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            const fn double(x: u32) -> u32 { x * 2 }
+            const FOO<|>: u32 = double(21);
+        "#,
+            &["const FOO: u32 = 42 (0x2A)"],
+        );
+        check_hover_result(
+            r#"
+            //- /main.rs
+            const FOO<|>: i32 = -1;
+        "#,
+            &["const FOO: i32 = -1"],
+        );
+        check_hover_result(
+            r#"
+            //- /main.rs
+            const FOO<|>: (bool, char) = (true, 'a');
+        "#,
+            &["const FOO: (bool, char) = (true, 'a')"],
+        );
+    }
+
+    #[test]
+    fn hover_shows_explicit_discriminant() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            const BASE: isize = 16;
+            enum Foo {
+                A = BASE,
+                B,
+            }
+            fn main() {
+                let a = Foo::A<|>;
+            }
+        "#,
+            &["Foo\nA = 16 (0x10)"],
+        );
+        check_hover_result(
+            r#"
+            //- /main.rs
+            enum Foo {
+                A = 16,
+                B<|>,
+            }
+        "#,
+            &["Foo\nB"],
+        );
+    }
+
+    #[test]
+    fn hover_shows_value_of_const_expr() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            const FOO: u32 = 4;
+            fn main() {
+                let a = FOO *<|> 64;
+            }
+        "#,
+            &["u32\n```\n\nvalue = 256 (0x100)"],
+        );
+        check_hover_result(
+            r#"
+            //- /main.rs
+            fn main() {
+                let x = 4;
+                let a = x *<|> 64;
+            }
+        "#,
+            &["i32"],
+        );
+    }
+
+    #[test]
+    fn hover_shows_int_literal_in_all_bases() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            fn main() {
+                let a: u8 = 4<|>2;
+            }
+        "#,
+            &["u8\n```\n\n* decimal: `42`\n* hex: `0x2A`\n* binary: `0b101010`\n* octal: `0o52`"],
+        );
+        check_hover_result(
+            r#"
+            //- /main.rs
+            fn main() {
+                let a = 0xF<|>F_u32;
+            }
+        "#,
+            &["u32\n```\n\n* decimal: `255`\n* hex: `0xFF`\n* binary: `0b11111111`\n* octal: `0o377`"],
+        );
+    }
+
+    #[test]
+    fn hover_shows_code_point_of_char_literals() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            fn main() {
+                let c = 'é<|>';
+            }
+        "#,
+            &["char\n```\n\ncode point = U+00E9"],
+        );
+        check_hover_result(
+            r#"
+            //- /main.rs
+            fn main() {
+                let b = b'a<|>';
+            }
+        "#,
+            &["u8\n```\n\ncode point = U+0061"],
+        );
+    }
+
+    #[test]
+    fn hover_shows_closure_signature_and_captures() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            fn main() {
+                let mut count = 0u32;
+                let f<|> = |x: u32| { count += x; count };
+            }
+        "#,
+            &["impl FnMut(u32) -> u32\n```\n\n## Captures\n* `count` by mut ref"],
+        );
+    }
+
+    #[test]
+    fn hover_shows_move_closure_captures() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            struct Token;
+            fn main() {
+                let token = Token;
+                let limit = 1u32;
+                let f = mo<|>ve || { let t = token; limit };
+            }
+        "#,
+            &["impl FnOnce() -> u32\n```\n\n## Captures\n* `token` by move\n* `limit` by move"],
+        );
+    }
+
+    #[test]
+    fn hover_omits_captures_of_closure_without_captures() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            fn main() {
+                let f<|> = |a: u32| { let b = a; b };
+            }
+        "#,
+            &["impl Fn(u32) -> u32"],
+        );
+    }
+
+    #[test]
+    fn hover_shows_keyword_docs() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            fn main() {
+                let f = asy<|>nc { 0 };
+            }
+
+            //- /std/lib.rs
+            mod keyword_docs {
+                #[doc(keyword = "async")]
+                /// Return a Future instead of blocking the current thread.
+                mod async_keyword {}
+            }
+        "#,
+            &["async\n```\n\nReturn a Future instead of blocking the current thread."],
+        );
+    }
+
+    #[test]
+    fn hover_shows_primitive_docs() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            fn foo(x: u3<|>2) {}
+
+            //- /std/lib.rs
+            mod primitive_docs {
+                #[doc(primitive = "u32")]
+                /// The 32-bit unsigned integer type.
+                mod prim_u32 {}
+            }
+        "#,
+            &["u32\n```\n\nThe 32-bit unsigned integer type."],
+        );
+    }
+
     #[test]
     fn hover_omits_default_generic_types() {
         check_hover_result(
@@ -587,7 +1177,7 @@ fn func(foo: i32) { if true { <|>foo; }; }
             ",
         );
         let hover = analysis.hover(position).unwrap().unwrap();
-        assert_eq!(trim_markup_opt(hover.info.first()), Some("const C: u32"));
+        assert_eq!(trim_markup_opt(hover.info.first()), Some("const C: u32 = 1 (0x1)"));
     }
 
     #[test]
@@ -706,7 +1296,7 @@ fn func(foo: i32) { if true { <|>foo; }; }
                 }
             }
             ",
-            &["fn foo()"],
+            &["fn bar(){ foo(); }\n```\n\nExpanded from `id!`", "fn foo()"],
         );
 
         assert_eq!(hover_on, "foo")
@@ -796,7 +1386,7 @@ fn func(foo: i32) { if true { <|>foo; }; }
 
     #[test]
     fn test_hover_through_assert_macro() {
-        let hover_on = check_hover_result(
+        let (analysis, position) = analysis_and_position(
             r#"
             //- /lib.rs
             #[rustc_builtin_macro]
@@ -807,10 +1397,37 @@ fn func(foo: i32) { if true { <|>foo; }; }
                 assert!(ba<|>r());
             }
             "#,
-            &["fn bar() -> bool"],
         );
+        let hover = analysis.hover(position).unwrap().unwrap();
+        let results = hover.info.results();
+        assert_eq!(trim_markup(&results[0]), "fn bar() -> bool");
+        assert!(results[1].ends_with("Expanded from `assert!`"));
+        assert_eq!(results.len(), 2);
 
-        assert_eq!(hover_on, "bar");
+        let content = analysis.db.file_text(position.file_id);
+        assert_eq!(&content[hover.range], "bar");
+    }
+
+    #[test]
+    fn test_hover_in_macro_truncates_expansion_preview() {
+        check_hover_result(
+            "
+            //- /lib.rs
+            macro_rules! id {
+                ($($tt:tt)*) => { $($tt)* }
+            }
+            fn foo() {}
+            id! {
+                fn bar() {
+                    fo<|>o(); foo(); foo(); foo(); foo(); foo(); foo(); foo(); foo(); foo();
+                }
+            }
+            ",
+            &[
+                "fn bar(){ foo(); foo(); foo(); foo(); foo(); foo(); foo(); f…\n```\n\nExpanded from `id!`",
+                "fn foo()",
+            ],
+        );
     }
 
     #[test]
@@ -880,4 +1497,22 @@ fn func(foo: i32) { if true { <|>foo; }; }
             &["unsafe trait foo"],
         );
     }
+
+    #[test]
+    fn test_hover_intra_doc_link() {
+        let range = check_hover_result(
+            "
+            //- /lib.rs
+            /// Wraps a [`Foo::ne<|>w`].
+            struct Wrapper;
+            struct Foo;
+            impl Foo {
+                /// Makes a foo.
+                fn new() -> Foo { Foo }
+            }
+            ",
+            &["Foo\nfn new() -> Foo\n```\n\nMakes a foo."],
+        );
+        assert_eq!(range, "`Foo::new`");
+    }
 }
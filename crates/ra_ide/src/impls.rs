@@ -1,7 +1,7 @@
 //! FIXME: write short doc here
 
-use hir::{Crate, ImplDef, Semantics};
-use ra_ide_db::RootDatabase;
+use hir::{AsAssocItem, Semantics};
+use ra_ide_db::{impls, RootDatabase};
 use ra_syntax::{algo::find_node_at_offset, ast, AstNode};
 
 use crate::{display::ToNav, FilePosition, NavigationTarget, RangeInfo};
@@ -14,17 +14,21 @@ pub(crate) fn goto_implementation(
     let source_file = sema.parse(position.file_id);
     let syntax = source_file.syntax().clone();
 
-    let krate = sema.to_module_def(position.file_id)?.krate();
+    if let Some(fn_def) = find_node_at_offset::<ast::FnDef>(&syntax, position.offset) {
+        if let Some(navs) = impls_for_trait_method(&sema, &fn_def) {
+            return Some(RangeInfo::new(fn_def.syntax().text_range(), navs));
+        }
+    }
 
     if let Some(nominal_def) = find_node_at_offset::<ast::NominalDef>(&syntax, position.offset) {
         return Some(RangeInfo::new(
             nominal_def.syntax().text_range(),
-            impls_for_def(&sema, &nominal_def, krate)?,
+            impls_for_def(&sema, &nominal_def)?,
         ));
     } else if let Some(trait_def) = find_node_at_offset::<ast::TraitDef>(&syntax, position.offset) {
         return Some(RangeInfo::new(
             trait_def.syntax().text_range(),
-            impls_for_trait(&sema, &trait_def, krate)?,
+            impls_for_trait(&sema, &trait_def)?,
         ));
     }
 
@@ -34,37 +38,45 @@ pub(crate) fn goto_implementation(
 fn impls_for_def(
     sema: &Semantics<RootDatabase>,
     node: &ast::NominalDef,
-    krate: Crate,
 ) -> Option<Vec<NavigationTarget>> {
-    let ty = match node {
-        ast::NominalDef::StructDef(def) => sema.to_def(def)?.ty(sema.db),
-        ast::NominalDef::EnumDef(def) => sema.to_def(def)?.ty(sema.db),
-        ast::NominalDef::UnionDef(def) => sema.to_def(def)?.ty(sema.db),
+    let adt: hir::Adt = match node {
+        ast::NominalDef::StructDef(def) => sema.to_def(def)?.into(),
+        ast::NominalDef::EnumDef(def) => sema.to_def(def)?.into(),
+        ast::NominalDef::UnionDef(def) => sema.to_def(def)?.into(),
     };
 
-    let impls = ImplDef::all_in_crate(sema.db, krate);
+    let impls = impls::impls_for_adt(sema.db, adt);
 
-    Some(
-        impls
-            .into_iter()
-            .filter(|impl_def| ty.is_equal_for_find_impls(&impl_def.target_ty(sema.db)))
-            .map(|imp| imp.to_nav(sema.db))
-            .collect(),
-    )
+    Some(impls.into_iter().map(|imp| imp.to_nav(sema.db)).collect())
 }
 
 fn impls_for_trait(
     sema: &Semantics<RootDatabase>,
     node: &ast::TraitDef,
-    krate: Crate,
 ) -> Option<Vec<NavigationTarget>> {
     let tr = sema.to_def(node)?;
 
-    let impls = ImplDef::for_trait(sema.db, krate, tr);
+    let impls = impls::impls_for_trait(sema.db, tr);
 
     Some(impls.into_iter().map(|imp| imp.to_nav(sema.db)).collect())
 }
 
+/// The implementations of `node`, if it is a trait method.
+fn impls_for_trait_method(
+    sema: &Semantics<RootDatabase>,
+    node: &ast::FnDef,
+) -> Option<Vec<NavigationTarget>> {
+    let func = sema.to_def(node)?;
+    match func.as_assoc_item(sema.db)?.container(sema.db) {
+        hir::AssocItemContainer::Trait(_) => {}
+        hir::AssocItemContainer::ImplDef(_) => return None,
+    }
+
+    let impls = impls::impls_for_trait_method(sema.db, func);
+
+    Some(impls.into_iter().map(|func| func.to_nav(sema.db)).collect())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::mock_analysis::analysis_and_position;
@@ -198,4 +210,71 @@ mod tests {
             &["impl IMPL_DEF FileId(1) 0..15"],
         );
     }
+
+    #[test]
+    fn goto_implementation_for_trait_in_dependent_crates() {
+        check_goto(
+            "
+            //- /lib.rs crate:lib
+            pub trait T<|> {}
+            pub struct Foo;
+            impl T for Foo {}
+            //- /main.rs crate:main deps:lib
+            struct Bar;
+            impl lib::T for Bar {}
+            ",
+            &["impl IMPL_DEF FileId(1) 31..48", "impl IMPL_DEF FileId(2) 12..34"],
+        );
+    }
+
+    #[test]
+    fn goto_implementation_for_type_in_dependent_crates() {
+        check_goto(
+            "
+            //- /lib.rs crate:lib
+            pub struct Foo<|>;
+            impl Foo {}
+            //- /main.rs crate:main deps:lib
+            trait T {}
+            impl T for lib::Foo {}
+            ",
+            &["impl IMPL_DEF FileId(1) 16..27", "impl IMPL_DEF FileId(2) 11..33"],
+        );
+    }
+
+    #[test]
+    fn goto_implementation_for_trait_method() {
+        check_goto(
+            "
+            //- /lib.rs
+            trait T {
+                fn f<|>(&self);
+            }
+            struct A;
+            impl T for A {
+                fn f(&self) {}
+            }
+            struct B;
+            impl T for B {
+                fn f(&self) {}
+            }
+            ",
+            &["f FN_DEF FileId(1) 58..72 61..62", "f FN_DEF FileId(1) 104..118 107..108"],
+        );
+    }
+
+    #[test]
+    fn goto_implementation_for_trait_method_skips_default_impls() {
+        check_goto(
+            "
+            //- /lib.rs
+            trait T {
+                fn f<|>(&self) {}
+            }
+            struct A;
+            impl T for A {}
+            ",
+            &[],
+        );
+    }
 }
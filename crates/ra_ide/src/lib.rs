@@ -26,7 +26,9 @@ mod goto_definition;
 mod goto_type_definition;
 mod extend_selection;
 mod hover;
+mod doc_links;
 mod call_hierarchy;
+mod type_hierarchy;
 mod call_info;
 mod syntax_highlighting;
 mod parent_module;
@@ -43,6 +45,7 @@ mod display;
 mod inlay_hints;
 mod expand_macro;
 mod ssr;
+mod change_signature;
 
 #[cfg(test)]
 mod marks;
@@ -65,17 +68,20 @@ use ra_syntax::{SourceFile, TextRange, TextSize};
 use crate::display::ToNav;
 
 pub use crate::{
-    assists::{Assist, AssistId},
+    assists::{Assist, AssistConfig, AssistId, AssistKind, InsertUseConfig, MergeBehaviour},
     call_hierarchy::CallItem,
+    change_signature::SignatureParam,
     completion::{
         CompletionConfig, CompletionItem, CompletionItemKind, CompletionScore, InsertTextFormat,
+        Snippet, SnippetScope,
     },
-    diagnostics::Severity,
+    diagnostics::{DiagnosticsConfig, Severity},
     display::{file_structure, FunctionSignature, NavigationTarget, StructureNode},
     expand_macro::ExpandedMacro,
     folding_ranges::{Fold, FoldKind},
-    hover::HoverResult,
+    hover::{HoverAction, HoverResult},
     inlay_hints::{InlayHint, InlayHintsConfig, InlayKind},
+    prime_caches::PrimeCachesProgress,
     references::{Declaration, Reference, ReferenceAccess, ReferenceKind, ReferenceSearchResult},
     runnables::{Runnable, RunnableKind, TestId},
     source_change::{FileSystemEdit, SourceChange, SourceFileEdit},
@@ -85,7 +91,7 @@ pub use crate::{
     },
 };
 
-pub use hir::Documentation;
+pub use hir::{Documentation, TraitSolverLimits};
 pub use ra_db::{
     Canceled, CrateGraph, CrateId, Edition, FileId, FilePosition, FileRange, SourceRootId,
 };
@@ -104,8 +110,17 @@ pub type Cancelable<T> = Result<T, Canceled>;
 pub struct Diagnostic {
     pub message: String,
     pub range: TextRange,
-    pub fix: Option<SourceChange>,
+    pub fixes: Vec<SourceChange>,
+    /// Other locations relevant to this diagnostic, like the definition of
+    /// the item it is about, with a message explaining each.
+    pub related: Vec<(FileId, TextRange, String)>,
     pub severity: Severity,
+    /// Whether the diagnostic is about code that has no effect, which clients
+    /// may render faded out.
+    pub unused: bool,
+    /// A stable name of the kind of this diagnostic, which can be disabled
+    /// via `DiagnosticsConfig`.
+    pub code: &'static str,
 }
 
 /// Info associated with a text range.
@@ -150,6 +165,10 @@ impl AnalysisHost {
         self.db.update_lru_capacity(lru_capacity);
     }
 
+    pub fn update_trait_solver_limits(&mut self, limits: TraitSolverLimits) {
+        self.db.update_trait_solver_limits(limits);
+    }
+
     /// Returns a snapshot of the current state, which you can query for
     /// semantic information.
     pub fn analysis(&self) -> Analysis {
@@ -238,8 +257,17 @@ impl Analysis {
         self.with_db(|db| status::status(&*db))
     }
 
-    pub fn prime_caches(&self, files: Vec<FileId>) -> Cancelable<()> {
-        self.with_db(|db| prime_caches::prime_caches(db, files))
+    /// Computes def maps and symbol indices for all crates, reporting progress
+    /// after each crate, and highlights the given `files`.
+    pub fn prime_caches(
+        &self,
+        files: Vec<FileId>,
+        cb: &(dyn Fn(PrimeCachesProgress) + Sync),
+    ) -> Cancelable<()> {
+        // The callback only reports progress, so observing it in a broken state
+        // after a cancellation is harmless.
+        let cb = std::panic::AssertUnwindSafe(cb);
+        self.with_db(move |db| prime_caches::prime_caches(db, files, cb.0))
     }
 
     /// Gets the text of the source file.
@@ -413,6 +441,24 @@ impl Analysis {
         self.with_db(|db| call_hierarchy::outgoing_calls(db, position))
     }
 
+    /// Computes type hierarchy candidates for the given file position.
+    pub fn type_hierarchy(
+        &self,
+        position: FilePosition,
+    ) -> Cancelable<Option<RangeInfo<Vec<NavigationTarget>>>> {
+        self.with_db(|db| type_hierarchy::type_hierarchy(db, position))
+    }
+
+    /// Computes the supertypes of the trait or type at the given file position.
+    pub fn supertypes(&self, position: FilePosition) -> Cancelable<Option<Vec<NavigationTarget>>> {
+        self.with_db(|db| type_hierarchy::supertypes(db, position))
+    }
+
+    /// Computes the subtypes of the trait or type at the given file position.
+    pub fn subtypes(&self, position: FilePosition) -> Cancelable<Option<Vec<NavigationTarget>>> {
+        self.with_db(|db| type_hierarchy::subtypes(db, position))
+    }
+
     /// Returns a `mod name;` declaration which created the current module.
     pub fn parent_module(&self, position: FilePosition) -> Cancelable<Vec<NavigationTarget>> {
         self.with_db(|db| parent_module::parent_module(db, position))
@@ -463,14 +509,54 @@ impl Analysis {
     }
 
     /// Computes assists (aka code actions aka intentions) for the given
-    /// position.
-    pub fn assists(&self, frange: FileRange) -> Cancelable<Vec<Assist>> {
-        self.with_db(|db| assists::assists(db, frange))
+    /// position. Their edits are only computed if `resolve` is set.
+    pub fn assists(
+        &self,
+        config: &AssistConfig,
+        resolve: bool,
+        frange: FileRange,
+    ) -> Cancelable<Vec<Assist>> {
+        self.with_db(|db| assists::assists(db, config, resolve, frange))
+    }
+
+    /// Computes the edit of the assist with the given `id` and `label`, as
+    /// returned by an unresolved `assists` call.
+    pub fn resolve_assist(
+        &self,
+        config: &AssistConfig,
+        frange: FileRange,
+        id: &str,
+        label: &str,
+    ) -> Cancelable<Option<Assist>> {
+        self.with_db(|db| assists::resolve_assist_edit(db, config, frange, id, label))
+    }
+
+    /// Sorts, groups and merges the `use` items of the given file.
+    pub fn organize_imports(
+        &self,
+        config: &InsertUseConfig,
+        file_id: FileId,
+    ) -> Cancelable<Option<SourceChange>> {
+        self.with_db(|db| assists::organize_imports(db, config, file_id))
     }
 
     /// Computes the set of diagnostics for the given file.
-    pub fn diagnostics(&self, file_id: FileId) -> Cancelable<Vec<Diagnostic>> {
-        self.with_db(|db| diagnostics::diagnostics(db, file_id))
+    pub fn diagnostics(
+        &self,
+        config: &DiagnosticsConfig,
+        file_id: FileId,
+    ) -> Cancelable<Vec<Diagnostic>> {
+        self.with_db(|db| diagnostics::diagnostics(db, config, file_id))
+    }
+
+    /// Computes a single change applying all the diagnostic fixes in the file
+    /// which don't need a choice from the user.
+    pub fn fix_all(
+        &self,
+        config: &DiagnosticsConfig,
+        file_id: FileId,
+    ) -> Cancelable<Option<SourceChange>> {
+        self.with_db(|db| diagnostics::fix_all(db, config, file_id))
     }
 
     /// Returns the edit required to rename reference at the position to the new
@@ -483,6 +569,16 @@ impl Analysis {
         self.with_db(|db| references::rename(db, position, new_name))
     }
 
+    /// Returns the edit changing the parameters of the function at the
+    /// position to `params`, together with all of its call sites.
+    pub fn change_signature(
+        &self,
+        position: FilePosition,
+        params: Vec<SignatureParam>,
+    ) -> Cancelable<Option<SourceChange>> {
+        self.with_db(|db| change_signature::change_signature(db, position, &params))
+    }
+
     pub fn structural_search_replace(
         &self,
         query: &str,
@@ -511,7 +607,13 @@ fn analysis_is_send() {
 
 #[cfg(test)]
 mod tests {
-    use crate::{display::NavigationTarget, mock_analysis::single_file, Query};
+    use std::sync::Arc;
+
+    use crate::{
+        display::NavigationTarget,
+        mock_analysis::{single_file, MockAnalysis},
+        AnalysisChange, AnalysisHost, Query,
+    };
     use ra_syntax::{
         SmolStr,
         SyntaxKind::{FN_DEF, STRUCT_DEF},
@@ -577,6 +679,61 @@ struct Foo;
         assert_eq!(struct_match, Some(STRUCT_DEF));
     }
 
+    #[test]
+    fn test_world_symbols_are_updated_on_file_change() {
+        let mock = MockAnalysis::with_files(
+            r#"
+//- /lib.rs
+mod foo;
+struct Bar;
+
+//- /foo.rs
+struct FooBar;
+"#,
+        );
+        let file_id = mock.id_of("/foo.rs");
+        let mut host = mock.analysis_host();
+
+        let symbol_names = |host: &AnalysisHost, query: Query| {
+            let symbols = host.analysis().symbol_search(query).unwrap();
+            let mut names = symbols.iter().map(|it| it.name().to_string()).collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+        assert_eq!(symbol_names(&host, Query::new("bar".into())), vec!["Bar", "FooBar"]);
+
+        let mut change = AnalysisChange::new();
+        change.change_file(file_id, Arc::new("struct BazBar;".to_string()));
+        host.apply_change(change);
+        assert_eq!(symbol_names(&host, Query::new("bar".into())), vec!["Bar", "BazBar"]);
+
+        let mut query = Query::new("BazBar".into());
+        query.exact();
+        assert_eq!(symbol_names(&host, query), vec!["BazBar"]);
+    }
+
+    #[test]
+    fn test_world_symbols_query_syntax() {
+        let code = r#"
+fn foo_bar() {}
+struct FooBar;
+mod foo {}
+"#;
+        let symbol_names = |query: &str| {
+            let (analysis, _) = single_file(code);
+            let symbols = analysis.symbol_search(Query::parse(query)).unwrap();
+            let mut names = symbols.iter().map(|it| it.name().to_string()).collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+
+        assert_eq!(symbol_names("foo"), vec!["FooBar", "foo", "foo_bar"]);
+        assert_eq!(symbol_names("foo#"), vec!["FooBar"]);
+        assert_eq!(symbol_names("foo*"), vec!["foo"]);
+        assert_eq!(symbol_names("foo kind:fn"), vec!["foo_bar"]);
+        assert_eq!(symbol_names("kind:module foo"), vec!["foo"]);
+    }
+
     fn get_symbols_matching(text: &str, query: &str) -> Vec<NavigationTarget> {
         let (analysis, _) = single_file(text);
         analysis.symbol_search(Query::new(query.into())).unwrap()
@@ -3,10 +3,115 @@
 //! request takes longer to compute. This modules implemented prepopulating of
 //! various caches, it's not really advanced at the moment.
 
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use hir::db::DefDatabase;
+use ra_db::{salsa::ParallelDatabase, CrateGraph, CrateId, SourceDatabase, SourceDatabaseExt};
+use ra_ide_db::symbol_index::SymbolsDatabase;
+#[cfg(not(feature = "wasm"))]
+use rayon::prelude::*;
+use rustc_hash::FxHashMap;
+
 use crate::{FileId, RootDatabase};
 
-pub(crate) fn prime_caches(db: &RootDatabase, files: Vec<FileId>) {
+/// Reported after each crate whose caches have been primed.
+#[derive(Debug)]
+pub struct PrimeCachesProgress {
+    pub on_crate: String,
+    pub n_done: usize,
+    pub n_total: usize,
+}
+
+pub(crate) fn prime_caches(
+    db: &RootDatabase,
+    files: Vec<FileId>,
+    cb: &(dyn Fn(PrimeCachesProgress) + Sync),
+) {
+    let graph = db.crate_graph();
+    let n_total = graph.iter().count();
+    let n_done = AtomicUsize::new(0);
+
+    // Crates are primed in dependency order, so that the def maps of a crate's
+    // dependencies are already computed by the time the crate itself is. The
+    // crates within one level don't depend on each other, so they're primed in
+    // parallel.
+    for level in crates_by_depth(&graph) {
+        let prime_crate = |db: &RootDatabase, krate: CrateId| {
+            db.crate_def_map(krate);
+            let on_crate = graph[krate]
+                .display_name
+                .as_ref()
+                .map_or_else(|| format!("{:?}", krate), |it| it.to_string());
+            let n_done = n_done.fetch_add(1, Ordering::SeqCst) + 1;
+            cb(PrimeCachesProgress { on_crate, n_done, n_total });
+        };
+
+        #[cfg(not(feature = "wasm"))]
+        level
+            .par_iter()
+            .for_each_with(Snap(db.snapshot()), |snap, &krate| prime_crate(&*snap.0, krate));
+
+        #[cfg(feature = "wasm")]
+        level.iter().for_each(|&krate| prime_crate(db, krate));
+    }
+
+    let mut local_files = Vec::new();
+    for &root in db.local_roots().iter() {
+        local_files.extend(db.source_root(root).walk());
+    }
+
+    #[cfg(not(feature = "wasm"))]
+    local_files.par_iter().for_each_with(Snap(db.snapshot()), |snap, &file_id| {
+        snap.0.file_symbols(file_id);
+    });
+
+    #[cfg(feature = "wasm")]
+    local_files.iter().for_each(|&file_id| {
+        db.file_symbols(file_id);
+    });
+
     for file in files {
         let _ = crate::syntax_highlighting::highlight(db, file, None);
     }
 }
+
+/// Groups crates by the length of the longest path to a crate without
+/// dependencies.
+fn crates_by_depth(graph: &CrateGraph) -> Vec<Vec<CrateId>> {
+    fn depth(graph: &CrateGraph, krate: CrateId, memo: &mut FxHashMap<CrateId, usize>) -> usize {
+        if let Some(&depth) = memo.get(&krate) {
+            return depth;
+        }
+        let depth = graph[krate]
+            .dependencies
+            .iter()
+            .map(|dep| depth(graph, dep.crate_id, memo) + 1)
+            .max()
+            .unwrap_or(0);
+        memo.insert(krate, depth);
+        depth
+    }
+
+    let mut memo = FxHashMap::default();
+    let mut levels: Vec<Vec<CrateId>> = Vec::new();
+    for krate in graph.iter() {
+        let depth = depth(graph, krate, &mut memo);
+        if levels.len() <= depth {
+            levels.resize_with(depth + 1, Vec::new);
+        }
+        levels[depth].push(krate);
+    }
+    levels
+}
+
+/// `for_each_with` clones its state for each worker, but snapshots can only be
+/// cloned explicitly.
+#[cfg(not(feature = "wasm"))]
+struct Snap(ra_db::salsa::Snapshot<RootDatabase>);
+
+#[cfg(not(feature = "wasm"))]
+impl Clone for Snap {
+    fn clone(&self) -> Snap {
+        Snap(self.0.snapshot())
+    }
+}
@@ -593,6 +593,61 @@ mod tests {
         check_result(refs, "i BIND_PAT FileId(1) 36..37 Other", &["FileId(1) 51..52 Other Write"]);
     }
 
+    #[test]
+    fn test_basic_highlight_compound_assignment_write() {
+        let code = r#"
+        fn foo() {
+            let mut i<|> = 0;
+            i += 1;
+        }"#;
+
+        let refs = get_all_refs(code);
+        check_result(
+            refs,
+            "i BIND_PAT FileId(1) 40..41 Other Write",
+            &["FileId(1) 59..60 Other Write"],
+        );
+    }
+
+    #[test]
+    fn test_basic_highlight_mut_borrow_write() {
+        let code = r#"
+        fn foo() {
+            let mut i<|> = 0;
+            let a = &mut i;
+            let b = &i;
+        }"#;
+
+        let refs = get_all_refs(code);
+        check_result(
+            refs,
+            "i BIND_PAT FileId(1) 40..41 Other Write",
+            &["FileId(1) 72..73 Other Write", "FileId(1) 96..97 Other Read"],
+        );
+    }
+
+    #[test]
+    fn test_basic_highlight_mut_autoref_write() {
+        let code = r#"
+        struct Foo;
+        impl Foo {
+            fn set(&mut self) {}
+            fn get(&self) {}
+        }
+        fn foo() {
+            let mut i<|> = Foo;
+            i.set();
+            i.get();
+        }"#;
+
+        let refs = get_all_refs(code);
+        check_result(
+            refs,
+            "i BIND_PAT FileId(1) 151..152 Other Write",
+            &["FileId(1) 172..173 Other Write", "FileId(1) 193..194 Other Read"],
+        );
+    }
+
     fn get_all_refs(text: &str) -> ReferenceSearchResult {
         let (analysis, position) = single_file_with_position(text);
         analysis.find_all_refs(position, None).unwrap().unwrap()
@@ -558,6 +558,7 @@ mod tests {
                         },
                     ],
                     cursor_position: None,
+                    is_snippet: false,
                 },
             },
         )
@@ -610,6 +611,7 @@ mod tests {
                         },
                     ],
                     cursor_position: None,
+                    is_snippet: false,
                 },
             },
         )
@@ -706,6 +708,7 @@ mod tests {
                         },
                     ],
                     cursor_position: None,
+                    is_snippet: false,
                 },
             },
         )
@@ -14,6 +14,8 @@ pub struct SourceChange {
     pub source_file_edits: Vec<SourceFileEdit>,
     pub file_system_edits: Vec<FileSystemEdit>,
     pub cursor_position: Option<FilePosition>,
+    /// Whether the edits contain snippet tabstops like `$0`.
+    pub is_snippet: bool,
 }
 
 impl SourceChange {
@@ -29,6 +31,7 @@ impl SourceChange {
             source_file_edits,
             file_system_edits,
             cursor_position: None,
+            is_snippet: false,
         }
     }
 
@@ -42,6 +45,7 @@ impl SourceChange {
             source_file_edits: edits,
             file_system_edits: vec![],
             cursor_position: None,
+            is_snippet: false,
         }
     }
 
@@ -53,6 +57,7 @@ impl SourceChange {
             source_file_edits: vec![],
             file_system_edits: edits,
             cursor_position: None,
+            is_snippet: false,
         }
     }
 
@@ -99,7 +104,7 @@ pub struct SourceFileEdit {
 
 #[derive(Debug)]
 pub enum FileSystemEdit {
-    CreateFile { source_root: SourceRootId, path: RelativePathBuf },
+    CreateFile { source_root: SourceRootId, path: RelativePathBuf, contents: String },
     MoveFile { src: FileId, dst_source_root: SourceRootId, dst_path: RelativePathBuf },
 }
 
@@ -116,6 +121,7 @@ impl SingleFileChange {
             source_file_edits: vec![SourceFileEdit { file_id, edit: self.edit }],
             file_system_edits: Vec::new(),
             cursor_position: self.cursor_position.map(|offset| FilePosition { file_id, offset }),
+            is_snippet: false,
         }
     }
 }
@@ -0,0 +1,168 @@
+//! Entry point for type-hierarchy
+//!
+//! The supertypes of a trait are its supertraits, and its subtypes are the
+//! types implementing it. The supertypes of a type are the traits it
+//! implements; types have no subtypes.
+
+use hir::{Adt, Semantics, Trait};
+use ra_ide_db::{impls, RootDatabase};
+use ra_syntax::{
+    algo::find_node_at_offset,
+    ast,
+    SyntaxKind::{ENUM_DEF, STRUCT_DEF, TRAIT_DEF, UNION_DEF},
+};
+
+use crate::{display::ToNav, goto_definition, FilePosition, NavigationTarget, RangeInfo};
+
+pub(crate) fn type_hierarchy(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<RangeInfo<Vec<NavigationTarget>>> {
+    let mut navs = goto_definition::goto_definition(db, position)?;
+    // Only traits and ADTs take part in the hierarchy.
+    navs.info.retain(|nav| match nav.kind() {
+        STRUCT_DEF | ENUM_DEF | UNION_DEF | TRAIT_DEF => true,
+        _ => false,
+    });
+    if navs.info.is_empty() {
+        return None;
+    }
+    Some(navs)
+}
+
+pub(crate) fn supertypes(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<Vec<NavigationTarget>> {
+    let sema = Semantics::new(db);
+    let traits = match item_at(&sema, position)? {
+        Item::Trait(it) => it.super_traits(db),
+        Item::Adt(it) => {
+            let mut traits = Vec::new();
+            for trait_ in impls::impls_for_adt(db, it).into_iter().filter_map(|it| it.trait_(db)) {
+                if !traits.contains(&trait_) {
+                    traits.push(trait_);
+                }
+            }
+            traits
+        }
+    };
+    Some(traits.into_iter().map(|it| it.to_nav(db)).collect())
+}
+
+pub(crate) fn subtypes(db: &RootDatabase, position: FilePosition) -> Option<Vec<NavigationTarget>> {
+    let sema = Semantics::new(db);
+    let trait_ = match item_at(&sema, position)? {
+        Item::Trait(it) => it,
+        Item::Adt(_) => return Some(Vec::new()),
+    };
+    let mut res = Vec::new();
+    for impl_def in impls::impls_for_trait(db, trait_) {
+        // Types which can't be navigated to, like `u32` or `&Foo`, are
+        // represented by their impl.
+        let nav = match impl_def.target_ty(db).as_adt() {
+            Some(adt) => adt.to_nav(db),
+            None => impl_def.to_nav(db),
+        };
+        if !res.contains(&nav) {
+            res.push(nav);
+        }
+    }
+    Some(res)
+}
+
+enum Item {
+    Adt(Adt),
+    Trait(Trait),
+}
+
+fn item_at(sema: &Semantics<RootDatabase>, position: FilePosition) -> Option<Item> {
+    let file = sema.parse(position.file_id);
+    let file = file.syntax();
+    if let Some(def) = find_node_at_offset::<ast::NominalDef>(file, position.offset) {
+        let adt = match def {
+            ast::NominalDef::StructDef(it) => sema.to_def(&it)?.into(),
+            ast::NominalDef::EnumDef(it) => sema.to_def(&it)?.into(),
+            ast::NominalDef::UnionDef(it) => sema.to_def(&it)?.into(),
+        };
+        return Some(Item::Adt(adt));
+    }
+    let def = find_node_at_offset::<ast::TraitDef>(file, position.offset)?;
+    Some(Item::Trait(sema.to_def(&def)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mock_analysis::analysis_and_position, FilePosition, NavigationTarget};
+
+    fn check_navs(mut navs: Vec<NavigationTarget>, expected: &[&str]) {
+        assert_eq!(navs.len(), expected.len());
+        navs.sort_by_key(|nav| (nav.file_id(), nav.full_range().start()));
+        navs.into_iter().enumerate().for_each(|(i, nav)| nav.assert_match(expected[i]));
+    }
+
+    fn check_hierarchy(
+        fixture: &str,
+        expected: &str,
+        expected_supertypes: &[&str],
+        expected_subtypes: &[&str],
+    ) {
+        let (analysis, pos) = analysis_and_position(fixture);
+
+        let mut navs = analysis.type_hierarchy(pos).unwrap().unwrap().info;
+        assert_eq!(navs.len(), 1);
+        let nav = navs.pop().unwrap();
+        nav.assert_match(expected);
+
+        let item_pos = FilePosition { file_id: nav.file_id(), offset: nav.range().start() };
+        check_navs(analysis.supertypes(item_pos).unwrap().unwrap(), expected_supertypes);
+        check_navs(analysis.subtypes(item_pos).unwrap().unwrap(), expected_subtypes);
+    }
+
+    #[test]
+    fn test_type_hierarchy_of_trait() {
+        check_hierarchy(
+            r#"
+            //- /lib.rs
+            trait Base {}
+            trait Sub<|>: Base {}
+            struct Foo;
+            impl Sub for Foo {}
+            impl Sub for u32 {}
+            "#,
+            "Sub TRAIT_DEF FileId(1) 14..32 20..23",
+            &["Base TRAIT_DEF FileId(1) 0..13 6..10"],
+            &["Foo STRUCT_DEF FileId(1) 33..44 40..43", "impl IMPL_DEF FileId(1) 65..84"],
+        );
+    }
+
+    #[test]
+    fn test_type_hierarchy_of_type() {
+        check_hierarchy(
+            r#"
+            //- /lib.rs
+            trait A {}
+            trait B {}
+            struct Foo;
+            impl A for Foo {}
+            impl B for Foo {}
+            impl A for Foo {}
+            fn f(_: Fo<|>o) {}
+            "#,
+            "Foo STRUCT_DEF FileId(1) 22..33 29..32",
+            &["A TRAIT_DEF FileId(1) 0..10 6..7", "B TRAIT_DEF FileId(1) 11..21 17..18"],
+            &[],
+        );
+    }
+
+    #[test]
+    fn test_type_hierarchy_ignores_functions() {
+        let (analysis, pos) = analysis_and_position(
+            r#"
+            //- /lib.rs
+            fn foo<|>() {}
+            "#,
+        );
+        assert!(analysis.type_hierarchy(pos).unwrap().is_none());
+    }
+}
@@ -1,8 +1,9 @@
 //! Defines a unit of change that can applied to a state of IDE to get the next
 //! state. Changes are transactional.
 
-use std::{fmt, sync::Arc, time};
+use std::{fmt, path::Path, sync::Arc, time};
 
+use hir::{db::DefDatabase, RawItems};
 use ra_db::{
     salsa::{Database, Durability, SweepStrategy},
     CrateGraph, FileId, RelativePathBuf, SourceDatabase, SourceDatabaseExt, SourceRoot,
@@ -15,6 +16,7 @@ use rayon::prelude::*;
 use rustc_hash::FxHashMap;
 
 use crate::{
+    library_cache::LibraryCache,
     symbol_index::{SymbolIndex, SymbolsDatabase},
     DebugData, RootDatabase,
 };
@@ -125,6 +127,9 @@ pub struct LibraryData {
     root_id: SourceRootId,
     root_change: RootChange,
     symbol_index: SymbolIndex,
+    /// Item trees of the library's files, if they were loaded from or stored
+    /// in the on-disk cache.
+    item_trees: FxHashMap<FileId, Arc<RawItems>>,
 }
 
 impl fmt::Debug for LibraryData {
@@ -138,27 +143,56 @@ impl fmt::Debug for LibraryData {
 }
 
 impl LibraryData {
+    /// Indexes the files of a library. If `cache_dir` is set, the index and
+    /// the item trees of the files are loaded from there when this library was
+    /// already indexed before, and stored there otherwise.
     pub fn prepare(
         root_id: SourceRootId,
         files: Vec<(FileId, RelativePathBuf, Arc<String>)>,
+        cache_dir: Option<&Path>,
     ) -> LibraryData {
         let _p = profile("LibraryData::prepare");
 
-        #[cfg(not(feature = "wasm"))]
-        let iter = files.par_iter();
-        #[cfg(feature = "wasm")]
-        let iter = files.iter();
-
-        let symbol_index = SymbolIndex::for_files(iter.map(|(file_id, _, text)| {
-            let parse = SourceFile::parse(text);
-            (*file_id, parse)
-        }));
+        let cache = cache_dir.map(|dir| LibraryCache::new(dir, &files));
+        let (symbol_index, item_trees) = match cache.as_ref().and_then(|cache| cache.load()) {
+            Some(it) => (it.symbol_index, it.item_trees),
+            None => {
+                #[cfg(not(feature = "wasm"))]
+                let iter = files.par_iter();
+                #[cfg(feature = "wasm")]
+                let iter = files.iter();
+
+                let parses: Vec<_> =
+                    iter.map(|(file_id, _, text)| (*file_id, SourceFile::parse(text))).collect();
+                let item_trees = match &cache {
+                    Some(_) => parses
+                        .iter()
+                        .map(|(file_id, parse)| {
+                            let raw_items = RawItems::from_source_file(*file_id, parse.tree());
+                            (*file_id, Arc::new(raw_items))
+                        })
+                        .collect(),
+                    None => FxHashMap::default(),
+                };
+
+                #[cfg(not(feature = "wasm"))]
+                let parses = parses.into_par_iter();
+                #[cfg(feature = "wasm")]
+                let parses = parses.into_iter();
+
+                let symbol_index = SymbolIndex::for_files(parses);
+                if let Some(cache) = &cache {
+                    cache.store(&symbol_index, &item_trees);
+                }
+                (symbol_index, item_trees)
+            }
+        };
         let mut root_change = RootChange::default();
         root_change.added = files
             .into_iter()
             .map(|(file_id, path, text)| AddFile { file_id, path, text })
             .collect();
-        LibraryData { root_id, root_change, symbol_index }
+        LibraryData { root_id, root_change, symbol_index, item_trees }
     }
 }
 
@@ -199,6 +233,7 @@ impl RootDatabase {
         }
         if !change.libraries_added.is_empty() {
             let mut libraries = Vec::clone(&self.library_roots());
+            let mut item_trees = FxHashMap::clone(&self.library_item_trees());
             for library in change.libraries_added {
                 libraries.push(library.root_id);
                 self.set_source_root_with_durability(
@@ -211,9 +246,11 @@ impl RootDatabase {
                     Arc::new(library.symbol_index),
                     Durability::HIGH,
                 );
+                item_trees.extend(library.item_trees);
                 self.apply_root_change(library.root_id, library.root_change);
             }
             self.set_library_roots_with_durability(Arc::new(libraries), Durability::HIGH);
+            self.set_library_item_trees_with_durability(Arc::new(item_trees), Durability::HIGH);
         }
         if let Some(crate_graph) = change.crate_graph {
             self.set_crate_graph_with_durability(Arc::new(crate_graph), Durability::HIGH)
@@ -315,6 +352,7 @@ impl RootDatabase {
 
             // DefDatabase
             hir::db::RawItemsQuery
+            hir::db::CachedRawItemsQuery
             hir::db::CrateDefMapQueryQuery
             hir::db::StructDataQuery
             hir::db::UnionDataQuery
@@ -359,10 +397,12 @@ impl RootDatabase {
             hir::db::GenericDefaultsQuery
             hir::db::ImplsInCrateQuery
             hir::db::ImplsForTraitQuery
+            hir::db::LookupMethodQuery
             hir::db::InternTypeCtorQuery
             hir::db::InternTypeParamIdQuery
             hir::db::InternChalkImplQuery
             hir::db::InternAssocTyValueQuery
+            hir::db::InternOpaqueBoundsQuery
             hir::db::AssociatedTyDataQuery
             hir::db::TraitDatumQuery
             hir::db::StructDatumQuery
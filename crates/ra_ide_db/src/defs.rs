@@ -181,6 +181,10 @@ fn classify_name_inner(sema: &Semantics<RootDatabase>, name: &ast::Name) -> Opti
                 let def = sema.to_def(&it)?;
                 Some(Definition::Macro(def))
             },
+            ast::MacroDef(it) => {
+                let def = sema.to_def(&it)?;
+                Some(Definition::Macro(def))
+            },
             ast::TypeParam(it) => {
                 let def = sema.to_def(&it)?;
                 Some(Definition::TypeParam(def))
@@ -0,0 +1,89 @@
+//! Finds the impl blocks of traits and types.
+//!
+//! Each crate has an index of its impls by trait (`impls_in_crate`), so this
+//! only has to pick the crates which can contain the impls: an impl must be
+//! in the crate of the trait or of the type, or in a crate that depends on
+//! them.
+
+use hir::{Adt, AsAssocItem, AssocItem, AssocItemContainer, Function, ImplDef, Trait, Type};
+
+use crate::RootDatabase;
+
+/// All the impls of `trait_`.
+pub fn impls_for_trait(db: &RootDatabase, trait_: Trait) -> Vec<ImplDef> {
+    let krate = trait_.module(db).krate();
+    krate
+        .transitive_reverse_dependencies(db)
+        .into_iter()
+        .flat_map(|krate| ImplDef::for_trait(db, krate, trait_))
+        .collect()
+}
+
+/// All the impls for `adt`, both inherent and trait impls. Impls for
+/// references to `adt`, like `impl Trait for &Adt`, are included.
+pub fn impls_for_adt(db: &RootDatabase, adt: Adt) -> Vec<ImplDef> {
+    let ty = adt.ty(db);
+    let krate = adt.module(db).krate();
+    krate
+        .transitive_reverse_dependencies(db)
+        .into_iter()
+        .flat_map(|krate| ImplDef::all_in_crate(db, krate))
+        .filter(|impl_def| ty.is_equal_for_find_impls(&impl_def.target_ty(db)))
+        .collect()
+}
+
+/// The implementations of the trait method `func` in the impls of its trait.
+/// The impls which use the default implementation are skipped.
+pub fn impls_for_trait_method(db: &RootDatabase, func: Function) -> Vec<Function> {
+    let trait_ = match func.as_assoc_item(db).map(|it| it.container(db)) {
+        Some(AssocItemContainer::Trait(it)) => it,
+        _ => return Vec::new(),
+    };
+    let name = func.name(db).to_string();
+    impls_for_trait(db, trait_)
+        .into_iter()
+        .filter_map(|impl_def| find_fn(db, impl_def.items(db), &name))
+        .collect()
+}
+
+/// The trait method the method `func` of a trait impl implements.
+pub fn trait_method_for_impl_method(db: &RootDatabase, func: Function) -> Option<Function> {
+    let impl_def = match func.as_assoc_item(db)?.container(db) {
+        AssocItemContainer::ImplDef(it) => it,
+        AssocItemContainer::Trait(_) => return None,
+    };
+    find_fn(db, impl_def.trait_(db)?.items(db), &func.name(db).to_string())
+}
+
+/// The implementations of the method `name` of `trait_` in the impls of the
+/// trait for `ty`. The method of the trait stands for the impls which use its
+/// default implementation.
+pub fn trait_method_impls_for_type(
+    db: &RootDatabase,
+    trait_: Trait,
+    ty: &Type,
+    name: &str,
+) -> Vec<Function> {
+    let trait_method = find_fn(db, trait_.items(db), name);
+    let mut res = Vec::new();
+    for impl_def in impls_for_trait(db, trait_) {
+        if !ty.is_equal_for_find_impls(&impl_def.target_ty(db)) {
+            continue;
+        }
+        let func = match find_fn(db, impl_def.items(db), name).or(trait_method) {
+            Some(it) => it,
+            None => continue,
+        };
+        if !res.contains(&func) {
+            res.push(func);
+        }
+    }
+    res
+}
+
+fn find_fn(db: &RootDatabase, items: Vec<AssocItem>, name: &str) -> Option<Function> {
+    items.into_iter().find_map(|item| match item {
+        AssocItem::Function(it) if it.name(db).to_string() == name => Some(it),
+        _ => None,
+    })
+}
@@ -23,22 +23,42 @@ impl<'a> ImportsLocator<'a> {
 
     pub fn find_imports(&mut self, name_to_import: &str) -> Vec<Either<ModuleDef, MacroDef>> {
         let _p = profile("search_for_imports");
-        let db = self.sema.db;
+        self.search(name_to_import, true, 40)
+    }
 
-        let project_results = {
-            let mut query = Query::new(name_to_import.to_string());
-            query.exact();
-            query.limit(40);
-            symbol_index::world_symbols(db, query)
-        };
-        let lib_results = {
-            let mut query = Query::new(name_to_import.to_string());
-            query.libs();
-            query.exact();
-            query.limit(40);
-            symbol_index::world_symbols(db, query)
+    /// Like `find_imports`, but finds the items whose name fuzzy matches
+    /// `fuzzy_name` instead of being equal to it.
+    pub fn find_similar_imports(
+        &mut self,
+        fuzzy_name: &str,
+        limit: usize,
+    ) -> Vec<Either<ModuleDef, MacroDef>> {
+        let _p = profile("search_for_similar_imports");
+        self.search(fuzzy_name, false, limit)
+    }
+
+    fn search(
+        &mut self,
+        name: &str,
+        exact: bool,
+        limit: usize,
+    ) -> Vec<Either<ModuleDef, MacroDef>> {
+        let db = self.sema.db;
+        let query = |libs: bool| {
+            let mut query = Query::new(name.to_string());
+            if libs {
+                query.libs();
+            }
+            if exact {
+                query.exact();
+            }
+            query.limit(limit);
+            query
         };
 
+        let project_results = symbol_index::world_symbols(db, query(false));
+        let lib_results = symbol_index::world_symbols(db, query(true));
+
         project_results
             .into_iter()
             .chain(lib_results.into_iter())
@@ -10,11 +10,16 @@ pub mod change;
 pub mod defs;
 pub mod search;
 pub mod imports_locator;
+pub mod impls;
+mod library_cache;
 mod wasm_shims;
 
 use std::sync::Arc;
 
-use hir::db::{AstDatabase, DefDatabase};
+use hir::{
+    db::{AstDatabase, DefDatabase, HirDatabase},
+    TraitSolverLimits,
+};
 use ra_db::{
     salsa::{self, Database, Durability},
     Canceled, CheckCanceled, CrateId, FileId, FileLoader, FileLoaderDelegate, RelativePath,
@@ -115,7 +120,9 @@ impl RootDatabase {
         db.set_crate_graph_with_durability(Default::default(), Durability::HIGH);
         db.set_local_roots_with_durability(Default::default(), Durability::HIGH);
         db.set_library_roots_with_durability(Default::default(), Durability::HIGH);
+        db.set_library_item_trees_with_durability(Default::default(), Durability::HIGH);
         db.update_lru_capacity(lru_capacity);
+        db.set_trait_solver_limits_with_durability(Default::default(), Durability::HIGH);
         db
     }
 
@@ -125,6 +132,12 @@ impl RootDatabase {
         self.query_mut(hir::db::ParseMacroQuery).set_lru_capacity(lru_capacity);
         self.query_mut(hir::db::MacroExpandQuery).set_lru_capacity(lru_capacity);
     }
+
+    pub fn update_trait_solver_limits(&mut self, limits: TraitSolverLimits) {
+        if self.trait_solver_limits() != limits {
+            self.set_trait_solver_limits_with_durability(limits, Durability::HIGH);
+        }
+    }
 }
 
 impl salsa::ParallelDatabase for RootDatabase {
@@ -0,0 +1,185 @@
+//! On-disk cache of library symbol indices and item trees.
+//!
+//! Libraries are assumed to never change, so re-indexing all of them every
+//! time a workspace is opened is wasted work. After a library is indexed, its
+//! `SymbolIndex` and the item trees of its files are written to the cache
+//! directory, under a key derived from the paths and contents of the library's
+//! files. On the next start, a library with the same key is loaded from there
+//! instead of being parsed again.
+//!
+//! The cache is bounded: whenever an entry is written, entries older than
+//! `MAX_AGE` are removed, as are all but the `MAX_ENTRIES` newest ones.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use hir::RawItems;
+use ra_db::{FileId, RelativePathBuf};
+use ra_syntax::SyntaxKind;
+use rustc_hash::FxHashMap;
+use stdx::StableHasher;
+
+use crate::symbol_index::SymbolIndex;
+
+/// Bump this whenever the encoding of `SymbolIndex` or `RawItems` changes.
+const CACHE_VERSION: u64 = 2;
+
+const MAX_ENTRIES: usize = 256;
+const MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Marks a file whose item tree could not be encoded.
+const NO_ITEM_TREE: u32 = u32::MAX;
+
+pub(crate) struct CachedLibrary {
+    pub(crate) symbol_index: SymbolIndex,
+    pub(crate) item_trees: FxHashMap<FileId, Arc<RawItems>>,
+}
+
+pub(crate) struct LibraryCache {
+    path: PathBuf,
+    /// Library files, in the order used to encode file ids.
+    file_ids: Vec<FileId>,
+}
+
+impl LibraryCache {
+    pub(crate) fn new(
+        dir: &Path,
+        files: &[(FileId, RelativePathBuf, Arc<String>)],
+    ) -> LibraryCache {
+        let mut files = files.iter().collect::<Vec<_>>();
+        files.sort_by(|(_, lhs, _), (_, rhs, _)| lhs.cmp(rhs));
+
+        let mut hasher = StableHasher::new();
+        hasher.write_u64(CACHE_VERSION);
+        // Catches changes to the numbering of syntax kinds between versions.
+        hasher.write_u64(SyntaxKind::__LAST as u64);
+        for (_, path, text) in files.iter() {
+            hasher.write_prefixed(path.as_str().as_bytes());
+            hasher.write_prefixed(text.as_bytes());
+        }
+        let key = hasher.finish();
+
+        let path = dir.join("libraries").join(format!("{:016x}", key));
+        let file_ids = files.iter().map(|(file_id, _, _)| *file_id).collect();
+        LibraryCache { path, file_ids }
+    }
+
+    pub(crate) fn load(&self) -> Option<CachedLibrary> {
+        let bytes = fs::read(&self.path).ok()?;
+        let res = self.decode(&bytes);
+        if res.is_none() {
+            log::warn!("invalid library cache: {}", self.path.display());
+        }
+        res
+    }
+
+    pub(crate) fn store(
+        &self,
+        symbol_index: &SymbolIndex,
+        item_trees: &FxHashMap<FileId, Arc<RawItems>>,
+    ) {
+        let bytes = match self.encode(symbol_index, item_trees) {
+            Some(it) => it,
+            None => return,
+        };
+        if let Err(err) = stdx::write_atomically(&self.path, &bytes) {
+            log::warn!("failed to write library cache {}: {}", self.path.display(), err);
+            return;
+        }
+        if let Some(dir) = self.path.parent() {
+            for (path, err) in stdx::evict_old_files(dir, MAX_ENTRIES, MAX_AGE) {
+                log::warn!("failed to evict library cache {}: {}", path.display(), err);
+            }
+        }
+    }
+
+    fn encode(
+        &self,
+        symbol_index: &SymbolIndex,
+        item_trees: &FxHashMap<FileId, Arc<RawItems>>,
+    ) -> Option<Vec<u8>> {
+        let mut buf = Vec::new();
+        write_bytes(&mut buf, &symbol_index.encode(&self.file_ids)?);
+        for file_id in self.file_ids.iter() {
+            match item_trees.get(file_id).and_then(|it| it.encode()) {
+                Some(bytes) => write_bytes(&mut buf, &bytes),
+                None => buf.extend_from_slice(&NO_ITEM_TREE.to_le_bytes()),
+            }
+        }
+        Some(buf)
+    }
+
+    fn decode(&self, mut bytes: &[u8]) -> Option<CachedLibrary> {
+        let symbol_index = SymbolIndex::decode(read_bytes(&mut bytes)?, &self.file_ids)?;
+        let mut item_trees = FxHashMap::default();
+        for &file_id in self.file_ids.iter() {
+            if bytes.get(..4)? == NO_ITEM_TREE.to_le_bytes() {
+                bytes = &bytes[4..];
+                continue;
+            }
+            let raw_items = RawItems::decode(read_bytes(&mut bytes)?)?;
+            item_trees.insert(file_id, Arc::new(raw_items));
+        }
+        if !bytes.is_empty() {
+            return None;
+        }
+        Some(CachedLibrary { symbol_index, item_trees })
+    }
+}
+
+fn write_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+    buf.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    buf.extend_from_slice(bytes);
+}
+
+fn read_bytes<'a>(bytes: &mut &'a [u8]) -> Option<&'a [u8]> {
+    let mut len = [0; 4];
+    len.copy_from_slice(bytes.get(..4)?);
+    let len = u32::from_le_bytes(len) as usize;
+    let res = bytes.get(4..4 + len)?;
+    *bytes = &bytes[4 + len..];
+    Some(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use ra_syntax::SourceFile;
+
+    use super::*;
+
+    #[test]
+    fn library_is_loaded_from_cache() {
+        let dir = std::env::temp_dir().join(format!("ra-library-cache-{}", std::process::id()));
+        let text = Arc::new("mod foo { fn bar() {} }\nstruct Baz;\nuse foo::bar;".to_string());
+        let files = |file_id| vec![(file_id, RelativePathBuf::from("lib.rs"), text.clone())];
+
+        let index_for = |file_id| {
+            SymbolIndex::for_files(rayon::iter::once((file_id, SourceFile::parse(&text))))
+        };
+        let item_trees_for = |file_id| {
+            let raw_items = RawItems::from_source_file(file_id, SourceFile::parse(&text).tree());
+            let mut res = FxHashMap::default();
+            res.insert(file_id, Arc::new(raw_items));
+            res
+        };
+
+        let cache = LibraryCache::new(&dir, &files(FileId(1)));
+        assert!(cache.load().is_none());
+        cache.store(&index_for(FileId(1)), &item_trees_for(FileId(1)));
+        let loaded = cache.load().unwrap();
+        assert_eq!(loaded.symbol_index, index_for(FileId(1)));
+        assert_eq!(loaded.item_trees, item_trees_for(FileId(1)));
+
+        // File ids are not stable between runs.
+        let cache = LibraryCache::new(&dir, &files(FileId(92)));
+        let loaded = cache.load().unwrap();
+        assert_eq!(loaded.symbol_index, index_for(FileId(92)));
+        assert_eq!(loaded.item_trees, item_trees_for(FileId(92)));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -6,7 +6,7 @@
 
 use std::{convert::TryInto, mem};
 
-use hir::{DefWithBody, HasSource, Module, ModuleSource, Semantics, Visibility};
+use hir::{DefWithBody, HasSource, Module, ModuleSource, Mutability, Semantics, Visibility};
 use once_cell::unsync::Lazy;
 use ra_db::{FileId, FileRange, SourceDatabaseExt};
 use ra_prof::profile;
@@ -237,7 +237,7 @@ impl Definition {
                         refs.push(Reference {
                             file_range,
                             kind,
-                            access: reference_access(&def, &name_ref),
+                            access: reference_access(&sema, &def, &name_ref),
                         });
                     }
                     Some(NameRefClass::FieldShorthand { local, field }) => {
@@ -245,12 +245,16 @@ impl Definition {
                             Definition::Field(_) if &field == self => refs.push(Reference {
                                 file_range: sema.original_range(name_ref.syntax()),
                                 kind: ReferenceKind::FieldShorthandForField,
-                                access: reference_access(&field, &name_ref),
+                                access: reference_access(&sema, &field, &name_ref),
                             }),
                             Definition::Local(l) if &local == l => refs.push(Reference {
                                 file_range: sema.original_range(name_ref.syntax()),
                                 kind: ReferenceKind::FieldShorthandForLocal,
-                                access: reference_access(&Definition::Local(local), &name_ref),
+                                access: reference_access(
+                                    &sema,
+                                    &Definition::Local(local),
+                                    &name_ref,
+                                ),
                             }),
 
                             _ => {} // not a usage
@@ -264,7 +268,11 @@ impl Definition {
     }
 }
 
-fn reference_access(def: &Definition, name_ref: &ast::NameRef) -> Option<ReferenceAccess> {
+fn reference_access(
+    sema: &Semantics<RootDatabase>,
+    def: &Definition,
+    name_ref: &ast::NameRef,
+) -> Option<ReferenceAccess> {
     // Only Locals and Fields have accesses for now.
     match def {
         Definition::Local(_) | Definition::Field(_) => {}
@@ -286,6 +294,28 @@ fn reference_access(def: &Definition, name_ref: &ast::NameRef) -> Option<Referen
                     }
                     Some(ReferenceAccess::Read)
                 },
+                ast::RefExpr(expr) => {
+                    // A `&mut` borrow of the variable or field is a Write.
+                    if expr.mut_token().is_some() {
+                        if let Some(operand) = expr.expr() {
+                            if operand.syntax().text_range().end() == name_ref.syntax().text_range().end() {
+                                return Some(ReferenceAccess::Write);
+                            }
+                        }
+                    }
+                    Some(ReferenceAccess::Read)
+                },
+                ast::MethodCallExpr(call) => {
+                    // A receiver implicitly borrowed as `&mut`, like `v` in `v.push(1)`, is written to.
+                    if sema.method_receiver_autoref(&call) == Some(Mutability::Mut) {
+                        if let Some(receiver) = call.expr() {
+                            if receiver.syntax().text_range().end() == name_ref.syntax().text_range().end() {
+                                return Some(ReferenceAccess::Write);
+                            }
+                        }
+                    }
+                    Some(ReferenceAccess::Read)
+                },
                 _ => None
             }
         }
@@ -37,18 +37,20 @@ use ra_syntax::{
     ast::{self, NameOwner},
     match_ast, AstNode, Parse, SmolStr, SourceFile,
     SyntaxKind::{self, *},
-    SyntaxNode, SyntaxNodePtr, TextRange, WalkEvent,
+    SyntaxNode, SyntaxNodePtr, TextRange, TextSize, WalkEvent,
 };
 #[cfg(not(feature = "wasm"))]
 use rayon::prelude::*;
+use rustc_hash::FxHashMap;
 
 use crate::RootDatabase;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Query {
     query: String,
     lowercased: String,
     only_types: bool,
+    kind: Option<SyntaxKind>,
     libs: bool,
     exact: bool,
     limit: usize,
@@ -61,16 +63,62 @@ impl Query {
             query,
             lowercased,
             only_types: false,
+            kind: None,
             libs: false,
             exact: false,
             limit: usize::max_value(),
         }
     }
 
+    /// Parses the query syntax of workspace symbol search: `#` restricts the
+    /// search to types, `*` asks for an exact match, and a `kind:` word like
+    /// `kind:fn` restricts it to one kind of item. Unknown kinds are ignored.
+    pub fn parse(input: &str) -> Query {
+        let mut only_types = false;
+        let mut exact = false;
+        let mut kind = None;
+        let mut words = Vec::new();
+        for word in input.split_whitespace() {
+            if word.starts_with("kind:") {
+                if let Some(it) = symbol_kind(&word["kind:".len()..]) {
+                    kind = Some(it);
+                }
+                continue;
+            }
+            let word: String = word
+                .chars()
+                .filter(|&c| match c {
+                    '#' => {
+                        only_types = true;
+                        false
+                    }
+                    '*' => {
+                        exact = true;
+                        false
+                    }
+                    _ => true,
+                })
+                .collect();
+            if !word.is_empty() {
+                words.push(word);
+            }
+        }
+
+        let mut query = Query::new(words.join(" "));
+        query.only_types = only_types;
+        query.exact = exact;
+        query.kind = kind;
+        query
+    }
+
     pub fn only_types(&mut self) {
         self.only_types = true;
     }
 
+    pub fn kind(&mut self, kind: SyntaxKind) {
+        self.kind = Some(kind);
+    }
+
     pub fn libs(&mut self) {
         self.libs = true;
     }
@@ -265,14 +313,151 @@ impl SymbolIndex {
         let start = (value >> 32) as usize;
         (start, end)
     }
+
+    /// Serializes the index into a compact binary form. `FileId`s are not
+    /// stable between runs, so files are stored as indices into `file_ids`.
+    pub(crate) fn encode(&self, file_ids: &[FileId]) -> Option<Vec<u8>> {
+        let file_indices: FxHashMap<FileId, u32> =
+            file_ids.iter().enumerate().map(|(idx, &file_id)| (file_id, idx as u32)).collect();
+
+        let mut buf = Vec::new();
+        let fst = self.map.as_fst().as_bytes();
+        write_u32(&mut buf, fst.len() as u32);
+        buf.extend_from_slice(fst);
+
+        write_u32(&mut buf, self.symbols.len() as u32);
+        for symbol in self.symbols.iter() {
+            write_u32(&mut buf, *file_indices.get(&symbol.file_id)?);
+            write_str(&mut buf, &symbol.name);
+            write_u32(&mut buf, symbol.kind as u16 as u32);
+            write_range(&mut buf, symbol.range);
+            match symbol.name_range {
+                Some(range) => {
+                    buf.push(1);
+                    write_range(&mut buf, range);
+                }
+                None => buf.push(0),
+            }
+            match &symbol.container_name {
+                Some(name) => {
+                    buf.push(1);
+                    write_str(&mut buf, name);
+                }
+                None => buf.push(0),
+            }
+        }
+        Some(buf)
+    }
+
+    /// The inverse of `encode`. Returns `None` if `bytes` is not a valid
+    /// encoding of an index over `file_ids`.
+    pub(crate) fn decode(bytes: &[u8], file_ids: &[FileId]) -> Option<SymbolIndex> {
+        let mut reader = Reader { bytes };
+
+        let fst_len = reader.u32()? as usize;
+        let map = fst::Map::new(reader.bytes(fst_len)?.to_vec()).ok()?;
+
+        let n_symbols = reader.u32()? as usize;
+        let mut symbols = Vec::with_capacity(n_symbols);
+        for _ in 0..n_symbols {
+            let file_id = *file_ids.get(reader.u32()? as usize)?;
+            let name = reader.str()?;
+            let kind = reader.u32()?;
+            if kind >= SyntaxKind::__LAST as u32 {
+                return None;
+            }
+            let kind = SyntaxKind::from(kind as u16);
+            let range = reader.range()?;
+            let name_range = if reader.flag()? { Some(reader.range()?) } else { None };
+            let container_name = if reader.flag()? { Some(reader.str()?) } else { None };
+            symbols.push(FileSymbol {
+                file_id,
+                name,
+                kind,
+                range,
+                ptr: SyntaxNodePtr::from_raw_parts(kind, range),
+                name_range,
+                container_name,
+            });
+        }
+        if !reader.bytes.is_empty() {
+            return None;
+        }
+        Some(SymbolIndex { symbols, map })
+    }
+}
+
+fn write_u32(buf: &mut Vec<u8>, value: u32) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn write_str(buf: &mut Vec<u8>, text: &str) {
+    write_u32(buf, text.len() as u32);
+    buf.extend_from_slice(text.as_bytes());
+}
+
+fn write_range(buf: &mut Vec<u8>, range: TextRange) {
+    write_u32(buf, range.start().into());
+    write_u32(buf, range.end().into());
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.bytes.len() < len {
+            return None;
+        }
+        let (res, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Some(res)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let mut buf = [0; 4];
+        buf.copy_from_slice(self.bytes(4)?);
+        Some(u32::from_le_bytes(buf))
+    }
+
+    fn flag(&mut self) -> Option<bool> {
+        match self.bytes(1)? {
+            [0] => Some(false),
+            [1] => Some(true),
+            _ => None,
+        }
+    }
+
+    fn str(&mut self) -> Option<SmolStr> {
+        let len = self.u32()? as usize;
+        let text = std::str::from_utf8(self.bytes(len)?).ok()?;
+        Some(SmolStr::new(text))
+    }
+
+    fn range(&mut self) -> Option<TextRange> {
+        let start = TextSize::from(self.u32()?);
+        let end = TextSize::from(self.u32()?);
+        if start > end {
+            return None;
+        }
+        Some(TextRange::new(start, end))
+    }
 }
 
 impl Query {
     pub(crate) fn search(self, indices: &[Arc<SymbolIndex>]) -> Vec<FileSymbol> {
         let mut op = fst::map::OpBuilder::new();
         for file_symbols in indices.iter() {
-            let automaton = fst::automaton::Subsequence::new(&self.lowercased);
-            op = op.add(file_symbols.map.search(automaton))
+            // Exact queries only need a single lookup, don't walk the whole
+            // map looking for subsequence matches.
+            if self.exact {
+                let automaton = fst::automaton::Str::new(&self.lowercased);
+                op = op.add(file_symbols.map.search(automaton))
+            } else {
+                let automaton = fst::automaton::Subsequence::new(&self.lowercased);
+                op = op.add(file_symbols.map.search(automaton))
+            }
         }
         let mut stream = op.union();
         let mut res = Vec::new();
@@ -288,6 +473,9 @@ impl Query {
                     if self.only_types && !is_type(symbol.kind) {
                         continue;
                     }
+                    if self.kind.map_or(false, |kind| kind != symbol.kind) {
+                        continue;
+                    }
                     if self.exact && symbol.name != self.query {
                         continue;
                     }
@@ -306,6 +494,24 @@ fn is_type(kind: SyntaxKind) -> bool {
     }
 }
 
+/// The kind of symbols named by `kind:` in queries. Both the keywords and the
+/// LSP names of the kinds are accepted.
+fn symbol_kind(name: &str) -> Option<SyntaxKind> {
+    let kind = match name {
+        "fn" | "function" => FN_DEF,
+        "struct" => STRUCT_DEF,
+        "enum" => ENUM_DEF,
+        "trait" | "interface" => TRAIT_DEF,
+        "mod" | "module" => MODULE,
+        "type" => TYPE_ALIAS_DEF,
+        "const" | "constant" => CONST_DEF,
+        "static" => STATIC_DEF,
+        "macro" => MACRO_CALL,
+        _ => return None,
+    };
+    Some(kind)
+}
+
 /// The actual data that is stored in the index. It should be as compact as
 /// possible.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -141,7 +141,7 @@ impl MacroRules {
         let mut src = TtIter::new(tt);
         let mut rules = Vec::new();
         while src.len() > 0 {
-            let rule = Rule::parse(&mut src)?;
+            let rule = Rule::parse(&mut src, true)?;
             rules.push(rule);
             if let Err(()) = src.expect_char(';') {
                 if src.len() > 0 {
@@ -158,6 +158,37 @@ impl MacroRules {
         Ok(MacroRules { rules, shift: Shift::new(tt) })
     }
 
+    /// Parses the body of a macros 2.0 `macro` item. It is either a list of
+    /// rules separated by `,` (`macro m { ($e:expr) => { .. }, .. }`) or a
+    /// single rule without the arrow (`macro m($e:expr) { .. }`).
+    pub fn parse_macro2(tt: &tt::Subtree) -> Result<MacroRules, ParseError> {
+        let mut src = TtIter::new(tt);
+        let mut rules = Vec::new();
+        if tt.delimiter_kind() == Some(tt::DelimiterKind::Brace) {
+            while src.len() > 0 {
+                let rule = Rule::parse(&mut src, true)?;
+                rules.push(rule);
+                if let Err(()) = src.expect_any_char(&[',', ';']) {
+                    if src.len() > 0 {
+                        return Err(ParseError::Expected("expected `,`".to_string()));
+                    }
+                    break;
+                }
+            }
+        } else {
+            rules.push(Rule::parse(&mut src, false)?);
+            if src.len() > 0 {
+                return Err(ParseError::Expected("expected end of macro definition".to_string()));
+            }
+        }
+
+        for rule in rules.iter() {
+            validate(&rule.lhs)?;
+        }
+
+        Ok(MacroRules { rules, shift: Shift::new(tt) })
+    }
+
     pub fn expand(&self, tt: &tt::Subtree) -> ExpandResult<tt::Subtree> {
         // apply shift
         let mut tt = tt.clone();
@@ -178,14 +209,16 @@ impl MacroRules {
 }
 
 impl Rule {
-    fn parse(src: &mut TtIter) -> Result<Rule, ParseError> {
+    fn parse(src: &mut TtIter, expect_arrow: bool) -> Result<Rule, ParseError> {
         let mut lhs = src
             .expect_subtree()
             .map_err(|()| ParseError::Expected("expected subtree".to_string()))?
             .clone();
         lhs.delimiter = None;
-        src.expect_char('=').map_err(|()| ParseError::Expected("expected `=`".to_string()))?;
-        src.expect_char('>').map_err(|()| ParseError::Expected("expected `>`".to_string()))?;
+        if expect_arrow {
+            src.expect_char('=').map_err(|()| ParseError::Expected("expected `=`".to_string()))?;
+            src.expect_char('>').map_err(|()| ParseError::Expected("expected `>`".to_string()))?;
+        }
         let mut rhs = src
             .expect_subtree()
             .map_err(|()| ParseError::Expected("expected subtree".to_string()))?
@@ -1083,6 +1083,32 @@ macro_rules! foo {
     );
 }
 
+#[test]
+fn test_macro2_rules() {
+    parse_macro2(
+        r#"
+macro foo {
+    ($a:ident) => { fn $a() {} },
+    ($a:ident, $b:ident) => { fn $a() {} fn $b() {} }
+}
+"#,
+    )
+    .assert_expand_items("foo!(bar);", "fn bar () {}")
+    .assert_expand_items("foo!(bar, baz);", "fn bar () {} fn baz () {}");
+}
+
+#[test]
+fn test_macro2_single_rule() {
+    parse_macro2(
+        r#"
+macro foo($a:ident, $b:expr) {
+    fn $a() -> u8 { $b }
+}
+"#,
+    )
+    .assert_expand_items("foo!(bar, 1);", "fn bar () -> u8 {1}");
+}
+
 // The following tests are based on real world situations
 #[test]
 fn test_vec() {
@@ -1678,6 +1704,16 @@ pub(crate) fn parse_macro(ra_fixture: &str) -> MacroFixture {
     MacroFixture { rules }
 }
 
+pub(crate) fn parse_macro2(ra_fixture: &str) -> MacroFixture {
+    let source_file = ast::SourceFile::parse(ra_fixture).ok().unwrap();
+    let macro_definition =
+        source_file.syntax().descendants().find_map(ast::MacroDef::cast).unwrap();
+
+    let (definition_tt, _) = ast_to_token_tree(&macro_definition.token_tree().unwrap()).unwrap();
+    let rules = MacroRules::parse_macro2(&definition_tt).unwrap();
+    MacroFixture { rules }
+}
+
 pub(crate) fn parse_macro_error(ra_fixture: &str) -> ParseError {
     let definition_tt = parse_macro_to_tt(ra_fixture);
 
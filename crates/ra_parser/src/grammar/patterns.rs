@@ -65,7 +65,17 @@ fn pattern_single_r(p: &mut Parser, recovery_set: TokenSet) {
             if p.at(range_op) {
                 let m = lhs.precede(p);
                 p.bump(range_op);
-                atom_pat(p, recovery_set);
+                // test half_open_range_pat
+                // fn main() {
+                //     match 92 {
+                //         0.. => (),
+                //         ..=9 => (),
+                //         ..10 | 20.. => (),
+                //     }
+                // }
+                if range_op != T![..] || p.at_ts(RANGE_PAT_END_FIRST) {
+                    atom_pat(p, recovery_set);
+                }
                 m.complete(p, RANGE_PAT);
                 return;
             }
@@ -73,6 +83,23 @@ fn pattern_single_r(p: &mut Parser, recovery_set: TokenSet) {
     }
 }
 
+/// Tokens which can start the end of a range pattern. Anything else after a
+/// `..` means there's no end, as in `0..` or the rest pattern `..`.
+const RANGE_PAT_END_FIRST: TokenSet =
+    expressions::LITERAL_FIRST.union(token_set![IDENT, SELF_KW, SUPER_KW, CRATE_KW, MINUS]);
+
+fn range_pat_without_start(p: &mut Parser, recovery_set: TokenSet) -> CompletedMarker {
+    let m = p.start();
+    for &range_op in [T![...], T![..=], T![..]].iter() {
+        if p.at(range_op) {
+            p.bump(range_op);
+            break;
+        }
+    }
+    atom_pat(p, recovery_set);
+    m.complete(p, RANGE_PAT)
+}
+
 const PAT_RECOVERY_SET: TokenSet =
     token_set![LET_KW, IF_KW, WHILE_KW, LOOP_KW, MATCH_KW, R_PAREN, COMMA];
 
@@ -91,6 +118,10 @@ fn atom_pat(p: &mut Parser, recovery_set: TokenSet) -> Option<CompletedMarker> {
         _ if paths::is_use_path_start(p) => path_or_macro_pat(p),
         _ if is_literal_pat_start(p) => literal_pat(p),
 
+        T![.] if p.at(T![..=]) || p.at(T![...]) => range_pat_without_start(p, recovery_set),
+        T![.] if p.at(T![..]) && RANGE_PAT_END_FIRST.contains(p.nth(2)) => {
+            range_pat_without_start(p, recovery_set)
+        }
         T![.] if p.at(T![..]) => dot_dot_pat(p),
         T![_] => placeholder_pat(p),
         T![&] => ref_pat(p),
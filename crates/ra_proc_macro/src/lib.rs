@@ -40,9 +40,9 @@ impl ra_tt::TokenExpander for ProcMacroProcessExpander {
     fn expand(
         &self,
         subtree: &Subtree,
-        _attr: Option<&Subtree>,
+        attr: Option<&Subtree>,
     ) -> Result<Subtree, ra_tt::ExpansionError> {
-        self.process.custom_derive(&self.dylib_path, subtree, &self.name)
+        self.process.expand(&self.dylib_path, subtree, attr, &self.name)
     }
 }
 
@@ -75,7 +75,7 @@ impl ProcMacroClient {
     pub fn by_dylib_path(
         &self,
         dylib_path: &Path,
-    ) -> Vec<(SmolStr, Arc<dyn ra_tt::TokenExpander>)> {
+    ) -> Vec<(SmolStr, ProcMacroKind, Arc<dyn ra_tt::TokenExpander>)> {
         match &self.kind {
             ProcMacroClientKind::Dummy => vec![],
             ProcMacroClientKind::Process { process, .. } => {
@@ -90,9 +90,9 @@ impl ProcMacroClient {
                 macros
                     .into_iter()
                     .filter_map(|(name, kind)| {
-                        // FIXME: Support custom derive only for now.
+                        // FIXME: Support function-like proc macros.
                         match kind {
-                            ProcMacroKind::CustomDerive => {
+                            ProcMacroKind::CustomDerive | ProcMacroKind::Attr => {
                                 let name = SmolStr::new(&name);
                                 let expander: Arc<dyn ra_tt::TokenExpander> =
                                     Arc::new(ProcMacroProcessExpander {
@@ -100,9 +100,9 @@ impl ProcMacroClient {
                                         name: name.clone(),
                                         dylib_path: dylib_path.into(),
                                     });
-                                Some((name, expander))
+                                Some((name, kind, expander))
                             }
-                            _ => None,
+                            ProcMacroKind::FuncLike => None,
                         }
                     })
                     .collect()
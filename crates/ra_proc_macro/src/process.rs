@@ -57,16 +57,17 @@ impl ProcMacroProcessSrv {
         Ok(result.macros)
     }
 
-    pub fn custom_derive(
+    pub fn expand(
         &self,
         dylib_path: &Path,
         subtree: &Subtree,
-        derive_name: &str,
+        attr: Option<&Subtree>,
+        macro_name: &str,
     ) -> Result<Subtree, ra_tt::ExpansionError> {
         let task = ExpansionTask {
             macro_body: subtree.clone(),
-            macro_name: derive_name.to_string(),
-            attributes: None,
+            macro_name: macro_name.to_string(),
+            attributes: attr.cloned(),
             lib: dylib_path.to_path_buf(),
         };
 
@@ -8,12 +8,14 @@ use std::{
     process::Command,
 };
 
-use anyhow::{Context, Result};
-use cargo_metadata::{BuildScript, CargoOpt, Message, MetadataCommand, PackageId};
+use anyhow::{bail, Context, Result};
+use cargo_metadata::{BuildScript, Message, Metadata, PackageId};
 use ra_arena::{Arena, Idx};
 use ra_db::Edition;
 use rustc_hash::FxHashMap;
 
+use crate::metadata_cache::MetadataCache;
+
 /// `CargoWorkspace` represents the logical structure of, well, a Cargo
 /// workspace. It pretty closely mirrors `cargo metadata` output.
 ///
@@ -59,6 +61,22 @@ pub struct CargoConfig {
 
     /// rustc target
     pub target: Option<String>,
+
+    /// Extra environment variables set for every cargo and rustc invocation.
+    /// `CARGO` and `RUSTC` can be used to override the binaries themselves.
+    pub extra_env: FxHashMap<String, String>,
+
+    /// Per-package overrides, keyed by package name
+    pub crate_overrides: FxHashMap<String, CrateOverride>,
+}
+
+/// Settings forced onto a package, for cases where the build system sets
+/// something we can't discover from `cargo metadata`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CrateOverride {
+    /// Extra `--cfg` flags, either `atom` or `key="value"`
+    pub cfgs: Vec<String>,
+    pub edition: Option<Edition>,
 }
 
 impl Default for CargoConfig {
@@ -69,6 +87,8 @@ impl Default for CargoConfig {
             features: Vec::new(),
             load_out_dirs_from_check: false,
             target: None,
+            extra_env: FxHashMap::default(),
+            crate_overrides: FxHashMap::default(),
         }
     }
 }
@@ -87,6 +107,8 @@ pub struct PackageData {
     pub dependencies: Vec<PackageDependency>,
     pub edition: Edition,
     pub features: Vec<String>,
+    /// Extra cfgs from `CargoConfig::crate_overrides`
+    pub cfgs: Vec<String>,
     pub out_dir: Option<PathBuf>,
     pub proc_macro_dylib_path: Option<PathBuf>,
 }
@@ -141,35 +163,61 @@ impl PackageData {
 }
 
 impl CargoWorkspace {
+    /// Runs `cargo metadata` for `cargo_toml`. If `cache_dir` is set, the
+    /// output is reused from there as long as the workspace's manifests and
+    /// lock file haven't changed since.
     pub fn from_cargo_metadata(
         cargo_toml: &Path,
         cargo_features: &CargoConfig,
+        cache_dir: Option<&Path>,
     ) -> Result<CargoWorkspace> {
-        let _ = Command::new(cargo_binary())
+        let cargo_version = cargo_command(cargo_features)
             .arg("--version")
             .output()
             .context("failed to run `cargo --version`, is `cargo` in PATH?")?;
 
-        let mut meta = MetadataCommand::new();
-        meta.manifest_path(cargo_toml);
-        if cargo_features.all_features {
-            meta.features(CargoOpt::AllFeatures);
-        } else if cargo_features.no_default_features {
-            // FIXME: `NoDefaultFeatures` is mutual exclusive with `SomeFeatures`
-            // https://github.com/oli-obk/cargo_metadata/issues/79
-            meta.features(CargoOpt::NoDefaultFeatures);
-        } else if !cargo_features.features.is_empty() {
-            meta.features(CargoOpt::SomeFeatures(cargo_features.features.clone()));
-        }
-        if let Some(parent) = cargo_toml.parent() {
-            meta.current_dir(parent);
-        }
-        if let Some(target) = cargo_features.target.as_ref() {
-            meta.other_options(&[String::from("--filter-platform"), target.clone()]);
-        }
-        let meta = meta.exec().with_context(|| {
-            format!("Failed to run `cargo metadata --manifest-path {}`", cargo_toml.display())
-        })?;
+        let cache = cache_dir
+            .map(|dir| MetadataCache::new(dir, cargo_toml, cargo_features, &cargo_version.stdout));
+        let meta = match cache.as_ref().and_then(|cache| cache.load()) {
+            Some(it) => it,
+            None => {
+                let mut meta = cargo_command(cargo_features);
+                meta.args(&["metadata", "--format-version", "1", "--manifest-path"])
+                    .arg(cargo_toml);
+                if cargo_features.all_features {
+                    meta.arg("--all-features");
+                } else if cargo_features.no_default_features {
+                    // FIXME: `NoDefaultFeatures` is mutual exclusive with `SomeFeatures`
+                    // https://github.com/oli-obk/cargo_metadata/issues/79
+                    meta.arg("--no-default-features");
+                } else if !cargo_features.features.is_empty() {
+                    meta.arg("--features").arg(cargo_features.features.join(" "));
+                }
+                if let Some(parent) = cargo_toml.parent() {
+                    meta.current_dir(parent);
+                }
+                if let Some(target) = cargo_features.target.as_ref() {
+                    meta.arg("--filter-platform").arg(target);
+                }
+                (|| -> Result<Metadata> {
+                    let output = meta.output()?;
+                    if !output.status.success() {
+                        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+                    }
+                    let metadata = serde_json::from_slice(&output.stdout)?;
+                    if let Some(cache) = &cache {
+                        cache.store(&metadata, &output.stdout);
+                    }
+                    Ok(metadata)
+                })()
+                .with_context(|| {
+                    format!(
+                        "Failed to run `cargo metadata --manifest-path {}`",
+                        cargo_toml.display()
+                    )
+                })?
+            }
+        };
 
         let mut out_dir_by_id = FxHashMap::default();
         let mut proc_macro_dylib_paths = FxHashMap::default();
@@ -189,9 +237,14 @@ impl CargoWorkspace {
             let cargo_metadata::Package { id, edition, name, manifest_path, version, .. } =
                 meta_pkg;
             let is_member = ws_members.contains(&id);
-            let edition = edition
+            let mut edition = edition
                 .parse::<Edition>()
                 .with_context(|| format!("Failed to parse edition {}", edition))?;
+            let mut cfgs = Vec::new();
+            if let Some(crate_override) = cargo_features.crate_overrides.get(&name) {
+                edition = crate_override.edition.unwrap_or(edition);
+                cfgs = crate_override.cfgs.clone();
+            }
             let pkg = packages.alloc(PackageData {
                 name,
                 version: version.to_string(),
@@ -201,6 +254,7 @@ impl CargoWorkspace {
                 edition,
                 dependencies: Vec::new(),
                 features: Vec::new(),
+                cfgs,
                 out_dir: out_dir_by_id.get(&id).cloned(),
                 proc_macro_dylib_path: proc_macro_dylib_paths.get(&id).cloned(),
             });
@@ -288,7 +342,7 @@ pub fn load_extern_resources(
     cargo_toml: &Path,
     cargo_features: &CargoConfig,
 ) -> Result<ExternResources> {
-    let mut cmd = Command::new(cargo_binary());
+    let mut cmd = cargo_command(cargo_features);
     cmd.args(&["check", "--message-format=json", "--manifest-path"]).arg(cargo_toml);
     if cargo_features.all_features {
         cmd.arg("--all-features");
@@ -337,6 +391,17 @@ fn is_dylib(path: &Path) -> bool {
     }
 }
 
-fn cargo_binary() -> String {
-    env::var("CARGO").unwrap_or_else(|_| "cargo".to_string())
+/// Returns the `cargo` binary to use, respecting a `CARGO` override in `extra_env`.
+pub fn cargo_binary(extra_env: &FxHashMap<String, String>) -> String {
+    extra_env
+        .get("CARGO")
+        .cloned()
+        .or_else(|| env::var("CARGO").ok())
+        .unwrap_or_else(|| "cargo".to_string())
+}
+
+fn cargo_command(cargo_features: &CargoConfig) -> Command {
+    let mut cmd = Command::new(cargo_binary(&cargo_features.extra_env));
+    cmd.envs(&cargo_features.extra_env);
+    cmd
 }
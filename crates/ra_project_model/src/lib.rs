@@ -2,6 +2,7 @@
 
 mod cargo_workspace;
 mod json_project;
+mod metadata_cache;
 mod sysroot;
 
 use std::{
@@ -13,12 +14,17 @@ use std::{
 
 use anyhow::{bail, Context, Result};
 use ra_cfg::CfgOptions;
-use ra_db::{CrateGraph, CrateName, Edition, Env, ExternSource, ExternSourceId, FileId};
+use ra_db::{
+    CrateGraph, CrateName, Edition, Env, ExternSource, ExternSourceId, FileId, ProcMacro,
+    ProcMacroKind,
+};
 use rustc_hash::FxHashMap;
 use serde_json::from_reader;
 
 pub use crate::{
-    cargo_workspace::{CargoConfig, CargoWorkspace, Package, Target, TargetKind},
+    cargo_workspace::{
+        cargo_binary, CargoConfig, CargoWorkspace, CrateOverride, Package, Target, TargetKind,
+    },
     json_project::JsonProject,
     sysroot::Sysroot,
 };
@@ -156,6 +162,7 @@ impl ProjectWorkspace {
         root: ProjectRoot,
         cargo_features: &CargoConfig,
         with_sysroot: bool,
+        cache_dir: Option<&Path>,
     ) -> Result<ProjectWorkspace> {
         let res = match root {
             ProjectRoot::ProjectJson(project_json) => {
@@ -170,15 +177,17 @@ impl ProjectWorkspace {
                 }
             }
             ProjectRoot::CargoToml(cargo_toml) => {
-                let cargo = CargoWorkspace::from_cargo_metadata(&cargo_toml, cargo_features)
-                    .with_context(|| {
-                        format!(
-                            "Failed to read Cargo metadata from Cargo.toml file {}",
-                            cargo_toml.display()
-                        )
-                    })?;
+                let cargo =
+                    CargoWorkspace::from_cargo_metadata(&cargo_toml, cargo_features, cache_dir)
+                        .with_context(|| {
+                            format!(
+                                "Failed to read Cargo metadata from Cargo.toml file {}",
+                                cargo_toml.display()
+                            )
+                        })?;
                 let sysroot = if with_sysroot {
-                    Sysroot::discover(&cargo_toml).with_context(|| {
+                    let extra_env = &cargo_features.extra_env;
+                    Sysroot::discover(&cargo_toml, extra_env).with_context(|| {
                         format!(
                             "Failed to find sysroot for Cargo.toml file {}. Is rust-src installed?",
                             cargo_toml.display()
@@ -199,17 +208,19 @@ impl ProjectWorkspace {
     /// the root is a member of the current workspace
     pub fn to_roots(&self) -> Vec<PackageRoot> {
         match self {
-            ProjectWorkspace::Json { project } => {
-                project.roots.iter().map(|r| PackageRoot::new_member(r.path.clone())).collect()
-            }
+            ProjectWorkspace::Json { project } => project
+                .roots
+                .iter()
+                .map(|r| PackageRoot::new_member(canonicalize_root(&r.path)))
+                .collect(),
             ProjectWorkspace::Cargo { cargo, sysroot } => cargo
                 .packages()
                 .map(|pkg| PackageRoot {
-                    path: cargo[pkg].root().to_path_buf(),
+                    path: canonicalize_root(cargo[pkg].root()),
                     is_member: cargo[pkg].is_member,
                 })
                 .chain(sysroot.crates().map(|krate| {
-                    PackageRoot::new_non_member(sysroot[krate].root_dir().to_path_buf())
+                    PackageRoot::new_non_member(canonicalize_root(sysroot[krate].root_dir()))
                 }))
                 .collect(),
         }
@@ -296,7 +307,7 @@ impl ProjectWorkspace {
                         let proc_macro = krate
                             .proc_macro_dylib_path
                             .clone()
-                            .map(|it| proc_macro_client.by_dylib_path(&it));
+                            .map(|it| load_proc_macros(proc_macro_client, &it));
                         // FIXME: No crate name in json definition such that we cannot add OUT_DIR to env
                         Some((
                             json_project::CrateId(seq_index),
@@ -399,6 +410,9 @@ impl ProjectWorkspace {
                             let cfg_options = {
                                 let mut opts = default_cfg_options.clone();
                                 opts.insert_features(cargo[pkg].features.iter().map(Into::into));
+                                for cfg in cargo[pkg].cfgs.iter() {
+                                    insert_cfg(&mut opts, cfg);
+                                }
                                 opts
                             };
                             let mut env = Env::default();
@@ -415,7 +429,7 @@ impl ProjectWorkspace {
                             let proc_macro = cargo[pkg]
                                 .proc_macro_dylib_path
                                 .as_ref()
-                                .map(|it| proc_macro_client.by_dylib_path(&it))
+                                .map(|it| load_proc_macros(proc_macro_client, &it))
                                 .unwrap_or_default();
 
                             let crate_id = crate_graph.add_crate_root(
@@ -543,7 +557,18 @@ impl ProjectWorkspace {
     }
 }
 
-pub fn get_rustc_cfg_options(target: Option<&String>) -> CfgOptions {
+/// Paths from `cargo metadata` are not necessarily canonical: a path dependency
+/// living next to the workspace shows up as `/ws/../dep`. Files are loaded by
+/// their canonical path, so roots must be canonical too, or files of such
+/// dependencies end up not belonging to any source root.
+fn canonicalize_root(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+pub fn get_rustc_cfg_options(
+    target: Option<&String>,
+    extra_env: &FxHashMap<String, String>,
+) -> CfgOptions {
     let mut cfg_options = CfgOptions::default();
 
     // Some nightly-only cfgs, which are required for stdlib
@@ -558,7 +583,8 @@ pub fn get_rustc_cfg_options(target: Option<&String>) -> CfgOptions {
 
     match (|| -> Result<String> {
         // `cfg(test)` and `cfg(debug_assertion)` are handled outside, so we suppress them here.
-        let mut cmd = Command::new("rustc");
+        let mut cmd = Command::new(sysroot::rustc_binary(extra_env));
+        cmd.envs(extra_env);
         cmd.args(&["--print", "cfg", "-O"]);
         if let Some(target) = target {
             cmd.args(&["--target", target.as_str()]);
@@ -577,14 +603,7 @@ pub fn get_rustc_cfg_options(target: Option<&String>) -> CfgOptions {
     })() {
         Ok(rustc_cfgs) => {
             for line in rustc_cfgs.lines() {
-                match line.find('=') {
-                    None => cfg_options.insert_atom(line.into()),
-                    Some(pos) => {
-                        let key = &line[..pos];
-                        let value = line[pos + 1..].trim_matches('"');
-                        cfg_options.insert_key_value(key.into(), value.into());
-                    }
-                }
+                insert_cfg(&mut cfg_options, line);
             }
         }
         Err(e) => log::error!("failed to get rustc cfgs: {}", e),
@@ -592,3 +611,31 @@ pub fn get_rustc_cfg_options(target: Option<&String>) -> CfgOptions {
 
     cfg_options
 }
+
+/// Parses a cfg in `rustc --print cfg` format, i.e. `atom` or `key="value"`.
+fn insert_cfg(cfg_options: &mut CfgOptions, cfg: &str) {
+    match cfg.find('=') {
+        None => cfg_options.insert_atom(cfg.into()),
+        Some(pos) => {
+            let key = &cfg[..pos];
+            let value = cfg[pos + 1..].trim_matches('"');
+            cfg_options.insert_key_value(key.into(), value.into());
+        }
+    }
+}
+
+/// Loads the proc macros of a proc-macro crate through the proc-macro server.
+fn load_proc_macros(client: &ProcMacroClient, dylib_path: &Path) -> Vec<ProcMacro> {
+    client
+        .by_dylib_path(dylib_path)
+        .into_iter()
+        .map(|(name, kind, expander)| {
+            let kind = match kind {
+                ra_proc_macro::ProcMacroKind::CustomDerive => ProcMacroKind::CustomDerive,
+                ra_proc_macro::ProcMacroKind::FuncLike => ProcMacroKind::FuncLike,
+                ra_proc_macro::ProcMacroKind::Attr => ProcMacroKind::Attr,
+            };
+            ProcMacro { name, kind, expander }
+        })
+        .collect()
+}
@@ -0,0 +1,133 @@
+//! On-disk cache of `cargo metadata` output.
+//!
+//! `cargo metadata` can take seconds on large workspaces, even though its
+//! output rarely changes between two starts. The output is stored under a key
+//! derived from the arguments it was run with and the version of cargo,
+//! together with a fingerprint of `Cargo.lock`, of the manifests of all local
+//! packages and of the `.cargo/config` files that apply to the workspace. A
+//! stored output is only used while its fingerprint still matches those files.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use cargo_metadata::Metadata;
+use stdx::StableHasher;
+
+use crate::CargoConfig;
+
+/// Bump this whenever the format of cache entries changes.
+const CACHE_VERSION: u64 = 1;
+
+const MAX_ENTRIES: usize = 64;
+const MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+pub(crate) struct MetadataCache {
+    path: PathBuf,
+}
+
+impl MetadataCache {
+    pub(crate) fn new(
+        dir: &Path,
+        cargo_toml: &Path,
+        cargo_features: &CargoConfig,
+        cargo_version: &[u8],
+    ) -> MetadataCache {
+        let mut hasher = StableHasher::new();
+        hasher.write_u64(CACHE_VERSION);
+        hasher.write_prefixed(cargo_version);
+        hasher.write_prefixed(cargo_toml.to_string_lossy().as_bytes());
+        hasher.write_u64(cargo_features.all_features as u64);
+        hasher.write_u64(cargo_features.no_default_features as u64);
+        hasher.write_u64(cargo_features.features.len() as u64);
+        for feature in cargo_features.features.iter() {
+            hasher.write_prefixed(feature.as_bytes());
+        }
+        hasher.write_prefixed(cargo_features.target.as_deref().unwrap_or_default().as_bytes());
+        let mut extra_env = cargo_features.extra_env.iter().collect::<Vec<_>>();
+        extra_env.sort();
+        hasher.write_u64(extra_env.len() as u64);
+        for (key, value) in extra_env {
+            hasher.write_prefixed(key.as_bytes());
+            hasher.write_prefixed(value.as_bytes());
+        }
+        let key = hasher.finish();
+
+        MetadataCache { path: dir.join("metadata").join(format!("{:016x}.json", key)) }
+    }
+
+    pub(crate) fn load(&self) -> Option<Metadata> {
+        let text = fs::read_to_string(&self.path).ok()?;
+        let mut lines = text.splitn(2, '\n');
+        let stored_fingerprint = lines.next()?;
+        let metadata: Metadata = match serde_json::from_str(lines.next()?) {
+            Ok(it) => it,
+            Err(err) => {
+                log::warn!("invalid metadata cache {}: {}", self.path.display(), err);
+                return None;
+            }
+        };
+        let fingerprint = fingerprint(&metadata)?;
+        if format!("{:016x}", fingerprint) != stored_fingerprint {
+            return None;
+        }
+        Some(metadata)
+    }
+
+    /// Stores `output`, the stdout of `cargo metadata`, which was parsed into
+    /// `metadata`.
+    pub(crate) fn store(&self, metadata: &Metadata, output: &[u8]) {
+        let fingerprint = match fingerprint(metadata) {
+            Some(it) => it,
+            None => return,
+        };
+        let mut bytes = format!("{:016x}\n", fingerprint).into_bytes();
+        bytes.extend_from_slice(output);
+
+        if let Err(err) = stdx::write_atomically(&self.path, &bytes) {
+            log::warn!("failed to write metadata cache {}: {}", self.path.display(), err);
+            return;
+        }
+        if let Some(dir) = self.path.parent() {
+            for (path, err) in stdx::evict_old_files(dir, MAX_ENTRIES, MAX_AGE) {
+                log::warn!("failed to evict metadata cache {}: {}", path.display(), err);
+            }
+        }
+    }
+}
+
+/// Hashes the files `metadata` was computed from. Returns `None` if any of
+/// them can't be read.
+fn fingerprint(metadata: &Metadata) -> Option<u64> {
+    let mut hasher = StableHasher::new();
+    // A missing lock file is fine: it is written by `cargo metadata` itself,
+    // and then changes the fingerprint.
+    let lock_file = fs::read(metadata.workspace_root.join("Cargo.lock")).unwrap_or_default();
+    hasher.write_prefixed(&lock_file);
+
+    let mut manifests = metadata
+        .packages
+        .iter()
+        .filter(|pkg| pkg.source.is_none())
+        .map(|pkg| pkg.manifest_path.as_path())
+        .collect::<Vec<_>>();
+    manifests.sort();
+    for manifest in manifests {
+        hasher.write_prefixed(manifest.to_string_lossy().as_bytes());
+        hasher.write_prefixed(&fs::read(manifest).ok()?);
+    }
+
+    // Cargo reads the config of the workspace root and of all its ancestors.
+    for dir in metadata.workspace_root.ancestors() {
+        for name in ["config", "config.toml"].iter() {
+            let config = dir.join(".cargo").join(name);
+            if let Ok(text) = fs::read(&config) {
+                hasher.write_prefixed(config.to_string_lossy().as_bytes());
+                hasher.write_prefixed(&text);
+            }
+        }
+    }
+    Some(hasher.finish())
+}
@@ -8,6 +8,7 @@ use std::{
 };
 
 use ra_arena::{Arena, Idx};
+use rustc_hash::FxHashMap;
 
 #[derive(Default, Debug, Clone)]
 pub struct Sysroot {
@@ -51,8 +52,8 @@ impl Sysroot {
         self.crates.iter().map(|(id, _data)| id)
     }
 
-    pub fn discover(cargo_toml: &Path) -> Result<Sysroot> {
-        let src = get_or_install_rust_src(cargo_toml)?;
+    pub fn discover(cargo_toml: &Path, extra_env: &FxHashMap<String, String>) -> Result<Sysroot> {
+        let src = get_or_install_rust_src(cargo_toml, extra_env)?;
         let mut sysroot = Sysroot { crates: Arena::default() };
         for name in SYSROOT_CRATES.trim().lines() {
             let root = src.join(format!("lib{}", name)).join("lib.rs");
@@ -88,9 +89,15 @@ fn create_command_text(program: &str, args: &[&str]) -> String {
     format!("{} {}", program, args.join(" "))
 }
 
-fn run_command_in_cargo_dir(cargo_toml: &Path, program: &str, args: &[&str]) -> Result<Output> {
+fn run_command_in_cargo_dir(
+    cargo_toml: &Path,
+    extra_env: &FxHashMap<String, String>,
+    program: &str,
+    args: &[&str],
+) -> Result<Output> {
     let output = Command::new(program)
         .current_dir(cargo_toml.parent().unwrap())
+        .envs(extra_env)
         .args(args)
         .output()
         .context(format!("{} failed", create_command_text(program, args)))?;
@@ -110,17 +117,30 @@ fn run_command_in_cargo_dir(cargo_toml: &Path, program: &str, args: &[&str]) ->
     Ok(output)
 }
 
-fn get_or_install_rust_src(cargo_toml: &Path) -> Result<PathBuf> {
+fn get_or_install_rust_src(
+    cargo_toml: &Path,
+    extra_env: &FxHashMap<String, String>,
+) -> Result<PathBuf> {
+    if let Some(path) = extra_env.get("RUST_SRC_PATH") {
+        return Ok(path.into());
+    }
     if let Ok(path) = env::var("RUST_SRC_PATH") {
         return Ok(path.into());
     }
-    let rustc_output = run_command_in_cargo_dir(cargo_toml, "rustc", &["--print", "sysroot"])?;
+    let rustc = rustc_binary(extra_env);
+    let rustc_output =
+        run_command_in_cargo_dir(cargo_toml, extra_env, &rustc, &["--print", "sysroot"])?;
     let stdout = String::from_utf8(rustc_output.stdout)?;
     let sysroot_path = Path::new(stdout.trim());
     let src_path = sysroot_path.join("lib/rustlib/src/rust/src");
 
     if !src_path.exists() {
-        run_command_in_cargo_dir(cargo_toml, "rustup", &["component", "add", "rust-src"])?;
+        run_command_in_cargo_dir(
+            cargo_toml,
+            extra_env,
+            "rustup",
+            &["component", "add", "rust-src"],
+        )?;
     }
     if !src_path.exists() {
         bail!(
@@ -134,6 +154,15 @@ fn get_or_install_rust_src(cargo_toml: &Path) -> Result<PathBuf> {
     Ok(src_path)
 }
 
+/// Returns the `rustc` binary to use, respecting a `RUSTC` override in `extra_env`.
+pub(crate) fn rustc_binary(extra_env: &FxHashMap<String, String>) -> String {
+    extra_env
+        .get("RUSTC")
+        .cloned()
+        .or_else(|| env::var("RUSTC").ok())
+        .unwrap_or_else(|| "rustc".to_string())
+}
+
 impl SysrootCrateData {
     pub fn root_dir(&self) -> &Path {
         self.root.parent().unwrap()
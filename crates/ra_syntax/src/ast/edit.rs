@@ -4,6 +4,8 @@
 use std::{iter, ops::RangeInclusive};
 
 use arrayvec::ArrayVec;
+use ra_text_edit::{TextEdit, TextEditBuilder};
+use rustc_hash::FxHashMap;
 
 use crate::{
     algo::{self, neighbor, SyntaxRewriter},
@@ -12,7 +14,7 @@ use crate::{
         make::{self, tokens},
         AstNode, TypeBoundsOwner,
     },
-    AstToken, Direction, InsertPosition, SmolStr, SyntaxElement, SyntaxKind,
+    AstToken, Direction, InsertPosition, NodeOrToken, SmolStr, SyntaxElement, SyntaxKind,
     SyntaxKind::{ATTR, COMMENT, WHITESPACE},
     SyntaxNode, SyntaxToken, T,
 };
@@ -396,7 +398,21 @@ impl ast::MatchArmList {
 
     #[must_use]
     pub fn append_arm(&self, item: ast::MatchArm) -> ast::MatchArmList {
-        let r_curly = match self.syntax().children_with_tokens().find(|it| it.kind() == T!['}']) {
+        let mut res = self.clone();
+        if let Some(last_arm) = res.arms().last() {
+            let has_comma = last_arm
+                .syntax()
+                .siblings_with_tokens(Direction::Next)
+                .skip(1)
+                .find(|it| !it.kind().is_trivia())
+                .map_or(false, |it| it.kind() == T![,]);
+            if !has_comma && needs_comma(&last_arm) {
+                let position = InsertPosition::After(last_arm.syntax().clone().into());
+                res = res.insert_children(position, iter::once(make::token(T![,]).into()));
+            }
+        }
+
+        let r_curly = match res.syntax().children_with_tokens().find(|it| it.kind() == T!['}']) {
             Some(t) => t,
             None => return self.clone(),
         };
@@ -404,12 +420,22 @@ impl ast::MatchArmList {
         let arm_ws = tokens::WsBuilder::new("    ");
         let match_indent = &leading_indent(self.syntax()).unwrap_or_default();
         let match_ws = tokens::WsBuilder::new(&format!("\n{}", match_indent));
-        let to_insert: ArrayVec<[SyntaxElement; 3]> =
-            [arm_ws.ws().into(), item.syntax().clone().into(), match_ws.ws().into()].into();
-        self.insert_children(position, to_insert)
+        let mut to_insert: ArrayVec<[SyntaxElement; 4]> = ArrayVec::new();
+        to_insert.push(arm_ws.ws().into());
+        to_insert.push(item.syntax().clone().into());
+        if needs_comma(&item) {
+            to_insert.push(make::token(T![,]).into());
+        }
+        to_insert.push(match_ws.ws().into());
+        res.insert_children(position, to_insert)
     }
 }
 
+/// Arms whose body is not a block must be separated from the next arm by a comma.
+fn needs_comma(arm: &ast::MatchArm) -> bool {
+    !matches!(arm.expr(), Some(ast::Expr::BlockExpr(_)))
+}
+
 #[must_use]
 pub fn remove_attrs_and_docs<N: ast::AttrsOwner>(node: &N) -> N {
     N::cast(remove_attrs_and_docs_inner(node.syntax().clone())).unwrap()
@@ -562,6 +588,169 @@ pub trait AstNodeEdit: AstNode + Sized {
 
 impl<N: AstNode> AstNodeEdit for N {}
 
+/// Collects edits to a syntax tree and applies them all at once.
+///
+/// Unlike the functions above, which build a fresh node for every change, the
+/// editor keeps referring to the elements of the original tree, so any number
+/// of edits can be recorded before producing the new tree or a `TextEdit`.
+/// Whitespace around attached and detached elements is fixed up to keep the
+/// surrounding formatting intact.
+#[derive(Debug)]
+pub struct SyntaxEditor {
+    root: SyntaxNode,
+    edits: FxHashMap<SyntaxElement, ElementEdit>,
+}
+
+#[derive(Debug, Default)]
+struct ElementEdit {
+    before: Vec<SyntaxElement>,
+    change: Option<Change>,
+    after: Vec<SyntaxElement>,
+}
+
+#[derive(Debug)]
+enum Change {
+    Detach,
+    Replace(SyntaxElement),
+}
+
+impl SyntaxEditor {
+    pub fn new(root: SyntaxNode) -> SyntaxEditor {
+        SyntaxEditor { root, edits: FxHashMap::default() }
+    }
+
+    pub fn root(&self) -> &SyntaxNode {
+        &self.root
+    }
+
+    /// Replaces `old` with `new`, re-indenting `new` to the level of `old`.
+    pub fn replace(&mut self, old: impl Into<SyntaxElement>, new: impl Into<SyntaxElement>) {
+        let old = old.into();
+        let new = match (&old, new.into()) {
+            (NodeOrToken::Node(old), NodeOrToken::Node(new)) => reindent(new, old).into(),
+            (_, new) => new,
+        };
+        self.edit(old).change = Some(Change::Replace(new));
+    }
+
+    /// Removes `element`, together with the whitespace separating it from its
+    /// neighbours.
+    pub fn detach(&mut self, element: impl Into<SyntaxElement>) {
+        let element = element.into();
+        let whitespace = element
+            .next_sibling_or_token()
+            .filter(|it| it.kind() == WHITESPACE)
+            .or_else(|| element.prev_sibling_or_token().filter(|it| it.kind() == WHITESPACE));
+        if let Some(whitespace) = whitespace {
+            self.edit(whitespace).change.get_or_insert(Change::Detach);
+        }
+        self.edit(element).change = Some(Change::Detach);
+    }
+
+    /// Attaches `new` in front of `anchor`, separated from it the same way
+    /// `anchor` is separated from its preceding sibling.
+    pub fn attach_before(
+        &mut self,
+        anchor: impl Into<SyntaxElement>,
+        new: impl Into<SyntaxElement>,
+    ) {
+        let anchor = anchor.into();
+        let (separator, new) = separated(&anchor, new.into());
+        self.edit(anchor).before.extend(vec![new, separator.into()]);
+    }
+
+    /// Attaches `new` after `anchor`, separated from it the same way `anchor`
+    /// is separated from its preceding sibling.
+    pub fn attach_after(
+        &mut self,
+        anchor: impl Into<SyntaxElement>,
+        new: impl Into<SyntaxElement>,
+    ) {
+        let anchor = anchor.into();
+        let (separator, new) = separated(&anchor, new.into());
+        // later attachments end up closer to the anchor
+        let after = &mut self.edit(anchor).after;
+        after.splice(0..0, vec![separator.into(), new]);
+    }
+
+    /// Applies all recorded edits, returning the new version of the root.
+    pub fn finish(&self) -> SyntaxNode {
+        SyntaxNode::new_root(self.rewrite(&self.root))
+    }
+
+    /// Applies all recorded edits and returns them as a `TextEdit` against the
+    /// original text.
+    pub fn into_text_edit(self) -> TextEdit {
+        let new_root = self.finish();
+        let mut builder = TextEditBuilder::default();
+        algo::diff(&self.root, &new_root).into_text_edit(&mut builder);
+        builder.finish()
+    }
+
+    fn edit(&mut self, element: SyntaxElement) -> &mut ElementEdit {
+        self.edits.entry(element).or_default()
+    }
+
+    fn rewrite(&self, node: &SyntaxNode) -> rowan::GreenNode {
+        let mut children = Vec::new();
+        for child in node.children_with_tokens() {
+            let edit = self.edits.get(&child);
+            if let Some(edit) = edit {
+                children.extend(edit.before.iter().cloned().map(to_green_element));
+            }
+            match edit.and_then(|it| it.change.as_ref()) {
+                None => children.push(match &child {
+                    NodeOrToken::Node(it) => self.rewrite(it).into(),
+                    NodeOrToken::Token(it) => it.green().clone().into(),
+                }),
+                Some(Change::Detach) => (),
+                Some(Change::Replace(new)) => children.push(to_green_element(new.clone())),
+            }
+            if let Some(edit) = edit {
+                children.extend(edit.after.iter().cloned().map(to_green_element));
+            }
+        }
+        rowan::GreenNode::new(rowan::SyntaxKind(node.kind() as u16), children)
+    }
+}
+
+/// Moves `node` from its current indentation level to the one of `target`.
+fn reindent(node: SyntaxNode, target: &SyntaxNode) -> SyntaxNode {
+    let node = match node.parent() {
+        Some(_) => IndentLevel::from_node(&node)._decrease_indent(node),
+        None => node,
+    };
+    IndentLevel::from_node(target)._increase_indent(node)
+}
+
+/// Returns the whitespace to put between `anchor` and a newly attached
+/// element, together with that element indented to fit in.
+fn separated(anchor: &SyntaxElement, new: SyntaxElement) -> (SyntaxToken, SyntaxElement) {
+    let leading_ws = anchor
+        .prev_sibling_or_token()
+        .and_then(|it| it.into_token())
+        .filter(|it| it.kind() == WHITESPACE && it.text().contains('\n'));
+    let separator = match leading_ws {
+        Some(ws) => {
+            let indent = ws.text().rsplit('\n').next().unwrap_or_default();
+            tokens::whitespace(&format!("\n{}", indent))
+        }
+        None => tokens::single_space(),
+    };
+    let new = match (anchor, new) {
+        (NodeOrToken::Node(anchor), NodeOrToken::Node(new)) => reindent(new, anchor).into(),
+        (_, new) => new,
+    };
+    (separator, new)
+}
+
+fn to_green_element(element: SyntaxElement) -> NodeOrToken<rowan::GreenNode, rowan::GreenToken> {
+    match element {
+        NodeOrToken::Node(it) => it.green().clone().into(),
+        NodeOrToken::Token(it) => it.green().clone().into(),
+    }
+}
+
 fn single_node(element: impl Into<SyntaxElement>) -> RangeInclusive<SyntaxElement> {
     let element = element.into();
     element.clone()..=element
@@ -589,3 +778,72 @@ fn test_increase_indent() {
         }"
     );
 }
+
+#[test]
+fn test_syntax_editor() {
+    let file = crate::SourceFile::parse(
+        "fn foo() {
+    let a = 1;
+    bar(a);
+}
+",
+    )
+    .tree();
+    let let_stmt = file.syntax().descendants().find_map(ast::LetStmt::cast).unwrap();
+    let expr_stmt = file.syntax().descendants().find_map(ast::ExprStmt::cast).unwrap();
+
+    let mut editor = SyntaxEditor::new(file.syntax().clone());
+    editor.detach(let_stmt.syntax().clone());
+    let new_stmt = make::let_stmt(make::bind_pat(make::name("b")).into(), None);
+    editor.attach_after(expr_stmt.syntax().clone(), new_stmt.syntax().clone());
+    editor.replace(expr_stmt.expr().unwrap().syntax().clone(), make::expr_unit().syntax().clone());
+    assert_eq!(
+        editor.finish().to_string(),
+        "fn foo() {
+    ();
+    let b;
+}
+"
+    );
+
+    let text = editor.into_text_edit().apply(file.syntax().to_string());
+    assert_eq!(
+        text,
+        "fn foo() {
+    ();
+    let b;
+}
+"
+    );
+}
+
+#[test]
+fn test_syntax_editor_reindents() {
+    let file = crate::SourceFile::parse(
+        "fn foo() {
+    match x {
+        _ => (),
+    }
+}
+",
+    )
+    .tree();
+    let arm_list = file.syntax().descendants().find_map(ast::MatchArmList::cast).unwrap();
+    let new_list = make::match_arm_list(vec![
+        make::match_arm(iter::once(make::placeholder_pat().into()), make::expr_unit()),
+        make::match_arm(iter::once(make::placeholder_pat().into()), make::expr_unit()),
+    ]);
+
+    let mut editor = SyntaxEditor::new(file.syntax().clone());
+    editor.replace(arm_list.syntax().clone(), new_list.syntax().clone());
+    assert_eq!(
+        editor.finish().to_string(),
+        "fn foo() {
+    match x {
+        _ => (),
+        _ => (),
+    }
+}
+"
+    );
+}
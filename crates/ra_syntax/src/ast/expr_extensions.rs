@@ -357,6 +357,49 @@ impl ast::Literal {
             _ => unreachable!(),
         }
     }
+
+    /// Returns the value of an integer or byte literal, ignoring its suffix.
+    pub fn int_value(&self) -> Option<u128> {
+        let token = self.token();
+        let text = token.text().as_str();
+        match self.kind() {
+            LiteralKind::IntNumber { suffix } => {
+                let text = &text[..text.len() - suffix.map_or(0, |it| it.len())];
+                let text = text.replace('_', "");
+                let (text, radix) = match text.get(..2) {
+                    Some("0x") => (&text[2..], 16),
+                    Some("0o") => (&text[2..], 8),
+                    Some("0b") => (&text[2..], 2),
+                    _ => (&text[..], 10),
+                };
+                u128::from_str_radix(text, radix).ok()
+            }
+            LiteralKind::Byte => {
+                let text = unquote(text, 2, '\'')?;
+                rustc_lexer::unescape::unescape_byte(text).ok().map(u128::from)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a char literal.
+    pub fn char_value(&self) -> Option<char> {
+        if self.kind() != LiteralKind::Char {
+            return None;
+        }
+        let token = self.token();
+        let text = token.text().as_str();
+        let text = unquote(text, 1, '\'')?;
+        rustc_lexer::unescape::unescape_char(text).ok()
+    }
+}
+
+fn unquote(text: &str, prefix_len: usize, end_delimiter: char) -> Option<&str> {
+    let text = text.get(prefix_len..)?;
+    if text.is_empty() || !text.ends_with(end_delimiter) {
+        return None;
+    }
+    Some(&text[..text.len() - end_delimiter.len_utf8()])
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -364,6 +407,7 @@ pub enum Effect {
     Async(SyntaxToken),
     Unsafe(SyntaxToken),
     Try(SyntaxToken),
+    Const(SyntaxToken),
     // Very much not an effect, but we stuff it into this node anyway
     Label(ast::Label),
 }
@@ -379,6 +423,9 @@ impl ast::EffectExpr {
         if let Some(token) = self.try_token() {
             return Effect::Try(token);
         }
+        if let Some(token) = self.const_token() {
+            return Effect::Const(token);
+        }
         if let Some(label) = self.label() {
             return Effect::Label(label);
         }
@@ -322,6 +322,46 @@ impl ast::SlicePat {
     }
 }
 
+impl ast::RangePat {
+    fn op_details(&self) -> Option<(usize, ast::RangeOp)> {
+        self.syntax().children_with_tokens().enumerate().find_map(|(ix, child)| {
+            let op = match child.into_token()?.kind() {
+                T![..] => ast::RangeOp::Exclusive,
+                T![..=] | T![...] => ast::RangeOp::Inclusive,
+                _ => return None,
+            };
+            Some((ix, op))
+        })
+    }
+
+    pub fn op_kind(&self) -> Option<ast::RangeOp> {
+        self.op_details().map(|t| t.1)
+    }
+
+    pub fn start(&self) -> Option<ast::Pat> {
+        let op_ix = self.op_details()?.0;
+        self.syntax()
+            .children_with_tokens()
+            .take(op_ix)
+            .find_map(|it| ast::Pat::cast(it.into_node()?))
+    }
+
+    pub fn end(&self) -> Option<ast::Pat> {
+        let op_ix = self.op_details()?.0;
+        self.syntax()
+            .children_with_tokens()
+            .skip(op_ix + 1)
+            .find_map(|it| ast::Pat::cast(it.into_node()?))
+    }
+}
+
+impl ast::LiteralPat {
+    /// Whether the literal is preceded by a `-`, as in `-1`.
+    pub fn is_negated(&self) -> bool {
+        self.syntax().children_with_tokens().any(|it| it.kind() == T![-])
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum SelfParamKind {
     /// self
@@ -439,24 +479,6 @@ impl ast::LifetimeParam {
     }
 }
 
-impl ast::RangePat {
-    pub fn start(&self) -> Option<ast::Pat> {
-        self.syntax()
-            .children_with_tokens()
-            .take_while(|it| !(it.kind() == T![..] || it.kind() == T![..=]))
-            .filter_map(|it| it.into_node())
-            .find_map(ast::Pat::cast)
-    }
-
-    pub fn end(&self) -> Option<ast::Pat> {
-        self.syntax()
-            .children_with_tokens()
-            .skip_while(|it| !(it.kind() == T![..] || it.kind() == T![..=]))
-            .filter_map(|it| it.into_node())
-            .find_map(ast::Pat::cast)
-    }
-}
-
 impl ast::TokenTree {
     pub fn left_delimiter_token(&self) -> Option<SyntaxToken> {
         self.syntax().first_child_or_token()?.into_token().filter(|it| match it.kind() {
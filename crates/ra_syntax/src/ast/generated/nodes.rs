@@ -487,6 +487,7 @@ impl EffectExpr {
     pub fn try_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![try]) }
     pub fn unsafe_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![unsafe]) }
     pub fn async_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![async]) }
+    pub fn const_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![const]) }
     pub fn block_expr(&self) -> Option<BlockExpr> { support::child(&self.syntax) }
 }
 
@@ -1360,8 +1361,12 @@ impl MetaItem {
 pub struct MacroDef {
     pub(crate) syntax: SyntaxNode,
 }
+impl ast::VisibilityOwner for MacroDef {}
+impl ast::NameOwner for MacroDef {}
+impl ast::AttrsOwner for MacroDef {}
+impl ast::DocCommentsOwner for MacroDef {}
 impl MacroDef {
-    pub fn name(&self) -> Option<Name> { support::child(&self.syntax) }
+    pub fn macro_token(&self) -> Option<SyntaxToken> { support::token(&self.syntax, T![macro]) }
     pub fn token_tree(&self) -> Option<TokenTree> { support::child(&self.syntax) }
 }
 
@@ -1422,6 +1427,7 @@ pub enum ModuleItem {
     StaticDef(StaticDef),
     Module(Module),
     MacroCall(MacroCall),
+    MacroDef(MacroDef),
     ExternBlock(ExternBlock),
 }
 impl ast::NameOwner for ModuleItem {}
@@ -3116,6 +3122,9 @@ impl From<Module> for ModuleItem {
 impl From<MacroCall> for ModuleItem {
     fn from(node: MacroCall) -> ModuleItem { ModuleItem::MacroCall(node) }
 }
+impl From<MacroDef> for ModuleItem {
+    fn from(node: MacroDef) -> ModuleItem { ModuleItem::MacroDef(node) }
+}
 impl From<ExternBlock> for ModuleItem {
     fn from(node: ExternBlock) -> ModuleItem { ModuleItem::ExternBlock(node) }
 }
@@ -3124,7 +3133,7 @@ impl AstNode for ModuleItem {
         match kind {
             STRUCT_DEF | UNION_DEF | ENUM_DEF | FN_DEF | TRAIT_DEF | TYPE_ALIAS_DEF | IMPL_DEF
             | USE_ITEM | EXTERN_CRATE_ITEM | CONST_DEF | STATIC_DEF | MODULE | MACRO_CALL
-            | EXTERN_BLOCK => true,
+            | MACRO_DEF | EXTERN_BLOCK => true,
             _ => false,
         }
     }
@@ -3143,6 +3152,7 @@ impl AstNode for ModuleItem {
             STATIC_DEF => ModuleItem::StaticDef(StaticDef { syntax }),
             MODULE => ModuleItem::Module(Module { syntax }),
             MACRO_CALL => ModuleItem::MacroCall(MacroCall { syntax }),
+            MACRO_DEF => ModuleItem::MacroDef(MacroDef { syntax }),
             EXTERN_BLOCK => ModuleItem::ExternBlock(ExternBlock { syntax }),
             _ => return None,
         };
@@ -3163,6 +3173,7 @@ impl AstNode for ModuleItem {
             ModuleItem::StaticDef(it) => &it.syntax,
             ModuleItem::Module(it) => &it.syntax,
             ModuleItem::MacroCall(it) => &it.syntax,
+            ModuleItem::MacroDef(it) => &it.syntax,
             ModuleItem::ExternBlock(it) => &it.syntax,
         }
     }
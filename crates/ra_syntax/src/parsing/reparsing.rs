@@ -4,7 +4,8 @@
 //!   - if the edit modifies only a single token (like changing an identifier's
 //!     letter), we replace only this token.
 //!   - otherwise, we search for the nearest `{}` block which contains the edit
-//!     and try to parse only this block.
+//!     and try to parse only this block. If the edit leaves that block
+//!     unbalanced, we move on to the enclosing blocks.
 
 use ra_parser::Reparser;
 use ra_text_edit::AtomTextEdit;
@@ -44,7 +45,7 @@ fn reparse_token<'node>(
     let prev_token = algo::find_covering_element(root, edit.delete).as_token()?.clone();
     let prev_token_kind = prev_token.kind();
     match prev_token_kind {
-        WHITESPACE | COMMENT | IDENT | STRING | RAW_STRING => {
+        WHITESPACE | COMMENT | IDENT | STRING | RAW_STRING | INT_NUMBER | LIFETIME => {
             if prev_token_kind == WHITESPACE || prev_token_kind == COMMENT {
                 // removing a new line may extends previous token
                 let deleted_range = edit.delete - prev_token.text_range().start();
@@ -90,22 +91,23 @@ fn reparse_block<'node>(
     root: &'node SyntaxNode,
     edit: &AtomTextEdit,
 ) -> Option<(GreenNode, Vec<SyntaxError>, TextRange)> {
-    let (node, reparser) = find_reparsable_node(root, edit.delete)?;
-    let text = get_text_after_edit(node.clone().into(), edit);
+    reparsable_ancestors(root, edit.delete).find_map(|(node, reparser)| {
+        let text = get_text_after_edit(node.clone().into(), edit);
 
-    let (tokens, new_lexer_errors) = tokenize(&text);
-    if !is_balanced(&tokens) {
-        return None;
-    }
+        let (tokens, new_lexer_errors) = tokenize(&text);
+        if !is_balanced(&tokens) {
+            return None;
+        }
 
-    let mut token_source = TextTokenSource::new(&text, &tokens);
-    let mut tree_sink = TextTreeSink::new(&text, &tokens);
-    reparser.parse(&mut token_source, &mut tree_sink);
+        let mut token_source = TextTokenSource::new(&text, &tokens);
+        let mut tree_sink = TextTreeSink::new(&text, &tokens);
+        reparser.parse(&mut token_source, &mut tree_sink);
 
-    let (green, mut new_parser_errors) = tree_sink.finish();
-    new_parser_errors.extend(new_lexer_errors);
+        let (green, mut new_parser_errors) = tree_sink.finish();
+        new_parser_errors.extend(new_lexer_errors);
 
-    Some((node.replace_with(green), new_parser_errors, node.text_range()))
+        Some((node.replace_with(green), new_parser_errors, node.text_range()))
+    })
 }
 
 fn get_text_after_edit(element: SyntaxElement, edit: &AtomTextEdit) -> String {
@@ -126,14 +128,19 @@ fn is_contextual_kw(text: &str) -> bool {
     }
 }
 
-fn find_reparsable_node(node: &SyntaxNode, range: TextRange) -> Option<(SyntaxNode, Reparser)> {
+/// Returns the nodes around `range` which can be reparsed on their own,
+/// innermost first.
+fn reparsable_ancestors(
+    node: &SyntaxNode,
+    range: TextRange,
+) -> impl Iterator<Item = (SyntaxNode, Reparser)> {
     let node = algo::find_covering_element(node, range);
 
-    let mut ancestors = match node {
+    let ancestors = match node {
         NodeOrToken::Token(it) => it.parent().ancestors(),
         NodeOrToken::Node(it) => it.ancestors(),
     };
-    ancestors.find_map(|node| {
+    ancestors.filter_map(|node| {
         let first_child = node.first_child_or_token().map(|it| it.kind());
         let parent = node.parent().map(|it| it.kind());
         Reparser::for_node(node.kind(), first_child, parent).map(|r| (node, r))
@@ -255,7 +262,7 @@ fn foo {
 }
 ",
             "62",
-            31, // FIXME: reparse only int literal here
+            2,
         );
         do_check(
             r"
@@ -322,6 +329,30 @@ extern {
         );
     }
 
+    #[test]
+    fn reparse_enclosing_block_when_inner_is_unbalanced() {
+        do_check(
+            r"
+fn foo() {
+    let x = { <|>1<|> };
+}
+",
+            "1 } + { 2",
+            22,
+        );
+        do_check(
+            r"
+fn foo() {
+    match x {
+        _ => { <|><|> }
+    }
+}
+",
+            "}\n        1 => {",
+            25,
+        );
+    }
+
     #[test]
     fn reparse_token_tests() {
         do_check(
@@ -404,6 +435,20 @@ fn -> &str { r#"Hello<|><|>"#
         );
         do_check(
             r"
+fn foo() -> i32 { <|>92<|> }
+",
+            "42",
+            2,
+        );
+        do_check(
+            r"
+fn foo<'a>(x: &<|>'a<|> str) {}
+",
+            "'b",
+            2,
+        );
+        do_check(
+            r"
 #[derive(<|>Copy<|>)]
 enum Foo {
 
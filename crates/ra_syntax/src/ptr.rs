@@ -21,6 +21,12 @@ impl SyntaxNodePtr {
         SyntaxNodePtr { range: node.text_range(), kind: node.kind() }
     }
 
+    /// Creates a pointer from its parts, e.g. when loading a previously stored
+    /// pointer. The node isn't required to exist.
+    pub fn from_raw_parts(kind: SyntaxKind, range: TextRange) -> SyntaxNodePtr {
+        SyntaxNodePtr { range, kind }
+    }
+
     pub fn to_node(&self, root: &SyntaxNode) -> SyntaxNode {
         assert!(root.parent().is_none());
         successors(Some(root.clone()), |node| {
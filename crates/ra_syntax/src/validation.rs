@@ -2,14 +2,17 @@
 
 mod block;
 
-use std::convert::TryFrom;
+use std::{convert::TryFrom, ops::Range};
 
 use rustc_lexer::unescape;
 
 use crate::{
     ast, match_ast, AstNode, SyntaxError,
-    SyntaxKind::{BYTE, BYTE_STRING, CHAR, CONST_DEF, FN_DEF, INT_NUMBER, STRING, TYPE_ALIAS_DEF},
-    SyntaxNode, SyntaxToken, TextSize, T,
+    SyntaxKind::{
+        self, BYTE, BYTE_STRING, CHAR, CONST_DEF, FLOAT_NUMBER, FN_DEF, INT_NUMBER,
+        RAW_BYTE_STRING, RAW_STRING, STRING, TYPE_ALIAS_DEF,
+    },
+    SyntaxNode, SyntaxToken, TextRange, TextSize, T,
 };
 
 fn rustc_unescape_error_to_string(err: unescape::EscapeError) -> &'static str {
@@ -112,43 +115,148 @@ fn validate_literal(literal: ast::Literal, acc: &mut Vec<SyntaxError>) {
 
     let token = literal.token();
     let text = token.text().as_str();
+    let token_start = token.text_range().start();
+    let range_in_token = |range: Range<usize>| {
+        let range = TextRange::new(
+            TextSize::try_from(range.start).unwrap(),
+            TextSize::try_from(range.end).unwrap(),
+        );
+        range + token_start
+    };
 
     // FIXME: lift this lambda refactor to `fn` (https://github.com/rust-analyzer/rust-analyzer/pull/2834#discussion_r366199205)
-    let mut push_err = |prefix_len, (off, err): (usize, unescape::EscapeError)| {
-        let off = token.text_range().start() + TextSize::try_from(off + prefix_len).unwrap();
-        acc.push(SyntaxError::new_at_offset(rustc_unescape_error_to_string(err), off));
+    let mut push_err = |prefix_len, range: Range<usize>, err: unescape::EscapeError| {
+        let range = range_in_token(range.start + prefix_len..range.end + prefix_len);
+        acc.push(SyntaxError::new(rustc_unescape_error_to_string(err), range));
     };
 
-    match token.kind() {
-        BYTE => {
-            if let Some(Err(e)) = unquote(text, 2, '\'').map(unescape::unescape_byte) {
-                push_err(2, e);
+    let literal_end = match token.kind() {
+        BYTE => unquote(text, 2, '\'').map(|without_quotes| {
+            if let Err((_, err)) = unescape::unescape_byte(without_quotes) {
+                push_err(2, 0..without_quotes.len(), err);
             }
-        }
-        CHAR => {
-            if let Some(Err(e)) = unquote(text, 1, '\'').map(unescape::unescape_char) {
-                push_err(1, e);
+            2 + without_quotes.len() + 1
+        }),
+        CHAR => unquote(text, 1, '\'').map(|without_quotes| {
+            if let Err((_, err)) = unescape::unescape_char(without_quotes) {
+                push_err(1, 0..without_quotes.len(), err);
+            }
+            1 + without_quotes.len() + 1
+        }),
+        BYTE_STRING => unquote(text, 2, '"').map(|without_quotes| {
+            unescape::unescape_byte_str(without_quotes, &mut |range, char| {
+                if let Err(err) = char {
+                    push_err(2, range, err);
+                }
+            });
+            2 + without_quotes.len() + 1
+        }),
+        STRING => unquote(text, 1, '"').map(|without_quotes| {
+            unescape::unescape_str(without_quotes, &mut |range, char| {
+                if let Err(err) = char {
+                    push_err(1, range, err);
+                }
+            });
+            1 + without_quotes.len() + 1
+        }),
+        RAW_STRING | RAW_BYTE_STRING => {
+            let prefix_len = text.find('"').map_or(0, |it| it + 1);
+            let hashes = text[..prefix_len].bytes().filter(|&it| it == b'#').count();
+            unquote(text, prefix_len, '"').map(|without_quotes| {
+                if token.kind() == RAW_STRING {
+                    unescape::unescape_raw_str(without_quotes, &mut |range, char| {
+                        if let Err(err) = char {
+                            push_err(prefix_len, range, err);
+                        }
+                    });
+                } else {
+                    unescape::unescape_raw_byte_str(without_quotes, &mut |range, char| {
+                        if let Err(err) = char {
+                            push_err(prefix_len, range, err);
+                        }
+                    });
+                }
+                prefix_len + without_quotes.len() + 1 + hashes
+            })
+        }
+        INT_NUMBER | FLOAT_NUMBER => {
+            let suffix_start = number_suffix_start(text);
+            let suffix = &text[suffix_start..];
+            if !suffix.is_empty() && !is_valid_number_suffix(text, token.kind(), suffix) {
+                let kind = if token.kind() == FLOAT_NUMBER { "float" } else { "number" };
+                acc.push(SyntaxError::new(
+                    format!("Invalid suffix `{}` for {} literal", suffix, kind),
+                    range_in_token(suffix_start..text.len()),
+                ));
             }
+            None
         }
-        BYTE_STRING => {
-            if let Some(without_quotes) = unquote(text, 2, '"') {
-                unescape::unescape_byte_str(without_quotes, &mut |range, char| {
-                    if let Err(err) = char {
-                        push_err(2, (range.start, err));
-                    }
-                })
+        _ => None,
+    };
+
+    if let Some(literal_end) = literal_end {
+        if literal_end < text.len() {
+            acc.push(SyntaxError::new(
+                "Suffixes on string and character literals are invalid",
+                range_in_token(literal_end..text.len()),
+            ));
+        }
+    }
+}
+
+/// Returns the offset at which the suffix of a numeric literal starts, that is
+/// the first character which can't be a part of the number itself.
+fn number_suffix_start(text: &str) -> usize {
+    let (mut idx, is_hex) = match text.get(..2) {
+        Some("0x") => (2, true),
+        Some("0o") | Some("0b") => (2, false),
+        _ => (0, false),
+    };
+    let bytes = text.as_bytes();
+    let is_digit = |idx: usize| match bytes.get(idx) {
+        Some(&b) => b.is_ascii_digit() || b == b'_' || (is_hex && b.is_ascii_hexdigit()),
+        None => false,
+    };
+    while is_digit(idx) {
+        idx += 1;
+    }
+    if !is_hex {
+        if bytes.get(idx) == Some(&b'.') && (idx + 1 == bytes.len() || is_digit(idx + 1)) {
+            idx += 1;
+            while is_digit(idx) {
+                idx += 1;
             }
         }
-        STRING => {
-            if let Some(without_quotes) = unquote(text, 1, '"') {
-                unescape::unescape_str(without_quotes, &mut |range, char| {
-                    if let Err(err) = char {
-                        push_err(1, (range.start, err));
-                    }
-                })
+        if let Some(b'e') | Some(b'E') = bytes.get(idx) {
+            let mut exp = idx + 1;
+            if let Some(b'+') | Some(b'-') = bytes.get(exp) {
+                exp += 1;
+            }
+            if is_digit(exp) {
+                idx = exp;
+                while is_digit(idx) {
+                    idx += 1;
+                }
             }
         }
-        _ => (),
+    }
+    idx
+}
+
+fn is_valid_number_suffix(text: &str, kind: SyntaxKind, suffix: &str) -> bool {
+    const INT_SUFFIXES: [&str; 12] =
+        ["u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"];
+    const FLOAT_SUFFIXES: [&str; 2] = ["f32", "f64"];
+
+    let is_decimal = match text.get(..2) {
+        Some("0x") | Some("0o") | Some("0b") => false,
+        _ => true,
+    };
+    match kind {
+        INT_NUMBER => {
+            INT_SUFFIXES.contains(&suffix) || (is_decimal && FLOAT_SUFFIXES.contains(&suffix))
+        }
+        _ => FLOAT_SUFFIXES.contains(&suffix),
     }
 }
 
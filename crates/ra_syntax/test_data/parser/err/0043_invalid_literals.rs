@@ -0,0 +1,8 @@
+fn main() {
+    let _ = "\q";
+    let _ = '\u{110000}';
+    let _ = 1u7;
+    let _ = 1.0f16;
+    let _ = "a"b;
+    let _ = r#"x"#y;
+}
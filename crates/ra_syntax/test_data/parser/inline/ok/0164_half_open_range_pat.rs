@@ -0,0 +1,7 @@
+fn main() {
+    match 92 {
+        0.. => (),
+        ..=9 => (),
+        ..10 | 20.. => (),
+    }
+}
@@ -45,6 +45,11 @@ pub(crate) enum Command {
         /// this would include the parser test files.
         all: bool,
     },
+    Lsif {
+        path: PathBuf,
+        load_output_dirs: bool,
+        with_proc_macro: bool,
+    },
     ProcMacro,
     RunServer,
     Version,
@@ -269,6 +274,38 @@ ARGS:
 
                 Command::Diagnostics { path, load_output_dirs, with_proc_macro, all }
             }
+            "lsif" => {
+                if matches.contains(["-h", "--help"]) {
+                    eprintln!(
+                        "\
+rust-analyzer lsif
+
+USAGE:
+    rust-analyzer lsif [FLAGS] [PATH]
+
+FLAGS:
+    -h, --help              Prints help information
+        --load-output-dirs  Load OUT_DIR values by running `cargo check` before analysis
+        --with-proc-macro   Use ra-proc-macro-srv for proc-macro expanding
+
+ARGS:
+    <PATH>"
+                    );
+                    return Ok(Err(HelpPrinted));
+                }
+
+                let load_output_dirs = matches.contains("--load-output-dirs");
+                let with_proc_macro = matches.contains("--with-proc-macro");
+                let path = {
+                    let mut trailing = matches.free()?;
+                    if trailing.len() != 1 {
+                        bail!("Invalid flags");
+                    }
+                    trailing.pop().unwrap().into()
+                };
+
+                Command::Lsif { path, load_output_dirs, with_proc_macro }
+            }
             "proc-macro" => Command::ProcMacro,
             _ => {
                 print_subcommands();
@@ -295,6 +332,7 @@ SUBCOMMANDS:
     analysis-stats
     highlight
     diagnostics
+    lsif
     proc-macro
     parse
     symbols"
@@ -4,6 +4,7 @@
 mod args;
 
 use lsp_server::Connection;
+use ra_project_model::CargoConfig;
 use rust_analyzer::{cli, config::Config, from_json, Result};
 
 use crate::args::HelpPrinted;
@@ -33,7 +34,7 @@ fn main() -> Result<()> {
             only.as_ref().map(String::as_ref),
             with_deps,
             randomize,
-            load_output_dirs,
+            &cargo_config(load_output_dirs),
             with_proc_macro,
         )?,
 
@@ -42,13 +43,17 @@ fn main() -> Result<()> {
                 args.verbosity,
                 path.as_ref(),
                 what,
-                load_output_dirs,
+                &cargo_config(load_output_dirs),
                 with_proc_macro,
             )?
         }
 
         args::Command::Diagnostics { path, load_output_dirs, with_proc_macro, all } => {
-            cli::diagnostics(path.as_ref(), load_output_dirs, with_proc_macro, all)?
+            cli::diagnostics(path.as_ref(), &cargo_config(load_output_dirs), with_proc_macro, all)?
+        }
+
+        args::Command::Lsif { path, load_output_dirs, with_proc_macro } => {
+            cli::lsif(path.as_ref(), &cargo_config(load_output_dirs), with_proc_macro)?
         }
 
         args::Command::ProcMacro => run_proc_macro_srv()?,
@@ -58,6 +63,10 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+fn cargo_config(load_out_dirs_from_check: bool) -> CargoConfig {
+    CargoConfig { load_out_dirs_from_check, ..CargoConfig::default() }
+}
+
 fn setup_logging() -> Result<()> {
     std::env::set_var("RUST_BACKTRACE", "short");
     env_logger::try_init()?;
@@ -98,11 +107,15 @@ fn run_server() -> Result<()> {
     let config = {
         let mut config = Config::default();
         if let Some(value) = &initialize_params.initialization_options {
-            config.update(value);
-        }
-        if let Some(caps) = &initialize_params.capabilities.text_document {
-            config.update_caps(caps);
+            for error in config.update(value) {
+                rust_analyzer::show_message(
+                    lsp_types::MessageType::Error,
+                    error,
+                    &connection.sender,
+                );
+            }
         }
+        config.update_caps(&initialize_params.capabilities);
         config
     };
 
@@ -13,6 +13,10 @@ use lsp_types::{
     TextDocumentSyncOptions, TypeDefinitionProviderCapability, WorkDoneProgressOptions,
 };
 
+/// The kind of the code action applying all the fixes in a file which don't
+/// need a choice from the user.
+pub const SOURCE_FIX_ALL: &str = "source.fixAll";
+
 pub fn server_capabilities() -> ServerCapabilities {
     ServerCapabilities {
         text_document_sync: Some(TextDocumentSyncCapability::Options(TextDocumentSyncOptions {
@@ -56,6 +60,7 @@ pub fn server_capabilities() -> ServerCapabilities {
                 lsp_types::code_action_kind::REFACTOR_REWRITE.to_string(),
                 lsp_types::code_action_kind::SOURCE.to_string(),
                 lsp_types::code_action_kind::SOURCE_ORGANIZE_IMPORTS.to_string(),
+                SOURCE_FIX_ALL.to_string(),
             ]),
             work_done_progress_options: Default::default(),
         })),
@@ -91,6 +96,7 @@ pub fn server_capabilities() -> ServerCapabilities {
             }
             .into(),
         ),
-        experimental: Default::default(),
+        // `lsp_types` predates the type hierarchy capability.
+        experimental: Some(serde_json::json!({ "typeHierarchyProvider": true })),
     }
 }
@@ -4,6 +4,7 @@ mod load_cargo;
 mod analysis_stats;
 mod analysis_bench;
 mod diagnostics;
+mod lsif;
 mod progress_report;
 
 use std::io::Read;
@@ -16,6 +17,7 @@ use ra_syntax::{AstNode, SourceFile};
 pub use analysis_bench::{analysis_bench, BenchWhat, Position};
 pub use analysis_stats::analysis_stats;
 pub use diagnostics::diagnostics;
+pub use lsif::lsif;
 
 #[derive(Clone, Copy)]
 pub enum Verbosity {
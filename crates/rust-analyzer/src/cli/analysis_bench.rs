@@ -12,7 +12,11 @@ use ra_db::{
     salsa::{Database, Durability},
     FileId, SourceDatabaseExt,
 };
-use ra_ide::{Analysis, AnalysisChange, AnalysisHost, CompletionConfig, FilePosition, LineCol};
+use ra_ide::{
+    Analysis, AnalysisChange, AnalysisHost, CompletionConfig, DiagnosticsConfig, FilePosition,
+    LineCol,
+};
+use ra_project_model::CargoConfig;
 
 use crate::cli::{load_cargo::load_cargo, Verbosity};
 
@@ -46,14 +50,14 @@ pub fn analysis_bench(
     verbosity: Verbosity,
     path: &Path,
     what: BenchWhat,
-    load_output_dirs: bool,
+    cargo_config: &CargoConfig,
     with_proc_macro: bool,
 ) -> Result<()> {
     ra_prof::init();
 
     let start = Instant::now();
     eprint!("loading: ");
-    let (mut host, roots) = load_cargo(path, load_output_dirs, with_proc_macro)?;
+    let (mut host, roots) = load_cargo(path, cargo_config, with_proc_macro)?;
     let db = host.raw_database();
     eprintln!("{:?}\n", start.elapsed());
 
@@ -83,7 +87,7 @@ pub fn analysis_bench(
     match &what {
         BenchWhat::Highlight { .. } => {
             let res = do_work(&mut host, file_id, |analysis| {
-                analysis.diagnostics(file_id).unwrap();
+                analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap();
                 analysis.highlight_as_html(file_id, false).unwrap()
             });
             if verbosity.is_verbose() {
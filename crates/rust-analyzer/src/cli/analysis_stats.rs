@@ -11,6 +11,7 @@ use hir_def::FunctionId;
 use hir_ty::{Ty, TypeWalk};
 use itertools::Itertools;
 use ra_db::SourceDatabaseExt;
+use ra_project_model::CargoConfig;
 use ra_syntax::AstNode;
 use rand::{seq::SliceRandom, thread_rng};
 use stdx::format_to;
@@ -24,11 +25,11 @@ pub fn analysis_stats(
     only: Option<&str>,
     with_deps: bool,
     randomize: bool,
-    load_output_dirs: bool,
+    cargo_config: &CargoConfig,
     with_proc_macro: bool,
 ) -> Result<()> {
     let db_load_time = Instant::now();
-    let (mut host, roots) = load_cargo(path, load_output_dirs, with_proc_macro)?;
+    let (mut host, roots) = load_cargo(path, cargo_config, with_proc_macro)?;
     let db = host.raw_database();
     println!("Database loaded, {} roots, {:?}", roots.len(), db_load_time.elapsed());
     let analysis_time = Instant::now();
@@ -3,7 +3,8 @@
 
 use anyhow::anyhow;
 use ra_db::SourceDatabaseExt;
-use ra_ide::Severity;
+use ra_ide::{DiagnosticsConfig, Severity};
+use ra_project_model::CargoConfig;
 use std::{collections::HashSet, path::Path};
 
 use crate::cli::{load_cargo::load_cargo, Result};
@@ -11,11 +12,11 @@ use hir::Semantics;
 
 pub fn diagnostics(
     path: &Path,
-    load_output_dirs: bool,
+    cargo_config: &CargoConfig,
     with_proc_macro: bool,
     all: bool,
 ) -> Result<()> {
-    let (host, roots) = load_cargo(path, load_output_dirs, with_proc_macro)?;
+    let (host, roots) = load_cargo(path, cargo_config, with_proc_macro)?;
     let db = host.raw_database();
     let analysis = host.analysis();
     let semantics = Semantics::new(db);
@@ -53,7 +54,9 @@ pub fn diagnostics(
                         crate_name,
                         db.file_relative_path(file_id)
                     );
-                    for diagnostic in analysis.diagnostics(file_id).unwrap() {
+                    for diagnostic in
+                        analysis.diagnostics(&DiagnosticsConfig::default(), file_id).unwrap()
+                    {
                         if matches!(diagnostic.severity, Severity::Error) {
                             found_error = true;
                         }
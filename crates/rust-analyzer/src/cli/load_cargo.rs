@@ -24,16 +24,12 @@ fn vfs_root_to_id(r: ra_vfs::VfsRoot) -> SourceRootId {
 
 pub(crate) fn load_cargo(
     root: &Path,
-    load_out_dirs_from_check: bool,
+    cargo_config: &CargoConfig,
     with_proc_macro: bool,
 ) -> Result<(AnalysisHost, FxHashMap<SourceRootId, PackageRoot>)> {
     let root = std::env::current_dir()?.join(root);
     let root = ProjectRoot::discover_single(&root)?;
-    let ws = ProjectWorkspace::load(
-        root,
-        &CargoConfig { load_out_dirs_from_check, ..Default::default() },
-        true,
-    )?;
+    let ws = ProjectWorkspace::load(root, cargo_config, true, None)?;
 
     let mut extern_dirs = FxHashSet::default();
     extern_dirs.extend(ws.out_dirs());
@@ -78,13 +74,15 @@ pub(crate) fn load_cargo(
         let path = std::env::current_exe()?;
         ProcMacroClient::extern_process(path, &["proc-macro"]).unwrap()
     };
-    let host = load(&source_roots, ws, &mut vfs, receiver, extern_dirs, &proc_macro_client);
+    let host =
+        load(&source_roots, ws, cargo_config, &mut vfs, receiver, extern_dirs, &proc_macro_client);
     Ok((host, source_roots))
 }
 
 pub(crate) fn load(
     source_roots: &FxHashMap<SourceRootId, PackageRoot>,
     ws: ProjectWorkspace,
+    cargo_config: &CargoConfig,
     vfs: &mut Vfs,
     receiver: Receiver<VfsTask>,
     extern_dirs: FxHashSet<PathBuf>,
@@ -149,7 +147,7 @@ pub(crate) fn load(
 
     // FIXME: cfg options?
     let default_cfg_options = {
-        let mut opts = get_rustc_cfg_options(None);
+        let mut opts = get_rustc_cfg_options(cargo_config.target.as_ref(), &cargo_config.extra_env);
         opts.insert_atom("test".into());
         opts.insert_atom("debug_assertion".into());
         opts
@@ -183,7 +181,7 @@ mod tests {
     #[test]
     fn test_loading_rust_analyzer() {
         let path = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().parent().unwrap();
-        let (host, _roots) = load_cargo(path, false, false).unwrap();
+        let (host, _roots) = load_cargo(path, &CargoConfig::default(), false).unwrap();
         let n_crates = Crate::all(host.raw_database()).len();
         // RA has quite a few crates, but the exact count doesn't matter
         assert!(n_crates > 20);
@@ -0,0 +1,268 @@
+//! Dumps an [LSIF] index of a Cargo project to stdout, so that go to
+//! definition, find references and hover work in tools that browse code
+//! without running a language server.
+//!
+//! Only the files of workspace members are indexed. References to items
+//! defined in dependencies are skipped.
+//!
+//! [LSIF]: https://github.com/microsoft/language-server-protocol/blob/master/indexFormat/specification.md
+
+use std::path::Path;
+
+use lsp_types::Url;
+use ra_db::{FileId, SourceDatabaseExt};
+use ra_ide::{Analysis, FilePosition, NavigationTarget};
+use ra_project_model::CargoConfig;
+use ra_syntax::{AstNode, SyntaxKind::IDENT, TextRange};
+use rustc_hash::FxHashMap;
+use serde_json::{json, Value};
+
+use crate::{
+    cli::{load_cargo::load_cargo, Result},
+    conv::ConvWith,
+    markdown::format_docs,
+};
+
+pub fn lsif(path: &Path, cargo_config: &CargoConfig, with_proc_macro: bool) -> Result<()> {
+    let (host, roots) = load_cargo(path, cargo_config, with_proc_macro)?;
+    let db = host.raw_database();
+    let analysis = host.analysis();
+
+    let mut files = Vec::new();
+    for (source_root_id, package_root) in roots {
+        if !package_root.is_member() {
+            continue;
+        }
+        for file_id in db.source_root(source_root_id).walk() {
+            let path = db.file_relative_path(file_id).to_path(package_root.path());
+            files.push((file_id, path));
+        }
+    }
+    files.sort();
+
+    let project_root = std::env::current_dir()?.join(path);
+    let mut emitter = Emitter::new(&analysis);
+    emitter.emit_vertex(json!({
+        "label": "metaData",
+        "version": "0.4.3",
+        "projectRoot": url(&project_root)?,
+        "positionEncoding": "utf-16",
+        "toolInfo": { "name": "rust-analyzer" },
+    }));
+    for (file_id, path) in &files {
+        emitter.add_document(*file_id, path)?;
+    }
+    for (file_id, _) in &files {
+        emitter.index_file(*file_id);
+    }
+    emitter.finish();
+    Ok(())
+}
+
+struct Emitter<'a> {
+    analysis: &'a Analysis,
+    next_id: u64,
+    documents: FxHashMap<FileId, Document>,
+    definitions: Vec<Definition>,
+    definition_ids: FxHashMap<(FileId, TextRange), usize>,
+}
+
+struct Document {
+    id: u64,
+    ranges: Vec<u64>,
+}
+
+struct Definition {
+    file_id: FileId,
+    range: u64,
+    result_set: u64,
+    references: Vec<(FileId, u64)>,
+}
+
+impl<'a> Emitter<'a> {
+    fn new(analysis: &'a Analysis) -> Emitter<'a> {
+        Emitter {
+            analysis,
+            next_id: 0,
+            documents: FxHashMap::default(),
+            definitions: Vec::new(),
+            definition_ids: FxHashMap::default(),
+        }
+    }
+
+    fn add_document(&mut self, file_id: FileId, path: &Path) -> Result<()> {
+        let id = self.emit_vertex(json!({
+            "label": "document",
+            "uri": url(path)?,
+            "languageId": "rust",
+        }));
+        self.documents.insert(file_id, Document { id, ranges: Vec::new() });
+        Ok(())
+    }
+
+    fn index_file(&mut self, file_id: FileId) {
+        let file = self.analysis.parse(file_id).unwrap();
+        let idents = file
+            .syntax()
+            .descendants_with_tokens()
+            .filter_map(|it| it.into_token())
+            .filter(|it| it.kind() == IDENT);
+        for token in idents {
+            let position = FilePosition { file_id, offset: token.text_range().start() };
+            let navs = self.analysis.goto_definition(position).unwrap();
+            let nav = match navs.and_then(|it| it.info.into_iter().next()) {
+                Some(it) => it,
+                None => continue,
+            };
+            let def = match self.definition(&nav) {
+                Some(it) => it,
+                None => continue,
+            };
+            if nav.file_id() == file_id && nav.range() == token.text_range() {
+                continue;
+            }
+            let range = self.add_range(file_id, token.text_range());
+            let result_set = self.definitions[def].result_set;
+            self.emit_edge("next", range, result_set);
+            self.definitions[def].references.push((file_id, range));
+        }
+    }
+
+    /// Returns the index of the definition `nav` points to, emitting its result
+    /// set when it's first seen.
+    fn definition(&mut self, nav: &NavigationTarget) -> Option<usize> {
+        let key = (nav.file_id(), nav.range());
+        if let Some(&idx) = self.definition_ids.get(&key) {
+            return Some(idx);
+        }
+        if !self.documents.contains_key(&nav.file_id()) {
+            return None;
+        }
+
+        let result_set = self.emit_vertex(json!({ "label": "resultSet" }));
+        let range = self.add_range(nav.file_id(), nav.range());
+        self.emit_edge("next", range, result_set);
+
+        let definition_result = self.emit_vertex(json!({ "label": "definitionResult" }));
+        self.emit_edge("textDocument/definition", result_set, definition_result);
+        let document = self.documents[&nav.file_id()].id;
+        self.emit_vertex_or_edge(json!({
+            "type": "edge",
+            "label": "item",
+            "outV": definition_result,
+            "inVs": [range],
+            "document": document,
+        }));
+
+        let position = FilePosition { file_id: nav.file_id(), offset: nav.range().start() };
+        if let Some(hover) = self.analysis.hover(position).unwrap() {
+            let hover_result = self.emit_vertex(json!({
+                "label": "hoverResult",
+                "result": {
+                    "contents": {
+                        "kind": "markdown",
+                        "value": format_docs(&hover.info.to_markup()),
+                    },
+                },
+            }));
+            self.emit_edge("textDocument/hover", result_set, hover_result);
+        }
+
+        let idx = self.definitions.len();
+        self.definitions.push(Definition {
+            file_id: nav.file_id(),
+            range,
+            result_set,
+            references: Vec::new(),
+        });
+        self.definition_ids.insert(key, idx);
+        Some(idx)
+    }
+
+    fn add_range(&mut self, file_id: FileId, range: TextRange) -> u64 {
+        let line_index = self.analysis.file_line_index(file_id).unwrap();
+        let range = range.conv_with(&line_index);
+        let id = self.emit_vertex(json!({
+            "label": "range",
+            "start": range.start,
+            "end": range.end,
+        }));
+        self.documents.get_mut(&file_id).unwrap().ranges.push(id);
+        id
+    }
+
+    /// Emits the reference results, which are only complete once all files are
+    /// indexed, and the ranges contained in each document.
+    fn finish(mut self) {
+        for def in std::mem::take(&mut self.definitions) {
+            let reference_result = self.emit_vertex(json!({ "label": "referenceResult" }));
+            self.emit_edge("textDocument/references", def.result_set, reference_result);
+            let document = self.documents[&def.file_id].id;
+            self.emit_vertex_or_edge(json!({
+                "type": "edge",
+                "label": "item",
+                "outV": reference_result,
+                "inVs": [def.range],
+                "document": document,
+                "property": "definitions",
+            }));
+
+            let mut references_by_file: FxHashMap<FileId, Vec<u64>> = FxHashMap::default();
+            for (file_id, range) in def.references {
+                references_by_file.entry(file_id).or_default().push(range);
+            }
+            let mut references_by_file = references_by_file.into_iter().collect::<Vec<_>>();
+            references_by_file.sort();
+            for (file_id, ranges) in references_by_file {
+                let document = self.documents[&file_id].id;
+                self.emit_vertex_or_edge(json!({
+                    "type": "edge",
+                    "label": "item",
+                    "outV": reference_result,
+                    "inVs": ranges,
+                    "document": document,
+                    "property": "references",
+                }));
+            }
+        }
+
+        let mut documents = std::mem::take(&mut self.documents).into_iter().collect::<Vec<_>>();
+        documents.sort_by_key(|(_, doc)| doc.id);
+        for (_, doc) in documents {
+            if doc.ranges.is_empty() {
+                continue;
+            }
+            self.emit_vertex_or_edge(json!({
+                "type": "edge",
+                "label": "contains",
+                "outV": doc.id,
+                "inVs": doc.ranges,
+            }));
+        }
+    }
+
+    fn emit_vertex(&mut self, mut vertex: Value) -> u64 {
+        vertex["type"] = "vertex".into();
+        self.emit_vertex_or_edge(vertex)
+    }
+
+    fn emit_edge(&mut self, label: &str, out_v: u64, in_v: u64) -> u64 {
+        self.emit_vertex_or_edge(json!({
+            "type": "edge",
+            "label": label,
+            "outV": out_v,
+            "inV": in_v,
+        }))
+    }
+
+    fn emit_vertex_or_edge(&mut self, mut element: Value) -> u64 {
+        self.next_id += 1;
+        element["id"] = self.next_id.into();
+        println!("{}", element);
+        self.next_id
+    }
+}
+
+fn url(path: &Path) -> Result<Url> {
+    Url::from_file_path(path).map_err(|()| anyhow::anyhow!("invalid path: {}", path.display()))
+}
@@ -9,10 +9,14 @@
 
 use std::{ffi::OsString, path::PathBuf};
 
-use lsp_types::TextDocumentClientCapabilities;
+use lsp_types::ClientCapabilities;
 use ra_flycheck::FlycheckConfig;
-use ra_ide::{CompletionConfig, InlayHintsConfig};
-use ra_project_model::CargoConfig;
+use ra_ide::{
+    AssistConfig, CompletionConfig, DiagnosticsConfig, InlayHintsConfig, MergeBehaviour, Snippet,
+    SnippetScope, TraitSolverLimits,
+};
+use ra_project_model::{CargoConfig, CrateOverride};
+use rustc_hash::FxHashMap;
 use serde::Deserialize;
 
 #[derive(Debug, Clone)]
@@ -21,7 +25,12 @@ pub struct Config {
 
     pub with_sysroot: bool,
     pub publish_diagnostics: bool,
+    pub diagnostics: DiagnosticsConfig,
     pub lru_capacity: Option<usize>,
+    pub trait_solver: TraitSolverLimits,
+    /// Where to cache library indices and `cargo metadata` output between
+    /// runs, if anywhere. Off unless enabled by the client.
+    pub cache_dir: Option<PathBuf>,
     pub proc_macro_srv: Option<(PathBuf, Vec<OsString>)>,
     pub files: FilesConfig,
     pub notifications: NotificationsConfig,
@@ -32,7 +41,12 @@ pub struct Config {
 
     pub inlay_hints: InlayHintsConfig,
     pub completion: CompletionConfig,
+    pub assist: AssistConfig,
     pub call_info_full: bool,
+    pub hover: HoverConfig,
+    pub lens: LensConfig,
+    /// Whether workspace symbol search also looks into dependencies.
+    pub workspace_symbol_search_libs: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -64,12 +78,36 @@ pub enum RustfmtConfig {
     },
 }
 
+/// Which actions are shown below hovers.
+#[derive(Debug, Clone)]
+pub struct HoverConfig {
+    pub implementations: bool,
+    /// Counting the references is expensive, so this is off by default.
+    pub references: bool,
+    pub run: bool,
+    pub debug: bool,
+}
+
+/// Which code lenses are shown.
+#[derive(Debug, Clone)]
+pub struct LensConfig {
+    /// A lens on every method of every trait and trait impl is noisy, so this
+    /// is off by default.
+    pub trait_methods: bool,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct ClientCapsConfig {
     pub location_link: bool,
     pub line_folding_only: bool,
     pub hierarchical_symbols: bool,
     pub code_action_literals: bool,
+    /// Whether the client can apply edits with snippet tabstops.
+    pub snippet_text_edit: bool,
+    /// Whether the client resolves the edits of code actions on demand.
+    pub resolve_code_action: bool,
+    /// Whether the client renders the actions of `HoverRequest`.
+    pub hover_actions: bool,
 }
 
 impl Default for Config {
@@ -79,7 +117,10 @@ impl Default for Config {
 
             with_sysroot: true,
             publish_diagnostics: true,
+            diagnostics: DiagnosticsConfig::default(),
             lru_capacity: None,
+            trait_solver: TraitSolverLimits::default(),
+            cache_dir: None,
             proc_macro_srv: None,
             files: FilesConfig { watcher: FilesWatcher::Notify, exclude: Vec::new() },
             notifications: NotificationsConfig { cargo_toml_not_found: true },
@@ -90,6 +131,7 @@ impl Default for Config {
                 command: "check".to_string(),
                 all_targets: true,
                 extra_args: Vec::new(),
+                extra_env: FxHashMap::default(),
             }),
 
             inlay_hints: InlayHintsConfig {
@@ -100,27 +142,51 @@ impl Default for Config {
             },
             completion: CompletionConfig {
                 enable_postfix_completions: true,
+                enable_autoimport_completions: true,
                 add_call_parenthesis: true,
                 add_call_argument_snippets: true,
                 ..CompletionConfig::default()
             },
+            assist: AssistConfig::default(),
             call_info_full: true,
+            hover: HoverConfig { implementations: true, references: false, run: true, debug: true },
+            lens: LensConfig { trait_methods: false },
+            workspace_symbol_search_libs: false,
         }
     }
 }
 
 impl Config {
+    /// Replaces the settings with the ones in `value`, returning descriptions
+    /// of the invalid settings that were ignored.
     #[rustfmt::skip]
-    pub fn update(&mut self, value: &serde_json::Value) {
+    pub fn update(&mut self, value: &serde_json::Value) -> Vec<String> {
         log::info!("Config::update({:#})", value);
 
+        let mut errors = Vec::new();
+
         let client_caps = self.client_caps.clone();
         *self = Default::default();
         self.client_caps = client_caps;
+        self.assist.allow_snippets(self.client_caps.snippet_text_edit);
 
         set(value, "/withSysroot", &mut self.with_sysroot);
         set(value, "/diagnostics/enable", &mut self.publish_diagnostics);
+        set(value, "/diagnostics/disabled", &mut self.diagnostics.disabled);
+        if let Some(overrides) = value.pointer("/diagnostics/severityOverrides").and_then(|it| it.as_object()) {
+            for (code, severity) in overrides {
+                if let Some(severity) = severity.as_str().and_then(|it| it.parse().ok()) {
+                    self.diagnostics.severity_overrides.insert(code.clone(), severity);
+                }
+            }
+        }
         set(value, "/lruCapacity", &mut self.lru_capacity);
+        set(value, "/traitSolver/recursionLimit", &mut self.trait_solver.overflow_depth);
+        set(value, "/traitSolver/fuel", &mut self.trait_solver.fuel);
+        self.cache_dir = match get(value, "/cache/enable") {
+            Some(true) => get::<PathBuf>(value, "/cache/directory").or_else(default_cache_dir),
+            _ => None,
+        };
         self.files.watcher = match get(value, "/files/watcher") {
             Some("client") => FilesWatcher::Client,
             Some("notify") | _ => FilesWatcher::Notify
@@ -132,6 +198,23 @@ impl Config {
         set(value, "/cargo/features", &mut self.cargo.features);
         set(value, "/cargo/loadOutDirsFromCheck", &mut self.cargo.load_out_dirs_from_check);
         set(value, "/cargo/target", &mut self.cargo.target);
+        set(value, "/cargo/extraEnv", &mut self.cargo.extra_env);
+        if let Some(overrides) = value.pointer("/cargo/crateOverrides").and_then(|it| it.as_object()) {
+            for (name, value) in overrides {
+                let mut crate_override = CrateOverride::default();
+                set(value, "/cfgs", &mut crate_override.cfgs);
+                if let Some(edition) = get::<String>(value, "/edition") {
+                    match edition.parse() {
+                        Ok(edition) => crate_override.edition = Some(edition),
+                        Err(err) => errors.push(format!(
+                            "invalid edition for crate `{}` in `cargo.crateOverrides`: {}",
+                            name, err
+                        )),
+                    }
+                }
+                self.cargo.crate_overrides.insert(name.clone(), crate_override);
+            }
+        }
 
         match get(value, "/procMacro/enable") {
             Some(true) => {
@@ -169,13 +252,15 @@ impl Config {
                     self.check = Some(FlycheckConfig::CustomCommand {
                         command,
                         args,
+                        extra_env: self.cargo.extra_env.clone(),
                     });
                 }
                 // otherwise configure command customizations
                 _ => {
-                    if let Some(FlycheckConfig::CargoCommand { command, extra_args, all_targets })
+                    if let Some(FlycheckConfig::CargoCommand { command, extra_args, all_targets, extra_env })
                         = &mut self.check
                     {
+                        *extra_env = self.cargo.extra_env.clone();
                         set(value, "/checkOnSave/extraArgs", extra_args);
                         set(value, "/checkOnSave/command", command);
                         set(value, "/checkOnSave/allTargets", all_targets);
@@ -189,10 +274,43 @@ impl Config {
         set(value, "/inlayHints/chainingHints", &mut self.inlay_hints.chaining_hints);
         set(value, "/inlayHints/maxLength", &mut self.inlay_hints.max_length);
         set(value, "/completion/postfix/enable", &mut self.completion.enable_postfix_completions);
+        set(value, "/completion/autoimport/enable", &mut self.completion.enable_autoimport_completions);
         set(value, "/completion/addCallParenthesis", &mut self.completion.add_call_parenthesis);
         set(value, "/completion/addCallArgumentSnippets", &mut self.completion.add_call_argument_snippets);
+        if let Some(snippets) = get::<Vec<FxHashMap<String, String>>>(value, "/completion/snippets") {
+            self.completion.snippets = snippets
+                .into_iter()
+                .filter_map(|mut snippet| {
+                    let scope = match snippet.get("scope").map(String::as_str) {
+                        Some("item") => SnippetScope::Item,
+                        Some("expr") | None => SnippetScope::Expr,
+                        Some(_) => return None,
+                    };
+                    Some(Snippet {
+                        trigger: snippet.remove("trigger")?,
+                        body: snippet.remove("body")?,
+                        scope,
+                    })
+                })
+                .collect();
+        }
+        self.assist.insert_use.merge = match get(value, "/assist/importMergeBehaviour") {
+            Some("none") => None,
+            Some("last") => Some(MergeBehaviour::Last),
+            Some("full") | _ => Some(MergeBehaviour::Full),
+        };
+        set(value, "/assist/importGroup", &mut self.assist.insert_use.group);
         set(value, "/callInfo/full", &mut self.call_info_full);
 
+        set(value, "/hoverActions/implementations", &mut self.hover.implementations);
+        set(value, "/hoverActions/references", &mut self.hover.references);
+        set(value, "/hoverActions/run", &mut self.hover.run);
+        set(value, "/hoverActions/debug", &mut self.hover.debug);
+
+        set(value, "/lens/traitMethods", &mut self.lens.trait_methods);
+
+        set(value, "/workspaceSymbol/searchLibraries", &mut self.workspace_symbol_search_libs);
+
         log::info!("Config::update() = {:#?}", self);
 
         fn get<'a, T: Deserialize<'a>>(value: &'a serde_json::Value, pointer: &str) -> Option<T> {
@@ -204,9 +322,28 @@ impl Config {
                 *slot = new_value
             }
         }
+
+        errors
+    }
+
+    pub fn update_caps(&mut self, caps: &ClientCapabilities) {
+        if let Some(caps) = &caps.text_document {
+            self.update_text_document_caps(caps);
+        }
+        let experimental = |name: &str| {
+            caps.experimental
+                .as_ref()
+                .and_then(|it| it.get(name))
+                .and_then(|it| it.as_bool())
+                .unwrap_or(false)
+        };
+        self.client_caps.snippet_text_edit = experimental("snippetTextEdit");
+        self.client_caps.resolve_code_action = experimental("resolveCodeAction");
+        self.client_caps.hover_actions = experimental("hoverActions");
+        self.assist.allow_snippets(self.client_caps.snippet_text_edit);
     }
 
-    pub fn update_caps(&mut self, caps: &TextDocumentClientCapabilities) {
+    fn update_text_document_caps(&mut self, caps: &lsp_types::TextDocumentClientCapabilities) {
         if let Some(value) = caps.definition.as_ref().and_then(|it| it.link_support) {
             self.client_caps.location_link = value;
         }
@@ -233,3 +370,107 @@ impl Config {
         }
     }
 }
+
+/// The default location of the on-disk cache, following the platform's
+/// conventions for per-user cache directories.
+fn default_cache_dir() -> Option<PathBuf> {
+    let env_dir = |var: &str| std::env::var_os(var).filter(|it| !it.is_empty()).map(PathBuf::from);
+    let base = if cfg!(windows) {
+        env_dir("LOCALAPPDATA")?
+    } else if cfg!(target_os = "macos") {
+        env_dir("HOME")?.join("Library").join("Caches")
+    } else {
+        match env_dir("XDG_CACHE_HOME") {
+            Some(it) => it,
+            None => env_dir("HOME")?.join(".cache"),
+        }
+    };
+    Some(base.join("rust-analyzer"))
+}
+
+#[cfg(test)]
+mod tests {
+    use ra_ide::Edition;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn extra_env_is_passed_to_cargo_and_check() {
+        let mut config = Config::default();
+        config.update(&json!({ "cargo": { "extraEnv": { "CARGO_TARGET_DIR": "/tmp/ra" } } }));
+        assert_eq!(
+            config.cargo.extra_env.get("CARGO_TARGET_DIR").map(String::as_str),
+            Some("/tmp/ra")
+        );
+        match &config.check {
+            Some(FlycheckConfig::CargoCommand { extra_env, .. }) => {
+                assert_eq!(extra_env, &config.cargo.extra_env)
+            }
+            check => panic!("unexpected check config: {:?}", check),
+        }
+    }
+
+    #[test]
+    fn extra_env_is_passed_to_custom_check_commands() {
+        let mut config = Config::default();
+        config.update(&json!({
+            "cargo": { "extraEnv": { "RUSTFLAGS": "--cfg foo" } },
+            "checkOnSave": { "overrideCommand": ["my-check", "--json"] },
+        }));
+        match &config.check {
+            Some(FlycheckConfig::CustomCommand { command, extra_env, .. }) => {
+                assert_eq!(command, "my-check");
+                assert_eq!(extra_env.get("RUSTFLAGS").map(String::as_str), Some("--cfg foo"));
+            }
+            check => panic!("unexpected check config: {:?}", check),
+        }
+    }
+
+    #[test]
+    fn extra_env_is_reset_by_updates() {
+        let mut config = Config::default();
+        config.update(&json!({ "cargo": { "extraEnv": { "RUSTFLAGS": "--cfg foo" } } }));
+        config.update(&json!({}));
+        assert!(config.cargo.extra_env.is_empty());
+    }
+
+    #[test]
+    fn crate_overrides_are_replaced_by_updates() {
+        let mut config = Config::default();
+        config.update(&json!({
+            "cargo": { "crateOverrides": {
+                "foo": { "edition": "2015", "cfgs": ["feature=\"bar\""] },
+                "baz": { "edition": "2015" },
+            } }
+        }));
+        assert_eq!(config.cargo.crate_overrides.len(), 2);
+        assert_eq!(config.cargo.crate_overrides["foo"].edition, Some(Edition::Edition2015));
+        assert_eq!(config.cargo.crate_overrides["foo"].cfgs, vec!["feature=\"bar\"".to_string()]);
+
+        config.update(&json!({ "cargo": { "crateOverrides": { "foo": {} } } }));
+        assert_eq!(config.cargo.crate_overrides.len(), 1);
+        assert_eq!(config.cargo.crate_overrides["foo"].edition, None);
+    }
+
+    #[test]
+    fn invalid_crate_override_edition_is_reported() {
+        let mut config = Config::default();
+        let errors = config.update(&json!({
+            "cargo": { "crateOverrides": { "foo": { "edition": "2021" } } }
+        }));
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("`foo`"), "{}", errors[0]);
+        assert_eq!(config.cargo.crate_overrides["foo"].edition, None);
+    }
+
+    #[test]
+    fn cache_is_opt_in() {
+        let mut config = Config::default();
+        config.update(&json!({ "cache": { "directory": "/tmp/ra-cache" } }));
+        assert_eq!(config.cache_dir, None);
+
+        config.update(&json!({ "cache": { "enable": true, "directory": "/tmp/ra-cache" } }));
+        assert_eq!(config.cache_dir, Some(PathBuf::from("/tmp/ra-cache")));
+    }
+}
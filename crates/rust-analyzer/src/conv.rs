@@ -2,17 +2,18 @@
 //! and LSP types.
 
 use lsp_types::{
-    self, CreateFile, DiagnosticSeverity, DocumentChangeOperation, DocumentChanges, Documentation,
-    Location, LocationLink, MarkupContent, MarkupKind, ParameterInformation, ParameterLabel,
-    Position, Range, RenameFile, ResourceOp, SemanticTokenModifier, SemanticTokenType,
-    SignatureInformation, SymbolKind, TextDocumentEdit, TextDocumentIdentifier, TextDocumentItem,
-    TextDocumentPositionParams, Url, VersionedTextDocumentIdentifier, WorkspaceEdit,
+    self, code_action_kind, CreateFile, DiagnosticSeverity, DocumentChangeOperation,
+    DocumentChanges, Documentation, Location, LocationLink, MarkupContent, MarkupKind,
+    ParameterInformation, ParameterLabel, Position, Range, RenameFile, ResourceOp,
+    SemanticTokenModifier, SemanticTokenType, SignatureInformation, SymbolKind, TextDocumentEdit,
+    TextDocumentIdentifier, TextDocumentItem, TextDocumentPositionParams, Url,
+    VersionedTextDocumentIdentifier, WorkspaceEdit,
 };
 use ra_ide::{
-    translate_offset_with_edit, CompletionItem, CompletionItemKind, FileId, FilePosition,
-    FileRange, FileSystemEdit, Fold, FoldKind, Highlight, HighlightModifier, HighlightTag,
-    InlayHint, InlayKind, InsertTextFormat, LineCol, LineIndex, NavigationTarget, RangeInfo,
-    ReferenceAccess, Severity, SourceChange, SourceFileEdit,
+    translate_offset_with_edit, AssistKind, CompletionItem, CompletionItemKind, FileId,
+    FilePosition, FileRange, FileSystemEdit, Fold, FoldKind, Highlight, HighlightModifier,
+    HighlightTag, InlayHint, InlayKind, InsertTextFormat, LineCol, LineIndex, NavigationTarget,
+    RangeInfo, ReferenceAccess, Severity, SourceChange, SourceFileEdit,
 };
 use ra_syntax::{SyntaxKind, TextRange, TextSize};
 use ra_text_edit::{AtomTextEdit, TextEdit};
@@ -50,7 +51,7 @@ impl Conv for SyntaxKind {
     fn conv(self) -> <Self as Conv>::Output {
         match self {
             SyntaxKind::FN_DEF => SymbolKind::Function,
-            SyntaxKind::STRUCT_DEF => SymbolKind::Struct,
+            SyntaxKind::STRUCT_DEF | SyntaxKind::UNION_DEF => SymbolKind::Struct,
             SyntaxKind::ENUM_DEF => SymbolKind::Enum,
             SyntaxKind::ENUM_VARIANT => SymbolKind::EnumMember,
             SyntaxKind::TRAIT_DEF => SymbolKind::Interface,
@@ -111,11 +112,29 @@ impl Conv for Severity {
     fn conv(self) -> DiagnosticSeverity {
         match self {
             Severity::Error => DiagnosticSeverity::Error,
-            Severity::WeakWarning => DiagnosticSeverity::Hint,
+            Severity::WeakWarning => DiagnosticSeverity::Warning,
+            Severity::Hint => DiagnosticSeverity::Hint,
         }
     }
 }
 
+impl Conv for AssistKind {
+    type Output = String;
+    fn conv(self) -> String {
+        let kind = match self {
+            // LSP has no kind for code generation, so it gets the empty kind
+            // which matches any request.
+            AssistKind::None | AssistKind::Generate => code_action_kind::EMPTY,
+            AssistKind::QuickFix => code_action_kind::QUICKFIX,
+            AssistKind::Refactor => code_action_kind::REFACTOR,
+            AssistKind::RefactorExtract => code_action_kind::REFACTOR_EXTRACT,
+            AssistKind::RefactorInline => code_action_kind::REFACTOR_INLINE,
+            AssistKind::RefactorRewrite => code_action_kind::REFACTOR_REWRITE,
+        };
+        kind.to_string()
+    }
+}
+
 impl ConvWith<(&LineIndex, LineEndings)> for CompletionItem {
     type Output = ::lsp_types::CompletionItem;
 
@@ -171,6 +190,19 @@ impl ConvWith<(&LineIndex, LineEndings)> for CompletionItem {
             res.preselect = Some(true)
         }
 
+        // Clients sort by `sort_text`, falling back to the label: the items
+        // matching the expected type come first, then the best fuzzy matches,
+        // and the deprecated items last.
+        if self.score().is_some() || self.match_score().is_some() || self.deprecated() {
+            let type_rank = match (self.deprecated(), self.score()) {
+                (true, _) => 2,
+                (false, Some(_)) => 0,
+                (false, None) => 1,
+            };
+            let match_rank = u32::MAX - self.match_score().unwrap_or(0);
+            res.sort_text = Some(format!("{}{:010}{}", type_rank, match_rank, self.label()));
+        }
+
         if self.deprecated() {
             res.tags = Some(vec![lsp_types::CompletionItemTag::Deprecated])
         }
@@ -498,8 +530,27 @@ impl TryConvWith<&WorldSnapshot> for SourceChange {
             }
         };
         let mut document_changes: Vec<DocumentChangeOperation> = Vec::new();
-        for resource_op in self.file_system_edits.try_conv_with(world)? {
-            document_changes.push(DocumentChangeOperation::Op(resource_op));
+        for file_system_edit in self.file_system_edits {
+            // Clients create files empty, so the contents are inserted with a
+            // separate edit right after the file is created.
+            let initial_contents = match &file_system_edit {
+                FileSystemEdit::CreateFile { source_root, path, contents }
+                    if !contents.is_empty() =>
+                {
+                    let text_document = VersionedTextDocumentIdentifier {
+                        uri: world.path_to_uri(*source_root, path)?,
+                        version: None,
+                    };
+                    let edit = lsp_types::TextEdit::new(Range::default(), contents.clone());
+                    Some(TextDocumentEdit { text_document, edits: vec![edit] })
+                }
+                _ => None,
+            };
+            document_changes
+                .push(DocumentChangeOperation::Op(file_system_edit.try_conv_with(world)?));
+            if let Some(edit) = initial_contents {
+                document_changes.push(DocumentChangeOperation::Edit(edit));
+            }
         }
         for text_document_edit in self.source_file_edits.try_conv_with(world)? {
             document_changes.push(DocumentChangeOperation::Edit(text_document_edit));
@@ -508,7 +559,12 @@ impl TryConvWith<&WorldSnapshot> for SourceChange {
             changes: None,
             document_changes: Some(DocumentChanges::Operations(document_changes)),
         };
-        Ok(req::SourceChange { label: self.label, workspace_edit, cursor_position })
+        Ok(req::SourceChange {
+            label: self.label,
+            workspace_edit,
+            cursor_position,
+            is_snippet: self.is_snippet,
+        })
     }
 }
 
@@ -531,7 +587,7 @@ impl TryConvWith<&WorldSnapshot> for FileSystemEdit {
     type Output = ResourceOp;
     fn try_conv_with(self, world: &WorldSnapshot) -> Result<ResourceOp> {
         let res = match self {
-            FileSystemEdit::CreateFile { source_root, path } => {
+            FileSystemEdit::CreateFile { source_root, path, .. } => {
                 let uri = world.path_to_uri(source_root, &path)?;
                 ResourceOp::Create(CreateFile { uri, options: None })
             }
@@ -619,6 +675,21 @@ pub fn to_call_hierarchy_item(
     })
 }
 
+pub fn to_type_hierarchy_item(
+    world: &WorldSnapshot,
+    nav: NavigationTarget,
+) -> Result<req::TypeHierarchyItem> {
+    let line_index = world.analysis().file_line_index(nav.file_id())?;
+    Ok(req::TypeHierarchyItem {
+        name: nav.name().to_string(),
+        kind: nav.kind().conv(),
+        detail: nav.description().map(|it| it.to_string()),
+        uri: nav.file_id().try_conv_with(world)?,
+        range: nav.full_range().conv_with(&line_index),
+        selection_range: nav.range().conv_with(&line_index),
+    })
+}
+
 pub fn to_location(
     file_id: FileId,
     range: TextRange,
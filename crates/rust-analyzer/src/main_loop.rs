@@ -26,7 +26,7 @@ use lsp_types::{
     WorkDoneProgressReport,
 };
 use ra_flycheck::{url_from_path_with_drive_lowercasing, CheckTask};
-use ra_ide::{Canceled, FileId, LibraryData, LineIndex, SourceRootId};
+use ra_ide::{Canceled, FileId, LibraryData, LineIndex, PrimeCachesProgress, SourceRootId};
 use ra_prof::profile;
 use ra_project_model::{PackageRoot, ProjectWorkspace};
 use ra_vfs::{VfsFile, VfsTask, Watch};
@@ -122,6 +122,7 @@ pub fn main_loop(ws_roots: Vec<PathBuf>, config: Config, connection: Connection)
                         root,
                         &config.cargo,
                         config.with_sysroot,
+                        config.cache_dir.as_deref(),
                     )
                     .map_err(|err| {
                         log::error!("failed to load workspace: {:#}", err);
@@ -382,7 +383,13 @@ fn loop_turn(
                         (None, Some(configs)) => {
                             if let Some(new_config) = configs.get(0) {
                                 let mut config = world_state.config.clone();
-                                config.update(&new_config);
+                                for error in config.update(&new_config) {
+                                    show_message(
+                                        req::MessageType::Error,
+                                        error,
+                                        &connection.sender,
+                                    );
+                                }
                                 world_state.update_configuration(config);
                             }
                         }
@@ -408,9 +415,10 @@ fn loop_turn(
         let (root, files) = loop_state.pending_libraries.pop().unwrap();
         loop_state.in_flight_libraries += 1;
         let sender = libdata_sender.clone();
+        let cache_dir = world_state.config.cache_dir.clone();
         pool.execute(move || {
             log::info!("indexing {:?} ... ", root);
-            let data = LibraryData::prepare(root, files);
+            let data = LibraryData::prepare(root, files, cache_dir.as_deref());
             sender.send(data).unwrap();
         });
     }
@@ -440,10 +448,22 @@ fn loop_turn(
             task_sender.clone(),
             loop_state.subscriptions.subscriptions(),
         );
+        // Only the first priming, right after the workspace is loaded, is slow
+        // enough to be worth reporting.
+        if show_progress {
+            let work_done_progress_create = request_new::<req::WorkDoneProgressCreate>(
+                loop_state.next_request_id(),
+                WorkDoneProgressCreateParams {
+                    token: req::ProgressToken::String("rustAnalyzer/primeCaches".into()),
+                },
+            );
+            connection.sender.send(work_done_progress_create.into()).unwrap();
+        }
         pool.execute({
             let subs = loop_state.subscriptions.subscriptions();
             let snap = world_state.snapshot();
-            move || snap.analysis().prime_caches(subs).unwrap_or_else(|_: Canceled| ())
+            let task_sender = task_sender.clone();
+            move || prime_caches(snap, subs, &task_sender, show_progress)
         });
     }
 
@@ -524,6 +544,7 @@ fn on_request(
         .on::<req::Runnables>(handlers::handle_runnables)?
         .on::<req::Completion>(handlers::handle_completion)?
         .on::<req::CodeActionRequest>(handlers::handle_code_action)?
+        .on::<req::ResolveCodeAction>(handlers::handle_resolve_code_action)?
         .on::<req::CodeLensRequest>(handlers::handle_code_lens)?
         .on::<req::CodeLensResolve>(handlers::handle_code_lens_resolve)?
         .on::<req::FoldingRangeRequest>(handlers::handle_folding_range)?
@@ -538,9 +559,13 @@ fn on_request(
         .on::<req::CallHierarchyPrepare>(handlers::handle_call_hierarchy_prepare)?
         .on::<req::CallHierarchyIncomingCalls>(handlers::handle_call_hierarchy_incoming)?
         .on::<req::CallHierarchyOutgoingCalls>(handlers::handle_call_hierarchy_outgoing)?
+        .on::<req::TypeHierarchyPrepare>(handlers::handle_type_hierarchy_prepare)?
+        .on::<req::TypeHierarchySupertypes>(handlers::handle_type_hierarchy_supertypes)?
+        .on::<req::TypeHierarchySubtypes>(handlers::handle_type_hierarchy_subtypes)?
         .on::<req::SemanticTokensRequest>(handlers::handle_semantic_tokens)?
         .on::<req::SemanticTokensRangeRequest>(handlers::handle_semantic_tokens_range)?
         .on::<req::Ssr>(handlers::handle_ssr)?
+        .on::<req::ChangeSignature>(handlers::handle_change_signature)?
         .finish();
     Ok(())
 }
@@ -827,6 +852,46 @@ fn send_startup_progress(sender: &Sender<Message>, loop_state: &mut LoopState) {
     }
 }
 
+fn prime_caches(
+    world: WorldSnapshot,
+    files: Vec<FileId>,
+    task_sender: &Sender<Task>,
+    show_progress: bool,
+) {
+    let send_progress = |work_done_progress: WorkDoneProgress| {
+        if !show_progress {
+            return;
+        }
+        let notif = notification_new::<req::Progress>(req::ProgressParams {
+            token: req::ProgressToken::String("rustAnalyzer/primeCaches".into()),
+            value: req::ProgressParamsValue::WorkDone(work_done_progress),
+        });
+        task_sender.send(Task::Notify(notif)).unwrap();
+    };
+
+    send_progress(WorkDoneProgress::Begin(WorkDoneProgressBegin {
+        title: "rust-analyzer".into(),
+        cancellable: None,
+        message: Some("indexing".into()),
+        percentage: Some(0.0),
+    }));
+    let res = world.analysis().prime_caches(files, &|progress: PrimeCachesProgress| {
+        send_progress(WorkDoneProgress::Report(WorkDoneProgressReport {
+            cancellable: None,
+            message: Some(format!(
+                "indexing {}/{} ({})",
+                progress.n_done, progress.n_total, progress.on_crate
+            )),
+            percentage: Some(100.0 * progress.n_done as f64 / progress.n_total as f64),
+        }))
+    });
+    let message = match res {
+        Ok(()) => "indexing done",
+        Err(Canceled { .. }) => "indexing canceled",
+    };
+    send_progress(WorkDoneProgress::End(WorkDoneProgressEnd { message: Some(message.into()) }));
+}
+
 struct PoolDispatcher<'a> {
     req: Option<Request>,
     pool: &'a ThreadPool,
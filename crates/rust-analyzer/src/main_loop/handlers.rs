@@ -9,31 +9,36 @@ use std::{
 
 use lsp_server::ErrorCode;
 use lsp_types::{
-    CallHierarchyIncomingCall, CallHierarchyIncomingCallsParams, CallHierarchyItem,
-    CallHierarchyOutgoingCall, CallHierarchyOutgoingCallsParams, CallHierarchyPrepareParams,
-    CodeAction, CodeActionResponse, CodeLens, Command, CompletionItem, Diagnostic,
-    DocumentFormattingParams, DocumentHighlight, DocumentSymbol, FoldingRange, FoldingRangeParams,
-    Hover, HoverContents, Location, MarkupContent, MarkupKind, Position, PrepareRenameResponse,
-    Range, RenameParams, SemanticTokensParams, SemanticTokensRangeParams,
-    SemanticTokensRangeResult, SemanticTokensResult, SymbolInformation, TextDocumentIdentifier,
-    TextEdit, Url, WorkspaceEdit,
+    code_action_kind, CallHierarchyIncomingCall, CallHierarchyIncomingCallsParams,
+    CallHierarchyItem, CallHierarchyOutgoingCall, CallHierarchyOutgoingCallsParams,
+    CallHierarchyPrepareParams, CodeAction, CodeActionResponse, CodeLens, Command, CompletionItem,
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticTag, DocumentFormattingParams,
+    DocumentHighlight, DocumentSymbol, FoldingRange, FoldingRangeParams, Hover, HoverContents,
+    Location, MarkupContent, MarkupKind, NumberOrString, Position, PrepareRenameResponse, Range,
+    RenameParams, SemanticTokensParams, SemanticTokensRangeParams, SemanticTokensRangeResult,
+    SemanticTokensResult, SymbolInformation, TextDocumentIdentifier, TextEdit, Url, WorkspaceEdit,
 };
 use ra_ide::{
-    Assist, FileId, FilePosition, FileRange, Query, RangeInfo, Runnable, RunnableKind, SearchScope,
+    Assist, AssistId, FileId, FilePosition, FileRange, HoverAction, Query, RangeInfo, Runnable,
+    RunnableKind, SearchScope, SignatureParam,
 };
 use ra_prof::profile;
-use ra_syntax::{AstNode, SyntaxKind, TextRange, TextSize};
+use ra_syntax::{
+    ast::{self, NameOwner},
+    AstNode, SyntaxKind, TextRange, TextSize,
+};
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 use serde_json::to_value;
 use stdx::format_to;
 
 use crate::{
+    caps::SOURCE_FIX_ALL,
     cargo_target_spec::CargoTargetSpec,
     config::RustfmtConfig,
     conv::{
-        to_call_hierarchy_item, to_location, Conv, ConvWith, FoldConvCtx, MapConvWith, TryConvWith,
-        TryConvWithToVec,
+        to_call_hierarchy_item, to_location, to_type_hierarchy_item, Conv, ConvWith, FoldConvCtx,
+        MapConvWith, TryConvWith, TryConvWithToVec,
     },
     diagnostics::DiagnosticTask,
     from_json,
@@ -284,25 +289,14 @@ pub fn handle_workspace_symbol(
     params: req::WorkspaceSymbolParams,
 ) -> Result<Option<Vec<SymbolInformation>>> {
     let _p = profile("handle_workspace_symbol");
-    let all_symbols = params.query.contains('#');
-    let libs = params.query.contains('*');
-    let query = {
-        let query: String = params.query.chars().filter(|&c| c != '#' && c != '*').collect();
-        let mut q = Query::new(query);
-        if !all_symbols {
-            q.only_types();
-        }
-        if libs {
-            q.libs();
-        }
-        q.limit(128);
-        q
-    };
-    let mut res = exec_query(&world, query)?;
-    if res.is_empty() && !all_symbols {
-        let mut query = Query::new(params.query);
-        query.limit(128);
-        res = exec_query(&world, query)?;
+    const LIMIT: usize = 128;
+    let mut query = Query::parse(&params.query);
+    query.limit(LIMIT);
+    let mut res = exec_query(&world, query.clone())?;
+    if world.config.workspace_symbol_search_libs && res.len() < LIMIT {
+        query.libs();
+        query.limit(LIMIT - res.len());
+        res.extend(exec_query(&world, query)?);
     }
 
     return Ok(Some(res));
@@ -399,10 +393,10 @@ pub fn handle_runnables(
                 res.push(req::Runnable {
                     range: Default::default(),
                     label: format!("cargo {} -p {}", cmd, spec.package),
-                    bin: "cargo".to_string(),
+                    bin: runnable_bin(&world),
                     args: vec![cmd.to_string(), "--package".to_string(), spec.package.clone()],
                     extra_args: Vec::new(),
-                    env: FxHashMap::default(),
+                    env: world.config.cargo.extra_env.clone(),
                     cwd: workspace_root.map(|root| root.to_owned()),
                 })
             }
@@ -411,10 +405,10 @@ pub fn handle_runnables(
             res.push(req::Runnable {
                 range: Default::default(),
                 label: "cargo check --workspace".to_string(),
-                bin: "cargo".to_string(),
+                bin: runnable_bin(&world),
                 args: vec!["check".to_string(), "--workspace".to_string()],
                 extra_args: Vec::new(),
-                env: FxHashMap::default(),
+                env: world.config.cargo.extra_env.clone(),
                 cwd: workspace_root.map(|root| root.to_owned()),
             });
         }
@@ -504,7 +498,7 @@ pub fn handle_signature_help(
     }
 }
 
-pub fn handle_hover(world: WorldSnapshot, params: req::HoverParams) -> Result<Option<Hover>> {
+pub fn handle_hover(world: WorldSnapshot, params: req::HoverParams) -> Result<Option<req::Hover>> {
     let _p = profile("handle_hover");
     let position = params.text_document_position_params.try_conv_with(&world)?;
     let info = match world.analysis().hover(position)? {
@@ -513,16 +507,114 @@ pub fn handle_hover(world: WorldSnapshot, params: req::HoverParams) -> Result<Op
     };
     let line_index = world.analysis.file_line_index(position.file_id)?;
     let range = info.range.conv_with(&line_index);
-    let res = Hover {
-        contents: HoverContents::Markup(MarkupContent {
-            kind: MarkupKind::Markdown,
-            value: crate::markdown::format_docs(&info.info.to_markup()),
-        }),
-        range: Some(range),
+    let res = req::Hover {
+        hover: Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: crate::markdown::format_docs(&info.info.to_markup()),
+            }),
+            range: Some(range),
+        },
+        actions: hover_actions(&world, info.info.actions())?,
     };
     Ok(Some(res))
 }
 
+fn hover_actions(
+    world: &WorldSnapshot,
+    actions: &[HoverAction],
+) -> Result<Vec<req::CommandLinkGroup>> {
+    if !world.config.client_caps.hover_actions {
+        return Ok(Vec::new());
+    }
+    let mut commands = Vec::new();
+    for action in actions {
+        match action {
+            HoverAction::Implementation(position) if world.config.hover.implementations => {
+                let navs = match world.analysis().goto_implementation(*position)? {
+                    Some(it) => it.info,
+                    None => continue,
+                };
+                let locations =
+                    navs.iter().map(|nav| nav.try_conv_with(world)).collect::<Result<Vec<_>>>()?;
+                let title = count_label(locations.len(), "implementation");
+                commands.push(show_references_command(world, *position, title, locations)?);
+            }
+            HoverAction::Reference(position) if world.config.hover.references => {
+                let refs = match world.analysis().find_all_refs(*position, None)? {
+                    Some(it) => it,
+                    None => continue,
+                };
+                let locations = refs
+                    .references()
+                    .iter()
+                    .map(|reference| {
+                        let file_id = reference.file_range.file_id;
+                        let line_index = world.analysis().file_line_index(file_id)?;
+                        to_location(file_id, reference.file_range.range, world, &line_index)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let title = count_label(locations.len(), "reference");
+                commands.push(show_references_command(world, *position, title, locations)?);
+            }
+            HoverAction::Runnable(file_id, runnable) => {
+                let r = to_lsp_runnable(world, *file_id, runnable.clone())?;
+                let tooltip = Some(r.label.clone());
+                if world.config.hover.run {
+                    let command = Command {
+                        title: "▶\u{fe0e} Run".into(),
+                        command: "rust-analyzer.runSingle".into(),
+                        arguments: Some(vec![to_value(&r).unwrap()]),
+                    };
+                    commands.push(req::CommandLink { command, tooltip: tooltip.clone() });
+                }
+                if world.config.hover.debug {
+                    let command = Command {
+                        title: "Debug".into(),
+                        command: "rust-analyzer.debugSingle".into(),
+                        arguments: Some(vec![to_value(debug_runnable(r)).unwrap()]),
+                    };
+                    commands.push(req::CommandLink { command, tooltip });
+                }
+            }
+            _ => {}
+        }
+    }
+    if commands.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(vec![req::CommandLinkGroup { title: None, commands }])
+}
+
+fn count_label(count: usize, what: &str) -> String {
+    if count == 1 {
+        format!("1 {}", what)
+    } else {
+        format!("{} {}s", count, what)
+    }
+}
+
+fn show_references_command(
+    world: &WorldSnapshot,
+    position: FilePosition,
+    title: String,
+    locations: Vec<Location>,
+) -> Result<req::CommandLink> {
+    let uri = position.file_id.try_conv_with(world)?;
+    let line_index = world.analysis().file_line_index(position.file_id)?;
+    let position = position.offset.conv_with(&line_index);
+    let command = Command {
+        title,
+        command: "rust-analyzer.showReferences".into(),
+        arguments: Some(vec![
+            to_value(&uri).unwrap(),
+            to_value(position).unwrap(),
+            to_value(locations).unwrap(),
+        ]),
+    };
+    Ok(req::CommandLink { command, tooltip: None })
+}
+
 pub fn handle_prepare_rename(
     world: WorldSnapshot,
     params: req::TextDocumentPositionParams,
@@ -688,18 +780,28 @@ pub fn handle_formatting(
     }]))
 }
 
-fn create_single_code_action(assist: Assist, world: &WorldSnapshot) -> Result<CodeAction> {
-    let arg = to_value(assist.source_change.try_conv_with(world)?)?;
-    let title = assist.label;
-    let command = Command {
-        title: title.clone(),
-        command: "rust-analyzer.applySourceChange".to_string(),
-        arguments: Some(vec![arg]),
+fn create_single_code_action(
+    assist: Assist,
+    params: &req::CodeActionParams,
+    world: &WorldSnapshot,
+) -> Result<CodeAction> {
+    let kind = assist.id.1.conv();
+    let command = match assist.source_change {
+        Some(source_change) => Command {
+            title: assist.label.clone(),
+            command: "rust-analyzer.applySourceChange".to_string(),
+            arguments: Some(vec![to_value(source_change.try_conv_with(world)?)?]),
+        },
+        None => Command {
+            title: assist.label.clone(),
+            command: "rust-analyzer.resolveCodeAction".to_string(),
+            arguments: Some(vec![to_value(resolve_params(&assist.label, assist.id, params))?]),
+        },
     };
 
     Ok(CodeAction {
-        title,
-        kind: Some(String::new()),
+        title: assist.label,
+        kind: Some(kind),
         diagnostics: None,
         edit: None,
         command: Some(command),
@@ -707,6 +809,19 @@ fn create_single_code_action(assist: Assist, world: &WorldSnapshot) -> Result<Co
     })
 }
 
+fn resolve_params(
+    label: &str,
+    id: AssistId,
+    params: &req::CodeActionParams,
+) -> req::ResolveCodeActionParams {
+    req::ResolveCodeActionParams {
+        text_document: params.text_document.clone(),
+        range: params.range,
+        id: id.0.to_string(),
+        label: label.to_string(),
+    }
+}
+
 pub fn handle_code_action(
     world: WorldSnapshot,
     params: req::CodeActionParams,
@@ -716,14 +831,13 @@ pub fn handle_code_action(
     let line_index = world.analysis().file_line_index(file_id)?;
     let range = params.range.conv_with(&line_index);
 
-    let diagnostics = world.analysis().diagnostics(file_id)?;
+    let diagnostics = world.analysis().diagnostics(&world.config.diagnostics, file_id)?;
     let mut res = CodeActionResponse::default();
 
     let fixes_from_diagnostics = diagnostics
         .into_iter()
-        .filter_map(|d| Some((d.range, d.fix?)))
-        .filter(|(diag_range, _fix)| diag_range.intersect(range).is_some())
-        .map(|(_range, fix)| fix);
+        .filter(|d| d.range.intersect(range).is_some())
+        .flat_map(|d| d.fixes);
 
     for source_edit in fixes_from_diagnostics {
         let title = source_edit.label.clone();
@@ -745,6 +859,61 @@ pub fn handle_code_action(
         res.push(action.into());
     }
 
+    // Only offered when asked for explicitly, as a file-wide action would show
+    // up at every cursor position otherwise.
+    let wants_fix_all = match &params.context.only {
+        Some(only) => only.iter().any(|kind| SOURCE_FIX_ALL.starts_with(kind.as_str())),
+        None => false,
+    };
+    if wants_fix_all {
+        if let Some(source_edit) = world.analysis().fix_all(&world.config.diagnostics, file_id)? {
+            let title = source_edit.label.clone();
+            let edit = source_edit.try_conv_with(&world)?;
+            let command = Command {
+                title,
+                command: "rust-analyzer.applySourceChange".to_string(),
+                arguments: Some(vec![to_value(edit).unwrap()]),
+            };
+            let action = CodeAction {
+                title: command.title.clone(),
+                kind: Some(SOURCE_FIX_ALL.to_string()),
+                diagnostics: None,
+                edit: None,
+                command: Some(command),
+                is_preferred: None,
+            };
+            res.push(action.into());
+        }
+    }
+
+    let wants_organize_imports = match &params.context.only {
+        Some(only) => only
+            .iter()
+            .any(|kind| code_action_kind::SOURCE_ORGANIZE_IMPORTS.starts_with(kind.as_str())),
+        None => false,
+    };
+    if wants_organize_imports {
+        let config = &world.config.assist.insert_use;
+        if let Some(source_edit) = world.analysis().organize_imports(config, file_id)? {
+            let title = source_edit.label.clone();
+            let edit = source_edit.try_conv_with(&world)?;
+            let command = Command {
+                title,
+                command: "rust-analyzer.applySourceChange".to_string(),
+                arguments: Some(vec![to_value(edit).unwrap()]),
+            };
+            let action = CodeAction {
+                title: command.title.clone(),
+                kind: Some(code_action_kind::SOURCE_ORGANIZE_IMPORTS.to_string()),
+                diagnostics: None,
+                edit: None,
+                command: Some(command),
+                is_preferred: None,
+            };
+            res.push(action.into());
+        }
+    }
+
     for fix in world.check_fixes.get(&file_id).into_iter().flatten() {
         let fix_range = fix.range.conv_with(&line_index);
         if fix_range.intersect(range).is_none() {
@@ -753,8 +922,22 @@ pub fn handle_code_action(
         res.push(fix.action.clone());
     }
 
+    // Clients resolving code actions lazily only get the edit of the one they apply.
+    let compute_edits = !world.config.client_caps.resolve_code_action;
+    let assists = world.analysis().assists(
+        &world.config.assist,
+        compute_edits,
+        FileRange { file_id, range },
+    )?;
     let mut grouped_assists: FxHashMap<String, (usize, Vec<Assist>)> = FxHashMap::default();
-    for assist in world.analysis().assists(FileRange { file_id, range })?.into_iter() {
+    for assist in assists {
+        if let Some(only) = &params.context.only {
+            let kind = assist.id.1.conv();
+            // Assists without a kind are offered whatever was asked for.
+            if !kind.is_empty() && !only.iter().any(|it| kind.starts_with(it.as_str())) {
+                continue;
+            }
+        }
         match &assist.group_label {
             Some(label) => grouped_assists
                 .entry(label.to_owned())
@@ -767,21 +950,27 @@ pub fn handle_code_action(
                 .1
                 .push(assist),
             None => {
-                res.push(create_single_code_action(assist, &world)?.into());
+                res.push(create_single_code_action(assist, &params, &world)?.into());
             }
         }
     }
 
     for (group_label, (idx, assists)) in grouped_assists {
         if assists.len() == 1 {
-            res[idx] =
-                create_single_code_action(assists.into_iter().next().unwrap(), &world)?.into();
+            let assist = assists.into_iter().next().unwrap();
+            res[idx] = create_single_code_action(assist, &params, &world)?.into();
         } else {
             let title = group_label;
 
+            // The client picks one of the edits, or one of the actions to
+            // resolve.
             let mut arguments = Vec::with_capacity(assists.len());
             for assist in assists {
-                arguments.push(to_value(assist.source_change.try_conv_with(&world)?)?);
+                let argument = match assist.source_change {
+                    Some(source_change) => to_value(source_change.try_conv_with(&world)?)?,
+                    None => to_value(resolve_params(&assist.label, assist.id, &params))?,
+                };
+                arguments.push(argument);
             }
 
             let command = Some(Command {
@@ -821,6 +1010,23 @@ pub fn handle_code_action(
     Ok(Some(res))
 }
 
+pub fn handle_resolve_code_action(
+    world: WorldSnapshot,
+    params: req::ResolveCodeActionParams,
+) -> Result<Option<req::SourceChange>> {
+    let _p = profile("handle_resolve_code_action");
+    let file_id = params.text_document.try_conv_with(&world)?;
+    let line_index = world.analysis().file_line_index(file_id)?;
+    let range = params.range.conv_with(&line_index);
+    let frange = FileRange { file_id, range };
+    let assist =
+        world.analysis().resolve_assist(&world.config.assist, frange, &params.id, &params.label)?;
+    match assist.and_then(|it| it.source_change) {
+        Some(source_change) => Ok(Some(source_change.try_conv_with(&world)?)),
+        None => Ok(None),
+    }
+}
+
 pub fn handle_code_lens(
     world: WorldSnapshot,
     params: req::CodeLensParams,
@@ -840,7 +1046,7 @@ pub fn handle_code_lens(
             RunnableKind::Bin => "Run",
         }
         .to_string();
-        let mut r = to_lsp_runnable(&world, file_id, runnable)?;
+        let r = to_lsp_runnable(&world, file_id, runnable)?;
         let lens = CodeLens {
             range: r.range,
             command: Some(Command {
@@ -852,11 +1058,7 @@ pub fn handle_code_lens(
         };
         lenses.push(lens);
 
-        if r.args[0] == "run" {
-            r.args[0] = "build".into();
-        } else {
-            r.args.push("--no-run".into());
-        }
+        let r = debug_runnable(r);
         let debug_lens = CodeLens {
             range: r.range,
             command: Some(Command {
@@ -898,6 +1100,42 @@ pub fn handle_code_lens(
             }),
     );
 
+    // Handle trait methods and the methods of trait impls
+    if world.config.lens.trait_methods {
+        let file = world.analysis().parse(file_id)?;
+        for fn_def in file.syntax().descendants().filter_map(ast::FnDef::cast) {
+            let name = match fn_def.name() {
+                Some(it) => it,
+                None => continue,
+            };
+            let range = fn_def.syntax().text_range().conv_with(&line_index);
+            let text_document_position_params = req::TextDocumentPositionParams::new(
+                params.text_document.clone(),
+                name.syntax().text_range().start().conv_with(&line_index),
+            );
+            // The grandparent of a method is its trait or impl, the parent
+            // being the item list.
+            let owner = fn_def.syntax().ancestors().nth(2);
+            let data = if owner.clone().and_then(ast::TraitDef::cast).is_some() {
+                CodeLensResolveData::Impls(req::GotoImplementationParams {
+                    text_document_position_params,
+                    work_done_progress_params: Default::default(),
+                    partial_result_params: Default::default(),
+                })
+            } else if owner.and_then(ast::ImplDef::cast).and_then(|it| it.target_trait()).is_some()
+            {
+                CodeLensResolveData::TraitMethod(req::GotoDefinitionParams {
+                    text_document_position_params,
+                    work_done_progress_params: Default::default(),
+                    partial_result_params: Default::default(),
+                })
+            } else {
+                continue;
+            };
+            lenses.push(CodeLens { range, command: None, data: Some(to_value(data).unwrap()) });
+        }
+    }
+
     Ok(Some(lenses))
 }
 
@@ -905,6 +1143,43 @@ pub fn handle_code_lens(
 #[serde(rename_all = "camelCase")]
 enum CodeLensResolveData {
     Impls(req::GotoImplementationParams),
+    TraitMethod(req::GotoDefinitionParams),
+}
+
+fn response_locations(response: Option<req::GotoDefinitionResponse>) -> Vec<Location> {
+    match response {
+        Some(req::GotoDefinitionResponse::Scalar(loc)) => vec![loc],
+        Some(req::GotoDefinitionResponse::Array(locs)) => locs,
+        Some(req::GotoDefinitionResponse::Link(links)) => links
+            .into_iter()
+            .map(|link| Location::new(link.target_uri, link.target_selection_range))
+            .collect(),
+        None => vec![],
+    }
+}
+
+fn range_contains(outer: Range, inner: Range) -> bool {
+    outer.start <= inner.start && inner.end <= outer.end
+}
+
+// We cannot use the 'editor.action.showReferences' command directly
+// because that command requires vscode types which we convert in the handler
+// on the client side.
+fn show_references_command(
+    title: String,
+    uri: &Url,
+    position: Position,
+    locations: Vec<Location>,
+) -> Command {
+    Command {
+        title,
+        command: "rust-analyzer.showReferences".into(),
+        arguments: Some(vec![
+            to_value(uri).unwrap(),
+            to_value(position).unwrap(),
+            to_value(locations).unwrap(),
+        ]),
+    }
 }
 
 pub fn handle_code_lens_resolve(world: WorldSnapshot, code_lens: CodeLens) -> Result<CodeLens> {
@@ -913,16 +1188,8 @@ pub fn handle_code_lens_resolve(world: WorldSnapshot, code_lens: CodeLens) -> Re
     let resolve = from_json::<Option<CodeLensResolveData>>("CodeLensResolveData", data)?;
     match resolve {
         Some(CodeLensResolveData::Impls(lens_params)) => {
-            let locations: Vec<Location> =
-                match handle_goto_implementation(world, lens_params.clone())? {
-                    Some(req::GotoDefinitionResponse::Scalar(loc)) => vec![loc],
-                    Some(req::GotoDefinitionResponse::Array(locs)) => locs,
-                    Some(req::GotoDefinitionResponse::Link(links)) => links
-                        .into_iter()
-                        .map(|link| Location::new(link.target_uri, link.target_selection_range))
-                        .collect(),
-                    _ => vec![],
-                };
+            let uri = lens_params.text_document_position_params.text_document.uri.clone();
+            let locations = response_locations(handle_goto_implementation(world, lens_params)?);
 
             let title = if locations.len() == 1 {
                 "1 implementation".into()
@@ -930,18 +1197,24 @@ pub fn handle_code_lens_resolve(world: WorldSnapshot, code_lens: CodeLens) -> Re
                 format!("{} implementations", locations.len())
             };
 
-            // We cannot use the 'editor.action.showReferences' command directly
-            // because that command requires vscode types which we convert in the handler
-            // on the client side.
-            let cmd = Command {
-                title,
-                command: "rust-analyzer.showReferences".into(),
-                arguments: Some(vec![
-                    to_value(&lens_params.text_document_position_params.text_document.uri).unwrap(),
-                    to_value(code_lens.range.start).unwrap(),
-                    to_value(locations).unwrap(),
-                ]),
-            };
+            let cmd = show_references_command(title, &uri, code_lens.range.start, locations);
+            Ok(CodeLens { range: code_lens.range, command: Some(cmd), data: None })
+        }
+        Some(CodeLensResolveData::TraitMethod(lens_params)) => {
+            let uri = lens_params.text_document_position_params.text_document.uri.clone();
+            let locations = response_locations(handle_goto_definition(world, lens_params)?)
+                .into_iter()
+                // Going to the definition of the method also finds the method
+                // itself.
+                .filter(|loc| loc.uri != uri || !range_contains(code_lens.range, loc.range))
+                .collect();
+
+            let cmd = show_references_command(
+                "trait method".into(),
+                &uri,
+                code_lens.range.start,
+                locations,
+            );
             Ok(CodeLens { range: code_lens.range, command: Some(cmd), data: None })
         }
         None => Ok(CodeLens {
@@ -987,23 +1260,55 @@ pub fn handle_ssr(world: WorldSnapshot, params: req::SsrParams) -> Result<req::S
         .try_conv_with(&world)
 }
 
+pub fn handle_change_signature(
+    world: WorldSnapshot,
+    params: req::ChangeSignatureParams,
+) -> Result<Option<req::SourceChange>> {
+    let _p = profile("handle_change_signature");
+    let file_id = params.text_document.try_conv_with(&world)?;
+    let line_index = world.analysis().file_line_index(file_id)?;
+    let offset = params.position.conv_with(&line_index);
+    let signature = params
+        .params
+        .into_iter()
+        .map(|param| match param {
+            req::SignatureParam::Existing { index } => SignatureParam::Existing { index },
+            req::SignatureParam::New { pattern, ty, default } => {
+                SignatureParam::New { pattern, ty, default }
+            }
+        })
+        .collect();
+    match world.analysis().change_signature(FilePosition { file_id, offset }, signature)? {
+        None => Ok(None),
+        Some(edit) => Ok(Some(edit.try_conv_with(&world)?)),
+    }
+}
+
 pub fn publish_diagnostics(world: &WorldSnapshot, file_id: FileId) -> Result<DiagnosticTask> {
     let _p = profile("publish_diagnostics");
     let line_index = world.analysis().file_line_index(file_id)?;
-    let diagnostics: Vec<Diagnostic> = world
-        .analysis()
-        .diagnostics(file_id)?
-        .into_iter()
-        .map(|d| Diagnostic {
+    let mut diagnostics = Vec::new();
+    for d in world.analysis().diagnostics(&world.config.diagnostics, file_id)? {
+        let mut related_information = Vec::new();
+        for (related_file_id, range, message) in d.related {
+            let line_index = world.analysis().file_line_index(related_file_id)?;
+            let location = to_location(related_file_id, range, &world, &line_index)?;
+            related_information.push(DiagnosticRelatedInformation { location, message });
+        }
+        diagnostics.push(Diagnostic {
             range: d.range.conv_with(&line_index),
-            severity: Some(d.severity.conv()),
-            code: None,
+            severity: Some(world.config.diagnostics.severity(d.code, d.severity).conv()),
+            code: Some(NumberOrString::String(d.code.to_string())),
             source: Some("rust-analyzer".to_string()),
             message: d.message,
-            related_information: None,
-            tags: None,
-        })
-        .collect();
+            related_information: if related_information.is_empty() {
+                None
+            } else {
+                Some(related_information)
+            },
+            tags: if d.unused { Some(vec![DiagnosticTag::Unnecessary]) } else { None },
+        });
+    }
     Ok(DiagnosticTask::SetNative(file_id, diagnostics))
 }
 
@@ -1025,18 +1330,35 @@ fn to_lsp_runnable(
     Ok(req::Runnable {
         range: runnable.range.conv_with(&line_index),
         label,
-        bin: "cargo".to_string(),
+        bin: runnable_bin(&world),
         args,
         extra_args,
         env: {
-            let mut m = FxHashMap::default();
-            m.insert("RUST_BACKTRACE".to_string(), "short".to_string());
+            let mut m = world.config.cargo.extra_env.clone();
+            m.entry("RUST_BACKTRACE".to_string()).or_insert_with(|| "short".to_string());
             m
         },
         cwd: world.workspace_root_for(file_id).map(|root| root.to_owned()),
     })
 }
 
+/// Debugging builds the runnable without running it, the client then starts
+/// the produced binary under the debugger.
+fn debug_runnable(mut r: req::Runnable) -> req::Runnable {
+    if r.args[0] == "run" {
+        r.args[0] = "build".into();
+    } else {
+        r.args.push("--no-run".into());
+    }
+    r
+}
+
+/// The client runs runnables with its own environment, so only an explicit
+/// `CARGO` override from `cargo.extraEnv` replaces the default binary.
+fn runnable_bin(world: &WorldSnapshot) -> String {
+    world.config.cargo.extra_env.get("CARGO").cloned().unwrap_or_else(|| "cargo".to_string())
+}
+
 pub fn handle_inlay_hints(
     world: WorldSnapshot,
     params: InlayHintsParams,
@@ -1140,6 +1462,71 @@ pub fn handle_call_hierarchy_outgoing(
     Ok(Some(res))
 }
 
+pub fn handle_type_hierarchy_prepare(
+    world: WorldSnapshot,
+    params: req::TypeHierarchyPrepareParams,
+) -> Result<Option<Vec<req::TypeHierarchyItem>>> {
+    let _p = profile("handle_type_hierarchy_prepare");
+    let position = params.text_document_position_params.try_conv_with(&world)?;
+
+    let nav_info = match world.analysis().type_hierarchy(position)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+
+    let res = nav_info
+        .info
+        .into_iter()
+        .filter_map(|it| to_type_hierarchy_item(&world, it).ok())
+        .collect();
+
+    Ok(Some(res))
+}
+
+pub fn handle_type_hierarchy_supertypes(
+    world: WorldSnapshot,
+    params: req::TypeHierarchyItemParams,
+) -> Result<Option<Vec<req::TypeHierarchyItem>>> {
+    let _p = profile("handle_type_hierarchy_supertypes");
+    let fpos = type_hierarchy_item_position(&world, params.item)?;
+
+    let navs = match world.analysis().supertypes(fpos)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+    let res =
+        navs.into_iter().map(|it| to_type_hierarchy_item(&world, it)).collect::<Result<_>>()?;
+
+    Ok(Some(res))
+}
+
+pub fn handle_type_hierarchy_subtypes(
+    world: WorldSnapshot,
+    params: req::TypeHierarchyItemParams,
+) -> Result<Option<Vec<req::TypeHierarchyItem>>> {
+    let _p = profile("handle_type_hierarchy_subtypes");
+    let fpos = type_hierarchy_item_position(&world, params.item)?;
+
+    let navs = match world.analysis().subtypes(fpos)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+    let res =
+        navs.into_iter().map(|it| to_type_hierarchy_item(&world, it)).collect::<Result<_>>()?;
+
+    Ok(Some(res))
+}
+
+/// The position of the name of the trait or type of `item`.
+fn type_hierarchy_item_position(
+    world: &WorldSnapshot,
+    item: req::TypeHierarchyItem,
+) -> Result<FilePosition> {
+    let doc = TextDocumentIdentifier::new(item.uri);
+    let frange: FileRange = (&doc, item.selection_range).try_conv_with(world)?;
+    Ok(FilePosition { file_id: frange.file_id, offset: frange.range.start() })
+}
+
 pub fn handle_semantic_tokens(
     world: WorldSnapshot,
     params: SemanticTokensParams,
@@ -10,10 +10,10 @@ pub use lsp_types::{
     DiagnosticTag, DidChangeConfigurationParams, DidChangeWatchedFilesParams,
     DidChangeWatchedFilesRegistrationOptions, DocumentHighlightParams,
     DocumentOnTypeFormattingParams, DocumentSymbolParams, DocumentSymbolResponse,
-    FileSystemWatcher, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverParams,
-    InitializeResult, MessageType, PartialResultParams, ProgressParams, ProgressParamsValue,
-    ProgressToken, PublishDiagnosticsParams, ReferenceParams, Registration, RegistrationParams,
-    SelectionRange, SelectionRangeParams, SemanticTokensParams, SemanticTokensRangeParams,
+    FileSystemWatcher, GotoDefinitionParams, GotoDefinitionResponse, HoverParams, InitializeResult,
+    MessageType, PartialResultParams, ProgressParams, ProgressParamsValue, ProgressToken,
+    PublishDiagnosticsParams, ReferenceParams, Registration, RegistrationParams, SelectionRange,
+    SelectionRangeParams, SemanticTokensParams, SemanticTokensRangeParams,
     SemanticTokensRangeResult, SemanticTokensResult, ServerCapabilities, ShowMessageParams,
     SignatureHelp, SignatureHelpParams, SymbolKind, TextDocumentEdit, TextDocumentPositionParams,
     TextEdit, WorkDoneProgressParams, WorkspaceEdit, WorkspaceSymbolParams,
@@ -152,6 +152,53 @@ pub struct SourceChange {
     pub label: String,
     pub workspace_edit: WorkspaceEdit,
     pub cursor_position: Option<TextDocumentPositionParams>,
+    /// Whether the edits contain snippet tabstops like `$0`, which the client
+    /// turns into a selection.
+    pub is_snippet: bool,
+}
+
+/// Computes the edit of a code action which was sent without one. This stands
+/// in for the `codeAction/resolve` request of LSP 3.16, which the protocol
+/// version we implement lacks.
+pub enum ResolveCodeAction {}
+
+impl Request for ResolveCodeAction {
+    type Params = ResolveCodeActionParams;
+    type Result = Option<SourceChange>;
+    const METHOD: &'static str = "rust-analyzer/resolveCodeAction";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveCodeActionParams {
+    pub text_document: TextDocumentIdentifier,
+    pub range: Range,
+    /// The id of the assist.
+    pub id: String,
+    pub label: String,
+}
+
+pub enum ChangeSignature {}
+
+impl Request for ChangeSignature {
+    type Params = ChangeSignatureParams;
+    type Result = Option<SourceChange>;
+    const METHOD: &'static str = "rust-analyzer/changeSignature";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSignatureParams {
+    pub text_document: TextDocumentIdentifier,
+    pub position: Position,
+    pub params: Vec<SignatureParam>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum SignatureParam {
+    Existing { index: usize },
+    New { pattern: String, ty: String, default: Option<String> },
 }
 
 pub enum InlayHints {}
@@ -196,3 +243,94 @@ pub struct SsrParams {
     pub query: String,
     pub parse_only: bool,
 }
+
+/// `textDocument/hover`, extended with the actions the client can show below
+/// the hover when it advertises the `hoverActions` capability.
+pub enum HoverRequest {}
+
+impl Request for HoverRequest {
+    type Params = HoverParams;
+    type Result = Option<Hover>;
+    const METHOD: &'static str = "textDocument/hover";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Hover {
+    #[serde(flatten)]
+    pub hover: lsp_types::Hover,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub actions: Vec<CommandLinkGroup>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CommandLinkGroup {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub commands: Vec<CommandLink>,
+}
+
+/// A command rendered as a link, e.g. `Run` or `2 implementations`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CommandLink {
+    #[serde(flatten)]
+    pub command: lsp_types::Command,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tooltip: Option<String>,
+}
+
+/// `textDocument/prepareTypeHierarchy`, from the upcoming version of the
+/// protocol.
+pub enum TypeHierarchyPrepare {}
+
+impl Request for TypeHierarchyPrepare {
+    type Params = TypeHierarchyPrepareParams;
+    type Result = Option<Vec<TypeHierarchyItem>>;
+    const METHOD: &'static str = "textDocument/prepareTypeHierarchy";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeHierarchyPrepareParams {
+    #[serde(flatten)]
+    pub text_document_position_params: TextDocumentPositionParams,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+}
+
+pub enum TypeHierarchySupertypes {}
+
+impl Request for TypeHierarchySupertypes {
+    type Params = TypeHierarchyItemParams;
+    type Result = Option<Vec<TypeHierarchyItem>>;
+    const METHOD: &'static str = "typeHierarchy/supertypes";
+}
+
+pub enum TypeHierarchySubtypes {}
+
+impl Request for TypeHierarchySubtypes {
+    type Params = TypeHierarchyItemParams;
+    type Result = Option<Vec<TypeHierarchyItem>>;
+    const METHOD: &'static str = "typeHierarchy/subtypes";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeHierarchyItemParams {
+    pub item: TypeHierarchyItem,
+    #[serde(flatten)]
+    pub work_done_progress_params: WorkDoneProgressParams,
+    #[serde(flatten)]
+    pub partial_result_params: PartialResultParams,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeHierarchyItem {
+    pub name: String,
+    pub kind: SymbolKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    pub uri: lsp_types::Url,
+    pub range: Range,
+    pub selection_range: Range,
+}
@@ -131,7 +131,8 @@ impl WorldState {
 
         // FIXME: Read default cfgs from config
         let default_cfg_options = {
-            let mut opts = get_rustc_cfg_options(config.cargo.target.as_ref());
+            let mut opts =
+                get_rustc_cfg_options(config.cargo.target.as_ref(), &config.cargo.extra_env);
             opts.insert_atom("test".into());
             opts.insert_atom("debug_assertion".into());
             opts
@@ -179,6 +180,7 @@ impl WorldState {
         let flycheck = config.check.as_ref().and_then(|c| create_flycheck(&workspaces, c));
 
         let mut analysis_host = AnalysisHost::new(lru_capacity);
+        analysis_host.update_trait_solver_limits(config.trait_solver);
         analysis_host.apply_change(change);
         WorldState {
             config,
@@ -196,6 +198,7 @@ impl WorldState {
 
     pub fn update_configuration(&mut self, config: Config) {
         self.analysis_host.update_lru_capacity(config.lru_capacity);
+        self.analysis_host.update_trait_solver_limits(config.trait_solver);
         if config.check != self.config.check {
             self.flycheck =
                 config.check.as_ref().and_then(|it| create_flycheck(&self.workspaces, it));
@@ -337,7 +337,7 @@ fn main() {}
               "arguments": [
                 {
                   "cursorPosition": null,
-                  "label": "Create module",
+                  "label": "Create module at `bar.rs`",
                   "workspaceEdit": {
                     "documentChanges": [
                       {
@@ -349,9 +349,30 @@ fn main() {}
                 }
               ],
               "command": "rust-analyzer.applySourceChange",
-              "title": "Create module"
+              "title": "Create module at `bar.rs`"
             },
-            "title": "Create module"
+            "title": "Create module at `bar.rs`"
+          },
+          {
+            "command": {
+              "arguments": [
+                {
+                  "cursorPosition": null,
+                  "label": "Create module at `bar/mod.rs`",
+                  "workspaceEdit": {
+                    "documentChanges": [
+                      {
+                        "kind": "create",
+                        "uri": "file:///[..]/src/bar/mod.rs"
+                      }
+                    ]
+                  }
+                }
+              ],
+              "command": "rust-analyzer.applySourceChange",
+              "title": "Create module at `bar/mod.rs`"
+            },
+            "title": "Create module at `bar/mod.rs`"
           }
         ]),
     );
@@ -420,7 +441,7 @@ fn main() {{}}
               "arguments": [
                 {
                   "cursorPosition": null,
-                  "label": "Create module",
+                  "label": "Create module at `bar.rs`",
                   "workspaceEdit": {
                     "documentChanges": [
                       {
@@ -432,9 +453,30 @@ fn main() {{}}
                 }
               ],
               "command": "rust-analyzer.applySourceChange",
-              "title": "Create module"
+              "title": "Create module at `bar.rs`"
             },
-            "title": "Create module"
+            "title": "Create module at `bar.rs`"
+          },
+          {
+            "command": {
+              "arguments": [
+                {
+                  "cursorPosition": null,
+                  "label": "Create module at `bar/mod.rs`",
+                  "workspaceEdit": {
+                    "documentChanges": [
+                      {
+                        "kind": "create",
+                        "uri": "file:///[..]/src/bar/mod.rs"
+                      }
+                    ]
+                  }
+                }
+              ],
+              "command": "rust-analyzer.applySourceChange",
+              "title": "Create module at `bar/mod.rs`"
+            },
+            "title": "Create module at `bar/mod.rs`"
           }
         ]),
     );
@@ -621,6 +663,48 @@ fn main() { message(); }
     assert!(format!("{}", res).contains("hello.rs"));
 }
 
+#[test]
+fn resolve_path_dependency_outside_of_root() {
+    if skip_slow_tests() {
+        return;
+    }
+
+    let server = Project::with_fixture(
+        r###"
+//- foo/Cargo.toml
+[package]
+name = "foo"
+version = "0.0.0"
+
+[dependencies]
+bar = { path = "../bar" }
+
+//- foo/src/main.rs
+fn main() { bar::bar(); }
+
+//- bar/Cargo.toml
+[package]
+name = "bar"
+version = "0.0.0"
+
+//- bar/src/lib.rs
+pub fn bar() {}
+"###,
+    )
+    .root("foo")
+    .server();
+    server.wait_until_workspace_is_loaded();
+    let res = server.send_request::<GotoDefinition>(GotoDefinitionParams {
+        text_document_position_params: TextDocumentPositionParams::new(
+            server.doc_id("foo/src/main.rs"),
+            Position::new(0, 18),
+        ),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    });
+    assert!(format!("{}", res).contains("bar/src/lib.rs"));
+}
+
 #[test]
 fn resolve_proc_macro() {
     if skip_slow_tests() {
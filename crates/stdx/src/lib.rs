@@ -1,6 +1,11 @@
 //! Missing batteries for standard libraries.
 
-use std::{cell::Cell, fmt, time::Instant};
+use std::{
+    cell::Cell,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
 
 #[inline(always)]
 pub fn is_ci() -> bool {
@@ -116,3 +121,112 @@ pub fn to_lower_snake_case(s: &str) -> String {
     }
     buf
 }
+
+/// A 64-bit FNV-1a hasher, for hashes that have to be the same across
+/// processes, platforms and compiler versions (unlike `DefaultHasher`).
+///
+/// Doesn't implement `std::hash::Hasher` on purpose: `Hash` impls of integers
+/// write native-endian bytes, which would defeat the point.
+#[derive(Clone)]
+pub struct StableHasher {
+    state: u64,
+}
+
+impl Default for StableHasher {
+    fn default() -> StableHasher {
+        StableHasher { state: 0xcbf2_9ce4_8422_2325 }
+    }
+}
+
+impl StableHasher {
+    pub fn new() -> StableHasher {
+        StableHasher::default()
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.state ^= u64::from(byte);
+            self.state = self.state.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes())
+    }
+
+    /// Writes the length of `bytes` before the bytes themselves, so that
+    /// consecutive writes can't be confused with each other.
+    pub fn write_prefixed(&mut self, bytes: &[u8]) {
+        self.write_u64(bytes.len() as u64);
+        self.write(bytes)
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+/// Writes `bytes` to a temporary file first and then renames it to `path`, so
+/// that concurrent readers never see a partially written file. Creates the
+/// parent directory if needed.
+pub fn write_atomically(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp_path = path.with_extension(format!("tmp{}", std::process::id()));
+    fs::write(&tmp_path, bytes)?;
+    fs::rename(&tmp_path, path)
+}
+
+/// Removes the files in `dir` older than `max_age`, and the oldest ones if
+/// there are more than `max_entries` left. Returns the files that couldn't be
+/// removed.
+pub fn evict_old_files(
+    dir: &Path,
+    max_entries: usize,
+    max_age: Duration,
+) -> Vec<(PathBuf, io::Error)> {
+    let entries = match fs::read_dir(dir) {
+        Ok(it) => it,
+        Err(_) => return Vec::new(),
+    };
+    let now = SystemTime::now();
+    let mut entries = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((modified, entry.path()))
+        })
+        .collect::<Vec<_>>();
+    // Newest first.
+    entries.sort_by(|(lhs, _), (rhs, _)| rhs.cmp(lhs));
+    let mut errors = Vec::new();
+    for (idx, (modified, path)) in entries.into_iter().enumerate() {
+        let expired = now.duration_since(modified).map_or(false, |age| age > max_age);
+        if idx >= max_entries || expired {
+            if let Err(err) = fs::remove_file(&path) {
+                errors.push((path, err));
+            }
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn old_files_are_evicted() {
+        let dir = std::env::temp_dir().join(format!("stdx-evict-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for idx in 0..10 {
+            write_atomically(&dir.join(idx.to_string()), b"").unwrap();
+        }
+        let errors = evict_old_files(&dir, 8, Duration::from_secs(60));
+        assert!(errors.is_empty());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 8);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,5 +1,6 @@
 import * as lc from 'vscode-languageclient';
 import * as vscode from 'vscode';
+import * as ra from './rust-analyzer-api';
 
 import { CallHierarchyFeature } from 'vscode-languageclient/lib/callHierarchy.proposed';
 import { SemanticTokensFeature, DocumentSemanticsTokensSignature } from 'vscode-languageclient/lib/semanticTokens.proposed';
@@ -31,6 +32,23 @@ export function createClient(serverPath: string, cwd: string): lc.LanguageClient
                 const res = await next(document, token);
                 if (res === undefined) throw new Error('busy');
                 return res;
+            },
+            // Renders the hover actions of the server, see `HoverRequest`.
+            async provideHover(document: vscode.TextDocument, position: vscode.Position, token: vscode.CancellationToken, _next: lc.ProvideHoverSignature) {
+                const params = res.code2ProtocolConverter.asTextDocumentPositionParams(document, position);
+                const result = await res.sendRequest(lc.HoverRequest.type, params, token).then(
+                    (result) => result,
+                    (error) => {
+                        res.logFailedRequest(lc.HoverRequest.type, error);
+                        return null;
+                    },
+                );
+                const hover = res.protocol2CodeConverter.asHover(result);
+                const actions = (<any>result)?.actions;
+                if (hover && actions) {
+                    hover.contents.push(renderHoverActions(actions));
+                }
+                return hover;
             }
         } as any
     };
@@ -49,6 +67,40 @@ export function createClient(serverPath: string, cwd: string): lc.LanguageClient
     // implementations are still in the "proposed" category for 3.16.
     res.registerFeature(new CallHierarchyFeature(res));
     res.registerFeature(new SemanticTokensFeature(res));
+    res.registerFeature(new ExperimentalFeatures());
 
     return res;
 }
+
+class ExperimentalFeatures implements lc.StaticFeature {
+    fillClientCapabilities(capabilities: lc.ClientCapabilities): void {
+        const caps: any = capabilities.experimental ?? {};
+        // Tabstops in source changes are turned into a selection, see
+        // `applySourceChange`.
+        caps.snippetTextEdit = true;
+        // Code actions come without edits, which are computed when applying
+        // them, see `resolveCodeAction`.
+        caps.resolveCodeAction = true;
+        // Hovers come with command links, see `renderHoverActions`.
+        caps.hoverActions = true;
+        capabilities.experimental = caps;
+    }
+    initialize(_capabilities: lc.ServerCapabilities, _documentSelector: lc.DocumentSelector | undefined): void {
+    }
+}
+
+function renderCommand(cmd: ra.CommandLink) {
+    const args = encodeURIComponent(JSON.stringify(cmd.arguments));
+    return `[${cmd.title}](command:${cmd.command}?${args} '${cmd.tooltip ?? ''}')`;
+}
+
+function renderHoverActions(actions: ra.CommandLinkGroup[]): vscode.MarkdownString {
+    const text = actions.map(group =>
+        (group.title ? (group.title + " ") : "") + group.commands.map(renderCommand).join(' | ')
+    ).join('___');
+
+    const result = new vscode.MarkdownString(text);
+    // Command links only work in trusted markdown.
+    result.isTrusted = true;
+    return result;
+}
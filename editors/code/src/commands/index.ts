@@ -40,14 +40,26 @@ export function applySourceChange(ctx: Ctx): Cmd {
     };
 }
 
+export function resolveCodeAction(ctx: Ctx): Cmd {
+    return async (params: ra.ResolveCodeActionParams) => {
+        const change = await ctx.client.sendRequest(ra.resolveCodeAction, params);
+        if (!change) return;
+        await sourceChange.applySourceChange(ctx, change);
+    };
+}
+
 export function selectAndApplySourceChange(ctx: Ctx): Cmd {
-    return async (changes: ra.SourceChange[]) => {
-        if (changes.length === 1) {
-            await sourceChange.applySourceChange(ctx, changes[0]);
-        } else if (changes.length > 0) {
-            const selectedChange = await vscode.window.showQuickPick(changes);
-            if (!selectedChange) return;
+    // The items are source changes, or code actions to resolve when the
+    // client resolves them lazily.
+    return async (changes: (ra.SourceChange | ra.ResolveCodeActionParams)[]) => {
+        const selectedChange = changes.length === 1
+            ? changes[0]
+            : await vscode.window.showQuickPick(changes);
+        if (!selectedChange) return;
+        if ('workspaceEdit' in selectedChange) {
             await sourceChange.applySourceChange(ctx, selectedChange);
+        } else {
+            await resolveCodeAction(ctx)(selectedChange);
         }
     };
 }
@@ -91,6 +91,7 @@ export async function activate(context: vscode.ExtensionContext) {
     ctx.registerCommand('showReferences', commands.showReferences);
     ctx.registerCommand('applySourceChange', commands.applySourceChange);
     ctx.registerCommand('selectAndApplySourceChange', commands.selectAndApplySourceChange);
+    ctx.registerCommand('resolveCodeAction', commands.resolveCodeAction);
 
     ctx.pushCleanup(activateTaskProvider(workspaceFolder));
 
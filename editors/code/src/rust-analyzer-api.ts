@@ -115,6 +115,17 @@ export interface SsrParams {
 export const ssr = request<SsrParams, SourceChange>("ssr");
 
 
+export type SignatureParam =
+    | { kind: "existing"; index: number }
+    | { kind: "new"; pattern: string; ty: string; default: Option<string> };
+export interface ChangeSignatureParams {
+    textDocument: lc.TextDocumentIdentifier;
+    position: lc.Position;
+    params: Vec<SignatureParam>;
+}
+export const changeSignature = request<ChangeSignatureParams, Option<SourceChange>>("changeSignature");
+
+
 export const publishDecorations = notification<PublishDecorationsParams>("publishDecorations");
 
 
@@ -122,4 +133,25 @@ export interface SourceChange {
     label: string;
     workspaceEdit: lc.WorkspaceEdit;
     cursorPosition: Option<lc.TextDocumentPositionParams>;
+    isSnippet: boolean;
+}
+
+export interface ResolveCodeActionParams {
+    textDocument: lc.TextDocumentIdentifier;
+    range: lc.Range;
+    id: string;
+    label: string;
+}
+export const resolveCodeAction = request<ResolveCodeActionParams, Option<SourceChange>>("resolveCodeAction");
+
+
+export interface CommandLink extends lc.Command {
+    /**
+     * A tooltip for the command, when represented in the UI.
+     */
+    tooltip?: string;
+}
+export interface CommandLinkGroup {
+    title?: string;
+    commands: CommandLink[];
 }
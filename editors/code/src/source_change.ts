@@ -8,6 +8,7 @@ export async function applySourceChange(ctx: Ctx, change: ra.SourceChange) {
     const client = ctx.client;
     if (!client) return;
 
+    const tabstop = change.isSnippet ? await removeTabstop(change.workspaceEdit) : undefined;
     const wsEdit = client.protocol2CodeConverter.asWorkspaceEdit(
         change.workspaceEdit,
     );
@@ -25,7 +26,15 @@ export async function applySourceChange(ctx: Ctx, change: ra.SourceChange) {
     const toOpen = created || moved;
     const toReveal = change.cursorPosition;
     await vscode.workspace.applyEdit(wsEdit);
-    if (toOpen) {
+    if (tabstop) {
+        const uri = client.protocol2CodeConverter.asUri(tabstop.uri);
+        const doc = await vscode.workspace.openTextDocument(uri);
+        const editor = await vscode.window.showTextDocument(doc);
+        const start = doc.positionAt(tabstop.start);
+        const end = doc.positionAt(tabstop.end);
+        editor.selection = new vscode.Selection(start, end);
+        editor.revealRange(new vscode.Range(start, end), vscode.TextEditorRevealType.Default);
+    } else if (toOpen) {
         const toOpenUri = vscode.Uri.parse(toOpen);
         const doc = await vscode.workspace.openTextDocument(toOpenUri);
         await vscode.window.showTextDocument(doc);
@@ -52,3 +61,36 @@ export async function applySourceChange(ctx: Ctx, change: ra.SourceChange) {
         );
     }
 }
+
+// Removes the first `$0` or `${0:placeholder}` tabstop of `wsEdit`, and
+// returns the offsets the placeholder will have once the edit is applied.
+async function removeTabstop(
+    wsEdit: lc.WorkspaceEdit,
+): Promise<{ uri: string; start: number; end: number } | undefined> {
+    for (const docChange of wsEdit.documentChanges ?? []) {
+        if (!lc.TextDocumentEdit.is(docChange)) continue;
+        for (const edit of docChange.edits) {
+            const match = /\$0|\$\{0:([^}]*)\}/.exec(edit.newText);
+            if (!match) continue;
+
+            const placeholder = match[1] ?? '';
+            edit.newText = edit.newText.slice(0, match.index) + placeholder +
+                edit.newText.slice(match.index + match[0].length);
+
+            // The edit moves by the length changes of the edits before it.
+            const uri = docChange.textDocument.uri;
+            const doc = await vscode.workspace.openTextDocument(vscode.Uri.parse(uri));
+            const editStart = doc.offsetAt(new vscode.Position(edit.range.start.line, edit.range.start.character));
+            let start = editStart + match.index;
+            for (const other of docChange.edits) {
+                const otherStart = doc.offsetAt(new vscode.Position(other.range.start.line, other.range.start.character));
+                const otherEnd = doc.offsetAt(new vscode.Position(other.range.end.line, other.range.end.character));
+                if (other !== edit && otherEnd <= editStart) {
+                    start += other.newText.length - (otherEnd - otherStart);
+                }
+            }
+            return { uri, start, end: start + placeholder.length };
+        }
+    }
+    return undefined;
+}
@@ -439,7 +439,7 @@ pub(crate) const AST_SRC: AstSrc = AstSrc {
         }
         struct IfExpr: AttrsOwner { T![if], Condition }
         struct LoopExpr: AttrsOwner, LoopBodyOwner { T![loop] }
-        struct EffectExpr: AttrsOwner { Label, T![try], T![unsafe], T![async], BlockExpr }
+        struct EffectExpr: AttrsOwner { Label, T![try], T![unsafe], T![async], T![const], BlockExpr }
         struct ForExpr: AttrsOwner, LoopBodyOwner {
             T![for],
             Pat,
@@ -629,8 +629,8 @@ pub(crate) const AST_SRC: AstSrc = AstSrc {
             Path, T![=], AttrInput, nested_meta_items: [MetaItem]
         }
 
-        struct MacroDef {
-            Name, TokenTree
+        struct MacroDef: VisibilityOwner, NameOwner, AttrsOwner, DocCommentsOwner {
+            T![macro], TokenTree
         }
     },
     enums: &ast_enums! {
@@ -681,6 +681,7 @@ pub(crate) const AST_SRC: AstSrc = AstSrc {
             StaticDef,
             Module,
             MacroCall,
+            MacroDef,
             ExternBlock
         }
 
@@ -27,6 +27,10 @@ fn check_todo(path: &Path, text: &str) {
         "doc_tests/generated.rs",
         "handlers/add_missing_impl_members.rs",
         "handlers/add_function.rs",
+        "handlers/add_test_fn.rs",
+        // Some diagnostic fixes and refactorings generate `todo!()` too.
+        "ra_ide/src/diagnostics.rs",
+        "ra_ide/src/change_signature.rs",
         // To support generating `todo!()` in assists, we have `expr_todo()` in ast::make.
         "ast/make.rs",
     ];